@@ -25,6 +25,7 @@ fn main() {
             os_guess: None,
             custom_name: None,
             notes: None,
+            security_grade: None,
         },
         DeviceRecord {
             id: 2,
@@ -38,6 +39,7 @@ fn main() {
             os_guess: None,
             custom_name: None,
             notes: None,
+            security_grade: None,
         },
     ];
 
@@ -48,18 +50,14 @@ fn main() {
             ip: "192.168.1.150".to_string(), // Changed from .100
             mac: "AA:BB:CC:DD:EE:01".to_string(),
             vendor: Some("Apple Inc".to_string()),
-            is_randomized: false,
             response_time_ms: Some(5),
             ttl: Some(64),
             os_guess: Some("iOS".to_string()),
             device_type: "MOBILE".to_string(),
             risk_score: 10,
-            open_ports: vec![],
             discovery_method: "ARP+ICMP".to_string(),
             hostname: Some("iphone".to_string()),
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
         // NEW device (new device alert)
         HostInfo {
@@ -69,15 +67,11 @@ fn main() {
             is_randomized: true,
             response_time_ms: Some(10),
             ttl: Some(64),
-            os_guess: None,
             device_type: "UNKNOWN".to_string(),
             risk_score: 60, // High risk!
             open_ports: vec![22, 23, 3389], // Has Telnet and RDP!
             discovery_method: "ARP".to_string(),
-            hostname: None,
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
     ];
     // Note: Device 2 (galaxy) is NOT in current scan - it went offline
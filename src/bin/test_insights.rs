@@ -14,7 +14,6 @@ fn main() {
             ip: "192.168.1.1".to_string(),
             mac: "00:11:22:33:44:55".to_string(),
             vendor: Some("Cisco".to_string()),
-            is_randomized: false,
             response_time_ms: Some(5),
             ttl: Some(64),
             os_guess: Some("Linux".to_string()),
@@ -23,26 +22,20 @@ fn main() {
             open_ports: vec![22, 80, 443],
             discovery_method: "ARP+ICMP".to_string(),
             hostname: Some("router".to_string()),
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
         HostInfo {
             ip: "192.168.1.100".to_string(),
             mac: "AA:BB:CC:DD:EE:01".to_string(),
             vendor: Some("Apple".to_string()),
-            is_randomized: false,
             response_time_ms: Some(10),
             ttl: Some(64),
             os_guess: Some("macOS".to_string()),
             device_type: "PC".to_string(),
             risk_score: 10,
-            open_ports: vec![],
             discovery_method: "ARP+ICMP".to_string(),
             hostname: Some("macbook".to_string()),
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
         HostInfo {
             ip: "192.168.1.101".to_string(),
@@ -51,15 +44,11 @@ fn main() {
             is_randomized: true,
             response_time_ms: Some(15),
             ttl: Some(64),
-            os_guess: None,
             device_type: "UNKNOWN".to_string(),
             risk_score: 55, // High risk!
             open_ports: vec![23, 3389], // Telnet + RDP
             discovery_method: "ARP".to_string(),
-            hostname: None,
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
         HostInfo {
             ip: "192.168.1.102".to_string(),
@@ -71,12 +60,9 @@ fn main() {
             os_guess: Some("Android".to_string()),
             device_type: "MOBILE".to_string(),
             risk_score: 20,
-            open_ports: vec![],
             discovery_method: "ARP+ICMP".to_string(),
             hostname: Some("galaxy-s21".to_string()),
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         },
     ];
 
@@ -2,15 +2,32 @@
 
 use std::time::Duration;
 
+use crate::scanner::RouterKind;
+
 /// Maximum concurrent ping operations (increased for speed)
+/// Default when the runtime limit cannot be detected, see `scanner::concurrency_limits`
 pub const MAX_CONCURRENT_PINGS: usize = 200;
 
+/// Maximum concurrent TCP connect attempts
+/// Default when the runtime limit cannot be detected, see `scanner::concurrency_limits`
+pub const MAX_CONCURRENT_TCP_CONNECTS: usize = 200;
+
 /// Timeout for each ICMP ping request (reduced from 2s)
 pub const PING_TIMEOUT: Duration = Duration::from_millis(800);
 
 /// Number of ping retries per host (reduced from 2)
 pub const PING_RETRIES: u8 = 1;
 
+/// Number of echo replies collected per host for latency statistics (1 = first reply only)
+/// A single RTT sample is unreliable on Wi-Fi, so several are taken
+pub const PING_SAMPLES: u8 = 4;
+
+/// Delay between consecutive latency samples to the same host
+pub const PING_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Echo request payload size in bytes (56 like `ping`; 1472 fills an Ethernet frame)
+pub const ICMP_PAYLOAD_SIZE: usize = 56;
+
 /// Default subnet prefix length when interface doesn't provide one
 pub const DEFAULT_PREFIX_LEN: u8 = 24;
 
@@ -33,13 +50,49 @@ pub const ARP_IDLE_TIMEOUT_MS: u64 = 300;
 /// Number of ARP scan rounds (reduced to 1 for speed)
 pub const ARP_ROUNDS: u8 = 1;
 
+/// Packet rate shared by all active senders (ARP, TCP connect, UDP probes)
+/// Set to 0 to send as fast as the interface allows
+/// Replaced by a rate sized for the link speed when it can be detected, see `ScanConfig::size_send_rate`
+pub const SEND_RATE_PPS: u32 = 20_000;
+
+/// Packets that may be sent back-to-back before pacing kicks in
+pub const SEND_BURST: u32 = 64;
+
+/// Halve the send rate on send errors, rate limiting or reply loss, then recover
+pub const ADAPTIVE_THROTTLING_ENABLED: bool = true;
+
+/// Lowest rate adaptive throttling backs off to
+pub const MIN_SEND_RATE_PPS: u32 = 500;
+
+/// Upper bound of the random delay added before each probe send, on top of
+/// the rate limit (zero disables jitter and keeps burst sending)
+pub const SEND_JITTER: Duration = Duration::ZERO;
+
 /// TCP probe timeout (reduced from 500ms)
+/// Used for hosts whose round trip was not measured; the others get an RTT-adaptive timeout
 pub const TCP_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
 
+/// RTT-adaptive probe timeout: this multiple of the host's measured round trip...
+pub const ADAPTIVE_TIMEOUT_RTT_MULTIPLIER: u32 = 4;
+
+/// ...but never shorter than this (scheduling noise on a fast LAN)
+pub const ADAPTIVE_TIMEOUT_MIN: Duration = Duration::from_millis(50);
+
+/// ...nor longer than this (a lossy link should not stall the scan)
+pub const ADAPTIVE_TIMEOUT_MAX: Duration = Duration::from_secs(3);
+
 /// Common ports to probe for host detection (reduced list for speed)
 /// Full list: [22, 80, 443, 445, 8080, 3389, 5353, 62078]
 pub const TCP_PROBE_PORTS: &[u16] = &[22, 80, 443, 445, 3389];
 
+// ====== Packet Capture ======
+/// Receive captured frames through a memory-mapped ring on Linux (TPACKET_V3)
+/// instead of one `recv` per frame; keeps up with reply bursts on fast links
+pub const CAPTURE_RING_ENABLED: bool = true;
+
+/// Size of each capture ring in MiB (one ring per open capture)
+pub const CAPTURE_RING_MB: usize = 16;
+
 // ====== SNMP Configuration (Optional Feature) ======
 
 /// Enable SNMP enrichment for discovered hosts (disabled by default)
@@ -54,8 +107,184 @@ pub const SNMP_TIMEOUT: Duration = Duration::from_secs(1);
 /// SNMP port
 pub const SNMP_PORT: u16 = 161;
 
+/// Community strings tried by the exposure check; append site-specific guesses here
+pub const SNMP_COMMUNITY_CANDIDATES: &[&str] = &["public", "private", "community", "manager", "admin", "monitor", "snmp"];
+
+/// Test accepted communities for write access by rewriting sysLocation unchanged
+/// (sends SNMP SETs to the device, so off by default)
+pub const SNMP_WRITE_CHECK_ENABLED: bool = false;
+
+// ====== Smart-Home and IoT Detection ======
+
+/// Probe hosts for Chromecast, Hue, Sonos, MQTT and Matter/Thread devices
+pub const SMART_HOME_DETECTION_ENABLED: bool = true;
+
+/// Read firmware versions from cameras, printers and smart plugs
+pub const FIRMWARE_PROBES_ENABLED: bool = true;
+
+// ====== UPnP Check ======
+
+/// Enumerate port forwards opened through the gateway's UPnP IGD service
+pub const UPNP_CHECK_ENABLED: bool = true;
+
+// ====== Exposure Checks ======
+
+/// Check hosts for cleartext and unauthenticated services (Telnet, FTP, VNC, admin panels)
+pub const EXPOSURE_CHECK_ENABLED: bool = true;
+
+// ====== DNS Server Checks ======
+
+/// Look for unexpected open resolvers and DNS servers allowing zone transfers
+pub const DNS_SERVER_CHECK_ENABLED: bool = true;
+
+// ====== Uptime Estimation ======
+
+/// Estimate uptime from TCP timestamps for hosts without SNMP (raw sockets)
+pub const TCP_UPTIME_PROBE_ENABLED: bool = true;
+
+/// Gap between the two SYN samples used to measure the timestamp clock
+pub const TCP_TIMESTAMP_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Uptime (days) beyond which a device is flagged as likely unpatched
+pub const LONG_UPTIME_DAYS: u64 = 3 * 365;
+
+// ====== Clock Skew ======
+
+/// Measure host clock skew (ICMP timestamps, HTTP Date fallback)
+pub const CLOCK_SKEW_CHECK_ENABLED: bool = true;
+
+/// Skew beyond which a host's clock is flagged
+pub const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(60);
+
+// ====== Throughput Probe Configuration (Optional Feature) ======
+
+/// Run the bandwidth micro-benchmark toward the gateway (disabled by default)
+pub const THROUGHPUT_PROBE_ENABLED: bool = false;
+
+/// Upper bound on the time spent transferring data
+pub const THROUGHPUT_MAX_DURATION: Duration = Duration::from_secs(3);
+
+/// Upper bound on the bytes transferred in one probe
+pub const THROUGHPUT_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+// ====== Scan Profiles ======
+
+/// Send rate of the stealth profile's active sweep
+pub const STEALTH_SEND_RATE_PPS: u32 = 50;
+
+/// Send jitter of the stealth profile
+pub const STEALTH_SEND_JITTER: Duration = Duration::from_millis(40);
+
+/// How long the stealth profile listens before sending anything
+pub const STEALTH_PASSIVE_LISTEN: Duration = Duration::from_secs(60);
+
+// ====== Checkpointing ======
+
+/// Addresses per checkpointed chunk (ARP sweep plus port probes)
+pub const CHECKPOINT_CHUNK_SIZE: usize = 1024;
+
+// ====== Router Import Configuration ======
+
+/// Router to import ARP/DHCP tables from over SSH (`user@host`), disabled when `None`
+pub const ROUTER_IMPORT_TARGET: Option<(&str, RouterKind)> = None;
+
+/// Upper bound on one SSH command run on a router during table import
+pub const ROUTER_SSH_TIMEOUT: Duration = Duration::from_secs(15);
+
+// ====== Link Medium Classification ======
+
+/// RTT jitter (ms) at or above which a host leans wireless
+pub const WIRELESS_JITTER_MS: f64 = 5.0;
+
+/// RTT jitter (ms) at or below which a host leans wired
+pub const WIRED_JITTER_MS: f64 = 1.0;
+
+// ====== Storm Detection ======
+
+/// Broadcast frames per second above which a broadcast storm is reported
+pub const BROADCAST_STORM_PPS: f64 = 300.0;
+
+/// Multicast frames per second (excluding broadcast) above which a storm is reported
+pub const MULTICAST_STORM_PPS: f64 = 1000.0;
+
+/// How long broadcast/multicast traffic is sampled
+pub const STORM_SAMPLE_DURATION: Duration = Duration::from_secs(5);
+
+// ====== NAT Detection Configuration ======
+
+/// STUN server asked for the public address (also the traceroute target)
+pub const NAT_STUN_SERVER: &str = "stun.l.google.com:19302";
+
+/// Hops traced when looking for a second NAT layer
+pub const NAT_TRACE_MAX_HOPS: u8 = 4;
+
+/// Look for double NAT and CGNAT after the LAN scan (disabled by default)
+pub const NAT_CHECK_ENABLED: bool = false;
+
+// ====== Captive Portal Detection ======
+
+/// Host serving the connectivity check URL (plain HTTP, answers 204)
+pub const CAPTIVE_PORTAL_CHECK_HOST: &str = "connectivitycheck.gstatic.com";
+
+/// Path of the connectivity check URL
+pub const CAPTIVE_PORTAL_CHECK_PATH: &str = "/generate_204";
+
+// ====== WAN Check Configuration (Optional Feature) ======
+
+/// Determine the public IP and upstream latency after the LAN scan (disabled by default)
+pub const WAN_CHECK_ENABLED: bool = false;
+
+/// STUN servers asked for the public address, in order
+pub const WAN_STUN_SERVERS: &[&str] = &["stun.l.google.com:19302", "stun.cloudflare.com:3478"];
+
+/// Plain-text "what is my IP" endpoints (host, path), used when STUN is blocked
+/// Fetched over plain HTTP: the crate has no TLS client, and STUN answers are preferred
+pub const WAN_IP_HTTP_ENDPOINTS: &[(&str, &str)] = &[("api.ipify.org", "/"), ("icanhazip.com", "/")];
+
+/// Upstream hosts used to measure reachability and connect latency
+pub const WAN_LATENCY_TARGETS: &[&str] = &["1.1.1.1:443", "8.8.8.8:443", "9.9.9.9:443"];
+
+// ====== Interface Selection ======
+
+/// Interface name patterns always eligible for scanning, even VPN/TAP/bridges
+/// or /32 addresses (a trailing `*` matches any suffix, e.g. `"tap-lab*"`)
+pub const INTERFACE_INCLUDE: &[&str] = &[];
+
+/// Interface name patterns never selected for scanning
+pub const INTERFACE_EXCLUDE: &[&str] = &[];
+
+// ====== Container Networks ======
+
+/// Sweep Docker/Podman bridges (docker0, br-*) separately and report their hosts
+/// as containers instead of LAN devices (disabled by default)
+pub const CONTAINER_SCAN_ENABLED: bool = false;
+
+// ====== IPv6 Router Advertisements ======
+
+/// Listen for IPv6 Router Advertisements during the scan to learn prefixes,
+/// RDNSS servers and advertising routers, and flag rogue advertisers (disabled by default)
+pub const RA_LISTEN_ENABLED: bool = false;
+
+/// How long to listen; routers advertise unsolicited every 200-600 s by default
+pub const RA_LISTEN_DURATION: Duration = Duration::from_secs(10);
+
+// ====== Service Identification ======
+
+/// Confidence from which a service guess counts as a firm identification
+/// (banners, certificates and sysDescr reach it; port conventions do not)
+pub const STRONG_SERVICE_CONFIDENCE: f32 = 0.7;
+
+// ====== Active Directory Detection ======
+
+/// Probe hosts for Kerberos/LDAP and look up the DC locator SRV records to
+/// tag the Active Directory domain on the report
+pub const AD_DETECTION_ENABLED: bool = true;
+
 // ====== Monitoring Configuration ======
 
+/// Sample internet latency every monitoring cycle and alert on deviations (disabled by default)
+pub const WAN_MONITOR_ENABLED: bool = false;
+
 /// Default monitoring interval in seconds
 pub const DEFAULT_MONITOR_INTERVAL: u64 = 60;
 
@@ -65,3 +294,18 @@ pub const MIN_MONITOR_INTERVAL: u64 = 10;
 /// Maximum monitoring interval in seconds
 pub const MAX_MONITOR_INTERVAL: u64 = 3600;
 
+/// Wait after a network change hint for DHCP and routes to settle
+pub const NETWORK_CHANGE_SETTLE: Duration = Duration::from_secs(3);
+
+/// How often interfaces are re-checked where change notifications are unavailable
+pub const NETWORK_CHANGE_POLL: Duration = Duration::from_secs(10);
+
+/// How long a cached round-trip time is reused between monitoring cycles
+pub const CACHE_RTT_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long cached open ports are reused between monitoring cycles
+pub const CACHE_PORTS_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// How long a cached reverse-DNS hostname is reused
+pub const CACHE_HOSTNAME_TTL: Duration = Duration::from_secs(3600);
+
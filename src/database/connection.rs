@@ -71,6 +71,15 @@ impl Database {
             seed_vulnerabilities(&conn)?;
             seed_port_warnings(&conn)?;
         }
+
+        // Firmware CVEs were added later, seed them separately for existing databases
+        let firmware_cve_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM firmware_cves", [], |row| row.get(0))
+            .unwrap_or(0);
+
+        if firmware_cve_count == 0 {
+            super::seed_cves::seed_firmware_vulnerabilities(&conn)?;
+        }
         
         Ok(())
     }
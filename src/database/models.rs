@@ -53,6 +53,73 @@ pub struct DeviceHistoryRecord {
     pub open_ports: Vec<u16>,
 }
 
+/// A device observation from a stored session, joined with its scan time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceObservation {
+    pub scan_id: i64,
+    pub scan_time: DateTime<Utc>,
+    pub mac: String,
+    pub ip: String,
+    pub response_time_ms: Option<i64>,
+    pub open_ports: Vec<u16>,
+}
+
+/// Per-scan aggregates used for trend analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanTrendRecord {
+    pub scan_id: i64,
+    pub scan_time: DateTime<Utc>,
+    pub total_hosts: i32,
+    /// Mean response time of online hosts (ms)
+    pub avg_latency_ms: Option<f64>,
+    /// Online hosts at or above the high-risk threshold
+    pub high_risk_hosts: i32,
+}
+
+/// Allowlist entry: a device the user has registered as known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowlistEntry {
+    pub id: i64,
+    /// Matched case-insensitively against the host MAC
+    pub mac: Option<String>,
+    /// Matched case-insensitively against the resolved hostname
+    pub hostname: Option<String>,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Expected IP to MAC binding, e.g. a DHCP reservation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacBinding {
+    pub id: i64,
+    pub ip: String,
+    /// Stored uppercase, compared case-insensitively
+    pub mac: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// User-defined tag on a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceTag {
+    pub id: i64,
+    /// Host identity (`HostInfo::identity`): uppercase MAC, or IP without one
+    pub host_id: String,
+    /// Stored lowercase, e.g. "critical", "lab"
+    pub tag: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// User-supplied friendly name and notes for a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostAnnotation {
+    /// Host identity (`HostInfo::identity`): uppercase MAC, or IP without one
+    pub host_id: String,
+    pub friendly_name: Option<String>,
+    pub notes: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// Alert record from database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlertRecord {
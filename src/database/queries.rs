@@ -2,7 +2,7 @@
 //!
 //! CRUD operations for scans, devices, and alerts
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
 
@@ -165,6 +165,44 @@ pub fn get_recent_scans(conn: &Connection, limit: i32) -> Result<Vec<ScanRecord>
     Ok(scans)
 }
 
+/// Get scan history for a single subnet, oldest first
+pub fn get_subnet_scans(conn: &Connection, subnet: &str, limit: i32) -> Result<Vec<ScanRecord>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, scan_time, interface_name, local_ip, local_mac, subnet,
+               scan_method, arp_discovered, icmp_discovered, total_hosts, duration_ms
+        FROM (
+            SELECT * FROM scans
+            WHERE subnet = ?1
+            ORDER BY scan_time DESC
+            LIMIT ?2
+        )
+        ORDER BY scan_time ASC
+        "#,
+    )?;
+
+    let scans = stmt
+        .query_map(params![subnet, limit], |row| {
+            Ok(ScanRecord {
+                id: row.get(0)?,
+                scan_time: parse_datetime(row.get::<_, String>(1)?),
+                interface_name: row.get(2)?,
+                local_ip: row.get(3)?,
+                local_mac: row.get(4)?,
+                subnet: row.get(5)?,
+                scan_method: row.get(6)?,
+                arp_discovered: row.get(7)?,
+                icmp_discovered: row.get(8)?,
+                total_hosts: row.get(9)?,
+                duration_ms: row.get(10)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(scans)
+}
+
 /// Get all devices
 pub fn get_all_devices(conn: &Connection) -> Result<Vec<DeviceRecord>> {
     let mut stmt = conn.prepare(
@@ -234,12 +272,22 @@ pub fn get_device_by_mac(conn: &Connection, mac: &str) -> Result<Option<DeviceRe
 }
 
 /// Update device custom name
+///
+/// Also stored as the host's friendly name so it carries into reports.
 pub fn update_device_name(conn: &Connection, mac: &str, custom_name: &str) -> Result<()> {
     conn.execute(
         "UPDATE devices SET custom_name = ?2 WHERE mac = ?1",
         params![mac, custom_name],
     )
     .context("Failed to update device name")?;
+    conn.execute(
+        r#"
+        INSERT INTO host_annotations (host_id, friendly_name) VALUES (?1, ?2)
+        ON CONFLICT(host_id) DO UPDATE SET friendly_name = excluded.friendly_name, updated_at = datetime('now')
+        "#,
+        params![mac.to_uppercase(), custom_name],
+    )
+    .context("Failed to update device name")?;
     Ok(())
 }
 
@@ -287,6 +335,74 @@ pub fn get_device_history(
     Ok(history)
 }
 
+/// Get online device observations from the last `sessions` scans
+pub fn get_recent_observations(conn: &Connection, sessions: i32) -> Result<Vec<DeviceObservation>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT s.id, s.scan_time, d.mac, h.ip, h.response_time_ms, h.open_ports
+        FROM device_history h
+        JOIN devices d ON d.id = h.device_id
+        JOIN scans s ON s.id = h.scan_id
+        WHERE h.is_online = 1
+          AND s.id IN (SELECT id FROM scans ORDER BY scan_time DESC LIMIT ?1)
+        ORDER BY s.scan_time ASC
+        "#,
+    )?;
+
+    let observations = stmt
+        .query_map(params![sessions], |row| {
+            let ports_str: String = row.get::<_, Option<String>>(5)?.unwrap_or_default();
+            let open_ports: Vec<u16> = ports_str
+                .split(',')
+                .filter_map(|s| s.parse().ok())
+                .collect();
+
+            Ok(DeviceObservation {
+                scan_id: row.get(0)?,
+                scan_time: parse_datetime(row.get::<_, String>(1)?),
+                mac: row.get(2)?,
+                ip: row.get(3)?,
+                response_time_ms: row.get(4)?,
+                open_ports,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(observations)
+}
+
+/// Get per-scan aggregates for the last `limit` scans, oldest first
+pub fn get_scan_trends(conn: &Connection, limit: i32) -> Result<Vec<ScanTrendRecord>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT s.id, s.scan_time, s.total_hosts,
+               AVG(h.response_time_ms),
+               COALESCE(SUM(CASE WHEN h.risk_score >= ?2 THEN 1 ELSE 0 END), 0)
+        FROM scans s
+        LEFT JOIN device_history h ON h.scan_id = s.id AND h.is_online = 1
+        WHERE s.id IN (SELECT id FROM scans ORDER BY scan_time DESC LIMIT ?1)
+        GROUP BY s.id
+        ORDER BY s.scan_time ASC
+        "#,
+    )?;
+
+    let trends = stmt
+        .query_map(params![limit, crate::alerts::HIGH_RISK_THRESHOLD], |row| {
+            Ok(ScanTrendRecord {
+                scan_id: row.get(0)?,
+                scan_time: parse_datetime(row.get::<_, String>(1)?),
+                total_hosts: row.get(2)?,
+                avg_latency_ms: row.get(3)?,
+                high_risk_hosts: row.get(4)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(trends)
+}
+
 /// Insert an alert
 pub fn insert_alert(
     conn: &Connection,
@@ -366,6 +482,206 @@ pub fn mark_all_alerts_read(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Add a device to the allowlist by MAC and/or hostname
+pub fn insert_allowlist_entry(
+    conn: &Connection,
+    mac: Option<&str>,
+    hostname: Option<&str>,
+    label: &str,
+) -> Result<i64> {
+    if mac.is_none() && hostname.is_none() {
+        return Err(anyhow!("Allowlist entry needs a MAC or hostname"));
+    }
+
+    conn.execute(
+        "INSERT INTO allowlist (mac, hostname, label) VALUES (?1, ?2, ?3)",
+        params![mac.map(|m| m.to_uppercase()), hostname, label],
+    )
+    .context("Failed to insert allowlist entry")?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Remove an allowlist entry
+pub fn delete_allowlist_entry(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM allowlist WHERE id = ?1", params![id])
+        .context("Failed to delete allowlist entry")?;
+    Ok(())
+}
+
+/// Get all allowlist entries
+pub fn get_allowlist(conn: &Connection) -> Result<Vec<AllowlistEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, mac, hostname, label, created_at FROM allowlist ORDER BY label",
+    )?;
+
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(AllowlistEntry {
+                id: row.get(0)?,
+                mac: row.get(1)?,
+                hostname: row.get(2)?,
+                label: row.get(3)?,
+                created_at: parse_datetime(row.get::<_, String>(4)?),
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(entries)
+}
+
+/// Set the expected MAC for an IP, replacing any existing binding for it
+pub fn upsert_mac_binding(conn: &Connection, ip: &str, mac: &str, label: Option<&str>) -> Result<i64> {
+    conn.execute(
+        r#"
+        INSERT INTO mac_bindings (ip, mac, label) VALUES (?1, ?2, ?3)
+        ON CONFLICT(ip) DO UPDATE SET mac = excluded.mac, label = excluded.label
+        "#,
+        params![ip, mac.to_uppercase(), label],
+    )
+    .context("Failed to save MAC binding")?;
+
+    let id = conn.query_row("SELECT id FROM mac_bindings WHERE ip = ?1", params![ip], |row| row.get(0))?;
+    Ok(id)
+}
+
+/// Remove an expected binding
+pub fn delete_mac_binding(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM mac_bindings WHERE id = ?1", params![id])
+        .context("Failed to delete MAC binding")?;
+    Ok(())
+}
+
+/// Get all expected bindings
+pub fn get_mac_bindings(conn: &Connection) -> Result<Vec<MacBinding>> {
+    let mut stmt = conn.prepare("SELECT id, ip, mac, label, created_at FROM mac_bindings ORDER BY ip")?;
+
+    let bindings = stmt
+        .query_map([], |row| {
+            Ok(MacBinding {
+                id: row.get(0)?,
+                ip: row.get(1)?,
+                mac: row.get(2)?,
+                label: row.get(3)?,
+                created_at: parse_datetime(row.get::<_, String>(4)?),
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(bindings)
+}
+
+/// Tag a host identity; tagging twice is a no-op
+pub fn add_device_tag(conn: &Connection, host_id: &str, tag: &str) -> Result<()> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err(anyhow!("Tag must not be empty"));
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO device_tags (host_id, tag) VALUES (?1, ?2)",
+        params![host_id.to_uppercase(), tag],
+    )
+    .context("Failed to add device tag")?;
+    Ok(())
+}
+
+/// Remove a tag from a host identity
+pub fn remove_device_tag(conn: &Connection, host_id: &str, tag: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM device_tags WHERE host_id = ?1 AND tag = ?2",
+        params![host_id.to_uppercase(), tag.trim().to_lowercase()],
+    )
+    .context("Failed to remove device tag")?;
+    Ok(())
+}
+
+/// Get all device tags
+pub fn get_device_tags(conn: &Connection) -> Result<Vec<DeviceTag>> {
+    let mut stmt = conn.prepare("SELECT id, host_id, tag, created_at FROM device_tags ORDER BY host_id, tag")?;
+
+    let tags = stmt
+        .query_map([], |row| {
+            Ok(DeviceTag {
+                id: row.get(0)?,
+                host_id: row.get(1)?,
+                tag: row.get(2)?,
+                created_at: parse_datetime(row.get::<_, String>(3)?),
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(tags)
+}
+
+/// Set the friendly name and notes of a host identity
+///
+/// Empty values clear the field; the annotation is removed once both are empty.
+pub fn set_host_annotation(
+    conn: &Connection,
+    host_id: &str,
+    friendly_name: Option<&str>,
+    notes: Option<&str>,
+) -> Result<()> {
+    let friendly_name = friendly_name.map(str::trim).filter(|n| !n.is_empty());
+    let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+    let host_id = host_id.to_uppercase();
+
+    if friendly_name.is_none() && notes.is_none() {
+        conn.execute("DELETE FROM host_annotations WHERE host_id = ?1", params![host_id])
+            .context("Failed to clear host annotation")?;
+        return Ok(());
+    }
+
+    conn.execute(
+        r#"
+        INSERT INTO host_annotations (host_id, friendly_name, notes) VALUES (?1, ?2, ?3)
+        ON CONFLICT(host_id) DO UPDATE SET
+            friendly_name = excluded.friendly_name,
+            notes = excluded.notes,
+            updated_at = datetime('now')
+        "#,
+        params![host_id, friendly_name, notes],
+    )
+    .context("Failed to save host annotation")?;
+    Ok(())
+}
+
+/// Get all host annotations
+pub fn get_host_annotations(conn: &Connection) -> Result<Vec<HostAnnotation>> {
+    let mut stmt = conn.prepare("SELECT host_id, friendly_name, notes, updated_at FROM host_annotations")?;
+
+    let annotations = stmt
+        .query_map([], |row| {
+            Ok(HostAnnotation {
+                host_id: row.get(0)?,
+                friendly_name: row.get(1)?,
+                notes: row.get(2)?,
+                updated_at: parse_datetime(row.get::<_, String>(3)?),
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(annotations)
+}
+
+/// Fill in friendly names and notes on scanned hosts from stored annotations
+pub fn apply_host_annotations(conn: &Connection, hosts: &mut [HostInfo]) -> Result<()> {
+    let annotations = get_host_annotations(conn)?;
+    for host in hosts.iter_mut() {
+        let identity = host.identity().to_uppercase();
+        if let Some(annotation) = annotations.iter().find(|a| a.host_id == identity) {
+            host.friendly_name = annotation.friendly_name.clone();
+            host.notes = annotation.notes.clone();
+        }
+    }
+    Ok(())
+}
+
 /// Get network statistics
 pub fn get_network_stats(conn: &Connection) -> Result<NetworkStats> {
     let total_devices: i64 = conn.query_row("SELECT COUNT(*) FROM devices", [], |row| row.get(0))?;
@@ -449,6 +765,7 @@ pub fn lookup_vulnerabilities(conn: &Connection, vendor: &str) -> Result<Vec<cra
                 description: row.get(1)?,
                 severity: row.get(2)?,
                 cvss_score: row.get(3)?,
+                cvss_vector: None,
             })
         })?
         .filter_map(|r| r.ok())
@@ -457,6 +774,49 @@ pub fn lookup_vulnerabilities(conn: &Connection, vendor: &str) -> Result<Vec<cra
     Ok(vulns)
 }
 
+/// Lookup CVEs fixed in a later firmware than the one a device runs
+///
+/// `vendor` is matched against the OUI vendor and the reported product,
+/// since IoT firmware often runs on another vendor's Wi-Fi module.
+pub fn lookup_firmware_vulnerabilities(
+    conn: &Connection,
+    vendor: Option<&str>,
+    firmware: &crate::models::FirmwareInfo,
+) -> Result<Vec<crate::models::VulnerabilityInfo>> {
+    let haystack = format!("{} {}", vendor.unwrap_or(""), firmware.product.as_deref().unwrap_or("")).to_lowercase();
+
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT vendor, cve_id, description, severity, cvss_score, fixed_in
+        FROM firmware_cves
+        ORDER BY cvss_score DESC NULLS LAST
+        "#,
+    )?;
+
+    let vulns = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(5)?,
+                crate::models::VulnerabilityInfo {
+                    cve_id: row.get(1)?,
+                    description: row.get(2)?,
+                    severity: row.get(3)?,
+                    cvss_score: row.get(4)?,
+                    cvss_vector: None,
+                },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .filter(|(cve_vendor, fixed_in, _)| {
+            haystack.contains(&cve_vendor.to_lowercase()) && firmware.is_older_than(fixed_in)
+        })
+        .map(|(_, _, vuln)| vuln)
+        .collect();
+
+    Ok(vulns)
+}
+
 /// Lookup port warnings for given ports
 pub fn lookup_port_warnings(conn: &Connection, ports: &[u16]) -> Result<Vec<crate::models::PortWarning>> {
     if ports.is_empty() {
@@ -509,6 +869,10 @@ mod tests {
             icmp_discovered: 3,
             total_hosts: 5,
             scan_duration_ms: 1500,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
             active_hosts: vec![],
         };
 
@@ -520,6 +884,71 @@ mod tests {
         assert_eq!(scans[0].interface_name, "eth0");
     }
 
+    #[test]
+    fn test_device_tags() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        add_device_tag(&conn, "aa:bb:cc:dd:ee:ff", " Critical ").unwrap();
+        add_device_tag(&conn, "AA:BB:CC:DD:EE:FF", "critical").unwrap();
+        add_device_tag(&conn, "AA:BB:CC:DD:EE:FF", "lab").unwrap();
+        assert!(add_device_tag(&conn, "AA:BB:CC:DD:EE:FF", "  ").is_err());
+
+        let tags = get_device_tags(&conn).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].host_id, "AA:BB:CC:DD:EE:FF");
+        assert_eq!(tags[0].tag, "critical");
+
+        remove_device_tag(&conn, "aa:bb:cc:dd:ee:ff", "LAB").unwrap();
+        assert_eq!(get_device_tags(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_host_annotations_merge_into_hosts() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        set_host_annotation(&conn, "aa:bb:cc:dd:ee:ff", Some("Bob's laptop"), Some("Loaner until June")).unwrap();
+        update_device_name(&conn, "AA:BB:CC:DD:EE:FF", "Bob's work laptop").unwrap();
+
+        let mut hosts: Vec<HostInfo> = serde_json::from_str(
+            r#"[{"ip": "192.168.1.20", "mac": "AA:BB:CC:DD:EE:FF", "device_type": "PC", "discovery_method": "ARP", "hostname": "bob-pc"},
+                {"ip": "192.168.1.21", "mac": "AA:BB:CC:DD:EE:01", "device_type": "PC", "discovery_method": "ARP", "hostname": null}]"#,
+        )
+        .unwrap();
+        apply_host_annotations(&conn, &mut hosts).unwrap();
+
+        assert_eq!(hosts[0].display_name(), Some("Bob's work laptop"));
+        assert_eq!(hosts[0].notes.as_deref(), Some("Loaner until June"));
+        assert_eq!(hosts[1].display_name(), None);
+
+        set_host_annotation(&conn, "AA:BB:CC:DD:EE:FF", None, Some("  ")).unwrap();
+        assert!(get_host_annotations(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_firmware_vulnerabilities() {
+        let db = Database::in_memory().unwrap();
+        let conn = db.connection();
+        let conn = conn.lock().unwrap();
+
+        let firmware = |version: &str| crate::models::FirmwareInfo {
+            product: Some("DS-2CD2032-I".to_string()),
+            version: version.to_string(),
+            source: "http://192.168.1.64:80/ISAPI/System/deviceInfo".to_string(),
+        };
+        let old = lookup_firmware_vulnerabilities(&conn, Some("Hangzhou Hikvision Digital Technology"), &firmware("V5.4.0"))
+            .unwrap();
+        assert_eq!(old.len(), 2);
+        assert_eq!(old[0].cve_id, "CVE-2017-7921");
+
+        let patched = lookup_firmware_vulnerabilities(&conn, Some("Hikvision"), &firmware("V5.5.800 build 210628")).unwrap();
+        assert!(patched.is_empty());
+        assert!(lookup_firmware_vulnerabilities(&conn, Some("Axis"), &firmware("V5.4.0")).unwrap().is_empty());
+    }
+
     #[test]
     fn test_network_stats() {
         let db = Database::in_memory().unwrap();
@@ -85,6 +85,19 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             UNIQUE(vendor, cve_id)
         );
 
+        -- Firmware CVEs: vulnerabilities fixed in a known firmware release
+        CREATE TABLE IF NOT EXISTS firmware_cves (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vendor TEXT NOT NULL,
+            product TEXT,
+            cve_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            cvss_score REAL,
+            fixed_in TEXT NOT NULL,
+            UNIQUE(vendor, cve_id)
+        );
+
         -- Port Warnings table: insecure port information
         CREATE TABLE IF NOT EXISTS port_warnings (
             port INTEGER PRIMARY KEY,
@@ -94,6 +107,41 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             recommendation TEXT
         );
 
+        -- Allowlist table: devices the user has registered as known
+        CREATE TABLE IF NOT EXISTS allowlist (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            mac TEXT UNIQUE,
+            hostname TEXT,
+            label TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        -- Expected IP/MAC bindings (e.g. imported DHCP reservations)
+        CREATE TABLE IF NOT EXISTS mac_bindings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ip TEXT NOT NULL UNIQUE,
+            mac TEXT NOT NULL,
+            label TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        -- User-defined tags keyed by host identity (MAC, or IP without one)
+        CREATE TABLE IF NOT EXISTS device_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(host_id, tag)
+        );
+
+        -- User annotations keyed by host identity, merged into reports
+        CREATE TABLE IF NOT EXISTS host_annotations (
+            host_id TEXT PRIMARY KEY,
+            friendly_name TEXT,
+            notes TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
         -- Indexes for performance
         CREATE INDEX IF NOT EXISTS idx_scans_time ON scans(scan_time);
         CREATE INDEX IF NOT EXISTS idx_devices_mac ON devices(mac);
@@ -104,6 +152,7 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_alerts_unread ON alerts(is_read) WHERE is_read = 0;
         CREATE INDEX IF NOT EXISTS idx_cve_vendor ON cve_cache(vendor);
         CREATE INDEX IF NOT EXISTS idx_cve_severity ON cve_cache(severity);
+        CREATE INDEX IF NOT EXISTS idx_device_tags_tag ON device_tags(tag);
         "#,
     )
     .context("Failed to create database tables")?;
@@ -116,6 +165,10 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 pub fn drop_tables(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         r#"
+        DROP TABLE IF EXISTS host_annotations;
+        DROP TABLE IF EXISTS device_tags;
+        DROP TABLE IF EXISTS mac_bindings;
+        DROP TABLE IF EXISTS allowlist;
         DROP TABLE IF EXISTS alerts;
         DROP TABLE IF EXISTS device_history;
         DROP TABLE IF EXISTS devices;
@@ -149,5 +202,10 @@ mod tests {
         assert!(tables.contains(&"devices".to_string()));
         assert!(tables.contains(&"device_history".to_string()));
         assert!(tables.contains(&"alerts".to_string()));
+        assert!(tables.contains(&"allowlist".to_string()));
+        assert!(tables.contains(&"mac_bindings".to_string()));
+        assert!(tables.contains(&"device_tags".to_string()));
+        assert!(tables.contains(&"host_annotations".to_string()));
+        assert!(tables.contains(&"firmware_cves".to_string()));
     }
 }
@@ -81,6 +81,38 @@ pub fn seed_vulnerabilities(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Seed firmware CVEs with the release that fixed them
+pub fn seed_firmware_vulnerabilities(conn: &Connection) -> Result<()> {
+    let cves = vec![
+        ("Hikvision", "IP Camera", "CVE-2017-7921",
+         "Improper authentication allows reading the configuration and user passwords",
+         "CRITICAL", 10.0, "5.4.5"),
+        ("Hikvision", "IP Camera", "CVE-2021-36260",
+         "Command injection in the web server allows unauthenticated remote code execution",
+         "CRITICAL", 9.8, "5.5.800"),
+        ("Dahua", "IP Camera", "CVE-2021-33044",
+         "Authentication bypass by crafted login packets",
+         "CRITICAL", 9.8, "2.800.0000000.17"),
+        ("Shelly", "Gen1", "CVE-2023-33383",
+         "Memory corruption in the web server through crafted JSON requests",
+         "HIGH", 7.5, "1.12.2"),
+        ("Tasmota", "Tasmota", "CVE-2021-36603",
+         "Cross-site scripting in the web UI configuration pages",
+         "MEDIUM", 6.1, "9.5.0"),
+    ];
+
+    for (vendor, product, cve_id, desc, severity, score, fixed_in) in cves {
+        conn.execute(
+            "INSERT OR IGNORE INTO firmware_cves 
+             (vendor, product, cve_id, description, severity, cvss_score, fixed_in) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![vendor, product, cve_id, desc, severity, score, fixed_in],
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Seed port warnings database
 pub fn seed_port_warnings(conn: &Connection) -> Result<()> {
     let warnings = vec![
@@ -4,6 +4,7 @@
 
 use crate::database::DeviceRecord;
 use crate::models::HostInfo;
+use crate::network::MacAddress;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use csv::Writer;
@@ -37,7 +38,7 @@ pub fn export_devices_csv(devices: &[DeviceRecord]) -> Result<String> {
 
         writer.write_record(&[
             device.last_ip.as_deref().unwrap_or("N/A"),
-            &device.mac,
+            &MacAddress::normalize(&device.mac),
             device.hostname.as_deref().unwrap_or("N/A"),
             device.custom_name.as_deref().unwrap_or(""),
             device.vendor.as_deref().unwrap_or("Unknown"),
@@ -70,6 +71,8 @@ pub fn export_hosts_csv(hosts: &[HostInfo]) -> Result<String> {
         "Open Ports",
         "Latency (ms)",
         "Is Randomized MAC",
+        "Name",
+        "Notes",
     ])?;
 
     // Write host records
@@ -88,7 +91,7 @@ pub fn export_hosts_csv(hosts: &[HostInfo]) -> Result<String> {
 
         writer.write_record(&[
             &host.ip,
-            &host.mac,
+            &MacAddress::normalize(&host.mac),
             host.hostname.as_deref().unwrap_or("N/A"),
             host.vendor.as_deref().unwrap_or("Unknown"),
             &host.device_type,
@@ -97,6 +100,8 @@ pub fn export_hosts_csv(hosts: &[HostInfo]) -> Result<String> {
             &open_ports,
             &latency,
             &host.is_randomized.to_string(),
+            host.friendly_name.as_deref().unwrap_or(""),
+            host.notes.as_deref().unwrap_or(""),
         ])?;
     }
 
@@ -133,12 +138,9 @@ mod tests {
                 risk_score: 15,
                 open_ports: vec![80, 443],
                 response_time_ms: Some(5),
-                is_randomized: false,
                 ttl: Some(64),
                 discovery_method: "ARP+ICMP+TCP".to_string(),
-                system_description: None,
-                uptime_seconds: None,
-                neighbors: vec![],
+                ..Default::default()
             },
         ];
 
@@ -146,5 +148,6 @@ mod tests {
         assert!(csv.contains("192.168.1.1"));
         assert!(csv.contains("router"));
         assert!(csv.contains("TP-Link"));
+        assert!(csv.contains("AA:BB:CC:DD:EE:FF"));
     }
 }
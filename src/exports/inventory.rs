@@ -0,0 +1,201 @@
+//! Asset inventory (CMDB) import files
+//!
+//! Writes discovered devices in the CSV layouts accepted by the NetBox bulk
+//! import forms and the phpIPAM address import, so a scan can seed the
+//! official inventory. Nothing is pushed over the network; the files are
+//! reviewed and imported by an operator.
+
+use crate::models::{HostInfo, ScanResult};
+use crate::network::MacAddress;
+use anyhow::Result;
+use csv::Writer;
+use serde::Serialize;
+
+/// NetBox bulk import files, one per object type
+///
+/// Import in field order: devices first, then interfaces (which reference
+/// devices by name), then IP addresses (which reference interfaces).
+#[derive(Debug, Clone, Serialize)]
+pub struct NetboxImport {
+    /// dcim > Devices > Import
+    pub devices: String,
+    /// dcim > Interfaces > Import
+    pub interfaces: String,
+    /// ipam > IP Addresses > Import
+    pub ip_addresses: String,
+}
+
+/// Device name in the inventory: friendly name, hostname, else derived from the IP
+fn inventory_name(host: &HostInfo) -> String {
+    match host.display_name() {
+        Some(name) => name.to_string(),
+        None => format!("host-{}", host.ip.replace(['.', ':'], "-")),
+    }
+}
+
+/// NetBox slug for a device role (e.g. "IOT_DEVICE" -> "iot-device")
+fn role_slug(device_type: &str) -> String {
+    device_type.to_lowercase().replace(['_', ' '], "-")
+}
+
+/// Prefix length of the scanned subnet ("192.168.1.0/24" -> 24)
+fn prefix_len(subnet: &str) -> u8 {
+    subnet.rsplit_once('/').and_then(|(_, len)| len.parse().ok()).unwrap_or(32)
+}
+
+/// Hosts worth recording: ones with a known MAC address
+fn inventory_hosts(scan: &ScanResult) -> impl Iterator<Item = &HostInfo> {
+    scan.active_hosts
+        .iter()
+        .filter(|h| !h.mac.is_empty() && h.mac != "00:00:00:00:00:00")
+}
+
+/// Generate NetBox CSV import files for a scan
+///
+/// Roles, manufacturers and device types are referenced by name/slug and
+/// must exist in NetBox (or be created) before the device import.
+pub fn export_netbox_csv(scan: &ScanResult, site: &str) -> Result<NetboxImport> {
+    let mut devices = Writer::from_writer(vec![]);
+    devices.write_record(["name", "role", "manufacturer", "device_type", "site", "status", "comments"])?;
+
+    let mut interfaces = Writer::from_writer(vec![]);
+    interfaces.write_record(["device", "name", "type", "mac_address", "description"])?;
+
+    let mut ip_addresses = Writer::from_writer(vec![]);
+    ip_addresses.write_record([
+        "address",
+        "status",
+        "dns_name",
+        "device",
+        "interface",
+        "is_primary",
+        "description",
+    ])?;
+
+    let prefix = prefix_len(&scan.subnet);
+    for host in inventory_hosts(scan) {
+        let name = inventory_name(host);
+        let manufacturer = host.vendor.as_deref().unwrap_or("Unknown");
+        let device_type = format!("Generic {}", host.device_type);
+
+        devices.write_record([
+            name.as_str(),
+            &role_slug(&host.device_type),
+            manufacturer,
+            &device_type,
+            site,
+            "active",
+            host.notes.as_deref().unwrap_or(""),
+        ])?;
+
+        let interface_type = match host.device_type.as_str() {
+            "MOBILE" | "LAPTOP" | "TABLET" => "ieee802.11ac",
+            _ => "1000base-t",
+        };
+        interfaces.write_record([
+            name.as_str(),
+            "eth0",
+            interface_type,
+            &MacAddress::normalize(&host.mac),
+            &format!("Seen on {} via {}", scan.subnet, scan.interface_name),
+        ])?;
+
+        ip_addresses.write_record([
+            format!("{}/{}", host.ip, prefix).as_str(),
+            "active",
+            host.hostname.as_deref().unwrap_or(""),
+            &name,
+            "eth0",
+            "true",
+            &format!("Discovered by {}", host.discovery_method),
+        ])?;
+    }
+
+    Ok(NetboxImport {
+        devices: String::from_utf8(devices.into_inner()?)?,
+        interfaces: String::from_utf8(interfaces.into_inner()?)?,
+        ip_addresses: String::from_utf8(ip_addresses.into_inner()?)?,
+    })
+}
+
+/// Generate a phpIPAM address import file (Subnets > Import) for a scan
+pub fn export_phpipam_csv(scan: &ScanResult) -> Result<String> {
+    let mut writer = Writer::from_writer(vec![]);
+    writer.write_record(["ip", "hostname", "description", "mac", "device", "note"])?;
+
+    for host in inventory_hosts(scan) {
+        writer.write_record([
+            host.ip.as_str(),
+            host.hostname.as_deref().unwrap_or(""),
+            host.friendly_name.as_deref().or(host.vendor.as_deref()).unwrap_or(""),
+            &MacAddress::normalize(&host.mac),
+            &host.device_type,
+            host.notes.as_deref().unwrap_or(""),
+        ])?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str, device_type: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            vendor: Some("Ubiquiti Inc.".to_string()),
+            device_type: device_type.to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn scan() -> ScanResult {
+        let mut ap = host("192.168.1.2", "aa:00:00:00:00:02", "ACCESS_POINT");
+        ap.friendly_name = Some("Office AP".to_string());
+        ScanResult {
+            interface_name: "eth0".to_string(),
+            local_ip: "192.168.1.10".to_string(),
+            local_mac: "AA:00:00:00:00:10".to_string(),
+            subnet: "192.168.1.0/24".to_string(),
+            scan_method: "ARP".to_string(),
+            arp_discovered: 2,
+            icmp_discovered: 0,
+            total_hosts: 3,
+            scan_duration_ms: 1000,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
+            active_hosts: vec![
+                host("192.168.1.1", "AA:00:00:00:00:01", "ROUTER"),
+                ap,
+                host("10.9.0.1", "", "UNKNOWN"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_netbox_import_files() {
+        let import = export_netbox_csv(&scan(), "hq").unwrap();
+
+        let devices: Vec<&str> = import.devices.lines().collect();
+        assert_eq!(devices.len(), 3, "host without MAC is skipped");
+        assert_eq!(devices[1], "host-192-168-1-1,router,Ubiquiti Inc.,Generic ROUTER,hq,active,");
+        assert!(devices[2].starts_with("Office AP,access-point,"));
+        assert!(import.interfaces.contains("Office AP,eth0,1000base-t,AA:00:00:00:00:02,"));
+        assert!(import.ip_addresses.contains("192.168.1.2/24,active,,Office AP,eth0,true,"));
+    }
+
+    #[test]
+    fn test_phpipam_import_file() {
+        let csv = export_phpipam_csv(&scan()).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "ip,hostname,description,mac,device,note");
+        assert_eq!(rows[1], "192.168.1.1,,Ubiquiti Inc.,AA:00:00:00:00:01,ROUTER,");
+        assert_eq!(rows[2], "192.168.1.2,,Office AP,AA:00:00:00:00:02,ACCESS_POINT,");
+        assert_eq!(rows.len(), 3);
+    }
+}
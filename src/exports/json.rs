@@ -2,7 +2,7 @@
 //!
 //! Export scan results and topology data to JSON format
 
-use crate::models::{HostInfo, ScanResult};
+use crate::models::{HostInfo, ScanResult, Topology};
 use anyhow::Result;
 use serde::Serialize;
 use serde_json;
@@ -20,10 +20,14 @@ pub struct TopologyExport {
 /// Device node for topology
 #[derive(Debug, Serialize)]
 pub struct DeviceNode {
-    pub id: String, // MAC address
+    pub id: String, // Host identity (MAC, else IP)
     pub ip: String,
     pub mac: String,
     pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
     pub vendor: Option<String>,
     pub device_type: String,
     pub os: Option<String>,
@@ -35,20 +39,24 @@ pub struct DeviceNode {
 /// Connection between devices
 #[derive(Debug, Serialize)]
 pub struct Connection {
-    pub source: String, // MAC address
-    pub target: String, // MAC address
+    pub source: String, // Device id
+    pub target: String, // Device id
     pub connection_type: String,
 }
 
 /// Export topology data to JSON
 pub fn export_topology_json(hosts: &[HostInfo], network: &str) -> Result<String> {
-    let devices: Vec<DeviceNode> = hosts
+    let topology = Topology::from_hosts(hosts);
+
+    let mut devices: Vec<DeviceNode> = hosts
         .iter()
         .map(|h| DeviceNode {
-            id: h.mac.clone(),
+            id: h.identity(),
             ip: h.ip.clone(),
             mac: h.mac.clone(),
             hostname: h.hostname.clone(),
+            friendly_name: h.friendly_name.clone(),
+            notes: h.notes.clone(),
             vendor: h.vendor.clone(),
             device_type: h.device_type.clone(),
             os: h.os_guess.clone(),
@@ -58,21 +66,32 @@ pub fn export_topology_json(hosts: &[HostInfo], network: &str) -> Result<String>
         })
         .collect();
 
-    // Infer connections (router to all devices)
-    let router = hosts.iter().find(|h| h.device_type == "Router");
-    let connections: Vec<Connection> = if let Some(router_device) = router {
-        hosts
-            .iter()
-            .filter(|h| h.mac != router_device.mac)
-            .map(|h| Connection {
-                source: router_device.mac.clone(),
-                target: h.mac.clone(),
-                connection_type: "ethernet".to_string(),
-            })
-            .collect()
-    } else {
-        vec![]
-    };
+    // Switches/routers only known from neighbor tables, so every connection has both ends
+    devices.extend(topology.nodes.iter().filter(|n| !n.scanned).map(|n| DeviceNode {
+        id: n.id.clone(),
+        ip: n.ip.clone().unwrap_or_default(),
+        mac: String::new(),
+        hostname: Some(n.label.clone()),
+        friendly_name: None,
+        notes: None,
+        vendor: None,
+        device_type: n.kind.as_str().to_uppercase(),
+        os: None,
+        risk_score: 0,
+        open_ports: vec![],
+        is_randomized: false,
+    }));
+
+    let connections: Vec<Connection> = topology
+        .l2_edges
+        .iter()
+        .chain(&topology.l3_edges)
+        .map(|e| Connection {
+            source: e.source.clone(),
+            target: e.target.clone(),
+            connection_type: e.kind.as_str().to_string(),
+        })
+        .collect();
 
     let export = TopologyExport {
         export_date: chrono::Utc::now().to_rfc3339(),
@@ -96,33 +115,23 @@ pub fn export_scan_result_json(scan: &ScanResult) -> Result<String> {
 mod tests {
     use super::*;
 
+    fn host(ip: &str, mac: &str, hostname: &str, device_type: &str, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: device_type.to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            hostname: Some(hostname.to_string()),
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_export_topology_json() {
         let hosts = vec![
-            HostInfo {
-                ip: "192.168.1.1".to_string(),
-                mac: "aa:bb:cc:dd:ee:ff".to_string(),
-                hostname: Some("router".to_string()),
-                vendor: Some("TP-Link".to_string()),
-                device_type: "Router".to_string(),
-                os: Some("Linux".to_string()),
-                risk_score: 15,
-                open_ports: vec![80, 443],
-                latency_ms: Some(5.2),
-                is_randomized: false,
-            },
-            HostInfo {
-                ip: "192.168.1.10".to_string(),
-                mac: "11:22:33:44:55:66".to_string(),
-                hostname: Some("laptop".to_string()),
-                vendor: Some("Apple".to_string()),
-                device_type: "PC".to_string(),
-                os: Some("macOS".to_string()),
-                risk_score: 5,
-                open_ports: vec![],
-                latency_ms: Some(2.5),
-                is_randomized: false,
-            },
+            host("192.168.1.1", "aa:bb:cc:dd:ee:ff", "router", "Router", vec![80, 443]),
+            host("192.168.1.10", "11:22:33:44:55:66", "laptop", "PC", vec![]),
         ];
 
         let json = export_topology_json(&hosts, "192.168.1.0/24").unwrap();
@@ -1,11 +1,16 @@
 //! Export functionality for reports
 //!
-//! Provides PDF, CSV, and JSON export capabilities
+//! Provides PDF, CSV, JSON, Markdown/HTML report and inventory (NetBox/phpIPAM)
+//! export capabilities
 
 pub mod csv;
+pub mod inventory;
 pub mod json;
 pub mod pdf;
+pub mod report;
 
 pub use csv::*;
+pub use inventory::*;
 pub use json::*;
 pub use pdf::*;
+pub use report::*;
@@ -176,7 +176,7 @@ pub fn generate_scan_report_pdf(
 
         current_layer.use_text(&device.ip, FONT_SIZE_BODY, Mm(col1_x), Mm(y_pos), &font);
 
-        let hostname = device.hostname.as_deref().unwrap_or("N/A");
+        let hostname = device.display_name().unwrap_or("N/A");
         current_layer.use_text(hostname, FONT_SIZE_BODY, Mm(col2_x), Mm(y_pos), &font);
 
         current_layer.use_text(
@@ -357,6 +357,10 @@ mod tests {
             icmp_discovered: 1,
             total_hosts: 1,
             scan_duration_ms: 12500,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
             active_hosts: vec![],
         };
 
@@ -370,12 +374,9 @@ mod tests {
             risk_score: 15,
             open_ports: vec![80, 443],
             response_time_ms: Some(5),
-            is_randomized: false,
             ttl: Some(64),
             discovery_method: "ARP+ICMP+TCP".to_string(),
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
+            ..Default::default()
         }];
 
         let result = generate_scan_report_pdf(&scan, &devices, None);
@@ -0,0 +1,482 @@
+//! Markdown/HTML report generation
+//!
+//! Turns a scan result and its insights into a document for management or
+//! clients: summary, host table, topology diagram, findings and recommendations
+
+use crate::insights::{NetworkHealth, Recommendation, SecurityReport};
+use crate::models::{HostInfo, ScanResult, WanHealth};
+use crate::scanner::NatReport;
+use anyhow::Result;
+use chrono::Utc;
+use std::fmt::Write;
+
+/// Output format for a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// A finding row in the report
+struct FindingRow<'a> {
+    host: &'a HostInfo,
+    severity: &'a str,
+    title: String,
+    detail: &'a str,
+}
+
+/// Generate a report in the requested format
+pub fn generate_report(scan: &ScanResult, format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Markdown => generate_markdown_report(scan),
+        ReportFormat::Html => generate_html_report(scan),
+    }
+}
+
+/// Generate a Markdown report
+pub fn generate_markdown_report(scan: &ScanResult) -> Result<String> {
+    let hosts = &scan.active_hosts;
+    let mut health = NetworkHealth::calculate(hosts);
+    if let Some(wan) = &scan.wan {
+        health.correlate_wan(wan);
+    }
+    let security = SecurityReport::generate(hosts);
+    let findings = collect_findings(hosts);
+    let mut out = String::new();
+
+    writeln!(out, "# Network Scan Report\n")?;
+    writeln!(out, "Generated: {}  ", Utc::now().format("%Y-%m-%d %H:%M:%S UTC"))?;
+    writeln!(out, "Subnet: `{}` via {} ({})\n", scan.subnet, scan.interface_name, scan.scan_method)?;
+
+    writeln!(out, "## Summary\n")?;
+    writeln!(out, "| Metric | Value |")?;
+    writeln!(out, "|---|---|")?;
+    writeln!(out, "| Health | {} ({}, {}/100) |", health.grade, health.status, health.score)?;
+    writeln!(out, "| Devices | {} |", hosts.len())?;
+    writeln!(out, "| Findings | {} |", findings.len())?;
+    if let Some(wan) = &scan.wan {
+        writeln!(out, "| Internet | {} |", wan_text(wan))?;
+    }
+    if let Some(nat) = &scan.nat {
+        writeln!(out, "| NAT | {} |", nat_text(nat))?;
+    }
+    writeln!(out, "| Scan duration | {:.2}s |\n", scan.scan_duration_ms as f64 / 1000.0)?;
+    writeln!(out, "{}\n", security.summary)?;
+    for insight in &health.insights {
+        writeln!(out, "- {}", insight)?;
+    }
+
+    writeln!(out, "\n## Hosts\n")?;
+    writeln!(out, "| IP | MAC | Name | Vendor | Type | Open ports | Grade |")?;
+    writeln!(out, "|---|---|---|---|---|---|---|")?;
+    for host in hosts {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            host.ip,
+            host.mac,
+            md_cell(host.display_name().unwrap_or("-")),
+            md_cell(host.vendor.as_deref().unwrap_or("-")),
+            host.device_type,
+            ports_text(&host.open_ports),
+            if host.security_grade.is_empty() { "-" } else { host.security_grade.as_str() }
+        )?;
+    }
+
+    writeln!(out, "\n## Topology\n")?;
+    writeln!(out, "```mermaid")?;
+    writeln!(out, "graph TD")?;
+    let gateway = find_gateway(hosts);
+    for host in hosts {
+        writeln!(out, "    {}[\"{}<br/>{}\"]", node_id(host), host.ip, host.device_type)?;
+    }
+    if let Some(gw) = gateway {
+        for host in hosts.iter().filter(|h| h.mac != gw.mac) {
+            writeln!(out, "    {} --- {}", node_id(gw), node_id(host))?;
+        }
+    }
+    writeln!(out, "```")?;
+
+    writeln!(out, "\n## Findings\n")?;
+    if findings.is_empty() {
+        writeln!(out, "No vulnerabilities or insecure services found.")?;
+    } else {
+        writeln!(out, "| Severity | Host | Finding | Detail |")?;
+        writeln!(out, "|---|---|---|---|")?;
+        for f in &findings {
+            writeln!(out, "| {} | {} | {} | {} |", f.severity, f.host.ip, md_cell(&f.title), md_cell(f.detail))?;
+        }
+    }
+
+    writeln!(out, "\n## Recommendations\n")?;
+    if security.action_plan.is_empty() {
+        writeln!(out, "No action required.")?;
+    } else {
+        writeln!(out, "| # | Priority | Action | Effort | Impact | Hosts |")?;
+        writeln!(out, "|---|---|---|---|---|---|")?;
+        for item in &security.action_plan {
+            writeln!(
+                out,
+                "| {} | {} | **{}** — {} | {} | {} | {} |",
+                item.rank,
+                item.priority.as_str(),
+                md_cell(&item.title),
+                md_cell(&item.description),
+                item.effort.as_str(),
+                item.impact,
+                item.affected_hosts.len()
+            )?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Generate a self-contained HTML report (inline CSS and SVG, no external assets)
+pub fn generate_html_report(scan: &ScanResult) -> Result<String> {
+    let hosts = &scan.active_hosts;
+    let mut health = NetworkHealth::calculate(hosts);
+    if let Some(wan) = &scan.wan {
+        health.correlate_wan(wan);
+    }
+    let security = SecurityReport::generate(hosts);
+    let findings = collect_findings(hosts);
+    let mut out = String::new();
+
+    writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Network Scan Report - {}</title>", escape(&scan.subnet))?;
+    writeln!(out, "<style>{}</style>\n</head>\n<body>", HTML_STYLE)?;
+
+    writeln!(out, "<h1>Network Scan Report</h1>")?;
+    writeln!(
+        out,
+        "<p class=\"meta\">Generated {} &middot; Subnet <code>{}</code> via {} ({})</p>",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        escape(&scan.subnet),
+        escape(&scan.interface_name),
+        escape(&scan.scan_method)
+    )?;
+
+    writeln!(out, "<h2>Summary</h2>\n<div class=\"cards\">")?;
+    writeln!(out, "<div class=\"card\"><b>{}</b><span>Health ({}/100)</span></div>", health.grade, health.score)?;
+    writeln!(out, "<div class=\"card\"><b>{}</b><span>Devices</span></div>", hosts.len())?;
+    writeln!(out, "<div class=\"card\"><b>{}</b><span>Findings</span></div>", findings.len())?;
+    if let Some(wan) = &scan.wan {
+        writeln!(out, "<div class=\"card\"><b>{}</b><span>Internet</span></div>", escape(&wan_text(wan)))?;
+    }
+    if let Some(nat) = &scan.nat {
+        writeln!(out, "<div class=\"card\"><b>{}</b><span>NAT</span></div>", escape(&nat_text(nat)))?;
+    }
+    writeln!(out, "</div>\n<p>{}</p>\n<ul>", escape(&security.summary))?;
+    for insight in &health.insights {
+        writeln!(out, "<li>{}</li>", escape(insight))?;
+    }
+    writeln!(out, "</ul>")?;
+
+    writeln!(out, "<h2>Hosts</h2>\n<table>")?;
+    writeln!(out, "<tr><th>IP</th><th>MAC</th><th>Name</th><th>Vendor</th><th>Type</th><th>Open ports</th><th>Grade</th></tr>")?;
+    for host in hosts {
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&host.ip),
+            escape(&host.mac),
+            escape(host.display_name().unwrap_or("-")),
+            escape(host.vendor.as_deref().unwrap_or("-")),
+            escape(&host.device_type),
+            ports_text(&host.open_ports),
+            escape(if host.security_grade.is_empty() { "-" } else { host.security_grade.as_str() })
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    writeln!(out, "<h2>Topology</h2>")?;
+    out.push_str(&topology_svg(hosts));
+
+    writeln!(out, "<h2>Findings</h2>")?;
+    if findings.is_empty() {
+        writeln!(out, "<p>No vulnerabilities or insecure services found.</p>")?;
+    } else {
+        writeln!(out, "<table>\n<tr><th>Severity</th><th>Host</th><th>Finding</th><th>Detail</th></tr>")?;
+        for f in &findings {
+            writeln!(
+                out,
+                "<tr><td class=\"sev {}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                f.severity.to_lowercase(),
+                escape(f.severity),
+                escape(&f.host.ip),
+                escape(&f.title),
+                escape(f.detail)
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    writeln!(out, "<h2>Recommendations</h2>\n<ol>")?;
+    for rec in &security.recommendations {
+        writeln!(out, "{}", html_recommendation(rec))?;
+    }
+    writeln!(out, "</ol>\n</body>\n</html>")?;
+
+    Ok(out)
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2em auto;max-width:960px;color:#222}\
+h1{margin-bottom:0}.meta{color:#666}table{border-collapse:collapse;width:100%;margin:1em 0}\
+th,td{border:1px solid #ddd;padding:6px 8px;text-align:left;font-size:14px}th{background:#f4f4f4}\
+.cards{display:flex;gap:1em}.card{border:1px solid #ddd;border-radius:6px;padding:1em;min-width:120px}\
+.card b{display:block;font-size:28px}.card span{color:#666}\
+.sev.critical{color:#b00020;font-weight:bold}.sev.high{color:#d35400}.sev.medium{color:#b7950b}.sev.low{color:#2e7d32}";
+
+/// Gather vulnerabilities and port warnings, most severe first
+fn collect_findings(hosts: &[HostInfo]) -> Vec<FindingRow<'_>> {
+    let mut findings = Vec::new();
+
+    for host in hosts {
+        for vuln in &host.vulnerabilities {
+            findings.push(FindingRow {
+                host,
+                severity: &vuln.severity,
+                title: match vuln.cvss_score {
+                    Some(score) => format!("{} (CVSS {:.1})", vuln.cve_id, score),
+                    None => vuln.cve_id.clone(),
+                },
+                detail: &vuln.description,
+            });
+        }
+        for warning in &host.port_warnings {
+            findings.push(FindingRow {
+                host,
+                severity: &warning.severity,
+                title: format!("{} on port {}", warning.service, warning.port),
+                detail: &warning.warning,
+            });
+        }
+    }
+
+    findings.sort_by_key(|f| severity_rank(f.severity));
+    findings
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity.to_uppercase().as_str() {
+        "CRITICAL" => 0,
+        "HIGH" => 1,
+        "MEDIUM" => 2,
+        "LOW" => 3,
+        _ => 4,
+    }
+}
+
+fn find_gateway(hosts: &[HostInfo]) -> Option<&HostInfo> {
+    hosts
+        .iter()
+        .find(|h| h.device_type.eq_ignore_ascii_case("ROUTER"))
+        .or_else(|| hosts.iter().find(|h| h.ip.ends_with(".1")))
+}
+
+/// Simple star topology: gateway on top, hosts in rows below
+fn topology_svg(hosts: &[HostInfo]) -> String {
+    const PER_ROW: usize = 6;
+    const CELL_W: usize = 150;
+    const ROW_H: usize = 90;
+
+    let gateway = find_gateway(hosts);
+    let others: Vec<&HostInfo> = hosts
+        .iter()
+        .filter(|h| gateway.map(|g| g.mac != h.mac).unwrap_or(true))
+        .collect();
+    let rows = others.len().div_ceil(PER_ROW).max(1);
+    let width = CELL_W * PER_ROW;
+    let height = 100 + rows * ROW_H;
+    let gw_x = width / 2;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"11\" font-family=\"sans-serif\">\n",
+        width, height
+    );
+
+    let node = |x: usize, y: usize, host: &HostInfo, fill: &str| {
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"130\" height=\"40\" rx=\"6\" fill=\"{}\" stroke=\"#555\"/>\
+<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\
+<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#555\">{}</text>\n",
+            x - 65, y, fill, x, y + 17, escape(&host.ip), x, y + 32, escape(&host.device_type)
+        )
+    };
+
+    for (i, host) in others.iter().enumerate() {
+        let x = (i % PER_ROW) * CELL_W + CELL_W / 2;
+        let y = 100 + (i / PER_ROW) * ROW_H;
+        if gateway.is_some() {
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"50\" x2=\"{}\" y2=\"{}\" stroke=\"#aaa\"/>\n",
+                gw_x, x, y
+            ));
+        }
+        svg.push_str(&node(x, y, host, if host.risk_score >= 50 { "#fde2e2" } else { "#eef3fb" }));
+    }
+    if let Some(gw) = gateway {
+        svg.push_str(&node(gw_x, 10, gw, "#e3f5e1"));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn html_recommendation(rec: &Recommendation) -> String {
+    let affected = if rec.affected_devices.is_empty() {
+        String::new()
+    } else {
+        format!("<br><small>Affected: {}</small>", escape(&rec.affected_devices.join(", ")))
+    };
+    format!(
+        "<li><b>[{}] {}</b> &mdash; {}{}</li>",
+        rec.priority.as_str(),
+        escape(&rec.title),
+        escape(&rec.description),
+        affected
+    )
+}
+
+/// Public IP and upstream latency, e.g. "203.0.113.7, 3/3 reachable, 24 ms"
+fn wan_text(wan: &WanHealth) -> String {
+    let mut text = format!(
+        "{}, {}/{} reachable",
+        wan.public_ip.as_deref().unwrap_or("public IP unknown"),
+        wan.reachable_targets,
+        wan.total_targets
+    );
+    if let Some(ms) = wan.latency_ms {
+        text.push_str(&format!(", {:.0} ms", ms));
+    }
+    text
+}
+
+fn nat_text(nat: &NatReport) -> String {
+    let mut text = nat.kind.as_str().to_string();
+    if nat.blocks_port_forwarding() {
+        text.push_str(", inbound port forwarding blocked");
+    }
+    text
+}
+
+fn node_id(host: &HostInfo) -> String {
+    format!("h{}", host.ip.replace(['.', ':'], "_"))
+}
+
+fn ports_text(ports: &[u16]) -> String {
+    if ports.is_empty() {
+        "-".to_string()
+    } else {
+        ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PortWarning;
+
+    fn host(ip: &str, device_type: &str, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: format!("00:11:22:33:44:{:02}", ip.rsplit('.').next().unwrap().parse::<u8>().unwrap()),
+            vendor: Some("Acme <Labs>".to_string()),
+            response_time_ms: Some(3),
+            ttl: Some(64),
+            device_type: device_type.to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn scan() -> ScanResult {
+        let mut telnet = host("192.168.1.20", "IOT_DEVICE", vec![23]);
+        telnet.port_warnings.push(PortWarning {
+            port: 23,
+            service: "Telnet".to_string(),
+            warning: "Plaintext remote login".to_string(),
+            severity: "CRITICAL".to_string(),
+            recommendation: None,
+        });
+
+        ScanResult {
+            interface_name: "eth0".to_string(),
+            local_ip: "192.168.1.10".to_string(),
+            local_mac: "00:11:22:33:44:10".to_string(),
+            subnet: "192.168.1.0/24".to_string(),
+            scan_method: "ARP".to_string(),
+            arp_discovered: 2,
+            icmp_discovered: 0,
+            total_hosts: 2,
+            scan_duration_ms: 1500,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
+            active_hosts: vec![host("192.168.1.1", "ROUTER", vec![80]), telnet],
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let md = generate_report(&scan(), ReportFormat::Markdown).unwrap();
+        for section in ["## Summary", "## Hosts", "## Topology", "## Findings", "## Recommendations"] {
+            assert!(md.contains(section), "missing {}", section);
+        }
+        assert!(md.contains("h192_168_1_1 --- h192_168_1_20"));
+        assert!(md.contains("| CRITICAL | 192.168.1.20 | Telnet on port 23 |"));
+    }
+
+    #[test]
+    fn test_report_includes_wan_health() {
+        let mut scan = scan();
+        scan.wan = Some(WanHealth {
+            public_ip: Some("203.0.113.7".to_string()),
+            public_ip_source: Some("stun://stun.l.google.com:19302".to_string()),
+            reachable_targets: 0,
+            total_targets: 3,
+            latency_ms: None,
+        });
+        let md = generate_report(&scan, ReportFormat::Markdown).unwrap();
+        assert!(md.contains("| Internet | 203.0.113.7, 0/3 reachable |"));
+        assert!(md.contains("Internet unreachable"));
+
+        let hops = vec![Some("192.168.1.1".parse().unwrap()), Some("100.72.0.1".parse().unwrap())];
+        scan.nat = Some(NatReport::classify(hops, None));
+        let md = generate_report(&scan, ReportFormat::Markdown).unwrap();
+        assert!(md.contains("| NAT | cgnat, inbound port forwarding blocked |"));
+    }
+
+    #[test]
+    fn test_html_report_is_escaped_and_self_contained() {
+        let html = generate_report(&scan(), ReportFormat::Html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Acme &lt;Labs&gt;"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Allowlist-based unknown device detection
+//!
+//! Reports every host not registered as known, with whatever fingerprint data
+//! is available, escalating when the device exposes services
+
+use serde::{Deserialize, Serialize};
+
+use super::recommendations::{Priority, Recommendation};
+use crate::database::AllowlistEntry;
+use crate::HostInfo;
+
+/// Ports whose exposure on an unknown device warrants a high priority
+const SENSITIVE_PORTS: &[u16] = &[21, 22, 23, 445, 3389, 5900];
+
+/// A host that is not on the allowlist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownDevice {
+    pub ip: String,
+    pub mac: String,
+    pub vendor: Option<String>,
+    pub hostname: Option<String>,
+    pub device_type: String,
+    pub os_guess: Option<String>,
+    pub open_ports: Vec<u16>,
+    /// One-line summary of the available fingerprint data
+    pub fingerprint: String,
+    pub priority: Priority,
+}
+
+/// Hosts checked against the allowlist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowlistReport {
+    pub known: usize,
+    pub unknown: Vec<UnknownDevice>,
+    pub summary: String,
+}
+
+impl AllowlistReport {
+    /// Check hosts against allowlist entries (MAC or hostname match)
+    pub fn analyze(hosts: &[HostInfo], allowlist: &[AllowlistEntry]) -> Self {
+        let mut unknown: Vec<UnknownDevice> = hosts
+            .iter()
+            .filter(|h| !is_allowed(h, allowlist))
+            .map(unknown_device)
+            .collect();
+        unknown.sort_by_key(|d| d.priority as u8);
+
+        let known = hosts.len() - unknown.len();
+        let summary = if unknown.is_empty() {
+            format!("✅ All {} device(s) are on the allowlist", known)
+        } else {
+            let exposing = unknown.iter().filter(|d| !d.open_ports.is_empty()).count();
+            format!(
+                "⚠️ {} device(s) not on the allowlist, {} exposing services",
+                unknown.len(),
+                exposing
+            )
+        };
+
+        Self {
+            known,
+            unknown,
+            summary,
+        }
+    }
+
+    /// One recommendation per priority level with unknown devices
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        let levels = [Priority::High, Priority::Medium, Priority::Low];
+
+        levels
+            .iter()
+            .filter_map(|priority| {
+                let devices: Vec<&UnknownDevice> = self.unknown.iter().filter(|d| d.priority == *priority).collect();
+                if devices.is_empty() {
+                    return None;
+                }
+
+                let description = match priority {
+                    Priority::High => "Unregistered devices are exposing sensitive services (remote access or file sharing). Identify the owners and remove or isolate them.",
+                    Priority::Medium => "Unregistered devices are exposing network services. Confirm they are authorized and add them to the allowlist.",
+                    _ => "Devices not on the allowlist were seen. Register them if they are known, or investigate.",
+                };
+
+                Some(Recommendation {
+                    priority: *priority,
+                    category: "Unknown Devices".to_string(),
+                    title: format!("{} device(s) not on the allowlist", devices.len()),
+                    description: description.to_string(),
+                    affected_devices: devices.iter().map(|d| format!("{} ({})", d.ip, d.fingerprint)).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether a host matches any allowlist entry
+pub fn is_allowed(host: &HostInfo, allowlist: &[AllowlistEntry]) -> bool {
+    allowlist.iter().any(|entry| {
+        let mac_match = entry
+            .mac
+            .as_deref()
+            .map(|m| m.eq_ignore_ascii_case(&host.mac))
+            .unwrap_or(false);
+        let hostname_match = match (entry.hostname.as_deref(), host.hostname.as_deref()) {
+            (Some(expected), Some(actual)) => expected.eq_ignore_ascii_case(actual),
+            _ => false,
+        };
+        mac_match || hostname_match
+    })
+}
+
+fn unknown_device(host: &HostInfo) -> UnknownDevice {
+    let priority = if host.open_ports.iter().any(|p| SENSITIVE_PORTS.contains(p)) {
+        Priority::High
+    } else if !host.open_ports.is_empty() {
+        Priority::Medium
+    } else {
+        Priority::Low
+    };
+
+    let mut parts = vec![host.mac.clone()];
+    if let Some(vendor) = &host.vendor {
+        parts.push(vendor.clone());
+    }
+    if let Some(hostname) = &host.hostname {
+        parts.push(hostname.clone());
+    }
+    if host.device_type != "UNKNOWN" {
+        parts.push(host.device_type.clone());
+    }
+    if let Some(os) = &host.os_guess {
+        parts.push(os.clone());
+    }
+    if host.is_randomized {
+        parts.push("randomized MAC".to_string());
+    }
+    if !host.open_ports.is_empty() {
+        let ports: Vec<String> = host.open_ports.iter().map(|p| p.to_string()).collect();
+        parts.push(format!("ports {}", ports.join(",")));
+    }
+
+    UnknownDevice {
+        ip: host.ip.clone(),
+        mac: host.mac.clone(),
+        vendor: host.vendor.clone(),
+        hostname: host.hostname.clone(),
+        device_type: host.device_type.clone(),
+        os_guess: host.os_guess.clone(),
+        open_ports: host.open_ports.clone(),
+        fingerprint: parts.join(", "),
+        priority,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn host(ip: &str, mac: &str, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            vendor: Some("Espressif Inc.".to_string()),
+            response_time_ms: Some(4),
+            ttl: Some(64),
+            device_type: "UNKNOWN".to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn entry(mac: Option<&str>, hostname: Option<&str>) -> AllowlistEntry {
+        AllowlistEntry {
+            id: 1,
+            mac: mac.map(|m| m.to_string()),
+            hostname: hostname.map(|h| h.to_string()),
+            label: "known".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_allowlist_matching() {
+        let mut named = host("192.168.1.3", "AA:00:00:00:00:03", vec![]);
+        named.hostname = Some("NAS.local".to_string());
+        let allowlist = vec![entry(Some("aa:00:00:00:00:01"), None), entry(None, Some("nas.local"))];
+
+        assert!(is_allowed(&host("192.168.1.1", "AA:00:00:00:00:01", vec![]), &allowlist));
+        assert!(is_allowed(&named, &allowlist));
+        assert!(!is_allowed(&host("192.168.1.2", "AA:00:00:00:00:02", vec![]), &allowlist));
+    }
+
+    #[test]
+    fn test_unknown_devices_escalate_on_exposure() {
+        let hosts = vec![
+            host("192.168.1.1", "AA:00:00:00:00:01", vec![]),
+            host("192.168.1.2", "AA:00:00:00:00:02", vec![]),
+            host("192.168.1.3", "AA:00:00:00:00:03", vec![8080]),
+            host("192.168.1.4", "AA:00:00:00:00:04", vec![23]),
+        ];
+        let report = AllowlistReport::analyze(&hosts, &[entry(Some("AA:00:00:00:00:01"), None)]);
+
+        assert_eq!(report.known, 1);
+        let priorities: Vec<Priority> = report.unknown.iter().map(|d| d.priority).collect();
+        assert_eq!(priorities, vec![Priority::High, Priority::Medium, Priority::Low]);
+        assert!(report.unknown[0].fingerprint.contains("Espressif"));
+        assert_eq!(report.recommendations().len(), 3);
+    }
+}
@@ -0,0 +1,257 @@
+//! Baseline learning and anomaly detection
+//!
+//! Learns what each device normally looks like from stored sessions and flags
+//! deviations in the current scan
+
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::recommendations::Priority;
+use crate::database::DeviceObservation;
+use crate::HostInfo;
+
+/// Latency multiple over baseline that counts as an anomaly
+const LATENCY_ANOMALY_FACTOR: f64 = 10.0;
+
+/// Latencies below this are never flagged (ms), avoids noise on sub-ms baselines
+const LATENCY_ANOMALY_FLOOR_MS: f64 = 20.0;
+
+/// Sessions a device must appear in before its active hours are trusted
+const MIN_SESSIONS_FOR_HOURS: usize = 5;
+
+/// What a single device normally looks like
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceBaseline {
+    pub mac: String,
+    pub sessions_seen: usize,
+    /// Every port seen open in the baseline window
+    pub known_ports: BTreeSet<u16>,
+    /// Mean response time across sessions (ms)
+    pub avg_latency_ms: Option<f64>,
+    /// Number of sessions the device was seen in, per UTC hour of day
+    pub active_hours: [u32; 24],
+}
+
+impl DeviceBaseline {
+    /// Whether the device has been seen within one hour of `hour`
+    fn active_near(&self, hour: u32) -> bool {
+        [(hour + 23) % 24, hour, (hour + 1) % 24]
+            .iter()
+            .any(|h| self.active_hours[*h as usize] > 0)
+    }
+}
+
+/// Baseline for the whole network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkBaseline {
+    /// Number of sessions the baseline was built from
+    pub sessions: usize,
+    pub devices: HashMap<String, DeviceBaseline>,
+}
+
+impl NetworkBaseline {
+    /// Build a baseline from stored device observations
+    pub fn build(observations: &[DeviceObservation]) -> Self {
+        let sessions: HashSet<i64> = observations.iter().map(|o| o.scan_id).collect();
+        let mut devices: HashMap<String, DeviceBaseline> = HashMap::new();
+        let mut latencies: HashMap<String, Vec<f64>> = HashMap::new();
+
+        for obs in observations {
+            let device = devices.entry(obs.mac.clone()).or_insert_with(|| DeviceBaseline {
+                mac: obs.mac.clone(),
+                sessions_seen: 0,
+                known_ports: BTreeSet::new(),
+                avg_latency_ms: None,
+                active_hours: [0; 24],
+            });
+
+            device.sessions_seen += 1;
+            device.known_ports.extend(obs.open_ports.iter().copied());
+            device.active_hours[obs.scan_time.hour() as usize] += 1;
+
+            if let Some(rtt) = obs.response_time_ms {
+                latencies.entry(obs.mac.clone()).or_default().push(rtt as f64);
+            }
+        }
+
+        for (mac, samples) in latencies {
+            if let Some(device) = devices.get_mut(&mac) {
+                device.avg_latency_ms = Some(samples.iter().sum::<f64>() / samples.len() as f64);
+            }
+        }
+
+        Self {
+            sessions: sessions.len(),
+            devices,
+        }
+    }
+}
+
+/// Kind of deviation from the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Host answering on a port never seen in the baseline
+    NewPort,
+    /// Latency far above the device's baseline
+    LatencySpike,
+    /// Device active at an hour it is normally not
+    UnusualHours,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::NewPort => "NEW_PORT",
+            AnomalyKind::LatencySpike => "LATENCY_SPIKE",
+            AnomalyKind::UnusualHours => "UNUSUAL_HOURS",
+        }
+    }
+}
+
+/// A deviation from the learned baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub mac: String,
+    pub ip: String,
+    pub priority: Priority,
+    pub message: String,
+}
+
+/// Compares scans against a baseline
+pub struct AnomalyDetector<'a> {
+    baseline: &'a NetworkBaseline,
+}
+
+impl<'a> AnomalyDetector<'a> {
+    pub fn new(baseline: &'a NetworkBaseline) -> Self {
+        Self { baseline }
+    }
+
+    /// Detect anomalies in a scan taken at `scan_time`
+    ///
+    /// Devices absent from the baseline are skipped; new devices are reported by the alert detector.
+    pub fn detect(&self, hosts: &[HostInfo], scan_time: DateTime<Utc>) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        let hour = scan_time.hour();
+
+        for host in hosts {
+            let device = match self.baseline.devices.get(&host.mac) {
+                Some(d) => d,
+                None => continue,
+            };
+
+            for port in &host.open_ports {
+                if !device.known_ports.contains(port) {
+                    anomalies.push(Anomaly {
+                        kind: AnomalyKind::NewPort,
+                        mac: host.mac.clone(),
+                        ip: host.ip.clone(),
+                        priority: Priority::High,
+                        message: format!("{} is answering on port {} which was never open before", host.ip, port),
+                    });
+                }
+            }
+
+            let current_latency = host
+                .latency_stats
+                .as_ref()
+                .map(|s| s.avg_ms)
+                .or_else(|| host.response_time_ms.map(|ms| ms as f64));
+            if let (Some(current), Some(baseline)) = (current_latency, device.avg_latency_ms) {
+                if current >= LATENCY_ANOMALY_FLOOR_MS && current >= baseline.max(1.0) * LATENCY_ANOMALY_FACTOR {
+                    anomalies.push(Anomaly {
+                        kind: AnomalyKind::LatencySpike,
+                        mac: host.mac.clone(),
+                        ip: host.ip.clone(),
+                        priority: Priority::Medium,
+                        message: format!(
+                            "{} latency is {:.0}ms, {:.0}x its baseline of {:.1}ms",
+                            host.ip,
+                            current,
+                            current / baseline.max(1.0),
+                            baseline
+                        ),
+                    });
+                }
+            }
+
+            if device.sessions_seen >= MIN_SESSIONS_FOR_HOURS && !device.active_near(hour) {
+                anomalies.push(Anomaly {
+                    kind: AnomalyKind::UnusualHours,
+                    mac: host.mac.clone(),
+                    ip: host.ip.clone(),
+                    priority: Priority::Low,
+                    message: format!("{} is active at {:02}:00 UTC, outside its usual hours", host.ip, hour),
+                });
+            }
+        }
+
+        anomalies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn observation(scan_id: i64, hour: u32, ports: Vec<u16>) -> DeviceObservation {
+        DeviceObservation {
+            scan_id,
+            scan_time: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap() + Duration::days(scan_id),
+            mac: "AA:BB:CC:DD:EE:01".to_string(),
+            ip: "192.168.1.50".to_string(),
+            response_time_ms: Some(2),
+            open_ports: ports,
+        }
+    }
+
+    fn host(ports: Vec<u16>, rtt: u64) -> HostInfo {
+        HostInfo {
+            ip: "192.168.1.50".to_string(),
+            mac: "AA:BB:CC:DD:EE:01".to_string(),
+            response_time_ms: Some(rtt),
+            ttl: Some(64),
+            device_type: "PC".to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn baseline() -> NetworkBaseline {
+        let observations: Vec<DeviceObservation> = (0..6).map(|i| observation(i, 10, vec![22, 80])).collect();
+        NetworkBaseline::build(&observations)
+    }
+
+    #[test]
+    fn test_build_baseline() {
+        let baseline = baseline();
+        assert_eq!(baseline.sessions, 6);
+        let device = &baseline.devices["AA:BB:CC:DD:EE:01"];
+        assert_eq!(device.sessions_seen, 6);
+        assert_eq!(device.known_ports.len(), 2);
+        assert_eq!(device.avg_latency_ms, Some(2.0));
+        assert_eq!(device.active_hours[10], 6);
+    }
+
+    #[test]
+    fn test_normal_scan_has_no_anomalies() {
+        let baseline = baseline();
+        let now = Utc.with_ymd_and_hms(2024, 2, 1, 11, 0, 0).unwrap();
+        assert!(AnomalyDetector::new(&baseline).detect(&[host(vec![22], 3)], now).is_empty());
+    }
+
+    #[test]
+    fn test_detects_port_latency_and_hours() {
+        let baseline = baseline();
+        let now = Utc.with_ymd_and_hms(2024, 2, 1, 3, 0, 0).unwrap();
+        let anomalies = AnomalyDetector::new(&baseline).detect(&[host(vec![22, 4444], 50)], now);
+
+        let kinds: Vec<AnomalyKind> = anomalies.iter().map(|a| a.kind).collect();
+        assert_eq!(kinds, vec![AnomalyKind::NewPort, AnomalyKind::LatencySpike, AnomalyKind::UnusualHours]);
+    }
+}
@@ -0,0 +1,258 @@
+//! Known IP/MAC binding validation
+//!
+//! Compares observed ARP bindings with an expected table (typically DHCP
+//! reservations). A reserved IP answering from a different MAC points to ARP
+//! spoofing or a swapped device; a reserved MAC showing up on another IP
+//! means the reservation is not being honoured.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::recommendations::{Priority, Recommendation};
+use crate::database::MacBinding;
+use crate::HostInfo;
+
+/// One row of an imported binding table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BindingRow {
+    pub ip: String,
+    pub mac: String,
+    pub label: Option<String>,
+}
+
+/// Parse a binding table for import
+///
+/// Accepts `ip,mac[,label]` CSV lines and dnsmasq `dhcp-host=mac,ip[,name]`
+/// lines; blank lines and `#` comments are skipped.
+pub fn parse_binding_table(text: &str) -> Result<Vec<BindingRow>> {
+    let mut rows = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (fields, dnsmasq) = match line.strip_prefix("dhcp-host=") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        let parts: Vec<&str> = fields.split(',').map(str::trim).collect();
+        if parts.len() < 2 {
+            return Err(anyhow!("Line {}: expected an IP and a MAC", index + 1));
+        }
+
+        let (ip, mac) = if dnsmasq { (parts[1], parts[0]) } else { (parts[0], parts[1]) };
+        if ip.parse::<std::net::Ipv4Addr>().is_err() {
+            // A header row such as "ip,mac,label" is allowed on the first line
+            if index == 0 && !dnsmasq {
+                continue;
+            }
+            return Err(anyhow!("Line {}: invalid IP address '{}'", index + 1, ip));
+        }
+        if !is_mac(mac) {
+            return Err(anyhow!("Line {}: invalid MAC address '{}'", index + 1, mac));
+        }
+
+        rows.push(BindingRow {
+            ip: ip.to_string(),
+            mac: mac.to_uppercase(),
+            label: parts.get(2).filter(|l| !l.is_empty()).map(|l| l.to_string()),
+        });
+    }
+
+    Ok(rows)
+}
+
+fn is_mac(s: &str) -> bool {
+    let octets: Vec<&str> = s.split([':', '-']).collect();
+    octets.len() == 6 && octets.iter().all(|o| o.len() == 2 && u8::from_str_radix(o, 16).is_ok())
+}
+
+/// What kind of mismatch was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingViolationKind {
+    /// The reserved IP answered from an unexpected MAC
+    MacMismatch,
+    /// The reserved MAC was seen on an unexpected IP
+    IpMismatch,
+}
+
+impl BindingViolationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BindingViolationKind::MacMismatch => "mac_mismatch",
+            BindingViolationKind::IpMismatch => "ip_mismatch",
+        }
+    }
+}
+
+/// A binding that disagrees with the expected table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingViolation {
+    pub kind: BindingViolationKind,
+    pub observed_ip: String,
+    pub observed_mac: String,
+    pub expected_ip: String,
+    pub expected_mac: String,
+    pub label: Option<String>,
+    pub priority: Priority,
+}
+
+/// Observed bindings checked against the expected table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingReport {
+    /// Hosts whose IP and MAC both matched a binding
+    pub matched: usize,
+    pub violations: Vec<BindingViolation>,
+}
+
+impl BindingReport {
+    pub fn validate(hosts: &[HostInfo], bindings: &[MacBinding]) -> Self {
+        let mut matched = 0;
+        let mut violations = Vec::new();
+
+        for host in hosts {
+            if let Some(expected) = bindings.iter().find(|b| b.ip == host.ip) {
+                if expected.mac.eq_ignore_ascii_case(&host.mac) {
+                    matched += 1;
+                } else {
+                    violations.push(BindingViolation {
+                        kind: BindingViolationKind::MacMismatch,
+                        observed_ip: host.ip.clone(),
+                        observed_mac: host.mac.clone(),
+                        expected_ip: expected.ip.clone(),
+                        expected_mac: expected.mac.clone(),
+                        label: expected.label.clone(),
+                        priority: Priority::High,
+                    });
+                }
+            }
+
+            let moved = bindings
+                .iter()
+                .find(|b| b.mac.eq_ignore_ascii_case(&host.mac) && b.ip != host.ip);
+            if let Some(expected) = moved {
+                violations.push(BindingViolation {
+                    kind: BindingViolationKind::IpMismatch,
+                    observed_ip: host.ip.clone(),
+                    observed_mac: host.mac.clone(),
+                    expected_ip: expected.ip.clone(),
+                    expected_mac: expected.mac.clone(),
+                    label: expected.label.clone(),
+                    priority: Priority::Medium,
+                });
+            }
+        }
+
+        violations.sort_by_key(|v| v.priority as u8);
+        Self { matched, violations }
+    }
+
+    /// One recommendation per violation kind
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        let mut recs = Vec::new();
+
+        let spoofed: Vec<&BindingViolation> = self
+            .violations
+            .iter()
+            .filter(|v| v.kind == BindingViolationKind::MacMismatch)
+            .collect();
+        if !spoofed.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::High,
+                category: "Address Bindings".to_string(),
+                title: format!("{} reserved IP(s) answered from an unexpected MAC", spoofed.len()),
+                description: "A reserved address is being claimed by a different device. This can be ARP spoofing \
+                              (man-in-the-middle) or a replaced device whose reservation was not updated. \
+                              Verify the device and enable Dynamic ARP Inspection where available."
+                    .to_string(),
+                affected_devices: spoofed
+                    .iter()
+                    .map(|v| format!("{} is {} (expected {})", v.observed_ip, v.observed_mac, v.expected_mac))
+                    .collect(),
+            });
+        }
+
+        let moved: Vec<&BindingViolation> = self
+            .violations
+            .iter()
+            .filter(|v| v.kind == BindingViolationKind::IpMismatch)
+            .collect();
+        if !moved.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::Medium,
+                category: "Address Bindings".to_string(),
+                title: format!("{} device(s) not on their reserved IP", moved.len()),
+                description: "Devices with a DHCP reservation are using a different address. Check for a static IP \
+                              configured on the device or a reservation missing on the DHCP server."
+                    .to_string(),
+                affected_devices: moved
+                    .iter()
+                    .map(|v| format!("{} on {} (reserved {})", v.observed_mac, v.observed_ip, v.expected_ip))
+                    .collect(),
+            });
+        }
+
+        recs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "UNKNOWN".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn binding(ip: &str, mac: &str) -> MacBinding {
+        MacBinding {
+            id: 1,
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            label: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_binding_table() {
+        let text = "ip,mac,label\n192.168.1.10,aa:bb:cc:dd:ee:01,NAS\n# comment\ndhcp-host=AA-BB-CC-DD-EE-02,192.168.1.11,printer\n";
+        let rows = parse_binding_table(text).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].mac, "AA:BB:CC:DD:EE:01");
+        assert_eq!(rows[1].ip, "192.168.1.11");
+        assert_eq!(rows[1].label.as_deref(), Some("printer"));
+
+        assert!(parse_binding_table("192.168.1.10,not-a-mac").is_err());
+    }
+
+    #[test]
+    fn test_validate_bindings() {
+        let bindings = vec![
+            binding("192.168.1.1", "AA:00:00:00:00:01"),
+            binding("192.168.1.2", "AA:00:00:00:00:02"),
+            binding("192.168.1.3", "AA:00:00:00:00:03"),
+        ];
+        let hosts = vec![
+            host("192.168.1.1", "aa:00:00:00:00:01"),
+            host("192.168.1.2", "BB:00:00:00:00:99"),
+            host("192.168.1.50", "AA:00:00:00:00:03"),
+        ];
+
+        let report = BindingReport::validate(&hosts, &bindings);
+        assert_eq!(report.matched, 1);
+        let kinds: Vec<BindingViolationKind> = report.violations.iter().map(|v| v.kind).collect();
+        assert_eq!(kinds, vec![BindingViolationKind::MacMismatch, BindingViolationKind::IpMismatch]);
+        assert_eq!(report.recommendations().len(), 2);
+    }
+}
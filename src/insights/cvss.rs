@@ -0,0 +1,363 @@
+//! CVSS v3.1 base score calculator
+//!
+//! Parses CVSS v3.1 vector strings and computes base scores so findings can be
+//! exchanged with vulnerability management tools
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::VulnerabilityInfo;
+
+/// Vector string prefix for CVSS v3.1
+const CVSS_V31_PREFIX: &str = "CVSS:3.1";
+
+/// Attack Vector (AV)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttackVector {
+    Network,
+    Adjacent,
+    Local,
+    Physical,
+}
+
+/// Attack Complexity (AC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttackComplexity {
+    Low,
+    High,
+}
+
+/// Privileges Required (PR)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivilegesRequired {
+    None,
+    Low,
+    High,
+}
+
+/// User Interaction (UI)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserInteraction {
+    None,
+    Required,
+}
+
+/// Scope (S)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvssScope {
+    Unchanged,
+    Changed,
+}
+
+/// Confidentiality / Integrity / Availability impact (C, I, A)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvssImpact {
+    High,
+    Low,
+    None,
+}
+
+/// A parsed CVSS v3.1 base vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CvssVector {
+    pub attack_vector: AttackVector,
+    pub attack_complexity: AttackComplexity,
+    pub privileges_required: PrivilegesRequired,
+    pub user_interaction: UserInteraction,
+    pub scope: CvssScope,
+    pub confidentiality: CvssImpact,
+    pub integrity: CvssImpact,
+    pub availability: CvssImpact,
+}
+
+impl CvssVector {
+    /// Parse a vector string such as `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+    ///
+    /// The `CVSS:3.1` prefix is optional; all eight base metrics are required.
+    pub fn parse(vector: &str) -> Result<Self> {
+        let mut av = None;
+        let mut ac = None;
+        let mut pr = None;
+        let mut ui = None;
+        let mut s = None;
+        let mut c = None;
+        let mut i = None;
+        let mut a = None;
+
+        for part in vector.trim().split('/') {
+            if part.eq_ignore_ascii_case(CVSS_V31_PREFIX) {
+                continue;
+            }
+            let (metric, value) = part
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Malformed CVSS metric: {}", part))?;
+
+            match metric {
+                "AV" => av = Some(match value {
+                    "N" => AttackVector::Network,
+                    "A" => AttackVector::Adjacent,
+                    "L" => AttackVector::Local,
+                    "P" => AttackVector::Physical,
+                    _ => return Err(anyhow!("Invalid AV value: {}", value)),
+                }),
+                "AC" => ac = Some(match value {
+                    "L" => AttackComplexity::Low,
+                    "H" => AttackComplexity::High,
+                    _ => return Err(anyhow!("Invalid AC value: {}", value)),
+                }),
+                "PR" => pr = Some(match value {
+                    "N" => PrivilegesRequired::None,
+                    "L" => PrivilegesRequired::Low,
+                    "H" => PrivilegesRequired::High,
+                    _ => return Err(anyhow!("Invalid PR value: {}", value)),
+                }),
+                "UI" => ui = Some(match value {
+                    "N" => UserInteraction::None,
+                    "R" => UserInteraction::Required,
+                    _ => return Err(anyhow!("Invalid UI value: {}", value)),
+                }),
+                "S" => s = Some(match value {
+                    "U" => CvssScope::Unchanged,
+                    "C" => CvssScope::Changed,
+                    _ => return Err(anyhow!("Invalid S value: {}", value)),
+                }),
+                "C" => c = Some(parse_impact(metric, value)?),
+                "I" => i = Some(parse_impact(metric, value)?),
+                "A" => a = Some(parse_impact(metric, value)?),
+                // Temporal/environmental metrics do not affect the base score
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            attack_vector: av.ok_or_else(|| anyhow!("Missing AV metric"))?,
+            attack_complexity: ac.ok_or_else(|| anyhow!("Missing AC metric"))?,
+            privileges_required: pr.ok_or_else(|| anyhow!("Missing PR metric"))?,
+            user_interaction: ui.ok_or_else(|| anyhow!("Missing UI metric"))?,
+            scope: s.ok_or_else(|| anyhow!("Missing S metric"))?,
+            confidentiality: c.ok_or_else(|| anyhow!("Missing C metric"))?,
+            integrity: i.ok_or_else(|| anyhow!("Missing I metric"))?,
+            availability: a.ok_or_else(|| anyhow!("Missing A metric"))?,
+        })
+    }
+
+    /// Compute the CVSS v3.1 base score (0.0-10.0)
+    pub fn base_score(&self) -> f32 {
+        let changed = self.scope == CvssScope::Changed;
+
+        let av = match self.attack_vector {
+            AttackVector::Network => 0.85,
+            AttackVector::Adjacent => 0.62,
+            AttackVector::Local => 0.55,
+            AttackVector::Physical => 0.2,
+        };
+        let ac = match self.attack_complexity {
+            AttackComplexity::Low => 0.77,
+            AttackComplexity::High => 0.44,
+        };
+        let pr = match (self.privileges_required, changed) {
+            (PrivilegesRequired::None, _) => 0.85,
+            (PrivilegesRequired::Low, false) => 0.62,
+            (PrivilegesRequired::Low, true) => 0.68,
+            (PrivilegesRequired::High, false) => 0.27,
+            (PrivilegesRequired::High, true) => 0.5,
+        };
+        let ui = match self.user_interaction {
+            UserInteraction::None => 0.85,
+            UserInteraction::Required => 0.62,
+        };
+
+        let iss = 1.0
+            - (1.0 - impact_weight(self.confidentiality))
+                * (1.0 - impact_weight(self.integrity))
+                * (1.0 - impact_weight(self.availability));
+
+        let impact = if changed {
+            7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+        } else {
+            6.42 * iss
+        };
+        let exploitability = 8.22 * av * ac * pr * ui;
+
+        if impact <= 0.0 {
+            return 0.0;
+        }
+
+        let score = if changed {
+            roundup((1.08 * (impact + exploitability)).min(10.0))
+        } else {
+            roundup((impact + exploitability).min(10.0))
+        };
+
+        score as f32
+    }
+
+    /// Qualitative severity rating for the base score
+    pub fn severity(&self) -> &'static str {
+        severity_for_score(self.base_score())
+    }
+}
+
+impl fmt::Display for CvssVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let av = match self.attack_vector {
+            AttackVector::Network => "N",
+            AttackVector::Adjacent => "A",
+            AttackVector::Local => "L",
+            AttackVector::Physical => "P",
+        };
+        let ac = match self.attack_complexity {
+            AttackComplexity::Low => "L",
+            AttackComplexity::High => "H",
+        };
+        let pr = match self.privileges_required {
+            PrivilegesRequired::None => "N",
+            PrivilegesRequired::Low => "L",
+            PrivilegesRequired::High => "H",
+        };
+        let ui = match self.user_interaction {
+            UserInteraction::None => "N",
+            UserInteraction::Required => "R",
+        };
+        let s = match self.scope {
+            CvssScope::Unchanged => "U",
+            CvssScope::Changed => "C",
+        };
+
+        write!(
+            f,
+            "{}/AV:{}/AC:{}/PR:{}/UI:{}/S:{}/C:{}/I:{}/A:{}",
+            CVSS_V31_PREFIX,
+            av,
+            ac,
+            pr,
+            ui,
+            s,
+            impact_letter(self.confidentiality),
+            impact_letter(self.integrity),
+            impact_letter(self.availability),
+        )
+    }
+}
+
+impl VulnerabilityInfo {
+    /// Attach a CVSS v3.1 vector, recomputing score and severity from it
+    pub fn with_cvss_vector(mut self, vector: &str) -> Result<Self> {
+        let parsed = CvssVector::parse(vector)?;
+        self.cvss_score = Some(parsed.base_score());
+        self.severity = parsed.severity().to_string();
+        self.cvss_vector = Some(parsed.to_string());
+        Ok(self)
+    }
+}
+
+/// Map a CVSS score to the severity labels used across findings
+///
+/// Scores of 0.0 map to "NONE".
+pub fn severity_for_score(score: f32) -> &'static str {
+    match score {
+        s if s >= 9.0 => "CRITICAL",
+        s if s >= 7.0 => "HIGH",
+        s if s >= 4.0 => "MEDIUM",
+        s if s > 0.0 => "LOW",
+        _ => "NONE",
+    }
+}
+
+fn parse_impact(metric: &str, value: &str) -> Result<CvssImpact> {
+    match value {
+        "H" => Ok(CvssImpact::High),
+        "L" => Ok(CvssImpact::Low),
+        "N" => Ok(CvssImpact::None),
+        _ => Err(anyhow!("Invalid {} value: {}", metric, value)),
+    }
+}
+
+fn impact_weight(impact: CvssImpact) -> f64 {
+    match impact {
+        CvssImpact::High => 0.56,
+        CvssImpact::Low => 0.22,
+        CvssImpact::None => 0.0,
+    }
+}
+
+fn impact_letter(impact: CvssImpact) -> &'static str {
+    match impact {
+        CvssImpact::High => "H",
+        CvssImpact::Low => "L",
+        CvssImpact::None => "N",
+    }
+}
+
+/// CVSS v3.1 "Roundup": smallest number with one decimal place >= input
+///
+/// Implemented with integer arithmetic as recommended by the specification
+/// to avoid floating point artifacts (e.g. 4.000001 -> 4.1).
+fn roundup(value: f64) -> f64 {
+    let int_input = (value * 100_000.0).round() as i64;
+    if int_input % 10_000 == 0 {
+        int_input as f64 / 100_000.0
+    } else {
+        ((int_input / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_network_vector() {
+        let v = CvssVector::parse("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H").unwrap();
+        assert_eq!(v.base_score(), 9.8);
+        assert_eq!(v.severity(), "CRITICAL");
+    }
+
+    #[test]
+    fn test_scope_changed_caps_at_10() {
+        let v = CvssVector::parse("AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H").unwrap();
+        assert_eq!(v.base_score(), 10.0);
+    }
+
+    #[test]
+    fn test_known_scores() {
+        let cases = [
+            ("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:N", 5.9),
+            ("CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H", 8.8),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:L/I:N/A:N", 5.3),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N", 0.0),
+        ];
+        for (vector, expected) in cases {
+            assert_eq!(CvssVector::parse(vector).unwrap().base_score(), expected, "{}", vector);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_errors() {
+        let vector = "CVSS:3.1/AV:A/AC:L/PR:N/UI:R/S:U/C:H/I:L/A:N";
+        assert_eq!(CvssVector::parse(vector).unwrap().to_string(), vector);
+        assert!(CvssVector::parse("CVSS:3.1/AV:N/AC:L").is_err());
+        assert!(CvssVector::parse("CVSS:3.1/AV:X/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H").is_err());
+    }
+
+    #[test]
+    fn test_vulnerability_with_vector() {
+        let vuln = VulnerabilityInfo {
+            cve_id: "CVE-TELNET-001".to_string(),
+            description: "Cleartext protocol".to_string(),
+            severity: "LOW".to_string(),
+            cvss_score: None,
+            cvss_vector: None,
+        }
+        .with_cvss_vector("AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N")
+        .unwrap();
+
+        assert_eq!(vuln.cvss_score, Some(7.5));
+        assert_eq!(vuln.severity, "HIGH");
+        assert_eq!(
+            vuln.cvss_vector.as_deref(),
+            Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N")
+        );
+    }
+}
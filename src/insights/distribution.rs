@@ -1,10 +1,12 @@
 //! Device distribution statistics
 //!
-//! Analyzes device type breakdown for insights
+//! Analyzes device breakdown by type, vendor, OS family and subnet, and
+//! compares it against a previous session
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use crate::HostInfo;
+use std::collections::{HashMap, HashSet};
+use crate::{HostInfo, ScanReport};
 
 /// Device distribution statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +24,11 @@ pub struct DeviceDistribution {
 }
 
 impl DeviceDistribution {
+    /// Distribution over a multi-interface report, each device counted once
+    pub fn from_report(report: &ScanReport) -> Self {
+        Self::calculate(&report.unique_hosts())
+    }
+
     /// Calculate distribution from scan results
     pub fn calculate(hosts: &[HostInfo]) -> Self {
         let total = hosts.len();
@@ -130,3 +137,348 @@ impl VendorDistribution {
         }
     }
 }
+
+/// Breakdown of hosts across every distribution dimension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionBreakdown {
+    pub total: usize,
+    pub by_type: HashMap<String, usize>,
+    pub by_vendor: HashMap<String, usize>,
+    pub by_os_family: HashMap<String, usize>,
+    pub by_subnet: HashMap<String, usize>,
+}
+
+impl DistributionBreakdown {
+    pub fn calculate(hosts: &[HostInfo]) -> Self {
+        let mut by_type: HashMap<String, usize> = HashMap::new();
+        let mut by_vendor: HashMap<String, usize> = HashMap::new();
+        let mut by_os_family: HashMap<String, usize> = HashMap::new();
+        let mut by_subnet: HashMap<String, usize> = HashMap::new();
+
+        for host in hosts {
+            *by_type.entry(host.device_type.clone()).or_insert(0) += 1;
+            *by_vendor.entry(vendor_key(host)).or_insert(0) += 1;
+            *by_os_family.entry(os_family(host).to_string()).or_insert(0) += 1;
+            *by_subnet.entry(subnet_key(&host.ip)).or_insert(0) += 1;
+        }
+
+        Self {
+            total: hosts.len(),
+            by_type,
+            by_vendor,
+            by_os_family,
+            by_subnet,
+        }
+    }
+
+    fn dimension(&self, dimension: DistributionDimension) -> &HashMap<String, usize> {
+        match dimension {
+            DistributionDimension::DeviceType => &self.by_type,
+            DistributionDimension::Vendor => &self.by_vendor,
+            DistributionDimension::OsFamily => &self.by_os_family,
+            DistributionDimension::Subnet => &self.by_subnet,
+        }
+    }
+}
+
+/// Dimension a distribution change belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionDimension {
+    DeviceType,
+    Vendor,
+    OsFamily,
+    Subnet,
+}
+
+impl DistributionDimension {
+    pub const ALL: [DistributionDimension; 4] = [
+        DistributionDimension::DeviceType,
+        DistributionDimension::Vendor,
+        DistributionDimension::OsFamily,
+        DistributionDimension::Subnet,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DistributionDimension::DeviceType => "device_type",
+            DistributionDimension::Vendor => "vendor",
+            DistributionDimension::OsFamily => "os_family",
+            DistributionDimension::Subnet => "subnet",
+        }
+    }
+}
+
+/// Count change for one bucket between two sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionChange {
+    pub dimension: DistributionDimension,
+    pub key: String,
+    pub previous: usize,
+    pub current: usize,
+    pub delta: i64,
+}
+
+/// Distribution compared against a previous session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionComparison {
+    pub current: DistributionBreakdown,
+    pub previous: DistributionBreakdown,
+    /// Buckets whose count changed, largest change first
+    pub changes: Vec<DistributionChange>,
+    /// MACs present now but not in the previous session
+    pub new_devices: Vec<String>,
+    /// MACs present in the previous session but not now
+    pub missing_devices: Vec<String>,
+    /// Human readable highlights, e.g. "3 new Espressif devices since last week"
+    pub highlights: Vec<String>,
+}
+
+impl DistributionComparison {
+    /// Compare the current hosts with a previous session's hosts
+    ///
+    /// `previous_time` is when the previous session ran, used to phrase highlights
+    pub fn compare(
+        current: &[HostInfo],
+        previous: &[HostInfo],
+        previous_time: Option<DateTime<Utc>>,
+    ) -> Self {
+        let current_breakdown = DistributionBreakdown::calculate(current);
+        let previous_breakdown = DistributionBreakdown::calculate(previous);
+
+        let mut changes = Vec::new();
+        for dimension in DistributionDimension::ALL {
+            let now = current_breakdown.dimension(dimension);
+            let before = previous_breakdown.dimension(dimension);
+            let keys: HashSet<&String> = now.keys().chain(before.keys()).collect();
+
+            for key in keys {
+                let current_count = *now.get(key).unwrap_or(&0);
+                let previous_count = *before.get(key).unwrap_or(&0);
+                if current_count != previous_count {
+                    changes.push(DistributionChange {
+                        dimension,
+                        key: key.clone(),
+                        previous: previous_count,
+                        current: current_count,
+                        delta: current_count as i64 - previous_count as i64,
+                    });
+                }
+            }
+        }
+        changes.sort_by(|a, b| b.delta.abs().cmp(&a.delta.abs()).then_with(|| a.key.cmp(&b.key)));
+
+        let current_macs: HashSet<&str> = current.iter().map(|h| h.mac.as_str()).collect();
+        let previous_macs: HashSet<&str> = previous.iter().map(|h| h.mac.as_str()).collect();
+
+        let new_hosts: Vec<&HostInfo> = current
+            .iter()
+            .filter(|h| !previous_macs.contains(h.mac.as_str()))
+            .collect();
+        let missing_hosts: Vec<&HostInfo> = previous
+            .iter()
+            .filter(|h| !current_macs.contains(h.mac.as_str()))
+            .collect();
+
+        let since = since_phrase(previous_time);
+        let mut highlights = Vec::new();
+        for (vendor, count) in count_by_vendor(&new_hosts) {
+            highlights.push(format!(
+                "{} new {} device{} {}",
+                count,
+                vendor,
+                if count == 1 { "" } else { "s" },
+                since
+            ));
+        }
+        for (vendor, count) in count_by_vendor(&missing_hosts) {
+            highlights.push(format!(
+                "{} {} device{} no longer seen {}",
+                count,
+                vendor,
+                if count == 1 { "" } else { "s" },
+                since
+            ));
+        }
+
+        Self {
+            current: current_breakdown,
+            previous: previous_breakdown,
+            changes,
+            new_devices: new_hosts.iter().map(|h| h.mac.clone()).collect(),
+            missing_devices: missing_hosts.iter().map(|h| h.mac.clone()).collect(),
+            highlights,
+        }
+    }
+}
+
+/// Coarse OS family from the OS guess, falling back to TTL
+///
+/// The TTL-based guesses of `guess_os_from_ttl` map one to one; other
+/// guesses (SNMP sysDescr, fingerprints) are matched word by word so
+/// "Cisco IOS" is not taken for Apple's iOS.
+pub fn os_family(host: &HostInfo) -> &'static str {
+    if let Some(guess) = host.os_guess.as_deref() {
+        match guess {
+            "Linux/Unix/macOS" => return "Linux/Unix",
+            "Windows" => return "Windows",
+            "Network Device (Router/Switch)" => return "Network OS",
+            _ => {}
+        }
+
+        let lower = guess.to_lowercase();
+        let words: Vec<&str> = lower.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+        let has = |candidates: &[&str]| words.iter().any(|w| candidates.contains(w));
+        if has(&["windows"]) {
+            return "Windows";
+        }
+        if has(&["cisco", "junos", "routeros", "network"]) {
+            return "Network OS";
+        }
+        if has(&["android"]) {
+            return "Android";
+        }
+        if has(&["linux", "unix"]) {
+            return "Linux/Unix";
+        }
+        if has(&["ios", "ipados", "macos", "apple", "darwin"]) {
+            return "Apple";
+        }
+    }
+
+    match host.ttl {
+        Some(ttl) if ttl > 128 => "Network OS",
+        Some(ttl) if ttl > 64 => "Windows",
+        Some(_) => "Linux/Unix",
+        None => "Unknown",
+    }
+}
+
+/// /24 bucket for an IPv4 address
+fn subnet_key(ip: &str) -> String {
+    let octets: Vec<&str> = ip.split('.').collect();
+    if octets.len() == 4 {
+        format!("{}.{}.{}.0/24", octets[0], octets[1], octets[2])
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// Vendor name without corporate suffixes ("Espressif Inc." -> "Espressif")
+fn vendor_key(host: &HostInfo) -> String {
+    const SUFFIXES: &[&str] = &["inc", "inc.", "ltd", "ltd.", "co", "co.", "corp", "corp.", "corporation", "llc", "gmbh", "limited"];
+
+    let vendor = match host.vendor.as_deref() {
+        Some(v) if !v.trim().is_empty() => v,
+        _ => return "Unknown".to_string(),
+    };
+
+    let mut words: Vec<&str> = vendor.split_whitespace().collect();
+    while words.len() > 1 {
+        let last = words[words.len() - 1].trim_end_matches(',').to_lowercase();
+        if SUFFIXES.contains(&last.as_str()) {
+            words.pop();
+        } else {
+            break;
+        }
+    }
+    words.join(" ").trim_end_matches(',').to_string()
+}
+
+fn count_by_vendor(hosts: &[&HostInfo]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for host in hosts {
+        *counts.entry(vendor_key(host)).or_insert(0) += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+fn since_phrase(previous_time: Option<DateTime<Utc>>) -> String {
+    let previous_time = match previous_time {
+        Some(t) => t,
+        None => return "since the last session".to_string(),
+    };
+
+    let days = (Utc::now() - previous_time).num_days();
+    match days {
+        d if d < 1 => "since the last session".to_string(),
+        d if d < 2 => "since yesterday".to_string(),
+        d if d < 14 => "since last week".to_string(),
+        d if d < 60 => "since last month".to_string(),
+        _ => format!("since {}", previous_time.format("%Y-%m-%d")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::guess_os_from_ttl;
+    use chrono::Duration;
+
+    fn host(ip: &str, mac: &str, vendor: &str, os: Option<&str>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            vendor: Some(vendor.to_string()),
+            response_time_ms: Some(5),
+            ttl: Some(64),
+            os_guess: os.map(|s| s.to_string()),
+            device_type: "IOT_DEVICE".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_breakdown_dimensions() {
+        let hosts = vec![
+            host("192.168.1.10", "AA:00:00:00:00:01", "Espressif Inc.", None),
+            host("192.168.2.10", "AA:00:00:00:00:02", "Microsoft Corporation", Some("Windows 10")),
+        ];
+        let breakdown = DistributionBreakdown::calculate(&hosts);
+        assert_eq!(breakdown.by_vendor.get("Espressif"), Some(&1));
+        assert_eq!(breakdown.by_vendor.get("Microsoft"), Some(&1));
+        assert_eq!(breakdown.by_os_family.get("Windows"), Some(&1));
+        assert_eq!(breakdown.by_os_family.get("Linux/Unix"), Some(&1));
+        assert_eq!(breakdown.by_subnet.len(), 2);
+    }
+
+    #[test]
+    fn test_os_family_from_guesses() {
+        let family = |guess: &str| os_family(&host("192.168.1.10", "AA:00:00:00:00:01", "", Some(guess)));
+        assert_eq!(family(&guess_os_from_ttl(64)), "Linux/Unix");
+        assert_eq!(family(&guess_os_from_ttl(128)), "Windows");
+        assert_eq!(family(&guess_os_from_ttl(255)), "Network OS");
+        assert_eq!(family("Cisco IOS 15.2"), "Network OS");
+        assert_eq!(family("Apple iOS 17"), "Apple");
+        assert_eq!(family("Darwin Kernel Version 23.1.0"), "Apple");
+        assert_eq!(family("Ubuntu Linux 22.04"), "Linux/Unix");
+        assert_eq!(family("Android 14"), "Android");
+    }
+
+    #[test]
+    fn test_comparison_highlights_new_devices() {
+        let previous = vec![host("192.168.1.10", "AA:00:00:00:00:01", "Apple, Inc.", None)];
+        let current = vec![
+            host("192.168.1.10", "AA:00:00:00:00:01", "Apple, Inc.", None),
+            host("192.168.1.20", "AA:00:00:00:00:02", "Espressif Inc.", None),
+            host("192.168.1.21", "AA:00:00:00:00:03", "Espressif Inc.", None),
+            host("192.168.1.22", "AA:00:00:00:00:04", "Espressif Inc.", None),
+        ];
+
+        let cmp = DistributionComparison::compare(&current, &previous, Some(Utc::now() - Duration::days(7)));
+        assert_eq!(cmp.new_devices.len(), 3);
+        assert!(cmp.missing_devices.is_empty());
+        assert_eq!(cmp.highlights, vec!["3 new Espressif devices since last week".to_string()]);
+
+        let vendor_change = cmp
+            .changes
+            .iter()
+            .find(|c| c.dimension == DistributionDimension::Vendor)
+            .unwrap();
+        assert_eq!(vendor_change.key, "Espressif");
+        assert_eq!(vendor_change.delta, 3);
+    }
+}
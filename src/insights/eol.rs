@@ -0,0 +1,230 @@
+//! End-of-life device detection
+//!
+//! Flags hosts whose fingerprint points at software or hardware that no longer
+//! receives security updates
+
+use serde::{Deserialize, Serialize};
+use crate::HostInfo;
+use super::recommendations::{Priority, Recommendation};
+
+/// A rule matching an end-of-life fingerprint
+struct EolRule {
+    /// Lowercase substring matched against the host fingerprint text
+    pattern: &'static str,
+    /// Human readable product name
+    product: &'static str,
+    /// End-of-support date (informational)
+    end_of_life: &'static str,
+    /// Suggested replacement
+    replacement: &'static str,
+    priority: Priority,
+}
+
+/// Operating systems and server software past end of support
+const SOFTWARE_RULES: &[EolRule] = &[
+    EolRule { pattern: "windows xp", product: "Windows XP", end_of_life: "2014-04", replacement: "Upgrade to a supported Windows release (Windows 11)", priority: Priority::Critical },
+    EolRule { pattern: "windows 2000", product: "Windows 2000", end_of_life: "2010-07", replacement: "Replace with a supported Windows release", priority: Priority::Critical },
+    EolRule { pattern: "server 2003", product: "Windows Server 2003", end_of_life: "2015-07", replacement: "Migrate workloads to Windows Server 2022 or later", priority: Priority::Critical },
+    EolRule { pattern: "windows 7", product: "Windows 7", end_of_life: "2020-01", replacement: "Upgrade to Windows 11", priority: Priority::High },
+    EolRule { pattern: "server 2008", product: "Windows Server 2008", end_of_life: "2020-01", replacement: "Migrate workloads to Windows Server 2022 or later", priority: Priority::High },
+    EolRule { pattern: "windows 8", product: "Windows 8/8.1", end_of_life: "2023-01", replacement: "Upgrade to Windows 11", priority: Priority::High },
+    EolRule { pattern: "server 2012", product: "Windows Server 2012", end_of_life: "2023-10", replacement: "Migrate workloads to Windows Server 2022 or later", priority: Priority::High },
+    EolRule { pattern: "linux 2.4", product: "Linux kernel 2.4", end_of_life: "2011-12", replacement: "Update device firmware or replace the device", priority: Priority::High },
+    EolRule { pattern: "linux 2.6", product: "Linux kernel 2.6", end_of_life: "2016-02", replacement: "Update device firmware or replace the device", priority: Priority::Medium },
+    EolRule { pattern: "openssh_4", product: "OpenSSH 4.x", end_of_life: "2010", replacement: "Upgrade OpenSSH to a current release", priority: Priority::High },
+    EolRule { pattern: "openssh_5", product: "OpenSSH 5.x", end_of_life: "2013", replacement: "Upgrade OpenSSH to a current release", priority: Priority::High },
+    EolRule { pattern: "openssh_6", product: "OpenSSH 6.x", end_of_life: "2016", replacement: "Upgrade OpenSSH to a current release", priority: Priority::Medium },
+    EolRule { pattern: "dropbear_0.", product: "Dropbear SSH 0.x", end_of_life: "2011", replacement: "Update device firmware to a build with a current Dropbear", priority: Priority::Medium },
+    EolRule { pattern: "apache/1.", product: "Apache httpd 1.x", end_of_life: "2010-02", replacement: "Upgrade to Apache httpd 2.4", priority: Priority::High },
+    EolRule { pattern: "apache/2.2", product: "Apache httpd 2.2", end_of_life: "2017-07", replacement: "Upgrade to Apache httpd 2.4", priority: Priority::Medium },
+    EolRule { pattern: "iis/6.0", product: "Microsoft IIS 6.0", end_of_life: "2015-07", replacement: "Migrate the site to a supported IIS release", priority: Priority::Critical },
+    EolRule { pattern: "iis/7.", product: "Microsoft IIS 7.x", end_of_life: "2020-01", replacement: "Migrate the site to a supported IIS release", priority: Priority::High },
+];
+
+/// Vendors whose product lines are discontinued entirely
+const VENDOR_RULES: &[EolRule] = &[
+    EolRule { pattern: "3com", product: "3Com networking equipment", end_of_life: "2010", replacement: "Replace with currently supported switching/routing hardware", priority: Priority::Medium },
+    EolRule { pattern: "nortel", product: "Nortel equipment", end_of_life: "2009", replacement: "Replace with currently supported hardware", priority: Priority::Medium },
+    EolRule { pattern: "bay networks", product: "Bay Networks equipment", end_of_life: "1998", replacement: "Replace with currently supported hardware", priority: Priority::Medium },
+    EolRule { pattern: "sun microsystems", product: "Sun Microsystems hardware", end_of_life: "2010", replacement: "Migrate workloads to supported hardware", priority: Priority::Low },
+    EolRule { pattern: "palm", product: "Palm devices", end_of_life: "2011", replacement: "Retire the device", priority: Priority::Low },
+];
+
+/// A single end-of-life finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EolFinding {
+    pub ip: String,
+    pub mac: String,
+    /// Product identified as end-of-life
+    pub product: String,
+    /// End-of-support date
+    pub end_of_life: String,
+    /// Fingerprint text that triggered the match
+    pub evidence: String,
+    /// Suggested replacement or upgrade path
+    pub replacement: String,
+    pub priority: Priority,
+}
+
+/// End-of-life analysis for a scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EolReport {
+    pub findings: Vec<EolFinding>,
+    /// Number of distinct hosts with at least one finding
+    pub affected_hosts: usize,
+    pub summary: String,
+}
+
+impl EolReport {
+    /// Analyze scan results for end-of-life software and hardware
+    pub fn analyze(hosts: &[HostInfo]) -> Self {
+        let mut findings = Vec::new();
+
+        for host in hosts {
+            findings.extend(check_host(host));
+        }
+
+        let mut affected: Vec<&str> = findings.iter().map(|f| f.ip.as_str()).collect();
+        affected.sort_unstable();
+        affected.dedup();
+        let affected_hosts = affected.len();
+
+        let summary = if findings.is_empty() {
+            "✅ No end-of-life devices detected".to_string()
+        } else {
+            format!("⚠️ {} end-of-life component(s) on {} device(s)", findings.len(), affected_hosts)
+        };
+
+        Self {
+            findings,
+            affected_hosts,
+            summary,
+        }
+    }
+
+    /// Convert findings into recommendations, one per product
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        let mut by_product: Vec<(&EolFinding, Vec<String>)> = Vec::new();
+
+        for finding in &self.findings {
+            match by_product.iter_mut().find(|(f, _)| f.product == finding.product) {
+                Some((_, devices)) => devices.push(finding.ip.clone()),
+                None => by_product.push((finding, vec![finding.ip.clone()])),
+            }
+        }
+
+        by_product
+            .into_iter()
+            .map(|(finding, affected_devices)| Recommendation {
+                priority: finding.priority,
+                category: "End of Life".to_string(),
+                title: format!("{} is end-of-life", finding.product),
+                description: format!(
+                    "{} stopped receiving security updates ({}). {}.",
+                    finding.product, finding.end_of_life, finding.replacement
+                ),
+                affected_devices,
+            })
+            .collect()
+    }
+}
+
+/// Check a single host against all EOL rules
+pub fn check_host(host: &HostInfo) -> Vec<EolFinding> {
+    let mut findings = Vec::new();
+
+    // Software fingerprints come from SNMP sysDescr, OS guess and probed firmware
+    let firmware = host
+        .firmware
+        .as_ref()
+        .map(|f| format!("{} {}", f.product.as_deref().unwrap_or(""), f.version).trim().to_string());
+    let fingerprints = [host.system_description.as_deref(), host.os_guess.as_deref(), firmware.as_deref()];
+    for text in fingerprints.into_iter().flatten() {
+        let lower = text.to_lowercase();
+        for rule in SOFTWARE_RULES {
+            if lower.contains(rule.pattern) && !findings.iter().any(|f: &EolFinding| f.product == rule.product) {
+                findings.push(finding_for(host, rule, text));
+            }
+        }
+    }
+
+    if let Some(vendor) = host.vendor.as_deref() {
+        let lower = vendor.to_lowercase();
+        if let Some(rule) = VENDOR_RULES.iter().find(|r| lower.contains(r.pattern)) {
+            findings.push(finding_for(host, rule, vendor));
+        }
+    }
+
+    // NetBIOS session service without direct SMB hosting indicates an SMBv1-only stack
+    if host.open_ports.contains(&139) && !host.open_ports.contains(&445) {
+        findings.push(EolFinding {
+            ip: host.ip.clone(),
+            mac: host.mac.clone(),
+            product: "SMBv1-only file sharing".to_string(),
+            end_of_life: "2014".to_string(),
+            evidence: "Port 139 open without port 445".to_string(),
+            replacement: "Disable SMBv1 and replace the host with one supporting SMBv2/3".to_string(),
+            priority: Priority::High,
+        });
+    }
+
+    findings
+}
+
+fn finding_for(host: &HostInfo, rule: &EolRule, evidence: &str) -> EolFinding {
+    EolFinding {
+        ip: host.ip.clone(),
+        mac: host.mac.clone(),
+        product: rule.product.to_string(),
+        end_of_life: rule.end_of_life.to_string(),
+        evidence: evidence.to_string(),
+        replacement: rule.replacement.to_string(),
+        priority: rule.priority,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, vendor: Option<&str>, sys_descr: Option<&str>, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: "00:11:22:33:44:55".to_string(),
+            vendor: vendor.map(|v| v.to_string()),
+            response_time_ms: Some(5),
+            ttl: Some(128),
+            device_type: "PC".to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            system_description: sys_descr.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_eol_software() {
+        let h = host("192.168.1.10", None, Some("Hardware: x86 - Software: Windows XP Version 5.1"), vec![]);
+        let findings = check_host(&h);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].product, "Windows XP");
+        assert_eq!(findings[0].priority, Priority::Critical);
+    }
+
+    #[test]
+    fn test_detects_smbv1_and_vendor() {
+        let h = host("192.168.1.20", Some("3Com Corporation"), None, vec![139]);
+        let findings = check_host(&h);
+        assert_eq!(findings.len(), 2);
+
+        let report = EolReport::analyze(&[h]);
+        assert_eq!(report.affected_hosts, 1);
+        assert_eq!(report.recommendations().len(), 2);
+    }
+
+    #[test]
+    fn test_modern_host_is_clean() {
+        let h = host("192.168.1.30", Some("Dell Inc."), Some("Linux 6.1.0 OpenSSH_9.6"), vec![22, 445]);
+        assert!(check_host(&h).is_empty());
+    }
+}
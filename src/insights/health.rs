@@ -1,9 +1,40 @@
 //! Network health scoring
 //!
-//! Calculates overall network security health score
+//! Calculates overall network security health score from weighted factors
 
 use serde::{Deserialize, Serialize};
-use crate::HostInfo;
+use crate::scanner::{FrameRates, NatKind, NatReport};
+use crate::{HostInfo, WanHealth};
+
+/// Latency (ms) at or below which the latency factor scores 100
+const LATENCY_GOOD_MS: f32 = 10.0;
+
+/// Latency (ms) at or above which the latency factor scores 0
+const LATENCY_BAD_MS: f32 = 500.0;
+
+/// Latency score points deducted per percent of average packet loss
+const LOSS_PENALTY_PER_PERCENT: f32 = 2.0;
+
+/// Jitter (ms) above which a host is reported as unstable
+const JITTER_WARN_MS: f64 = 30.0;
+
+/// Packet loss (%) at or above which a host is reported as dropping packets
+const LOSS_WARN_PERCENT: f64 = 10.0;
+
+/// Average RTT (ms) above which a host is reported as slow
+const SLOW_HOST_MS: f64 = 100.0;
+
+/// Latency factor score below which latency is considered a problem
+const LATENCY_FACTOR_WARN: f32 = 60.0;
+
+/// Measured gateway throughput (Mbps) below which the link is treated as saturated
+const SATURATED_MBPS: f64 = 5.0;
+
+/// Median internet connect time (ms) above which the WAN is reported as slow
+const WAN_SLOW_MS: f64 = 150.0;
+
+/// Address space utilization above which the utilization factor starts dropping
+const UTILIZATION_COMFORT: f32 = 0.7;
 
 /// Network health status
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +47,9 @@ pub struct NetworkHealth {
     pub grade: char,
     /// Breakdown of score components
     pub breakdown: HealthBreakdown,
+    /// Per-factor scores that make up the overall score
+    #[serde(default)]
+    pub factors: Vec<HealthFactor>,
     /// Summary insights
     pub insights: Vec<String>,
 }
@@ -31,49 +65,138 @@ pub struct HealthBreakdown {
     pub compliance: u8,
 }
 
+/// Factors contributing to the health score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthFactorKind {
+    Latency,
+    Vulnerabilities,
+    UnknownDevices,
+    Utilization,
+}
+
+impl HealthFactorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthFactorKind::Latency => "latency",
+            HealthFactorKind::Vulnerabilities => "vulnerabilities",
+            HealthFactorKind::UnknownDevices => "unknown_devices",
+            HealthFactorKind::Utilization => "utilization",
+        }
+    }
+}
+
+/// User-defined weight per health factor
+///
+/// Weights are relative; they are normalized over the factors that could be
+/// evaluated, so `{1, 1, 1, 1}` and `{25, 25, 25, 25}` are equivalent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthWeights {
+    pub latency: f32,
+    pub vulnerabilities: f32,
+    pub unknown_devices: f32,
+    pub utilization: f32,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            latency: 20.0,
+            vulnerabilities: 50.0,
+            unknown_devices: 20.0,
+            utilization: 10.0,
+        }
+    }
+}
+
+impl HealthWeights {
+    fn weight_for(&self, kind: HealthFactorKind) -> f32 {
+        let w = match kind {
+            HealthFactorKind::Latency => self.latency,
+            HealthFactorKind::Vulnerabilities => self.vulnerabilities,
+            HealthFactorKind::UnknownDevices => self.unknown_devices,
+            HealthFactorKind::Utilization => self.utilization,
+        };
+        w.max(0.0)
+    }
+}
+
+/// Score of a single health factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthFactor {
+    pub kind: HealthFactorKind,
+    /// Factor score before weighting (0-100)
+    pub score: f32,
+    /// Normalized weight applied to this factor (0.0-1.0)
+    pub weight: f32,
+    /// Points this factor contributed to the overall score
+    pub contribution: f32,
+    /// Short explanation of the measured value
+    pub detail: String,
+}
+
 impl NetworkHealth {
-    /// Calculate network health from scan results
+    /// Calculate network health from scan results using default weights
     pub fn calculate(hosts: &[HostInfo]) -> Self {
+        Self::calculate_weighted(hosts, &HealthWeights::default(), None)
+    }
+
+    /// Calculate network health with user-defined factor weights
+    ///
+    /// `subnet_capacity` is the number of usable addresses in the scanned
+    /// subnet; when unknown the utilization factor is left out.
+    pub fn calculate_weighted(
+        hosts: &[HostInfo],
+        weights: &HealthWeights,
+        subnet_capacity: Option<usize>,
+    ) -> Self {
         let total = hosts.len();
         if total == 0 {
             return Self::empty();
         }
 
-        // Calculate security score (0-40 points)
-        let high_risk_count = hosts.iter()
-            .filter(|h| h.risk_score >= 50)
-            .count();
-        let medium_risk_count = hosts.iter()
-            .filter(|h| h.risk_score >= 25 && h.risk_score < 50)
-            .count();
-        
-        let security = if high_risk_count == 0 && medium_risk_count == 0 {
-            40
-        } else {
-            let penalty = (high_risk_count * 15 + medium_risk_count * 5) as u8;
-            40u8.saturating_sub(penalty)
-        };
+        let breakdown = legacy_breakdown(hosts);
 
-        // Calculate stability score (0-30 points)
-        let responsive_count = hosts.iter()
-            .filter(|h| h.response_time_ms.is_some())
-            .count();
-        let response_rate = responsive_count as f32 / total as f32;
-        let stability = (response_rate * 30.0) as u8;
+        // Evaluate each factor that has data
+        let mut raw: Vec<(HealthFactorKind, f32, String)> = Vec::new();
+        if let Some((score, detail)) = latency_factor(hosts) {
+            raw.push((HealthFactorKind::Latency, score, detail));
+        }
+        let (vuln_score, vuln_detail) = vulnerability_factor(hosts);
+        raw.push((HealthFactorKind::Vulnerabilities, vuln_score, vuln_detail));
+        let (unknown_score, unknown_detail) = unknown_device_factor(hosts);
+        raw.push((HealthFactorKind::UnknownDevices, unknown_score, unknown_detail));
+        if let Some(capacity) = subnet_capacity {
+            let (score, detail) = utilization_factor(total, capacity);
+            raw.push((HealthFactorKind::Utilization, score, detail));
+        }
 
-        // Calculate compliance score (0-30 points)
-        let randomized_count = hosts.iter()
-            .filter(|h| h.is_randomized)
-            .count();
-        let unknown_count = hosts.iter()
-            .filter(|h| h.device_type == "UNKNOWN")
-            .count();
-        let compliance_penalty = (randomized_count * 3 + unknown_count * 2) as u8;
-        let compliance = 30u8.saturating_sub(compliance_penalty);
+        let weight_sum: f32 = raw.iter().map(|(k, _, _)| weights.weight_for(*k)).sum();
+        let factors: Vec<HealthFactor> = raw
+            .into_iter()
+            .map(|(kind, score, detail)| {
+                let weight = if weight_sum > 0.0 {
+                    weights.weight_for(kind) / weight_sum
+                } else {
+                    0.0
+                };
+                HealthFactor {
+                    kind,
+                    score,
+                    weight,
+                    contribution: score * weight,
+                    detail,
+                }
+            })
+            .collect();
+
+        let score = factors
+            .iter()
+            .map(|f| f.contribution)
+            .sum::<f32>()
+            .round()
+            .clamp(0.0, 100.0) as u8;
 
-        // Total score
-        let score = security + stability + compliance;
-        
         // Determine grade
         let grade = match score {
             90..=100 => 'A',
@@ -93,9 +216,13 @@ impl NetworkHealth {
         };
 
         // Generate insights
+        let high_risk_count = hosts.iter().filter(|h| h.risk_score >= 50).count();
+        let randomized_count = hosts.iter().filter(|h| h.is_randomized).count();
+        let unknown_count = hosts.iter().filter(|h| h.device_type == "UNKNOWN").count();
+
         let mut insights = Vec::new();
         insights.push(format!("{} devices scanned", total));
-        
+
         if high_risk_count > 0 {
             insights.push(format!("⚠️ {} high-risk devices detected", high_risk_count));
         }
@@ -105,6 +232,43 @@ impl NetworkHealth {
         if unknown_count > 0 {
             insights.push(format!("❓ {} unidentified device types", unknown_count));
         }
+
+        let lossy_count = hosts
+            .iter()
+            .filter(|h| host_loss_percent(h).is_some_and(|loss| loss >= LOSS_WARN_PERCENT))
+            .count();
+        // Slow but answering reliably: usually distance or a busy link, not a fault
+        let slow_count = hosts
+            .iter()
+            .filter(|h| host_avg_ms(h).is_some_and(|ms| ms > SLOW_HOST_MS))
+            .filter(|h| host_loss_percent(h).unwrap_or(0.0) < LOSS_WARN_PERCENT)
+            .count();
+        let jittery_count = hosts
+            .iter()
+            .filter(|h| h.latency_stats.as_ref().is_some_and(|s| s.jitter_ms > JITTER_WARN_MS))
+            .count();
+        if lossy_count > 0 {
+            insights.push(format!(
+                "📶 {} devices dropping {}%+ of ping replies",
+                lossy_count, LOSS_WARN_PERCENT
+            ));
+        }
+        if slow_count > 0 {
+            insights.push(format!(
+                "🐢 {} devices slow (>{} ms) but not dropping packets",
+                slow_count, SLOW_HOST_MS
+            ));
+        }
+        if jittery_count > 0 {
+            insights.push(format!("〰️ {} devices with jitter above {} ms", jittery_count, JITTER_WARN_MS));
+        }
+        if let Some(weakest) = factors
+            .iter()
+            .filter(|f| f.weight > 0.0 && f.score < 60.0)
+            .min_by(|a, b| a.score.total_cmp(&b.score))
+        {
+            insights.push(format!("📉 Weakest factor: {} ({})", weakest.kind.as_str(), weakest.detail));
+        }
         if score >= 80 {
             insights.push("✅ Network health is good".to_string());
         }
@@ -113,15 +277,94 @@ impl NetworkHealth {
             score,
             status,
             grade,
-            breakdown: HealthBreakdown {
-                security,
-                stability,
-                compliance,
-            },
+            breakdown,
+            factors,
             insights,
         }
     }
 
+    /// Correlate poor latency with a measured gateway throughput
+    ///
+    /// Adds an insight telling whether high latency coincides with a saturated
+    /// link or persists with bandwidth to spare (interference, a busy host).
+    pub fn correlate_throughput(&mut self, gateway_mbps: f64) {
+        let latency_poor = self
+            .factors
+            .iter()
+            .any(|f| f.kind == HealthFactorKind::Latency && f.score < LATENCY_FACTOR_WARN);
+
+        let insight = match (latency_poor, gateway_mbps < SATURATED_MBPS) {
+            (true, true) => format!(
+                "🚦 High latency with only {:.1} Mbps to the gateway: the link looks saturated",
+                gateway_mbps
+            ),
+            (true, false) => format!(
+                "📡 High latency despite {:.1} Mbps to the gateway: likely interference or overloaded devices, not bandwidth",
+                gateway_mbps
+            ),
+            (false, true) => format!("🚦 Gateway throughput is low ({:.1} Mbps)", gateway_mbps),
+            (false, false) => return,
+        };
+        self.insights.push(insight);
+    }
+
+    /// Add a connectivity insight when address translation breaks inbound traffic
+    pub fn correlate_nat(&mut self, nat: &NatReport) {
+        let insight = match nat.kind {
+            NatKind::Double => "🔁 Double NAT detected: port forwarding and some VPNs will not work until one router is put in bridge mode",
+            NatKind::Carrier => "🏢 Behind carrier-grade NAT: inbound connections are impossible without an ISP public IP or a tunnel",
+            NatKind::Single | NatKind::Unknown => return,
+        };
+        self.insights.push(insight.to_string());
+    }
+
+    /// Relate LAN health to internet reachability and latency
+    pub fn correlate_wan(&mut self, wan: &WanHealth) {
+        let latency_poor = self
+            .factors
+            .iter()
+            .any(|f| f.kind == HealthFactorKind::Latency && f.score < LATENCY_FACTOR_WARN);
+
+        let insight = if wan.is_offline() {
+            "🌐 Internet unreachable while the LAN responds: the problem is upstream (modem or ISP)".to_string()
+        } else if wan.reachable_targets < wan.total_targets {
+            format!(
+                "🌐 Only {}/{} internet test targets reachable: upstream filtering or partial outage",
+                wan.reachable_targets, wan.total_targets
+            )
+        } else {
+            match wan.latency_ms {
+                Some(ms) if ms > WAN_SLOW_MS && !latency_poor => {
+                    format!("🌐 Internet latency is high ({:.0} ms) while the LAN is fast: the delay is upstream", ms)
+                }
+                _ => return,
+            }
+        };
+        self.insights.push(insight);
+    }
+
+    /// Flag broadcast or multicast rates above the storm thresholds
+    pub fn correlate_frame_rates(&mut self, rates: &FrameRates) {
+        let talker = rates
+            .top_sources
+            .first()
+            .map(|s| format!(" (top sender {} at {:.0}/s)", s.mac, s.pps))
+            .unwrap_or_default();
+
+        if rates.is_broadcast_storm() {
+            self.insights.push(format!(
+                "🌪️ Broadcast storm: {:.0} frames/s{}, check for a switching loop or a faulty NIC",
+                rates.broadcast_pps, talker
+            ));
+        }
+        if rates.is_multicast_storm() {
+            self.insights.push(format!(
+                "🌪️ Multicast flood: {:.0} frames/s{}, enable IGMP snooping or limit the sender",
+                rates.multicast_pps, talker
+            ));
+        }
+    }
+
     fn empty() -> Self {
         Self {
             score: 0,
@@ -132,7 +375,287 @@ impl NetworkHealth {
                 stability: 0,
                 compliance: 0,
             },
+            factors: Vec::new(),
             insights: vec!["No devices scanned".to_string()],
         }
     }
 }
+
+/// Security/stability/compliance point breakdown (kept for existing dashboards)
+fn legacy_breakdown(hosts: &[HostInfo]) -> HealthBreakdown {
+    let total = hosts.len();
+
+    // Security (0-40 points)
+    let high_risk_count = hosts.iter().filter(|h| h.risk_score >= 50).count();
+    let medium_risk_count = hosts
+        .iter()
+        .filter(|h| h.risk_score >= 25 && h.risk_score < 50)
+        .count();
+    let penalty = (high_risk_count * 15 + medium_risk_count * 5).min(40) as u8;
+    let security = 40u8.saturating_sub(penalty);
+
+    // Stability (0-30 points)
+    let responsive_count = hosts.iter().filter(|h| h.response_time_ms.is_some()).count();
+    let stability = (responsive_count as f32 / total as f32 * 30.0) as u8;
+
+    // Compliance (0-30 points)
+    let randomized_count = hosts.iter().filter(|h| h.is_randomized).count();
+    let unknown_count = hosts.iter().filter(|h| h.device_type == "UNKNOWN").count();
+    let compliance_penalty = (randomized_count * 3 + unknown_count * 2).min(30) as u8;
+    let compliance = 30u8.saturating_sub(compliance_penalty);
+
+    HealthBreakdown {
+        security,
+        stability,
+        compliance,
+    }
+}
+
+/// Latency factor: average RTT of responsive hosts mapped onto 0-100
+///
+/// Uses the multi-sample average when available and deducts points for
+/// packet loss, since a lossy link feels slow regardless of RTT.
+fn latency_factor(hosts: &[HostInfo]) -> Option<(f32, String)> {
+    let samples: Vec<f32> = hosts.iter().filter_map(|h| host_avg_ms(h).map(|ms| ms as f32)).collect();
+    if samples.is_empty() {
+        return None;
+    }
+
+    let avg = samples.iter().sum::<f32>() / samples.len() as f32;
+    let span = LATENCY_BAD_MS - LATENCY_GOOD_MS;
+    let mut score = (100.0 * (1.0 - (avg - LATENCY_GOOD_MS) / span)).clamp(0.0, 100.0);
+
+    let losses: Vec<f32> = hosts.iter().filter_map(|h| host_loss_percent(h).map(|l| l as f32)).collect();
+    let avg_loss = if losses.is_empty() {
+        0.0
+    } else {
+        losses.iter().sum::<f32>() / losses.len() as f32
+    };
+    score = (score - avg_loss * LOSS_PENALTY_PER_PERCENT).clamp(0.0, 100.0);
+
+    Some((
+        score,
+        format!("avg {:.1} ms, {:.1}% loss over {} hosts", avg, avg_loss, samples.len()),
+    ))
+}
+
+/// Average RTT of a host, preferring the multi-sample average
+fn host_avg_ms(host: &HostInfo) -> Option<f64> {
+    match &host.latency_stats {
+        Some(stats) if stats.received > 0 => Some(stats.avg_ms),
+        Some(_) => None,
+        None => host.response_time_ms.map(|ms| ms as f64),
+    }
+}
+
+/// Packet loss of a host, preferring the per-host probe count over the sample stats
+fn host_loss_percent(host: &HostInfo) -> Option<f64> {
+    host.packet_loss_percent
+        .or_else(|| host.latency_stats.as_ref().map(|s| s.loss_percent))
+}
+
+/// Vulnerability factor: average per-host severity penalty
+fn vulnerability_factor(hosts: &[HostInfo]) -> (f32, String) {
+    let mut total_penalty = 0u32;
+    let mut finding_count = 0usize;
+
+    for host in hosts {
+        for vuln in &host.vulnerabilities {
+            total_penalty += severity_penalty(&vuln.severity);
+            finding_count += 1;
+        }
+        for warning in &host.port_warnings {
+            total_penalty += severity_penalty(&warning.severity);
+            finding_count += 1;
+        }
+    }
+
+    let avg_penalty = total_penalty as f32 / hosts.len() as f32;
+    let score = (100.0 - avg_penalty).clamp(0.0, 100.0);
+
+    (score, format!("{} findings across {} hosts", finding_count, hosts.len()))
+}
+
+fn severity_penalty(severity: &str) -> u32 {
+    match severity.to_uppercase().as_str() {
+        "CRITICAL" => 25,
+        "HIGH" => 15,
+        "MEDIUM" => 7,
+        "LOW" => 3,
+        _ => 0,
+    }
+}
+
+/// Unknown device factor: share of hosts that could be classified
+fn unknown_device_factor(hosts: &[HostInfo]) -> (f32, String) {
+    let unknown = hosts.iter().filter(|h| h.device_type == "UNKNOWN").count();
+    let score = 100.0 * (1.0 - unknown as f32 / hosts.len() as f32);
+
+    (score, format!("{} of {} devices unidentified", unknown, hosts.len()))
+}
+
+/// Utilization factor: penalizes subnets approaching exhaustion
+fn utilization_factor(used: usize, capacity: usize) -> (f32, String) {
+    if capacity == 0 {
+        return (0.0, "subnet has no usable addresses".to_string());
+    }
+
+    let ratio = used as f32 / capacity as f32;
+    let score = if ratio <= UTILIZATION_COMFORT {
+        100.0
+    } else {
+        (100.0 * (1.0 - (ratio - UTILIZATION_COMFORT) / (1.0 - UTILIZATION_COMFORT))).clamp(0.0, 100.0)
+    };
+
+    (score, format!("{} of {} addresses in use ({:.0}%)", used, capacity, ratio * 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LatencyStats;
+    use std::net::Ipv4Addr;
+
+    fn host(device_type: &str, latency: Option<u64>) -> HostInfo {
+        HostInfo {
+            ip: "192.168.1.10".to_string(),
+            mac: "00:11:22:33:44:55".to_string(),
+            response_time_ms: latency,
+            device_type: device_type.to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_factor_breakdown_exposed() {
+        let hosts = vec![host("PC", Some(5)), host("UNKNOWN", Some(5))];
+        let health = NetworkHealth::calculate_weighted(&hosts, &HealthWeights::default(), Some(254));
+
+        assert_eq!(health.factors.len(), 4);
+        let unknown = health
+            .factors
+            .iter()
+            .find(|f| f.kind == HealthFactorKind::UnknownDevices)
+            .unwrap();
+        assert_eq!(unknown.score, 50.0);
+
+        let weight_sum: f32 = health.factors.iter().map(|f| f.weight).sum();
+        assert!((weight_sum - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_custom_weights_change_score() {
+        let hosts = vec![host("UNKNOWN", Some(5)), host("UNKNOWN", Some(5))];
+
+        let only_latency = HealthWeights {
+            latency: 1.0,
+            vulnerabilities: 0.0,
+            unknown_devices: 0.0,
+            utilization: 0.0,
+        };
+        let only_unknown = HealthWeights {
+            latency: 0.0,
+            vulnerabilities: 0.0,
+            unknown_devices: 1.0,
+            utilization: 0.0,
+        };
+
+        assert_eq!(NetworkHealth::calculate_weighted(&hosts, &only_latency, None).score, 100);
+        assert_eq!(NetworkHealth::calculate_weighted(&hosts, &only_unknown, None).score, 0);
+    }
+
+    #[test]
+    fn test_latency_factor_penalizes_loss() {
+        let mut lossy = host("PC", Some(5));
+        lossy.latency_stats = LatencyStats::from_samples(&[Some(5.0), None, Some(5.0), None]);
+        let clean = host("PC", Some(5));
+
+        let (lossy_score, _) = latency_factor(&[lossy]).unwrap();
+        let (clean_score, _) = latency_factor(&[clean]).unwrap();
+        assert_eq!(clean_score, 100.0);
+        assert_eq!(lossy_score, 0.0);
+    }
+
+    #[test]
+    fn test_slow_hosts_distinguished_from_lossy() {
+        let mut slow = host("PC", Some(180));
+        slow.packet_loss_percent = Some(0.0);
+        let mut lossy = host("PC", Some(180));
+        lossy.packet_loss_percent = Some(40.0);
+
+        let health = NetworkHealth::calculate(&[slow, lossy]);
+        assert!(health.insights.iter().any(|i| i.starts_with("📶 1 devices dropping")));
+        assert!(health.insights.iter().any(|i| i.starts_with("🐢 1 devices slow")));
+    }
+
+    #[test]
+    fn test_nat_correlation() {
+        let mut health = NetworkHealth::calculate(&[host("PC", Some(5))]);
+        let before = health.insights.len();
+        health.correlate_nat(&NatReport::classify(vec![Some(Ipv4Addr::new(192, 168, 1, 1))], None));
+        assert_eq!(health.insights.len(), before);
+
+        let hops = vec![Some(Ipv4Addr::new(192, 168, 1, 1)), Some(Ipv4Addr::new(10, 0, 0, 1))];
+        health.correlate_nat(&NatReport::classify(hops, None));
+        assert!(health.insights.last().unwrap().contains("Double NAT"));
+    }
+
+    #[test]
+    fn test_wan_correlation() {
+        let wan = |reachable_targets: usize, latency_ms: Option<f64>| WanHealth {
+            public_ip: None,
+            public_ip_source: None,
+            reachable_targets,
+            total_targets: 3,
+            latency_ms,
+        };
+
+        let mut health = NetworkHealth::calculate(&[host("PC", Some(5))]);
+        let before = health.insights.len();
+        health.correlate_wan(&wan(3, Some(20.0)));
+        assert_eq!(health.insights.len(), before);
+
+        health.correlate_wan(&wan(3, Some(400.0)));
+        assert!(health.insights.last().unwrap().contains("upstream"));
+
+        health.correlate_wan(&wan(0, None));
+        assert!(health.insights.last().unwrap().starts_with("🌐 Internet unreachable"));
+    }
+
+    #[test]
+    fn test_frame_rate_correlation() {
+        let mut counter = crate::scanner::passive::FrameRateCounter::new();
+        let mut frame = vec![0xff; 6];
+        frame.extend_from_slice(&[0xaa, 0, 0, 0, 0, 1]);
+        for _ in 0..100 {
+            counter.record(&frame);
+        }
+
+        let mut health = NetworkHealth::calculate(&[host("PC", Some(5))]);
+        let before = health.insights.len();
+        health.correlate_frame_rates(&counter.rates(std::time::Duration::from_secs(10)));
+        assert_eq!(health.insights.len(), before);
+
+        health.correlate_frame_rates(&counter.rates(std::time::Duration::from_millis(100)));
+        assert!(health.insights.last().unwrap().starts_with("🌪️ Broadcast storm: 1000 frames/s"));
+    }
+
+    #[test]
+    fn test_throughput_correlation() {
+        let mut slow = NetworkHealth::calculate(&[host("PC", Some(450))]);
+        slow.correlate_throughput(2.0);
+        assert!(slow.insights.last().unwrap().contains("saturated"));
+
+        let mut fast = NetworkHealth::calculate(&[host("PC", Some(5))]);
+        let before = fast.insights.len();
+        fast.correlate_throughput(300.0);
+        assert_eq!(fast.insights.len(), before);
+    }
+
+    #[test]
+    fn test_utilization_factor() {
+        assert_eq!(utilization_factor(100, 254).0, 100.0);
+        assert_eq!(utilization_factor(254, 254).0, 0.0);
+    }
+}
@@ -0,0 +1,303 @@
+//! IPv6 readiness assessment
+//!
+//! Most hosts run IPv6 whether or not anyone configured it. When no router
+//! advertises prefixes and no DHCPv6 server answers, IPv6 is unmanaged:
+//! hosts still talk link-local, and the first device to send a Router
+//! Advertisement or DHCPv6 reply (mitm6-style attacks) becomes their
+//! router and DNS server without touching the IPv4 network at all.
+
+use serde::{Deserialize, Serialize};
+
+use super::recommendations::{Priority, Recommendation};
+use crate::network::{Ipv6Neighbor, MacAddress};
+use crate::scanner::RouterAdvertisement;
+use crate::HostInfo;
+
+/// How IPv6 is run on the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ipv6Status {
+    /// No IPv6 traffic observed
+    Inactive,
+    /// Hosts speak IPv6 but no router or DHCPv6 server manages it
+    Unmanaged,
+    /// Router advertisements and/or DHCPv6 are present
+    Managed,
+}
+
+impl Ipv6Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ipv6Status::Inactive => "inactive",
+            Ipv6Status::Unmanaged => "unmanaged",
+            Ipv6Status::Managed => "managed",
+        }
+    }
+}
+
+/// IPv6 posture of the scanned network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ipv6Readiness {
+    pub status: Ipv6Status,
+    /// Hosts with a global/unique-local IPv6 address as well as IPv4
+    pub dual_stack_hosts: Vec<String>,
+    /// Hosts seen on IPv6 only through link-local addresses
+    pub link_local_only_hosts: Vec<String>,
+    /// Hosts never seen on IPv6
+    pub ipv4_only_hosts: usize,
+    /// Neighbors flagged as routers (sent Router Advertisements)
+    pub advertising_routers: Vec<String>,
+    pub dhcpv6_servers: Vec<String>,
+    /// Prefixes announced in captured Router Advertisements
+    #[serde(default)]
+    pub advertised_prefixes: Vec<String>,
+    /// DNS servers announced in Router Advertisements (RDNSS)
+    #[serde(default)]
+    pub rdns_servers: Vec<String>,
+    /// Devices other than the gateway advertising themselves as router,
+    /// prefix or DNS source (`address (MAC)`)
+    #[serde(default)]
+    pub rogue_advertisers: Vec<String>,
+}
+
+impl Ipv6Readiness {
+    /// Assess IPv6 from the neighbor cache and a DHCPv6 solicit
+    ///
+    /// `neighbors` comes from `network::ipv6_neighbors`, `dhcpv6_servers` from
+    /// `scanner::dhcpv6_probe`.
+    pub fn assess(hosts: &[HostInfo], neighbors: &[Ipv6Neighbor], dhcpv6_servers: &[String]) -> Self {
+        let mut dual_stack_hosts = Vec::new();
+        let mut link_local_only_hosts = Vec::new();
+
+        for host in hosts {
+            let addresses: Vec<&Ipv6Neighbor> = neighbors
+                .iter()
+                .filter(|n| n.mac.eq_ignore_ascii_case(&host.mac))
+                .collect();
+            if addresses.iter().any(|n| !n.is_link_local()) {
+                dual_stack_hosts.push(host.ip.clone());
+            } else if !addresses.is_empty() {
+                link_local_only_hosts.push(host.ip.clone());
+            }
+        }
+
+        let mut advertising_routers: Vec<String> = neighbors
+            .iter()
+            .filter(|n| n.is_router)
+            .map(|n| n.address.to_string())
+            .collect();
+        advertising_routers.sort();
+        advertising_routers.dedup();
+
+        let status = if !advertising_routers.is_empty() || !dhcpv6_servers.is_empty() {
+            Ipv6Status::Managed
+        } else if neighbors.is_empty() {
+            Ipv6Status::Inactive
+        } else {
+            Ipv6Status::Unmanaged
+        };
+
+        Self {
+            status,
+            ipv4_only_hosts: hosts.len() - dual_stack_hosts.len() - link_local_only_hosts.len(),
+            dual_stack_hosts,
+            link_local_only_hosts,
+            advertising_routers,
+            dhcpv6_servers: dhcpv6_servers.to_vec(),
+            advertised_prefixes: Vec::new(),
+            rdns_servers: Vec::new(),
+            rogue_advertisers: Vec::new(),
+        }
+    }
+
+    /// Add what captured Router Advertisements show
+    ///
+    /// With the IPv4 gateway's MAC known, any other device that offers
+    /// itself as default router or announces prefixes or DNS servers is a
+    /// rogue advertiser: hosts would follow it just the same.
+    pub fn apply_router_advertisements(&mut self, advertisements: &[RouterAdvertisement], gateway_mac: Option<&str>) {
+        for ra in advertisements {
+            let router = ra.router.to_string();
+            if !self.advertising_routers.contains(&router) {
+                self.advertising_routers.push(router.clone());
+            }
+            for network in ra.networks() {
+                let network = network.to_string();
+                if !self.advertised_prefixes.contains(&network) {
+                    self.advertised_prefixes.push(network);
+                }
+            }
+            for server in &ra.rdns_servers {
+                let server = server.to_string();
+                if !self.rdns_servers.contains(&server) {
+                    self.rdns_servers.push(server);
+                }
+            }
+
+            let from_gateway = gateway_mac.is_some_and(|mac| MacAddress::normalize(mac) == ra.router_mac);
+            let influences_hosts = ra.router_lifetime > 0 || !ra.prefixes.is_empty() || !ra.rdns_servers.is_empty();
+            let suspect = format!("{} ({})", router, ra.router_mac);
+            if gateway_mac.is_some() && !from_gateway && influences_hosts && !self.rogue_advertisers.contains(&suspect) {
+                self.rogue_advertisers.push(suspect);
+            }
+        }
+        self.advertising_routers.sort();
+        if !advertisements.is_empty() {
+            self.status = Ipv6Status::Managed;
+        }
+    }
+
+    /// One-line summary for reports
+    pub fn summary(&self) -> String {
+        format!(
+            "IPv6 {}: {} dual-stack, {} link-local only, {} IPv4-only hosts",
+            self.status.as_str(),
+            self.dual_stack_hosts.len(),
+            self.link_local_only_hosts.len(),
+            self.ipv4_only_hosts
+        )
+    }
+
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        let mut recs = Vec::new();
+
+        if !self.rogue_advertisers.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::Critical,
+                category: "IPv6".to_string(),
+                title: format!("Rogue IPv6 Router Advertisements from {} devices", self.rogue_advertisers.len()),
+                description: "Devices other than the gateway are sending Router Advertisements. Hosts accept \
+                              them without authentication and route traffic or resolve names through the \
+                              sender. Identify the devices, and enable RA Guard on access ports."
+                    .to_string(),
+                affected_devices: self.rogue_advertisers.clone(),
+            });
+        }
+
+        if self.status == Ipv6Status::Unmanaged {
+            recs.push(Recommendation {
+                priority: Priority::High,
+                category: "IPv6".to_string(),
+                title: "IPv6 is active but unmanaged".to_string(),
+                description: "Hosts communicate over IPv6 but no router advertises it and no DHCPv6 server \
+                              answers. Any device can send Router Advertisements or DHCPv6 replies and become \
+                              the router/DNS server for every host (mitm6). Either deploy IPv6 properly on the \
+                              router or enable RA Guard and DHCPv6 Guard on the switches."
+                    .to_string(),
+                affected_devices: self
+                    .link_local_only_hosts
+                    .iter()
+                    .chain(self.dual_stack_hosts.iter())
+                    .cloned()
+                    .collect(),
+            });
+        }
+
+        if self.advertising_routers.len() > 1 {
+            recs.push(Recommendation {
+                priority: Priority::Medium,
+                category: "IPv6".to_string(),
+                title: format!("{} devices send IPv6 Router Advertisements", self.advertising_routers.len()),
+                description: "More than one device advertises itself as an IPv6 router. Unless this is a \
+                              deliberate redundant setup, one of them is misconfigured or rogue. Verify each \
+                              and enable RA Guard on access ports."
+                    .to_string(),
+                affected_devices: self.advertising_routers.clone(),
+            });
+        }
+
+        if self.status == Ipv6Status::Managed && !self.dual_stack_hosts.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::Low,
+                category: "IPv6".to_string(),
+                title: format!("{} dual-stack hosts reachable over IPv6", self.dual_stack_hosts.len()),
+                description: "These hosts have routable IPv6 addresses. Make sure the router firewall applies \
+                              the same inbound rules to IPv6 as to IPv4; IPv6 traffic bypasses NAT."
+                    .to_string(),
+                affected_devices: self.dual_stack_hosts.clone(),
+            });
+        }
+
+        recs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn neighbor(address: &str, mac: &str, is_router: bool) -> Ipv6Neighbor {
+        Ipv6Neighbor {
+            address: address.parse().unwrap(),
+            mac: mac.to_string(),
+            is_router,
+        }
+    }
+
+    #[test]
+    fn test_unmanaged_ipv6() {
+        let hosts = vec![host("192.168.1.10", "AA:00:00:00:00:10"), host("192.168.1.11", "AA:00:00:00:00:11")];
+        let neighbors = vec![neighbor("fe80::10", "aa:00:00:00:00:10", false)];
+
+        let readiness = Ipv6Readiness::assess(&hosts, &neighbors, &[]);
+        assert_eq!(readiness.status, Ipv6Status::Unmanaged);
+        assert_eq!(readiness.link_local_only_hosts, vec!["192.168.1.10".to_string()]);
+        assert_eq!(readiness.ipv4_only_hosts, 1);
+        assert_eq!(readiness.recommendations()[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn test_managed_dual_stack() {
+        let hosts = vec![host("192.168.1.1", "AA:00:00:00:00:01"), host("192.168.1.10", "AA:00:00:00:00:10")];
+        let neighbors = vec![
+            neighbor("fe80::1", "AA:00:00:00:00:01", true),
+            neighbor("2001:db8::10", "AA:00:00:00:00:10", false),
+        ];
+
+        let readiness = Ipv6Readiness::assess(&hosts, &neighbors, &[]);
+        assert_eq!(readiness.status, Ipv6Status::Managed);
+        assert_eq!(readiness.dual_stack_hosts, vec!["192.168.1.10".to_string()]);
+        assert_eq!(readiness.advertising_routers, vec!["fe80::1".to_string()]);
+        let recs = readiness.recommendations();
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].priority, Priority::Low);
+    }
+
+    #[test]
+    fn test_rogue_router_advertisement() {
+        let hosts = vec![host("192.168.1.1", "AA:00:00:00:00:01")];
+        let advertisement = |router: &str, mac: &str, lifetime: u16| RouterAdvertisement {
+            router: router.parse().unwrap(),
+            router_mac: mac.to_string(),
+            router_lifetime: lifetime,
+            hop_limit: 64,
+            managed: false,
+            other_config: false,
+            prefixes: Vec::new(),
+            rdns_servers: vec!["2001:db8::53".parse().unwrap()],
+            mtu: None,
+        };
+        let ras = vec![
+            advertisement("fe80::1", "AA:00:00:00:00:01", 1800),
+            advertisement("fe80::bad", "AA:00:00:00:00:66", 1800),
+        ];
+
+        let mut readiness = Ipv6Readiness::assess(&hosts, &[], &[]);
+        assert_eq!(readiness.status, Ipv6Status::Inactive);
+        readiness.apply_router_advertisements(&ras, Some("aa-00-00-00-00-01"));
+        assert_eq!(readiness.status, Ipv6Status::Managed);
+        assert_eq!(readiness.rdns_servers, vec!["2001:db8::53".to_string()]);
+        assert_eq!(readiness.rogue_advertisers, vec!["fe80::bad (AA:00:00:00:00:66)".to_string()]);
+        assert_eq!(readiness.recommendations()[0].priority, Priority::Critical);
+    }
+}
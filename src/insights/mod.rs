@@ -2,14 +2,38 @@
 //!
 //! Rule-based network analysis and recommendations
 
+pub mod allowlist;
+pub mod baseline;
+pub mod bindings;
+pub mod cvss;
 pub mod health;
 pub mod distribution;
+pub mod eol;
+pub mod ipv6;
+pub mod policy;
 pub mod recommendations;
 pub mod security;
+pub mod stp;
+pub mod tags;
+pub mod trends;
+pub mod uptime;
+pub mod utilization;
 pub mod vulnerability_filter;
 
+pub use allowlist::*;
+pub use baseline::*;
+pub use bindings::*;
+pub use cvss::*;
 pub use health::*;
 pub use distribution::*;
+pub use eol::*;
+pub use ipv6::*;
+pub use policy::*;
 pub use recommendations::*;
 pub use security::*;
+pub use stp::*;
+pub use tags::*;
+pub use trends::*;
+pub use uptime::*;
+pub use utilization::*;
 pub use vulnerability_filter::*;
@@ -0,0 +1,274 @@
+//! Policy-based compliance checking
+//!
+//! A policy file declares the state the network is expected to be in:
+//! hosts that must be present, ports that must be closed everywhere and the
+//! only hardware vendors allowed. Each scan is evaluated against it, one
+//! pass/fail finding per declared expectation.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::recommendations::{Priority, Recommendation};
+use crate::HostInfo;
+
+/// Evaluates every scan against this policy file for the CLI
+pub const POLICY_ENV: &str = "HOST_DISCOVERY_POLICY";
+
+/// Expected network state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    pub name: Option<String>,
+    /// Hosts that must be found, each an IP, MAC or hostname
+    pub required_hosts: Vec<String>,
+    /// Ports that must not be open on any host
+    pub forbidden_ports: Vec<u16>,
+    /// Vendor names (case-insensitive substrings) allowed on the network;
+    /// empty allows any vendor
+    pub allowed_vendors: Vec<String>,
+    /// Hosts whose vendor is unknown (randomized MACs, missing OUI) pass the
+    /// vendor rule
+    pub allow_unknown_vendor: bool,
+}
+
+impl Policy {
+    /// Policy from a JSON file:
+    /// `{"required_hosts": ["192.168.1.1"], "forbidden_ports": [23], "allowed_vendors": ["Cisco"]}`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid policy file {}", path.display()))
+    }
+
+    fn vendor_allowed(&self, host: &HostInfo) -> bool {
+        if self.allowed_vendors.is_empty() {
+            return true;
+        }
+        match host.vendor.as_deref() {
+            Some(vendor) => {
+                let vendor = vendor.to_lowercase();
+                self.allowed_vendors.iter().any(|v| vendor.contains(&v.to_lowercase()))
+            }
+            None => self.allow_unknown_vendor,
+        }
+    }
+}
+
+/// Which part of the policy a finding checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    RequiredHost,
+    ForbiddenPort,
+    AllowedVendors,
+}
+
+impl PolicyRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyRule::RequiredHost => "required_host",
+            PolicyRule::ForbiddenPort => "forbidden_port",
+            PolicyRule::AllowedVendors => "allowed_vendors",
+        }
+    }
+}
+
+/// Outcome of one policy expectation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyFinding {
+    pub rule: PolicyRule,
+    /// The expectation checked: a host, a port, or the vendor list
+    pub subject: String,
+    pub passed: bool,
+    pub detail: String,
+    /// Hosts violating the expectation
+    pub affected_devices: Vec<String>,
+}
+
+/// A scan evaluated against a policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub policy: Option<String>,
+    pub findings: Vec<PolicyFinding>,
+    pub passed: usize,
+    pub failed: usize,
+    pub summary: String,
+}
+
+impl ComplianceReport {
+    /// Evaluate the scanned hosts against `policy`
+    pub fn evaluate(policy: &Policy, hosts: &[HostInfo]) -> Self {
+        let mut findings = Vec::new();
+
+        for wanted in &policy.required_hosts {
+            let found = hosts.iter().find(|h| host_matches(h, wanted));
+            findings.push(PolicyFinding {
+                rule: PolicyRule::RequiredHost,
+                subject: wanted.clone(),
+                passed: found.is_some(),
+                detail: match found {
+                    Some(h) => format!("{} found at {}", wanted, h.ip),
+                    None => format!("{} was not found", wanted),
+                },
+                affected_devices: Vec::new(),
+            });
+        }
+
+        for port in &policy.forbidden_ports {
+            let open: Vec<String> = hosts
+                .iter()
+                .filter(|h| h.open_ports.contains(port))
+                .map(|h| format!("{} ({})", h.ip, h.mac))
+                .collect();
+            findings.push(PolicyFinding {
+                rule: PolicyRule::ForbiddenPort,
+                subject: port.to_string(),
+                passed: open.is_empty(),
+                detail: if open.is_empty() {
+                    format!("Port {} is closed on every host", port)
+                } else {
+                    format!("Port {} is open on {} host(s)", port, open.len())
+                },
+                affected_devices: open,
+            });
+        }
+
+        if !policy.allowed_vendors.is_empty() {
+            let disallowed: Vec<String> = hosts
+                .iter()
+                .filter(|h| !policy.vendor_allowed(h))
+                .map(|h| format!("{} ({})", h.ip, h.vendor.as_deref().unwrap_or("unknown vendor")))
+                .collect();
+            findings.push(PolicyFinding {
+                rule: PolicyRule::AllowedVendors,
+                subject: policy.allowed_vendors.join(", "),
+                passed: disallowed.is_empty(),
+                detail: if disallowed.is_empty() {
+                    "Every host is from an allowed vendor".to_string()
+                } else {
+                    format!("{} host(s) from vendors outside the policy", disallowed.len())
+                },
+                affected_devices: disallowed,
+            });
+        }
+
+        let passed = findings.iter().filter(|f| f.passed).count();
+        let failed = findings.len() - passed;
+        let summary = if failed == 0 {
+            format!("✅ Compliant: all {} policy check(s) passed", passed)
+        } else {
+            format!("⚠️ Not compliant: {} of {} policy check(s) failed", failed, findings.len())
+        };
+
+        Self {
+            policy: policy.name.clone(),
+            findings,
+            passed,
+            failed,
+            summary,
+        }
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// One recommendation per failed finding
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        self.findings
+            .iter()
+            .filter(|f| !f.passed)
+            .map(|f| {
+                let (priority, description) = match f.rule {
+                    PolicyRule::ForbiddenPort => (
+                        Priority::High,
+                        "The policy requires this port to be closed network-wide. Disable the service or block it on the affected hosts.",
+                    ),
+                    PolicyRule::RequiredHost => (
+                        Priority::Medium,
+                        "A host the policy requires did not answer. Check that it is powered on and connected.",
+                    ),
+                    PolicyRule::AllowedVendors => (
+                        Priority::Medium,
+                        "Devices from vendors the policy does not allow are on the network. Identify and remove them, or extend the policy.",
+                    ),
+                };
+                Recommendation {
+                    priority,
+                    category: "Policy".to_string(),
+                    title: format!("Policy violation: {}", f.detail),
+                    description: description.to_string(),
+                    affected_devices: f.affected_devices.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether `host` is the one a policy entry names (IP, MAC or hostname)
+fn host_matches(host: &HostInfo, wanted: &str) -> bool {
+    let wanted = wanted.trim();
+    host.ip == wanted
+        || host.mac.eq_ignore_ascii_case(wanted)
+        || host
+            .hostname
+            .as_deref()
+            .is_some_and(|name| name.trim_end_matches('.').eq_ignore_ascii_case(wanted.trim_end_matches('.')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, vendor: Option<&str>, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: format!("AA:00:00:00:00:{:02X}", ip.rsplit('.').next().unwrap().parse::<u8>().unwrap()),
+            vendor: vendor.map(|v| v.to_string()),
+            device_type: "PC".to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compliance_evaluation() {
+        let policy: Policy = serde_json::from_str(
+            r#"{"name": "office", "required_hosts": ["192.168.1.1", "aa:00:00:00:00:02", "nas.lan"],
+                "forbidden_ports": [23, 3389], "allowed_vendors": ["cisco", "Apple"]}"#,
+        )
+        .unwrap();
+        let hosts = vec![
+            host("192.168.1.1", Some("Cisco Systems, Inc"), vec![22]),
+            host("192.168.1.2", Some("Apple, Inc."), vec![]),
+            host("192.168.1.3", Some("Espressif Inc."), vec![23]),
+            host("192.168.1.4", None, vec![]),
+        ];
+        let report = ComplianceReport::evaluate(&policy, &hosts);
+
+        let outcome: Vec<(PolicyRule, &str, bool)> =
+            report.findings.iter().map(|f| (f.rule, f.subject.as_str(), f.passed)).collect();
+        assert_eq!(
+            outcome,
+            vec![
+                (PolicyRule::RequiredHost, "192.168.1.1", true),
+                (PolicyRule::RequiredHost, "aa:00:00:00:00:02", true),
+                (PolicyRule::RequiredHost, "nas.lan", false),
+                (PolicyRule::ForbiddenPort, "23", false),
+                (PolicyRule::ForbiddenPort, "3389", true),
+                (PolicyRule::AllowedVendors, "cisco, Apple", false),
+            ]
+        );
+        assert_eq!(report.findings[3].affected_devices, vec!["192.168.1.3 (AA:00:00:00:00:03)".to_string()]);
+        assert_eq!(report.findings[5].affected_devices.len(), 2);
+        assert_eq!((report.passed, report.failed), (3, 3));
+        assert!(!report.is_compliant());
+        assert_eq!(report.recommendations()[1].priority, Priority::High);
+
+        let lenient = Policy { allow_unknown_vendor: true, ..policy };
+        let report = ComplianceReport::evaluate(&lenient, &hosts);
+        assert_eq!(report.findings[5].affected_devices, vec!["192.168.1.3 (Espressif Inc.)".to_string()]);
+    }
+}
@@ -3,6 +3,7 @@
 //! Generates actionable security advice based on scan results
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use crate::HostInfo;
 
 /// Priority level for recommendations
@@ -45,6 +46,9 @@ pub struct SecurityReport {
     pub high_count: usize,
     pub total_issues: usize,
     pub summary: String,
+    /// Recommendations as an ordered work queue
+    #[serde(default)]
+    pub action_plan: Vec<ActionItem>,
 }
 
 impl SecurityReport {
@@ -163,6 +167,11 @@ impl SecurityReport {
             });
         }
 
+        // Check for untrusted devices sharing a segment with workstations/servers
+        if let Some(plan) = SegmentationPlan::propose(hosts) {
+            recommendations.push(plan.recommendation());
+        }
+
         // If no issues, add positive note
         if recommendations.is_empty() {
             recommendations.push(Recommendation {
@@ -192,12 +201,315 @@ impl SecurityReport {
             "✅ No critical security issues found".to_string()
         };
 
+        let action_plan = build_action_plan(&recommendations);
+
         Self {
             recommendations,
             critical_count,
             high_count,
             total_issues,
             summary,
+            action_plan,
+        }
+    }
+}
+
+/// Estimated effort to carry out a recommendation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl Effort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Effort::Low => "LOW",
+            Effort::Medium => "MEDIUM",
+            Effort::High => "HIGH",
+        }
+    }
+
+    /// Rough hands-on time for the whole item
+    pub fn estimated_hours(&self) -> f32 {
+        match self {
+            Effort::Low => 0.5,
+            Effort::Medium => 2.0,
+            Effort::High => 8.0,
+        }
+    }
+
+    fn bump(self) -> Self {
+        match self {
+            Effort::Low => Effort::Medium,
+            _ => Effort::High,
+        }
+    }
+}
+
+/// One step of an ordered remediation plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionItem {
+    /// Position in the plan, starting at 1
+    pub rank: usize,
+    pub priority: Priority,
+    pub category: String,
+    pub title: String,
+    pub description: String,
+    pub effort: Effort,
+    /// Expected risk reduction (0-100)
+    pub impact: u8,
+    pub affected_hosts: Vec<String>,
+}
+
+/// Order recommendations into a work queue: priority first, then impact per unit of effort
+///
+/// Purely informational entries (Info priority without affected hosts) are left out.
+pub fn build_action_plan(recommendations: &[Recommendation]) -> Vec<ActionItem> {
+    let mut items: Vec<ActionItem> = recommendations
+        .iter()
+        .filter(|r| r.priority != Priority::Info || !r.affected_devices.is_empty())
+        .map(|r| {
+            let mut effort = effort_for(&r.category);
+            if r.affected_devices.len() > 5 {
+                effort = effort.bump();
+            }
+
+            ActionItem {
+                rank: 0,
+                priority: r.priority,
+                category: r.category.clone(),
+                title: r.title.clone(),
+                description: r.description.clone(),
+                effort,
+                impact: impact_for(r.priority, r.affected_devices.len()),
+                affected_hosts: r.affected_devices.clone(),
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        let value = |i: &ActionItem| i.impact as f32 / i.effort.estimated_hours();
+        priority_rank(a.priority)
+            .cmp(&priority_rank(b.priority))
+            .then_with(|| value(b).total_cmp(&value(a)))
+    });
+
+    for (i, item) in items.iter_mut().enumerate() {
+        item.rank = i + 1;
+    }
+
+    items
+}
+
+fn priority_rank(priority: Priority) -> u8 {
+    match priority {
+        Priority::Critical => 0,
+        Priority::High => 1,
+        Priority::Medium => 2,
+        Priority::Low => 3,
+        Priority::Info => 4,
+    }
+}
+
+/// Baseline effort for a recommendation category
+fn effort_for(category: &str) -> Effort {
+    match category {
+        "Insecure Services" | "Device Tracking" | "Device Classification" => Effort::Low,
+        "Network Segmentation" | "End of Life" => Effort::High,
+        _ => Effort::Medium,
+    }
+}
+
+fn impact_for(priority: Priority, affected: usize) -> u8 {
+    let base = match priority {
+        Priority::Critical => 80,
+        Priority::High => 60,
+        Priority::Medium => 40,
+        Priority::Low => 20,
+        Priority::Info => 5,
+    };
+    (base + affected.min(10) * 2).min(100) as u8
+}
+
+/// Device types that should not share a segment with trusted hosts
+const UNTRUSTED_TYPES: &[&str] = &["IOT_DEVICE", "CAMERA", "PRINTER", "SMART_TV", "GAME_CONSOLE"];
+
+/// Workstations and servers
+const TRUSTED_TYPES: &[&str] = &["PC", "LAPTOP", "SERVER", "NAS"];
+
+/// A proposed VLAN in a segmentation plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedSegment {
+    pub name: String,
+    pub vlan_id: u16,
+    /// Hosts to move into this segment ("ip (mac)")
+    pub hosts: Vec<String>,
+    /// Prefix length sized for the hosts with room to grow
+    pub suggested_prefix: u8,
+}
+
+/// Segmentation plan for a mixed trusted/untrusted network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentationPlan {
+    /// Shared segments that mix untrusted and trusted devices
+    pub mixed_segments: Vec<String>,
+    pub segments: Vec<ProposedSegment>,
+}
+
+impl SegmentationPlan {
+    /// Propose a plan if IoT/camera/printer devices share a /24 with workstations or servers
+    pub fn propose(hosts: &[HostInfo]) -> Option<Self> {
+        let mut by_segment: BTreeMap<String, Vec<&HostInfo>> = BTreeMap::new();
+        for host in hosts {
+            let octets: Vec<&str> = host.ip.split('.').collect();
+            if octets.len() == 4 {
+                let key = format!("{}.{}.{}.0/24", octets[0], octets[1], octets[2]);
+                by_segment.entry(key).or_default().push(host);
+            }
+        }
+
+        let mut mixed_segments = Vec::new();
+        let mut to_move: Vec<&HostInfo> = Vec::new();
+        for (segment, members) in &by_segment {
+            let untrusted: Vec<&HostInfo> = members
+                .iter()
+                .copied()
+                .filter(|h| UNTRUSTED_TYPES.contains(&h.device_type.as_str()))
+                .collect();
+            let has_trusted = members
+                .iter()
+                .any(|h| TRUSTED_TYPES.contains(&h.device_type.as_str()));
+
+            if has_trusted && !untrusted.is_empty() {
+                mixed_segments.push(segment.clone());
+                to_move.extend(untrusted);
+            }
         }
+
+        if to_move.is_empty() {
+            return None;
+        }
+
+        let groups: [(&str, u16, &[&str]); 3] = [
+            ("IoT", 20, &["IOT_DEVICE", "SMART_TV", "GAME_CONSOLE"]),
+            ("Cameras", 30, &["CAMERA"]),
+            ("Printers", 40, &["PRINTER"]),
+        ];
+
+        let segments = groups
+            .iter()
+            .filter_map(|(name, vlan_id, types)| {
+                let members: Vec<String> = to_move
+                    .iter()
+                    .filter(|h| types.contains(&h.device_type.as_str()))
+                    .map(|h| format!("{} ({})", h.ip, h.mac))
+                    .collect();
+                if members.is_empty() {
+                    return None;
+                }
+                Some(ProposedSegment {
+                    name: name.to_string(),
+                    vlan_id: *vlan_id,
+                    suggested_prefix: prefix_for(members.len()),
+                    hosts: members,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            mixed_segments,
+            segments,
+        })
+    }
+
+    /// Summarize the plan as a single recommendation
+    pub fn recommendation(&self) -> Recommendation {
+        let steps: Vec<String> = self
+            .segments
+            .iter()
+            .map(|s| format!("VLAN {} \"{}\" (/{}, {} host(s))", s.vlan_id, s.name, s.suggested_prefix, s.hosts.len()))
+            .collect();
+
+        Recommendation {
+            priority: Priority::Medium,
+            category: "Network Segmentation".to_string(),
+            title: "Untrusted devices share a segment with workstations".to_string(),
+            description: format!(
+                "IoT, camera or printer devices are on the same segment as workstations/servers ({}). Move them to: {}. Allow only the traffic they need back to the trusted LAN.",
+                self.mixed_segments.join(", "),
+                steps.join("; ")
+            ),
+            affected_devices: self.segments.iter().flat_map(|s| s.hosts.clone()).collect(),
+        }
+    }
+}
+
+/// Smallest prefix (/24 to /29) fitting twice the host count
+fn prefix_for(hosts: usize) -> u8 {
+    let needed = hosts * 2;
+    (24..=29u8)
+        .rev()
+        .find(|p| (1usize << (32 - *p as u32)) - 2 >= needed)
+        .unwrap_or(24)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, device_type: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: format!("00:11:22:33:44:{}", ip.rsplit('.').next().unwrap()),
+            response_time_ms: Some(5),
+            ttl: Some(64),
+            device_type: device_type.to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_segmentation_plan_for_mixed_segment() {
+        let hosts = vec![
+            host("192.168.1.10", "PC"),
+            host("192.168.1.20", "CAMERA"),
+            host("192.168.1.21", "IOT_DEVICE"),
+            host("192.168.1.22", "IOT_DEVICE"),
+            host("192.168.1.30", "PRINTER"),
+        ];
+
+        let plan = SegmentationPlan::propose(&hosts).unwrap();
+        assert_eq!(plan.mixed_segments, vec!["192.168.1.0/24".to_string()]);
+        assert_eq!(plan.segments.len(), 3);
+        assert_eq!(plan.segments[0].name, "IoT");
+        assert_eq!(plan.segments[0].hosts.len(), 2);
+        assert_eq!(plan.segments[0].suggested_prefix, 29);
+        assert_eq!(plan.recommendation().affected_devices.len(), 4);
+    }
+
+    #[test]
+    fn test_action_plan_ordering() {
+        let mut telnet = host("192.168.1.40", "IOT_DEVICE");
+        telnet.open_ports = vec![23];
+        let mut rdp = host("192.168.1.41", "PC");
+        rdp.open_ports = vec![3389];
+        let hosts = vec![rdp, telnet, host("192.168.1.42", "UNKNOWN")];
+
+        let report = SecurityReport::generate(&hosts);
+        let plan = &report.action_plan;
+        assert_eq!(plan[0].rank, 1);
+        assert_eq!(plan[0].title, "Telnet (port 23) detected");
+        assert_eq!(plan[0].effort, Effort::Low);
+        assert!(plan.windows(2).all(|w| priority_rank(w[0].priority) <= priority_rank(w[1].priority)));
+        assert!(plan.iter().any(|i| i.category == "Device Classification"));
+    }
+
+    #[test]
+    fn test_no_plan_when_already_separated() {
+        let hosts = vec![host("192.168.1.10", "PC"), host("192.168.20.5", "CAMERA")];
+        assert!(SegmentationPlan::propose(&hosts).is_none());
     }
 }
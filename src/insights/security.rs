@@ -1,8 +1,13 @@
 //! Security grading and vulnerability assessment
 //!
-//! Calculates security grades (A-F) for network devices
+//! Calculates security grades (A-F) for network devices and flags
+//! network services that put the whole LAN at risk (open DNS resolvers,
+//! NTP amplification)
 
+use super::recommendations::{Priority, Recommendation};
 use crate::models::HostInfo;
+use crate::models::ActiveDirectoryInfo;
+use crate::scanner::{DnsServerInfo, NtpServerInfo, ZoneTransferResult};
 
 /// Calculate security grade for a host based on vulnerabilities and risk factors
 /// 
@@ -55,10 +60,157 @@ pub fn calculate_security_grade(host: &HostInfo) -> String {
     }
 }
 
+/// Flag DNS servers that resolve external names for any LAN client
+///
+/// The DHCP-advertised resolver is expected to recurse; any other host doing
+/// so is usually an unmanaged forwarder that can end up reachable from the
+/// WAN and be abused for DNS amplification.
+pub fn open_resolver_recommendations(servers: &[DnsServerInfo]) -> Vec<Recommendation> {
+    let unexpected: Vec<&DnsServerInfo> = servers.iter().filter(|s| s.is_unexpected_resolver()).collect();
+    if unexpected.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Recommendation {
+        priority: Priority::High,
+        category: "DNS".to_string(),
+        title: format!("{} unexpected open DNS resolver(s)", unexpected.len()),
+        description: "These devices answer recursive DNS queries but are not the resolver handed out by DHCP. \
+                      Disable their DNS service or restrict recursion to trusted clients, and make sure UDP/53 \
+                      is not forwarded from the internet."
+            .to_string(),
+        affected_devices: unexpected.iter().map(|s| s.ip.clone()).collect(),
+    }]
+}
+
+/// Flag DNS servers that hand their zone to any client (unauthenticated AXFR)
+pub fn zone_transfer_recommendations(results: &[ZoneTransferResult]) -> Vec<Recommendation> {
+    let mut open: Vec<&ZoneTransferResult> = results.iter().filter(|r| r.allowed).collect();
+    if open.is_empty() {
+        return Vec::new();
+    }
+    open.sort_by(|a, b| a.ip.cmp(&b.ip));
+
+    let mut affected: Vec<String> = open.iter().map(|r| r.ip.clone()).collect();
+    affected.dedup();
+    let mut zones: Vec<&str> = open.iter().map(|r| r.zone.as_str()).collect();
+    zones.sort();
+    zones.dedup();
+
+    vec![Recommendation {
+        priority: Priority::High,
+        category: "DNS".to_string(),
+        title: format!("{} DNS server(s) allow zone transfers", affected.len()),
+        description: format!(
+            "Any client can download the complete zone ({}) with an AXFR request, listing every host on the \
+             network. Restrict zone transfers to the secondary servers' addresses or require TSIG keys.",
+            zones.join(", ")
+        ),
+        affected_devices: affected,
+    }]
+}
+
+/// Domain-specific advice for networks joined to an Active Directory domain
+///
+/// Flags domain controllers that accept LDAP only in clear text and domains
+/// that depend on a single controller.
+pub fn active_directory_recommendations(ad: &ActiveDirectoryInfo) -> Vec<Recommendation> {
+    let domain = ad.domain.as_deref().unwrap_or("the domain");
+    let mut recommendations = Vec::new();
+
+    let cleartext: Vec<String> = ad
+        .domain_controllers
+        .iter()
+        .filter(|dc| dc.ldap && !dc.ldaps)
+        .map(|dc| dc.ip.clone())
+        .collect();
+    if !cleartext.is_empty() {
+        recommendations.push(Recommendation {
+            priority: Priority::High,
+            category: "Active Directory".to_string(),
+            title: format!("{} domain controller(s) offer LDAP without TLS", cleartext.len()),
+            description: format!(
+                "Controllers for {} answer on LDAP (389) but not LDAPS (636), so binds and directory \
+                 queries can cross the network unencrypted. Install a DC certificate for LDAPS and enforce \
+                 LDAP signing and channel binding.",
+                domain
+            ),
+            affected_devices: cleartext,
+        });
+    }
+
+    if ad.domain_controllers.len() == 1 {
+        recommendations.push(Recommendation {
+            priority: Priority::Medium,
+            category: "Active Directory".to_string(),
+            title: "Single domain controller".to_string(),
+            description: format!(
+                "Only one controller for {} was found; logons, Group Policy and internal DNS stop when it \
+                 is down. Add a second domain controller.",
+                domain
+            ),
+            affected_devices: vec![ad.domain_controllers[0].ip.clone()],
+        });
+    }
+    recommendations
+}
+
+/// Flag NTP servers answering the legacy mode 6/7 control queries
+pub fn ntp_exposure_recommendations(servers: &[NtpServerInfo]) -> Vec<Recommendation> {
+    let mut recs = Vec::new();
+
+    let monlist: Vec<&NtpServerInfo> = servers.iter().filter(|s| s.monlist_enabled).collect();
+    if !monlist.is_empty() {
+        recs.push(Recommendation {
+            priority: Priority::High,
+            category: "NTP".to_string(),
+            title: format!("{} NTP server(s) answer monlist", monlist.len()),
+            description: "The mode 7 monlist command returns up to 600 recent clients per request and is a \
+                          well-known DDoS amplification vector (CVE-2013-5211). Update the NTP daemon or add \
+                          'disable monitor' / 'restrict default noquery' to its configuration."
+                .to_string(),
+            affected_devices: monlist
+                .iter()
+                .map(|s| format!("{} ({} bytes per request)", s.ip, s.amplification_bytes))
+                .collect(),
+        });
+    }
+
+    let mode6: Vec<&NtpServerInfo> = servers
+        .iter()
+        .filter(|s| s.mode6_enabled && !s.monlist_enabled)
+        .collect();
+    if !mode6.is_empty() {
+        recs.push(Recommendation {
+            priority: Priority::Medium,
+            category: "NTP".to_string(),
+            title: format!("{} NTP server(s) answer mode 6 control queries", mode6.len()),
+            description: "Mode 6 (ntpq) queries from unauthenticated clients leak the daemon version and peers \
+                          and can be used for amplification. Add 'restrict default noquery' to the NTP configuration."
+                .to_string(),
+            affected_devices: mode6.iter().map(|s| s.ip.clone()).collect(),
+        });
+    }
+
+    recs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{VulnerabilityInfo, PortWarning};
+    use crate::models::{DomainController, VulnerabilityInfo, PortWarning};
+    use crate::scanner::DnsServerSource;
+
+    fn ntp_server(ip: &str, mode6_enabled: bool, monlist_enabled: bool) -> NtpServerInfo {
+        NtpServerInfo {
+            ip: ip.to_string(),
+            stratum: 2,
+            refid: "192.168.1.1".to_string(),
+            mode6_enabled,
+            monlist_enabled,
+            amplification_bytes: if monlist_enabled { 4400 } else { 0 },
+        }
+    }
 
     #[test]
     fn test_grade_a_no_issues() {
@@ -66,21 +218,12 @@ mod tests {
             ip: "192.168.1.1".to_string(),
             mac: "AA:BB:CC:DD:EE:FF".to_string(),
             vendor: Some("TestVendor".to_string()),
-            is_randomized: false,
             response_time_ms: Some(10),
             ttl: Some(64),
             os_guess: Some("Linux".to_string()),
             device_type: "ROUTER".to_string(),
-            risk_score: 0,
-            open_ports: vec![],
             discovery_method: "ARP".to_string(),
-            hostname: None,
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
-            vulnerabilities: vec![],
-            port_warnings: vec![],
-            security_grade: String::new(),
+            ..Default::default()
         };
         
         assert_eq!(calculate_security_grade(&host), "A");
@@ -92,7 +235,6 @@ mod tests {
             ip: "192.168.1.1".to_string(),
             mac: "AA:BB:CC:DD:EE:FF".to_string(),
             vendor: Some("TestVendor".to_string()),
-            is_randomized: false,
             response_time_ms: Some(10),
             ttl: Some(64),
             os_guess: Some("Linux".to_string()),
@@ -100,13 +242,7 @@ mod tests {
             risk_score: 50,
             open_ports: vec![23, 21],
             discovery_method: "ARP".to_string(),
-            hostname: None,
-            system_description: None,
-            uptime_seconds: None,
-            neighbors: vec![],
-            vulnerabilities: vec![],
-            port_warnings: vec![],
-            security_grade: String::new(),
+            ..Default::default()
         };
         
         // Add critical vulnerabilities
@@ -115,6 +251,7 @@ mod tests {
             description: "Critical vulnerability".to_string(),
             severity: "CRITICAL".to_string(),
             cvss_score: Some(9.8),
+            cvss_vector: None,
         });
         
         host.port_warnings.push(PortWarning {
@@ -128,4 +265,82 @@ mod tests {
         let grade = calculate_security_grade(&host);
         assert_eq!(grade, "F");
     }
+
+    #[test]
+    fn test_open_resolver_recommendations() {
+        let server = |ip: &str, source: DnsServerSource| DnsServerInfo {
+            ip: ip.to_string(),
+            source,
+            responding: true,
+            recursion_available: true,
+            open_resolver: true,
+            rcode: Some(0),
+        };
+
+        assert!(open_resolver_recommendations(&[server("192.168.1.1", DnsServerSource::Dhcp)]).is_empty());
+
+        let recs = open_resolver_recommendations(&[
+            server("192.168.1.1", DnsServerSource::Dhcp),
+            server("192.168.1.60", DnsServerSource::Probe),
+        ]);
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].priority, Priority::High);
+        assert_eq!(recs[0].affected_devices, vec!["192.168.1.60".to_string()]);
+    }
+
+    #[test]
+    fn test_zone_transfer_recommendations() {
+        let result = |ip: &str, allowed: bool| ZoneTransferResult {
+            ip: ip.to_string(),
+            zone: "corp.example".to_string(),
+            allowed,
+            records: if allowed { 40 } else { 0 },
+            rcode: Some(if allowed { 0 } else { 5 }),
+        };
+
+        assert!(zone_transfer_recommendations(&[result("192.168.1.1", false)]).is_empty());
+
+        let recs = zone_transfer_recommendations(&[result("192.168.1.10", true), result("192.168.1.1", false)]);
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].priority, Priority::High);
+        assert_eq!(recs[0].affected_devices, vec!["192.168.1.10".to_string()]);
+    }
+
+    #[test]
+    fn test_active_directory_recommendations() {
+        let dc = |ip: &str, ldaps: bool| DomainController {
+            ip: ip.to_string(),
+            hostname: None,
+            kerberos: true,
+            ldap: true,
+            ldaps,
+        };
+        let mut ad = ActiveDirectoryInfo {
+            domain: Some("corp.example".to_string()),
+            domain_controllers: vec![dc("10.0.0.10", true), dc("10.0.0.11", true)],
+            srv_targets: vec!["dc01.corp.example".to_string(), "dc02.corp.example".to_string()],
+        };
+        assert!(active_directory_recommendations(&ad).is_empty());
+
+        ad.domain_controllers = vec![dc("10.0.0.10", false)];
+        let recs = active_directory_recommendations(&ad);
+        assert_eq!(recs.len(), 2);
+        assert_eq!(recs[0].priority, Priority::High);
+        assert_eq!(recs[0].affected_devices, vec!["10.0.0.10".to_string()]);
+        assert_eq!(recs[1].title, "Single domain controller");
+    }
+
+    #[test]
+    fn test_ntp_exposure_recommendations() {
+        assert!(ntp_exposure_recommendations(&[ntp_server("192.168.1.1", false, false)]).is_empty());
+
+        let recs = ntp_exposure_recommendations(&[
+            ntp_server("192.168.1.1", true, true),
+            ntp_server("192.168.1.2", true, false),
+        ]);
+        assert_eq!(recs.len(), 2);
+        assert_eq!(recs[0].priority, Priority::High);
+        assert_eq!(recs[0].affected_devices, vec!["192.168.1.1 (4400 bytes per request)".to_string()]);
+        assert_eq!(recs[1].affected_devices, vec!["192.168.1.2".to_string()]);
+    }
 }
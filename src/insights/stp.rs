@@ -0,0 +1,190 @@
+//! Spanning tree health from captured BPDUs
+//!
+//! Identifies the root bridge and flags the usual STP misconfigurations:
+//! a root elected on default priority (often a desktop switch or an end
+//! host bridging two ports), several bridges claiming root at once, and
+//! topology-change storms that flush MAC tables and cause flooding.
+
+use serde::{Deserialize, Serialize};
+
+use super::recommendations::{Priority, Recommendation};
+use crate::scanner::passive::{BridgeId, StpBpdu};
+use crate::HostInfo;
+
+/// Bridge priority when none is configured (the low 12 bits carry the VLAN/instance)
+const DEFAULT_BRIDGE_PRIORITY: u16 = 32768;
+
+/// Topology changes per minute above which the network is flapping
+const TC_STORM_PER_MINUTE: f64 = 3.0;
+
+/// Device types expected to act as root bridge
+const INFRASTRUCTURE_TYPES: &[&str] = &["SWITCH", "ROUTER", "FIREWALL"];
+
+/// Spanning tree state observed during a capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StpReport {
+    /// Best root bridge advertised
+    pub root: Option<BridgeId>,
+    /// Scanned host owning the root MAC, if any
+    pub root_host: Option<String>,
+    /// Every bridge that sent BPDUs, with its own priority
+    pub bridges: Vec<BridgeId>,
+    /// Distinct roots advertised (more than one = inconsistent tree)
+    pub advertised_roots: Vec<BridgeId>,
+    pub topology_changes: usize,
+    pub topology_changes_per_minute: f64,
+    pub bpdus: usize,
+}
+
+impl StpReport {
+    /// Summarize BPDUs captured over `window_secs`
+    pub fn analyze(bpdus: &[StpBpdu], hosts: &[HostInfo], window_secs: u64) -> Self {
+        let mut advertised_roots: Vec<BridgeId> = bpdus.iter().filter_map(|b| b.root.clone()).collect();
+        advertised_roots.sort();
+        advertised_roots.dedup();
+
+        let mut bridges: Vec<BridgeId> = bpdus.iter().filter_map(|b| b.bridge.clone()).collect();
+        bridges.sort();
+        bridges.dedup();
+
+        // The numerically lowest bridge ID wins the election
+        let root = advertised_roots.first().cloned();
+        let root_host = root.as_ref().and_then(|r| {
+            hosts
+                .iter()
+                .find(|h| h.mac.eq_ignore_ascii_case(&r.mac))
+                .map(|h| h.ip.clone())
+        });
+
+        let topology_changes = bpdus.iter().filter(|b| b.topology_change).count();
+        let minutes = (window_secs.max(1)) as f64 / 60.0;
+
+        Self {
+            root,
+            root_host,
+            bridges,
+            advertised_roots,
+            topology_changes,
+            topology_changes_per_minute: topology_changes as f64 / minutes,
+            bpdus: bpdus.len(),
+        }
+    }
+
+    pub fn recommendations(&self, hosts: &[HostInfo]) -> Vec<Recommendation> {
+        let mut recs = Vec::new();
+        let Some(root) = &self.root else { return recs };
+
+        let root_device = self
+            .root_host
+            .as_ref()
+            .and_then(|ip| hosts.iter().find(|h| &h.ip == ip));
+        let unexpected_root = root_device.is_some_and(|h| !INFRASTRUCTURE_TYPES.contains(&h.device_type.as_str()));
+
+        if unexpected_root || root.priority & 0xf000 >= DEFAULT_BRIDGE_PRIORITY {
+            let who = match root_device {
+                Some(h) => format!("{} ({})", h.ip, h.vendor.as_deref().unwrap_or(&h.device_type)),
+                None => root.mac.clone(),
+            };
+            recs.push(Recommendation {
+                priority: if unexpected_root { Priority::High } else { Priority::Medium },
+                category: "Spanning Tree".to_string(),
+                title: format!("Root bridge {} elected on default priority", who),
+                description: "No bridge has a lowered STP priority, so the root was chosen by lowest MAC \
+                              address. An unmanaged desktop switch or a host bridging two ports can end up as \
+                              root and carry all inter-switch traffic. Set priority 4096 on the core switch \
+                              (and 8192 on its backup)."
+                    .to_string(),
+                affected_devices: vec![who],
+            });
+        }
+
+        if self.advertised_roots.len() > 1 {
+            recs.push(Recommendation {
+                priority: Priority::Medium,
+                category: "Spanning Tree".to_string(),
+                title: format!("{} different root bridges advertised", self.advertised_roots.len()),
+                description: "Bridges disagree about the root, which happens while the tree reconverges or \
+                              when BPDUs are filtered between switches. If it persists, check for BPDU \
+                              filtering and mismatched STP modes between switches."
+                    .to_string(),
+                affected_devices: self.advertised_roots.iter().map(|r| r.mac.clone()).collect(),
+            });
+        }
+
+        if self.topology_changes_per_minute > TC_STORM_PER_MINUTE {
+            recs.push(Recommendation {
+                priority: Priority::High,
+                category: "Spanning Tree".to_string(),
+                title: format!("Topology change storm ({:.1}/min)", self.topology_changes_per_minute),
+                description: "Frequent topology changes flush switch MAC tables and flood traffic to every port. \
+                              Usually a flapping link or an access port without PortFast/edge configuration; \
+                              enable PortFast on end-device ports and check switch logs for flapping links."
+                    .to_string(),
+                affected_devices: self.bridges.iter().map(|b| b.mac.clone()).collect(),
+            });
+        }
+
+        recs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge(priority: u16, mac: &str) -> BridgeId {
+        BridgeId {
+            priority,
+            mac: mac.to_string(),
+        }
+    }
+
+    fn bpdu(root: BridgeId, sender: BridgeId, topology_change: bool) -> StpBpdu {
+        StpBpdu {
+            version: 2,
+            is_tcn: false,
+            topology_change,
+            root: Some(root),
+            root_path_cost: Some(0),
+            bridge: Some(sender),
+            port_id: Some(0x8001),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn host(ip: &str, mac: &str, device_type: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: device_type.to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_desktop_root_flagged() {
+        let hosts = vec![host("192.168.1.30", "AA:00:00:00:00:30", "PC")];
+        let root = bridge(32769, "AA:00:00:00:00:30");
+        let bpdus = vec![bpdu(root.clone(), root.clone(), false), bpdu(root, bridge(32769, "AA:00:00:00:00:40"), false)];
+
+        let report = StpReport::analyze(&bpdus, &hosts, 60);
+        assert_eq!(report.root_host.as_deref(), Some("192.168.1.30"));
+        assert_eq!(report.bridges.len(), 2);
+        let recs = report.recommendations(&hosts);
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn test_configured_root_and_tc_storm() {
+        let root = bridge(4097, "AA:00:00:00:00:01");
+        let bpdus: Vec<StpBpdu> = (0..10).map(|_| bpdu(root.clone(), root.clone(), true)).collect();
+
+        let report = StpReport::analyze(&bpdus, &[], 120);
+        assert_eq!(report.topology_changes_per_minute, 5.0);
+        let recs = report.recommendations(&[]);
+        assert_eq!(recs.len(), 1);
+        assert!(recs[0].title.starts_with("Topology change storm"));
+    }
+}
@@ -0,0 +1,139 @@
+//! User-defined host tags
+//!
+//! Tags ("critical", "lab", "guest", ...) are stored per host identity (MAC
+//! first, IP for hosts without one) so they survive DHCP address changes.
+//! Insight rules use them to scope recommendations, e.g. only raise issues
+//! that touch hosts tagged "critical".
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::recommendations::Recommendation;
+use crate::database::DeviceTag;
+use crate::HostInfo;
+
+/// Tags indexed by host identity
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostTags {
+    by_host: HashMap<String, Vec<String>>,
+}
+
+impl HostTags {
+    pub fn new(tags: &[DeviceTag]) -> Self {
+        let mut by_host: HashMap<String, Vec<String>> = HashMap::new();
+        for tag in tags {
+            by_host
+                .entry(tag.host_id.to_uppercase())
+                .or_default()
+                .push(tag.tag.to_lowercase());
+        }
+        Self { by_host }
+    }
+
+    /// Tags attached to a host (empty if untagged)
+    pub fn tags_for(&self, host: &HostInfo) -> &[String] {
+        self.by_host
+            .get(&host.identity().to_uppercase())
+            .map(|t| t.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn has_tag(&self, host: &HostInfo, tag: &str) -> bool {
+        self.tags_for(host).iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Hosts carrying `tag`
+    pub fn hosts_with_tag<'a>(&self, hosts: &'a [HostInfo], tag: &str) -> Vec<&'a HostInfo> {
+        hosts.iter().filter(|h| self.has_tag(h, tag)).collect()
+    }
+
+    /// Narrow recommendations to the hosts carrying `tag`
+    ///
+    /// Affected-device entries are matched on their leading IP or MAC;
+    /// recommendations left without an affected tagged host are dropped.
+    pub fn scope_recommendations(
+        &self,
+        recommendations: Vec<Recommendation>,
+        hosts: &[HostInfo],
+        tag: &str,
+    ) -> Vec<Recommendation> {
+        let tagged = self.hosts_with_tag(hosts, tag);
+        let is_tagged = |device: &str| {
+            let key = device
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .trim_end_matches([':', ',']);
+            tagged
+                .iter()
+                .any(|h| h.ip == key || (!h.mac.is_empty() && h.mac.eq_ignore_ascii_case(key)))
+        };
+
+        recommendations
+            .into_iter()
+            .filter_map(|mut rec| {
+                rec.affected_devices.retain(|d| is_tagged(d));
+                (!rec.affected_devices.is_empty()).then_some(rec)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insights::Priority;
+    use chrono::Utc;
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tag(host_id: &str, tag: &str) -> DeviceTag {
+        DeviceTag {
+            id: 0,
+            host_id: host_id.to_string(),
+            tag: tag.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tags_follow_mac_identity() {
+        let tags = HostTags::new(&[tag("AA:00:00:00:00:01", "critical"), tag("10.0.5.9", "lab")]);
+
+        // Same device after a DHCP address change
+        assert!(tags.has_tag(&host("192.168.1.77", "aa:00:00:00:00:01"), "Critical"));
+        // Routed host without a MAC falls back to its IP
+        assert_eq!(tags.tags_for(&host("10.0.5.9", "")), ["lab".to_string()]);
+        assert!(tags.tags_for(&host("192.168.1.2", "AA:00:00:00:00:02")).is_empty());
+    }
+
+    #[test]
+    fn test_scope_recommendations_to_tag() {
+        let hosts = vec![host("192.168.1.1", "AA:00:00:00:00:01"), host("192.168.1.2", "AA:00:00:00:00:02")];
+        let tags = HostTags::new(&[tag("AA:00:00:00:00:01", "critical")]);
+        let rec = |devices: &[&str]| Recommendation {
+            priority: Priority::High,
+            category: "Security".to_string(),
+            title: "Telnet open".to_string(),
+            description: String::new(),
+            affected_devices: devices.iter().map(|d| d.to_string()).collect(),
+        };
+
+        let scoped = tags.scope_recommendations(
+            vec![rec(&["192.168.1.1 (AA:00:00:00:00:01)", "192.168.1.2"]), rec(&["192.168.1.2"])],
+            &hosts,
+            "critical",
+        );
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].affected_devices, vec!["192.168.1.1 (AA:00:00:00:00:01)".to_string()]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Time-series trend analysis
+//!
+//! Turns stored scan history into chartable series (host count, latency,
+//! findings) with simple trend statistics
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::ScanTrendRecord;
+
+/// Direction of a series over the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendDirection {
+    Rising,
+    Falling,
+    Stable,
+}
+
+/// A single point in a series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// A metric over time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeSeries {
+    pub metric: String,
+    pub unit: String,
+    pub points: Vec<SeriesPoint>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// Last value minus first value
+    pub change: Option<f64>,
+    /// Least-squares slope per day
+    pub slope_per_day: Option<f64>,
+    pub direction: TrendDirection,
+}
+
+impl TimeSeries {
+    /// Build a series and its statistics from (timestamp, value) points
+    pub fn new(metric: &str, unit: &str, mut points: Vec<SeriesPoint>) -> Self {
+        points.sort_by_key(|p| p.timestamp);
+
+        let values: Vec<f64> = points.iter().map(|p| p.value).collect();
+        let min = values.iter().copied().reduce(f64::min);
+        let max = values.iter().copied().reduce(f64::max);
+        let mean = if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let change = match (values.first(), values.last()) {
+            (Some(first), Some(last)) if values.len() > 1 => Some(last - first),
+            _ => None,
+        };
+
+        let pairs: Vec<(DateTime<Utc>, f64)> = points.iter().map(|p| (p.timestamp, p.value)).collect();
+        let slope_per_day = slope_per_day(&pairs);
+        let direction = direction_of(slope_per_day, mean);
+
+        Self {
+            metric: metric.to_string(),
+            unit: unit.to_string(),
+            points,
+            min,
+            max,
+            mean,
+            change,
+            slope_per_day,
+            direction,
+        }
+    }
+}
+
+/// Trend series for dashboards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkTrends {
+    pub host_count: TimeSeries,
+    pub avg_latency: TimeSeries,
+    pub findings: TimeSeries,
+}
+
+impl NetworkTrends {
+    /// Compute trends from per-scan aggregates (see `database::get_scan_trends`)
+    pub fn from_records(records: &[ScanTrendRecord]) -> Self {
+        let host_count = records
+            .iter()
+            .map(|r| SeriesPoint { timestamp: r.scan_time, value: r.total_hosts as f64 })
+            .collect();
+        let avg_latency = records
+            .iter()
+            .filter_map(|r| r.avg_latency_ms.map(|v| SeriesPoint { timestamp: r.scan_time, value: v }))
+            .collect();
+        let findings = records
+            .iter()
+            .map(|r| SeriesPoint { timestamp: r.scan_time, value: r.high_risk_hosts as f64 })
+            .collect();
+
+        Self {
+            host_count: TimeSeries::new("host_count", "hosts", host_count),
+            avg_latency: TimeSeries::new("avg_latency", "ms", avg_latency),
+            findings: TimeSeries::new("findings", "high-risk hosts", findings),
+        }
+    }
+}
+
+/// Least-squares slope of values over time, in units per day
+pub(crate) fn slope_per_day(points: &[(DateTime<Utc>, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let origin = points[0].0;
+    let xs: Vec<f64> = points
+        .iter()
+        .map(|(t, _)| (*t - origin).num_seconds() as f64 / 86_400.0)
+        .collect();
+    let ys: Vec<f64> = points.iter().map(|(_, v)| *v).collect();
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let var_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = xs.iter().zip(&ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+
+    Some(cov / var_x)
+}
+
+/// Rising/falling when the daily slope exceeds 1% of the mean
+fn direction_of(slope: Option<f64>, mean: Option<f64>) -> TrendDirection {
+    let (slope, mean) = match (slope, mean) {
+        (Some(s), Some(m)) => (s, m),
+        _ => return TrendDirection::Stable,
+    };
+
+    let threshold = (mean.abs() * 0.01).max(f64::EPSILON);
+    if slope > threshold {
+        TrendDirection::Rising
+    } else if slope < -threshold {
+        TrendDirection::Falling
+    } else {
+        TrendDirection::Stable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn record(days_ago: i64, hosts: i32, latency: Option<f64>, risky: i32) -> ScanTrendRecord {
+        ScanTrendRecord {
+            scan_id: 100 - days_ago,
+            scan_time: Utc::now() - Duration::days(days_ago),
+            total_hosts: hosts,
+            avg_latency_ms: latency,
+            high_risk_hosts: risky,
+        }
+    }
+
+    #[test]
+    fn test_series_statistics() {
+        let records = vec![
+            record(2, 10, Some(5.0), 2),
+            record(1, 12, None, 2),
+            record(0, 14, Some(5.0), 1),
+        ];
+        let trends = NetworkTrends::from_records(&records);
+
+        assert_eq!(trends.host_count.points.len(), 3);
+        assert_eq!(trends.host_count.change, Some(4.0));
+        assert!((trends.host_count.slope_per_day.unwrap() - 2.0).abs() < 0.01);
+        assert_eq!(trends.host_count.direction, TrendDirection::Rising);
+
+        assert_eq!(trends.avg_latency.points.len(), 2);
+        assert_eq!(trends.avg_latency.direction, TrendDirection::Stable);
+        assert_eq!(trends.findings.direction, TrendDirection::Falling);
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let trends = NetworkTrends::from_records(&[]);
+        assert!(trends.host_count.points.is_empty());
+        assert_eq!(trends.host_count.mean, None);
+        assert_eq!(trends.host_count.direction, TrendDirection::Stable);
+    }
+}
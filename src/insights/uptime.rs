@@ -0,0 +1,138 @@
+//! Uptime signals
+//!
+//! Compares each host's uptime (SNMP sysUpTime, else the TCP timestamp
+//! estimate) with the previous scan. A host whose uptime is shorter than
+//! the time since that scan has rebooted in between, and a device that has
+//! not rebooted in years has not installed a firmware update in years. The
+//! TCP estimate may carry a random per-peer offset, so it only feeds the
+//! reboot check, never the long-uptime one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::recommendations::{Priority, Recommendation};
+use crate::config::LONG_UPTIME_DAYS;
+use crate::HostInfo;
+
+/// Slack for clock granularity and estimate error when detecting reboots
+const REBOOT_TOLERANCE_SECS: u64 = 300;
+
+/// Reboots and long-running hosts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UptimeAnalysis {
+    /// Hosts that restarted since the previous scan
+    pub rebooted: Vec<String>,
+    /// Hosts up for `LONG_UPTIME_DAYS` or more
+    pub long_running: Vec<String>,
+}
+
+impl UptimeAnalysis {
+    /// Analyze uptimes against the previous scan, taken `elapsed_secs` earlier
+    pub fn analyze(hosts: &[HostInfo], previous: &[HostInfo], elapsed_secs: u64) -> Self {
+        let before: HashMap<String, &HostInfo> = previous.iter().map(|h| (h.identity(), h)).collect();
+
+        let mut analysis = Self::default();
+        for host in hosts {
+            // Compare like with like: SNMP with SNMP, estimate with estimate
+            let pair = before.get(&host.identity()).and_then(|earlier| {
+                host.uptime_seconds
+                    .zip(earlier.uptime_seconds)
+                    .or(host.tcp_uptime_seconds.zip(earlier.tcp_uptime_seconds))
+            });
+            if let Some((uptime, earlier)) = pair {
+                if uptime + REBOOT_TOLERANCE_SECS < earlier + elapsed_secs {
+                    analysis.rebooted.push(host.ip.clone());
+                }
+            }
+            let Some(uptime) = host.uptime_seconds else { continue };
+            if uptime >= LONG_UPTIME_DAYS * 86_400 {
+                analysis.long_running.push(host.ip.clone());
+            }
+        }
+        analysis
+    }
+
+    pub fn recommendations(&self) -> Vec<Recommendation> {
+        let mut recs = Vec::new();
+
+        if !self.long_running.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::Medium,
+                category: "Patching".to_string(),
+                title: format!(
+                    "{} device(s) up for {}+ years, likely unpatched",
+                    self.long_running.len(),
+                    LONG_UPTIME_DAYS / 365
+                ),
+                description: "These devices have not restarted in years, so no firmware or kernel update has \
+                              been applied in that time. Check for vendor updates and schedule a maintenance \
+                              window to install them."
+                    .to_string(),
+                affected_devices: self.long_running.clone(),
+            });
+        }
+
+        if !self.rebooted.is_empty() {
+            recs.push(Recommendation {
+                priority: Priority::Info,
+                category: "Availability".to_string(),
+                title: format!("{} device(s) rebooted since the last scan", self.rebooted.len()),
+                description: "Uptime is shorter than the time since the previous scan. Expected after updates \
+                              or power work; unexplained repeated reboots point to power, overheating or \
+                              firmware crashes."
+                    .to_string(),
+                affected_devices: self.rebooted.clone(),
+            });
+        }
+
+        recs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str, uptime: Option<u64>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            uptime_seconds: uptime,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reboot_and_long_uptime() {
+        let day = 86_400;
+        let previous = vec![host("10.0.0.1", "AA:00:00:00:00:01", Some(10 * day)), host("10.0.0.2", "AA:00:00:00:00:02", Some(day))];
+        let current = vec![
+            host("10.0.0.1", "AA:00:00:00:00:01", Some(3_600)),
+            host("10.0.0.2", "AA:00:00:00:00:02", Some(2 * day)),
+            host("10.0.0.3", "AA:00:00:00:00:03", Some(4 * 365 * day)),
+        ];
+
+        let analysis = UptimeAnalysis::analyze(&current, &previous, day);
+        assert_eq!(analysis.rebooted, vec!["10.0.0.1"]);
+        assert_eq!(analysis.long_running, vec!["10.0.0.3"]);
+        assert_eq!(analysis.recommendations().len(), 2);
+    }
+
+    #[test]
+    fn test_tcp_estimate_only_detects_reboots() {
+        let day = 86_400;
+        let estimated = |uptime: u64| {
+            let mut h = host("10.0.0.4", "AA:00:00:00:00:04", None);
+            h.tcp_uptime_seconds = Some(uptime);
+            h
+        };
+        // An offset clock reading years: not reported as long-running
+        let analysis = UptimeAnalysis::analyze(&[estimated(5 * 365 * day)], &[estimated(5 * 365 * day - day)], day);
+        assert!(analysis.rebooted.is_empty() && analysis.long_running.is_empty());
+
+        let analysis = UptimeAnalysis::analyze(&[estimated(600)], &[estimated(5 * 365 * day)], day);
+        assert_eq!(analysis.rebooted, vec!["10.0.0.4"]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Subnet utilization and DHCP exhaustion insight
+//!
+//! Computes address-space usage per subnet, its trend over stored scan
+//! history, and when the address pool will run out at the current growth rate
+
+use chrono::{DateTime, Duration, Utc};
+use ipnetwork::Ipv4Network;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::recommendations::{Priority, Recommendation};
+use super::trends::slope_per_day;
+use crate::database::ScanRecord;
+
+/// Utilization at which a resize is recommended
+const RESIZE_THRESHOLD: f64 = 0.8;
+
+/// Target utilization after resizing
+const TARGET_UTILIZATION: f64 = 0.7;
+
+/// Horizon (days) used when sizing for growth
+const GROWTH_HORIZON_DAYS: f64 = 365.0;
+
+/// Largest network suggested by the resize recommendation
+const MIN_SUGGESTED_PREFIX: u8 = 8;
+
+/// Utilization analysis for a single subnet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubnetUtilization {
+    pub subnet: String,
+    pub prefix_len: u8,
+    /// Usable addresses in the pool (DHCP pool size if known, else subnet hosts)
+    pub capacity: usize,
+    /// Addresses currently in use
+    pub used: usize,
+    pub utilization_percent: f64,
+    /// Linear growth in hosts per day over stored history
+    pub growth_per_day: Option<f64>,
+    /// Days until the pool is exhausted at the current growth rate
+    pub days_until_exhaustion: Option<f64>,
+    pub projected_exhaustion: Option<DateTime<Utc>>,
+    /// Prefix length recommended to fit a year of growth
+    pub recommended_prefix: Option<u8>,
+    /// (timestamp, host count) points used for the trend, oldest first
+    pub history: Vec<(DateTime<Utc>, usize)>,
+}
+
+impl SubnetUtilization {
+    /// Analyze a subnet's utilization
+    ///
+    /// `history` holds past scans of this subnet (any order). `pool_size`
+    /// overrides the capacity when the DHCP scope is smaller than the subnet.
+    pub fn analyze(
+        subnet: &str,
+        used: usize,
+        history: &[ScanRecord],
+        pool_size: Option<usize>,
+    ) -> Option<Self> {
+        let network: Ipv4Network = subnet.parse().ok()?;
+        let capacity = pool_size.unwrap_or_else(|| usable_hosts(network.prefix()));
+
+        let mut points: Vec<(DateTime<Utc>, usize)> = history
+            .iter()
+            .filter(|s| s.subnet == subnet)
+            .map(|s| (s.scan_time, s.total_hosts.max(0) as usize))
+            .collect();
+        points.sort_by_key(|(t, _)| *t);
+
+        let utilization = if capacity > 0 { used as f64 / capacity as f64 } else { 1.0 };
+        let series: Vec<(DateTime<Utc>, f64)> = points.iter().map(|(t, n)| (*t, *n as f64)).collect();
+        let growth_per_day = slope_per_day(&series);
+
+        let days_until_exhaustion = match growth_per_day {
+            Some(rate) if rate > 0.0 => Some((capacity.saturating_sub(used)) as f64 / rate),
+            _ => None,
+        };
+        let projected_exhaustion = days_until_exhaustion
+            .map(|days| Utc::now() + Duration::seconds((days * 86_400.0) as i64));
+
+        let projected_used = projected_used(used, growth_per_day);
+        let needs_resize = utilization >= RESIZE_THRESHOLD || projected_used > capacity as f64;
+        let recommended_prefix = if needs_resize {
+            suggest_prefix(network.prefix(), projected_used.max(used as f64))
+        } else {
+            None
+        };
+
+        Some(Self {
+            subnet: subnet.to_string(),
+            prefix_len: network.prefix(),
+            capacity,
+            used,
+            utilization_percent: utilization * 100.0,
+            growth_per_day,
+            days_until_exhaustion,
+            projected_exhaustion,
+            recommended_prefix,
+            history: points,
+        })
+    }
+
+    /// Recommendation to resize the prefix, if one is warranted
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        let prefix = self.recommended_prefix?;
+
+        let priority = match self.days_until_exhaustion {
+            Some(days) if days < 30.0 => Priority::High,
+            _ if self.utilization_percent >= 95.0 => Priority::High,
+            _ => Priority::Medium,
+        };
+
+        let outlook = match self.days_until_exhaustion {
+            Some(days) => format!(" At the current growth rate the pool is exhausted in about {:.0} days.", days),
+            None => String::new(),
+        };
+        let needed = projected_used(self.used, self.growth_per_day).max(self.used as f64);
+        let fit = if (usable_hosts(prefix) as f64) * TARGET_UTILIZATION < needed {
+            " Even that leaves no headroom for projected growth; split the hosts across several subnets."
+        } else {
+            ""
+        };
+
+        Some(Recommendation {
+            priority,
+            category: "Capacity".to_string(),
+            title: format!("Subnet {} is running out of addresses", self.subnet),
+            description: format!(
+                "{} of {} addresses in use ({:.0}%).{} Consider resizing /{} to /{} ({} usable addresses) or shortening DHCP lease times.{}",
+                self.used,
+                self.capacity,
+                self.utilization_percent,
+                outlook,
+                self.prefix_len,
+                prefix,
+                usable_hosts(prefix),
+                fit
+            ),
+            affected_devices: vec![],
+        })
+    }
+}
+
+/// Analyze every subnet present in the scan history, using its latest scan as current
+pub fn utilization_by_subnet(scans: &[ScanRecord]) -> Vec<SubnetUtilization> {
+    let mut by_subnet: HashMap<&str, Vec<&ScanRecord>> = HashMap::new();
+    for scan in scans {
+        by_subnet.entry(scan.subnet.as_str()).or_default().push(scan);
+    }
+
+    let mut results: Vec<SubnetUtilization> = by_subnet
+        .into_iter()
+        .filter_map(|(subnet, records)| {
+            let latest = records.iter().max_by_key(|s| s.scan_time)?;
+            let history: Vec<ScanRecord> = records.iter().map(|s| (*s).clone()).collect();
+            SubnetUtilization::analyze(subnet, latest.total_hosts.max(0) as usize, &history, None)
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.utilization_percent.total_cmp(&a.utilization_percent));
+    results
+}
+
+/// Usable host addresses for a prefix length
+fn usable_hosts(prefix: u8) -> usize {
+    match prefix {
+        32 => 1,
+        31 => 2,
+        p => (1usize << (32 - p as u32)) - 2,
+    }
+}
+
+/// Hosts expected after `GROWTH_HORIZON_DAYS` at the current growth rate
+fn projected_used(used: usize, growth_per_day: Option<f64>) -> f64 {
+    used as f64 + growth_per_day.unwrap_or(0.0).max(0.0) * GROWTH_HORIZON_DAYS
+}
+
+/// Smallest network (largest prefix) that keeps `needed` hosts under the target utilization
+///
+/// When even a `MIN_SUGGESTED_PREFIX` network is too small, that largest
+/// size is returned and the recommendation says it is not enough.
+fn suggest_prefix(current: u8, needed: f64) -> Option<u8> {
+    let required = (needed / TARGET_UTILIZATION).ceil() as usize;
+    (MIN_SUGGESTED_PREFIX..current)
+        .rev()
+        .find(|p| usable_hosts(*p) >= required)
+        .or((current > MIN_SUGGESTED_PREFIX).then_some(MIN_SUGGESTED_PREFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(days_ago: i64, hosts: i32) -> ScanRecord {
+        ScanRecord {
+            id: days_ago,
+            scan_time: Utc::now() - Duration::days(days_ago),
+            interface_name: "eth0".to_string(),
+            local_ip: "192.168.1.10".to_string(),
+            local_mac: "00:11:22:33:44:55".to_string(),
+            subnet: "192.168.1.0/24".to_string(),
+            scan_method: "ARP".to_string(),
+            arp_discovered: hosts,
+            icmp_discovered: hosts,
+            total_hosts: hosts,
+            duration_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_growth_projection() {
+        let history = vec![scan(20, 200), scan(10, 210), scan(0, 220)];
+        let util = SubnetUtilization::analyze("192.168.1.0/24", 220, &history, None).unwrap();
+
+        assert_eq!(util.capacity, 254);
+        let rate = util.growth_per_day.unwrap();
+        assert!((rate - 1.0).abs() < 0.01);
+        let days = util.days_until_exhaustion.unwrap();
+        assert!((days - 34.0).abs() < 0.5);
+        assert_eq!(util.recommended_prefix, Some(22));
+        assert!(util.recommendation().is_some());
+    }
+
+    #[test]
+    fn test_quiet_subnet_needs_no_resize() {
+        let history = vec![scan(10, 20), scan(0, 20)];
+        let util = SubnetUtilization::analyze("192.168.1.0/24", 20, &history, None).unwrap();
+        assert_eq!(util.days_until_exhaustion, None);
+        assert!(util.recommendation().is_none());
+    }
+
+    #[test]
+    fn test_dhcp_pool_override() {
+        let util = SubnetUtilization::analyze("192.168.1.0/24", 90, &[], Some(100)).unwrap();
+        assert_eq!(util.capacity, 100);
+        assert_eq!(util.recommended_prefix, Some(23));
+    }
+
+    #[test]
+    fn test_large_network_resize() {
+        let util = SubnetUtilization::analyze("10.0.0.0/16", 60_000, &[], None).unwrap();
+        assert_eq!(util.recommended_prefix, Some(15));
+        assert!(!util.recommendation().unwrap().description.contains("split the hosts"));
+
+        let util = SubnetUtilization::analyze("10.0.0.0/9", 20_000_000, &[], Some(20_000_000)).unwrap();
+        assert_eq!(util.recommended_prefix, Some(8));
+        assert!(util.recommendation().unwrap().description.contains("split the hosts"));
+    }
+}
@@ -81,6 +81,7 @@ fn get_wildcard_cve(conn: &Connection, cve_id: &str) -> Result<Vec<Vulnerability
                 description: row.get(1)?,
                 severity: row.get(2)?,
                 cvss_score: row.get(3)?,
+                cvss_vector: None,
             })
         })?
         .filter_map(|r| r.ok())
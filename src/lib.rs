@@ -22,14 +22,75 @@ pub mod network;
 pub mod scanner;
 
 pub use config::*;
-pub use database::{Database, AlertRecord, AlertSeverity, AlertType, DeviceRecord, NetworkStats, ScanRecord};
+pub use database::{
+    Database,
+    AlertRecord, AlertSeverity, AlertType, DeviceRecord, DeviceTag, HostAnnotation, NetworkStats, ScanRecord,
+};
 pub use models::*;
 pub use monitor::{BackgroundMonitor, MonitoringStatus, NetworkEvent};
-pub use network::{calculate_risk_score, calculate_subnet_ips, dns_scan, find_valid_interface, infer_device_type, is_local_subnet, is_special_address, lookup_vendor, lookup_vendor_info, DeviceType};
-pub use scanner::{active_arp_scan, icmp_scan, guess_os_from_ttl, IcmpResult, snmp_enrich, tcp_probe_scan, SnmpData, SnmpNeighbor};
+pub use network::{
+    calculate_risk_score, infer_device_type, DeviceType,
+    calculate_alias_subnet_ips, calculate_subnet_ips, is_local_subnet, is_special_address,
+    container_bridges, is_container_mac, separate_containers,
+    classify_virtual_machines, hypervisor_from_mac, likely_hypervisor_host, Hypervisor,
+    resolve_hostnames, HostnameConflict, HostnamePolicy,
+    classify_link_media,
+    correlate_dual_stack,
+    ipv6_neighbors, arp_cache, Ipv4Neighbor,
+    is_incomplete, RescanPlan, PREVIOUS_REPORT_ENV,
+    dns_scan,
+    find_interface, find_valid_interface, InterfaceFilter, InterfaceKind,
+    add_service_guess, guess_from_banner, guess_from_certificate, guess_from_port, guess_from_sys_descr,
+    identify_services,
+    MacAddress, MacFormat,
+    lookup_vendor, lookup_vendor_info, lookup_vendors, VendorInfo,
+};
+pub use scanner::{
+    active_arp_scan, arp_scan_ips, on_link_targets, parse_target_list, TARGETS_ENV, streaming_arp_scan,
+    pipelined_discovery, DiscoveryResult,
+    captive_portal_check, ConnectivityState,
+    ScanCheckpoint, CHECKPOINT_ENV,
+    clock_skew_recommendation, clock_skew_scan, ClockSkew, ClockSource,
+    apply_custom_probes, custom_probe_scan, CustomProbe, CustomProbeMatch, ProbeRegistry, ResponseMatcher,
+    CUSTOM_PROBES_ENV,
+    dns_server_scan, DnsServerInfo,
+    local_zone_candidates, zone_transfer_scan, ZoneTransferResult,
+    active_directory_scan,
+    exposure_scan, ExposureFinding,
+    fdb_scan, locate_hosts,
+    apply_firmware, firmware_scan,
+    dscp_latency_comparison, init_probe_marking, Dscp, ProbeMarking, QosLatency, PROBE_DSCP_ENV, PROBE_TTL_ENV,
+    init_audit_log, probes_to, read_audit_log, AuditRecord, ProbeKind, AUDIT_LOG_ENV,
+    icmp_scan, icmp_scan_with_payload, guess_os_from_ttl, HostState, IcmpPayload, IcmpResult, PayloadPattern,
+    ICMP_PAYLOAD_ENV,
+    nat_check, NatKind, NatReport,
+    ntp_server_scan, NtpServerInfo,
+    open_ports, connect_times, tcp_port_scan_adaptive, tcp_port_scan, tcp_probe_scan,
+    adaptive_timeout, HostTimeouts,
+    init_shared_pacer,
+    ScanConfig, ScanProfile, SCAN_PROFILE_ENV,
+    router_import, RouterEntrySource, RouterHost, RouterKind,
+    apply_smart_home, smart_home_scan, SmartHomeDevice, SmartHomeKind,
+    smb_shares, SmbShare, SmbShareReport,
+    snmp_enrich, SnmpData, SnmpNeighbor,
+    community_findings, snmp_community_scan, CommunityAccess, CommunityAccessLevel,
+    tcp_uptime_scan, TcpUptime,
+    throughput_probe, ThroughputMethod, ThroughputResult,
+    tls_assess, TlsReport, TlsVersion, WeakCipherClass,
+    upnp_port_mappings, PortMapping, UpnpReport,
+    sample_wan_latency, wan_check,
+    management_plane, ManagementPlaneReport, WebAuth, WebInterface,
+    capture_router_advertisements, RouterAdvertisement,
+};
 pub use alerts::{detect_alerts, Alert, has_high_priority_alerts};
-pub use insights::{NetworkHealth, DeviceDistribution, VendorDistribution, SecurityReport, Recommendation};
-pub use exports::{export_devices_csv, export_hosts_csv, export_topology_json, export_scan_result_json, generate_scan_report_pdf, generate_network_health_pdf};
+pub use insights::{
+    NetworkHealth, HealthWeights, DeviceDistribution, VendorDistribution, DistributionComparison, SecurityReport,
+    Recommendation, CvssVector,
+};
+pub use exports::{
+    export_devices_csv, export_hosts_csv, export_topology_json, export_scan_result_json, generate_scan_report_pdf,
+    generate_network_health_pdf, generate_report, ReportFormat, export_netbox_csv, export_phpipam_csv, NetboxImport,
+};
 
 // Re-export logging macros for use across crate
 pub use crate::logging::macros;
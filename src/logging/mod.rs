@@ -4,6 +4,8 @@
 //! Logs are written to: %APPDATA%/netmapper/logs/
 
 pub mod macros;
+#[cfg(feature = "otel")]
+pub mod otel;
 
 use std::path::PathBuf;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
@@ -24,6 +26,9 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Env
 /// Set `RUST_LOG` environment variable to control log level:
 /// - `RUST_LOG=debug` for debug level
 /// - `RUST_LOG=trace` for trace level
+///
+/// With the `otel` feature spans are also exported over OTLP, which needs
+/// a Tokio runtime: call from within one.
 pub fn init_logging() -> Result<PathBuf, Box<dyn std::error::Error>> {
     // Get log directory path
     let log_dir = get_log_directory()?;
@@ -59,11 +64,18 @@ pub fn init_logging() -> Result<PathBuf, Box<dyn std::error::Error>> {
     let filter = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new("info"))?;
     
+    // Export spans to an OpenTelemetry collector
+    #[cfg(feature = "otel")]
+    let otel_layer = Some(otel::otel_layer()?);
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
     // Initialize subscriber with both layers
     tracing_subscriber::registry()
         .with(filter)
         .with(console_layer)
         .with(file_layer)
+        .with(otel_layer)
         .init();
     
     tracing::info!("Logging initialized. Log directory: {}", log_dir.display());
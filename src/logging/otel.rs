@@ -0,0 +1,48 @@
+//! OpenTelemetry trace export (`otel` feature)
+//!
+//! Scan phases and per-host probes are `tracing` spans. With the `otel`
+//! feature they are exported over OTLP (gRPC, `OTEL_EXPORTER_OTLP_ENDPOINT`,
+//! default `http://localhost:4317`) so scan activity lines up with network
+//! incidents in the collector's traces. Without the feature the spans cost
+//! next to nothing and go nowhere.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// Service name reported with every span
+const SERVICE_NAME: &str = "host-discovery";
+
+/// Layer exporting spans over OTLP; must be created inside a Tokio runtime
+pub fn otel_layer<S>() -> Result<impl Layer<S>, Box<dyn std::error::Error>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder().with_tonic().build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", SERVICE_NAME),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]))
+        .build();
+    let tracer = provider.tracer(SERVICE_NAME);
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Export spans from a process that has no other subscriber (the CLI);
+/// must be called inside a Tokio runtime
+pub fn init_span_export() -> Result<(), Box<dyn std::error::Error>> {
+    let filter = EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new("info"))?;
+    tracing_subscriber::registry().with(filter).with(otel_layer()?).try_init()?;
+    Ok(())
+}
+
+/// Flush spans still batched for export; call before exiting
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
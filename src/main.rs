@@ -6,15 +6,37 @@
 //! - TCP port probing (service detection)
 //! - SNMP enrichment (optional)
 
-use anyhow::{Context, Result};
-use std::net::Ipv4Addr;
+use anyhow::{anyhow, Context, Result};
+use ipnetwork::Ipv4Network;
+use pnet::util::MacAddr;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Instant;
+use tracing::Instrument;
 
+use host_discovery::scanner::ArpMonitor;
 use host_discovery::{
-    active_arp_scan, calculate_risk_score, calculate_subnet_ips, dns_scan, find_valid_interface,
-    guess_os_from_ttl, icmp_scan, infer_device_type, lookup_vendor_info, snmp_enrich,
-    tcp_probe_scan, HostInfo, InterfaceInfo, NeighborInfo, ScanResult, SNMP_ENABLED,
+    active_arp_scan, pipelined_discovery, calculate_risk_score, calculate_alias_subnet_ips, calculate_subnet_ips, is_local_subnet, on_link_targets, parse_target_list, TARGETS_ENV, classify_link_media, correlate_dual_stack, ipv6_neighbors, RescanPlan, PREVIOUS_REPORT_ENV, dns_scan, fdb_scan, find_valid_interface, locate_hosts,
+    guess_os_from_ttl, icmp_scan_with_payload, ICMP_PAYLOAD_ENV, init_probe_marking, init_audit_log, AUDIT_LOG_ENV, dscp_latency_comparison, PROBE_DSCP_ENV, PROBE_TTL_ENV, PING_SAMPLES, infer_device_type, lookup_vendor_info, open_ports, snmp_enrich,
+    tcp_port_scan, throughput_probe, HostInfo, InterfaceInfo, NeighborInfo, ScanResult,
+    ThroughputMethod, wan_check, router_import, RouterEntrySource, ROUTER_IMPORT_TARGET,
+    THROUGHPUT_PROBE_ENABLED, WAN_CHECK_ENABLED, apply_smart_home, smart_home_scan,
+    apply_firmware, firmware_scan, upnp_port_mappings, UPNP_CHECK_ENABLED,
+    ScanCheckpoint, CHECKPOINT_ENV, init_shared_pacer, ScanConfig, ScanProfile, SCAN_PROFILE_ENV,
+    CHECKPOINT_CHUNK_SIZE, Port, connect_times,
+    tcp_uptime_scan, TCP_UPTIME_PROBE_ENABLED, clock_skew_recommendation, clock_skew_scan, CLOCK_SKEW_CHECK_ENABLED,
+    container_bridges, separate_containers, DeviceType, CONTAINER_SCAN_ENABLED, classify_virtual_machines,
+    resolve_hostnames, HostnameRecord, HostnameSource,
+    apply_custom_probes, custom_probe_scan, ProbeRegistry, CUSTOM_PROBES_ENV, MacAddress, lookup_vendors, identify_services,
+    capture_router_advertisements, RA_LISTEN_DURATION, RA_LISTEN_ENABLED, active_directory_scan, AD_DETECTION_ENABLED,
+    dns_server_scan, local_zone_candidates, zone_transfer_scan, DNS_SERVER_CHECK_ENABLED,
+    exposure_scan, EXPOSURE_CHECK_ENABLED, nat_check, NAT_CHECK_ENABLED, community_findings, snmp_community_scan,
 };
+use host_discovery::insights::{
+    active_directory_recommendations, open_resolver_recommendations, zone_transfer_recommendations,
+    ComplianceReport, Ipv6Readiness, Policy, POLICY_ENV,
+};
+use host_discovery::network::{default_gateway, system_nameservers, Ipv6Neighbor};
 
 /// Logs a message to stderr
 macro_rules! log_stderr {
@@ -30,35 +52,257 @@ macro_rules! log_error {
     };
 }
 
+/// Hosts heard in ARP traffic without sending anything (stealth profile)
+async fn passive_arp_listen(interface: &InterfaceInfo, duration: std::time::Duration) -> HashMap<Ipv4Addr, MacAddr> {
+    log_stderr!("Listening passively for {}s before sending...", duration.as_secs());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(256);
+    let monitor = ArpMonitor::new(interface.pnet_interface.clone());
+    tokio::spawn(async move {
+        if let Err(e) = monitor.start_monitoring(tx).await {
+            log_error!("Passive ARP listening failed: {}", e);
+        }
+    });
+
+    let mut heard = HashMap::new();
+    let deadline = tokio::time::Instant::now() + duration;
+    while let Ok(Some(event)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+        if let (Ok(ip), Ok(mac)) = (event.sender_ip.parse::<Ipv4Addr>(), event.sender_mac.parse::<MacAddr>()) {
+            heard.insert(ip, mac);
+        }
+    }
+    log_stderr!("Passive listening heard {} hosts", heard.len());
+    heard
+}
+
+/// ARP sweep and TCP probes in resumable chunks (phases 1 and 3)
+///
+/// Each finished chunk is written to the checkpoint; chunks completed by an
+/// earlier, interrupted run are skipped and their results reused.
+async fn checkpointed_sweep(
+    interface: &InterfaceInfo,
+    subnet: &Ipv4Network,
+    ips: &[Ipv4Addr],
+    ports: &[u16],
+    mut checkpoint: ScanCheckpoint,
+) -> Result<(HashMap<Ipv4Addr, MacAddr>, HashMap<Ipv4Addr, Vec<Port>>)> {
+    let total = checkpoint.chunk_count(ips);
+    let pending: Vec<(usize, Vec<Ipv4Addr>)> = checkpoint.pending(ips).map(|(i, chunk)| (i, chunk.to_vec())).collect();
+    if pending.len() < total {
+        log_stderr!("Resuming from checkpoint: {} of {} chunks already done", total - pending.len(), total);
+    }
+
+    for (index, chunk) in pending {
+        log_stderr!("Chunk {}/{}: {} addresses", index + 1, total, chunk.len());
+        let hosts = tokio::task::spawn_blocking({
+            let interface = interface.clone();
+            let subnet = *subnet;
+            move || active_arp_scan(&interface, &chunk, &subnet)
+        })
+        .await
+        .context("ARP scan task failed")??;
+        let probes = tcp_port_scan(&hosts, ports).await?;
+        checkpoint.complete_chunk(index, &hosts, &probes)?;
+    }
+
+    let hosts = checkpoint.host_map();
+    let ports = checkpoint.ports.clone().into_iter().collect();
+    if let Err(e) = checkpoint.finish() {
+        log_error!("Could not remove checkpoint: {}", e);
+    }
+    Ok((hosts, ports))
+}
+
+/// Capture IPv6 Router Advertisements, log what they announce and warn about rogue advertisers
+///
+/// Returns the advertising routers as neighbors so they are correlated with their IPv4 hosts.
+async fn listen_router_advertisements(interface: &InterfaceInfo, hosts: &[HostInfo]) -> Vec<Ipv6Neighbor> {
+    log_stderr!("Listening for IPv6 Router Advertisements ({}s)...", RA_LISTEN_DURATION.as_secs());
+    let captured = tokio::task::spawn_blocking({
+        let pnet_interface = interface.pnet_interface.clone();
+        move || capture_router_advertisements(&pnet_interface, RA_LISTEN_DURATION)
+    })
+    .await
+    .context("RA capture task failed")
+    .and_then(|result| result);
+    let advertisements = match captured {
+        Ok(advertisements) => advertisements,
+        Err(e) => {
+            log_error!("Router Advertisement capture failed: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let gateway = default_gateway().map(|ip| ip.to_string());
+    let gateway_mac = hosts.iter().find(|h| Some(&h.ip) == gateway.as_ref()).map(|h| h.mac.as_str());
+    let mut readiness = Ipv6Readiness::assess(hosts, &[], &[]);
+    readiness.apply_router_advertisements(&advertisements, gateway_mac);
+    log_stderr!(
+        "{} IPv6 routers advertising prefixes [{}], DNS [{}]",
+        readiness.advertising_routers.len(),
+        readiness.advertised_prefixes.join(", "),
+        readiness.rdns_servers.join(", ")
+    );
+    for suspect in &readiness.rogue_advertisers {
+        log_error!("Rogue IPv6 Router Advertisement from {}", suspect);
+    }
+    advertisements.iter().map(|ra| ra.as_neighbor()).collect()
+}
+
+/// ARP sweep of each local Docker/Podman bridge, hosts labeled as containers
+async fn scan_container_bridges() -> Vec<HostInfo> {
+    let mut containers = Vec::new();
+    for bridge in container_bridges() {
+        let (subnet, ips) = match calculate_subnet_ips(&bridge) {
+            Ok(range) => range,
+            Err(e) => {
+                log_error!("Skipping container bridge {}: {}", bridge.name, e);
+                continue;
+            }
+        };
+        log_stderr!("Scanning container bridge {} ({})...", bridge.name, subnet);
+        let found = tokio::task::spawn_blocking({
+            let bridge = bridge.clone();
+            move || active_arp_scan(&bridge, &ips, &subnet)
+        })
+        .await
+        .context("ARP scan task failed")
+        .and_then(|result| result);
+        let hosts = match found {
+            Ok(hosts) => hosts,
+            Err(e) => {
+                log_error!("Container bridge {} scan failed: {}", bridge.name, e);
+                continue;
+            }
+        };
+
+        let vendors = lookup_vendors(&hosts);
+        for (ip, mac) in hosts.into_iter().filter(|(ip, _)| *ip != bridge.ip) {
+            let mac = MacAddress::from(mac).to_string();
+            let vendor_info = vendors.get(&ip).cloned().unwrap_or_default();
+            containers.push(HostInfo {
+                ip: ip.to_string(),
+                mac,
+                ipv6_addresses: Vec::new(),
+                vendor: vendor_info.vendor,
+                is_randomized: vendor_info.is_randomized,
+                response_time_ms: None,
+                latency_stats: None,
+                packet_loss_percent: None,
+                ttl: None,
+                os_guess: None,
+                device_type: DeviceType::Container.as_str().to_string(),
+                risk_score: calculate_risk_score(DeviceType::Container, &[], vendor_info.is_randomized),
+                open_ports: Vec::new(),
+                ports: Vec::new(),
+                discovery_method: "CONTAINER_ARP".to_string(),
+                hostname: None,
+                system_description: None,
+                uptime_seconds: None,
+                tcp_uptime_seconds: None,
+                firmware: None,
+                neighbors: Vec::new(),
+                switch_port: None,
+                link_medium: None,
+                vulnerabilities: Vec::new(),
+                port_warnings: Vec::new(),
+                security_grade: String::new(),
+                friendly_name: None,
+                notes: None,
+                hostname_records: Vec::new(),
+                port_connect_ms: BTreeMap::new(),
+                observed_interfaces: Vec::new(),
+                service_guesses: Vec::new(),
+                arp_reply: None,
+            });
+        }
+    }
+    containers
+}
+
+/// Default gateway from the routing table, when it sits on the scanned interface's subnet
+fn interface_gateway(interface: &InterfaceInfo) -> Option<Ipv4Addr> {
+    default_gateway().filter(|gateway| is_local_subnet(*gateway, interface))
+}
+
+/// Span covering one scan phase, exported with the `otel` feature
+fn phase_span(phase: &'static str) -> tracing::Span {
+    tracing::info_span!("scan_phase", phase)
+}
+
 /// Performs the complete network scan
-async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
+async fn scan_network(interface: &InterfaceInfo, config: &ScanConfig) -> Result<ScanResult> {
     let start_time = Instant::now();
-    let (subnet, ips) = calculate_subnet_ips(interface)?;
+    let (subnet, mut ips) = calculate_subnet_ips(interface)?;
+    for (alias, alias_ips) in calculate_alias_subnet_ips(interface)? {
+        log_stderr!("Interface also attached to {}, adding {} targets", alias, alias_ips.len());
+        ips.extend(alias_ips);
+    }
+
+    // Explicit target list replaces the subnet sweep (must be on-link)
+    if let Ok(list) = std::env::var(TARGETS_ENV) {
+        ips = on_link_targets(interface, &parse_target_list(&list)?)?;
+        log_stderr!("Scanning {} listed targets instead of the full subnet", ips.len());
+    }
+
+    // Incremental mode: only previously silent addresses and incomplete hosts
+    let plan = match std::env::var(PREVIOUS_REPORT_ENV) {
+        Ok(path) => {
+            let json = std::fs::read_to_string(&path).with_context(|| format!("Cannot read {}", path))?;
+            let previous: ScanResult =
+                serde_json::from_str(&json).with_context(|| format!("Invalid previous report {}", path))?;
+            let plan = RescanPlan::from_previous(&previous, &ips);
+            log_stderr!(
+                "Incremental rescan: {} addresses to probe, {} hosts carried over",
+                plan.targets.len(),
+                plan.carried_over.len()
+            );
+            ips = plan.targets.clone();
+            Some(plan)
+        }
+        Err(_) => None,
+    };
 
-    log_stderr!("Starting Active ARP + ICMP scan on subnet {}...", subnet);
+    log_stderr!("Starting {} scan on subnet {}...", config.profile.as_str(), subnet);
     log_stderr!("================================================");
 
-    // Phase 1: Active ARP Scan
-    let arp_hosts = tokio::task::spawn_blocking({
-        let interface = interface.clone();
-        let ips = ips.clone();
-        let subnet = subnet.clone();
-        move || active_arp_scan(&interface, &ips, &subnet)
-    })
-    .await
-    .context("ARP scan task failed")??;
+    let heard = match config.passive_listen {
+        Some(duration) => passive_arp_listen(interface, duration).await,
+        None => HashMap::new(),
+    };
 
-    let arp_count = arp_hosts.len();
+    let (mut arp_hosts, arp_replies, mut vendors, response_times, port_results) =
+        match std::env::var(CHECKPOINT_ENV) {
+            Ok(path) => {
+                let checkpoint = ScanCheckpoint::load_or_new(path, &subnet.to_string(), CHECKPOINT_CHUNK_SIZE)?;
+                let (arp_hosts, port_results) =
+                    checkpointed_sweep(interface, &subnet, &ips, &config.tcp_ports, checkpoint).instrument(phase_span("discovery")).await?;
+                // Phase 2: latency is not checkpointed, it would be stale on resume
+                let response_times = if config.icmp {
+                    icmp_scan_with_payload(&arp_hosts, &config.icmp_payload).instrument(phase_span("icmp")).await?
+                } else {
+                    HashMap::new()
+                };
+                let vendors = lookup_vendors(&arp_hosts);
+                // Reply statistics only exist for the chunks swept in this run
+                (arp_hosts, HashMap::new(), vendors, response_times, port_results)
+            }
+            Err(_) => {
+                // Phases 1-3: ICMP and TCP probes start as each ARP reply arrives
+                let found = pipelined_discovery(interface, &ips, &subnet, &config.tcp_ports, config.icmp.then_some(&config.icmp_payload))
+                    .instrument(phase_span("discovery"))
+                    .await?;
+                (found.hosts, found.arp_replies, found.vendors, found.latency, found.ports)
+            }
+        };
 
-    // Phase 2 & 3: Run ICMP ping and TCP probe in parallel for faster scanning
-    let (response_times_result, port_results_result) = tokio::join!(
-        icmp_scan(&arp_hosts),
-        tcp_probe_scan(&arp_hosts)
-    );
-    
-    let response_times = response_times_result?;
-    let icmp_count = response_times.len();
-    let port_results = port_results_result?;
+    let heard: HashMap<Ipv4Addr, MacAddr> = heard
+        .into_iter()
+        .filter(|(ip, _)| is_local_subnet(*ip, interface) && !arp_hosts.contains_key(ip))
+        .collect();
+    vendors.extend(lookup_vendors(&heard));
+    arp_hosts.extend(heard);
+    let arp_count = arp_hosts.len();
+    let icmp_count = response_times.values().filter(|r| r.is_up()).count();
 
     // Phase 4: SNMP enrichment (if enabled)
     let host_ips: Vec<Ipv4Addr> = arp_hosts
@@ -67,14 +311,63 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
         .copied()
         .collect();
     
-    let snmp_data = if SNMP_ENABLED {
-        snmp_enrich(&host_ips).await.unwrap_or_default()
+    let snmp_data = if config.snmp {
+        snmp_enrich(&host_ips).instrument(phase_span("snmp")).await.unwrap_or_default()
     } else {
         std::collections::HashMap::new()
     };
 
+    // Guessable (and, if enabled, writable) community strings
+    let snmp_communities = if config.snmp {
+        snmp_community_scan(&host_ips).instrument(phase_span("snmp_community")).await
+    } else {
+        HashMap::new()
+    };
+
+    // Switch forwarding tables from the SNMP responders, for host port locations
+    let snmp_agents: Vec<Ipv4Addr> = snmp_data.keys().copied().collect();
+    let fdb_tables = fdb_scan(&snmp_agents).instrument(phase_span("fdb")).await;
+
     // Phase 5: DNS reverse lookup
-    let dns_hostnames = dns_scan(&host_ips).await;
+    let dns_hostnames = dns_scan(&host_ips).instrument(phase_span("dns")).await;
+
+    // Phase 6: Gateway throughput (if enabled)
+    if THROUGHPUT_PROBE_ENABLED {
+        let gateway = interface_gateway(interface)
+            .filter(|ip| port_results.get(ip).is_some_and(|p| open_ports(p).contains(&80)));
+        if let Some(gateway) = gateway {
+            let target = SocketAddr::new(IpAddr::V4(gateway), 80);
+            let method = ThroughputMethod::HttpDownload { path: "/".to_string() };
+            if let Err(e) = throughput_probe(target, method).await {
+                log_error!("Throughput probe failed: {}", e);
+            }
+        }
+    }
+
+    // Phase 7: Public IP and internet reachability (if enabled)
+    let wan = if WAN_CHECK_ENABLED { Some(wan_check().instrument(phase_span("wan")).await) } else { None };
+
+    // Double NAT and carrier-grade NAT (if enabled)
+    let nat = if NAT_CHECK_ENABLED {
+        match nat_check().instrument(phase_span("nat")).await {
+            Ok(report) => Some(report),
+            Err(e) => {
+                log_error!("NAT check failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Phase 8: Router ARP/DHCP tables, for hosts on other VLANs (if configured)
+    let router_hosts = match ROUTER_IMPORT_TARGET {
+        Some((target, kind)) => router_import(target, kind).instrument(phase_span("router_import")).await.unwrap_or_else(|e| {
+            log_error!("Router import failed: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
 
     // Build results (exclude local machine from ARP - we add it separately)
     let mut active_hosts: Vec<HostInfo> = arp_hosts
@@ -82,10 +375,10 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
         .filter(|(ip, _)| **ip != interface.ip)
         .map(|(ip, mac)| {
             let icmp_result = response_times.get(ip);
-            let response_time = icmp_result.map(|r| r.duration.as_millis() as u64);
+            let response_time = icmp_result.and_then(|r| r.duration).map(|d| d.as_millis() as u64);
             let ttl = icmp_result.and_then(|r| r.ttl);
             let os_guess = ttl.map(guess_os_from_ttl);
-            let open_ports = port_results.get(ip).cloned().unwrap_or_default();
+            let open_ports = port_results.get(ip).map(|p| open_ports(p)).unwrap_or_default();
             let snmp = snmp_data.get(ip);
             
             let mut method = match (response_time.is_some(), !open_ports.is_empty()) {
@@ -99,8 +392,8 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
                 method.push_str("+SNMP");
             }
 
-            let mac_str = format!("{}", mac);
-            let vendor_info = lookup_vendor_info(&mac_str);
+            let mac_str = MacAddress::from(*mac).to_string();
+            let vendor_info = vendors.get(ip).cloned().unwrap_or_default();
             
             // Infer device type and calculate risk score
             // Gateway detection: typically ends in .1 or has web interface on port 80
@@ -118,18 +411,32 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
                 vendor: vendor_info.vendor,
                 is_randomized: vendor_info.is_randomized,
                 mac: mac_str,
+                ipv6_addresses: Vec::new(),
                 response_time_ms: response_time,
+                latency_stats: icmp_result.and_then(|r| r.stats.clone()),
+                packet_loss_percent: icmp_result.filter(|r| r.is_up()).map(|r| r.loss_percent()),
                 ttl,
                 os_guess,
                 device_type: device_type.as_str().to_string(),
                 risk_score,
+                port_connect_ms: port_results.get(ip).map(|p| connect_times(p)).unwrap_or_default(),
                 open_ports,
+                ports: port_results.get(ip).cloned().unwrap_or_default(),
                 discovery_method: method,
-                // DNS hostname takes precedence, fallback to SNMP hostname
+                // DNS hostname for now; the configured precedence is applied once all sources are in
                 hostname: dns_hostnames.get(ip).cloned()
                     .or_else(|| snmp.and_then(|s| s.hostname.clone())),
+                hostname_records: [
+                    dns_hostnames.get(ip).cloned().map(|name| HostnameRecord { source: HostnameSource::Ptr, name }),
+                    snmp.and_then(|s| s.hostname.clone()).map(|name| HostnameRecord { source: HostnameSource::Snmp, name }),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
                 system_description: snmp.and_then(|s| s.system_description.clone()),
                 uptime_seconds: snmp.and_then(|s| s.uptime_seconds),
+                tcp_uptime_seconds: None,
+                firmware: None,
                 neighbors: snmp.map(|s| {
                     s.neighbors.iter().map(|n| NeighborInfo {
                         local_port: n.local_port.clone(),
@@ -138,6 +445,19 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
                         remote_ip: n.remote_ip.clone(),
                     }).collect()
                 }).unwrap_or_default(),
+                switch_port: None,
+                link_medium: None,
+                observed_interfaces: Vec::new(),
+                service_guesses: Vec::new(),
+                arp_reply: arp_replies.get(ip).cloned(),
+                vulnerabilities: Vec::new(),
+                port_warnings: snmp_communities
+                    .get(ip)
+                    .map(|accepted| community_findings(accepted).iter().map(|f| f.to_port_warning()).collect())
+                    .unwrap_or_default(),
+                security_grade: String::new(),
+                friendly_name: None,
+                notes: None,
             }
         })
         .collect();
@@ -156,19 +476,226 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
         vendor: local_vendor_info.vendor,
         is_randomized: local_vendor_info.is_randomized,
         mac: local_mac,
+        ipv6_addresses: Vec::new(),
         response_time_ms: Some(0),
+        latency_stats: None,
+        packet_loss_percent: None,
         ttl: None,
         os_guess: None,
         device_type: local_device_type.as_str().to_string(),
         risk_score: 0,
         open_ports: Vec::new(),
+        ports: Vec::new(),
         discovery_method: "LOCAL".to_string(),
         hostname: None,
         system_description: None,
         uptime_seconds: None,
+        tcp_uptime_seconds: None,
+        firmware: None,
         neighbors: Vec::new(),
+        switch_port: None,
+        link_medium: None,
+        hostname_records: Vec::new(),
+        port_connect_ms: BTreeMap::new(),
+        observed_interfaces: Vec::new(),
+        service_guesses: Vec::new(),
+        arp_reply: None,
+        vulnerabilities: Vec::new(),
+        port_warnings: Vec::new(),
+        security_grade: String::new(),
+        friendly_name: None,
+        notes: None,
     });
 
+    // Add hosts only the router knows about
+    for entry in router_hosts.into_iter().filter(|h| h.ip != interface.ip && !arp_hosts.contains_key(&h.ip)) {
+        let vendor_info = lookup_vendor_info(&entry.mac);
+        let device_type = infer_device_type(vendor_info.vendor.as_deref(), entry.hostname.as_deref(), &[], false);
+        active_hosts.push(HostInfo {
+            ip: entry.ip.to_string(),
+            mac: entry.mac,
+            ipv6_addresses: Vec::new(),
+            vendor: vendor_info.vendor,
+            is_randomized: vendor_info.is_randomized,
+            response_time_ms: None,
+            latency_stats: None,
+            packet_loss_percent: None,
+            ttl: None,
+            os_guess: None,
+            device_type: device_type.as_str().to_string(),
+            risk_score: calculate_risk_score(device_type, &[], vendor_info.is_randomized),
+            open_ports: Vec::new(),
+            ports: Vec::new(),
+            discovery_method: match entry.source {
+                RouterEntrySource::Arp => "ROUTER_ARP".to_string(),
+                RouterEntrySource::DhcpLease => "ROUTER_DHCP".to_string(),
+            },
+            hostname_records: match (&entry.source, &entry.hostname) {
+                (RouterEntrySource::DhcpLease, Some(name)) => {
+                    vec![HostnameRecord { source: HostnameSource::Dhcp, name: name.clone() }]
+                }
+                _ => Vec::new(),
+            },
+            hostname: entry.hostname,
+            system_description: None,
+            uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
+            neighbors: Vec::new(),
+            switch_port: None,
+            link_medium: None,
+            vulnerabilities: Vec::new(),
+            port_warnings: Vec::new(),
+            security_grade: String::new(),
+            friendly_name: None,
+            notes: None,
+            port_connect_ms: BTreeMap::new(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
+        });
+    }
+
+    locate_hosts(&fdb_tables, &mut active_hosts);
+    let mut neighbors = ipv6_neighbors();
+    if RA_LISTEN_ENABLED {
+        neighbors.extend(listen_router_advertisements(interface, &active_hosts).await);
+    }
+    correlate_dual_stack(&mut active_hosts, &neighbors);
+    classify_virtual_machines(&mut active_hosts);
+
+    // Phase 9: Smart-home identification and IoT firmware (after merging, so router-imported hosts are covered)
+    if config.smart_home {
+        let devices = smart_home_scan(&active_hosts).instrument(phase_span("smart_home")).await;
+        apply_smart_home(&mut active_hosts, &devices);
+    }
+    if config.banners {
+        let firmware = firmware_scan(&active_hosts).instrument(phase_span("firmware")).await;
+        apply_firmware(&mut active_hosts, &firmware);
+    }
+    classify_link_media(&mut active_hosts, &[]);
+
+    // In-house services from user-registered probe payloads (if configured)
+    if let Ok(path) = std::env::var(CUSTOM_PROBES_ENV) {
+        let registry = ProbeRegistry::load(&path)?;
+        let matches = custom_probe_scan(&active_hosts, &registry).instrument(phase_span("custom_probes")).await;
+        apply_custom_probes(&mut active_hosts, &matches);
+    }
+    resolve_hostnames(&mut active_hosts, &config.hostname_policy);
+    identify_services(&mut active_hosts);
+
+    // Uptime from TCP timestamps where SNMP gave none (raw sockets)
+    if TCP_UPTIME_PROBE_ENABLED {
+        let targets: Vec<(Ipv4Addr, u16)> = active_hosts
+            .iter()
+            .filter(|h| h.uptime_seconds.is_none())
+            .filter_map(|h| Some((h.ip.parse().ok()?, *h.open_ports.first()?)))
+            .collect();
+        let source = interface.ip;
+        match tokio::task::spawn_blocking(move || tcp_uptime_scan(source, &targets)).await {
+            Ok(Ok(uptimes)) => {
+                for host in active_hosts.iter_mut() {
+                    if let Some(uptime) = host.ip.parse::<Ipv4Addr>().ok().and_then(|ip| uptimes.get(&ip)) {
+                        host.tcp_uptime_seconds = Some(uptime.uptime_seconds);
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                log_error!("TCP timestamp sampling failed: {}", e);
+            }
+            Err(e) => {
+                log_error!("TCP timestamp task failed: {}", e);
+            }
+        }
+    }
+
+    // Clock skew (ICMP timestamps, HTTP Date fallback)
+    if CLOCK_SKEW_CHECK_ENABLED {
+        let skews = clock_skew_scan(&active_hosts).await;
+        let measured: Vec<_> = skews.into_values().collect();
+        if let Some(rec) = clock_skew_recommendation(&measured) {
+            log_stderr!("{}: {}", rec.title, rec.affected_devices.join(", "));
+        }
+    }
+
+    // Active Directory domain (Kerberos/LDAP hosts, DC locator records)
+    let active_directory = if AD_DETECTION_ENABLED { active_directory_scan(&active_hosts).instrument(phase_span("active_directory")).await } else { None };
+    if let Some(ad) = &active_directory {
+        for rec in active_directory_recommendations(ad) {
+            log_stderr!("{}: {}", rec.title, rec.affected_devices.join(", "));
+        }
+    }
+
+    // DNS servers on the LAN: unexpected open resolvers and zone transfers
+    if DNS_SERVER_CHECK_ENABLED && config.banners {
+        let servers = dns_server_scan(&active_hosts, &system_nameservers()).instrument(phase_span("dns_servers")).await;
+        let zones = local_zone_candidates(&active_hosts);
+        let transfers = zone_transfer_scan(&servers, &zones).instrument(phase_span("zone_transfer")).await;
+        for rec in open_resolver_recommendations(&servers).into_iter().chain(zone_transfer_recommendations(&transfers)) {
+            log_stderr!("{}: {}", rec.title, rec.affected_devices.join(", "));
+        }
+    }
+
+    // Cleartext and unauthenticated services (Telnet, FTP, VNC, admin panels), with the banner probes
+    if EXPOSURE_CHECK_ENABLED && config.banners {
+        let exposures = exposure_scan(&active_hosts).instrument(phase_span("exposure")).await;
+        for host in active_hosts.iter_mut() {
+            let Some(found) = host.ip.parse::<Ipv4Addr>().ok().and_then(|ip| exposures.get(&ip)) else { continue };
+            host.port_warnings.extend(found.iter().map(|f| f.to_port_warning()));
+        }
+    }
+
+    // Phase 10: Port forwards opened through the gateway's UPnP IGD
+    let gateway = interface_gateway(interface);
+
+    // QoS: compare marked and best-effort echo latency to the gateway
+    if let (Some(dscp), Some(gateway)) = (config.marking.dscp, gateway) {
+        match dscp_latency_comparison(&[gateway], dscp, PING_SAMPLES).await {
+            Ok(results) => {
+                for result in results {
+                    match result.advantage_ms() {
+                        Some(ms) => {
+                            log_stderr!("QoS: {} probes to {} were {:.2} ms faster than best-effort", dscp, result.ip, ms);
+                        }
+                        None => {
+                            log_stderr!("QoS: {} did not answer both marked and unmarked probes", result.ip);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                log_error!("QoS comparison failed: {}", e);
+            }
+        }
+    }
+    if let (true, Some(gateway)) = (UPNP_CHECK_ENABLED, gateway) {
+        match upnp_port_mappings(gateway).await {
+            Ok(report) => {
+                let findings = report.findings();
+                for host in active_hosts.iter_mut() {
+                    let Some(found) = host.ip.parse::<Ipv4Addr>().ok().and_then(|ip| findings.get(&ip)) else { continue };
+                    host.port_warnings.extend(found.iter().map(|f| f.to_port_warning()));
+                }
+            }
+            Err(e) => {
+                log_error!("UPnP check failed: {}", e);
+            }
+        }
+    }
+
+    if let Some(plan) = plan {
+        active_hosts = plan.merge(active_hosts);
+    }
+
+    // Containers (Docker MACs on the LAN, local bridges if enabled) are reported separately
+    let mut container_hosts = separate_containers(&mut active_hosts);
+    if CONTAINER_SCAN_ENABLED {
+        container_hosts.extend(scan_container_bridges().await);
+    }
+    if !container_hosts.is_empty() {
+        log_stderr!("{} container hosts kept out of the LAN inventory", container_hosts.len());
+    }
+
     // Sort by IP
     active_hosts.sort_by(|a, b| {
         let ip_a: Ipv4Addr = a.ip.parse().unwrap_or(Ipv4Addr::UNSPECIFIED);
@@ -198,13 +725,26 @@ async fn scan_network(interface: &InterfaceInfo) -> Result<ScanResult> {
         icmp_discovered: icmp_count,
         total_hosts,
         scan_duration_ms: scan_duration.as_millis() as u64,
+        wan,
+        nat,
+        active_directory,
         active_hosts,
+        container_hosts,
     })
 }
 
 #[tokio::main]
 async fn main() {
-    match run().await {
+    #[cfg(feature = "otel")]
+    if let Err(e) = host_discovery::logging::otel::init_span_export() {
+        log_error!("OpenTelemetry export disabled: {}", e);
+    }
+
+    let outcome = run().await;
+    #[cfg(feature = "otel")]
+    host_discovery::logging::otel::shutdown();
+
+    match outcome {
         Ok(result) => {
             println!("{}", serde_json::to_string_pretty(&result).unwrap());
         }
@@ -224,7 +764,59 @@ async fn run() -> Result<ScanResult> {
     log_stderr!("Detecting network interfaces...");
     let interface = find_valid_interface()?;
 
-    scan_network(&interface).await
+    let profile: ScanProfile = match std::env::var(SCAN_PROFILE_ENV) {
+        Ok(name) => name.parse()?,
+        Err(_) => ScanProfile::default(),
+    };
+    let mut config = ScanConfig::from_profile(profile);
+    config.size_send_rate(&interface.name);
+    if let Ok(payload) = std::env::var(ICMP_PAYLOAD_ENV) {
+        config.icmp_payload = payload.parse()?;
+    }
+    if let Ok(dscp) = std::env::var(PROBE_DSCP_ENV) {
+        config.marking.dscp = Some(dscp.parse()?);
+    }
+    if let Ok(ttl) = std::env::var(PROBE_TTL_ENV) {
+        let ttl: u8 = ttl.parse().ok().filter(|t| *t > 0).ok_or_else(|| anyhow!("Invalid probe TTL '{}' (1-255)", ttl))?;
+        config.marking.ttl = Some(ttl);
+    }
+    init_shared_pacer(config.send_rate_pps, config.send_jitter);
+    let limits = host_discovery::scanner::concurrency_limits();
+    log_stderr!(
+        "Sized for this system: {} concurrent pings, {} TCP connects, {} packets/s",
+        limits.pings,
+        limits.tcp_connects,
+        config.send_rate_pps
+    );
+    init_probe_marking(config.marking);
+    if let Ok(path) = std::env::var(AUDIT_LOG_ENV) {
+        init_audit_log(&path)?;
+        log_stderr!("Recording every probe sent to audit log {}", path);
+    }
+    let policy = match std::env::var(POLICY_ENV) {
+        Ok(path) => Some(Policy::load(path)?),
+        Err(_) => None,
+    };
+    if let Some(policy) = &policy {
+        // Forbidden ports can only be confirmed closed if they are probed
+        for port in &policy.forbidden_ports {
+            if !config.tcp_ports.contains(port) {
+                config.tcp_ports.push(*port);
+            }
+        }
+    }
+
+    let scan_span = tracing::info_span!("scan", interface = %interface.name, profile = config.profile.as_str());
+    let result = scan_network(&interface, &config).instrument(scan_span).await?;
+
+    if let Some(policy) = &policy {
+        let report = ComplianceReport::evaluate(policy, &result.active_hosts);
+        log_stderr!("{}", report.summary);
+        for rec in report.recommendations() {
+            log_stderr!("{}: {}", rec.title, rec.affected_devices.join(", "));
+        }
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -243,6 +835,10 @@ mod tests {
             icmp_discovered: 3,
             total_hosts: 5,
             scan_duration_ms: 1000,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
             active_hosts: vec![
                 HostInfo {
                     ip: "192.168.1.1".to_string(),
@@ -250,6 +846,7 @@ mod tests {
                     response_time_ms: Some(10),
                     open_ports: vec![80],
                     discovery_method: "ARP+ICMP+TCP".to_string(),
+                    ..Default::default()
                 },
             ],
         };
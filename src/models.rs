@@ -1,10 +1,16 @@
 //! Data models for the Network Topology Mapper
 
+use ipnetwork::Ipv4Network;
 use pnet::datalink::NetworkInterface;
 use pnet::util::MacAddr;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::Ipv4Addr;
 
+use crate::config::STRONG_SERVICE_CONFIDENCE;
+use crate::network::{likely_hypervisor_host, MacAddress};
+use crate::scanner::NatReport;
+
 /// Result structure for the host discovery scan
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScanResult {
@@ -17,14 +23,205 @@ pub struct ScanResult {
     pub icmp_discovered: usize,
     pub total_hosts: usize,
     pub scan_duration_ms: u64,
+    /// Public egress IP and upstream reachability (optional WAN phase)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wan: Option<WanHealth>,
+    /// NAT layers between the LAN and the internet (optional NAT phase)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nat: Option<NatReport>,
+    /// Active Directory domain, when domain controllers were found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_directory: Option<ActiveDirectoryInfo>,
     pub active_hosts: Vec<HostInfo>,
+    /// Hosts on container bridges or with container MACs, kept out of `active_hosts`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub container_hosts: Vec<HostInfo>,
+}
+
+/// Internet-side view of the network
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WanHealth {
+    /// Public address the network egresses from
+    pub public_ip: Option<String>,
+    /// Endpoint that reported the public address
+    pub public_ip_source: Option<String>,
+    /// Upstream targets that accepted a connection
+    pub reachable_targets: usize,
+    pub total_targets: usize,
+    /// Median TCP connect time to the reachable targets
+    pub latency_ms: Option<f64>,
+}
+
+impl WanHealth {
+    /// No upstream target could be reached
+    pub fn is_offline(&self) -> bool {
+        self.reachable_targets == 0
+    }
+}
+
+/// Host answering Kerberos and LDAP
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DomainController {
+    pub ip: String,
+    pub hostname: Option<String>,
+    pub kerberos: bool,
+    pub ldap: bool,
+    pub ldaps: bool,
+}
+
+/// Active Directory domain found on the network
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ActiveDirectoryInfo {
+    /// DNS name of the domain; `None` when no DC locator record answered
+    pub domain: Option<String>,
+    pub domain_controllers: Vec<DomainController>,
+    /// DC names published in `_ldap._tcp.dc._msdcs.<domain>`
+    #[serde(default)]
+    pub srv_targets: Vec<String>,
+}
+
+impl ActiveDirectoryInfo {
+    /// Domain confirmed by its DC locator SRV records
+    pub fn is_confirmed(&self) -> bool {
+        self.domain.is_some() && !self.srv_targets.is_empty()
+    }
+}
+
+/// Aggregated report over several subnets/interfaces scanned in one run
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// One result per scanned subnet; hosts stay attributed to their subnet
+    pub scans: Vec<ScanResult>,
+    /// Devices (by MAC) seen on more than one subnet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<CrossSubnetDuplicate>,
+}
+
+/// One place a host was seen
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HostSighting {
+    pub subnet: String,
+    pub interface_name: String,
+    pub ip: String,
+}
+
+/// Same MAC address reached through more than one subnet/interface
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CrossSubnetDuplicate {
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+    pub sightings: Vec<HostSighting>,
+}
+
+impl ScanReport {
+    /// Combine per-subnet results and find cross-subnet duplicates
+    pub fn from_scans(scans: Vec<ScanResult>) -> Self {
+        let mut by_mac: Vec<CrossSubnetDuplicate> = Vec::new();
+        for (scan, host) in scans.iter().flat_map(|s| s.active_hosts.iter().map(move |h| (s, h))) {
+            // Unknown MACs (ICMP-only, routed hosts) cannot be correlated
+            let Some(mac) = host.mac_address() else {
+                continue;
+            };
+            let mac = format!("{:X}", mac);
+            let sighting = HostSighting {
+                subnet: scan.subnet.clone(),
+                interface_name: scan.interface_name.clone(),
+                ip: host.ip.clone(),
+            };
+            match by_mac.iter_mut().find(|d| d.mac == mac) {
+                Some(entry) => entry.sightings.push(sighting),
+                None => by_mac.push(CrossSubnetDuplicate { mac, sightings: vec![sighting] }),
+            }
+        }
+
+        let duplicates = by_mac
+            .into_iter()
+            .filter(|d| {
+                d.sightings
+                    .iter()
+                    .any(|s| s.subnet != d.sightings[0].subnet || s.interface_name != d.sightings[0].interface_name)
+            })
+            .collect();
+
+        Self { scans, duplicates }
+    }
+
+    /// All hosts with the subnet they were found on
+    pub fn hosts(&self) -> impl Iterator<Item = (&str, &HostInfo)> {
+        self.scans
+            .iter()
+            .flat_map(|s| s.active_hosts.iter().map(move |h| (s.subnet.as_str(), h)))
+    }
+
+    /// One host per device across all interfaces, with the interfaces that saw it
+    ///
+    /// Wi-Fi and Ethernet attached to the same LAN both see every device;
+    /// hosts are merged by MAC so insights count each device once. Open
+    /// ports and IPv6 addresses are combined, other fields come from the
+    /// first sighting unless it lacked them. Hosts without a MAC are only
+    /// merged with the same address on the same subnet.
+    pub fn unique_hosts(&self) -> Vec<HostInfo> {
+        let mut merged: Vec<(String, HostInfo)> = Vec::new();
+        for scan in &self.scans {
+            for host in &scan.active_hosts {
+                let key = match host.identity() {
+                    id if id == host.ip => format!("{}/{}", scan.subnet, host.ip),
+                    id => id,
+                };
+                let index = match merged.iter().position(|(k, _)| *k == key) {
+                    Some(index) => index,
+                    None => {
+                        let mut first = host.clone();
+                        first.observed_interfaces.clear();
+                        merged.push((key, first));
+                        merged.len() - 1
+                    }
+                };
+                let existing = &mut merged[index].1;
+                if !existing.observed_interfaces.contains(&scan.interface_name) {
+                    existing.observed_interfaces.push(scan.interface_name.clone());
+                }
+                for port in &host.open_ports {
+                    if !existing.open_ports.contains(port) {
+                        existing.open_ports.push(*port);
+                    }
+                }
+                existing.open_ports.sort_unstable();
+                for address in &host.ipv6_addresses {
+                    if !existing.ipv6_addresses.contains(address) {
+                        existing.ipv6_addresses.push(address.clone());
+                    }
+                }
+                existing.hostname = existing.hostname.take().or_else(|| host.hostname.clone());
+                existing.vendor = existing.vendor.take().or_else(|| host.vendor.clone());
+                existing.response_time_ms = existing.response_time_ms.or(host.response_time_ms);
+            }
+        }
+        merged.into_iter().map(|(_, host)| host).collect()
+    }
+
+    /// Distinct devices across all subnets (duplicates counted once)
+    pub fn unique_host_count(&self) -> usize {
+        let total: usize = self.scans.iter().map(|s| s.active_hosts.len()).sum();
+        let extra: usize = self.duplicates.iter().map(|d| d.sightings.len() - 1).sum();
+        total - extra
+    }
 }
 
 /// Information about a discovered host
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct HostInfo {
     pub ip: String,
     pub mac: String,
+    /// IPv6 addresses seen for the same MAC (dual-stack correlation)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ipv6_addresses: Vec<String>,
+    /// Local interfaces the host was seen through, when several were scanned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub observed_interfaces: Vec<String>,
+    /// Services identified on the host, with the evidence for each
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service_guesses: Vec<ServiceGuess>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vendor: Option<String>,
     /// True if MAC is locally administered (randomized/virtual)
@@ -32,6 +229,15 @@ pub struct HostInfo {
     pub is_randomized: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_time_ms: Option<u64>,
+    /// Latency statistics across multiple ICMP samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_stats: Option<LatencyStats>,
+    /// Share of ICMP probes left unanswered (0-100), including retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packet_loss_percent: Option<f64>,
+    /// How the host answered the ARP sweep
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arp_reply: Option<ArpReplyStats>,
     /// TTL value from ICMP response (used for OS fingerprinting)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl: Option<u8>,
@@ -45,16 +251,37 @@ pub struct HostInfo {
     pub risk_score: u8,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub open_ports: Vec<u16>,
+    /// Every probed port with its state, identified service and banner
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<Port>,
+    /// TCP handshake time per open port in milliseconds (service-level latency)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub port_connect_ms: BTreeMap<u16, f64>,
     pub discovery_method: String,
     // DNS/SNMP hostname
     pub hostname: Option<String>,
+    /// Every name reported for the host, by source (kept when the hostname policy keeps all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hostname_records: Vec<HostnameRecord>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system_description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uptime_seconds: Option<u64>,
+    /// Uptime estimated from TCP timestamps; low confidence, compared across scans only for reboots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp_uptime_seconds: Option<u64>,
+    /// Firmware version read from the device's web interface (IoT probes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firmware: Option<FirmwareInfo>,
     // LLDP/CDP neighbor discovery (for topology mapping)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub neighbors: Vec<NeighborInfo>,
+    /// Switch port the host is plugged into (bridge-MIB forwarding database)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub switch_port: Option<SwitchPort>,
+    /// Likely wired or wireless attachment (OUI, jitter, AP association)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_medium: Option<LinkMedium>,
     
     // Vulnerability information
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -63,6 +290,358 @@ pub struct HostInfo {
     pub port_warnings: Vec<PortWarning>,
     #[serde(default)]
     pub security_grade: String,  // "A", "B", "C", "D", "F"
+    /// User-assigned display name, merged from stored annotations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    /// User notes, merged from stored annotations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl HostInfo {
+    /// Stable identity across scans: the MAC when known, otherwise the IP
+    ///
+    /// Hosts behind a router (no MAC) fall back to their address, which is
+    /// stable enough for statically addressed servers.
+    pub fn identity(&self) -> String {
+        match self.mac_address() {
+            Some(mac) => format!("{:X}", mac),
+            None => self.ip.clone(),
+        }
+    }
+
+    /// Parsed MAC address, `None` when unknown or all zeros
+    pub fn mac_address(&self) -> Option<MacAddress> {
+        self.mac.parse::<MacAddress>().ok().filter(|mac| !mac.is_zero())
+    }
+
+    /// Name shown in reports: the user's friendly name, else the hostname
+    pub fn display_name(&self) -> Option<&str> {
+        self.friendly_name.as_deref().or(self.hostname.as_deref())
+    }
+}
+
+/// How a host answered the ARP sweep
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArpReplyStats {
+    /// Sweep round the reply arrived in (0 = before the first request went out)
+    pub round: u8,
+    /// ARP requests sent to the host before it answered
+    pub requests: u8,
+    /// Time from the latest request to the reply
+    pub latency_ms: Option<f64>,
+}
+
+impl ArpReplyStats {
+    /// 1.0 for a host that answered its first request, lower the more
+    /// requests it needed (sleeping, overloaded or lossy)
+    pub fn responsiveness(&self) -> f32 {
+        1.0 / self.requests.max(1) as f32
+    }
+}
+
+/// Round-trip statistics from multiple ping samples
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LatencyStats {
+    /// Number of echo requests sent
+    pub sent: u32,
+    /// Number of echo replies received
+    pub received: u32,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    /// Mean absolute difference between consecutive RTTs (RFC 3550 style)
+    pub jitter_ms: f64,
+    /// Percentage of requests without a reply (0-100)
+    pub loss_percent: f64,
+}
+
+impl LatencyStats {
+    /// Build statistics from per-request RTTs in send order (`None` = lost)
+    ///
+    /// Returns `None` when no request was sent.
+    pub fn from_samples(samples: &[Option<f64>]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let sent = samples.len() as u32;
+        let rtts: Vec<f64> = samples.iter().flatten().copied().collect();
+        let received = rtts.len() as u32;
+        let loss_percent = (sent - received) as f64 / sent as f64 * 100.0;
+
+        if rtts.is_empty() {
+            return Some(Self {
+                sent,
+                received,
+                min_ms: 0.0,
+                avg_ms: 0.0,
+                p95_ms: 0.0,
+                max_ms: 0.0,
+                jitter_ms: 0.0,
+                loss_percent,
+            });
+        }
+
+        let jitter_ms = if rtts.len() > 1 {
+            rtts.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / (rtts.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        let mut sorted = rtts.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        // Nearest-rank percentile
+        let rank = ((0.95 * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+
+        Some(Self {
+            sent,
+            received,
+            min_ms: sorted[0],
+            avg_ms: rtts.iter().sum::<f64>() / rtts.len() as f64,
+            p95_ms: sorted[rank - 1],
+            max_ms: sorted[sorted.len() - 1],
+            jitter_ms,
+            loss_percent,
+        })
+    }
+}
+
+/// Firmware identification read from a device's web interface
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FirmwareInfo {
+    /// Model or product line reported alongside the version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    /// Version string as reported ("V5.4.5 build 170124")
+    pub version: String,
+    /// Endpoint the version was read from
+    pub source: String,
+}
+
+impl FirmwareInfo {
+    /// Numeric components of the version ("V5.4.5 build 170124" -> [5, 4, 5])
+    pub fn version_numbers(&self) -> Vec<u64> {
+        version_numbers(&self.version)
+    }
+
+    /// True if this firmware predates `fixed_in`; unparseable versions never match
+    pub fn is_older_than(&self, fixed_in: &str) -> bool {
+        let (current, fixed) = (self.version_numbers(), version_numbers(fixed_in));
+        if current.is_empty() || fixed.is_empty() {
+            return false;
+        }
+        let len = current.len().max(fixed.len());
+        let pad = |v: Vec<u64>| v.into_iter().chain(std::iter::repeat(0)).take(len).collect::<Vec<_>>();
+        pad(current) < pad(fixed)
+    }
+}
+
+/// Dotted numeric prefix of the first token containing a digit
+fn version_numbers(text: &str) -> Vec<u64> {
+    let Some(token) = text.split_whitespace().find(|t| t.chars().any(|c| c.is_ascii_digit())) else {
+        return Vec::new();
+    };
+    let token = token.trim_start_matches(|c: char| !c.is_ascii_digit());
+    token
+        .split('.')
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+/// Protocol a hostname was learned from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HostnameSource {
+    /// Reverse DNS (PTR record)
+    Ptr,
+    /// mDNS/SSDP announcements and smart-home device names
+    Mdns,
+    /// SNMP sysName
+    Snmp,
+    /// DHCP lease on the router
+    Dhcp,
+}
+
+impl HostnameSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HostnameSource::Ptr => "ptr",
+            HostnameSource::Mdns => "mdns",
+            HostnameSource::Snmp => "snmp",
+            HostnameSource::Dhcp => "dhcp",
+        }
+    }
+}
+
+/// A hostname and where it came from
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HostnameRecord {
+    pub source: HostnameSource,
+    pub name: String,
+}
+
+/// Transport protocol of a port
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Tcp,
+    Udp,
+}
+
+impl Transport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transport::Tcp => "tcp",
+            Transport::Udp => "udp",
+        }
+    }
+}
+
+/// State of a probed port
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PortState {
+    /// Connection accepted
+    Open,
+    /// RST received: the host is reachable but nothing listens
+    Closed,
+    /// No answer within the timeout: a firewall is dropping the SYN
+    Filtered,
+    /// Raw ACK probe drew a RST: reachable, open or closed unknown
+    Unfiltered,
+    /// Raw FIN/NULL/Xmas probe went unanswered: open, or dropped by a firewall
+    OpenFiltered,
+}
+
+impl PortState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PortState::Open => "open",
+            PortState::Closed => "closed",
+            PortState::Filtered => "filtered",
+            PortState::Unfiltered => "unfiltered",
+            PortState::OpenFiltered => "open|filtered",
+        }
+    }
+}
+
+/// What a service identification is based on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceEvidence {
+    /// Greeting or reply the service sent
+    Banner,
+    /// Only the port number it listens on
+    PortHeuristic,
+    /// Common name of the TLS certificate it presented
+    TlsCertificate,
+    /// SNMP sysDescr of the host
+    SnmpSysDescr,
+}
+
+impl ServiceEvidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceEvidence::Banner => "banner",
+            ServiceEvidence::PortHeuristic => "port_heuristic",
+            ServiceEvidence::TlsCertificate => "tls_certificate",
+            ServiceEvidence::SnmpSysDescr => "snmp_sys_descr",
+        }
+    }
+
+    /// Default confidence of a guess based on this evidence (0.0-1.0)
+    pub fn confidence(&self) -> f32 {
+        match self {
+            ServiceEvidence::Banner => 0.9,
+            ServiceEvidence::SnmpSysDescr => 0.8,
+            ServiceEvidence::TlsCertificate => 0.7,
+            ServiceEvidence::PortHeuristic => 0.3,
+        }
+    }
+}
+
+/// An identified service and how sure the identification is
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ServiceGuess {
+    pub service: String,
+    /// Port the service was seen on; `None` for host-wide evidence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub evidence: ServiceEvidence,
+    /// The matched banner line, certificate name or sysDescr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// 0.0-1.0
+    pub confidence: f32,
+}
+
+impl ServiceGuess {
+    /// Guess with the evidence's default confidence
+    pub fn new(service: impl Into<String>, evidence: ServiceEvidence) -> Self {
+        Self {
+            service: service.into(),
+            port: None,
+            evidence,
+            detail: None,
+            confidence: evidence.confidence(),
+        }
+    }
+
+    pub fn on_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn with_detail(mut self, detail: String) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+
+    /// Firm identification rather than a convention-based hint
+    pub fn is_strong(&self) -> bool {
+        self.confidence >= STRONG_SERVICE_CONFIDENCE
+    }
+}
+
+/// A probed port and what was learned about it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Port {
+    pub number: u16,
+    pub transport: Transport,
+    pub state: PortState,
+    /// Service identified on the port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceGuess>,
+    /// First bytes the service sent, as text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+    /// Time to complete the TCP handshake, for open TCP ports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_ms: Option<f64>,
+}
+
+impl Port {
+    /// TCP port with nothing known beyond its state
+    pub fn tcp(number: u16, state: PortState) -> Self {
+        Self {
+            number,
+            transport: Transport::Tcp,
+            state,
+            service: None,
+            banner: None,
+            connect_ms: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state == PortState::Open
+    }
 }
 
 /// Information about a network neighbor (from LLDP/CDP)
@@ -79,6 +658,407 @@ pub struct NeighborInfo {
     pub remote_ip: Option<String>,
 }
 
+/// Physical location of a host on a managed switch
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SwitchPort {
+    /// Switch management IP
+    pub switch: String,
+    /// Switch sysName, when it answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub switch_name: Option<String>,
+    /// Interface name (ifName), or the bridge port number if unnamed
+    pub port: String,
+}
+
+/// Role of a node in the topology graph
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Host,
+    Switch,
+    Router,
+}
+
+impl NodeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Host => "host",
+            NodeKind::Switch => "switch",
+            NodeKind::Router => "router",
+        }
+    }
+
+    /// Node kind for an inferred device type
+    fn for_device_type(device_type: &str) -> Self {
+        match device_type {
+            "SWITCH" => NodeKind::Switch,
+            "ROUTER" | "FIREWALL" => NodeKind::Router,
+            _ => NodeKind::Host,
+        }
+    }
+}
+
+/// Kind of link between two nodes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Same broadcast domain, directly cabled or through a switch port
+    L2Adjacency,
+    /// Consecutive routed hops
+    L3Hop,
+    /// Client associated with an access point
+    WirelessAssociation,
+}
+
+impl EdgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::L2Adjacency => "l2_adjacency",
+            EdgeKind::L3Hop => "l3_hop",
+            EdgeKind::WirelessAssociation => "wireless_association",
+        }
+    }
+}
+
+/// Evidence an edge was derived from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeSource {
+    /// LLDP/CDP neighbor table read over SNMP
+    Lldp,
+    /// Switch forwarding database
+    Fdb,
+    Traceroute,
+    /// Guessed from device types (no direct evidence)
+    Heuristic,
+}
+
+/// Device in the topology graph
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TopologyNode {
+    /// Host identity (MAC, else IP) or the management IP/name of infrastructure
+    pub id: String,
+    pub kind: NodeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    pub label: String,
+    /// False for switches/routers only known from neighbor tables or traces
+    pub scanned: bool,
+    /// Node id of the hypervisor host a virtual machine most likely runs on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hypervisor: Option<String>,
+}
+
+/// Link between two nodes (undirected)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TopologyEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+    pub evidence: EdgeSource,
+    /// Port on the source side, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_port: Option<String>,
+    /// Port on the target side, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_port: Option<String>,
+}
+
+/// Layer 3 segment: one scanned subnet
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TopologySubnet {
+    /// Subnet in CIDR notation
+    pub cidr: String,
+    /// Node id of the router serving the subnet, when one was identified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+}
+
+/// Cross-layer link: a node (L2) holding an address in a subnet (L3)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LayerLink {
+    pub node: String,
+    pub subnet: String,
+    pub ip: String,
+}
+
+/// Network graph built from scanned hosts and discovery evidence
+///
+/// The Layer 2 view (`l2_edges`: cabling, switch ports, wireless
+/// association) and the Layer 3 view (`subnets` and routed `l3_edges`)
+/// are kept apart and joined by `cross_links`, so a device can sit on
+/// one switch while holding addresses in several subnets.
+///
+/// Edges with direct evidence (LLDP, FDB, traceroute) are added first;
+/// hosts left unconnected are attached by heuristics so the graph stays
+/// connected around the gateway.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Topology {
+    pub nodes: Vec<TopologyNode>,
+    pub l2_edges: Vec<TopologyEdge>,
+    pub l3_edges: Vec<TopologyEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subnets: Vec<TopologySubnet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cross_links: Vec<LayerLink>,
+}
+
+impl Topology {
+    /// Build the graph from scanned hosts (LLDP neighbors, switch ports, heuristics)
+    pub fn from_hosts(hosts: &[HostInfo]) -> Self {
+        let mut topology = Self::default();
+        for host in hosts {
+            // Multi-homed hosts appear once per subnet but are one device
+            if topology.node(&host.identity()).is_some() {
+                continue;
+            }
+            topology.nodes.push(TopologyNode {
+                id: host.identity(),
+                kind: NodeKind::for_device_type(&host.device_type),
+                ip: Some(host.ip.clone()),
+                label: host.display_name().unwrap_or(&host.ip).to_string(),
+                scanned: true,
+                hypervisor: likely_hypervisor_host(host, hosts).map(|h| h.identity()),
+            });
+        }
+
+        for host in hosts {
+            let id = host.identity();
+            for neighbor in &host.neighbors {
+                let remote = topology.resolve(hosts, neighbor.remote_ip.as_deref(), Some(&neighbor.remote_device));
+                topology.add_edge(TopologyEdge {
+                    source: id.clone(),
+                    target: remote,
+                    kind: EdgeKind::L2Adjacency,
+                    evidence: EdgeSource::Lldp,
+                    source_port: Some(neighbor.local_port.clone()),
+                    target_port: Some(neighbor.remote_port.clone()),
+                });
+            }
+            if let Some(port) = &host.switch_port {
+                let switch = topology.resolve(hosts, Some(&port.switch), port.switch_name.as_deref());
+                topology.add_edge(TopologyEdge {
+                    source: switch,
+                    target: id.clone(),
+                    kind: EdgeKind::L2Adjacency,
+                    evidence: EdgeSource::Fdb,
+                    source_port: Some(port.port.clone()),
+                    target_port: None,
+                });
+            }
+        }
+
+        topology.attach_unconnected(hosts);
+        topology
+    }
+
+    /// Build both layers from a multi-subnet report
+    pub fn from_report(report: &ScanReport) -> Self {
+        let hosts: Vec<HostInfo> = report.hosts().map(|(_, h)| h.clone()).collect();
+        let mut topology = Self::from_hosts(&hosts);
+        for scan in &report.scans {
+            topology.add_subnet(&scan.subnet, &scan.active_hosts);
+        }
+        topology
+    }
+
+    /// Add a subnet to the L3 layer and link its hosts to it
+    pub fn add_subnet(&mut self, cidr: &str, hosts: &[HostInfo]) {
+        let gateway = hosts
+            .iter()
+            .find(|h| NodeKind::for_device_type(&h.device_type) == NodeKind::Router)
+            .map(|h| h.identity());
+        if !self.subnets.iter().any(|s| s.cidr == cidr) {
+            self.subnets.push(TopologySubnet {
+                cidr: cidr.to_string(),
+                gateway,
+            });
+        }
+        for host in hosts {
+            self.cross_links.push(LayerLink {
+                node: host.identity(),
+                subnet: cidr.to_string(),
+                ip: host.ip.clone(),
+            });
+        }
+    }
+
+    /// Subnets a node holds an address in
+    pub fn subnets_of(&self, id: &str) -> Vec<&str> {
+        let mut subnets: Vec<&str> = self
+            .cross_links
+            .iter()
+            .filter(|l| l.node == id)
+            .map(|l| l.subnet.as_str())
+            .collect();
+        subnets.dedup();
+        subnets
+    }
+
+    /// Switches carrying hosts from more than one subnet, with those subnets
+    ///
+    /// Expected for trunked VLANs; on an unmanaged switch it means subnets
+    /// share a broadcast domain and are not isolated from each other.
+    pub fn switches_spanning_subnets(&self) -> Vec<(&TopologyNode, Vec<&str>)> {
+        self.nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Switch)
+            .filter_map(|switch| {
+                let mut subnets: Vec<&str> = Self::adjacent(&self.l2_edges, &switch.id)
+                    .flat_map(|id| self.subnets_of(id))
+                    .collect();
+                subnets.sort_unstable();
+                subnets.dedup();
+                (subnets.len() > 1).then_some((switch, subnets))
+            })
+            .collect()
+    }
+
+    /// Add a traceroute path as a chain of L3 hops; silent hops break the chain
+    pub fn add_trace(&mut self, hosts: &[HostInfo], hops: &[Option<Ipv4Addr>]) {
+        let mut previous: Option<String> = None;
+        for hop in hops {
+            let Some(hop) = hop else {
+                previous = None;
+                continue;
+            };
+            let ip = hop.to_string();
+            let id = self.resolve(hosts, Some(&ip), None);
+            if let Some(node) = self.nodes.iter_mut().find(|n| n.id == id && !n.scanned) {
+                node.kind = NodeKind::Router;
+            }
+            if let Some(previous) = previous {
+                self.add_edge(TopologyEdge {
+                    source: previous,
+                    target: id.clone(),
+                    kind: EdgeKind::L3Hop,
+                    evidence: EdgeSource::Traceroute,
+                    source_port: None,
+                    target_port: None,
+                });
+            }
+            previous = Some(id);
+        }
+    }
+
+    pub fn node(&self, id: &str) -> Option<&TopologyNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Nodes linked to `id` by an edge in either layer
+    pub fn neighbors<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a TopologyNode> + 'a {
+        Self::adjacent(&self.l2_edges, id)
+            .chain(Self::adjacent(&self.l3_edges, id))
+            .filter_map(|other| self.node(other))
+    }
+
+    /// Ids at the other end of the edges touching `id`
+    fn adjacent<'a>(edges: &'a [TopologyEdge], id: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        edges.iter().filter_map(move |e| match (e.source == id, e.target == id) {
+            (true, _) => Some(e.target.as_str()),
+            (_, true) => Some(e.source.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Node id for a device referenced by IP or name, adding an unscanned switch if unknown
+    fn resolve(&mut self, hosts: &[HostInfo], ip: Option<&str>, name: Option<&str>) -> String {
+        let known = hosts.iter().find(|h| {
+            ip.is_some_and(|ip| h.ip == ip)
+                || name.is_some_and(|name| h.hostname.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+        });
+        if let Some(host) = known {
+            return host.identity();
+        }
+
+        let id = ip.or(name).unwrap_or("unknown").to_string();
+        if self.node(&id).is_none() {
+            self.nodes.push(TopologyNode {
+                id: id.clone(),
+                kind: NodeKind::Switch,
+                ip: ip.map(str::to_string),
+                label: name.or(ip).unwrap_or("unknown").to_string(),
+                scanned: false,
+                hypervisor: None,
+            });
+        }
+        id
+    }
+
+    /// Add an edge to its layer unless the same link is already present (in either direction)
+    fn add_edge(&mut self, edge: TopologyEdge) {
+        let edges = match edge.kind {
+            EdgeKind::L3Hop => &mut self.l3_edges,
+            EdgeKind::L2Adjacency | EdgeKind::WirelessAssociation => &mut self.l2_edges,
+        };
+        let exists = edges.iter().any(|e| {
+            e.kind == edge.kind
+                && ((e.source == edge.source && e.target == edge.target)
+                    || (e.source == edge.target && e.target == edge.source))
+        });
+        if !exists && edge.source != edge.target {
+            edges.push(edge);
+        }
+    }
+
+    /// Attach hosts without evidence: VM guests to their hypervisor, wireless
+    /// clients to the only access point, the rest to the gateway
+    fn attach_unconnected(&mut self, hosts: &[HostInfo]) {
+        let gateway = hosts
+            .iter()
+            .find(|h| NodeKind::for_device_type(&h.device_type) == NodeKind::Router)
+            .map(|h| h.identity());
+        let access_points: Vec<String> = hosts
+            .iter()
+            .filter(|h| h.device_type == "ACCESS_POINT")
+            .map(|h| h.identity())
+            .collect();
+
+        for host in hosts {
+            let id = host.identity();
+            if Self::adjacent(&self.l2_edges, &id).next().is_some() {
+                continue;
+            }
+            let wireless = match host.link_medium {
+                Some(medium) => medium == LinkMedium::Wireless,
+                None => matches!(host.device_type.as_str(), "MOBILE" | "TABLET"),
+            };
+            let hypervisor = self.node(&id).and_then(|n| n.hypervisor.clone());
+            let (target, kind) = match (hypervisor, &gateway, access_points.as_slice()) {
+                (Some(hypervisor), _, _) => (hypervisor, EdgeKind::L2Adjacency),
+                (None, _, [ap]) if wireless => (ap.clone(), EdgeKind::WirelessAssociation),
+                (None, Some(gateway), _) => (gateway.clone(), EdgeKind::L2Adjacency),
+                (None, None, _) => continue,
+            };
+            self.add_edge(TopologyEdge {
+                source: target,
+                target: id,
+                kind,
+                evidence: EdgeSource::Heuristic,
+                source_port: None,
+                target_port: None,
+            });
+        }
+    }
+}
+
+/// How a host is attached to the network
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMedium {
+    Wired,
+    Wireless,
+}
+
+impl LinkMedium {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkMedium::Wired => "wired",
+            LinkMedium::Wireless => "wireless",
+        }
+    }
+}
+
 /// Network interface information with MAC address
 #[derive(Debug, Clone)]
 pub struct InterfaceInfo {
@@ -86,9 +1066,41 @@ pub struct InterfaceInfo {
     pub ip: Ipv4Addr,
     pub mac: MacAddr,
     pub prefix_len: u8,
+    /// Further IPv4 addresses on the same interface (aliases, secondary addresses)
+    pub aliases: Vec<Ipv4Network>,
     pub pnet_interface: NetworkInterface,
 }
 
+impl InterfaceInfo {
+    /// Every subnet the interface is attached to, primary first
+    pub fn subnets(&self) -> Vec<Ipv4Network> {
+        let primary = Ipv4Network::new(self.ip, self.prefix_len).ok();
+        let mut subnets: Vec<Ipv4Network> = Vec::new();
+        for address in primary.iter().chain(&self.aliases) {
+            let Ok(subnet) = Ipv4Network::new(address.network(), address.prefix()) else { continue };
+            if !subnets.contains(&subnet) {
+                subnets.push(subnet);
+            }
+        }
+        subnets
+    }
+
+    /// Local address to use as the source towards an on-link `target`
+    ///
+    /// The primary address when its subnet holds the target, otherwise the
+    /// alias whose subnet does, so replies come back to an address the
+    /// target considers local.
+    pub fn source_ip_for(&self, target: Ipv4Addr) -> Ipv4Addr {
+        if Ipv4Network::new(self.ip, self.prefix_len).is_ok_and(|primary| primary.contains(target)) {
+            return self.ip;
+        }
+        self.aliases
+            .iter()
+            .find(|alias| alias.contains(target))
+            .map_or(self.ip, |alias| alias.ip())
+    }
+}
+
 /// CVE vulnerability information
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VulnerabilityInfo {
@@ -96,6 +1108,9 @@ pub struct VulnerabilityInfo {
     pub description: String,
     pub severity: String,  // CRITICAL, HIGH, MEDIUM, LOW
     pub cvss_score: Option<f32>,
+    /// CVSS v3.1 vector string (e.g. "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss_vector: Option<String>,
 }
 
 /// Port-based security warning
@@ -107,3 +1122,192 @@ pub struct PortWarning {
     pub severity: String,  // CRITICAL, HIGH, MEDIUM, LOW
     pub recommendation: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats_from_samples() {
+        let stats = LatencyStats::from_samples(&[Some(10.0), None, Some(20.0), Some(12.0)]).unwrap();
+        assert_eq!(stats.sent, 4);
+        assert_eq!(stats.received, 3);
+        assert_eq!(stats.loss_percent, 25.0);
+        assert_eq!(stats.min_ms, 10.0);
+        assert_eq!(stats.max_ms, 20.0);
+        assert_eq!(stats.p95_ms, 20.0);
+        assert_eq!(stats.avg_ms, 14.0);
+        assert_eq!(stats.jitter_ms, 9.0);
+    }
+
+    #[test]
+    fn test_latency_stats_all_lost() {
+        let stats = LatencyStats::from_samples(&[None, None]).unwrap();
+        assert_eq!(stats.received, 0);
+        assert_eq!(stats.loss_percent, 100.0);
+        assert!(LatencyStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_arp_reply_responsiveness() {
+        let reply = |requests: u8| ArpReplyStats { round: requests, requests, latency_ms: Some(1.5) };
+        assert_eq!(reply(1).responsiveness(), 1.0);
+        assert_eq!(reply(4).responsiveness(), 0.25);
+        // Reply heard before any request was recorded (e.g. gratuitous ARP)
+        assert_eq!(reply(0).responsiveness(), 1.0);
+    }
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn scan(interface_name: &str, subnet: &str, active_hosts: Vec<HostInfo>) -> ScanResult {
+        ScanResult {
+            interface_name: interface_name.to_string(),
+            local_ip: String::new(),
+            local_mac: String::new(),
+            subnet: subnet.to_string(),
+            scan_method: "ARP".to_string(),
+            arp_discovered: active_hosts.len(),
+            icmp_discovered: 0,
+            total_hosts: active_hosts.len(),
+            scan_duration_ms: 0,
+            wan: None,
+            nat: None,
+            active_directory: None,
+            container_hosts: Vec::new(),
+            active_hosts,
+        }
+    }
+
+    #[test]
+    fn test_firmware_version_comparison() {
+        let firmware = |version: &str| FirmwareInfo {
+            product: None,
+            version: version.to_string(),
+            source: "/".to_string(),
+        };
+        assert_eq!(firmware("V5.4.5 build 170124").version_numbers(), vec![5, 4, 5]);
+        assert_eq!(firmware("2.800.0000000.16.R").version_numbers(), vec![2, 800, 0, 16]);
+        assert!(firmware("V5.4.5 build 170124").is_older_than("5.5.800"));
+        assert!(!firmware("5.5").is_older_than("5.5.0"));
+        assert!(!firmware("unknown").is_older_than("1.0"));
+    }
+
+    #[test]
+    fn test_topology_from_hosts() {
+        let mut router = host("192.168.1.1", "AA:00:00:00:00:01");
+        router.device_type = "ROUTER".to_string();
+        let mut ap = host("192.168.1.2", "AA:00:00:00:00:02");
+        ap.device_type = "ACCESS_POINT".to_string();
+        let mut phone = host("192.168.1.30", "AA:00:00:00:00:30");
+        phone.device_type = "MOBILE".to_string();
+        let mut pc = host("192.168.1.40", "AA:00:00:00:00:40");
+        pc.switch_port = Some(SwitchPort {
+            switch: "192.168.1.3".to_string(),
+            switch_name: Some("sw-office".to_string()),
+            port: "Gi0/4".to_string(),
+        });
+        router.neighbors = vec![NeighborInfo {
+            local_port: "lan1".to_string(),
+            remote_device: "sw-office".to_string(),
+            remote_port: "Gi0/24".to_string(),
+            remote_ip: Some("192.168.1.3".to_string()),
+        }];
+        let hosts = vec![router, ap, phone, pc];
+
+        let mut topology = Topology::from_hosts(&hosts);
+        let switch = topology.node("192.168.1.3").unwrap();
+        assert_eq!(switch.kind, NodeKind::Switch);
+        assert!(!switch.scanned);
+        // LLDP router-switch, FDB switch-pc, AP to gateway, phone to AP
+        assert_eq!(topology.l2_edges.len(), 4);
+        let phone_links: Vec<&TopologyEdge> = topology.l2_edges.iter().filter(|e| e.target == "AA:00:00:00:00:30").collect();
+        assert_eq!(phone_links[0].kind, EdgeKind::WirelessAssociation);
+        assert_eq!(topology.neighbors("192.168.1.3").count(), 2);
+
+        topology.add_trace(&hosts, &[Some(Ipv4Addr::new(192, 168, 1, 1)), Some(Ipv4Addr::new(100, 64, 0, 1)), None]);
+        assert_eq!(topology.node("100.64.0.1").unwrap().kind, NodeKind::Router);
+        assert_eq!(topology.l3_edges.len(), 1);
+        assert_eq!(topology.l3_edges[0].source, "AA:00:00:00:00:01");
+    }
+
+    #[test]
+    fn test_topology_layers() {
+        let on_switch = |ip: &str, mac: &str| {
+            let mut h = host(ip, mac);
+            h.switch_port = Some(SwitchPort {
+                switch: "192.168.1.3".to_string(),
+                switch_name: None,
+                port: "1".to_string(),
+            });
+            h
+        };
+        let report = ScanReport::from_scans(vec![
+            scan("eth0", "192.168.1.0/24", vec![on_switch("192.168.1.10", "AA:00:00:00:00:10")]),
+            scan(
+                "eth1",
+                "10.0.0.0/24",
+                vec![on_switch("10.0.0.20", "AA:00:00:00:00:20"), on_switch("10.0.0.10", "AA:00:00:00:00:10")],
+            ),
+        ]);
+
+        let topology = Topology::from_report(&report);
+        assert_eq!(topology.nodes.iter().filter(|n| n.scanned).count(), 2);
+        assert_eq!(topology.subnets.len(), 2);
+        assert_eq!(topology.subnets_of("AA:00:00:00:00:10"), vec!["192.168.1.0/24", "10.0.0.0/24"]);
+
+        let spanning = topology.switches_spanning_subnets();
+        assert_eq!(spanning.len(), 1);
+        assert_eq!(spanning[0].0.id, "192.168.1.3");
+        assert_eq!(spanning[0].1, vec!["10.0.0.0/24", "192.168.1.0/24"]);
+    }
+
+    #[test]
+    fn test_scan_report_cross_subnet_duplicates() {
+        let report = ScanReport::from_scans(vec![
+            scan(
+                "eth0",
+                "192.168.1.0/24",
+                vec![host("192.168.1.1", "AA:00:00:00:00:01"), host("192.168.1.10", "AA:00:00:00:00:10")],
+            ),
+            scan(
+                "eth1",
+                "10.0.0.0/24",
+                vec![host("10.0.0.1", "aa:00:00:00:00:01"), host("10.0.0.5", "00:00:00:00:00:00")],
+            ),
+        ]);
+
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].mac, "AA:00:00:00:00:01");
+        assert_eq!(report.duplicates[0].sightings[1].ip, "10.0.0.1");
+        assert_eq!(report.hosts().filter(|(subnet, _)| *subnet == "10.0.0.0/24").count(), 2);
+        assert_eq!(report.unique_host_count(), 3);
+    }
+
+    #[test]
+    fn test_scan_report_unique_hosts_across_interfaces() {
+        let mut wired_nas = host("192.168.1.20", "AA:00:00:00:00:20");
+        wired_nas.open_ports = vec![445];
+        let mut wifi_nas = host("192.168.1.20", "aa:00:00:00:00:20");
+        wifi_nas.open_ports = vec![80, 445];
+        wifi_nas.hostname = Some("nas".to_string());
+        let report = ScanReport::from_scans(vec![
+            scan("eth0", "192.168.1.0/24", vec![wired_nas, host("192.168.1.30", "00:00:00:00:00:00")]),
+            scan("wlan0", "192.168.1.0/24", vec![wifi_nas, host("192.168.1.30", "00:00:00:00:00:00")]),
+        ]);
+
+        let hosts = report.unique_hosts();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].observed_interfaces, vec!["eth0", "wlan0"]);
+        assert_eq!(hosts[0].open_ports, vec![80, 445]);
+        assert_eq!(hosts[0].hostname.as_deref(), Some("nas"));
+        assert_eq!(hosts[1].observed_interfaces.len(), 2);
+    }
+}
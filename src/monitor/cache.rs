@@ -0,0 +1,171 @@
+//! Per-host result cache
+//!
+//! Monitoring loops rescan every minute, but most of what a scan learns
+//! changes far slower than that: a vendor never changes, open ports change
+//! over hours, latency over minutes. Results are kept per host identity
+//! with a TTL per field, so a cycle only re-probes what has gone stale.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::config::{CACHE_HOSTNAME_TTL, CACHE_PORTS_TTL, CACHE_RTT_TTL};
+
+/// How long each cached field stays fresh (`None` = forever)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheTtls {
+    pub vendor: Option<Duration>,
+    pub rtt: Option<Duration>,
+    pub open_ports: Option<Duration>,
+    pub hostname: Option<Duration>,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        Self {
+            vendor: None,
+            rtt: Some(CACHE_RTT_TTL),
+            open_ports: Some(CACHE_PORTS_TTL),
+            hostname: Some(CACHE_HOSTNAME_TTL),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Stamped<T> {
+    value: T,
+    stored: Instant,
+}
+
+impl<T> Stamped<T> {
+    fn new(value: T) -> Self {
+        Self { value, stored: Instant::now() }
+    }
+
+    fn fresh(&self, ttl: Option<Duration>) -> Option<&T> {
+        match ttl {
+            Some(ttl) if self.stored.elapsed() >= ttl => None,
+            _ => Some(&self.value),
+        }
+    }
+}
+
+/// Cached results for one host; `None` inner values record a negative result
+#[derive(Debug, Clone, Default)]
+struct CachedHost {
+    vendor: Option<Stamped<Option<String>>>,
+    rtt: Option<Stamped<Option<Duration>>>,
+    open_ports: Option<Stamped<Vec<u16>>>,
+    hostname: Option<Stamped<Option<String>>>,
+}
+
+/// In-memory scan results keyed by host identity (`HostInfo::identity`)
+#[derive(Debug, Clone, Default)]
+pub struct ResultCache {
+    ttls: CacheTtls,
+    hosts: HashMap<String, CachedHost>,
+}
+
+impl ResultCache {
+    pub fn new(ttls: CacheTtls) -> Self {
+        Self { ttls, hosts: HashMap::new() }
+    }
+
+    fn host(&self, identity: &str) -> Option<&CachedHost> {
+        self.hosts.get(&identity.to_uppercase())
+    }
+
+    fn host_mut(&mut self, identity: &str) -> &mut CachedHost {
+        self.hosts.entry(identity.to_uppercase()).or_default()
+    }
+
+    /// Fresh vendor, `Some(None)` when the lookup found none
+    pub fn vendor(&self, identity: &str) -> Option<Option<String>> {
+        self.host(identity)?.vendor.as_ref()?.fresh(self.ttls.vendor).cloned()
+    }
+
+    pub fn set_vendor(&mut self, identity: &str, vendor: Option<String>) {
+        self.host_mut(identity).vendor = Some(Stamped::new(vendor));
+    }
+
+    /// Fresh round-trip time, `Some(None)` when the host did not answer
+    pub fn rtt(&self, identity: &str) -> Option<Option<Duration>> {
+        self.host(identity)?.rtt.as_ref()?.fresh(self.ttls.rtt).copied()
+    }
+
+    pub fn set_rtt(&mut self, identity: &str, rtt: Option<Duration>) {
+        self.host_mut(identity).rtt = Some(Stamped::new(rtt));
+    }
+
+    pub fn open_ports(&self, identity: &str) -> Option<Vec<u16>> {
+        self.host(identity)?.open_ports.as_ref()?.fresh(self.ttls.open_ports).cloned()
+    }
+
+    pub fn set_open_ports(&mut self, identity: &str, ports: Vec<u16>) {
+        self.host_mut(identity).open_ports = Some(Stamped::new(ports));
+    }
+
+    /// Fresh hostname, `Some(None)` when reverse DNS had no answer
+    pub fn hostname(&self, identity: &str) -> Option<Option<String>> {
+        self.host(identity)?.hostname.as_ref()?.fresh(self.ttls.hostname).cloned()
+    }
+
+    pub fn set_hostname(&mut self, identity: &str, hostname: Option<String>) {
+        self.host_mut(identity).hostname = Some(Stamped::new(hostname));
+    }
+
+    /// Forget a host entirely (e.g. its MAC moved to another address)
+    pub fn invalidate(&mut self, identity: &str) {
+        self.hosts.remove(&identity.to_uppercase());
+    }
+
+    /// Drop hosts whose every field has expired
+    pub fn purge_expired(&mut self) {
+        let ttls = self.ttls;
+        self.hosts.retain(|_, host| {
+            host.vendor.as_ref().is_some_and(|f| f.fresh(ttls.vendor).is_some())
+                || host.rtt.as_ref().is_some_and(|f| f.fresh(ttls.rtt).is_some())
+                || host.open_ports.as_ref().is_some_and(|f| f.fresh(ttls.open_ports).is_some())
+                || host.hostname.as_ref().is_some_and(|f| f.fresh(ttls.hostname).is_some())
+        });
+    }
+
+    /// Forget everything (e.g. after moving to another network)
+    pub fn clear(&mut self) {
+        self.hosts.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_field_ttl() {
+        let ttls = CacheTtls {
+            rtt: Some(Duration::ZERO),
+            ..CacheTtls::default()
+        };
+        let mut cache = ResultCache::new(ttls);
+        cache.set_vendor("aa:bb:cc:00:00:01", Some("Ubiquiti".to_string()));
+        cache.set_rtt("AA:BB:CC:00:00:01", Some(Duration::from_millis(3)));
+        cache.set_hostname("AA:BB:CC:00:00:01", None);
+
+        assert_eq!(cache.vendor("AA:BB:CC:00:00:01"), Some(Some("Ubiquiti".to_string())));
+        assert_eq!(cache.hostname("AA:BB:CC:00:00:01"), Some(None));
+        assert_eq!(cache.rtt("AA:BB:CC:00:00:01"), None);
+        assert_eq!(cache.open_ports("AA:BB:CC:00:00:01"), None);
+
+        cache.purge_expired();
+        assert_eq!(cache.len(), 1);
+        cache.invalidate("aa:bb:cc:00:00:01");
+        assert!(cache.is_empty());
+    }
+}
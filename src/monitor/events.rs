@@ -60,6 +60,30 @@ pub enum NetworkEvent {
         new_ip: String,
     },
     
+    /// Captive portal appeared or went away
+    CaptivePortalChanged {
+        detected: bool,
+        portal_url: Option<String>,
+    },
+
+    /// Internet latency left the learned baseline (`None` = unreachable)
+    WanLatencyDegraded {
+        latency_ms: Option<f64>,
+        baseline_ms: f64,
+    },
+
+    /// Internet latency returned to the learned baseline
+    WanLatencyRecovered {
+        latency_ms: f64,
+        baseline_ms: f64,
+    },
+
+    /// The machine moved networks (addresses or default gateway changed)
+    NetworkChanged {
+        previous: String,
+        current: String,
+    },
+
     /// Error during monitoring
     MonitoringError { message: String },
 }
@@ -73,6 +97,12 @@ pub struct MonitoringStatus {
     pub last_scan_time: Option<String>,
     pub devices_online: usize,
     pub devices_total: usize,
+    /// Internet access is intercepted by a captive portal
+    #[serde(default)]
+    pub captive_portal: bool,
+    /// Learned internet latency baseline (once warmed up)
+    #[serde(default)]
+    pub wan_baseline_ms: Option<f64>,
 }
 
 impl Default for MonitoringStatus {
@@ -84,6 +114,8 @@ impl Default for MonitoringStatus {
             last_scan_time: None,
             devices_online: 0,
             devices_total: 0,
+            captive_portal: false,
+            wan_baseline_ms: None,
         }
     }
 }
@@ -2,10 +2,16 @@
 //!
 //! Provides background scanning and live event emission
 
+pub mod cache;
 pub mod events;
+pub mod netchange;
 pub mod watcher;
 pub mod passive_integration;
+pub mod wan_baseline;
 
+pub use cache::*;
 pub use events::*;
+pub use netchange::*;
 pub use watcher::*;
 pub use passive_integration::*;
+pub use wan_baseline::*;
@@ -0,0 +1,145 @@
+//! Network change detection
+//!
+//! A laptop that moves from office to home Wi-Fi keeps scanning the old
+//! subnet until monitoring is restarted, and every old device is reported
+//! offline. Link, address and route changes are watched instead (netlink
+//! notifications on Linux, polling elsewhere); when the machine's addresses
+//! or default gateway differ afterwards, the monitor re-detects the
+//! interface and rescans right away.
+
+use pnet::datalink;
+use std::net::{IpAddr, Ipv4Addr};
+use tokio::sync::mpsc;
+
+use crate::config::NETWORK_CHANGE_POLL;
+use crate::network::default_gateway;
+
+/// What identifies the attached network: local IPv4 addresses and the gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFingerprint {
+    /// (interface, address, prefix) of every up, non-loopback interface
+    pub addresses: Vec<(String, Ipv4Addr, u8)>,
+    pub gateway: Option<Ipv4Addr>,
+}
+
+impl NetworkFingerprint {
+    /// Snapshot of the current interfaces and default route
+    pub fn current() -> Self {
+        let mut addresses: Vec<(String, Ipv4Addr, u8)> = datalink::interfaces()
+            .into_iter()
+            .filter(|i| i.is_up() && !i.is_loopback())
+            .flat_map(|i| {
+                let name = i.name.clone();
+                i.ips.into_iter().filter_map(move |net| match net.ip() {
+                    IpAddr::V4(ip) if !ip.is_link_local() => Some((name.clone(), ip, net.prefix())),
+                    _ => None,
+                })
+            })
+            .collect();
+        addresses.sort();
+        Self {
+            addresses,
+            gateway: default_gateway(),
+        }
+    }
+
+    /// Short description for events, e.g. `wlan0 192.168.1.20/24 via 192.168.1.1`
+    pub fn describe(&self) -> String {
+        let addresses: Vec<String> =
+            self.addresses.iter().map(|(name, ip, prefix)| format!("{} {}/{}", name, ip, prefix)).collect();
+        let addresses = if addresses.is_empty() { "no addresses".to_string() } else { addresses.join(", ") };
+        match self.gateway {
+            Some(gateway) => format!("{} via {}", addresses, gateway),
+            None => format!("{} (no default route)", addresses),
+        }
+    }
+}
+
+/// Notifications that the network configuration may have changed
+///
+/// Each message is only a hint; compare `NetworkFingerprint`s to find out
+/// whether anything relevant changed. The sender side stops once the
+/// receiver is dropped.
+pub fn network_change_hints() -> mpsc::UnboundedReceiver<()> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(socket) = netlink::subscribe() {
+            std::thread::spawn(move || netlink::forward(socket, tx));
+            return rx;
+        }
+    }
+
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(NETWORK_CHANGE_POLL);
+        loop {
+            ticks.tick().await;
+            if tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(target_os = "linux")]
+mod netlink {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use tokio::sync::mpsc::UnboundedSender;
+
+    /// Netlink socket subscribed to link, IPv4 address and route changes
+    pub fn subscribe() -> Option<OwnedFd> {
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_ROUTE) };
+        if fd < 0 {
+            return None;
+        }
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = (libc::RTMGRP_LINK | libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV4_ROUTE) as u32;
+        let bound = unsafe {
+            libc::bind(
+                socket.as_raw_fd(),
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        (bound == 0).then_some(socket)
+    }
+
+    /// Send a hint per netlink message until the receiver is gone
+    pub fn forward(socket: OwnedFd, tx: UnboundedSender<()>) {
+        let mut buffer = [0u8; 8192];
+        loop {
+            let n = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+            if n < 0 && std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return;
+            }
+            if n > 0 && tx.send(()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_describe() {
+        let fingerprint = NetworkFingerprint {
+            addresses: vec![("wlan0".to_string(), Ipv4Addr::new(192, 168, 1, 20), 24)],
+            gateway: Some(Ipv4Addr::new(192, 168, 1, 1)),
+        };
+        assert_eq!(fingerprint.describe(), "wlan0 192.168.1.20/24 via 192.168.1.1");
+
+        let moved = NetworkFingerprint {
+            gateway: Some(Ipv4Addr::new(10, 0, 0, 1)),
+            ..fingerprint.clone()
+        };
+        assert_ne!(moved, fingerprint);
+    }
+}
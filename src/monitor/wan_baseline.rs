@@ -0,0 +1,112 @@
+//! Learned internet latency baseline
+//!
+//! Tracks an exponentially weighted mean and variance of the WAN latency
+//! samples taken each monitoring cycle. Once warmed up, a sample far above
+//! the baseline (or an unreachable internet) marks the WAN as degraded;
+//! the state flips back when samples return to normal, so each episode
+//! produces one event pair instead of one event per cycle.
+
+use serde::{Deserialize, Serialize};
+
+/// Weight of a new sample in the moving mean/variance
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Samples needed before deviations are reported
+const WARMUP_SAMPLES: u32 = 5;
+
+/// Standard deviations above the mean that count as a deviation
+const DEVIATION_SIGMA: f64 = 3.0;
+
+/// Minimum absolute increase (ms) that counts, avoids noise on steady links
+const DEVIATION_FLOOR_MS: f64 = 30.0;
+
+/// Change of WAN state produced by a sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WanLatencyChange {
+    /// Latency left the baseline (`None` = anchors unreachable)
+    Degraded { latency_ms: Option<f64>, baseline_ms: f64 },
+    /// Latency is back within the baseline
+    Recovered { latency_ms: f64, baseline_ms: f64 },
+}
+
+/// Moving latency baseline for the internet anchors
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WanLatencyBaseline {
+    pub mean_ms: f64,
+    pub variance: f64,
+    pub samples: u32,
+    pub degraded: bool,
+}
+
+impl WanLatencyBaseline {
+    /// Baseline latency once warmed up
+    pub fn baseline_ms(&self) -> Option<f64> {
+        (self.samples >= WARMUP_SAMPLES).then_some(self.mean_ms)
+    }
+
+    /// Latency above which a sample is a deviation
+    fn threshold_ms(&self) -> f64 {
+        self.mean_ms + (DEVIATION_SIGMA * self.variance.sqrt()).max(DEVIATION_FLOOR_MS)
+    }
+
+    /// Feed one sample (`None` = unreachable) and report a state change
+    pub fn observe(&mut self, sample: Option<f64>) -> Option<WanLatencyChange> {
+        let warmed_up = self.samples >= WARMUP_SAMPLES;
+        let deviates = warmed_up && sample.is_none_or(|ms| ms > self.threshold_ms());
+        let baseline_ms = self.mean_ms;
+
+        // Outliers are kept out of the baseline so a long outage is not learned as normal
+        if let Some(ms) = sample.filter(|_| !deviates) {
+            if self.samples == 0 {
+                self.mean_ms = ms;
+            } else {
+                let delta = ms - self.mean_ms;
+                self.mean_ms += EWMA_ALPHA * delta;
+                self.variance = (1.0 - EWMA_ALPHA) * (self.variance + EWMA_ALPHA * delta * delta);
+            }
+            self.samples += 1;
+        }
+
+        match (deviates, self.degraded) {
+            (true, false) => {
+                self.degraded = true;
+                Some(WanLatencyChange::Degraded { latency_ms: sample, baseline_ms })
+            }
+            (false, true) => {
+                self.degraded = false;
+                sample.map(|latency_ms| WanLatencyChange::Recovered { latency_ms, baseline_ms })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_alerts_during_warmup() {
+        let mut baseline = WanLatencyBaseline::default();
+        for _ in 0..WARMUP_SAMPLES {
+            assert_eq!(baseline.observe(None), None);
+        }
+        assert_eq!(baseline.baseline_ms(), None);
+    }
+
+    #[test]
+    fn test_degraded_and_recovered() {
+        let mut baseline = WanLatencyBaseline::default();
+        for ms in [20.0, 22.0, 19.0, 21.0, 20.0, 23.0] {
+            assert_eq!(baseline.observe(Some(ms)), None);
+        }
+        let learned = baseline.baseline_ms().unwrap();
+
+        assert!(matches!(baseline.observe(Some(300.0)), Some(WanLatencyChange::Degraded { .. })));
+        // Still degraded: no repeated event, baseline untouched
+        assert_eq!(baseline.observe(None), None);
+        assert_eq!(baseline.baseline_ms(), Some(learned));
+
+        assert!(matches!(baseline.observe(Some(21.0)), Some(WanLatencyChange::Recovered { .. })));
+    }
+}
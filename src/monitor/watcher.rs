@@ -3,18 +3,26 @@
 //! Provides continuous network scanning in background thread
 //! Uses callbacks for event notification (Tauri-agnostic)
 
+use pnet::util::MacAddr;
 use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 
+use super::cache::ResultCache;
+use super::netchange::{network_change_hints, NetworkFingerprint};
 use super::events::{DeviceSnapshot, MonitoringStatus, NetworkEvent};
-use crate::config::{DEFAULT_MONITOR_INTERVAL, MAX_MONITOR_INTERVAL, MIN_MONITOR_INTERVAL};
+use super::wan_baseline::{WanLatencyBaseline, WanLatencyChange};
+use crate::config::{
+    DEFAULT_MONITOR_INTERVAL, MAX_MONITOR_INTERVAL, MIN_MONITOR_INTERVAL, NETWORK_CHANGE_SETTLE, TCP_PROBE_PORTS, WAN_MONITOR_ENABLED,
+};
 use crate::{
     find_valid_interface, calculate_subnet_ips,
-    active_arp_scan, icmp_scan, tcp_probe_scan, dns_scan,
-    lookup_vendor_info, infer_device_type,
+    active_arp_scan, icmp_scan, tcp_port_scan_adaptive, HostTimeouts, dns_scan, open_ports,
+    captive_portal_check, sample_wan_latency, ConnectivityState,
+    lookup_vendor_info, infer_device_type, MacAddress,
 };
 
 /// Event callback type
@@ -26,8 +34,14 @@ pub struct BackgroundMonitor {
     interval_seconds: Arc<Mutex<u64>>,
     scan_count: Arc<AtomicU32>,
     last_scan_time: Arc<Mutex<Option<String>>>,
+    /// Result of the last captive portal check
+    captive_portal: Arc<AtomicBool>,
+    /// Internet latency baseline learned across cycles
+    wan_baseline: Arc<Mutex<WanLatencyBaseline>>,
     /// Previous scan results for change detection (MAC -> DeviceSnapshot)
     previous_devices: Arc<Mutex<HashMap<String, DeviceSnapshot>>>,
+    /// Per-host results reused while fresh, so cycles skip re-probing
+    cache: Arc<Mutex<ResultCache>>,
 }
 
 impl BackgroundMonitor {
@@ -37,7 +51,10 @@ impl BackgroundMonitor {
             interval_seconds: Arc::new(Mutex::new(DEFAULT_MONITOR_INTERVAL)),
             scan_count: Arc::new(AtomicU32::new(0)),
             last_scan_time: Arc::new(Mutex::new(None)),
+            captive_portal: Arc::new(AtomicBool::new(false)),
+            wan_baseline: Arc::new(Mutex::new(WanLatencyBaseline::default())),
             previous_devices: Arc::new(Mutex::new(HashMap::new())),
+            cache: Arc::new(Mutex::new(ResultCache::default())),
         }
     }
 
@@ -70,10 +87,23 @@ impl BackgroundMonitor {
         let is_running = Arc::clone(&self.is_running);
         let scan_count = Arc::clone(&self.scan_count);
         let last_scan_time = Arc::clone(&self.last_scan_time);
+        let captive_portal = Arc::clone(&self.captive_portal);
+        let wan_baseline = Arc::clone(&self.wan_baseline);
         let previous_devices = Arc::clone(&self.previous_devices);
+        let cache = Arc::clone(&self.cache);
         let interval_seconds = Arc::clone(&self.interval_seconds);
         let cb = Arc::clone(&callback);
 
+        // Rescan immediately when the machine moves networks
+        let rescan = Arc::new(Notify::new());
+        tokio::spawn(watch_network_changes(
+            Arc::clone(&is_running),
+            Arc::clone(&rescan),
+            Arc::clone(&previous_devices),
+            Arc::clone(&cache),
+            Arc::clone(&callback),
+        ));
+
         // Spawn background scanning task
         tokio::spawn(async move {
             eprintln!("[MONITOR] Background monitoring started (interval: {}s)", interval_secs);
@@ -88,10 +118,40 @@ impl BackgroundMonitor {
                 });
 
                 eprintln!("[MONITOR] Starting scan #{}", current_scan);
+
+                // A portal waiting for a login explains failing upstream checks
+                let connectivity = captive_portal_check().await;
+                let behind_portal = connectivity.state == ConnectivityState::CaptivePortal;
+                if captive_portal.swap(behind_portal, Ordering::SeqCst) != behind_portal {
+                    (*cb)(NetworkEvent::CaptivePortalChanged {
+                        detected: behind_portal,
+                        portal_url: connectivity.portal_url,
+                    });
+                }
+
+                // Behind a portal every anchor looks unreachable; don't learn that
+                if WAN_MONITOR_ENABLED && !behind_portal {
+                    let sample = sample_wan_latency().await;
+                    let change = wan_baseline.lock().await.observe(sample);
+                    match change {
+                        Some(WanLatencyChange::Degraded { latency_ms, baseline_ms }) => {
+                            eprintln!("[MONITOR] WAN latency degraded: {:?} ms (baseline {:.0} ms)", latency_ms, baseline_ms);
+                            (*cb)(NetworkEvent::WanLatencyDegraded { latency_ms, baseline_ms });
+                        }
+                        Some(WanLatencyChange::Recovered { latency_ms, baseline_ms }) => {
+                            (*cb)(NetworkEvent::WanLatencyRecovered { latency_ms, baseline_ms });
+                        }
+                        None => {}
+                    }
+                }
                 let start = Instant::now();
 
                 // Run the actual scan
-                match run_background_scan(&*cb).await {
+                let mut cached = cache.lock().await;
+                cached.purge_expired();
+                let outcome = run_background_scan(&*cb, &mut cached).await;
+                drop(cached);
+                match outcome {
                     Ok(devices) => {
                         let duration = start.elapsed().as_millis() as u64;
 
@@ -117,9 +177,12 @@ impl BackgroundMonitor {
                     }
                     Err(e) => {
                         eprintln!("[MONITOR] Scan #{} failed: {}", current_scan, e);
-                        (*cb)(NetworkEvent::MonitoringError {
-                            message: e,
-                        });
+                        let message = if behind_portal {
+                            format!("{} (network is behind a captive portal; log in to restore access)", e)
+                        } else {
+                            e
+                        };
+                        (*cb)(NetworkEvent::MonitoringError { message });
                     }
                 }
 
@@ -128,7 +191,10 @@ impl BackgroundMonitor {
                     if !is_running.load(Ordering::SeqCst) {
                         break;
                     }
-                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+                        _ = rescan.notified() => break,
+                    }
                 }
             }
 
@@ -156,6 +222,8 @@ impl BackgroundMonitor {
             last_scan_time: self.last_scan_time.lock().await.clone(),
             devices_online: online_count,
             devices_total: prev.len(),
+            captive_portal: self.captive_portal.load(Ordering::SeqCst),
+            wan_baseline_ms: self.wan_baseline.lock().await.baseline_ms(),
         }
     }
 
@@ -171,8 +239,51 @@ impl Default for BackgroundMonitor {
     }
 }
 
+/// Watch for interface, address and default-route changes while monitoring
+///
+/// On a real change the old network's devices are forgotten (so they are
+/// not all reported offline) and the scan loop is woken to rescan.
+async fn watch_network_changes<F>(
+    is_running: Arc<AtomicBool>,
+    rescan: Arc<Notify>,
+    previous_devices: Arc<Mutex<HashMap<String, DeviceSnapshot>>>,
+    cache: Arc<Mutex<ResultCache>>,
+    callback: Arc<F>,
+) where
+    F: Fn(NetworkEvent) + Send + Sync + 'static,
+{
+    let mut hints = network_change_hints();
+    let mut known = NetworkFingerprint::current();
+
+    while is_running.load(Ordering::SeqCst) {
+        match tokio::time::timeout(Duration::from_secs(1), hints.recv()).await {
+            Ok(Some(())) => {}
+            Ok(None) => break,
+            Err(_) => continue,
+        }
+
+        // Let DHCP and routing settle, then coalesce the burst of hints
+        tokio::time::sleep(NETWORK_CHANGE_SETTLE).await;
+        while hints.try_recv().is_ok() {}
+
+        let current = NetworkFingerprint::current();
+        if current == known {
+            continue;
+        }
+        eprintln!("[MONITOR] Network changed: {} -> {}", known.describe(), current.describe());
+        previous_devices.lock().await.clear();
+        cache.lock().await.clear();
+        (*callback)(NetworkEvent::NetworkChanged {
+            previous: known.describe(),
+            current: current.describe(),
+        });
+        known = current;
+        rescan.notify_one();
+    }
+}
+
 /// Run a background scan and return device snapshots
-async fn run_background_scan<F>(callback: &F) -> Result<Vec<DeviceSnapshot>, String>
+async fn run_background_scan<F>(callback: &F, cache: &mut ResultCache) -> Result<Vec<DeviceSnapshot>, String>
 where
     F: Fn(NetworkEvent),
 {
@@ -209,20 +320,43 @@ where
         .map_err(|e| format!("ARP scan error: {}", e))?
     };
 
+    // Only hosts whose cached latency or ports have expired are probed again
+    let identity = |mac: &MacAddr| format!("{:X}", MacAddress::from(*mac));
+    let stale = |fresh: &dyn Fn(&str) -> bool| -> HashMap<Ipv4Addr, MacAddr> {
+        arp_hosts.iter().filter(|(_, mac)| !fresh(&identity(mac))).map(|(ip, mac)| (*ip, *mac)).collect()
+    };
+    let rtt_stale = stale(&|id| cache.rtt(id).is_some());
+    let ports_stale = stale(&|id| cache.open_ports(id).is_some());
+
     // Emit progress: ICMP scan
     callback(NetworkEvent::ScanProgress {
         phase: "ICMP".to_string(),
         percent: 50,
-        message: format!("ICMP scanning {} hosts...", arp_hosts.len()),
+        message: format!(
+            "ICMP scanning {} hosts ({} cached)...",
+            rtt_stale.len(),
+            arp_hosts.len() - rtt_stale.len()
+        ),
     });
 
-    let (response_times, port_results) = tokio::join!(
-        icmp_scan(&arp_hosts),
-        tcp_probe_scan(&arp_hosts)
-    );
+    let response_times = icmp_scan(&rtt_stale).await.unwrap_or_default();
+    for (ip, mac) in &rtt_stale {
+        cache.set_rtt(&identity(mac), response_times.get(ip).and_then(|r| r.duration));
+    }
 
-    let _response_times = response_times.unwrap_or_default();
-    let port_results = port_results.unwrap_or_default();
+    // Connect timeouts sized by each host's round trip, fresh or cached
+    let mut timeouts = HostTimeouts::from_icmp(&response_times);
+    for (ip, mac) in &ports_stale {
+        if let Some(Some(rtt)) = cache.rtt(&identity(mac)) {
+            timeouts.insert(*ip, rtt);
+        }
+    }
+    let port_results = tcp_port_scan_adaptive(&ports_stale, TCP_PROBE_PORTS, &timeouts).await;
+    for (ip, mac) in &ports_stale {
+        if let Some(probes) = port_results.as_ref().ok().and_then(|results| results.get(ip)) {
+            cache.set_open_ports(&identity(mac), open_ports(probes));
+        }
+    }
 
     // Emit progress: DNS lookup
     callback(NetworkEvent::ScanProgress {
@@ -231,13 +365,16 @@ where
         message: "Resolving hostnames...".to_string(),
     });
 
-    let host_ips: Vec<std::net::Ipv4Addr> = arp_hosts
-        .keys()
-        .filter(|ip| **ip != interface.ip)
-        .copied()
+    let host_ips: Vec<Ipv4Addr> = arp_hosts
+        .iter()
+        .filter(|(ip, mac)| **ip != interface.ip && cache.hostname(&identity(mac)).is_none())
+        .map(|(ip, _)| *ip)
         .collect();
 
-    let dns_hostnames = dns_scan(&host_ips).await;
+    let resolved = dns_scan(&host_ips).await;
+    for ip in &host_ips {
+        cache.set_hostname(&identity(&arp_hosts[ip]), resolved.get(ip).cloned());
+    }
 
     // Build device snapshots
     callback(NetworkEvent::ScanProgress {
@@ -250,14 +387,20 @@ where
         .iter()
         .filter(|(ip, _)| **ip != interface.ip)
         .map(|(ip, mac)| {
-            let mac_str = format!("{}", mac);
-            let vendor_info = lookup_vendor_info(&mac_str);
-            let open_ports = port_results.get(ip).cloned().unwrap_or_default();
+            let mac_str = MacAddress::from(*mac).to_string();
+            let id = identity(mac);
+            let vendor = cache.vendor(&id).unwrap_or_else(|| {
+                let vendor = lookup_vendor_info(&mac_str).vendor;
+                cache.set_vendor(&id, vendor.clone());
+                vendor
+            });
+            let open_ports = cache.open_ports(&id).unwrap_or_default();
+            let hostname = cache.hostname(&id).flatten();
             let is_gateway = ip.octets()[3] == 1 || open_ports.contains(&80);
             
             let device_type = infer_device_type(
-                vendor_info.vendor.as_deref(),
-                dns_hostnames.get(ip).map(|s| s.as_str()),
+                vendor.as_deref(),
+                hostname.as_deref(),
                 &open_ports,
                 is_gateway,
             );
@@ -265,7 +408,7 @@ where
             DeviceSnapshot {
                 mac: mac_str,
                 ip: ip.to_string(),
-                hostname: dns_hostnames.get(ip).cloned(),
+                hostname,
                 device_type: device_type.as_str().to_string(),
                 is_online: true,
             }
@@ -0,0 +1,91 @@
+//! Container network detection
+//!
+//! Docker and Podman put their bridges (`docker0`, `br-<id>`, `podman0`)
+//! next to the LAN adapter, and containers published with macvlan or host
+//! networking show up on the LAN itself. Both are kept out of the physical
+//! inventory: bridges are swept separately when asked, and LAN hosts with
+//! Docker's MAC prefix are moved to the container list.
+
+use pnet::datalink;
+use pnet::util::MacAddr;
+use std::net::IpAddr;
+
+use super::device::DeviceType;
+use super::interface::{classify_interface, InterfaceKind};
+use super::mac::MacAddress;
+use crate::models::{HostInfo, InterfaceInfo};
+
+/// Docker derives container MACs from `02:42` followed by the IPv4 address
+const DOCKER_MAC_PREFIX: [u8; 2] = [0x02, 0x42];
+
+/// Whether a MAC address looks like a Docker container's
+pub fn is_container_mac(mac: &str) -> bool {
+    mac.parse::<MacAddress>().is_ok_and(|mac| mac.octets()[..2] == DOCKER_MAC_PREFIX)
+}
+
+/// Host side of each container bridge that has a subnet to sweep
+///
+/// veth pairs are skipped: they are the container ends of a bridge and
+/// carry no address of their own.
+pub fn container_bridges() -> Vec<InterfaceInfo> {
+    datalink::interfaces()
+        .into_iter()
+        .filter(|i| i.is_up() && !i.name.starts_with("veth"))
+        .filter(|i| classify_interface(&i.name, i.is_point_to_point()) == InterfaceKind::Container)
+        .filter_map(|i| {
+            let mac = i.mac.filter(|m| *m != MacAddr::zero())?;
+            let (ip, prefix_len) = i.ips.iter().find_map(|net| match net.ip() {
+                IpAddr::V4(ip) if net.prefix() < 31 => Some((ip, net.prefix())),
+                _ => None,
+            })?;
+            Some(InterfaceInfo {
+                name: i.name.clone(),
+                ip,
+                mac,
+                prefix_len,
+                aliases: Vec::new(),
+                pnet_interface: i.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Move hosts with container MACs out of `hosts`, labeled as containers
+pub fn separate_containers(hosts: &mut Vec<HostInfo>) -> Vec<HostInfo> {
+    let (mut containers, lan): (Vec<HostInfo>, Vec<HostInfo>) =
+        std::mem::take(hosts).into_iter().partition(|h| is_container_mac(&h.mac));
+    *hosts = lan;
+    for host in containers.iter_mut() {
+        host.device_type = DeviceType::Container.as_str().to_string();
+    }
+    containers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_separate_containers() {
+        let mut hosts = vec![
+            host("192.168.1.10", "AA:BB:CC:00:00:01"),
+            host("192.168.1.20", "02:42:C0:A8:01:14"),
+        ];
+        let containers = separate_containers(&mut hosts);
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].ip, "192.168.1.10");
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].device_type, "CONTAINER");
+        assert!(!is_container_mac("00:42:c0:a8:01:14"));
+    }
+}
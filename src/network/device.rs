@@ -24,6 +24,8 @@ pub enum DeviceType {
     Printer,
     Camera,
     GameConsole,
+    Container,
+    VirtualMachine,
     Unknown,
 }
 
@@ -45,6 +47,8 @@ impl DeviceType {
             DeviceType::Printer => "PRINTER",
             DeviceType::Camera => "CAMERA",
             DeviceType::GameConsole => "GAME_CONSOLE",
+            DeviceType::Container => "CONTAINER",
+            DeviceType::VirtualMachine => "VIRTUAL_MACHINE",
             DeviceType::Unknown => "UNKNOWN",
         }
     }
@@ -260,6 +264,8 @@ pub fn calculate_risk_score(
         DeviceType::SmartTv => 15,
         DeviceType::GameConsole => 5,
         DeviceType::Switch | DeviceType::AccessPoint => 10,
+        DeviceType::Container => 10,
+        DeviceType::VirtualMachine => 15,
         DeviceType::Unknown => 20,  // Unknown devices are concerning
     };
     
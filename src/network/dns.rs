@@ -39,6 +39,21 @@ pub fn reverse_lookup(ip: Ipv4Addr) -> Option<String> {
     }
 }
 
+/// IPv4 `nameserver` entries of a resolv.conf
+pub fn parse_resolv_conf(text: &str) -> Vec<Ipv4Addr> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .filter_map(|server| server.trim().parse().ok())
+        .collect()
+}
+
+/// Nameservers this machine resolves through
+///
+/// On a DHCP client these are the servers handed out in option 6.
+pub fn system_nameservers() -> Vec<Ipv4Addr> {
+    parse_resolv_conf(&std::fs::read_to_string("/etc/resolv.conf").unwrap_or_default())
+}
+
 /// Perform reverse DNS lookup for multiple IP addresses concurrently
 pub async fn dns_scan(ips: &[Ipv4Addr]) -> HashMap<Ipv4Addr, String> {
     if ips.is_empty() {
@@ -94,4 +109,10 @@ mod tests {
         println!("Localhost reverse lookup: {:?}", result);
         // Usually returns "localhost" or similar
     }
+
+    #[test]
+    fn test_parse_resolv_conf() {
+        let text = "# generated by dhclient\nsearch lan\nnameserver 192.168.1.1\nnameserver fe80::1\nnameserver 9.9.9.9\n";
+        assert_eq!(parse_resolv_conf(text), vec![Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(9, 9, 9, 9)]);
+    }
 }
@@ -0,0 +1,124 @@
+//! Dual-stack host correlation
+//!
+//! A host answering on both address families is one device, and its MAC is
+//! the only thing both observations share. IPv6 entries whose MAC matches
+//! an IPv4 host are folded into that host's `ipv6_addresses` instead of
+//! being listed separately; IPv6-only hosts keep their own entry.
+
+use std::collections::HashMap;
+use std::net::Ipv6Addr;
+
+use super::mac::MacAddress;
+use super::neighbors::Ipv6Neighbor;
+use crate::models::HostInfo;
+
+/// MAC used for matching, `None` when the host has no usable MAC
+fn mac_key(mac: &str) -> Option<String> {
+    let mac = mac.parse::<MacAddress>().ok().filter(|mac| !mac.is_zero())?;
+    Some(format!("{:X}", mac))
+}
+
+fn add_address(host: &mut HostInfo, address: String) {
+    if address != host.ip && !host.ipv6_addresses.contains(&address) {
+        host.ipv6_addresses.push(address);
+    }
+}
+
+/// Merge an IPv6 observation into the host's primary entry
+fn absorb(primary: &mut HostInfo, other: HostInfo) {
+    add_address(primary, other.ip);
+    for address in other.ipv6_addresses {
+        add_address(primary, address);
+    }
+    for port in other.open_ports {
+        if !primary.open_ports.contains(&port) {
+            primary.open_ports.push(port);
+        }
+    }
+    primary.open_ports.sort_unstable();
+    primary.hostname = primary.hostname.take().or(other.hostname);
+    primary.vendor = primary.vendor.take().or(other.vendor);
+    primary.os_guess = primary.os_guess.take().or(other.os_guess);
+}
+
+/// Fold IPv6 observations into the IPv4 host with the same MAC
+///
+/// `neighbors` (from `ipv6_neighbors`) adds addresses of hosts only seen in
+/// the neighbor cache. Global addresses are listed before link-local ones.
+pub fn correlate_dual_stack(hosts: &mut Vec<HostInfo>, neighbors: &[Ipv6Neighbor]) {
+    let (ipv4, ipv6): (Vec<HostInfo>, Vec<HostInfo>) =
+        hosts.drain(..).partition(|h| h.ip.parse::<Ipv6Addr>().is_err());
+
+    // IPv4 entries come first so they stay the primary record; two IPv4
+    // entries sharing a MAC (proxy ARP, secondary addresses) are kept apart
+    let mut by_mac: HashMap<String, usize> = HashMap::new();
+    for host in ipv4 {
+        if let Some(mac) = mac_key(&host.mac) {
+            by_mac.entry(mac).or_insert(hosts.len());
+        }
+        hosts.push(host);
+    }
+    for host in ipv6 {
+        match mac_key(&host.mac).and_then(|mac| by_mac.get(&mac).copied()) {
+            Some(index) => absorb(&mut hosts[index], host),
+            None => {
+                if let Some(mac) = mac_key(&host.mac) {
+                    by_mac.insert(mac, hosts.len());
+                }
+                hosts.push(host);
+            }
+        }
+    }
+
+    for neighbor in neighbors {
+        if let Some(&index) = by_mac.get(&MacAddress::normalize(&neighbor.mac)) {
+            add_address(&mut hosts[index], neighbor.address.to_string());
+        }
+    }
+
+    for host in hosts.iter_mut() {
+        host.ipv6_addresses.sort_by_key(|a| {
+            let link_local = a.parse::<Ipv6Addr>().is_ok_and(|ip| (ip.segments()[0] & 0xffc0) == 0xfe80);
+            (link_local, a.clone())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_correlate_dual_stack() {
+        let mut hosts = vec![
+            host("2001:db8::10", "aa:bb:cc:00:00:10", vec![22, 443]),
+            host("192.168.1.10", "AA:BB:CC:00:00:10", vec![22]),
+            host("192.168.1.11", "AA:BB:CC:00:00:10", vec![]),
+            host("2001:db8::99", "AA:BB:CC:00:00:99", vec![]),
+        ];
+        let neighbors = vec![Ipv6Neighbor {
+            address: "fe80::10".parse().unwrap(),
+            mac: "aa:bb:cc:00:00:10".to_string(),
+            is_router: false,
+        }];
+
+        correlate_dual_stack(&mut hosts, &neighbors);
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts[0].ip, "192.168.1.10");
+        assert_eq!(hosts[0].ipv6_addresses, vec!["2001:db8::10", "fe80::10"]);
+        assert_eq!(hosts[0].open_ports, vec![22, 443]);
+        assert!(hosts[1].ipv6_addresses.is_empty());
+        assert_eq!(hosts[2].ip, "2001:db8::99");
+    }
+}
@@ -0,0 +1,127 @@
+//! Hostname source precedence
+//!
+//! Reverse DNS, mDNS, SNMP sysName and DHCP leases often disagree: the PTR
+//! record says `dhcp-42.corp.example`, the device announces itself as
+//! `Kitchen Speaker`. Which one becomes the hostname, and whether the
+//! others are kept, is a policy choice rather than a fixed rule.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{HostInfo, HostnameRecord, HostnameSource};
+
+/// What to do with the names that lose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostnameConflict {
+    /// Keep only the winning name
+    #[default]
+    PreferSource,
+    /// Keep every distinct name in `hostname_records`
+    KeepAll,
+}
+
+impl HostnameConflict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HostnameConflict::PreferSource => "prefer_source",
+            HostnameConflict::KeepAll => "keep_all",
+        }
+    }
+}
+
+/// Which hostname source wins and what happens to the rest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostnamePolicy {
+    /// Sources from most to least trusted; unlisted sources are ignored
+    pub precedence: Vec<HostnameSource>,
+    pub conflict: HostnameConflict,
+}
+
+impl Default for HostnamePolicy {
+    /// DNS first, then SNMP, then self-announced and DHCP names
+    fn default() -> Self {
+        Self {
+            precedence: vec![
+                HostnameSource::Ptr,
+                HostnameSource::Snmp,
+                HostnameSource::Mdns,
+                HostnameSource::Dhcp,
+            ],
+            conflict: HostnameConflict::PreferSource,
+        }
+    }
+}
+
+impl HostnamePolicy {
+    /// Pick the hostname from `records`; returns it and the records to keep
+    pub fn resolve(&self, records: &[HostnameRecord]) -> (Option<String>, Vec<HostnameRecord>) {
+        let mut ranked: Vec<(usize, &HostnameRecord)> = records
+            .iter()
+            .filter(|r| !r.name.trim().is_empty())
+            .filter_map(|r| Some((self.precedence.iter().position(|s| *s == r.source)?, r)))
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+
+        let hostname = ranked.first().map(|(_, r)| r.name.clone());
+        let kept = match self.conflict {
+            HostnameConflict::PreferSource => Vec::new(),
+            HostnameConflict::KeepAll => {
+                let mut kept: Vec<HostnameRecord> = Vec::new();
+                for (_, record) in ranked {
+                    if !kept.contains(record) {
+                        kept.push(record.clone());
+                    }
+                }
+                kept
+            }
+        };
+        (hostname, kept)
+    }
+}
+
+/// Set each host's hostname from its collected `hostname_records`
+///
+/// Hosts without records keep whatever hostname they already have.
+pub fn resolve_hostnames(hosts: &mut [HostInfo], policy: &HostnamePolicy) {
+    for host in hosts.iter_mut().filter(|h| !h.hostname_records.is_empty()) {
+        let (hostname, kept) = policy.resolve(&host.hostname_records);
+        if hostname.is_some() {
+            host.hostname = hostname;
+        }
+        host.hostname_records = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(source: HostnameSource, name: &str) -> HostnameRecord {
+        HostnameRecord {
+            source,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_precedence_and_conflict() {
+        let records = vec![
+            record(HostnameSource::Mdns, "Kitchen Speaker"),
+            record(HostnameSource::Ptr, "dhcp-42.corp.example"),
+            record(HostnameSource::Dhcp, "Kitchen Speaker"),
+        ];
+
+        let (hostname, kept) = HostnamePolicy::default().resolve(&records);
+        assert_eq!(hostname.as_deref(), Some("dhcp-42.corp.example"));
+        assert!(kept.is_empty());
+
+        let policy = HostnamePolicy {
+            precedence: vec![HostnameSource::Mdns, HostnameSource::Ptr],
+            conflict: HostnameConflict::KeepAll,
+        };
+        let (hostname, kept) = policy.resolve(&records);
+        assert_eq!(hostname.as_deref(), Some("Kitchen Speaker"));
+        // DHCP is not in the precedence list
+        assert_eq!(kept, vec![records[0].clone(), records[1].clone()]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Incremental rescans
+//!
+//! Tight monitoring intervals cannot afford a full subnet sweep every time.
+//! Given the previous report, only addresses that were silent and hosts
+//! whose data is incomplete are probed again; fully identified hosts are
+//! carried over as they were. A carried-over host that has since gone
+//! offline is not noticed until the next full scan.
+
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+
+use crate::models::{HostInfo, ScanResult};
+
+/// Path of a previous JSON report; when set, the CLI rescans incrementally
+pub const PREVIOUS_REPORT_ENV: &str = "HOST_DISCOVERY_PREVIOUS_REPORT";
+
+/// Whether a host is missing data worth another probe
+///
+/// No usable MAC, an unclassified device, or no evidence beyond ARP (no
+/// ping reply and no open port) all count as incomplete.
+pub fn is_incomplete(host: &HostInfo) -> bool {
+    host.mac.is_empty()
+        || host.mac == "00:00:00:00:00:00"
+        || host.device_type == "UNKNOWN"
+        || (host.response_time_ms.is_none() && host.open_ports.is_empty())
+}
+
+/// Addresses to probe and hosts reused from the previous report
+#[derive(Debug, Clone, Default)]
+pub struct RescanPlan {
+    /// Previously silent addresses plus incomplete hosts
+    pub targets: Vec<Ipv4Addr>,
+    /// Complete hosts kept from the previous report
+    pub carried_over: Vec<HostInfo>,
+}
+
+impl RescanPlan {
+    /// Plan a rescan of `subnet_ips` from the previous report
+    pub fn from_previous(previous: &ScanResult, subnet_ips: &[Ipv4Addr]) -> Self {
+        let mut complete: HashSet<Ipv4Addr> = HashSet::new();
+        let mut carried_over = Vec::new();
+        for host in &previous.active_hosts {
+            let Ok(ip) = host.ip.parse::<Ipv4Addr>() else { continue };
+            if subnet_ips.contains(&ip) && !is_incomplete(host) {
+                complete.insert(ip);
+                carried_over.push(host.clone());
+            }
+        }
+
+        Self {
+            targets: subnet_ips.iter().copied().filter(|ip| !complete.contains(ip)).collect(),
+            carried_over,
+        }
+    }
+
+    /// Combine rescanned hosts with the carried-over ones
+    ///
+    /// A fresh result for an address replaces the carried-over entry.
+    pub fn merge(self, rescanned: Vec<HostInfo>) -> Vec<HostInfo> {
+        let fresh: HashSet<String> = rescanned.iter().map(|h| h.ip.clone()).collect();
+        let mut hosts = rescanned;
+        hosts.extend(self.carried_over.into_iter().filter(|h| !fresh.contains(&h.ip)));
+        hosts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str, device_type: &str, ports: Vec<u16>) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: device_type.to_string(),
+            open_ports: ports,
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rescan_plan() {
+        let previous: ScanResult = serde_json::from_value(serde_json::json!({
+            "interface_name": "eth0", "local_ip": "10.0.0.50", "local_mac": "", "subnet": "10.0.0.0/29",
+            "scan_method": "", "arp_discovered": 3, "icmp_discovered": 0, "total_hosts": 3, "scan_duration_ms": 0,
+            "active_hosts": []
+        }))
+        .unwrap();
+        let previous = ScanResult {
+            active_hosts: vec![
+                host("10.0.0.1", "AA:00:00:00:00:01", "ROUTER", vec![80]),
+                host("10.0.0.2", "AA:00:00:00:00:02", "UNKNOWN", vec![22]),
+                host("10.0.0.3", "AA:00:00:00:00:03", "PC", vec![]),
+            ],
+            ..previous
+        };
+        let subnet: Vec<Ipv4Addr> = (1..=6).map(|n| Ipv4Addr::new(10, 0, 0, n)).collect();
+
+        let plan = RescanPlan::from_previous(&previous, &subnet);
+        assert_eq!(plan.targets.len(), 5);
+        assert!(!plan.targets.contains(&Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(plan.carried_over.len(), 1);
+
+        let merged = plan.merge(vec![host("10.0.0.2", "AA:00:00:00:00:02", "NAS", vec![22, 445])]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].device_type, "NAS");
+    }
+}
@@ -1,10 +1,13 @@
 //! Network interface detection and selection
 
 use anyhow::{anyhow, Result};
+use ipnetwork::Ipv4Network;
 use pnet::datalink;
 use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, Ipv4Addr};
 
+use crate::config::{INTERFACE_EXCLUDE, INTERFACE_INCLUDE};
 use crate::models::InterfaceInfo;
 
 /// Logs a message to stderr
@@ -14,9 +17,138 @@ macro_rules! log_stderr {
     };
 }
 
+/// What kind of link an interface is, from its name and flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceKind {
+    Physical,
+    /// Layer 3 tunnels: TUN, WireGuard, PPP, Tailscale, ZeroTier
+    Tunnel,
+    /// Layer 2 TAP devices (OpenVPN bridged mode)
+    Tap,
+    /// Docker/Podman/CNI bridges and veth pairs
+    Container,
+    /// Hypervisor host-only and NAT networks (Hyper-V, VMware, VirtualBox, libvirt, WSL)
+    VmBridge,
+}
+
+impl InterfaceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterfaceKind::Physical => "physical",
+            InterfaceKind::Tunnel => "tunnel",
+            InterfaceKind::Tap => "tap",
+            InterfaceKind::Container => "container",
+            InterfaceKind::VmBridge => "vm_bridge",
+        }
+    }
+}
+
+/// Name prefixes of Unix interfaces, by kind
+const KIND_PREFIXES: &[(&str, InterfaceKind)] = &[
+    ("tun", InterfaceKind::Tunnel),
+    ("utun", InterfaceKind::Tunnel),
+    ("wg", InterfaceKind::Tunnel),
+    ("ppp", InterfaceKind::Tunnel),
+    ("ipsec", InterfaceKind::Tunnel),
+    ("tailscale", InterfaceKind::Tunnel),
+    ("zt", InterfaceKind::Tunnel),
+    ("nordlynx", InterfaceKind::Tunnel),
+    ("tap", InterfaceKind::Tap),
+    ("docker", InterfaceKind::Container),
+    ("br-", InterfaceKind::Container),
+    ("veth", InterfaceKind::Container),
+    ("cni", InterfaceKind::Container),
+    ("flannel", InterfaceKind::Container),
+    ("podman", InterfaceKind::Container),
+    ("virbr", InterfaceKind::VmBridge),
+    ("vmnet", InterfaceKind::VmBridge),
+    ("vboxnet", InterfaceKind::VmBridge),
+];
+
+/// Substrings of Windows adapter names, by kind
+const KIND_SUBSTRINGS: &[(&str, InterfaceKind)] = &[
+    ("wireguard", InterfaceKind::Tunnel),
+    ("wintun", InterfaceKind::Tunnel),
+    ("openvpn", InterfaceKind::Tap),
+    ("tap-windows", InterfaceKind::Tap),
+    ("hyper-v", InterfaceKind::VmBridge),
+    ("vmware", InterfaceKind::VmBridge),
+    ("virtualbox", InterfaceKind::VmBridge),
+    ("vethernet", InterfaceKind::VmBridge),
+    ("wsl", InterfaceKind::VmBridge),
+];
+
+/// Classify an interface by name, falling back to the point-to-point flag
+pub fn classify_interface(name: &str, point_to_point: bool) -> InterfaceKind {
+    let name = name.to_lowercase();
+    if let Some((_, kind)) = KIND_SUBSTRINGS.iter().find(|(pattern, _)| name.contains(pattern)) {
+        return *kind;
+    }
+    if let Some((_, kind)) = KIND_PREFIXES.iter().find(|(prefix, _)| name.starts_with(prefix)) {
+        return *kind;
+    }
+    if point_to_point {
+        InterfaceKind::Tunnel
+    } else {
+        InterfaceKind::Physical
+    }
+}
+
+/// Case-insensitive name match; a trailing `*` matches any suffix
+fn name_matches(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.to_lowercase(), name.to_lowercase());
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+/// Which interfaces `find_interface` may select
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceFilter {
+    /// Name patterns always considered, whatever their kind or prefix length
+    pub include: Vec<String>,
+    /// Name patterns never considered
+    pub exclude: Vec<String>,
+    /// Kinds considered when not explicitly included
+    pub kinds: Vec<InterfaceKind>,
+}
+
+impl Default for InterfaceFilter {
+    fn default() -> Self {
+        Self {
+            include: INTERFACE_INCLUDE.iter().map(|p| p.to_string()).collect(),
+            exclude: INTERFACE_EXCLUDE.iter().map(|p| p.to_string()).collect(),
+            kinds: vec![InterfaceKind::Physical],
+        }
+    }
+}
+
+impl InterfaceFilter {
+    /// Whether an interface with this name, kind and prefix may be scanned
+    ///
+    /// Point-to-point style /31 and /32 prefixes have nothing to sweep and
+    /// are only accepted when explicitly included.
+    pub fn allows(&self, name: &str, kind: InterfaceKind, prefix_len: u8) -> bool {
+        if self.exclude.iter().any(|p| name_matches(p, name)) {
+            return false;
+        }
+        if self.include.iter().any(|p| name_matches(p, name)) {
+            return true;
+        }
+        self.kinds.contains(&kind) && prefix_len < 31
+    }
+}
+
 /// Finds the first valid IPv4 network interface with MAC address
 /// Prefers physical adapters over virtual ones (Hyper-V, VMware, etc.)
 pub fn find_valid_interface() -> Result<InterfaceInfo> {
+    find_interface(&InterfaceFilter::default())
+}
+
+/// Finds the best IPv4 interface allowed by `filter`
+pub fn find_interface(filter: &InterfaceFilter) -> Result<InterfaceInfo> {
     let pnet_interfaces = datalink::interfaces();
 
     log_stderr!("Scanning {} network interfaces...", pnet_interfaces.len());
@@ -35,20 +167,11 @@ pub fn find_valid_interface() -> Result<InterfaceInfo> {
             _ => continue,
         };
 
-        // Skip known virtual adapter patterns (Windows)
-        let name_lower = pnet_if.name.to_lowercase();
-        if name_lower.contains("hyper-v")
-            || name_lower.contains("vmware")
-            || name_lower.contains("virtualbox")
-            || name_lower.contains("docker")
-            || name_lower.contains("vethernet")
-            || name_lower.contains("wsl")
-        {
-            log_stderr!("Skipping virtual adapter: {}", pnet_if.name);
-            continue;
-        }
+        let kind = classify_interface(&pnet_if.name, pnet_if.is_point_to_point());
+        let explicitly_included = filter.include.iter().any(|p| name_matches(p, &pnet_if.name));
 
-        // Find IPv4 addresses
+        // Find IPv4 addresses; every usable one is a candidate, the rest are its aliases
+        let mut addresses: Vec<Ipv4Network> = Vec::new();
         for ip_network in &pnet_if.ips {
             if let IpAddr::V4(ipv4) = ip_network.ip() {
                 // Skip link-local (169.254.x.x)
@@ -56,17 +179,19 @@ pub fn find_valid_interface() -> Result<InterfaceInfo> {
                     continue;
                 }
 
+                let prefix_len = ip_network.prefix();
+                if !filter.allows(&pnet_if.name, kind, prefix_len) {
+                    log_stderr!("Skipping {} adapter: {} ({}/{})", kind.as_str(), pnet_if.name, ipv4, prefix_len);
+                    continue;
+                }
+
                 // Skip Hyper-V/WSL typical ranges (172.16-31.x.x with large subnets)
                 let octets = ipv4.octets();
-                if octets[0] == 172 && octets[1] >= 16 && octets[1] <= 31 {
-                    if ip_network.prefix() <= 20 {
-                        log_stderr!("Skipping virtual subnet: {}/{}", ipv4, ip_network.prefix());
-                        continue;
-                    }
+                if !explicitly_included && octets[0] == 172 && (16..=31).contains(&octets[1]) && prefix_len <= 20 {
+                    log_stderr!("Skipping virtual subnet: {}/{}", ipv4, prefix_len);
+                    continue;
                 }
 
-                let prefix_len = ip_network.prefix() as u8;
-
                 log_stderr!(
                     "Found candidate interface: {} (IP: {}/{}, MAC: {})",
                     pnet_if.name,
@@ -75,15 +200,22 @@ pub fn find_valid_interface() -> Result<InterfaceInfo> {
                     mac
                 );
 
-                candidates.push(InterfaceInfo {
-                    name: pnet_if.name.clone(),
-                    ip: ipv4,
-                    mac,
-                    prefix_len,
-                    pnet_interface: pnet_if.clone(),
-                });
+                if let Ok(address) = Ipv4Network::new(ipv4, prefix_len) {
+                    addresses.push(address);
+                }
             }
         }
+
+        for address in &addresses {
+            candidates.push(InterfaceInfo {
+                name: pnet_if.name.clone(),
+                ip: address.ip(),
+                mac,
+                prefix_len: address.prefix(),
+                aliases: addresses.iter().filter(|a| *a != address).copied().collect(),
+                pnet_interface: pnet_if.clone(),
+            });
+        }
     }
 
     // Sort candidates: prefer 192.168.x.x, then 10.x.x.x, then others
@@ -101,6 +233,9 @@ pub fn find_valid_interface() -> Result<InterfaceInfo> {
             best.prefix_len,
             best.mac
         );
+        for alias in &best.aliases {
+            log_stderr!("  also attached: {}", alias);
+        }
         return Ok(best);
     }
 
@@ -132,3 +267,28 @@ pub fn interface_score(ip: &Ipv4Addr) -> u32 {
         _ => 70,                         // Other private IPs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interface_filter() {
+        assert_eq!(classify_interface("wg0", true), InterfaceKind::Tunnel);
+        assert_eq!(classify_interface("br-3f2a9c", false), InterfaceKind::Container);
+        assert_eq!(classify_interface("vEthernet (WSL)", false), InterfaceKind::VmBridge);
+        assert_eq!(classify_interface("enp3s0", false), InterfaceKind::Physical);
+        assert_eq!(classify_interface("gpd0", true), InterfaceKind::Tunnel);
+
+        let filter = InterfaceFilter {
+            include: vec!["tap-lab*".to_string()],
+            exclude: vec!["eth1".to_string()],
+            kinds: vec![InterfaceKind::Physical],
+        };
+        assert!(filter.allows("eth0", InterfaceKind::Physical, 24));
+        assert!(!filter.allows("eth1", InterfaceKind::Physical, 24));
+        assert!(!filter.allows("eth0", InterfaceKind::Physical, 32));
+        assert!(!filter.allows("wg0", InterfaceKind::Tunnel, 24));
+        assert!(filter.allows("tap-lab0", InterfaceKind::Tap, 32));
+    }
+}
@@ -0,0 +1,167 @@
+//! MAC address type
+//!
+//! MACs arrive as `aa:bb:cc:dd:ee:ff` from pnet, `AA-BB-CC-DD-EE-FF` from
+//! Windows, `aabb.ccdd.eeff` from Cisco CLIs and bare hex from some router
+//! exports. `MacAddress` parses all of them and formats any of them, so
+//! callers compare and print addresses without hand-formatting.
+
+use pnet::util::MacAddr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// Textual notation of a MAC address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacFormat {
+    /// `aa:bb:cc:dd:ee:ff`
+    Colon,
+    /// `aa-bb-cc-dd-ee-ff`
+    Dash,
+    /// `aabb.ccdd.eeff`
+    CiscoDotted,
+    /// `aabbccddeeff`
+    PlainHex,
+}
+
+/// A 48-bit MAC address
+///
+/// `Display` gives lower-case colon notation (`{:X}` for upper case);
+/// serialized as upper-case colon notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    pub const ZERO: MacAddress = MacAddress([0; 6]);
+    pub const BROADCAST: MacAddress = MacAddress([0xff; 6]);
+
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// First three octets (the vendor prefix)
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// Locally administered bit: randomized, virtual or otherwise self-assigned
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Lower-case text in the given notation
+    pub fn format(&self, style: MacFormat) -> String {
+        let hex: Vec<String> = self.0.iter().map(|b| format!("{:02x}", b)).collect();
+        match style {
+            MacFormat::Colon => hex.join(":"),
+            MacFormat::Dash => hex.join("-"),
+            MacFormat::CiscoDotted => hex.chunks(2).map(|pair| pair.concat()).collect::<Vec<_>>().join("."),
+            MacFormat::PlainHex => hex.concat(),
+        }
+    }
+
+    /// Canonical upper-case colon form of a MAC string, or the input unchanged if it is not a MAC
+    pub fn normalize(text: &str) -> String {
+        match text.parse::<MacAddress>() {
+            Ok(mac) => format!("{:X}", mac),
+            Err(_) => text.to_string(),
+        }
+    }
+}
+
+impl FromStr for MacAddress {
+    type Err = anyhow::Error;
+
+    /// Accepts colon, dash, Cisco dotted and plain hex notation, any case
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let digits: String = s.trim().chars().filter(|c| !matches!(c, ':' | '-' | '.')).collect();
+        if digits.len() != 12 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid MAC address '{}'", s));
+        }
+        let separators = s.trim().chars().filter(|c| matches!(c, ':' | '-' | '.')).count();
+        if ![0, 2, 5].contains(&separators) {
+            return Err(anyhow!("Invalid MAC address '{}'", s));
+        }
+        let mut octets = [0u8; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16)?;
+        }
+        Ok(MacAddress(octets))
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(MacFormat::Colon))
+    }
+}
+
+impl fmt::UpperHex for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(MacFormat::Colon).to_uppercase())
+    }
+}
+
+impl From<MacAddr> for MacAddress {
+    fn from(mac: MacAddr) -> Self {
+        MacAddress(mac.octets())
+    }
+}
+
+impl From<MacAddress> for MacAddr {
+    fn from(mac: MacAddress) -> Self {
+        let [a, b, c, d, e, f] = mac.0;
+        MacAddr::new(a, b, c, d, e, f)
+    }
+}
+
+impl Serialize for MacAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:X}", self))
+    }
+}
+
+impl<'de> Deserialize<'de> for MacAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        let expected = MacAddress([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+        for text in ["00:1a:2b:3c:4d:5e", "00-1A-2B-3C-4D-5E", "001a.2b3c.4d5e", "001A2B3C4D5E"] {
+            assert_eq!(text.parse::<MacAddress>().unwrap(), expected, "{}", text);
+        }
+        assert!("00:1a:2b:3c:4d".parse::<MacAddress>().is_err());
+        assert!("00:1a:2b:3c:4d:zz".parse::<MacAddress>().is_err());
+        assert!("00:1a:2b3c:4d:5e".parse::<MacAddress>().is_err());
+
+        assert_eq!(expected.to_string(), "00:1a:2b:3c:4d:5e");
+        assert_eq!(format!("{:X}", expected), "00:1A:2B:3C:4D:5E");
+        assert_eq!(expected.format(MacFormat::Dash), "00-1a-2b-3c-4d-5e");
+        assert_eq!(expected.format(MacFormat::CiscoDotted), "001a.2b3c.4d5e");
+        assert_eq!(expected.format(MacFormat::PlainHex), "001a2b3c4d5e");
+        assert_eq!(MacAddress::normalize("001a.2b3c.4d5e"), "00:1A:2B:3C:4D:5E");
+        assert_eq!(MacAddress::normalize("unknown_10.0.0.5"), "unknown_10.0.0.5");
+
+        let pnet = MacAddr::new(0x02, 0x42, 0xac, 0x11, 0x00, 0x02);
+        let mac = MacAddress::from(pnet);
+        assert!(mac.is_locally_administered() && !mac.is_multicast());
+        assert_eq!(MacAddr::from(mac), pnet);
+        assert_eq!(serde_json::to_string(&mac).unwrap(), "\"02:42:AC:11:00:02\"");
+    }
+}
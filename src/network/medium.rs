@@ -0,0 +1,129 @@
+//! Wired vs wireless classification
+//!
+//! There is no packet-level signal that says "this host is on Wi-Fi", so
+//! the classifier adds up weak evidence: the device type and radio-only
+//! chipset vendors, MAC randomization (done by phones and laptops on
+//! Wi-Fi), and RTT jitter, which is high on a shared radio channel and
+//! near zero on a switch port. Association lists pulled from access
+//! points or a wireless controller override the heuristics.
+
+use std::collections::HashSet;
+
+use crate::config::{WIRED_JITTER_MS, WIRELESS_JITTER_MS};
+use crate::models::{HostInfo, LinkMedium};
+
+/// Score at which the evidence is considered conclusive
+const DECISION_SCORE: i32 = 2;
+
+/// Chipset/module vendors that only ship radios
+const WIRELESS_ONLY_VENDORS: &[&str] = &["espressif", "murata", "azurewave", "tuya", "shenzhen bilian", "liteon"];
+
+/// Device types that are almost always on a radio
+const WIRELESS_TYPES: &[&str] = &["MOBILE", "TABLET"];
+
+/// Device types that are almost always cabled
+const WIRED_TYPES: &[&str] = &["ROUTER", "SWITCH", "FIREWALL", "SERVER", "NAS", "ACCESS_POINT"];
+
+/// Evidence score: positive leans wireless, negative leans wired
+fn medium_score(host: &HostInfo) -> i32 {
+    let mut score = 0;
+
+    if WIRELESS_TYPES.contains(&host.device_type.as_str()) {
+        score += 2;
+    } else if WIRED_TYPES.contains(&host.device_type.as_str()) {
+        score -= 2;
+    }
+
+    if let Some(vendor) = &host.vendor {
+        let vendor = vendor.to_lowercase();
+        if WIRELESS_ONLY_VENDORS.iter().any(|v| vendor.contains(v)) {
+            score += 2;
+        }
+    }
+
+    if host.is_randomized {
+        score += 1;
+    }
+
+    if let Some(stats) = host.latency_stats.as_ref().filter(|s| s.received > 1) {
+        if stats.jitter_ms >= WIRELESS_JITTER_MS {
+            score += 1;
+        } else if stats.jitter_ms <= WIRED_JITTER_MS {
+            score -= 1;
+        }
+    }
+
+    score
+}
+
+/// Classify one host; `None` when the evidence is inconclusive
+///
+/// `associated_macs` holds upper-case MACs reported as associated clients
+/// by access points.
+pub fn classify_link_medium(host: &HostInfo, associated_macs: &HashSet<String>) -> Option<LinkMedium> {
+    if associated_macs.contains(&host.mac.to_uppercase()) {
+        return Some(LinkMedium::Wireless);
+    }
+
+    match medium_score(host) {
+        s if s >= DECISION_SCORE => Some(LinkMedium::Wireless),
+        s if s <= -DECISION_SCORE => Some(LinkMedium::Wired),
+        _ => None,
+    }
+}
+
+/// Set `link_medium` on every host
+pub fn classify_link_media(hosts: &mut [HostInfo], associated_macs: &[String]) {
+    let associated: HashSet<String> = associated_macs.iter().map(|m| m.to_uppercase()).collect();
+    for host in hosts.iter_mut() {
+        host.link_medium = classify_link_medium(host, &associated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LatencyStats;
+
+    fn host(device_type: &str, vendor: Option<&str>, jitter_ms: Option<f64>) -> HostInfo {
+        let mut host = HostInfo {
+            ip: "192.168.1.50".to_string(),
+            mac: "aa:bb:cc:00:00:50".to_string(),
+            vendor: vendor.map(str::to_string),
+            device_type: device_type.to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        };
+        host.latency_stats = jitter_ms.map(|jitter_ms| LatencyStats {
+            sent: 5,
+            received: 5,
+            min_ms: 1.0,
+            avg_ms: 3.0,
+            p95_ms: 9.0,
+            max_ms: 9.0,
+            jitter_ms,
+            loss_percent: 0.0,
+        });
+        host
+    }
+
+    #[test]
+    fn test_heuristic_classification() {
+        let none = HashSet::new();
+        assert_eq!(classify_link_medium(&host("MOBILE", None, None), &none), Some(LinkMedium::Wireless));
+        assert_eq!(
+            classify_link_medium(&host("IOT_DEVICE", Some("Espressif Inc."), None), &none),
+            Some(LinkMedium::Wireless)
+        );
+        assert_eq!(classify_link_medium(&host("NAS", None, Some(0.2)), &none), Some(LinkMedium::Wired));
+        // PC with low jitter is only one point of evidence
+        assert_eq!(classify_link_medium(&host("PC", None, Some(0.2)), &none), None);
+    }
+
+    #[test]
+    fn test_association_overrides() {
+        let mut hosts = vec![host("NAS", None, Some(0.2))];
+        classify_link_media(&mut hosts, &["AA:BB:CC:00:00:50".to_string()]);
+        assert_eq!(hosts[0].link_medium, Some(LinkMedium::Wireless));
+    }
+}
@@ -1,13 +1,47 @@
 //! Network module - interface detection, subnet utilities, DNS resolution, device inference
 
+/// Container network detection
+mod containers;
 mod device;
 mod dns;
+/// IPv4/IPv6 host correlation
+mod dual_stack;
+/// Hostname source precedence
+mod hostname;
+/// Incremental rescans
+mod incremental;
 mod interface;
+/// MAC address type and formatting
+mod mac;
+/// Wired vs wireless classification
+mod medium;
+/// Operating system neighbor caches
+mod neighbors;
+/// Default route lookup
+mod route;
+/// Service identification
+mod services;
 mod subnet;
 mod vendor;
+/// Virtual machine detection
+mod virtualization;
 
+pub use containers::{container_bridges, is_container_mac, separate_containers};
 pub use device::{infer_device_type, calculate_risk_score, DeviceType};
-pub use dns::dns_scan;
-pub use interface::{find_valid_interface, interface_score};
-pub use subnet::{calculate_subnet_ips, is_local_subnet, is_special_address};
-pub use vendor::{lookup_vendor, lookup_vendor_info};
+pub use dns::{dns_scan, parse_resolv_conf, system_nameservers};
+pub use dual_stack::correlate_dual_stack;
+pub use hostname::{resolve_hostnames, HostnameConflict, HostnamePolicy};
+pub use incremental::{is_incomplete, RescanPlan, PREVIOUS_REPORT_ENV};
+pub use interface::{classify_interface, find_interface, find_valid_interface, interface_score, InterfaceFilter, InterfaceKind};
+pub use mac::{MacAddress, MacFormat};
+pub use medium::{classify_link_media, classify_link_medium};
+pub use neighbors::{arp_cache, ipv6_neighbors, parse_bsd_arp, parse_ip_neigh, parse_ndp, Ipv4Neighbor, Ipv6Neighbor};
+pub use route::{default_gateway, parse_proc_net_route};
+pub use services::{
+    add_service_guess, guess_from_banner, guess_from_certificate, guess_from_port, guess_from_sys_descr, identify_services,
+};
+pub use subnet::{calculate_alias_subnet_ips, calculate_subnet_ips, is_local_subnet, is_special_address};
+pub use vendor::{lookup_vendor, lookup_vendor_info, lookup_vendors, VendorInfo};
+pub use virtualization::{
+    classify_virtual_machines, hypervisor_from_mac, hypervisor_host_kind, likely_hypervisor_host, Hypervisor,
+};
@@ -0,0 +1,178 @@
+//! Operating system neighbor cache
+//!
+//! Reads the IPv6 neighbor table (NDP cache) kept by the kernel. Every
+//! host that exchanged IPv6 traffic with this machine shows up there with
+//! its MAC, and routers that sent Router Advertisements carry a router
+//! flag, which shows IPv6 activity without sending any IPv6 probes.
+//!
+//! Linux is read through `ip neigh`, macOS through `ndp -an` and `arp -an`,
+//! which print MACs without leading zeros (`a:bb:c:dd:e:f`).
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::process::Command;
+
+/// One entry of the IPv6 neighbor cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv6Neighbor {
+    pub address: Ipv6Addr,
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+    /// The neighbor has advertised itself as a router
+    pub is_router: bool,
+}
+
+impl Ipv6Neighbor {
+    /// Link-local (fe80::/10) address, present whenever the IPv6 stack is up
+    pub fn is_link_local(&self) -> bool {
+        (self.address.segments()[0] & 0xffc0) == 0xfe80
+    }
+}
+
+/// One entry of the IPv4 ARP cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv4Neighbor {
+    pub address: Ipv4Addr,
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+    /// Interface the entry was learned on
+    pub interface: String,
+}
+
+/// Normalize a BSD-style MAC (`a:bb:c:dd:e:f`) to upper-case colon notation
+fn bsd_mac(mac: &str) -> Option<String> {
+    let octets: Vec<u8> = mac
+        .split(':')
+        .map(|o| if o.len() <= 2 { u8::from_str_radix(o, 16).ok() } else { None })
+        .collect::<Option<_>>()?;
+    if octets.len() != 6 {
+        return None;
+    }
+    let octets: Vec<String> = octets.iter().map(|o| format!("{:02X}", o)).collect();
+    Some(octets.join(":"))
+}
+
+/// Parse macOS `ndp -an` output
+///
+/// Scoped addresses carry the interface (`fe80::1%en0`); the suffix is
+/// dropped. Incomplete entries are skipped, `R` in the flags marks routers.
+pub fn parse_ndp(output: &str) -> Vec<Ipv6Neighbor> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let address: Ipv6Addr = fields.first()?.split('%').next()?.parse().ok()?;
+            // Neighbor, Linklayer Address, Netif, Expire, St, Flgs
+            Some(Ipv6Neighbor {
+                address,
+                mac: bsd_mac(fields.get(1)?)?,
+                is_router: fields.get(5).is_some_and(|flags| flags.contains('R')),
+            })
+        })
+        .collect()
+}
+
+/// Parse BSD/macOS `arp -an` output
+/// (`? (192.168.1.1) at a:bb:c:dd:e:f on en0 ifscope [ethernet]`)
+pub fn parse_bsd_arp(output: &str) -> Vec<Ipv4Neighbor> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let address = fields.get(1)?.trim_start_matches('(').trim_end_matches(')').parse().ok()?;
+            let at = fields.iter().position(|f| *f == "at")?;
+            let on = fields.iter().position(|f| *f == "on")?;
+            Some(Ipv4Neighbor {
+                address,
+                mac: bsd_mac(fields.get(at + 1)?)?,
+                interface: fields.get(on + 1)?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `ip -6 neigh show` output
+///
+/// Entries without a link-layer address (INCOMPLETE/FAILED) are skipped.
+pub fn parse_ip_neigh(output: &str) -> Vec<Ipv6Neighbor> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let address: Ipv6Addr = fields.first()?.parse().ok()?;
+            let mac_index = fields.iter().position(|f| *f == "lladdr")? + 1;
+            Some(Ipv6Neighbor {
+                address,
+                mac: fields.get(mac_index)?.to_uppercase(),
+                is_router: fields.contains(&"router"),
+            })
+        })
+        .collect()
+}
+
+/// Read the IPv6 neighbor cache (empty where unsupported)
+pub fn ipv6_neighbors() -> Vec<Ipv6Neighbor> {
+    if cfg!(target_os = "macos") {
+        return command_output("ndp", &["-an"]).map(|out| parse_ndp(&out)).unwrap_or_default();
+    }
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    command_output("ip", &["-6", "neigh", "show"]).map(|out| parse_ip_neigh(&out)).unwrap_or_default()
+}
+
+/// Read the kernel's IPv4 ARP cache on macOS (empty elsewhere)
+///
+/// Lists hosts this machine exchanged traffic with recently, without
+/// sending anything, so it still works when BPF capture is unavailable.
+pub fn arp_cache() -> Vec<Ipv4Neighbor> {
+    if !cfg!(target_os = "macos") {
+        return Vec::new();
+    }
+    command_output("arp", &["-an"]).map(|out| parse_bsd_arp(&out)).unwrap_or_default()
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => Some(String::from_utf8_lossy(&output.stdout).into_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ip_neigh() {
+        let output = "fe80::1 dev eth0 lladdr aa:bb:cc:dd:ee:01 router REACHABLE\n\
+                      2001:db8::42 dev eth0 lladdr aa:bb:cc:dd:ee:42 STALE\n\
+                      fe80::99 dev eth0 FAILED\n";
+        let neighbors = parse_ip_neigh(output);
+        assert_eq!(neighbors.len(), 2);
+        assert!(neighbors[0].is_router && neighbors[0].is_link_local());
+        assert_eq!(neighbors[1].mac, "AA:BB:CC:DD:EE:42");
+        assert!(!neighbors[1].is_router && !neighbors[1].is_link_local());
+    }
+
+    #[test]
+    fn test_parse_macos_caches() {
+        let ndp = "Neighbor                        Linklayer Address  Netif Expire    St Flgs Prbs\n\
+                   fe80::1%en0                     a:bb:c:dd:e:1      en0 23h59m58s S  R\n\
+                   2001:db8::42                    aa:bb:cc:dd:ee:42  en0 permanent R\n\
+                   fe80::99%en0                    (incomplete)       en0 expired   N\n";
+        let neighbors = parse_ndp(ndp);
+        assert_eq!(neighbors.len(), 2);
+        assert_eq!(neighbors[0].mac, "0A:BB:0C:DD:0E:01");
+        assert!(neighbors[0].is_router && neighbors[0].is_link_local());
+        assert!(!neighbors[1].is_router);
+
+        let arp = "? (192.168.1.1) at a:bb:c:dd:e:f on en0 ifscope [ethernet]\n\
+                   ? (192.168.1.7) at (incomplete) on en0 ifscope [ethernet]\n\
+                   ? (224.0.0.251) at 1:0:5e:0:0:fb on en0 ifscope permanent [ethernet]\n";
+        let entries = parse_bsd_arp(arp);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].address, Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(entries[0].mac, "0A:BB:0C:DD:0E:0F");
+        assert_eq!(entries[0].interface, "en0");
+    }
+}
@@ -0,0 +1,59 @@
+//! Default route lookup
+//!
+//! The default gateway identifies which network the machine is attached
+//! to; it changes when the machine moves networks even if the interface
+//! and its DHCP address happen to stay the same.
+
+use std::net::Ipv4Addr;
+use std::process::Command;
+
+/// Gateway of the first default route in `/proc/net/route`
+///
+/// Addresses in that file are little-endian hex.
+pub fn parse_proc_net_route(table: &str) -> Option<Ipv4Addr> {
+    table.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(1) != Some(&"00000000") {
+            return None;
+        }
+        let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+        (gateway != 0).then(|| Ipv4Addr::from(gateway.swap_bytes()))
+    })
+}
+
+/// Gateway from `route -n get default` (macOS/BSD)
+fn parse_route_get(output: &str) -> Option<Ipv4Addr> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("gateway:"))
+        .and_then(|gateway| gateway.trim().parse().ok())
+}
+
+/// Current IPv4 default gateway, if any
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    if cfg!(target_os = "linux") {
+        return parse_proc_net_route(&std::fs::read_to_string("/proc/net/route").ok()?);
+    }
+    if cfg!(target_os = "macos") {
+        let output = Command::new("route").args(["-n", "get", "default"]).output().ok()?;
+        return parse_route_get(&String::from_utf8_lossy(&output.stdout));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_gateway() {
+        let table = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                     wlan0\t0001A8C0\t00000000\t0001\t0\t0\t600\t00FFFFFF\n\
+                     wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\n";
+        assert_eq!(parse_proc_net_route(table), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_proc_net_route("Iface\tDestination\n"), None);
+
+        let route = "   route to: default\ndestination: default\n    gateway: 10.0.0.1\n  interface: en0\n";
+        assert_eq!(parse_route_get(route), Some(Ipv4Addr::new(10, 0, 0, 1)));
+    }
+}
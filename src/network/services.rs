@@ -0,0 +1,185 @@
+//! Service identification
+//!
+//! A service can be named from a banner it sent, the certificate it
+//! presented, an SNMP sysDescr, or just the port it listens on. These are
+//! not equally trustworthy: port 8080 is "probably HTTP", an `SSH-2.0-`
+//! greeting is SSH. Each guess carries its evidence and a confidence so
+//! insights can tell the two apart.
+
+use crate::models::{HostInfo, ServiceEvidence, ServiceGuess};
+
+/// Conventional service per port
+const WELL_KNOWN_PORTS: &[(u16, &str)] = &[
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (80, "http"),
+    (110, "pop3"),
+    (139, "netbios"),
+    (143, "imap"),
+    (161, "snmp"),
+    (443, "https"),
+    (445, "smb"),
+    (548, "afp"),
+    (631, "ipp"),
+    (1883, "mqtt"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5432, "postgresql"),
+    (5900, "vnc"),
+    (6379, "redis"),
+    (8080, "http"),
+    (8443, "https"),
+    (9100, "jetdirect"),
+];
+
+/// Banner prefixes and the service that sends them
+const BANNER_PREFIXES: &[(&str, &str)] = &[
+    ("SSH-", "ssh"),
+    ("HTTP/", "http"),
+    ("RFB ", "vnc"),
+    ("+OK", "pop3"),
+    ("* OK", "imap"),
+    ("AMQP", "amqp"),
+];
+
+/// Fragments of certificate common names issued by appliances to themselves
+const CERTIFICATE_NAMES: &[(&str, &str)] = &[
+    ("idrac", "Dell iDRAC"),
+    ("ilo", "HPE iLO"),
+    ("unifi", "UniFi controller"),
+    ("synology", "Synology DSM"),
+    ("qnap", "QNAP QTS"),
+    ("fortigate", "FortiGate admin"),
+    ("pfsense", "pfSense admin"),
+    ("vmware", "VMware management"),
+];
+
+/// sysDescr fragments that name the service platform
+const SYS_DESCR_NAMES: &[(&str, &str)] = &[
+    ("routeros", "MikroTik RouterOS"),
+    ("cisco ios", "Cisco IOS"),
+    ("junos", "Juniper Junos"),
+    ("jetdirect", "jetdirect"),
+    ("windows", "Windows SNMP agent"),
+    ("net-snmp", "Net-SNMP agent"),
+];
+
+/// Conventional service on a port; a weak guess
+pub fn guess_from_port(port: u16) -> Option<ServiceGuess> {
+    WELL_KNOWN_PORTS
+        .iter()
+        .find(|(p, _)| *p == port)
+        .map(|(_, name)| ServiceGuess::new(*name, ServiceEvidence::PortHeuristic).on_port(port))
+}
+
+/// Service that sent `banner` on `port`
+///
+/// `220` greetings are shared by FTP and SMTP and are told apart by the
+/// text that follows.
+pub fn guess_from_banner(port: u16, banner: &str) -> Option<ServiceGuess> {
+    let banner = banner.trim_start();
+    let name = if banner.starts_with("220") {
+        let upper = banner.to_uppercase();
+        if upper.contains("SMTP") || upper.contains("POSTFIX") || upper.contains("EXIM") {
+            "smtp"
+        } else if upper.contains("FTP") {
+            "ftp"
+        } else {
+            return None;
+        }
+    } else {
+        BANNER_PREFIXES.iter().find(|(prefix, _)| banner.starts_with(prefix))?.1
+    };
+    let first_line = banner.lines().next().unwrap_or_default().to_string();
+    Some(
+        ServiceGuess::new(name, ServiceEvidence::Banner)
+            .on_port(port)
+            .with_detail(first_line),
+    )
+}
+
+/// Appliance that issued the certificate served on `port`
+pub fn guess_from_certificate(port: u16, common_name: &str) -> Option<ServiceGuess> {
+    let lower = common_name.to_lowercase();
+    let (_, name) = CERTIFICATE_NAMES.iter().find(|(fragment, _)| lower.contains(fragment))?;
+    Some(
+        ServiceGuess::new(*name, ServiceEvidence::TlsCertificate)
+            .on_port(port)
+            .with_detail(common_name.to_string()),
+    )
+}
+
+/// Platform named by an SNMP sysDescr
+pub fn guess_from_sys_descr(sys_descr: &str) -> Option<ServiceGuess> {
+    let lower = sys_descr.to_lowercase();
+    let (_, name) = SYS_DESCR_NAMES.iter().find(|(fragment, _)| lower.contains(fragment))?;
+    Some(ServiceGuess::new(*name, ServiceEvidence::SnmpSysDescr).with_detail(sys_descr.to_string()))
+}
+
+/// Add a guess, keeping only the most confident one per port (or per host
+/// for guesses not tied to a port)
+pub fn add_service_guess(host: &mut HostInfo, guess: ServiceGuess) {
+    match host.service_guesses.iter_mut().find(|g| g.port == guess.port) {
+        Some(existing) if existing.confidence >= guess.confidence => {}
+        Some(existing) => *existing = guess,
+        None => host.service_guesses.push(guess),
+    }
+}
+
+/// Guess services from what every host already reports: open ports and sysDescr
+///
+/// Stronger evidence gathered by probes (banners, certificates) is added
+/// with `add_service_guess` and replaces these. The best guess per port is
+/// recorded on the host's probed `ports`.
+pub fn identify_services(hosts: &mut [HostInfo]) {
+    for host in hosts.iter_mut() {
+        let port_guesses: Vec<ServiceGuess> = host.open_ports.iter().filter_map(|p| guess_from_port(*p)).collect();
+        for guess in port_guesses {
+            add_service_guess(host, guess);
+        }
+        if let Some(guess) = host.system_description.as_deref().and_then(guess_from_sys_descr) {
+            add_service_guess(host, guess);
+        }
+        host.service_guesses.sort_by_key(|g| g.port);
+        for port in host.ports.iter_mut() {
+            port.service = host.service_guesses.iter().find(|g| g.port == Some(port.number)).cloned();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Port, PortState};
+
+    #[test]
+    fn test_banner_outranks_port() {
+        let mut host = HostInfo {
+            device_type: "PC".to_string(),
+            open_ports: vec![22, 2222],
+            ports: vec![Port::tcp(22, PortState::Open)],
+            discovery_method: "ARP".to_string(),
+            system_description: Some("RouterOS RB4011".to_string()),
+            ..Default::default()
+        };
+        identify_services(std::slice::from_mut(&mut host));
+        assert_eq!(host.service_guesses.len(), 2);
+        assert_eq!(host.service_guesses[0].evidence, ServiceEvidence::SnmpSysDescr);
+        assert!(!host.service_guesses[1].is_strong());
+        assert_eq!(host.ports[0].service.as_ref().map(|g| g.service.as_str()), Some("ssh"));
+
+        add_service_guess(&mut host, guess_from_banner(22, "SSH-2.0-OpenSSH_9.6\r\n").unwrap());
+        add_service_guess(&mut host, guess_from_banner(2222, "SSH-2.0-dropbear").unwrap());
+        let ssh = host.service_guesses.iter().find(|g| g.port == Some(22)).unwrap();
+        assert_eq!(ssh.evidence, ServiceEvidence::Banner);
+        assert!(ssh.is_strong());
+        assert_eq!(host.service_guesses.len(), 3);
+
+        assert_eq!(guess_from_banner(25, "220 mail.example ESMTP Postfix").unwrap().service, "smtp");
+        assert!(guess_from_banner(25, "220 welcome").is_none());
+        assert_eq!(guess_from_certificate(443, "idrac-7XK2QM3").unwrap().service, "Dell iDRAC");
+    }
+}
@@ -26,23 +26,37 @@ pub fn is_special_address(ip: Ipv4Addr, subnet: &Ipv4Network) -> bool {
     ip == subnet.network() || ip == subnet.broadcast()
 }
 
-/// Checks if a target IP is in the same subnet as the local interface (L2 reachable)
+/// Checks if a target IP is in one of the local interface's subnets (L2 reachable)
 /// Returns true if target is local (ARP will work), false if remote (needs L3 routing)
 pub fn is_local_subnet(target_ip: Ipv4Addr, local_interface: &InterfaceInfo) -> bool {
-    if let Ok(local_network) = Ipv4Network::new(local_interface.ip, local_interface.prefix_len) {
-        local_network.contains(target_ip)
-    } else {
-        false
-    }
+    local_interface.subnets().iter().any(|subnet| subnet.contains(target_ip))
 }
 
 /// Calculates the subnet range and generates the list of target IPs
 /// Limits to MAX_SCAN_HOSTS to prevent scanning huge subnets
 pub fn calculate_subnet_ips(interface: &InterfaceInfo) -> Result<(Ipv4Network, Vec<Ipv4Addr>)> {
-    let network = Ipv4Network::new(interface.ip, interface.prefix_len)
+    subnet_targets(interface.ip, interface.prefix_len)
+}
+
+/// Target IPs of each alias subnet not already covered by the primary one
+pub fn calculate_alias_subnet_ips(interface: &InterfaceInfo) -> Result<Vec<(Ipv4Network, Vec<Ipv4Addr>)>> {
+    interface
+        .subnets()
+        .into_iter()
+        .skip(1)
+        .map(|subnet| {
+            let local_ip = interface.source_ip_for(subnet.network());
+            subnet_targets(local_ip, subnet.prefix())
+        })
+        .collect()
+}
+
+/// Subnet of `local_ip` and its target IPs, centered on `local_ip` when truncated
+fn subnet_targets(local_ip: Ipv4Addr, prefix_len: u8) -> Result<(Ipv4Network, Vec<Ipv4Addr>)> {
+    let network = Ipv4Network::new(local_ip, prefix_len)
         .context("Failed to create network from interface IP and prefix")?;
 
-    let subnet = Ipv4Network::new(network.network(), interface.prefix_len)
+    let subnet = Ipv4Network::new(network.network(), prefix_len)
         .context("Failed to create subnet network")?;
 
     // Exclude network and broadcast addresses
@@ -60,7 +74,7 @@ pub fn calculate_subnet_ips(interface: &InterfaceInfo) -> Result<(Ipv4Network, V
             MAX_SCAN_HOSTS
         );
         // Take hosts around the local IP for better relevance
-        let local_octets = interface.ip.octets();
+        let local_octets = local_ip.octets();
         let local_last = local_octets[3] as usize;
         
         // Calculate start index to center around local IP
@@ -15,6 +15,7 @@ mod tests {
             ip: ip.parse().unwrap(),
             mac: MacAddr::zero(),
             prefix_len,
+            aliases: Vec::new(),
             pnet_interface: NetworkInterface {
                 name: "eth0".to_string(),
                 description: "Test interface".to_string(),
@@ -66,6 +67,23 @@ mod tests {
         assert!(!is_local_subnet(target, &interface));
     }
 
+    #[test]
+    fn test_alias_subnets() {
+        let mut interface = create_test_interface("192.168.1.10", 24);
+        interface.aliases = vec!["10.0.0.5/29".parse().unwrap(), "192.168.1.20/24".parse().unwrap()];
+
+        assert!(is_local_subnet("10.0.0.3".parse().unwrap(), &interface));
+        assert_eq!(interface.source_ip_for("10.0.0.3".parse().unwrap()), Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(interface.source_ip_for("192.168.1.50".parse().unwrap()), Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(interface.source_ip_for("8.8.8.8".parse().unwrap()), Ipv4Addr::new(192, 168, 1, 10));
+
+        // The second /24 address shares the primary subnet and adds no targets
+        let aliases = calculate_alias_subnet_ips(&interface).unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].0.to_string(), "10.0.0.0/29");
+        assert_eq!(aliases[0].1.len(), 6);
+    }
+
     #[test]
     fn test_calculate_subnet_ips_class_c() {
         let interface = create_test_interface("192.168.1.10", 24);
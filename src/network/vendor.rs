@@ -3,8 +3,13 @@
 //! Uses the macaddress.io database to identify device manufacturers.
 
 use mac_oui::Oui;
+use pnet::util::MacAddr;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::sync::OnceLock;
 
+use super::mac::MacAddress;
+
 /// Global OUI database instance (loaded once)
 static OUI_DB: OnceLock<Option<Oui>> = OnceLock::new();
 
@@ -16,6 +21,7 @@ fn get_oui_db() -> Option<&'static Oui> {
 }
 
 /// Vendor lookup result with randomization detection
+#[derive(Debug, Clone, Default)]
 pub struct VendorInfo {
     pub vendor: Option<String>,
     pub is_randomized: bool,
@@ -81,6 +87,21 @@ pub fn lookup_vendor(mac: &str) -> Option<String> {
     lookup_vendor_info(mac).vendor
 }
 
+/// Look up the vendor of every host in an ARP result
+///
+/// Each distinct MAC is looked up once, however many addresses it answered for.
+pub fn lookup_vendors(hosts: &HashMap<Ipv4Addr, MacAddr>) -> HashMap<Ipv4Addr, VendorInfo> {
+    let mut by_mac: HashMap<MacAddress, VendorInfo> = HashMap::new();
+    hosts
+        .iter()
+        .map(|(ip, mac)| {
+            let mac = MacAddress::from(*mac);
+            let info = by_mac.entry(mac).or_insert_with(|| lookup_vendor_info(&mac.to_string()));
+            (*ip, info.clone())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +118,18 @@ mod tests {
         assert!(!is_locally_administered("00:1C:B3:00:00:00"));
     }
     
+    #[test]
+    fn test_lookup_vendors() {
+        let randomized = MacAddr::new(0x5a, 0x05, 0xd7, 0x51, 0x07, 0x81);
+        let hosts = HashMap::from([
+            (Ipv4Addr::new(192, 168, 1, 10), randomized),
+            (Ipv4Addr::new(192, 168, 1, 11), randomized),
+        ]);
+        let vendors = lookup_vendors(&hosts);
+        assert_eq!(vendors.len(), 2);
+        assert!(vendors.values().all(|v| v.is_randomized));
+    }
+
     #[test]
     fn test_lookup_vendor() {
         let result = lookup_vendor("00:1C:B3:00:00:00");
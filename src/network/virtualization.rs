@@ -0,0 +1,167 @@
+//! Virtual machine and hypervisor detection
+//!
+//! Hypervisors hand their guests MACs from their own OUI blocks, so a
+//! guest is recognised by address alone. Hypervisor hosts have no such
+//! marker and are found by their management ports and hostnames instead;
+//! guests are then grouped under the host that most likely runs them.
+
+use serde::{Deserialize, Serialize};
+
+use super::device::DeviceType;
+use super::mac::MacAddress;
+use crate::models::HostInfo;
+
+/// Virtualization platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hypervisor {
+    Vmware,
+    HyperV,
+    /// QEMU/KVM, including Proxmox and libvirt
+    Qemu,
+    VirtualBox,
+    Xen,
+    Parallels,
+}
+
+impl Hypervisor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Hypervisor::Vmware => "vmware",
+            Hypervisor::HyperV => "hyper_v",
+            Hypervisor::Qemu => "qemu",
+            Hypervisor::VirtualBox => "virtualbox",
+            Hypervisor::Xen => "xen",
+            Hypervisor::Parallels => "parallels",
+        }
+    }
+}
+
+/// OUI prefixes assigned to virtual NICs, by platform
+const VM_OUI_PREFIXES: &[(&str, Hypervisor)] = &[
+    ("00:05:69", Hypervisor::Vmware),
+    ("00:0C:29", Hypervisor::Vmware),
+    ("00:1C:14", Hypervisor::Vmware),
+    ("00:50:56", Hypervisor::Vmware),
+    ("00:15:5D", Hypervisor::HyperV),
+    ("52:54:00", Hypervisor::Qemu),
+    ("08:00:27", Hypervisor::VirtualBox),
+    ("0A:00:27", Hypervisor::VirtualBox),
+    ("00:16:3E", Hypervisor::Xen),
+    ("00:1C:42", Hypervisor::Parallels),
+];
+
+/// Management ports that only hypervisor hosts listen on
+const HYPERVISOR_PORTS: &[(u16, Hypervisor)] = &[
+    (902, Hypervisor::Vmware),   // ESXi/Workstation authd
+    (2179, Hypervisor::HyperV),  // VMConnect
+    (8006, Hypervisor::Qemu),    // Proxmox VE web UI
+    (16509, Hypervisor::Qemu),   // libvirtd TCP
+    (16514, Hypervisor::Qemu),   // libvirtd TLS
+];
+
+/// Hostname fragments of hypervisor hosts
+const HYPERVISOR_HOSTNAMES: &[(&str, Hypervisor)] = &[
+    ("esxi", Hypervisor::Vmware),
+    ("vsphere", Hypervisor::Vmware),
+    ("hyperv", Hypervisor::HyperV),
+    ("hyper-v", Hypervisor::HyperV),
+    ("proxmox", Hypervisor::Qemu),
+    ("pve", Hypervisor::Qemu),
+    ("xcp-ng", Hypervisor::Xen),
+    ("xenserver", Hypervisor::Xen),
+];
+
+/// Platform whose virtual NIC OUI the MAC carries
+pub fn hypervisor_from_mac(mac: &str) -> Option<Hypervisor> {
+    let mac = MacAddress::normalize(mac);
+    VM_OUI_PREFIXES
+        .iter()
+        .find(|(prefix, _)| mac.starts_with(prefix))
+        .map(|(_, hypervisor)| *hypervisor)
+}
+
+/// Platform a host appears to run guests for (management ports, then hostname)
+pub fn hypervisor_host_kind(host: &HostInfo) -> Option<Hypervisor> {
+    let by_port = HYPERVISOR_PORTS
+        .iter()
+        .find(|(port, _)| host.open_ports.contains(port))
+        .map(|(_, hypervisor)| *hypervisor);
+    by_port.or_else(|| {
+        let hostname = host.hostname.as_deref()?.to_lowercase();
+        HYPERVISOR_HOSTNAMES
+            .iter()
+            .find(|(fragment, _)| hostname.contains(fragment))
+            .map(|(_, hypervisor)| *hypervisor)
+    })
+}
+
+/// Label hosts with virtual NIC OUIs as virtual machines
+///
+/// Hosts that look like hypervisors themselves (nested virtualization, or
+/// a management VM such as vCenter) keep their inferred type.
+pub fn classify_virtual_machines(hosts: &mut [HostInfo]) {
+    for host in hosts.iter_mut() {
+        if hypervisor_from_mac(&host.mac).is_some() && hypervisor_host_kind(host).is_none() {
+            host.device_type = DeviceType::VirtualMachine.as_str().to_string();
+        }
+    }
+}
+
+/// Host most likely running `guest`
+///
+/// A hypervisor of the guest's platform wins; failing that, the only
+/// hypervisor on the network. Ambiguous cases are left ungrouped.
+pub fn likely_hypervisor_host<'a>(guest: &HostInfo, hosts: &'a [HostInfo]) -> Option<&'a HostInfo> {
+    let platform = hypervisor_from_mac(&guest.mac)?;
+    let candidates: Vec<(&HostInfo, Hypervisor)> = hosts
+        .iter()
+        .filter(|h| h.identity() != guest.identity())
+        .filter_map(|h| Some((h, hypervisor_host_kind(h)?)))
+        .collect();
+
+    let matching: Vec<&HostInfo> = candidates.iter().filter(|(_, kind)| *kind == platform).map(|(h, _)| *h).collect();
+    match (matching.as_slice(), candidates.as_slice()) {
+        ([host], _) => Some(host),
+        ([], [(host, _)]) => Some(host),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "PC".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_classify_and_group_guests() {
+        let mut esxi = host("192.168.1.5", "AA:00:00:00:00:05");
+        esxi.open_ports = vec![443, 902];
+        let mut proxmox = host("192.168.1.6", "AA:00:00:00:00:06");
+        proxmox.hostname = Some("pve01.lan".to_string());
+        let mut hosts = vec![
+            esxi,
+            proxmox,
+            host("192.168.1.50", "00:50:56:aa:bb:cc"),
+            host("192.168.1.51", "52:54:00:12:34:56"),
+            host("192.168.1.52", "08:00:27:00:00:01"),
+        ];
+        classify_virtual_machines(&mut hosts);
+        assert_eq!(hosts[2].device_type, "VIRTUAL_MACHINE");
+        assert_eq!(hosts[0].device_type, "PC");
+
+        assert_eq!(likely_hypervisor_host(&hosts[2], &hosts).unwrap().ip, "192.168.1.5");
+        assert_eq!(likely_hypervisor_host(&hosts[3], &hosts).unwrap().ip, "192.168.1.6");
+        // VirtualBox guest with two unrelated hypervisors: ambiguous
+        assert!(likely_hypervisor_host(&hosts[4], &hosts).is_none());
+    }
+}
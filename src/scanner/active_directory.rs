@@ -0,0 +1,245 @@
+//! Active Directory environment detection
+//!
+//! Domain controllers are the only hosts that answer Kerberos (88) and LDAP
+//! (389/636) together. Once candidates are found, the domain is confirmed
+//! by asking them for the `_ldap._tcp.dc._msdcs.<domain>` SRV records that
+//! every AD domain publishes, trying the DCs' own name suffixes and the
+//! local search domains as `<domain>`.
+
+use futures::stream::{self, StreamExt};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use super::tcp::{open_ports, probe_host_ports};
+use super::zone_transfer::local_zone_candidates;
+use crate::config::TCP_PROBE_TIMEOUT;
+use crate::models::{ActiveDirectoryInfo, DomainController, HostInfo};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+const KERBEROS_PORT: u16 = 88;
+const LDAP_PORT: u16 = 389;
+const LDAPS_PORT: u16 = 636;
+
+/// DNS port
+const DNS_PORT: u16 = 53;
+
+/// Timeout for each SRV query
+const SRV_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// QTYPE SRV
+const QTYPE_SRV: u16 = 33;
+
+/// DNS header flags: query/response, recursion desired
+const FLAG_QR: u16 = 0x8000;
+const FLAG_RD: u16 = 0x0100;
+
+/// Owner name of the SRV records locating a domain's controllers
+fn dc_locator_name(domain: &str) -> String {
+    format!("_ldap._tcp.dc._msdcs.{}", domain)
+}
+
+/// Build an SRV query for `name`
+fn build_srv_query(id: u16, name: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(12 + name.len() + 6);
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&FLAG_RD.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&QTYPE_SRV.to_be_bytes());
+    packet.extend_from_slice(&[0, 1]); // QCLASS IN
+    packet
+}
+
+/// Read a (possibly compressed) name at `offset`; returns the name and the
+/// offset just past it in the original position
+fn read_name(message: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    // Bound the number of pointer jumps so a looping message cannot hang us
+    for _ in 0..32 {
+        let length = *message.get(offset)? as usize;
+        match length {
+            0 => {
+                return Some((labels.join("."), end.unwrap_or(offset + 1)));
+            }
+            l if l & 0xC0 == 0xC0 => {
+                let pointer = ((l & 0x3F) << 8) | *message.get(offset + 1)? as usize;
+                end.get_or_insert(offset + 2);
+                offset = pointer;
+            }
+            l => {
+                let label = message.get(offset + 1..offset + 1 + l)?;
+                labels.push(String::from_utf8_lossy(label).to_lowercase());
+                offset += 1 + l;
+            }
+        }
+    }
+    None
+}
+
+/// SRV targets in a response to query `id`
+fn parse_srv_reply(id: u16, message: &[u8]) -> Option<Vec<String>> {
+    let header = message.get(..12)?;
+    let flags = u16::from_be_bytes([header[2], header[3]]);
+    if u16::from_be_bytes([header[0], header[1]]) != id || flags & FLAG_QR == 0 || flags & 0x000F != 0 {
+        return None;
+    }
+    let questions = u16::from_be_bytes([header[4], header[5]]);
+    let answers = u16::from_be_bytes([header[6], header[7]]);
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = read_name(message, offset)?.1 + 4;
+    }
+    let mut targets = Vec::new();
+    for _ in 0..answers {
+        offset = read_name(message, offset)?.1;
+        let record = message.get(offset..offset + 10)?;
+        let rtype = u16::from_be_bytes([record[0], record[1]]);
+        let length = u16::from_be_bytes([record[8], record[9]]) as usize;
+        let data = offset + 10;
+        if rtype == QTYPE_SRV && length > 6 {
+            // Priority, weight and port precede the target
+            targets.push(read_name(message, data + 6)?.0);
+        }
+        offset = data + length;
+    }
+    Some(targets)
+}
+
+/// Ask `server` for the domain controllers of `domain`
+async fn query_dc_locator(server: Ipv4Addr, domain: &str, id: u16) -> Option<Vec<String>> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await.ok()?;
+    let target = SocketAddr::from((server, DNS_PORT));
+    socket.send_to(&build_srv_query(id, &dc_locator_name(domain)), target).await.ok()?;
+
+    let mut buf = [0u8; 1500];
+    let deadline = tokio::time::Instant::now() + SRV_QUERY_TIMEOUT;
+    loop {
+        let remaining = deadline.checked_duration_since(tokio::time::Instant::now())?;
+        let (n, from) = timeout(remaining, socket.recv_from(&mut buf)).await.ok()?.ok()?;
+        if from == target {
+            return parse_srv_reply(id, &buf[..n]).filter(|targets| !targets.is_empty());
+        }
+    }
+}
+
+/// Domain a host name belongs to (`dc01.corp.example` -> `corp.example`)
+fn domain_of(hostname: &str) -> Option<String> {
+    let name = hostname.trim_end_matches('.').to_lowercase();
+    name.split_once('.').map(|(_, domain)| domain.to_string())
+}
+
+/// Detect an Active Directory domain among the scanned hosts
+///
+/// Probes every host for Kerberos and LDAP, then confirms the domain via
+/// the DC locator SRV records. Returns `None` when no host looks like a
+/// domain controller.
+pub async fn active_directory_scan(hosts: &[HostInfo]) -> Option<ActiveDirectoryInfo> {
+    let targets: Vec<(Ipv4Addr, Option<String>)> = hosts
+        .iter()
+        .filter_map(|h| Some((h.ip.parse().ok()?, h.hostname.clone())))
+        .collect();
+
+    let mut controllers: Vec<DomainController> = stream::iter(targets)
+        .map(|(ip, hostname)| async move {
+            let open = open_ports(&probe_host_ports(ip, &[KERBEROS_PORT, LDAP_PORT, LDAPS_PORT], TCP_PROBE_TIMEOUT).await);
+            DomainController {
+                ip: ip.to_string(),
+                hostname,
+                kerberos: open.contains(&KERBEROS_PORT),
+                ldap: open.contains(&LDAP_PORT),
+                ldaps: open.contains(&LDAPS_PORT),
+            }
+        })
+        .buffer_unordered(concurrency_limits().tcp_connects)
+        .filter(|dc| std::future::ready(dc.kerberos && (dc.ldap || dc.ldaps)))
+        .collect()
+        .await;
+    if controllers.is_empty() {
+        return None;
+    }
+    controllers.sort_by_key(|dc| dc.ip.parse::<Ipv4Addr>().unwrap_or(Ipv4Addr::UNSPECIFIED));
+    log_stderr!("Active Directory: {} domain controller candidate(s)", controllers.len());
+
+    // The DCs' own suffixes are the likeliest domain names
+    let mut domains: Vec<String> = Vec::new();
+    for domain in controllers
+        .iter()
+        .filter_map(|dc| dc.hostname.as_deref().and_then(domain_of))
+        .chain(local_zone_candidates(hosts))
+    {
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    let mut query_id = std::process::id() as u16;
+    for domain in &domains {
+        for dc in &controllers {
+            let Ok(server) = dc.ip.parse::<Ipv4Addr>() else { continue };
+            query_id = query_id.wrapping_add(1);
+            if let Some(srv_targets) = query_dc_locator(server, domain, query_id).await {
+                log_stderr!("Active Directory: domain {} ({} DC records)", domain, srv_targets.len());
+                return Some(ActiveDirectoryInfo {
+                    domain: Some(domain.clone()),
+                    domain_controllers: controllers,
+                    srv_targets,
+                });
+            }
+        }
+    }
+
+    // Kerberos + LDAP without a locator record: still AD, domain unknown
+    Some(ActiveDirectoryInfo {
+        domain: None,
+        domain_controllers: controllers,
+        srv_targets: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dc_locator_reply() {
+        let query = build_srv_query(0x4242, &dc_locator_name("corp.example"));
+        let mut reply = query.clone();
+        reply[2..4].copy_from_slice(&(FLAG_QR | FLAG_RD | 0x0080).to_be_bytes());
+        reply[7] = 2;
+        for target in [b"\x04dc01".as_slice(), b"\x04dc02".as_slice()] {
+            // Owner name points at the question, target ends with a pointer to "corp.example"
+            reply.extend_from_slice(&[0xC0, 12, 0, 33, 0, 1, 0, 0, 0x02, 0x58]);
+            reply.extend_from_slice(&((6 + target.len() + 2) as u16).to_be_bytes());
+            reply.extend_from_slice(&[0, 0, 0, 100, 0x01, 0x85]);
+            reply.extend_from_slice(target);
+            reply.extend_from_slice(&[0xC0, 12 + 21]);
+        }
+
+        assert_eq!(
+            parse_srv_reply(0x4242, &reply),
+            Some(vec!["dc01.corp.example".to_string(), "dc02.corp.example".to_string()])
+        );
+        assert!(parse_srv_reply(0x4243, &reply).is_none());
+        // NXDOMAIN
+        reply[3] |= 0x03;
+        assert!(parse_srv_reply(0x4242, &reply).is_none());
+
+        assert_eq!(domain_of("DC01.corp.example."), Some("corp.example".to_string()));
+        assert_eq!(domain_of("laptop"), None);
+    }
+}
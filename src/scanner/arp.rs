@@ -3,19 +3,28 @@
 use anyhow::{anyhow, Result};
 use ipnetwork::Ipv4Network;
 use pnet::datalink::{self, Channel};
-use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket};
-use pnet::packet::ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket};
-use pnet::packet::Packet;
+use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, MutableArpPacket};
+use pnet::packet::ethernet::{EtherTypes, MutableEthernetPacket};
 use pnet::util::MacAddr;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::Ipv4Addr;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 use crate::config::{ARP_CHECK_INTERVAL_MS, ARP_IDLE_TIMEOUT_MS, ARP_MAX_WAIT_MS, ARP_ROUNDS};
-use crate::models::InterfaceInfo;
-use crate::network::is_special_address;
+use crate::models::{ArpReplyStats, InterfaceInfo};
+use crate::network::{is_local_subnet, is_special_address};
+use super::audit::{audit_probe, ProbeKind};
+use super::capture::{CaptureFilter, FrameReceiver};
+use super::frame::{parse_arp_frame, ArpOperation};
+#[cfg(windows)]
+use super::ip_helper::send_arp_sweep;
+use super::pacer::{is_send_congestion, shared_pacer, Congestion};
+#[cfg(any(test, feature = "sim"))]
+use super::sim::sim_backend;
+
+/// Comma-separated IPs, ranges (`a-b`) or CIDRs to ARP-scan instead of the whole subnet
+pub const TARGETS_ENV: &str = "HOST_DISCOVERY_TARGETS";
 
 /// Broadcast MAC address for ARP requests
 const BROADCAST_MAC: MacAddr = MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff);
@@ -27,13 +36,27 @@ macro_rules! log_stderr {
     };
 }
 
-/// Creates an ARP request packet
-fn create_arp_request(
-    source_mac: MacAddr,
-    source_ip: Ipv4Addr,
-    target_ip: Ipv4Addr,
-) -> Vec<u8> {
-    let mut buffer = vec![0u8; 42];
+/// Length of an Ethernet + ARP request frame
+const ARP_FRAME_LEN: usize = 42;
+
+/// Offset of the ARP target protocol address within the frame
+const ARP_TARGET_IP_OFFSET: usize = 38;
+
+/// Frames handed to the datalink sender per `build_and_send` call
+///
+/// pnet builds a batch in its write buffer and sends nothing at all when
+/// the batch does not fit, so the pacer's burst is capped by the buffer.
+fn send_batch_size(burst: usize, write_buffer_size: usize) -> usize {
+    burst.min(write_buffer_size / ARP_FRAME_LEN).max(1)
+}
+
+/// Builds an ARP request frame with an unset target IP
+///
+/// Built once per scan and local address; each target only needs
+/// `patch_target_ip`. The Ethernet source is always the interface MAC; the
+/// ARP sender addresses differ from it only in lab mode.
+fn arp_request_template(source_mac: MacAddr, sender_mac: MacAddr, sender_ip: Ipv4Addr) -> [u8; ARP_FRAME_LEN] {
+    let mut buffer = [0u8; ARP_FRAME_LEN];
 
     // Build Ethernet frame
     {
@@ -45,117 +68,376 @@ fn create_arp_request(
 
     // Build ARP packet
     {
-        let mut arp_packet = MutableArpPacket::new(&mut buffer[14..42]).unwrap();
+        let mut arp_packet = MutableArpPacket::new(&mut buffer[14..]).unwrap();
         arp_packet.set_hardware_type(ArpHardwareTypes::Ethernet);
         arp_packet.set_protocol_type(EtherTypes::Ipv4);
         arp_packet.set_hw_addr_len(6);
         arp_packet.set_proto_addr_len(4);
         arp_packet.set_operation(ArpOperations::Request);
-        arp_packet.set_sender_hw_addr(source_mac);
-        arp_packet.set_sender_proto_addr(source_ip);
+        arp_packet.set_sender_hw_addr(sender_mac);
+        arp_packet.set_sender_proto_addr(sender_ip);
         arp_packet.set_target_hw_addr(MacAddr::zero());
-        arp_packet.set_target_proto_addr(target_ip);
+        arp_packet.set_target_proto_addr(Ipv4Addr::UNSPECIFIED);
     }
 
     buffer
 }
 
+/// Writes the target IP into a frame built from the template
+fn patch_target_ip(frame: &mut [u8], target_ip: Ipv4Addr) {
+    frame[ARP_TARGET_IP_OFFSET..ARP_FRAME_LEN].copy_from_slice(&target_ip.octets());
+}
+
+/// Extracts sender IP and MAC from an Ethernet/IPv4 ARP reply
+fn parse_arp_reply(frame: &[u8]) -> Option<(Ipv4Addr, MacAddr)> {
+    parse_arp_frame(frame)
+        .ok()
+        .filter(|arp| arp.operation == ArpOperation::Reply)
+        .map(|arp| (arp.sender_ip, arp.sender_mac))
+}
+
+/// Replies of a sweep, keyed by host
+type ArpReplies = HashMap<Ipv4Addr, (MacAddr, ArpReplyStats)>;
+
+/// Host map without the per-reply statistics
+fn reply_macs(replies: ArpReplies) -> HashMap<Ipv4Addr, MacAddr> {
+    replies.into_iter().map(|(ip, (mac, _))| (ip, mac)).collect()
+}
+
 /// Performs Adaptive ARP scan with early termination
 pub fn active_arp_scan(
     interface: &InterfaceInfo,
     target_ips: &[Ipv4Addr],
     subnet: &Ipv4Network,
 ) -> Result<HashMap<Ipv4Addr, MacAddr>> {
+    arp_scan(interface, target_ips, subnet, interface.mac, None, |_, _| {}).map(reply_macs)
+}
+
+/// Parse a target list such as `192.168.1.10, 192.168.1.100-192.168.1.150, 10.0.0.0/29`
+///
+/// CIDR blocks expand without their network and broadcast addresses.
+pub fn parse_target_list(list: &str) -> Result<Vec<Ipv4Addr>> {
+    let mut targets = Vec::new();
+    for item in list.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        if let Some((start, end)) = item.split_once('-') {
+            let parse = |ip: &str| ip.trim().parse::<Ipv4Addr>().map(u32::from);
+            let (Ok(start), Ok(end)) = (parse(start), parse(end)) else {
+                return Err(anyhow!("Invalid target range '{}'", item));
+            };
+            if start > end {
+                return Err(anyhow!("Target range '{}' ends before it starts", item));
+            }
+            targets.extend((start..=end).map(Ipv4Addr::from));
+        } else if item.contains('/') {
+            let network: Ipv4Network = item.parse().map_err(|e| anyhow!("Invalid target block '{}': {}", item, e))?;
+            targets.extend(network.iter().filter(|ip| network.prefix() >= 31 || !is_special_address(*ip, &network)));
+        } else {
+            targets.push(item.parse().map_err(|_| anyhow!("Invalid target address '{}'", item))?);
+        }
+    }
+    Ok(targets)
+}
+
+/// Deduplicated targets, rejecting any that ARP cannot reach
+///
+/// ARP only works on the local segment, so every target must fall in one
+/// of the interface's subnets; the interface's own addresses are dropped.
+pub fn on_link_targets(interface: &InterfaceInfo, targets: &[Ipv4Addr]) -> Result<Vec<Ipv4Addr>> {
+    let off_link: Vec<String> = targets
+        .iter()
+        .filter(|ip| !is_local_subnet(**ip, interface))
+        .map(|ip| ip.to_string())
+        .collect();
+    if !off_link.is_empty() {
+        return Err(anyhow!(
+            "{} target(s) are not on-link for {} and cannot be ARP-scanned: {}",
+            off_link.len(),
+            interface.name,
+            off_link.join(", ")
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    Ok(targets
+        .iter()
+        .copied()
+        .filter(|ip| *ip != interface.ip && !interface.aliases.iter().any(|a| a.ip() == *ip))
+        .filter(|ip| seen.insert(*ip))
+        .collect())
+}
+
+/// ARP scan of an explicit list of on-link addresses
+///
+/// For a DHCP pool or a handful of known devices, without sweeping the
+/// whole prefix. Fails if any target is off-link; only the listed
+/// addresses are returned.
+pub fn arp_scan_ips(interface: &InterfaceInfo, targets: &[Ipv4Addr]) -> Result<HashMap<Ipv4Addr, MacAddr>> {
+    let targets = on_link_targets(interface, targets)?;
+    let subnet = Ipv4Network::new(interface.ip, interface.prefix_len)?;
+    let mut found = reply_macs(arp_scan(interface, &targets, &subnet, interface.mac, None, |_, _| {})?);
+    let wanted: HashSet<Ipv4Addr> = targets.into_iter().collect();
+    found.retain(|ip, _| wanted.contains(ip));
+    Ok(found)
+}
+
+/// ARP scan that reports each host as soon as its reply arrives
+///
+/// Lets later phases start on early responders while the sweep is still
+/// waiting for the rest. Returns the full result like `active_arp_scan`,
+/// plus how each host answered.
+pub fn streaming_arp_scan(
+    interface: &InterfaceInfo,
+    target_ips: &[Ipv4Addr],
+    subnet: &Ipv4Network,
+    found: tokio::sync::mpsc::UnboundedSender<(Ipv4Addr, MacAddr)>,
+) -> Result<(HashMap<Ipv4Addr, MacAddr>, HashMap<Ipv4Addr, ArpReplyStats>)> {
+    let replies = arp_scan(interface, target_ips, subnet, interface.mac, None, |ip, mac| {
+        let _ = found.send((ip, mac));
+    })?;
+    let stats = replies.iter().map(|(ip, (_, stats))| (*ip, stats.clone())).collect();
+    Ok((reply_macs(replies), stats))
+}
+
+/// Overridden ARP sender addresses for lab mode
+///
+/// Unset fields keep the interface's own address.
+#[cfg(feature = "lab-mode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LabArpSender {
+    pub mac: Option<MacAddr>,
+    pub ip: Option<Ipv4Addr>,
+}
+
+/// ARP scan with spoofed sender hardware/protocol addresses
+///
+/// For testing how your own switches and IDS react to spoofed ARP. Replies
+/// are addressed to the spoofed sender, so hosts may not show up in the
+/// result. Only built with the `lab-mode` feature; never use it on networks
+/// you do not administer.
+#[cfg(feature = "lab-mode")]
+pub fn lab_arp_scan(
+    interface: &InterfaceInfo,
+    target_ips: &[Ipv4Addr],
+    subnet: &Ipv4Network,
+    sender: &LabArpSender,
+) -> Result<HashMap<Ipv4Addr, MacAddr>> {
+    let sender_mac = sender.mac.unwrap_or(interface.mac);
+    let sender_ip = sender.ip.unwrap_or(interface.ip);
+    eprintln!(
+        "[WARN] LAB MODE: ARP requests will claim {} is at {}",
+        sender_ip, sender_mac
+    );
+    arp_scan(interface, target_ips, subnet, sender_mac, Some(sender_ip), |_, _| {}).map(reply_macs)
+}
+
+/// Error for a datalink channel that could not be opened, with install
+/// guidance when capture support is missing
+fn channel_open_error(e: std::io::Error) -> anyhow::Error {
+    #[cfg(target_os = "macos")]
+    if let Some(guidance) = bpf_guidance(&e) {
+        return anyhow!(
+            "Failed to open a BPF device for ARP scanning.\n\n{}\n\nOriginal error: {}",
+            guidance,
+            e
+        );
+    }
+    let error_msg = format!("{}", e);
+    if error_msg.contains("requires")
+        || error_msg.contains("permission")
+        || error_msg.contains("Access")
+        || error_msg.contains("Npcap")
+        || error_msg.contains("WinPcap")
+    {
+        return anyhow!(
+            "Failed to open network interface for ARP scanning.\n\n\
+             On Windows, this requires Npcap to be installed:\n\
+             1. Download from: https://npcap.com/#download\n\
+             2. Install with 'WinPcap API-compatible Mode' checked\n\
+             3. Run this program as Administrator\n\n\
+             Original error: {}",
+            e
+        );
+    }
+    anyhow!("Failed to open datalink channel: {}", e)
+}
+
+/// What to do when macOS refuses a BPF device
+///
+/// pnet tries `/dev/bpf0`, `/dev/bpf1`, ... in turn: permission errors
+/// mean the devices are root-only, EBUSY that every one is taken.
+#[cfg(target_os = "macos")]
+fn bpf_guidance(e: &std::io::Error) -> Option<&'static str> {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        return Some(
+            "The /dev/bpf* capture devices are only accessible to root. Run with sudo, \
+             or install Wireshark's ChmodBPF helper to give the admin group access.",
+        );
+    }
+    if e.raw_os_error() == Some(libc::EBUSY) {
+        return Some(
+            "Every /dev/bpf* capture device is in use (Wireshark, tcpdump, VPN clients). \
+             Close one of them, or add devices with 'sudo sysctl debug.bpf_maxdevices=256'.",
+        );
+    }
+    None
+}
+
+/// ARP sweep of `target_ips` through `SendARP`, one request per host
+#[cfg(windows)]
+fn ip_helper_arp_scan(
+    interface: &InterfaceInfo,
+    target_ips: &[Ipv4Addr],
+    sender_ip: Option<Ipv4Addr>,
+    mut on_host: impl FnMut(Ipv4Addr, MacAddr),
+) -> ArpReplies {
+    let mut discovered: ArpReplies = HashMap::new();
+    send_arp_sweep(
+        target_ips,
+        |target| sender_ip.unwrap_or_else(|| interface.source_ip_for(target)),
+        |ip, mac, latency| {
+            let stats = ArpReplyStats {
+                round: 1,
+                requests: 1,
+                latency_ms: Some(latency.as_secs_f64() * 1000.0),
+            };
+            discovered.insert(ip, (mac, stats));
+            on_host(ip, mac);
+        },
+    );
+    log_stderr!("IP Helper ARP: {} of {} hosts answered", discovered.len(), target_ips.len());
+    discovered
+}
+
+/// ARP sweep of `target_ips`
+///
+/// Each request claims the interface address on the target's subnet (see
+/// `InterfaceInfo::source_ip_for`) unless `sender_ip` overrides it. Replies
+/// are accepted from `subnet` and every other subnet of the interface. Each
+/// reply records the round it arrived in, the requests the host needed and
+/// the time since the latest of them.
+fn arp_scan(
+    interface: &InterfaceInfo,
+    target_ips: &[Ipv4Addr],
+    subnet: &Ipv4Network,
+    sender_mac: MacAddr,
+    sender_ip: Option<Ipv4Addr>,
+    mut on_host: impl FnMut(Ipv4Addr, MacAddr),
+) -> Result<ArpReplies> {
     log_stderr!(
         "Phase 1: Active ARP scanning {} hosts (adaptive timing)...",
         target_ips.len()
     );
 
+    #[cfg(any(test, feature = "sim"))]
+    if let Some(sim) = sim_backend() {
+        return Ok(sim.arp_sweep(target_ips, on_host));
+    }
+
     // Open datalink channel
-    let (mut tx, mut rx) = match datalink::channel(&interface.pnet_interface, Default::default()) {
+    let channel_config = datalink::Config::default();
+    let (mut tx, rx) = match datalink::channel(&interface.pnet_interface, channel_config) {
         Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
         Ok(_) => return Err(anyhow!("Unsupported channel type")),
         Err(e) => {
-            let error_msg = format!("{}", e);
-            if error_msg.contains("requires")
-                || error_msg.contains("permission")
-                || error_msg.contains("Access")
-                || error_msg.contains("Npcap")
-                || error_msg.contains("WinPcap")
-            {
-                return Err(anyhow!(
-                    "Failed to open network interface for ARP scanning.\n\n\
-                     On Windows, this requires Npcap to be installed:\n\
-                     1. Download from: https://npcap.com/#download\n\
-                     2. Install with 'WinPcap API-compatible Mode' checked\n\
-                     3. Run this program as Administrator\n\n\
-                     Original error: {}",
-                    e
-                ));
+            // Without Npcap the OS can still resolve addresses, unless the
+            // requests must claim another sender MAC (lab mode)
+            #[cfg(windows)]
+            if sender_mac == interface.mac {
+                log_stderr!("Raw capture unavailable ({}), resolving through the IP Helper API instead (slower)", e);
+                return Ok(ip_helper_arp_scan(interface, target_ips, sender_ip, on_host));
             }
-            return Err(anyhow!("Failed to open datalink channel: {}", e));
+            return Err(channel_open_error(e));
         }
     };
 
-    let discovered: Arc<std::sync::Mutex<HashMap<Ipv4Addr, MacAddr>>> =
-        Arc::new(std::sync::Mutex::new(HashMap::new()));
-    let host_count = Arc::new(AtomicUsize::new(0));
+    // Replies flow from the receiver thread to this coordinator over a channel,
+    // so the send loop never contends on a lock with the receiver
+    let (reply_tx, reply_rx) = mpsc::channel::<(Ipv4Addr, MacAddr, Instant)>();
+    let mut discovered: ArpReplies = HashMap::new();
+    // Requests sent per target and when the latest went out
+    let mut requests: HashMap<Ipv4Addr, (u8, Instant)> = HashMap::new();
     let scan_start = Instant::now();
+    let mut collect = |discovered: &mut ArpReplies, requests: &HashMap<Ipv4Addr, (u8, Instant)>, round: u8| {
+        for (ip, mac, received) in reply_rx.try_iter() {
+            if discovered.contains_key(&ip) {
+                continue;
+            }
+            let (sent, last_sent) = requests.get(&ip).copied().map_or((0, None), |(n, at)| (n, Some(at)));
+            let stats = ArpReplyStats {
+                round,
+                requests: sent,
+                latency_ms: last_sent.map(|at| received.saturating_duration_since(at).as_secs_f64() * 1000.0),
+            };
+            discovered.insert(ip, (mac, stats));
+            on_host(ip, mac);
+        }
+    };
 
     // Calculate total timeout for receiver thread (all rounds + buffer)
     let total_timeout = Duration::from_millis(ARP_MAX_WAIT_MS * ARP_ROUNDS as u64 + 500);
 
-    let discovered_clone = Arc::clone(&discovered);
-    let host_count_clone = Arc::clone(&host_count);
-    let subnet_clone = subnet.clone();
+    let accepted: Vec<Ipv4Network> = std::iter::once(*subnet).chain(interface.subnets()).collect();
+    let mut rx = FrameReceiver::open(&interface.pnet_interface, CaptureFilter::Arp, rx);
 
     // Start receiver thread
     let receiver_handle = std::thread::spawn(move || {
         let deadline = Instant::now() + total_timeout;
+        let mut seen: HashSet<Ipv4Addr> = HashSet::new();
 
         while Instant::now() < deadline {
-            match rx.next() {
+            match rx.next_frame() {
                 Ok(packet) => {
-                    if let Some(ethernet) = EthernetPacket::new(packet) {
-                        if ethernet.get_ethertype() == EtherTypes::Arp {
-                            if let Some(arp) = ArpPacket::new(ethernet.payload()) {
-                                if arp.get_operation() == ArpOperations::Reply {
-                                    let sender_ip = arp.get_sender_proto_addr();
-                                    let sender_mac = arp.get_sender_hw_addr();
-
-                                    if subnet_clone.contains(sender_ip)
-                                        && !is_special_address(sender_ip, &subnet_clone)
-                                    {
-                                        let mut map = discovered_clone.lock().unwrap();
-                                        if !map.contains_key(&sender_ip) {
-                                            map.insert(sender_ip, sender_mac);
-                                            host_count_clone.fetch_add(1, Ordering::SeqCst);
-                                        }
-                                    }
-                                }
-                            }
+                    // Cheap slice checks drop unrelated traffic before any parsing
+                    if let Some((sender_ip, sender_mac)) = parse_arp_reply(packet) {
+                        if accepted.iter().any(|s| s.contains(sender_ip) && !is_special_address(sender_ip, s))
+                            && seen.insert(sender_ip)
+                            && reply_tx.send((sender_ip, sender_mac, Instant::now())).is_err()
+                        {
+                            // Coordinator is gone, nothing left to report to
+                            return;
                         }
                     }
                 }
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    // Filtered frame or receive timeout, check the deadline again
+                }
                 Err(_) => {
                     std::thread::sleep(Duration::from_micros(50));
                 }
             }
         }
+        if let Some(drops) = rx.drops().filter(|d| *d > 0) {
+            log_stderr!("Capture dropped {} ARP frames, some replies may be missing", drops);
+        }
     });
 
     // Give receiver time to start
     std::thread::sleep(Duration::from_millis(10));
 
+    // One template per local address; each target gets the one on its subnet
+    let sources: Vec<Ipv4Addr> = match sender_ip {
+        Some(ip) => vec![ip],
+        None => std::iter::once(interface.ip).chain(interface.aliases.iter().map(|a| a.ip())).collect(),
+    };
+    let templates: Vec<(Ipv4Addr, [u8; ARP_FRAME_LEN])> = sources
+        .iter()
+        .map(|ip| (*ip, arp_request_template(interface.mac, sender_mac, *ip)))
+        .collect();
+    let template_for = |target: Ipv4Addr| {
+        let source = sender_ip.unwrap_or_else(|| interface.source_ip_for(target));
+        templates.iter().find(|(ip, _)| *ip == source).map_or(&templates[0].1, |(_, template)| template)
+    };
+
     // Adaptive ARP scan rounds
     for round in 1..=ARP_ROUNDS {
         let round_start = Instant::now();
-        let initial_count = host_count.load(Ordering::SeqCst);
+        collect(&mut discovered, &requests, round.saturating_sub(1));
+        let initial_count = discovered.len();
 
         // Get remaining IPs to scan
         let remaining: Vec<Ipv4Addr> = target_ips
             .iter()
-            .filter(|ip| !discovered.lock().unwrap().contains_key(ip))
+            .filter(|ip| !discovered.contains_key(ip))
             .copied()
             .collect();
 
@@ -165,17 +447,52 @@ pub fn active_arp_scan(
         }
 
         log_stderr!(
-            "Round {}/{}: Blasting {} requests ({} already found)...",
+            "Round {}/{}: Sending {} requests ({} already found)...",
             round,
             ARP_ROUNDS,
             remaining.len(),
             initial_count
         );
 
-        // BLAST: Send all requests as fast as possible
-        for target_ip in &remaining {
-            let packet = create_arp_request(interface.mac, interface.ip, *target_ip);
-            let _ = tx.send_to(&packet, None);
+        // Send paced batches written straight into the sender's buffers
+        let pacer = shared_pacer();
+        let batch_size = send_batch_size(pacer.burst(), channel_config.write_buffer_size);
+        for batch in remaining.chunks(batch_size) {
+            pacer.acquire(batch.len() as u32);
+            let mut targets = batch.iter();
+            let sent_at = Instant::now();
+            for target_ip in batch {
+                let entry = requests.entry(*target_ip).or_insert((0, sent_at));
+                *entry = (entry.0.saturating_add(1), sent_at);
+                audit_probe(*target_ip, ProbeKind::Arp, None);
+            }
+            let sent = tx.build_and_send(batch.len(), ARP_FRAME_LEN, &mut |frame| {
+                if let Some(target_ip) = targets.next() {
+                    frame.copy_from_slice(template_for(*target_ip));
+                    patch_target_ip(frame, *target_ip);
+                }
+            });
+            let results = match sent {
+                Some(result) => vec![result],
+                // The batch did not fit the write buffer: send frame by frame
+                None => batch
+                    .iter()
+                    .map(|target_ip| {
+                        let mut frame = *template_for(*target_ip);
+                        patch_target_ip(&mut frame, *target_ip);
+                        tx.send_to(&frame, None).unwrap_or_else(|| {
+                            Err(std::io::Error::other("ARP frame does not fit the datalink write buffer"))
+                        })
+                    })
+                    .collect(),
+            };
+            for e in results.into_iter().filter_map(|result| result.err()) {
+                if is_send_congestion(&e) {
+                    pacer.report(Congestion::SendError);
+                } else {
+                    log_stderr!("ARP send failed: {}", e);
+                }
+            }
         }
 
         // ADAPTIVE WAIT: Check periodically, stop early if idle
@@ -183,13 +500,14 @@ pub fn active_arp_scan(
         let check_interval = Duration::from_millis(ARP_CHECK_INTERVAL_MS);
         let idle_timeout = Duration::from_millis(ARP_IDLE_TIMEOUT_MS);
 
-        let mut last_count = host_count.load(Ordering::SeqCst);
+        let mut last_count = discovered.len();
         let mut last_change = Instant::now();
 
         while round_start.elapsed() < max_wait {
             std::thread::sleep(check_interval);
 
-            let current_count = host_count.load(Ordering::SeqCst);
+            collect(&mut discovered, &requests, round);
+            let current_count = discovered.len();
 
             if current_count > last_count {
                 // New hosts found, reset idle timer
@@ -206,7 +524,7 @@ pub fn active_arp_scan(
             }
         }
 
-        let final_count = host_count.load(Ordering::SeqCst);
+        let final_count = discovered.len();
         log_stderr!(
             "Round {} complete: {} hosts found ({} new) in {:?}",
             round,
@@ -216,19 +534,103 @@ pub fn active_arp_scan(
         );
     }
 
-    // Wait for receiver to finish
+    // Wait for receiver to finish, then collect any late replies
     let _ = receiver_handle.join();
+    collect(&mut discovered, &requests, ARP_ROUNDS);
 
-    let map = discovered.lock().unwrap();
-    for (ip, mac) in map.iter() {
+    for (ip, (mac, _)) in discovered.iter() {
         log_stderr!("[ARP] Found: {} -> {}", ip, mac);
     }
 
     log_stderr!(
         "Phase 1 complete: {} hosts found in {:?}",
-        map.len(),
+        discovered.len(),
         scan_start.elapsed()
     );
 
-    Ok(map.clone())
+    Ok(discovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pnet::packet::arp::ArpPacket;
+    use pnet::packet::ethernet::EthernetPacket;
+    use pnet::packet::Packet;
+
+    #[test]
+    fn test_send_batch_fits_write_buffer() {
+        let buffer = datalink::Config::default().write_buffer_size;
+        assert_eq!(send_batch_size(64, buffer), 64);
+        // Pacing disabled or a large burst: capped, never larger than the buffer
+        let batch = send_batch_size(u32::MAX as usize, buffer);
+        assert_eq!(batch, buffer / ARP_FRAME_LEN);
+        assert!(batch * ARP_FRAME_LEN <= buffer);
+        assert_eq!(send_batch_size(0, buffer), 1);
+    }
+
+    #[test]
+    fn test_template_patching() {
+        let mac = MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+        let mut frame = arp_request_template(mac, mac, Ipv4Addr::new(192, 168, 1, 10));
+        patch_target_ip(&mut frame, Ipv4Addr::new(192, 168, 1, 77));
+
+        let ethernet = EthernetPacket::new(&frame).unwrap();
+        assert_eq!(ethernet.get_destination(), BROADCAST_MAC);
+        assert_eq!(ethernet.get_ethertype(), EtherTypes::Arp);
+
+        let arp = ArpPacket::new(ethernet.payload()).unwrap();
+        assert_eq!(arp.get_operation(), ArpOperations::Request);
+        assert_eq!(arp.get_sender_hw_addr(), mac);
+        assert_eq!(arp.get_sender_proto_addr(), Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(arp.get_target_proto_addr(), Ipv4Addr::new(192, 168, 1, 77));
+    }
+
+    #[test]
+    fn test_template_with_overridden_sender() {
+        let own = MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+        let spoofed = MacAddr(0x02, 0xde, 0xad, 0xbe, 0xef, 0x01);
+        let frame = arp_request_template(own, spoofed, Ipv4Addr::new(192, 168, 1, 1));
+
+        let ethernet = EthernetPacket::new(&frame).unwrap();
+        assert_eq!(ethernet.get_source(), own);
+        let arp = ArpPacket::new(ethernet.payload()).unwrap();
+        assert_eq!(arp.get_sender_hw_addr(), spoofed);
+        assert_eq!(arp.get_sender_proto_addr(), Ipv4Addr::new(192, 168, 1, 1));
+    }
+
+    #[test]
+    fn test_parse_arp_reply() {
+        let mac = MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+        let mut frame = arp_request_template(mac, mac, Ipv4Addr::new(192, 168, 1, 10));
+
+        // Requests are ignored
+        assert_eq!(parse_arp_reply(&frame), None);
+
+        frame[21] = 2;
+        assert_eq!(parse_arp_reply(&frame), Some((Ipv4Addr::new(192, 168, 1, 10), mac)));
+
+        // Non-ARP ethertype and truncated frames are rejected
+        frame[12..14].copy_from_slice(&[0x08, 0x00]);
+        assert_eq!(parse_arp_reply(&frame), None);
+        assert_eq!(parse_arp_reply(&frame[..20]), None);
+    }
+
+    #[test]
+    fn test_parse_target_list() {
+        let targets = parse_target_list("192.168.1.10, 192.168.1.20-192.168.1.22,10.0.0.0/30").unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                Ipv4Addr::new(192, 168, 1, 10),
+                Ipv4Addr::new(192, 168, 1, 20),
+                Ipv4Addr::new(192, 168, 1, 21),
+                Ipv4Addr::new(192, 168, 1, 22),
+                Ipv4Addr::new(10, 0, 0, 1),
+                Ipv4Addr::new(10, 0, 0, 2),
+            ]
+        );
+        assert!(parse_target_list("192.168.1.30-192.168.1.20").is_err());
+        assert!(parse_target_list("printer").is_err());
+    }
 }
@@ -0,0 +1,173 @@
+//! Probe audit trail
+//!
+//! When enabled, every probe the scanner sends is appended to a JSON-lines
+//! file as it leaves: when, to which address, what kind and which port.
+//! The file is opened in append mode and never rewritten, so it can answer
+//! "did the scanner touch 10.0.0.12 at 14:32?" long after the scan, across
+//! any number of runs.
+//!
+//! Recording happens at the shared send paths (ARP batches, echo requests,
+//! TCP connects, raw TCP and IP protocol sends, UDP service queries and
+//! traceroute hops). A scan without an audit log pays nothing.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[WARN] {}", format!($($arg)*));
+    };
+}
+
+/// Appends a probe audit trail to this file for the CLI
+pub const AUDIT_LOG_ENV: &str = "HOST_DISCOVERY_AUDIT_LOG";
+
+/// What kind of probe was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeKind {
+    Arp,
+    Icmp,
+    TcpConnect,
+    TcpRaw,
+    IpProtocol,
+    Udp,
+}
+
+impl ProbeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProbeKind::Arp => "arp",
+            ProbeKind::Icmp => "icmp",
+            ProbeKind::TcpConnect => "tcp_connect",
+            ProbeKind::TcpRaw => "tcp_raw",
+            ProbeKind::IpProtocol => "ip_protocol",
+            ProbeKind::Udp => "udp",
+        }
+    }
+}
+
+/// One line of the audit trail
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub target: IpAddr,
+    pub kind: ProbeKind,
+    /// Destination port, or the protocol number of IP protocol probes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+/// Append-only audit file
+struct AuditLog {
+    file: Mutex<File>,
+    /// A write already failed and was reported
+    failed: AtomicBool,
+}
+
+impl AuditLog {
+    fn record(&self, record: &AuditRecord) {
+        let Ok(mut line) = serde_json::to_string(record) else { return };
+        line.push('\n');
+        // One write per line: O_APPEND keeps concurrent lines whole
+        let written = match self.file.lock() {
+            Ok(mut file) => file.write_all(line.as_bytes()),
+            Err(_) => return,
+        };
+        if let Err(e) = written {
+            if !self.failed.swap(true, Ordering::Relaxed) {
+                log_stderr!("Audit log write failed, probes are no longer recorded: {}", e);
+            }
+        }
+    }
+}
+
+static AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+
+/// Start appending every probe sent from now on to `path`
+///
+/// Returns `false` if an audit log was already open; the first one stays.
+pub fn init_audit_log(path: impl AsRef<Path>) -> Result<bool> {
+    let path = path.as_ref();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open audit log {}: {}", path.display(), e))?;
+    Ok(AUDIT_LOG
+        .set(AuditLog {
+            file: Mutex::new(file),
+            failed: AtomicBool::new(false),
+        })
+        .is_ok())
+}
+
+/// Record a probe to `target`; does nothing unless an audit log is open
+pub(super) fn audit_probe(target: impl Into<IpAddr>, kind: ProbeKind, port: Option<u16>) {
+    if let Some(log) = AUDIT_LOG.get() {
+        log.record(&AuditRecord {
+            timestamp: Utc::now(),
+            target: target.into(),
+            kind,
+            port,
+        });
+    }
+}
+
+/// Read an audit trail back, skipping lines that do not parse
+/// (a line cut short by a crash, for instance)
+pub fn read_audit_log(path: impl AsRef<Path>) -> Result<Vec<AuditRecord>> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open audit log {}: {}", path.display(), e))?;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(record) = serde_json::from_str(&line?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Probes sent to `target` between `from` and `to` (inclusive)
+pub fn probes_to(records: &[AuditRecord], target: IpAddr, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<&AuditRecord> {
+    records
+        .iter()
+        .filter(|r| r.target == target && r.timestamp >= from && r.timestamp <= to)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_audit_record_round_trip_and_query() {
+        let at = |minute: u32| Utc.with_ymd_and_hms(2024, 5, 2, 14, minute, 0).unwrap();
+        let record = AuditRecord {
+            timestamp: at(32),
+            target: "10.0.0.12".parse().unwrap(),
+            kind: ProbeKind::TcpConnect,
+            port: Some(443),
+        };
+        let line = serde_json::to_string(&record).unwrap();
+        assert_eq!(line, r#"{"timestamp":"2024-05-02T14:32:00Z","target":"10.0.0.12","kind":"tcp_connect","port":443}"#);
+        assert_eq!(serde_json::from_str::<AuditRecord>(&line).unwrap(), record);
+
+        let records = vec![
+            record.clone(),
+            AuditRecord { timestamp: at(40), port: None, kind: ProbeKind::Icmp, ..record.clone() },
+            AuditRecord { target: "10.0.0.13".parse().unwrap(), ..record.clone() },
+        ];
+        let touched = probes_to(&records, "10.0.0.12".parse().unwrap(), at(30), at(35));
+        assert_eq!(touched, vec![&records[0]]);
+    }
+}
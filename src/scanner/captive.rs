@@ -0,0 +1,155 @@
+//! Captive portal detection
+//!
+//! Fetches a well-known "no content" URL over plain HTTP, the same check
+//! operating systems use. A 204 means traffic reaches the internet
+//! untouched; a redirect or a login page in its place means a captive
+//! portal is intercepting requests. Without this, a network waiting for a
+//! portal login looks like one where every upstream check fails.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::config::{CAPTIVE_PORTAL_CHECK_HOST, CAPTIVE_PORTAL_CHECK_PATH};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Timeout for connecting and reading the check response
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Internet connectivity as seen through the check URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectivityState {
+    /// The check URL answered 204 No Content
+    Online,
+    /// Something else answered in its place (redirect or login page)
+    CaptivePortal,
+    /// The check URL could not be reached at all
+    Offline,
+}
+
+impl ConnectivityState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectivityState::Online => "online",
+            ConnectivityState::CaptivePortal => "captive_portal",
+            ConnectivityState::Offline => "offline",
+        }
+    }
+}
+
+/// Result of the captive portal check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectivityCheck {
+    pub state: ConnectivityState,
+    /// HTTP status of the response, if any
+    pub status_code: Option<u16>,
+    /// Portal login URL from the Location header, if redirected
+    pub portal_url: Option<String>,
+}
+
+impl ConnectivityCheck {
+    fn offline() -> Self {
+        Self {
+            state: ConnectivityState::Offline,
+            status_code: None,
+            portal_url: None,
+        }
+    }
+
+    /// Classify the raw HTTP response to the check request
+    fn from_response(response: &str) -> Self {
+        let mut lines = response.lines();
+        let status_code = lines
+            .next()
+            .filter(|l| l.starts_with("HTTP/"))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok());
+        let Some(code) = status_code else {
+            return Self::offline();
+        };
+
+        let portal_url = lines
+            .take_while(|l| !l.is_empty())
+            .find_map(|l| {
+                let (name, value) = l.split_once(':')?;
+                name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+            });
+
+        Self {
+            state: if code == 204 { ConnectivityState::Online } else { ConnectivityState::CaptivePortal },
+            status_code: Some(code),
+            portal_url,
+        }
+    }
+}
+
+/// Check whether internet access is intercepted by a captive portal
+pub async fn captive_portal_check() -> ConnectivityCheck {
+    let target = format!("{}:80", CAPTIVE_PORTAL_CHECK_HOST);
+    let mut stream = match timeout(CHECK_TIMEOUT, TcpStream::connect(&target)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return ConnectivityCheck::offline(),
+    };
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: NetworkTopologyMapper\r\nConnection: close\r\n\r\n",
+        CAPTIVE_PORTAL_CHECK_PATH, CAPTIVE_PORTAL_CHECK_HOST
+    );
+    if stream.write_all(request.as_bytes()).await.is_err() {
+        return ConnectivityCheck::offline();
+    }
+
+    // Headers are enough; portals can serve large login pages
+    let mut buf = vec![0u8; 4096];
+    let n = match timeout(CHECK_TIMEOUT, stream.read(&mut buf)).await {
+        Ok(Ok(n)) => n,
+        _ => return ConnectivityCheck::offline(),
+    };
+
+    let check = ConnectivityCheck::from_response(&String::from_utf8_lossy(&buf[..n]));
+    if check.state == ConnectivityState::CaptivePortal {
+        log_stderr!(
+            "Captive portal detected (HTTP {}{})",
+            check.status_code.unwrap_or_default(),
+            check.portal_url.as_deref().map(|u| format!(" -> {}", u)).unwrap_or_default()
+        );
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_online_response() {
+        let check = ConnectivityCheck::from_response("HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(check.state, ConnectivityState::Online);
+        assert_eq!(check.status_code, Some(204));
+    }
+
+    #[test]
+    fn test_portal_responses() {
+        let redirect = ConnectivityCheck::from_response(
+            "HTTP/1.1 302 Found\r\nlocation: http://portal.hotel.example/login?next=/\r\n\r\n",
+        );
+        assert_eq!(redirect.state, ConnectivityState::CaptivePortal);
+        assert_eq!(redirect.portal_url.as_deref(), Some("http://portal.hotel.example/login?next=/"));
+
+        // Login page served in place of the 204
+        let page = ConnectivityCheck::from_response("HTTP/1.1 200 OK\r\n\r\n<html>Accept terms</html>");
+        assert_eq!(page.state, ConnectivityState::CaptivePortal);
+        assert!(page.portal_url.is_none());
+
+        assert_eq!(ConnectivityCheck::from_response("garbage").state, ConnectivityState::Offline);
+    }
+}
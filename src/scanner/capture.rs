@@ -0,0 +1,631 @@
+//! Kernel-filtered frame capture
+//!
+//! Attaches a classic BPF program to the capture socket so the kernel drops
+//! traffic unrelated to the current phase (ARP, ICMPv6, DHCP, STP, broadcast) instead of waking the
+//! receiver thread for every frame. Where a kernel filter cannot be attached
+//! the same program is evaluated in userspace on the pnet receiver.
+//!
+//! On Linux the filtered socket also gets a TPACKET_V3 receive ring: the
+//! kernel writes matching frames straight into memory shared with the
+//! receiver, in blocks, so bursts of replies on a busy 10GbE link are
+//! absorbed by the ring instead of overflowing a socket buffer drained one
+//! `recv` at a time.
+//!
+//! AF_XDP and loaded eBPF programs are not used: they need driver support,
+//! CAP_BPF and a program loader, while classic filters attach on any kernel
+//! (which translates them to eBPF internally).
+
+use pnet::datalink::{DataLinkReceiver, NetworkInterface};
+use std::io;
+
+#[cfg(target_os = "linux")]
+use crate::config::CAPTURE_RING_ENABLED;
+
+/// Snapshot length returned by accepting filter programs
+const SNAP_LEN: u32 = 0x0004_0000;
+
+/// A classic BPF instruction (layout of `struct sock_filter`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BpfInsn {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
+const fn insn(code: u16, jt: u8, jf: u8, k: u32) -> BpfInsn {
+    BpfInsn { code, jt, jf, k }
+}
+
+// Opcodes used by the programs below
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_LD_H_ABS: u16 = 0x28;
+const BPF_LD_B_ABS: u16 = 0x30;
+const BPF_LD_H_IND: u16 = 0x48;
+const BPF_LDX_B_MSH: u16 = 0xb1;
+const BPF_JEQ_K: u16 = 0x15;
+const BPF_JSET_K: u16 = 0x45;
+const BPF_RET_K: u16 = 0x06;
+
+/// `arp`
+const ARP_PROGRAM: &[BpfInsn] = &[
+    insn(BPF_LD_H_ABS, 0, 0, 12),
+    insn(BPF_JEQ_K, 0, 1, 0x0806),
+    insn(BPF_RET_K, 0, 0, SNAP_LEN),
+    insn(BPF_RET_K, 0, 0, 0),
+];
+
+/// `icmp6` (including a single IPv6 fragment header)
+const ICMPV6_PROGRAM: &[BpfInsn] = &[
+    insn(BPF_LD_H_ABS, 0, 0, 12),
+    insn(BPF_JEQ_K, 0, 6, 0x86dd),
+    insn(BPF_LD_B_ABS, 0, 0, 20),
+    insn(BPF_JEQ_K, 3, 0, 58),
+    insn(BPF_JEQ_K, 0, 3, 44),
+    insn(BPF_LD_B_ABS, 0, 0, 54),
+    insn(BPF_JEQ_K, 0, 1, 58),
+    insn(BPF_RET_K, 0, 0, SNAP_LEN),
+    insn(BPF_RET_K, 0, 0, 0),
+];
+
+/// `udp and (dst port 67 or dst port 68)` over IPv4
+const DHCP_PROGRAM: &[BpfInsn] = &[
+    insn(BPF_LD_H_ABS, 0, 0, 12),
+    insn(BPF_JEQ_K, 0, 7, 0x0800),
+    insn(BPF_LD_B_ABS, 0, 0, 23),
+    insn(BPF_JEQ_K, 0, 5, 17),
+    insn(BPF_LDX_B_MSH, 0, 0, 14),
+    insn(BPF_LD_H_IND, 0, 0, 16),
+    insn(BPF_JEQ_K, 1, 0, 67),
+    insn(BPF_JEQ_K, 0, 1, 68),
+    insn(BPF_RET_K, 0, 0, SNAP_LEN),
+    insn(BPF_RET_K, 0, 0, 0),
+];
+
+/// `ether dst 01:80:c2:00:00:00` (STP/RSTP/MSTP BPDUs)
+const STP_PROGRAM: &[BpfInsn] = &[
+    insn(BPF_LD_W_ABS, 0, 0, 0),
+    insn(BPF_JEQ_K, 0, 3, 0x0180_c200),
+    insn(BPF_LD_H_ABS, 0, 0, 4),
+    insn(BPF_JEQ_K, 0, 1, 0x0000),
+    insn(BPF_RET_K, 0, 0, SNAP_LEN),
+    insn(BPF_RET_K, 0, 0, 0),
+];
+
+/// `ether multicast` (group bit of the destination MAC, includes broadcast)
+const MULTICAST_PROGRAM: &[BpfInsn] = &[
+    insn(BPF_LD_B_ABS, 0, 0, 0),
+    insn(BPF_JSET_K, 0, 1, 0x01),
+    insn(BPF_RET_K, 0, 0, SNAP_LEN),
+    insn(BPF_RET_K, 0, 0, 0),
+];
+
+/// Traffic a capture phase is interested in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFilter {
+    Arp,
+    Icmpv6,
+    Dhcp,
+    Stp,
+    Multicast,
+}
+
+impl CaptureFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureFilter::Arp => "arp",
+            CaptureFilter::Icmpv6 => "icmp6",
+            CaptureFilter::Dhcp => "dhcp",
+            CaptureFilter::Stp => "stp",
+            CaptureFilter::Multicast => "multicast",
+        }
+    }
+
+    /// Classic BPF program implementing the filter
+    pub fn program(&self) -> &'static [BpfInsn] {
+        match self {
+            CaptureFilter::Arp => ARP_PROGRAM,
+            CaptureFilter::Icmpv6 => ICMPV6_PROGRAM,
+            CaptureFilter::Dhcp => DHCP_PROGRAM,
+            CaptureFilter::Stp => STP_PROGRAM,
+            CaptureFilter::Multicast => MULTICAST_PROGRAM,
+        }
+    }
+
+    /// Evaluate the program in userspace (fallback when no kernel filter is attached)
+    pub fn matches(&self, frame: &[u8]) -> bool {
+        run_program(self.program(), frame) > 0
+    }
+}
+
+/// Minimal interpreter for the instructions used by the built-in programs
+fn run_program(program: &[BpfInsn], frame: &[u8]) -> u32 {
+    let mut acc: u32 = 0;
+    let mut x: usize = 0;
+    let mut pc = 0usize;
+
+    while let Some(i) = program.get(pc) {
+        let k = i.k as usize;
+        match i.code {
+            BPF_LD_W_ABS => match frame.get(k..k + 4) {
+                Some(b) => acc = u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+                None => return 0,
+            },
+            BPF_LD_H_ABS => match frame.get(k..k + 2) {
+                Some(b) => acc = u16::from_be_bytes([b[0], b[1]]) as u32,
+                None => return 0,
+            },
+            BPF_LD_B_ABS => match frame.get(k) {
+                Some(b) => acc = *b as u32,
+                None => return 0,
+            },
+            BPF_LD_H_IND => match frame.get(x + k..x + k + 2) {
+                Some(b) => acc = u16::from_be_bytes([b[0], b[1]]) as u32,
+                None => return 0,
+            },
+            BPF_LDX_B_MSH => match frame.get(k) {
+                Some(b) => x = ((*b & 0x0f) as usize) * 4,
+                None => return 0,
+            },
+            BPF_JEQ_K => {
+                pc += if acc == i.k { i.jt as usize } else { i.jf as usize };
+            }
+            BPF_JSET_K => {
+                pc += if acc & i.k != 0 { i.jt as usize } else { i.jf as usize };
+            }
+            BPF_RET_K => return i.k,
+            _ => return 0,
+        }
+        pc += 1;
+    }
+
+    0
+}
+
+/// Frame source for a capture phase
+pub enum FrameReceiver {
+    /// Memory-mapped AF_PACKET ring with the filter attached in the kernel
+    #[cfg(target_os = "linux")]
+    Ring(linux::RingSocket),
+    /// AF_PACKET socket with the filter attached in the kernel
+    #[cfg(target_os = "linux")]
+    Kernel(linux::FilteredSocket, Vec<u8>),
+    /// pnet receiver with the filter evaluated per frame
+    Userspace(Box<dyn DataLinkReceiver>, CaptureFilter),
+}
+
+impl FrameReceiver {
+    /// Open a kernel-filtered receiver (ring-backed where possible), falling
+    /// back to filtering `fallback` in userspace
+    pub fn open(
+        interface: &NetworkInterface,
+        filter: CaptureFilter,
+        fallback: Box<dyn DataLinkReceiver>,
+    ) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            if CAPTURE_RING_ENABLED {
+                match linux::RingSocket::open(interface.index, filter) {
+                    Ok(ring) => return FrameReceiver::Ring(ring),
+                    Err(e) => tracing::debug!("Capture ring unavailable ({}), using a plain {} socket", e, filter.as_str()),
+                }
+            }
+            match linux::FilteredSocket::open(interface.index, filter) {
+                Ok(socket) => return FrameReceiver::Kernel(socket, vec![0u8; 65536]),
+                Err(e) => tracing::debug!("Kernel {} filter unavailable ({}), filtering in userspace", filter.as_str(), e),
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = interface;
+
+        FrameReceiver::Userspace(fallback, filter)
+    }
+
+    /// Next frame matching the filter
+    ///
+    /// Returns an error on timeout so callers can check their deadline.
+    pub fn next_frame(&mut self) -> io::Result<&[u8]> {
+        match self {
+            #[cfg(target_os = "linux")]
+            FrameReceiver::Ring(ring) => ring.next_frame(),
+            #[cfg(target_os = "linux")]
+            FrameReceiver::Kernel(socket, buf) => {
+                let n = socket.recv(buf)?;
+                Ok(&buf[..n])
+            }
+            FrameReceiver::Userspace(rx, filter) => {
+                let frame = rx.next()?;
+                if filter.matches(frame) {
+                    Ok(frame)
+                } else {
+                    Err(io::Error::new(io::ErrorKind::WouldBlock, "frame filtered"))
+                }
+            }
+        }
+    }
+
+    /// Matching frames the kernel dropped since the last call, where it
+    /// keeps count (AF_PACKET sockets)
+    pub fn drops(&self) -> Option<u32> {
+        match self {
+            #[cfg(target_os = "linux")]
+            FrameReceiver::Ring(ring) => linux::packet_drops(ring.fd()),
+            #[cfg(target_os = "linux")]
+            FrameReceiver::Kernel(socket, _) => linux::packet_drops(socket.fd()),
+            FrameReceiver::Userspace(..) => None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::CaptureFilter;
+    use crate::config::CAPTURE_RING_MB;
+    use std::io;
+    use std::ops::Range;
+    use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+    use std::sync::atomic::{fence, Ordering};
+
+    /// Receive timeout so the receiver loop can observe its deadline
+    const RECV_TIMEOUT_US: libc::suseconds_t = 50_000;
+
+    // AF_PACKET socket options and ring constants (linux/if_packet.h)
+    const PACKET_RX_RING: libc::c_int = 5;
+    const PACKET_STATISTICS: libc::c_int = 6;
+    const PACKET_VERSION: libc::c_int = 10;
+    const TPACKET_V3: libc::c_int = 2;
+    const TP_STATUS_KERNEL: u32 = 0;
+    const TP_STATUS_USER: u32 = 1;
+
+    /// Ring block size; frames are packed into blocks the kernel hands over whole
+    const RING_BLOCK_SIZE: usize = 1 << 20;
+
+    /// Nominal frame slot size (TPACKET_V3 packs frames, this only sizes the request)
+    const RING_FRAME_SIZE: usize = 2048;
+
+    /// A partly filled block is handed over after this long, so quiet
+    /// captures still see their frames promptly (and reply latencies stay honest)
+    const RING_BLOCK_TIMEOUT_MS: u32 = 2;
+
+    /// Offsets in `struct tpacket_block_desc`
+    const BLOCK_STATUS: usize = 8;
+    const BLOCK_NUM_PKTS: usize = 12;
+    const BLOCK_FIRST_PKT: usize = 16;
+
+    /// `struct tpacket_req3`
+    #[repr(C)]
+    struct TpacketReq3 {
+        block_size: u32,
+        block_nr: u32,
+        frame_size: u32,
+        frame_nr: u32,
+        retire_blk_tov: u32,
+        sizeof_priv: u32,
+        feature_req_word: u32,
+    }
+
+    /// Raw AF_PACKET socket bound to one interface with a BPF program attached
+    pub struct FilteredSocket {
+        fd: OwnedFd,
+    }
+
+    impl FilteredSocket {
+        pub fn open(ifindex: u32, filter: CaptureFilter) -> io::Result<Self> {
+            let fd = filtered_socket(filter)?;
+            let timeout = libc::timeval { tv_sec: 0, tv_usec: RECV_TIMEOUT_US };
+            set_option(&fd, libc::SOL_SOCKET, libc::SO_RCVTIMEO, &timeout)?;
+            bind(&fd, ifindex)?;
+            Ok(Self { fd })
+        }
+
+        pub fn fd(&self) -> BorrowedFd<'_> {
+            self.fd.as_fd()
+        }
+
+        pub fn recv(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = unsafe { libc::recv(self.fd.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if n < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(n as usize)
+            }
+        }
+    }
+
+    /// Filtered AF_PACKET socket receiving into a TPACKET_V3 ring
+    pub struct RingSocket {
+        fd: OwnedFd,
+        ring: *mut u8,
+        blocks: usize,
+        /// Block being read
+        block: usize,
+        /// The current block belongs to us until handed back to the kernel
+        held: bool,
+        /// Frames left in the current block and the offset of the next one
+        remaining: u32,
+        offset: usize,
+    }
+
+    // The ring is only touched through `&mut self`
+    unsafe impl Send for RingSocket {}
+
+    impl RingSocket {
+        pub fn open(ifindex: u32, filter: CaptureFilter) -> io::Result<Self> {
+            let fd = filtered_socket(filter)?;
+            set_option(&fd, libc::SOL_PACKET, PACKET_VERSION, &TPACKET_V3)?;
+
+            let blocks = (CAPTURE_RING_MB << 20) / RING_BLOCK_SIZE;
+            let request = TpacketReq3 {
+                block_size: RING_BLOCK_SIZE as u32,
+                block_nr: blocks as u32,
+                frame_size: RING_FRAME_SIZE as u32,
+                frame_nr: (blocks * RING_BLOCK_SIZE / RING_FRAME_SIZE) as u32,
+                retire_blk_tov: RING_BLOCK_TIMEOUT_MS,
+                sizeof_priv: 0,
+                feature_req_word: 0,
+            };
+            set_option(&fd, libc::SOL_PACKET, PACKET_RX_RING, &request)?;
+
+            let ring = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    blocks * RING_BLOCK_SIZE,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd.as_raw_fd(),
+                    0,
+                )
+            };
+            if ring == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            // Owned from here on, so a failed bind still unmaps the ring
+            let socket = Self {
+                fd,
+                ring: ring as *mut u8,
+                blocks,
+                block: 0,
+                held: false,
+                remaining: 0,
+                offset: 0,
+            };
+            bind(&socket.fd, ifindex)?;
+            Ok(socket)
+        }
+
+        pub fn fd(&self) -> BorrowedFd<'_> {
+            self.fd.as_fd()
+        }
+
+        fn block_ptr(&self, index: usize) -> *mut u8 {
+            unsafe { self.ring.add(index * RING_BLOCK_SIZE) }
+        }
+
+        fn block_word(&self, offset: usize) -> u32 {
+            unsafe { std::ptr::read_volatile(self.block_ptr(self.block).add(offset) as *const u32) }
+        }
+
+        /// Next frame, waiting up to the receive timeout for a block
+        pub fn next_frame(&mut self) -> io::Result<&[u8]> {
+            loop {
+                if self.remaining > 0 {
+                    let block = unsafe { std::slice::from_raw_parts(self.block_ptr(self.block), RING_BLOCK_SIZE) };
+                    let (frame, next) = frame_at(block, self.offset)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed capture ring block"))?;
+                    self.remaining -= 1;
+                    self.offset += next;
+                    return Ok(&block[frame]);
+                }
+                if self.held {
+                    // Every frame of the block was returned: hand it back
+                    fence(Ordering::Release);
+                    unsafe {
+                        std::ptr::write_volatile(
+                            self.block_ptr(self.block).add(BLOCK_STATUS) as *mut u32,
+                            TP_STATUS_KERNEL,
+                        )
+                    };
+                    self.held = false;
+                    self.block = (self.block + 1) % self.blocks;
+                }
+
+                if self.block_word(BLOCK_STATUS) & TP_STATUS_USER == 0 {
+                    let mut poll = libc::pollfd { fd: self.fd.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+                    let ready = unsafe { libc::poll(&mut poll, 1, (RECV_TIMEOUT_US / 1000) as libc::c_int) };
+                    if ready < 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    if self.block_word(BLOCK_STATUS) & TP_STATUS_USER == 0 {
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "no capture block ready"));
+                    }
+                }
+                fence(Ordering::Acquire);
+                self.held = true;
+                self.remaining = self.block_word(BLOCK_NUM_PKTS);
+                self.offset = self.block_word(BLOCK_FIRST_PKT) as usize;
+            }
+        }
+    }
+
+    impl Drop for RingSocket {
+        fn drop(&mut self) {
+            unsafe { libc::munmap(self.ring as *mut libc::c_void, self.blocks * RING_BLOCK_SIZE) };
+        }
+    }
+
+    /// Location of the frame whose `tpacket3_hdr` is at `offset` in a block,
+    /// and the distance to the next header
+    pub(super) fn frame_at(block: &[u8], offset: usize) -> Option<(Range<usize>, usize)> {
+        let header = block.get(offset..offset + 28)?;
+        let word = |at: usize| u32::from_ne_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]]) as usize;
+        let (next, snaplen) = (word(0), word(12));
+        let mac = u16::from_ne_bytes([header[24], header[25]]) as usize;
+        let start = offset + mac;
+        block.get(start..start + snaplen)?;
+        Some((start..start + snaplen, next))
+    }
+
+    /// Frames dropped for lack of buffer space since the last call
+    pub fn packet_drops(fd: BorrowedFd<'_>) -> Option<u32> {
+        // struct tpacket_stats_v3: packets, drops, freeze_q_cnt
+        let mut stats = [0u32; 3];
+        let mut len = std::mem::size_of_val(&stats) as libc::socklen_t;
+        let ret = unsafe {
+            libc::getsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_PACKET,
+                PACKET_STATISTICS,
+                stats.as_mut_ptr() as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        (ret == 0).then_some(stats[1])
+    }
+
+    /// AF_PACKET socket with the filter attached
+    ///
+    /// Created with protocol 0, so the kernel queues nothing until `bind`
+    /// sets the protocol and interface; every queued frame has passed the
+    /// filter and arrived on the bound interface.
+    fn filtered_socket(filter: CaptureFilter) -> io::Result<OwnedFd> {
+        let raw = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW | libc::SOCK_CLOEXEC, 0) };
+        if raw < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        let program = filter.program();
+        let fprog = libc::sock_fprog {
+            len: program.len() as u16,
+            filter: program.as_ptr() as *mut libc::sock_filter,
+        };
+        set_option(&fd, libc::SOL_SOCKET, libc::SO_ATTACH_FILTER, &fprog)?;
+        Ok(fd)
+    }
+
+    /// Start receiving every protocol on one interface
+    fn bind(fd: &OwnedFd, ifindex: u32) -> io::Result<()> {
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = (libc::ETH_P_ALL as u16).to_be();
+        addr.sll_ifindex = ifindex as i32;
+        check(unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &addr as *const _ as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        })
+    }
+
+    fn set_option<T>(fd: &OwnedFd, level: libc::c_int, name: libc::c_int, value: &T) -> io::Result<()> {
+        check(unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                level,
+                name,
+                value as *const T as *const libc::c_void,
+                std::mem::size_of::<T>() as libc::socklen_t,
+            )
+        })
+    }
+
+    fn check(ret: libc::c_int) -> io::Result<()> {
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ethernet_frame(ethertype: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0u8; 12];
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn test_arp_filter() {
+        assert!(CaptureFilter::Arp.matches(&ethernet_frame(0x0806, &[0; 28])));
+        assert!(!CaptureFilter::Arp.matches(&ethernet_frame(0x0800, &[0; 28])));
+        assert!(!CaptureFilter::Arp.matches(&[0u8; 10]));
+    }
+
+    #[test]
+    fn test_dhcp_filter() {
+        // IPv4 header with options (IHL 6) followed by UDP to port 67
+        let mut ipv4 = vec![0u8; 24];
+        ipv4[0] = 0x46;
+        ipv4[9] = 17;
+        let mut udp = vec![0u8; 8];
+        udp[2..4].copy_from_slice(&67u16.to_be_bytes());
+        let frame = ethernet_frame(0x0800, &[ipv4.clone(), udp.clone()].concat());
+        assert!(CaptureFilter::Dhcp.matches(&frame));
+
+        udp[2..4].copy_from_slice(&53u16.to_be_bytes());
+        assert!(!CaptureFilter::Dhcp.matches(&ethernet_frame(0x0800, &[ipv4, udp].concat())));
+    }
+
+    #[test]
+    fn test_stp_filter() {
+        let mut bpdu = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x00, 0, 0, 0, 0, 0, 1, 0x00, 0x26];
+        bpdu.extend_from_slice(&[0x42, 0x42, 0x03]);
+        assert!(CaptureFilter::Stp.matches(&bpdu));
+
+        // LLDP uses the neighbouring group address 01:80:c2:00:00:0e
+        bpdu[5] = 0x0e;
+        assert!(!CaptureFilter::Stp.matches(&bpdu));
+    }
+
+    #[test]
+    fn test_multicast_filter() {
+        let mut frame = ethernet_frame(0x0800, &[0; 20]);
+        assert!(!CaptureFilter::Multicast.matches(&frame));
+        frame[..6].copy_from_slice(&[0xff; 6]);
+        assert!(CaptureFilter::Multicast.matches(&frame));
+        frame[..6].copy_from_slice(&[0x01, 0x00, 0x5e, 0x00, 0x00, 0xfb]);
+        assert!(CaptureFilter::Multicast.matches(&frame));
+    }
+
+    #[test]
+    fn test_icmpv6_filter() {
+        let mut ipv6 = vec![0u8; 40];
+        ipv6[6] = 58; // next header: ICMPv6
+        assert!(CaptureFilter::Icmpv6.matches(&ethernet_frame(0x86dd, &ipv6)));
+
+        ipv6[6] = 6; // TCP
+        assert!(!CaptureFilter::Icmpv6.matches(&ethernet_frame(0x86dd, &ipv6)));
+
+        // Fragment header carrying ICMPv6
+        ipv6[6] = 44;
+        ipv6.extend_from_slice(&[58, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(CaptureFilter::Icmpv6.matches(&ethernet_frame(0x86dd, &ipv6)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_ring_block_walk() {
+        // Two tpacket3_hdr entries: a 60-byte frame at mac offset 68, then a 42-byte one
+        let mut block = vec![0u8; 512];
+        let header = |block: &mut [u8], at: usize, next: u32, snaplen: u32, mac: u16| {
+            block[at..at + 4].copy_from_slice(&next.to_ne_bytes());
+            block[at + 12..at + 16].copy_from_slice(&snaplen.to_ne_bytes());
+            block[at + 24..at + 26].copy_from_slice(&mac.to_ne_bytes());
+        };
+        header(&mut block, 48, 144, 60, 68);
+        header(&mut block, 192, 0, 42, 68);
+
+        assert_eq!(linux::frame_at(&block, 48), Some((116..176, 144)));
+        assert_eq!(linux::frame_at(&block, 192), Some((260..302, 0)));
+        // Snap length running past the block
+        header(&mut block, 192, 0, 400, 68);
+        assert!(linux::frame_at(&block, 192).is_none());
+    }
+}
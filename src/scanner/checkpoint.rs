@@ -0,0 +1,145 @@
+//! Scan checkpointing
+//!
+//! A /16 sweep with port probes runs for hours; losing it to a laptop lid or
+//! a dropped SSH session should not mean starting over. The address list is
+//! split into fixed chunks, and after each chunk's ARP sweep and port probes
+//! finish the results are written to a checkpoint file. A later run against
+//! the same subnet skips the completed chunks. The file is replaced
+//! atomically, so an interruption mid-write leaves the previous checkpoint.
+
+use anyhow::{Context, Result};
+use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+use crate::models::Port;
+
+/// Path of the checkpoint file; when set, the CLI scans in resumable chunks
+pub const CHECKPOINT_ENV: &str = "HOST_DISCOVERY_CHECKPOINT";
+
+/// Progress of a chunked scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// Subnet the checkpoint belongs to
+    pub subnet: String,
+    /// Addresses per chunk; a different size invalidates chunk indexes
+    pub chunk_size: usize,
+    /// Chunks whose sweep and port probes completed
+    pub completed_chunks: BTreeSet<usize>,
+    /// Hosts found in completed chunks (IP -> MAC)
+    pub hosts: BTreeMap<Ipv4Addr, String>,
+    /// Port probes of hosts in completed chunks
+    pub ports: BTreeMap<Ipv4Addr, Vec<Port>>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl ScanCheckpoint {
+    /// Resume from `path` if it holds progress for this subnet and chunk size,
+    /// otherwise start fresh
+    pub fn load_or_new(path: impl AsRef<Path>, subnet: &str, chunk_size: usize) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let fresh = Self {
+            subnet: subnet.to_string(),
+            chunk_size: chunk_size.max(1),
+            completed_chunks: BTreeSet::new(),
+            hosts: BTreeMap::new(),
+            ports: BTreeMap::new(),
+            path: path.clone(),
+        };
+
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(fresh),
+            Err(e) => return Err(e).with_context(|| format!("Cannot read checkpoint {}", path.display())),
+        };
+        let saved: Self =
+            serde_json::from_str(&json).with_context(|| format!("Invalid checkpoint {}", path.display()))?;
+        if saved.subnet != fresh.subnet || saved.chunk_size != fresh.chunk_size {
+            return Ok(fresh);
+        }
+        Ok(Self { path, ..saved })
+    }
+
+    /// Chunks of `ips` still to scan, with their indexes
+    pub fn pending<'a>(&'a self, ips: &'a [Ipv4Addr]) -> impl Iterator<Item = (usize, &'a [Ipv4Addr])> + 'a {
+        ips.chunks(self.chunk_size)
+            .enumerate()
+            .filter(|(index, _)| !self.completed_chunks.contains(index))
+    }
+
+    /// Number of chunks `ips` is split into
+    pub fn chunk_count(&self, ips: &[Ipv4Addr]) -> usize {
+        ips.len().div_ceil(self.chunk_size)
+    }
+
+    /// Record a finished chunk and persist the checkpoint
+    pub fn complete_chunk(
+        &mut self,
+        index: usize,
+        hosts: &HashMap<Ipv4Addr, MacAddr>,
+        ports: &HashMap<Ipv4Addr, Vec<Port>>,
+    ) -> Result<()> {
+        self.hosts.extend(hosts.iter().map(|(ip, mac)| (*ip, mac.to_string())));
+        self.ports.extend(ports.iter().map(|(ip, probes)| (*ip, probes.clone())));
+        self.completed_chunks.insert(index);
+        self.save()
+    }
+
+    /// Hosts recorded by completed chunks
+    pub fn host_map(&self) -> HashMap<Ipv4Addr, MacAddr> {
+        self.hosts
+            .iter()
+            .filter_map(|(ip, mac)| mac.parse().ok().map(|mac| (*ip, mac)))
+            .collect()
+    }
+
+    /// Write to a temporary file, then rename over the checkpoint
+    fn save(&self) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)
+            .with_context(|| format!("Cannot write checkpoint {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Cannot replace checkpoint {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Delete the checkpoint once the scan has finished
+    pub fn finish(self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PortState;
+
+    #[test]
+    fn test_resume_skips_completed_chunks() {
+        let path = std::env::temp_dir().join(format!("checkpoint-test-{}.json", std::process::id()));
+        let ips: Vec<Ipv4Addr> = (1..=10).map(|n| Ipv4Addr::new(10, 0, 0, n)).collect();
+
+        let mut checkpoint = ScanCheckpoint::load_or_new(&path, "10.0.0.0/28", 4).unwrap();
+        assert_eq!(checkpoint.chunk_count(&ips), 3);
+        let hosts = HashMap::from([(ips[1], MacAddr::new(0xaa, 0, 0, 0, 0, 2))]);
+        let ports = HashMap::from([(ips[1], vec![Port { connect_ms: Some(0.4), ..Port::tcp(22, PortState::Open) }])]);
+        checkpoint.complete_chunk(0, &hosts, &ports).unwrap();
+
+        let resumed = ScanCheckpoint::load_or_new(&path, "10.0.0.0/28", 4).unwrap();
+        let pending: Vec<usize> = resumed.pending(&ips).map(|(index, _)| index).collect();
+        assert_eq!(pending, vec![1, 2]);
+        assert_eq!(resumed.host_map(), hosts);
+        assert_eq!(resumed.ports[&ips[1]][0].number, 22);
+
+        // Another subnet starts over
+        assert!(ScanCheckpoint::load_or_new(&path, "10.0.1.0/28", 4).unwrap().completed_chunks.is_empty());
+        resumed.finish().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,279 @@
+//! Per-host clock skew measurement
+//!
+//! Kerberos rejects tickets from clocks more than five minutes apart, TLS
+//! rejects certificates "not yet valid", and logs from a skewed device
+//! cannot be correlated with anything else. Two sources of a host's clock
+//! are used: the ICMP Timestamp reply (RFC 792, milliseconds since midnight
+//! UTC, needs a raw socket) and, where that is filtered, the `Date` header
+//! of a web interface (one-second resolution). Both are corrected for half
+//! the round trip.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use pnet::packet::icmp::IcmpPacket;
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::Packet;
+use pnet::transport::{icmp_packet_iter, transport_channel, TransportChannelType, TransportProtocol};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::config::CLOCK_SKEW_THRESHOLD;
+use crate::insights::{Priority, Recommendation};
+use crate::models::HostInfo;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// How long to wait for ICMP timestamp replies
+const ICMP_REPLY_WAIT: Duration = Duration::from_millis(1500);
+
+/// Timeout for each connect/read step of the HTTP fallback
+const HTTP_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Web ports tried for a Date header
+const HTTP_PORTS: &[u16] = &[80, 8080];
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Kerberos default maximum tolerated skew
+const KERBEROS_MAX_SKEW: Duration = Duration::from_secs(300);
+
+/// Where a skew measurement came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockSource {
+    IcmpTimestamp,
+    HttpDate,
+}
+
+impl ClockSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClockSource::IcmpTimestamp => "ICMP timestamp",
+            ClockSource::HttpDate => "HTTP Date header",
+        }
+    }
+}
+
+/// Offset of a host's clock from the local clock
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkew {
+    pub ip: Ipv4Addr,
+    /// Remote minus local time; positive means the host runs ahead
+    pub skew_ms: i64,
+    pub source: ClockSource,
+}
+
+impl ClockSkew {
+    pub fn exceeds(&self, threshold: Duration) -> bool {
+        self.skew_ms.unsigned_abs() > threshold.as_millis() as u64
+    }
+}
+
+/// Insight listing hosts whose clock drifts beyond `CLOCK_SKEW_THRESHOLD`
+pub fn clock_skew_recommendation(skews: &[ClockSkew]) -> Option<Recommendation> {
+    let mut drifting: Vec<&ClockSkew> = skews.iter().filter(|s| s.exceeds(CLOCK_SKEW_THRESHOLD)).collect();
+    if drifting.is_empty() {
+        return None;
+    }
+    drifting.sort_by_key(|s| std::cmp::Reverse(s.skew_ms.unsigned_abs()));
+
+    let kerberos = drifting.iter().any(|s| s.exceeds(KERBEROS_MAX_SKEW));
+    Some(Recommendation {
+        priority: if kerberos { Priority::High } else { Priority::Medium },
+        category: "Time Sync".to_string(),
+        title: format!("{} device(s) with a skewed clock", drifting.len()),
+        description: "These clocks differ from this machine's by more than the allowed drift. Beyond five \
+                      minutes Kerberos/Active Directory logins fail, and skewed clocks break TLS certificate \
+                      checks and log correlation. Point the devices at the network's NTP server."
+            .to_string(),
+        affected_devices: drifting
+            .iter()
+            .map(|s| format!("{} ({:+.1}s, {})", s.ip, s.skew_ms as f64 / 1000.0, s.source.as_str()))
+            .collect(),
+    })
+}
+
+/// Milliseconds since midnight UTC, the ICMP timestamp unit
+fn ms_since_midnight(now: DateTime<Utc>) -> i64 {
+    now.timestamp_millis().rem_euclid(MS_PER_DAY)
+}
+
+/// Fold a millisecond difference across midnight into [-12h, 12h)
+fn wrap_day(diff: i64) -> i64 {
+    (diff + MS_PER_DAY / 2).rem_euclid(MS_PER_DAY) - MS_PER_DAY / 2
+}
+
+/// ICMP timestamp request (type 13)
+fn timestamp_request(id: u16, seq: u16, originate: u32) -> [u8; 20] {
+    let mut packet = [0u8; 20];
+    packet[0] = 13;
+    packet[4..6].copy_from_slice(&id.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    packet[8..12].copy_from_slice(&originate.to_be_bytes());
+    let checksum = pnet::util::checksum(&packet, 1);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    packet
+}
+
+/// (id, originate, receive) from an ICMP timestamp reply (type 14)
+///
+/// Replies with the high bit set carry a non-standard time and are ignored.
+fn parse_timestamp_reply(icmp: &[u8]) -> Option<(u16, u32, u32)> {
+    if icmp.len() < 20 || icmp[0] != 14 {
+        return None;
+    }
+    let word = |at: usize| u32::from_be_bytes([icmp[at], icmp[at + 1], icmp[at + 2], icmp[at + 3]]);
+    let receive = word(12);
+    (receive & 0x8000_0000 == 0).then(|| (u16::from_be_bytes([icmp[4], icmp[5]]), word(8), receive))
+}
+
+/// Measure skew of each target with ICMP timestamps (blocking)
+fn icmp_timestamp_skews(targets: &[Ipv4Addr]) -> Result<HashMap<Ipv4Addr, ClockSkew>> {
+    let protocol = TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Icmp));
+    let (mut tx, mut rx) = transport_channel(4096, protocol)
+        .map_err(|e| anyhow!("Raw ICMP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+
+    let id = (std::process::id() & 0xffff) as u16;
+    let mut sent: HashMap<Ipv4Addr, Instant> = HashMap::new();
+    for (seq, &target) in targets.iter().enumerate() {
+        let request = timestamp_request(id, seq as u16, ms_since_midnight(Utc::now()) as u32);
+        let packet = IcmpPacket::new(&request).expect("buffer holds an ICMP header");
+        tx.send_to(packet, IpAddr::V4(target))?;
+        sent.insert(target, Instant::now());
+    }
+
+    let mut skews = HashMap::new();
+    let mut packets = icmp_packet_iter(&mut rx);
+    let deadline = Instant::now() + ICMP_REPLY_WAIT;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match packets.next_with_timeout(remaining) {
+            Ok(Some((reply, IpAddr::V4(from)))) => {
+                let Some((reply_id, originate, receive)) = parse_timestamp_reply(reply.packet()) else { continue };
+                let Some(sent_at) = sent.get(&from) else { continue };
+                if reply_id != id {
+                    continue;
+                }
+                let half_rtt = sent_at.elapsed().as_millis() as i64 / 2;
+                let skew_ms = wrap_day(receive as i64 - (originate as i64 + half_rtt));
+                skews.entry(from).or_insert(ClockSkew { ip: from, skew_ms, source: ClockSource::IcmpTimestamp });
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(skews)
+}
+
+/// Date header of a `HEAD /` response
+fn parse_http_date(response: &str) -> Option<DateTime<Utc>> {
+    let value = response
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .find_map(|l| l.split_once(':').filter(|(name, _)| name.trim().eq_ignore_ascii_case("date")))?
+        .1
+        .trim();
+    DateTime::parse_from_rfc2822(value).ok().map(|d| d.with_timezone(&Utc))
+}
+
+/// Measure skew from a web server's Date header
+async fn http_date_skew(ip: Ipv4Addr, port: u16) -> Option<ClockSkew> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    let mut stream = match timeout(HTTP_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return None,
+    };
+    let sent = Utc::now();
+    let request = format!("HEAD / HTTP/1.0\r\nHost: {}\r\n\r\n", ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+    let mut response = vec![0u8; 2048];
+    let n = timeout(HTTP_TIMEOUT, stream.read(&mut response)).await.ok()?.ok()?;
+    let received = Utc::now();
+
+    let date = parse_http_date(&String::from_utf8_lossy(&response[..n]))?;
+    let midpoint = sent + (received - sent) / 2;
+    Some(ClockSkew { ip, skew_ms: (date - midpoint).num_milliseconds(), source: ClockSource::HttpDate })
+}
+
+/// Measure clock skew of discovered hosts
+///
+/// ICMP timestamps are tried first; hosts that do not answer them fall back
+/// to the Date header of an open web port.
+pub async fn clock_skew_scan(hosts: &[HostInfo]) -> HashMap<Ipv4Addr, ClockSkew> {
+    let targets: Vec<Ipv4Addr> = hosts.iter().filter_map(|h| h.ip.parse().ok()).collect();
+    log_stderr!("Clock skew: measuring {} hosts...", targets.len());
+
+    let icmp_targets = targets.clone();
+    let mut skews = match tokio::task::spawn_blocking(move || icmp_timestamp_skews(&icmp_targets)).await {
+        Ok(Ok(skews)) => skews,
+        Ok(Err(e)) => {
+            log_stderr!("Clock skew: {}, using HTTP Date headers only", e);
+            HashMap::new()
+        }
+        Err(_) => HashMap::new(),
+    };
+
+    for host in hosts {
+        let Ok(ip) = host.ip.parse::<Ipv4Addr>() else { continue };
+        if skews.contains_key(&ip) {
+            continue;
+        }
+        for &port in HTTP_PORTS.iter().filter(|p| host.open_ports.contains(*p)) {
+            if let Some(skew) = http_date_skew(ip, port).await {
+                skews.insert(ip, skew);
+                break;
+            }
+        }
+    }
+
+    log_stderr!(
+        "Clock skew complete: {} hosts measured, {} beyond {}s",
+        skews.len(),
+        skews.values().filter(|s| s.exceeds(CLOCK_SKEW_THRESHOLD)).count(),
+        CLOCK_SKEW_THRESHOLD.as_secs()
+    );
+    skews
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icmp_timestamp_roundtrip() {
+        let request = timestamp_request(0x1234, 1, 3_600_000);
+        assert_eq!(pnet::util::checksum(&request, 1), u16::from_be_bytes([request[2], request[3]]));
+
+        let mut reply = request;
+        reply[0] = 14;
+        reply[12..16].copy_from_slice(&3_660_500u32.to_be_bytes());
+        assert_eq!(parse_timestamp_reply(&reply), Some((0x1234, 3_600_000, 3_660_500)));
+        reply[12] |= 0x80;
+        assert_eq!(parse_timestamp_reply(&reply), None);
+
+        // Host just past midnight, we just before
+        assert_eq!(wrap_day(1_000 - (MS_PER_DAY - 2_000)), 3_000);
+    }
+
+    #[test]
+    fn test_http_date_and_threshold() {
+        let response = "HTTP/1.1 200 OK\r\nServer: lighttpd\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n";
+        assert_eq!(parse_http_date(response).unwrap().timestamp(), 784_111_777);
+        assert!(parse_http_date("HTTP/1.1 200 OK\r\n\r\n").is_none());
+
+        let skew = |ip: u8, skew_ms: i64| ClockSkew { ip: Ipv4Addr::new(10, 0, 0, ip), skew_ms, source: ClockSource::HttpDate };
+        let rec = clock_skew_recommendation(&[skew(1, 400), skew(2, -600_000)]).unwrap();
+        assert_eq!(rec.priority, Priority::High);
+        assert_eq!(rec.affected_devices, vec!["10.0.0.2 (-600.0s, HTTP Date header)"]);
+    }
+}
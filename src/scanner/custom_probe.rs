@@ -0,0 +1,264 @@
+//! Custom probe payloads
+//!
+//! In-house services (PLC gateways, building controllers, proprietary
+//! control protocols) answer nothing the built-in probes send. Operators
+//! register a payload per port together with what a matching reply looks
+//! like; hosts whose reply matches are identified by the probe's name.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use super::timeouts::HostTimeouts;
+use crate::models::{HostInfo, ServiceEvidence, ServiceGuess, Transport};
+use crate::network::add_service_guess;
+
+/// Path of a JSON file with custom probes to run (see `ProbeRegistry::load`)
+pub const CUSTOM_PROBES_ENV: &str = "HOST_DISCOVERY_PROBES";
+
+/// Timeout for each connect/send/read step of a probe to a host without a measured round trip
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Bytes of the reply kept for matching
+const MAX_REPLY_BYTES: usize = 4096;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// What a reply must look like for the probe to match (byte strings are hex in JSON)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseMatcher {
+    /// Any reply at all
+    Any,
+    /// Reply starts with these bytes
+    Prefix(#[serde(with = "hex_bytes")] Vec<u8>),
+    /// Reply contains these bytes anywhere
+    Contains(#[serde(with = "hex_bytes")] Vec<u8>),
+}
+
+impl ResponseMatcher {
+    pub fn matches(&self, reply: &[u8]) -> bool {
+        match self {
+            ResponseMatcher::Any => !reply.is_empty(),
+            ResponseMatcher::Prefix(prefix) => reply.starts_with(prefix),
+            ResponseMatcher::Contains(needle) => {
+                needle.is_empty() || reply.windows(needle.len()).any(|window| window == needle.as_slice())
+            }
+        }
+    }
+}
+
+/// A payload for one port and the reply that identifies the service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProbe {
+    /// Service name reported on a match
+    pub name: String,
+    pub transport: Transport,
+    pub port: u16,
+    /// Bytes sent after connecting; empty reads the TCP greeting only
+    #[serde(default, with = "hex_bytes")]
+    pub payload: Vec<u8>,
+    pub matcher: ResponseMatcher,
+}
+
+/// Registered custom probes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeRegistry {
+    pub probes: Vec<CustomProbe>,
+}
+
+impl ProbeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, probe: CustomProbe) -> &mut Self {
+        self.probes.push(probe);
+        self
+    }
+
+    /// Registry from a JSON file: `{"probes": [{"name": ..., "transport": "tcp", ...}]}`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid probe file {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.probes.is_empty()
+    }
+}
+
+/// A host whose reply matched a custom probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProbeMatch {
+    pub ip: Ipv4Addr,
+    pub port: u16,
+    pub transport: Transport,
+    pub name: String,
+    /// First line of the reply, when it reads as text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+}
+
+/// First line of a reply that reads as text
+fn reply_banner(reply: &[u8]) -> Option<String> {
+    let line = std::str::from_utf8(reply).ok()?.lines().next()?.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Send one probe and read the reply
+///
+/// Connecting takes a network round trip and uses the host's adaptive
+/// timeout; the reply also waits on the service, so it never gets less
+/// than `PROBE_TIMEOUT`.
+async fn exchange(ip: Ipv4Addr, probe: &CustomProbe, connect_timeout: Duration) -> Option<Vec<u8>> {
+    let reply_timeout = connect_timeout.max(PROBE_TIMEOUT);
+    let addr = SocketAddr::new(IpAddr::V4(ip), probe.port);
+    let mut reply = vec![0u8; MAX_REPLY_BYTES];
+    let len = match probe.transport {
+        Transport::Tcp => {
+            let mut stream = timeout(connect_timeout, TcpStream::connect(addr)).await.ok()?.ok()?;
+            if !probe.payload.is_empty() {
+                timeout(reply_timeout, stream.write_all(&probe.payload)).await.ok()?.ok()?;
+            }
+            timeout(reply_timeout, stream.read(&mut reply)).await.ok()?.ok()?
+        }
+        Transport::Udp => {
+            let socket = UdpSocket::bind("0.0.0.0:0").await.ok()?;
+            socket.connect(addr).await.ok()?;
+            socket.send(&probe.payload).await.ok()?;
+            timeout(reply_timeout, socket.recv(&mut reply)).await.ok()?.ok()?
+        }
+    };
+    reply.truncate(len);
+    Some(reply)
+}
+
+/// Run every registered probe against the hosts
+///
+/// TCP probes only go to hosts where the port was found open; UDP probes go
+/// to every host, since a silent UDP port looks the same as a closed one.
+pub async fn custom_probe_scan(hosts: &[HostInfo], registry: &ProbeRegistry) -> Vec<CustomProbeMatch> {
+    let timeouts = HostTimeouts::from_hosts(hosts);
+    let mut targets: Vec<(Ipv4Addr, CustomProbe)> = Vec::new();
+    for host in hosts {
+        let Ok(ip) = host.ip.parse::<Ipv4Addr>() else { continue };
+        for probe in &registry.probes {
+            if probe.transport == Transport::Udp || host.open_ports.contains(&probe.port) {
+                targets.push((ip, probe.clone()));
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    log_stderr!("Running {} custom probes...", targets.len());
+
+    let slots = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let handles: Vec<_> = targets
+        .into_iter()
+        .map(|(ip, probe)| {
+            let slots = Arc::clone(&slots);
+            let connect_timeout = timeouts.timeout_for(ip, PROBE_TIMEOUT);
+            tokio::spawn(async move {
+                let _permit = slots.acquire_owned().await.ok()?;
+                let reply = exchange(ip, &probe, connect_timeout).await?;
+                let banner = reply_banner(&reply);
+                probe.matcher.matches(&reply).then_some(CustomProbeMatch {
+                    ip,
+                    port: probe.port,
+                    transport: probe.transport,
+                    name: probe.name,
+                    banner,
+                })
+            })
+        })
+        .collect();
+
+    let mut matches = Vec::new();
+    for handle in handles {
+        if let Ok(Some(found)) = handle.await {
+            matches.push(found);
+        }
+    }
+    log_stderr!("Custom probes identified {} services", matches.len());
+    matches
+}
+
+/// Record matched custom services on their hosts
+///
+/// Each match is a banner-grade service guess and its reply becomes the
+/// port's banner; hosts without a system description are also described
+/// by their matched services.
+pub fn apply_custom_probes(hosts: &mut [HostInfo], matches: &[CustomProbeMatch]) {
+    for host in hosts.iter_mut() {
+        let found: Vec<&CustomProbeMatch> = matches.iter().filter(|m| m.ip.to_string() == host.ip).collect();
+        if found.is_empty() {
+            continue;
+        }
+        for m in &found {
+            add_service_guess(host, ServiceGuess::new(m.name.clone(), ServiceEvidence::Banner).on_port(m.port));
+            let port = host.ports.iter_mut().find(|p| p.number == m.port && p.transport == m.transport);
+            if let Some(port) = port.filter(|p| p.banner.is_none()) {
+                port.banner = m.banner.clone();
+            }
+        }
+        if host.system_description.is_none() {
+            let services: Vec<String> =
+                found.iter().map(|m| format!("{} ({}/{})", m.name, m.transport.as_str(), m.port)).collect();
+            host.system_description = Some(services.join(", "));
+        }
+    }
+}
+
+/// Byte strings as hex in JSON
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        hex::decode(text.replace([' ', ':'], "")).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_from_json() {
+        let registry: ProbeRegistry = serde_json::from_str(
+            r#"{"probes": [
+                {"name": "Acme PLC", "transport": "tcp", "port": 9600, "payload": "46 49 4e 53", "matcher": {"prefix": "46494e53"}},
+                {"name": "Lobby HVAC", "transport": "udp", "port": 47808, "payload": "810a0011", "matcher": "any"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(registry.probes.len(), 2);
+        assert_eq!(registry.probes[0].payload, b"FINS");
+        assert!(registry.probes[0].matcher.matches(b"FINS\x00\x00\x00\x0c"));
+        assert!(!registry.probes[0].matcher.matches(b"HTTP/1.1 400"));
+        assert!(registry.probes[1].matcher.matches(b"\x81"));
+        assert!(!registry.probes[1].matcher.matches(b""));
+
+        assert!(ResponseMatcher::Contains(b"ctrl".to_vec()).matches(b"OK ctrl v2"));
+    }
+}
@@ -0,0 +1,102 @@
+//! DHCPv6 server discovery
+//!
+//! Multicasts a DHCPv6 Solicit to All_DHCP_Relay_Agents_and_Servers
+//! (ff02::1:2) on the scanned interface and collects the servers that
+//! answer with an Advertise. No Request follows, so no lease is taken.
+//! Binding the client port (546) needs root/administrator privileges.
+
+use anyhow::{anyhow, Result};
+use std::net::{Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+use crate::models::InterfaceInfo;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// All_DHCP_Relay_Agents_and_Servers (RFC 8415)
+const ALL_DHCP_SERVERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 1, 2);
+
+/// DHCPv6 client and server ports
+const DHCPV6_CLIENT_PORT: u16 = 546;
+const DHCPV6_SERVER_PORT: u16 = 547;
+
+/// How long to collect Advertise messages
+const DHCPV6_WAIT: Duration = Duration::from_secs(2);
+
+/// DHCPv6 message types
+const MSG_SOLICIT: u8 = 1;
+const MSG_ADVERTISE: u8 = 2;
+
+/// Build a Solicit with a DUID-LL client identifier and an elapsed-time option
+fn build_solicit(transaction: [u8; 3], mac: [u8; 6]) -> Vec<u8> {
+    let mut packet = vec![MSG_SOLICIT];
+    packet.extend_from_slice(&transaction);
+    // OPTION_CLIENTID: DUID-LL (type 3), hardware type 1 (Ethernet)
+    packet.extend_from_slice(&[0, 1, 0, 10, 0, 3, 0, 1]);
+    packet.extend_from_slice(&mac);
+    // OPTION_ELAPSED_TIME: 0
+    packet.extend_from_slice(&[0, 8, 0, 2, 0, 0]);
+    // OPTION_IA_NA with IAID 1 and no addresses, so servers answer
+    packet.extend_from_slice(&[0, 3, 0, 12, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    packet
+}
+
+/// An Advertise answering our transaction
+fn is_advertise(transaction: [u8; 3], data: &[u8]) -> bool {
+    data.len() >= 4 && data[0] == MSG_ADVERTISE && data[1..4] == transaction
+}
+
+/// Find DHCPv6 servers on the interface's link
+pub async fn dhcpv6_probe(interface: &InterfaceInfo) -> Result<Vec<Ipv6Addr>> {
+    let socket = UdpSocket::bind(SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, DHCPV6_CLIENT_PORT, 0, 0))
+        .await
+        .map_err(|e| anyhow!("Cannot bind DHCPv6 client port (requires root): {}", e))?;
+
+    let scope = interface.pnet_interface.index;
+    let transaction = [(scope >> 8) as u8, scope as u8, std::process::id() as u8];
+    let target = SocketAddrV6::new(ALL_DHCP_SERVERS, DHCPV6_SERVER_PORT, 0, scope);
+    socket.send_to(&build_solicit(transaction, interface.mac.octets()), target).await?;
+
+    log_stderr!("DHCPv6: soliciting servers on {}...", interface.name);
+
+    let mut servers = Vec::new();
+    let mut buf = [0u8; 1500];
+    let deadline = tokio::time::Instant::now() + DHCPV6_WAIT;
+    while let Some(remaining) = deadline.checked_duration_since(tokio::time::Instant::now()) {
+        match timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(Ok((n, SocketAddr::V6(from)))) if is_advertise(transaction, &buf[..n]) => {
+                if !servers.contains(from.ip()) {
+                    servers.push(*from.ip());
+                }
+            }
+            Ok(Ok(_)) => continue,
+            _ => break,
+        }
+    }
+
+    log_stderr!("DHCPv6: {} servers answered", servers.len());
+    Ok(servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solicit_and_advertise() {
+        let solicit = build_solicit([1, 2, 3], [0xaa, 0xbb, 0xcc, 0, 0, 1]);
+        assert_eq!(&solicit[..4], &[MSG_SOLICIT, 1, 2, 3]);
+        assert_eq!(&solicit[12..18], &[0xaa, 0xbb, 0xcc, 0, 0, 1]);
+
+        assert!(is_advertise([1, 2, 3], &[MSG_ADVERTISE, 1, 2, 3, 0, 1]));
+        assert!(!is_advertise([1, 2, 3], &[MSG_ADVERTISE, 9, 9, 9]));
+        assert!(!is_advertise([1, 2, 3], &solicit));
+    }
+}
@@ -0,0 +1,238 @@
+//! DNS server discovery and open-resolver check
+//!
+//! Sends a recursive query for an external name to every discovered host on
+//! UDP/53 and to the servers handed out in DHCP option 6. Hosts that answer
+//! are DNS servers; one that resolves the name performs recursion for any
+//! client that can reach it. That is expected of the advertised resolver,
+//! but a printer or IoT box running its own resolver is easily exposed to
+//! the WAN and abused for DNS amplification.
+//!
+//! The check runs from inside the LAN, so it cannot prove a resolver is
+//! reachable from the internet, only that it recurses for clients it was
+//! never advertised to.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+use super::audit::{audit_probe, ProbeKind};
+use super::limits::concurrency_limits;
+use crate::models::HostInfo;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// DNS port
+const DNS_PORT: u16 = 53;
+
+/// Timeout for each DNS query
+const DNS_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// External name used to test recursion
+const RECURSION_TEST_NAME: &str = "example.com";
+
+/// DNS header flag: query/response
+const FLAG_QR: u16 = 0x8000;
+/// DNS header flag: recursion desired
+const FLAG_RD: u16 = 0x0100;
+/// DNS header flag: recursion available
+const FLAG_RA: u16 = 0x0080;
+
+/// DNS response codes of interest
+const RCODE_NOERROR: u8 = 0;
+const RCODE_REFUSED: u8 = 5;
+
+/// How a DNS server was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsServerSource {
+    /// A discovered host answered on UDP/53
+    Probe,
+    /// Advertised to clients in DHCP option 6
+    Dhcp,
+}
+
+impl DnsServerSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsServerSource::Probe => "probe",
+            DnsServerSource::Dhcp => "dhcp",
+        }
+    }
+}
+
+/// Outcome of the recursive query against one server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsServerInfo {
+    pub ip: String,
+    pub source: DnsServerSource,
+    /// The server answered the query at all
+    pub responding: bool,
+    /// Recursion-available flag set in the response
+    pub recursion_available: bool,
+    /// The external name was resolved (recursion performed)
+    pub open_resolver: bool,
+    /// Response code, when the server answered
+    pub rcode: Option<u8>,
+}
+
+impl DnsServerInfo {
+    /// Recursing resolver that clients were never pointed at
+    pub fn is_unexpected_resolver(&self) -> bool {
+        self.open_resolver && self.source != DnsServerSource::Dhcp
+    }
+}
+
+/// Fields of a DNS response relevant to the recursion check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DnsReply {
+    rcode: u8,
+    recursion_available: bool,
+    answers: u16,
+}
+
+/// Build a recursive A query for `name`
+fn build_query(id: u16, name: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(12 + name.len() + 6);
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&FLAG_RD.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&[0, 1, 0, 1]); // QTYPE A, QCLASS IN
+    packet
+}
+
+/// Parse the header of a response to query `id`
+fn parse_reply(id: u16, data: &[u8]) -> Option<DnsReply> {
+    let header = data.get(..12)?;
+    if u16::from_be_bytes([header[0], header[1]]) != id {
+        return None;
+    }
+    let flags = u16::from_be_bytes([header[2], header[3]]);
+    if flags & FLAG_QR == 0 {
+        return None;
+    }
+    Some(DnsReply {
+        rcode: (flags & 0x000F) as u8,
+        recursion_available: flags & FLAG_RA != 0,
+        answers: u16::from_be_bytes([header[6], header[7]]),
+    })
+}
+
+/// Send one recursive query and wait for the matching reply
+async fn query_server(ip: Ipv4Addr, id: u16) -> Option<DnsReply> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await.ok()?;
+    let target = SocketAddr::from((ip, DNS_PORT));
+    audit_probe(ip, ProbeKind::Udp, Some(DNS_PORT));
+    socket.send_to(&build_query(id, RECURSION_TEST_NAME), target).await.ok()?;
+
+    let mut buf = [0u8; 512];
+    let deadline = tokio::time::Instant::now() + DNS_QUERY_TIMEOUT;
+    loop {
+        let remaining = deadline.checked_duration_since(tokio::time::Instant::now())?;
+        let (n, from) = timeout(remaining, socket.recv_from(&mut buf)).await.ok()?.ok()?;
+        if from == target {
+            if let Some(reply) = parse_reply(id, &buf[..n]) {
+                return Some(reply);
+            }
+        }
+    }
+}
+
+async fn check_server(ip: Ipv4Addr, source: DnsServerSource, id: u16) -> DnsServerInfo {
+    let reply = query_server(ip, id).await;
+    DnsServerInfo {
+        ip: ip.to_string(),
+        source,
+        responding: reply.is_some(),
+        recursion_available: reply.is_some_and(|r| r.recursion_available),
+        open_resolver: reply.is_some_and(|r| r.rcode == RCODE_NOERROR && r.answers > 0),
+        rcode: reply.map(|r| r.rcode),
+    }
+}
+
+/// Find DNS servers and test whether they resolve external names
+///
+/// Every host in `hosts` is queried, along with the `advertised` servers
+/// from DHCP option 6 (see `advertised_dns_servers`). Returns the hosts that
+/// answered plus every advertised server, so an advertised resolver that
+/// stays silent is visible too. Public resolvers handed out by DHCP are
+/// skipped.
+pub async fn dns_server_scan(hosts: &[HostInfo], advertised: &[Ipv4Addr]) -> Vec<DnsServerInfo> {
+    let mut candidates: Vec<(Ipv4Addr, DnsServerSource)> = advertised
+        .iter()
+        .filter(|ip| ip.is_private())
+        .map(|ip| (*ip, DnsServerSource::Dhcp))
+        .collect();
+    for ip in hosts.iter().filter_map(|h| h.ip.parse::<Ipv4Addr>().ok()) {
+        if !candidates.iter().any(|(c, _)| *c == ip) {
+            candidates.push((ip, DnsServerSource::Probe));
+        }
+    }
+
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    log_stderr!("DNS servers: querying {} hosts on UDP/{}...", candidates.len(), DNS_PORT);
+
+    let base_id = std::process::id() as u16;
+    let servers: Vec<DnsServerInfo> = stream::iter(candidates.into_iter().enumerate())
+        .map(|(i, (ip, source))| check_server(ip, source, base_id.wrapping_add(i as u16)))
+        .buffer_unordered(concurrency_limits().tcp_connects)
+        .filter(|s| std::future::ready(s.responding || s.source == DnsServerSource::Dhcp))
+        .collect()
+        .await;
+
+    for server in servers.iter().filter(|s| s.rcode == Some(RCODE_REFUSED)) {
+        log_stderr!("DNS servers: {} refuses recursion", server.ip);
+    }
+    log_stderr!(
+        "DNS servers: {} responding, {} open resolvers",
+        servers.iter().filter(|s| s.responding).count(),
+        servers.iter().filter(|s| s.open_resolver).count()
+    );
+
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_query() {
+        let query = build_query(0x1234, "example.com");
+        assert_eq!(&query[..4], &[0x12, 0x34, 0x01, 0x00]);
+        assert_eq!(&query[12..], b"\x07example\x03com\x00\x00\x01\x00\x01");
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let mut reply = build_query(7, "example.com");
+        reply[2..4].copy_from_slice(&(FLAG_QR | FLAG_RD | FLAG_RA).to_be_bytes());
+        reply[7] = 1;
+        assert_eq!(
+            parse_reply(7, &reply),
+            Some(DnsReply { rcode: RCODE_NOERROR, recursion_available: true, answers: 1 })
+        );
+
+        reply[3] = RCODE_REFUSED;
+        assert_eq!(parse_reply(7, &reply).map(|r| r.rcode), Some(RCODE_REFUSED));
+
+        // Wrong id or a query echoed back is not a reply
+        assert!(parse_reply(8, &reply).is_none());
+        assert!(parse_reply(7, &build_query(7, "example.com")).is_none());
+    }
+}
@@ -0,0 +1,650 @@
+//! Insecure service exposure checks
+//!
+//! Actively verifies plaintext and weakly protected services:
+//! - Telnet accepting connections
+//! - FTP allowing anonymous login
+//! - VNC offering the "None" security type
+//! - HTTP admin panels on routers
+//! - Printers accepting raw/IPP jobs or serving an unprotected web UI
+//! - IP cameras streaming RTSP or answering ONVIF without (or with default) credentials
+//! - SMB shares readable or writable by anonymous or guest sessions
+//! - HTTPS endpoints accepting TLS 1.0/1.1 or weak cipher suites
+//! - Web management interfaces served over plain HTTP or on vendor default login pages
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use super::smb::{smb_shares, SMB_PORT};
+use super::tls::{tls_assess, TLS_PORTS};
+use super::web_posture::management_plane;
+use crate::models::{HostInfo, PortWarning, Transport};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Timeout for each connect/read step of an exposure check
+const CHECK_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Cleartext remote access and file transfer ports
+const TELNET_PORT: u16 = 23;
+const FTP_PORT: u16 = 21;
+const VNC_PORTS: &[u16] = &[5900, 5901];
+
+/// Ports serving router web admin interfaces
+const ADMIN_HTTP_PORTS: &[u16] = &[80, 8080];
+
+/// Device types whose web UIs are management interfaces
+const ADMIN_DEVICE_TYPES: &[&str] = &["ROUTER", "SWITCH", "ACCESS_POINT", "FIREWALL", "NAS", "PRINTER", "CAMERA"];
+
+/// Raw (JetDirect) and IPP printing ports
+const PRINTER_RAW_PORT: u16 = 9100;
+const PRINTER_IPP_PORT: u16 = 631;
+
+/// Markers of printer embedded web servers
+const PRINTER_WEB_MARKERS: &[&str] = &[
+    "embedded web server",
+    "hp laserjet",
+    "web image monitor",
+    "centreware",
+    "command center",
+    "brother",
+    "epson",
+    "printer status",
+];
+
+/// RTSP and ONVIF ports used by IP cameras
+const RTSP_PORT: u16 = 554;
+const ONVIF_HTTP_PORTS: &[u16] = &[80, 8000, 8080];
+
+/// Factory credentials commonly left on IP cameras
+const DEFAULT_CAMERA_CREDENTIALS: &[(&str, &str)] = &[
+    ("admin", ""),
+    ("admin", "admin"),
+    ("admin", "12345"),
+    ("admin", "123456"),
+    ("root", "pass"),
+    ("root", "root"),
+];
+
+/// Kind of insecure exposure detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExposureKind {
+    TelnetOpen,
+    FtpAnonymous,
+    SnmpPublicCommunity,
+    SnmpGuessableCommunity,
+    SnmpWritableCommunity,
+    VncNoAuth,
+    RouterHttpAdmin,
+    PrinterRawPrinting,
+    PrinterIppOpen,
+    PrinterWebUiNoAuth,
+    CameraRtspNoAuth,
+    CameraRtspDefaultCredentials,
+    CameraOnvifNoAuth,
+    SmbShareReadable,
+    SmbShareWritable,
+    TlsLegacyProtocol,
+    TlsWeakCipher,
+    ManagementPlaneExposure,
+    UpnpPortForward,
+}
+
+impl ExposureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExposureKind::TelnetOpen => "TELNET_OPEN",
+            ExposureKind::FtpAnonymous => "FTP_ANONYMOUS",
+            ExposureKind::SnmpPublicCommunity => "SNMP_PUBLIC_COMMUNITY",
+            ExposureKind::SnmpGuessableCommunity => "SNMP_GUESSABLE_COMMUNITY",
+            ExposureKind::SnmpWritableCommunity => "SNMP_WRITABLE_COMMUNITY",
+            ExposureKind::VncNoAuth => "VNC_NO_AUTH",
+            ExposureKind::RouterHttpAdmin => "ROUTER_HTTP_ADMIN",
+            ExposureKind::PrinterRawPrinting => "PRINTER_RAW_PRINTING",
+            ExposureKind::PrinterIppOpen => "PRINTER_IPP_OPEN",
+            ExposureKind::PrinterWebUiNoAuth => "PRINTER_WEB_UI_NO_AUTH",
+            ExposureKind::CameraRtspNoAuth => "CAMERA_RTSP_NO_AUTH",
+            ExposureKind::CameraRtspDefaultCredentials => "CAMERA_RTSP_DEFAULT_CREDENTIALS",
+            ExposureKind::CameraOnvifNoAuth => "CAMERA_ONVIF_NO_AUTH",
+            ExposureKind::SmbShareReadable => "SMB_SHARE_READABLE",
+            ExposureKind::SmbShareWritable => "SMB_SHARE_WRITABLE",
+            ExposureKind::TlsLegacyProtocol => "TLS_LEGACY_PROTOCOL",
+            ExposureKind::TlsWeakCipher => "TLS_WEAK_CIPHER",
+            ExposureKind::ManagementPlaneExposure => "MANAGEMENT_PLANE_EXPOSURE",
+            ExposureKind::UpnpPortForward => "UPNP_PORT_FORWARD",
+        }
+    }
+}
+
+/// A concrete insecure service finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureFinding {
+    pub kind: ExposureKind,
+    pub port: u16,
+    pub service: String,
+    pub severity: String, // CRITICAL, HIGH, MEDIUM, LOW
+    /// What the check observed (banner, response code, ...)
+    pub evidence: String,
+    pub recommendation: String,
+}
+
+impl ExposureFinding {
+    /// Convert into a port warning for attaching to `HostInfo`
+    pub fn to_port_warning(&self) -> PortWarning {
+        PortWarning {
+            port: self.port,
+            service: self.service.clone(),
+            warning: format!("{} ({})", self.kind.as_str(), self.evidence),
+            severity: self.severity.clone(),
+            recommendation: Some(self.recommendation.clone()),
+        }
+    }
+}
+
+/// Connect to a TCP port with the check timeout
+async fn connect(ip: Ipv4Addr, port: u16) -> Option<TcpStream> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    match timeout(CHECK_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => Some(stream),
+        _ => None,
+    }
+}
+
+/// Read whatever the server sends next (up to 1 KiB)
+async fn read_some(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut buf = vec![0u8; 1024];
+    match timeout(CHECK_TIMEOUT, stream.read(&mut buf)).await {
+        Ok(Ok(n)) if n > 0 => {
+            buf.truncate(n);
+            Some(buf)
+        }
+        _ => None,
+    }
+}
+
+/// Telnet: any accepted connection is a finding
+async fn check_telnet(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    let _stream = connect(ip, TELNET_PORT).await?;
+    Some(ExposureFinding {
+        kind: ExposureKind::TelnetOpen,
+        port: TELNET_PORT,
+        service: "Telnet".to_string(),
+        severity: "HIGH".to_string(),
+        evidence: "TCP/23 accepted connection".to_string(),
+        recommendation: "Disable Telnet and use SSH for remote management".to_string(),
+    })
+}
+
+/// FTP: attempt an anonymous login
+async fn check_ftp_anonymous(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    let mut stream = connect(ip, FTP_PORT).await?;
+
+    let banner = read_some(&mut stream).await?;
+    if !banner.starts_with(b"220") {
+        return None;
+    }
+
+    stream.write_all(b"USER anonymous\r\n").await.ok()?;
+    let reply = read_some(&mut stream).await?;
+    if reply.starts_with(b"230") {
+        return Some(ftp_finding("USER anonymous accepted without password"));
+    }
+    if !reply.starts_with(b"331") {
+        return None;
+    }
+
+    stream.write_all(b"PASS anonymous@example.com\r\n").await.ok()?;
+    let reply = read_some(&mut stream).await?;
+    let _ = stream.write_all(b"QUIT\r\n").await;
+
+    if reply.starts_with(b"230") {
+        Some(ftp_finding("Anonymous login accepted (230)"))
+    } else {
+        None
+    }
+}
+
+fn ftp_finding(evidence: &str) -> ExposureFinding {
+    ExposureFinding {
+        kind: ExposureKind::FtpAnonymous,
+        port: FTP_PORT,
+        service: "FTP".to_string(),
+        severity: "HIGH".to_string(),
+        evidence: evidence.to_string(),
+        recommendation: "Disable anonymous FTP access or replace FTP with SFTP".to_string(),
+    }
+}
+
+/// Parse RFB security types after the version handshake
+///
+/// Returns true if the server offers security type 1 ("None").
+fn rfb_offers_no_auth(version: &[u8], security: &[u8]) -> bool {
+    // RFB 3.3: server picks a single u32 security type
+    if version.starts_with(b"RFB 003.003") {
+        return security.len() >= 4 && security[..4] == [0, 0, 0, 1];
+    }
+
+    // RFB 3.7+: count byte followed by the list of types (count 0 = failure)
+    match security.split_first() {
+        Some((&count, types)) if count > 0 => types.iter().take(count as usize).any(|&t| t == 1),
+        _ => false,
+    }
+}
+
+/// VNC: complete the RFB version handshake and inspect security types
+async fn check_vnc_noauth(ip: Ipv4Addr, port: u16) -> Option<ExposureFinding> {
+    let mut stream = connect(ip, port).await?;
+
+    let version = read_some(&mut stream).await?;
+    if !version.starts_with(b"RFB ") || version.len() < 12 {
+        return None;
+    }
+
+    // Echo the server's version back to proceed with its protocol
+    stream.write_all(&version[..12]).await.ok()?;
+    let security = read_some(&mut stream).await?;
+
+    if rfb_offers_no_auth(&version, &security) {
+        Some(ExposureFinding {
+            kind: ExposureKind::VncNoAuth,
+            port,
+            service: "VNC".to_string(),
+            severity: "CRITICAL".to_string(),
+            evidence: format!("{} offers security type None", String::from_utf8_lossy(&version[..11])),
+            recommendation: "Require VNC authentication and tunnel VNC over SSH or VPN".to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Classify an HTTP response as a reachable admin interface
+fn looks_like_admin_panel(response: &str) -> bool {
+    let lower = response.to_lowercase();
+    let status_ok = lower.starts_with("http/1.1 200") || lower.starts_with("http/1.0 200");
+    let basic_auth = lower.contains("www-authenticate");
+    let admin_markers = ["login", "password", "admin", "router", "setup"];
+
+    basic_auth || (status_ok && admin_markers.iter().any(|m| lower.contains(m)))
+}
+
+/// HTTP: fetch / on router admin ports and look for a login page
+async fn check_router_http_admin(ip: Ipv4Addr, port: u16) -> Option<ExposureFinding> {
+    let mut stream = connect(ip, port).await?;
+    let request = format!("GET / HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut body = Vec::new();
+    let _ = timeout(CHECK_TIMEOUT, stream.read_to_end(&mut body)).await;
+    let response = String::from_utf8_lossy(&body);
+
+    if looks_like_admin_panel(&response) {
+        let status_line = response.lines().next().unwrap_or_default().to_string();
+        Some(ExposureFinding {
+            kind: ExposureKind::RouterHttpAdmin,
+            port,
+            service: "HTTP".to_string(),
+            severity: "MEDIUM".to_string(),
+            evidence: format!("Admin interface over plaintext HTTP ({})", status_line),
+            recommendation: "Restrict the admin interface to HTTPS and to a management VLAN".to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Fetch / over plain HTTP and return the raw response text
+async fn http_get_root(ip: Ipv4Addr, port: u16) -> Option<String> {
+    let mut stream = connect(ip, port).await?;
+    let request = format!("GET / HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut body = Vec::new();
+    let _ = timeout(CHECK_TIMEOUT, stream.read_to_end(&mut body)).await;
+    if body.is_empty() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Printer raw port: JetDirect prints whatever is sent, no authentication exists
+async fn check_printer_raw(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    let _stream = connect(ip, PRINTER_RAW_PORT).await?;
+    Some(ExposureFinding {
+        kind: ExposureKind::PrinterRawPrinting,
+        port: PRINTER_RAW_PORT,
+        service: "JetDirect".to_string(),
+        severity: "MEDIUM".to_string(),
+        evidence: "TCP/9100 accepts raw print jobs".to_string(),
+        recommendation: "Disable raw port 9100 printing or restrict it to the print server; PJL/PostScript over 9100 can read and change printer settings".to_string(),
+    })
+}
+
+/// Printer IPP: the CUPS/IPP web endpoint answering without authentication
+async fn check_printer_ipp(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    let response = http_get_root(ip, PRINTER_IPP_PORT).await?;
+    let lower = response.to_lowercase();
+    if !lower.starts_with("http/1.1 200") && !lower.starts_with("http/1.0 200") {
+        return None;
+    }
+
+    Some(ExposureFinding {
+        kind: ExposureKind::PrinterIppOpen,
+        port: PRINTER_IPP_PORT,
+        service: "IPP".to_string(),
+        severity: "LOW".to_string(),
+        evidence: "IPP/CUPS interface reachable without authentication".to_string(),
+        recommendation: "Require authentication for IPP administration and limit IPP to the client VLAN".to_string(),
+    })
+}
+
+/// Classify an HTTP response as an unprotected printer web UI
+fn looks_like_open_printer_ui(response: &str) -> bool {
+    let lower = response.to_lowercase();
+    let status_ok = lower.starts_with("http/1.1 200") || lower.starts_with("http/1.0 200");
+    let login_required = lower.contains("www-authenticate") || lower.contains("type=\"password\"");
+
+    status_ok && !login_required && PRINTER_WEB_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Printer web UI: embedded web server reachable without a login
+async fn check_printer_web_ui(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    let response = http_get_root(ip, 80).await?;
+    if !looks_like_open_printer_ui(&response) {
+        return None;
+    }
+
+    Some(ExposureFinding {
+        kind: ExposureKind::PrinterWebUiNoAuth,
+        port: 80,
+        service: "HTTP".to_string(),
+        severity: "MEDIUM".to_string(),
+        evidence: "Printer embedded web server opens without a login".to_string(),
+        recommendation: "Set an administrator password on the printer web UI, enable HTTPS and update the printer firmware".to_string(),
+    })
+}
+
+/// Status code of an RTSP response ("RTSP/1.0 200 OK" -> 200)
+fn rtsp_status(response: &[u8]) -> Option<u16> {
+    let text = String::from_utf8_lossy(response);
+    let mut parts = text.split_whitespace();
+    if !parts.next()?.starts_with("RTSP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+/// Send an RTSP DESCRIBE for the root stream, optionally with Basic credentials
+async fn rtsp_describe(ip: Ipv4Addr, credentials: Option<(&str, &str)>) -> Option<u16> {
+    let mut stream = connect(ip, RTSP_PORT).await?;
+    let auth = match credentials {
+        Some((user, pass)) => format!("Authorization: Basic {}\r\n", BASE64.encode(format!("{}:{}", user, pass))),
+        None => String::new(),
+    };
+    let request = format!(
+        "DESCRIBE rtsp://{}:{}/ RTSP/1.0\r\nCSeq: 2\r\nAccept: application/sdp\r\n{}\r\n",
+        ip, RTSP_PORT, auth
+    );
+    stream.write_all(request.as_bytes()).await.ok()?;
+    let response = read_some(&mut stream).await?;
+    rtsp_status(&response)
+}
+
+/// Camera RTSP: stream description available anonymously or with factory credentials
+async fn check_camera_rtsp(ip: Ipv4Addr) -> Option<ExposureFinding> {
+    match rtsp_describe(ip, None).await? {
+        200 => {
+            return Some(ExposureFinding {
+                kind: ExposureKind::CameraRtspNoAuth,
+                port: RTSP_PORT,
+                service: "RTSP".to_string(),
+                severity: "CRITICAL".to_string(),
+                evidence: "DESCRIBE returned 200 without credentials".to_string(),
+                recommendation: "Enable RTSP authentication, set a strong password and keep cameras on an isolated VLAN without internet access".to_string(),
+            });
+        }
+        401 => {}
+        _ => return None,
+    }
+
+    for &(user, pass) in DEFAULT_CAMERA_CREDENTIALS {
+        if rtsp_describe(ip, Some((user, pass))).await == Some(200) {
+            return Some(ExposureFinding {
+                kind: ExposureKind::CameraRtspDefaultCredentials,
+                port: RTSP_PORT,
+                service: "RTSP".to_string(),
+                severity: "CRITICAL".to_string(),
+                evidence: format!("Default credentials accepted ({}/{})", user, if pass.is_empty() { "<empty>" } else { "****" }),
+                recommendation: "Change the camera's default password, disable unused accounts and update the firmware".to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+/// ONVIF GetDeviceInformation request without a WS-Security header
+const ONVIF_DEVICE_INFO_REQUEST: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<s:Envelope xmlns:s=\"http://www.w3.org/2003/05/soap-envelope\">\
+<s:Body><GetDeviceInformation xmlns=\"http://www.onvif.org/ver10/device/wsdl\"/></s:Body>\
+</s:Envelope>";
+
+/// Camera ONVIF: device service answering management calls without authentication
+async fn check_camera_onvif(ip: Ipv4Addr, port: u16) -> Option<ExposureFinding> {
+    let mut stream = connect(ip, port).await?;
+    let request = format!(
+        "POST /onvif/device_service HTTP/1.0\r\nHost: {}\r\nContent-Type: application/soap+xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        ip,
+        ONVIF_DEVICE_INFO_REQUEST.len(),
+        ONVIF_DEVICE_INFO_REQUEST
+    );
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut body = Vec::new();
+    let _ = timeout(CHECK_TIMEOUT, stream.read_to_end(&mut body)).await;
+    let response = String::from_utf8_lossy(&body);
+
+    if response.contains("GetDeviceInformationResponse") {
+        Some(ExposureFinding {
+            kind: ExposureKind::CameraOnvifNoAuth,
+            port,
+            service: "ONVIF".to_string(),
+            severity: "HIGH".to_string(),
+            evidence: "GetDeviceInformation answered without WS-Security credentials".to_string(),
+            recommendation: "Enable ONVIF user authentication (or disable ONVIF if unused) and block camera management ports from client networks".to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Whether a port may be open: found open, or not covered by the port scan
+///
+/// Scan profiles probe different port lists, so a port the scan never tried
+/// gets its own connection attempt rather than being assumed closed.
+fn may_be_open(port: u16, open_ports: &[u16], probed: &[u16]) -> bool {
+    open_ports.contains(&port) || !probed.contains(&port)
+}
+
+/// Run all applicable exposure checks against one host
+async fn check_host(ip: Ipv4Addr, open_ports: Vec<u16>, probed: Vec<u16>, device_type: String) -> Vec<ExposureFinding> {
+    let mut findings = Vec::new();
+
+    let (telnet, ftp) = tokio::join!(
+        async { if may_be_open(TELNET_PORT, &open_ports, &probed) { check_telnet(ip).await } else { None } },
+        async { if may_be_open(FTP_PORT, &open_ports, &probed) { check_ftp_anonymous(ip).await } else { None } }
+    );
+    findings.extend(telnet);
+    findings.extend(ftp);
+
+    for &port in VNC_PORTS.iter().filter(|p| may_be_open(**p, &open_ports, &probed)) {
+        if let Some(f) = check_vnc_noauth(ip, port).await {
+            findings.push(f);
+        }
+    }
+
+    // Without port scan data, fall back to the device classification
+    let probe = |port: u16, kind: &str| {
+        if open_ports.is_empty() { device_type == kind } else { open_ports.contains(&port) }
+    };
+
+    if device_type == "ROUTER" {
+        for &port in ADMIN_HTTP_PORTS {
+            if open_ports.is_empty() || open_ports.contains(&port) {
+                if let Some(f) = check_router_http_admin(ip, port).await {
+                    findings.push(f);
+                }
+            }
+        }
+    }
+
+    if probe(PRINTER_RAW_PORT, "PRINTER") {
+        findings.extend(check_printer_raw(ip).await);
+    }
+    if probe(PRINTER_IPP_PORT, "PRINTER") {
+        findings.extend(check_printer_ipp(ip).await);
+    }
+    if device_type == "PRINTER" || open_ports.contains(&PRINTER_RAW_PORT) {
+        findings.extend(check_printer_web_ui(ip).await);
+    }
+
+    if probe(RTSP_PORT, "CAMERA") {
+        findings.extend(check_camera_rtsp(ip).await);
+    }
+    if device_type == "CAMERA" || open_ports.contains(&RTSP_PORT) {
+        for &port in ONVIF_HTTP_PORTS {
+            if let Some(f) = check_camera_onvif(ip, port).await {
+                findings.push(f);
+                break;
+            }
+        }
+    }
+
+    // Appliances often serve their admin UI on an alternate HTTPS port the scan skipped
+    let admin_interface = ADMIN_DEVICE_TYPES.contains(&device_type.as_str());
+    let tls_ports = TLS_PORTS.iter().filter(|p| open_ports.contains(*p) || (admin_interface && !probed.contains(*p)));
+    for &port in tls_ports {
+        if let Some(report) = tls_assess(ip, port).await {
+            findings.extend(report.findings(admin_interface));
+        }
+    }
+
+    if let Some(finding) = management_plane(ip, &open_ports).await.and_then(|r| r.finding()) {
+        findings.push(finding);
+    }
+
+    // File servers get a share listing even when the scan never probed 445
+    let file_server = matches!(device_type.as_str(), "NAS" | "SERVER");
+    if open_ports.contains(&SMB_PORT) || (file_server && !probed.contains(&SMB_PORT)) {
+        if let Ok(report) = smb_shares(ip).await {
+            findings.extend(report.findings());
+        }
+    }
+
+    findings
+}
+
+/// Check discovered hosts for insecure service exposure
+///
+/// Telnet, FTP and VNC are tried unless the port scan probed the port and
+/// found it closed; the remaining TCP checks follow the open ports or the
+/// device type. SNMP communities are checked by the SNMP phase instead.
+/// Returns findings keyed by host IP (hosts without findings are omitted).
+pub async fn exposure_scan(hosts: &[HostInfo]) -> HashMap<Ipv4Addr, Vec<ExposureFinding>> {
+    log_stderr!("Exposure checks: probing {} hosts for insecure services...", hosts.len());
+
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let results = Arc::new(Mutex::new(HashMap::new()));
+    let mut handles = Vec::new();
+
+    for host in hosts {
+        let ip: Ipv4Addr = match host.ip.parse() {
+            Ok(ip) => ip,
+            Err(_) => continue,
+        };
+        let open_ports = host.open_ports.clone();
+        let probed: Vec<u16> = host.ports.iter().filter(|p| p.transport == Transport::Tcp).map(|p| p.number).collect();
+        let device_type = host.device_type.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let results = Arc::clone(&results);
+
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+            let findings = check_host(ip, open_ports, probed, device_type).await;
+            if !findings.is_empty() {
+                results.lock().await.insert(ip, findings);
+            }
+        }));
+    }
+
+    for handle in handles {
+        let _ = handle.await;
+    }
+
+    let res = results.lock().await;
+    log_stderr!(
+        "Exposure checks complete: {} findings on {} hosts",
+        res.values().map(|v| v.len()).sum::<usize>(),
+        res.len()
+    );
+
+    res.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfb_security_types() {
+        assert!(rfb_offers_no_auth(b"RFB 003.008\n", &[2, 2, 1]));
+        assert!(!rfb_offers_no_auth(b"RFB 003.008\n", &[1, 2]));
+        assert!(!rfb_offers_no_auth(b"RFB 003.008\n", &[0]));
+        assert!(rfb_offers_no_auth(b"RFB 003.003\n", &[0, 0, 0, 1]));
+        assert!(!rfb_offers_no_auth(b"RFB 003.003\n", &[0, 0, 0, 2]));
+    }
+
+    #[test]
+    fn test_admin_panel_detection() {
+        assert!(looks_like_admin_panel("HTTP/1.1 200 OK\r\n\r\n<title>Router Login</title>"));
+        assert!(looks_like_admin_panel("HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"R\"\r\n"));
+        assert!(!looks_like_admin_panel("HTTP/1.1 404 Not Found\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_printer_ui_detection() {
+        assert!(looks_like_open_printer_ui("HTTP/1.1 200 OK\r\n\r\n<title>HP LaserJet M404 - Embedded Web Server</title>"));
+        assert!(!looks_like_open_printer_ui("HTTP/1.1 200 OK\r\n\r\n<title>Brother</title><input type=\"password\">"));
+        assert!(!looks_like_open_printer_ui("HTTP/1.1 200 OK\r\n\r\n<title>Welcome</title>"));
+    }
+
+    #[test]
+    fn test_unprobed_ports_are_tried() {
+        assert!(may_be_open(TELNET_PORT, &[TELNET_PORT], &[TELNET_PORT, 22]));
+        assert!(!may_be_open(TELNET_PORT, &[22], &[TELNET_PORT, 22]));
+        assert!(may_be_open(TELNET_PORT, &[22], &[22, 80, 443]));
+    }
+
+    #[test]
+    fn test_rtsp_status_parsing() {
+        assert_eq!(rtsp_status(b"RTSP/1.0 200 OK\r\nCSeq: 2\r\n"), Some(200));
+        assert_eq!(rtsp_status(b"RTSP/1.0 401 Unauthorized\r\n"), Some(401));
+        assert_eq!(rtsp_status(b"HTTP/1.1 200 OK\r\n"), None);
+    }
+}
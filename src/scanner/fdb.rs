@@ -0,0 +1,313 @@
+//! Switch forwarding database walk (BRIDGE-MIB / Q-BRIDGE-MIB)
+//!
+//! Managed switches expose the MAC addresses they learned per port. Walking
+//! dot1dTpFdbPort (or dot1qTpFdbPort on VLAN-aware switches) and mapping
+//! bridge ports to interface names answers "which port is this device
+//! plugged into?". A MAC is learned on every switch along its path, so the
+//! edge port is the one with the fewest MACs behind it; uplinks carry many.
+
+use snmp2::{AsyncSession, Oid, Value};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use tokio::time::timeout;
+
+use crate::config::{SNMP_COMMUNITY, SNMP_PORT, SNMP_TIMEOUT};
+use crate::models::{HostInfo, SwitchPort};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// sysName.0
+const OID_SYS_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 5, 0];
+/// dot1dTpFdbPort: MAC (6 sub-ids) -> bridge port
+const OID_DOT1D_FDB_PORT: &[u64] = &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 2];
+/// dot1qTpFdbPort: FDB id, MAC (6 sub-ids) -> bridge port
+const OID_DOT1Q_FDB_PORT: &[u64] = &[1, 3, 6, 1, 2, 1, 17, 7, 1, 2, 2, 1, 2];
+/// dot1dBasePortIfIndex: bridge port -> ifIndex
+const OID_BASE_PORT_IFINDEX: &[u64] = &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 2];
+/// ifName: ifIndex -> interface name
+const OID_IF_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 1];
+
+/// Upper bound on rows read per table, protects against looping agents
+const MAX_WALK_ROWS: usize = 8192;
+
+/// One learned MAC address
+#[derive(Debug, Clone, PartialEq)]
+pub struct FdbEntry {
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+    pub bridge_port: i64,
+    /// Interface name, or the bridge port number if unnamed
+    pub port_name: String,
+}
+
+/// Forwarding database of one switch
+#[derive(Debug, Clone)]
+pub struct SwitchFdb {
+    pub switch: Ipv4Addr,
+    pub switch_name: Option<String>,
+    pub entries: Vec<FdbEntry>,
+}
+
+impl SwitchFdb {
+    /// Number of MACs learned per bridge port
+    fn port_load(&self) -> HashMap<i64, usize> {
+        let mut load = HashMap::new();
+        for entry in &self.entries {
+            *load.entry(entry.bridge_port).or_insert(0) += 1;
+        }
+        load
+    }
+}
+
+/// Value of a walked row
+enum WalkValue {
+    Int(i64),
+    Text(String),
+    Other,
+}
+
+/// Walk a table with GETNEXT, returning (index sub-ids, value) rows
+async fn walk(session: &mut AsyncSession, base: &[u64]) -> Vec<(Vec<u64>, WalkValue)> {
+    let mut rows = Vec::new();
+    let mut current = base.to_vec();
+
+    while rows.len() < MAX_WALK_ROWS {
+        let Ok(oid) = Oid::from(&current) else { break };
+        let Ok(Ok(mut response)) = timeout(SNMP_TIMEOUT, session.getnext(&oid)).await else {
+            break;
+        };
+        let Some((name, value)) = response.varbinds.next() else { break };
+        let Some(components) = name.iter().map(|ids| ids.collect::<Vec<u64>>()) else { break };
+        // Left the table, or a broken agent returned a non-increasing OID
+        if !components.starts_with(base) || components <= current {
+            break;
+        }
+
+        let value = match value {
+            Value::Integer(n) => WalkValue::Int(n),
+            Value::OctetString(bytes) => WalkValue::Text(String::from_utf8_lossy(bytes).trim().to_string()),
+            _ => WalkValue::Other,
+        };
+        rows.push((components[base.len()..].to_vec(), value));
+        current = components;
+    }
+    rows
+}
+
+/// MAC address from the last six sub-identifiers of an FDB index
+fn mac_from_index(index: &[u64]) -> Option<String> {
+    let octets = index.get(index.len().checked_sub(6)?..)?;
+    if octets.iter().any(|o| *o > 255) {
+        return None;
+    }
+    Some(
+        octets
+            .iter()
+            .map(|o| format!("{:02X}", o))
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+/// Build FDB entries from the walked port table and interface names
+fn build_entries(
+    fdb_rows: &[(Vec<u64>, WalkValue)],
+    port_ifindex: &HashMap<i64, i64>,
+    if_names: &HashMap<i64, String>,
+) -> Vec<FdbEntry> {
+    let mut entries: Vec<FdbEntry> = fdb_rows
+        .iter()
+        .filter_map(|(index, value)| {
+            let WalkValue::Int(bridge_port) = value else { return None };
+            // Port 0 = learned on the switch itself / unknown
+            if *bridge_port == 0 {
+                return None;
+            }
+            let port_name = port_ifindex
+                .get(bridge_port)
+                .and_then(|ifindex| if_names.get(ifindex))
+                .cloned()
+                .unwrap_or_else(|| bridge_port.to_string());
+            Some(FdbEntry {
+                mac: mac_from_index(index)?,
+                bridge_port: *bridge_port,
+                port_name,
+            })
+        })
+        .collect();
+    // Q-BRIDGE lists a MAC once per VLAN
+    entries.sort_by(|a, b| a.mac.cmp(&b.mac).then(a.bridge_port.cmp(&b.bridge_port)));
+    entries.dedup();
+    entries
+}
+
+/// Read the forwarding database of one switch
+async fn query_switch_fdb(switch: Ipv4Addr) -> Option<SwitchFdb> {
+    let addr = format!("{}:{}", switch, SNMP_PORT);
+    let mut session = match timeout(SNMP_TIMEOUT, AsyncSession::new_v2c(&addr, SNMP_COMMUNITY.as_bytes(), 0)).await {
+        Ok(Ok(s)) => s,
+        _ => return None,
+    };
+
+    let mut fdb_rows = walk(&mut session, OID_DOT1D_FDB_PORT).await;
+    if fdb_rows.is_empty() {
+        fdb_rows = walk(&mut session, OID_DOT1Q_FDB_PORT).await;
+    }
+    if fdb_rows.is_empty() {
+        return None;
+    }
+
+    let port_ifindex: HashMap<i64, i64> = walk(&mut session, OID_BASE_PORT_IFINDEX)
+        .await
+        .into_iter()
+        .filter_map(|(index, value)| match (index.first(), value) {
+            (Some(port), WalkValue::Int(ifindex)) => Some((*port as i64, ifindex)),
+            _ => None,
+        })
+        .collect();
+    let if_names: HashMap<i64, String> = walk(&mut session, OID_IF_NAME)
+        .await
+        .into_iter()
+        .filter_map(|(index, value)| match (index.first(), value) {
+            (Some(ifindex), WalkValue::Text(name)) if !name.is_empty() => Some((*ifindex as i64, name)),
+            _ => None,
+        })
+        .collect();
+
+    let switch_name = match Oid::from(OID_SYS_NAME) {
+        Ok(oid) => match timeout(SNMP_TIMEOUT, session.get(&oid)).await {
+            Ok(Ok(mut response)) => match response.varbinds.next() {
+                Some((_, Value::OctetString(bytes))) => Some(String::from_utf8_lossy(bytes).trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        },
+        Err(_) => None,
+    };
+
+    Some(SwitchFdb {
+        switch,
+        switch_name: switch_name.filter(|n| !n.is_empty()),
+        entries: build_entries(&fdb_rows, &port_ifindex, &if_names),
+    })
+}
+
+/// Walk the forwarding databases of the given switches
+///
+/// Devices without BRIDGE-MIB support are skipped.
+pub async fn fdb_scan(switches: &[Ipv4Addr]) -> Vec<SwitchFdb> {
+    if switches.is_empty() {
+        return Vec::new();
+    }
+    log_stderr!("FDB walk: reading forwarding tables from {} devices...", switches.len());
+
+    let mut tables = Vec::new();
+    for switch in switches {
+        if let Some(fdb) = query_switch_fdb(*switch).await {
+            tables.push(fdb);
+        }
+    }
+
+    log_stderr!(
+        "FDB walk complete: {} switches, {} MAC entries",
+        tables.len(),
+        tables.iter().map(|t| t.entries.len()).sum::<usize>()
+    );
+    tables
+}
+
+/// Attach the edge switch port to every host found in the forwarding tables
+///
+/// When a MAC is learned on several ports (uplinks, trunks, multiple
+/// switches), the port with the fewest learned MACs is the access port.
+pub fn locate_hosts(tables: &[SwitchFdb], hosts: &mut [HostInfo]) {
+    let loads: Vec<HashMap<i64, usize>> = tables.iter().map(|t| t.port_load()).collect();
+
+    for host in hosts.iter_mut() {
+        let best = tables
+            .iter()
+            .zip(&loads)
+            .flat_map(|(table, load)| {
+                table
+                    .entries
+                    .iter()
+                    .filter(|e| e.mac.eq_ignore_ascii_case(&host.mac))
+                    .map(move |e| (table, e, load[&e.bridge_port]))
+            })
+            .min_by_key(|(_, _, load)| *load);
+
+        if let Some((table, entry, _)) = best {
+            host.switch_port = Some(SwitchPort {
+                switch: table.switch.to_string(),
+                switch_name: table.switch_name.clone(),
+                port: entry.port_name.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mac: &str, bridge_port: i64, port_name: &str) -> FdbEntry {
+        FdbEntry {
+            mac: mac.to_string(),
+            bridge_port,
+            port_name: port_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_entries() {
+        let rows = vec![
+            (vec![0, 17, 34, 51, 68, 85], WalkValue::Int(3)),
+            // Q-BRIDGE index: FDB id, then the MAC
+            (vec![10, 170, 187, 204, 0, 0, 1], WalkValue::Int(7)),
+            (vec![0, 0, 0, 0, 0, 1], WalkValue::Int(0)),
+        ];
+        let port_ifindex = HashMap::from([(3, 10003)]);
+        let if_names = HashMap::from([(10003, "Gi1/0/3".to_string())]);
+
+        let entries = build_entries(&rows, &port_ifindex, &if_names);
+        assert_eq!(
+            entries,
+            vec![entry("00:11:22:33:44:55", 3, "Gi1/0/3"), entry("AA:BB:CC:00:00:01", 7, "7")]
+        );
+    }
+
+    #[test]
+    fn test_locate_prefers_edge_port() {
+        let core = SwitchFdb {
+            switch: Ipv4Addr::new(192, 168, 1, 2),
+            switch_name: Some("core".to_string()),
+            entries: vec![
+                entry("AA:00:00:00:00:01", 24, "uplink"),
+                entry("AA:00:00:00:00:02", 24, "uplink"),
+                entry("AA:00:00:00:00:03", 24, "uplink"),
+            ],
+        };
+        let access = SwitchFdb {
+            switch: Ipv4Addr::new(192, 168, 1, 3),
+            switch_name: None,
+            entries: vec![entry("AA:00:00:00:00:01", 5, "port5")],
+        };
+
+        let mut hosts: Vec<HostInfo> = serde_json::from_str(
+            r#"[{"ip": "192.168.1.50", "mac": "aa:00:00:00:00:01", "device_type": "PC", "discovery_method": "ARP", "hostname": null},
+                {"ip": "192.168.1.51", "mac": "AA:00:00:00:00:09", "device_type": "PC", "discovery_method": "ARP", "hostname": null}]"#,
+        )
+        .unwrap();
+        locate_hosts(&[core, access], &mut hosts);
+
+        let port = hosts[0].switch_port.as_ref().unwrap();
+        assert_eq!(port.switch, "192.168.1.3");
+        assert_eq!(port.port, "port5");
+        assert!(hosts[1].switch_port.is_none());
+    }
+}
@@ -0,0 +1,228 @@
+//! IoT firmware version probing
+//!
+//! Cameras, printers and smart plugs rarely run anything that reports a
+//! version over SNMP, but their web interfaces expose it on well-known
+//! read-only pages. Each probe is a single GET of a page the device's own
+//! UI loads, selected by inferred device type so a host only receives the
+//! requests that apply to it. The result feeds the EOL and firmware CVE
+//! checks.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use crate::models::{FirmwareInfo, HostInfo};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Timeout for each connect/read step of a probe
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Upper bound on the response read from a device
+const MAX_RESPONSE_BYTES: u64 = 256 * 1024;
+
+/// Product and version extracted from a response body
+type Parsed = (Option<String>, String);
+
+/// One read-only version endpoint
+struct FirmwareProbe {
+    /// Device types the endpoint is tried on
+    device_types: &'static [&'static str],
+    port: u16,
+    path: &'static str,
+    parse: fn(&str) -> Option<Parsed>,
+}
+
+const PROBES: &[FirmwareProbe] = &[
+    // Hikvision ISAPI
+    FirmwareProbe { device_types: &["CAMERA"], port: 80, path: "/ISAPI/System/deviceInfo", parse: parse_isapi },
+    // Dahua and OEM rebrands
+    FirmwareProbe { device_types: &["CAMERA"], port: 80, path: "/cgi-bin/magicBox.cgi?action=getSoftwareVersion", parse: parse_magicbox },
+    // HP LEDM
+    FirmwareProbe { device_types: &["PRINTER"], port: 80, path: "/DevMgmt/ProductConfigDyn.xml", parse: parse_hp_ledm },
+    // Brother embedded web server
+    FirmwareProbe { device_types: &["PRINTER"], port: 80, path: "/general/information.html", parse: parse_labeled_html },
+    // Shelly Gen1
+    FirmwareProbe { device_types: &["IOT_DEVICE"], port: 80, path: "/shelly", parse: parse_shelly },
+    // Tasmota
+    FirmwareProbe { device_types: &["IOT_DEVICE"], port: 80, path: "/cm?cmnd=Status%202", parse: parse_tasmota },
+];
+
+/// GET a path and return the body of a 200 response
+async fn http_get(ip: Ipv4Addr, port: u16, path: &str) -> Option<String> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    let mut stream = match timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return None,
+    };
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", path, ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut response = Vec::new();
+    let _ = timeout(PROBE_TIMEOUT, (&mut stream).take(MAX_RESPONSE_BYTES).read_to_end(&mut response)).await;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    Some(body.to_string())
+}
+
+/// Text of the first `<tag>` element, ignoring namespace prefixes
+fn xml_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = [format!("<{}>", tag), format!(":{}>", tag)];
+    let start = open.iter().filter_map(|o| xml.find(o.as_str()).map(|i| i + o.len())).min()?;
+    let end = start + xml[start..].find("</")?;
+    Some(xml[start..end].trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Hikvision: `<model>` and `<firmwareVersion>`
+fn parse_isapi(body: &str) -> Option<Parsed> {
+    Some((xml_tag(body, "model"), xml_tag(body, "firmwareVersion")?))
+}
+
+/// Dahua: `version=2.800.0000000.16.R,build:2021-07-19`
+fn parse_magicbox(body: &str) -> Option<Parsed> {
+    let version = body.lines().find_map(|l| l.trim().strip_prefix("version="))?;
+    let version = version.split(",build").next()?.trim();
+    (!version.is_empty()).then(|| (Some("Dahua".to_string()), version.to_string()))
+}
+
+/// HP LEDM: `<dd:MakeAndModel>` and `<dd:Revision>`
+fn parse_hp_ledm(body: &str) -> Option<Parsed> {
+    Some((xml_tag(body, "MakeAndModel"), xml_tag(body, "Revision")?))
+}
+
+/// Status pages listing "Firmware Version" in a table cell
+fn parse_labeled_html(body: &str) -> Option<Parsed> {
+    let after = &body[body.find("Firmware Version")? + "Firmware Version".len()..];
+    // Text nodes sit between '>' and '<'; take the first one holding a version
+    let text = after
+        .split(['<', '>'])
+        .step_by(2)
+        .map(|t| t.trim().trim_start_matches(':').trim())
+        .find(|t| !t.is_empty() && t.chars().any(|c| c.is_ascii_digit()))?;
+    let model = xml_tag(body, "title");
+    Some((model, text.to_string()))
+}
+
+/// Shelly Gen1: `{"type": "SHSW-1", "fw": "20230913-114010/v1.14.0-gcb84623"}`
+fn parse_shelly(body: &str) -> Option<Parsed> {
+    let info: serde_json::Value = serde_json::from_str(body).ok()?;
+    let fw = info.get("fw")?.as_str()?;
+    let version = fw.rsplit_once("/v").map(|(_, v)| v).unwrap_or(fw);
+    let model = info.get("type").and_then(|t| t.as_str()).map(|t| format!("Shelly {}", t));
+    Some((model, version.to_string()))
+}
+
+/// Tasmota: `{"StatusFWR": {"Version": "13.1.0(tasmota)", ...}}`
+fn parse_tasmota(body: &str) -> Option<Parsed> {
+    let status: serde_json::Value = serde_json::from_str(body).ok()?;
+    let version = status.get("StatusFWR")?.get("Version")?.as_str()?;
+    Some((Some("Tasmota".to_string()), version.to_string()))
+}
+
+/// Try the probes that apply to a host; the first answer wins
+async fn probe_host(ip: Ipv4Addr, device_type: &str, open_ports: &[u16]) -> Option<FirmwareInfo> {
+    for probe in PROBES {
+        if !probe.device_types.contains(&device_type) || !open_ports.contains(&probe.port) {
+            continue;
+        }
+        let Some(body) = http_get(ip, probe.port, probe.path).await else { continue };
+        if let Some((product, version)) = (probe.parse)(&body) {
+            return Some(FirmwareInfo {
+                product,
+                version,
+                source: format!("http://{}:{}{}", ip, probe.port, probe.path),
+            });
+        }
+    }
+    None
+}
+
+/// Read firmware versions from recognized IoT devices
+pub async fn firmware_scan(hosts: &[HostInfo]) -> HashMap<Ipv4Addr, FirmwareInfo> {
+    let targets: Vec<&HostInfo> = hosts
+        .iter()
+        .filter(|h| PROBES.iter().any(|p| p.device_types.contains(&h.device_type.as_str())))
+        .collect();
+    if targets.is_empty() {
+        return HashMap::new();
+    }
+    log_stderr!("Firmware probes: querying {} IoT devices...", targets.len());
+
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let results = Arc::new(Mutex::new(HashMap::new()));
+    let mut handles = Vec::new();
+    for host in targets {
+        let Ok(ip) = host.ip.parse::<Ipv4Addr>() else { continue };
+        let (device_type, open_ports) = (host.device_type.clone(), host.open_ports.clone());
+        let semaphore = Arc::clone(&semaphore);
+        let results = Arc::clone(&results);
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+            if let Some(firmware) = probe_host(ip, &device_type, &open_ports).await {
+                results.lock().await.insert(ip, firmware);
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.await;
+    }
+
+    let res = results.lock().await.clone();
+    log_stderr!("Firmware probes complete: {} versions identified", res.len());
+    res
+}
+
+/// Attach probed firmware to hosts
+pub fn apply_firmware(hosts: &mut [HostInfo], firmware: &HashMap<Ipv4Addr, FirmwareInfo>) {
+    for host in hosts.iter_mut() {
+        if let Some(info) = host.ip.parse::<Ipv4Addr>().ok().and_then(|ip| firmware.get(&ip)) {
+            host.firmware = Some(info.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_camera_and_printer_pages() {
+        let isapi = "<?xml version=\"1.0\"?><DeviceInfo><model>DS-2CD2032-I</model>\
+                     <firmwareVersion>V5.4.5</firmwareVersion></DeviceInfo>";
+        assert_eq!(parse_isapi(isapi), Some((Some("DS-2CD2032-I".to_string()), "V5.4.5".to_string())));
+
+        let dahua = "version=2.800.0000000.16.R,build:2021-07-19\r\n";
+        assert_eq!(parse_magicbox(dahua).unwrap().1, "2.800.0000000.16.R");
+
+        let ledm = "<prdcfgdyn:ProductConfigDyn><dd:Version><dd:Revision>TJP1CN1923AR</dd:Revision></dd:Version>\
+                    <dd:MakeAndModel>HP LaserJet Pro M404</dd:MakeAndModel></prdcfgdyn:ProductConfigDyn>";
+        assert_eq!(parse_hp_ledm(ledm).unwrap().0.as_deref(), Some("HP LaserJet Pro M404"));
+
+        let brother = "<title>HL-L2350DW</title><dl><dt>Firmware Version</dt><dd>1.72</dd></dl>";
+        assert_eq!(parse_labeled_html(brother), Some((Some("HL-L2350DW".to_string()), "1.72".to_string())));
+    }
+
+    #[test]
+    fn test_parse_smart_plug_status() {
+        let shelly = r#"{"type": "SHSW-1", "mac": "AABBCC000001", "fw": "20230913-114010/v1.14.0-gcb84623"}"#;
+        assert_eq!(parse_shelly(shelly), Some((Some("Shelly SHSW-1".to_string()), "1.14.0-gcb84623".to_string())));
+
+        let tasmota = r#"{"StatusFWR": {"Version": "13.1.0(tasmota)", "BuildDateTime": "2023-08-14"}}"#;
+        assert_eq!(parse_tasmota(tasmota).unwrap().1, "13.1.0(tasmota)");
+        assert!(parse_tasmota("{}").is_none());
+    }
+}
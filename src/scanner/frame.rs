@@ -0,0 +1,191 @@
+//! Ethernet/ARP frame parsing
+//!
+//! Captured frames are untrusted input: anything on the segment can send a
+//! short, oversized or nonsensical frame. The parser reads the raw slice
+//! with bounds-checked accesses only, so every byte sequence yields either
+//! an `ArpFrame` or a `FrameError` saying what was wrong, never a panic.
+//! It needs no socket or state, which makes `parse_arp_frame` a direct
+//! fuzzing target. The active sweep's receiver and the passive ARP monitor
+//! both parse through it.
+
+use pnet::util::MacAddr;
+use std::fmt;
+use std::net::Ipv4Addr;
+
+/// Ethernet II header: destination, source, ethertype
+const ETHERNET_HEADER_LEN: usize = 14;
+
+/// ARP body for Ethernet/IPv4 (RFC 826 with 6-byte MACs, 4-byte addresses)
+const ARP_BODY_LEN: usize = 28;
+
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ARP_HARDWARE_ETHERNET: u16 = 1;
+const ARP_PROTOCOL_IPV4: u16 = 0x0800;
+
+/// Why a frame is not a usable ARP packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// Frame ends before the header being read
+    Truncated { needed: usize, len: usize },
+    /// Ethernet frame carrying another protocol
+    NotArp { ethertype: u16 },
+    /// ARP for a hardware or protocol type other than Ethernet/IPv4
+    UnsupportedArp { hardware: u16, protocol: u16 },
+    /// Address lengths that do not match the declared types
+    BadAddressLength { hardware: u8, protocol: u8 },
+    /// Operation other than request (1) or reply (2)
+    UnknownOperation(u16),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Truncated { needed, len } => write!(f, "frame truncated: {} of {} bytes", len, needed),
+            FrameError::NotArp { ethertype } => write!(f, "not an ARP frame (ethertype 0x{:04x})", ethertype),
+            FrameError::UnsupportedArp { hardware, protocol } => {
+                write!(f, "unsupported ARP hardware {} / protocol 0x{:04x}", hardware, protocol)
+            }
+            FrameError::BadAddressLength { hardware, protocol } => {
+                write!(f, "bad ARP address lengths {}/{}", hardware, protocol)
+            }
+            FrameError::UnknownOperation(op) => write!(f, "unknown ARP operation {}", op),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// ARP operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArpOperation {
+    Request,
+    Reply,
+}
+
+/// Ethernet/IPv4 ARP packet with the Ethernet source it arrived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArpFrame {
+    /// Ethernet source; differs from `sender_mac` for proxied or spoofed ARP
+    pub source_mac: MacAddr,
+    pub operation: ArpOperation,
+    pub sender_mac: MacAddr,
+    pub sender_ip: Ipv4Addr,
+    pub target_mac: MacAddr,
+    pub target_ip: Ipv4Addr,
+}
+
+/// Bytes `at..at + N` of `frame`, or how many were needed
+fn field<const N: usize>(frame: &[u8], at: usize) -> Result<[u8; N], FrameError> {
+    frame
+        .get(at..at + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(FrameError::Truncated { needed: at + N, len: frame.len() })
+}
+
+fn mac_at(frame: &[u8], at: usize) -> Result<MacAddr, FrameError> {
+    let [a, b, c, d, e, f] = field(frame, at)?;
+    Ok(MacAddr(a, b, c, d, e, f))
+}
+
+fn ipv4_at(frame: &[u8], at: usize) -> Result<Ipv4Addr, FrameError> {
+    field::<4>(frame, at).map(Ipv4Addr::from)
+}
+
+/// Parse an Ethernet frame carrying Ethernet/IPv4 ARP
+///
+/// The ethertype is checked first, so unrelated traffic is rejected after
+/// reading two bytes. Trailing bytes (Ethernet padding) are ignored.
+pub fn parse_arp_frame(frame: &[u8]) -> Result<ArpFrame, FrameError> {
+    let ethertype = u16::from_be_bytes(field(frame, 12)?);
+    if ethertype != ETHERTYPE_ARP {
+        return Err(FrameError::NotArp { ethertype });
+    }
+    if frame.len() < ETHERNET_HEADER_LEN + ARP_BODY_LEN {
+        return Err(FrameError::Truncated {
+            needed: ETHERNET_HEADER_LEN + ARP_BODY_LEN,
+            len: frame.len(),
+        });
+    }
+
+    let arp = &frame[ETHERNET_HEADER_LEN..];
+    let hardware = u16::from_be_bytes(field(arp, 0)?);
+    let protocol = u16::from_be_bytes(field(arp, 2)?);
+    if hardware != ARP_HARDWARE_ETHERNET || protocol != ARP_PROTOCOL_IPV4 {
+        return Err(FrameError::UnsupportedArp { hardware, protocol });
+    }
+    let [hardware_len, protocol_len] = field(arp, 4)?;
+    if (hardware_len, protocol_len) != (6, 4) {
+        return Err(FrameError::BadAddressLength {
+            hardware: hardware_len,
+            protocol: protocol_len,
+        });
+    }
+    let operation = match u16::from_be_bytes(field(arp, 6)?) {
+        1 => ArpOperation::Request,
+        2 => ArpOperation::Reply,
+        op => return Err(FrameError::UnknownOperation(op)),
+    };
+
+    Ok(ArpFrame {
+        source_mac: mac_at(frame, 6)?,
+        operation,
+        sender_mac: mac_at(arp, 8)?,
+        sender_ip: ipv4_at(arp, 14)?,
+        target_mac: mac_at(arp, 18)?,
+        target_ip: ipv4_at(arp, 24)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply_frame() -> Vec<u8> {
+        let mut frame = vec![0xff; 6];
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01, 0x08, 0x06]);
+        frame.extend_from_slice(&[0, 1, 0x08, 0, 6, 4, 0, 2]);
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01, 192, 168, 1, 10]);
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 192, 168, 1, 20]);
+        frame
+    }
+
+    #[test]
+    fn test_parse_arp_frame() {
+        let mut frame = reply_frame();
+        let arp = parse_arp_frame(&frame).unwrap();
+        assert_eq!(arp.operation, ArpOperation::Reply);
+        assert_eq!(arp.sender_mac, MacAddr(0x02, 0, 0, 0, 0, 0x01));
+        assert_eq!(arp.sender_ip, Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(arp.target_ip, Ipv4Addr::new(192, 168, 1, 20));
+
+        // Ethernet padding to the 60-byte minimum is ignored
+        frame.resize(60, 0);
+        assert_eq!(parse_arp_frame(&frame), Ok(arp));
+
+        frame[21] = 9;
+        assert_eq!(parse_arp_frame(&frame), Err(FrameError::UnknownOperation(9)));
+        frame[18] = 8;
+        assert_eq!(parse_arp_frame(&frame), Err(FrameError::BadAddressLength { hardware: 8, protocol: 4 }));
+        frame[12..14].copy_from_slice(&[0x08, 0x00]);
+        assert_eq!(parse_arp_frame(&frame), Err(FrameError::NotArp { ethertype: 0x0800 }));
+        assert_eq!(parse_arp_frame(&frame[..30]), Err(FrameError::NotArp { ethertype: 0x0800 }));
+        assert_eq!(parse_arp_frame(&reply_frame()[..30]), Err(FrameError::Truncated { needed: 42, len: 30 }));
+        assert_eq!(parse_arp_frame(&[]), Err(FrameError::Truncated { needed: 14, len: 0 }));
+    }
+
+    #[test]
+    fn test_malformed_frames_never_panic() {
+        let frame = reply_frame();
+        for len in 0..frame.len() {
+            assert!(parse_arp_frame(&frame[..len]).is_err());
+        }
+        // Cheap deterministic noise: every byte value at every position
+        for at in 0..frame.len() {
+            for byte in 0..=u8::MAX {
+                let mut mutated = frame.clone();
+                mutated[at] = byte;
+                let _ = parse_arp_frame(&mutated);
+            }
+        }
+    }
+}
@@ -1,16 +1,29 @@
-//! ICMP ping scanning with TTL-based OS fingerprinting
+//! ICMP ping scanning with TTL-based OS fingerprinting and latency statistics
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use futures::stream::{self, StreamExt};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::Packet;
+use pnet::transport::{ipv4_packet_iter, transport_channel, TransportChannelType};
 use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread::JoinHandle;
 use std::time::Duration;
-use surge_ping::{Client, Config, PingIdentifier, PingSequence, IcmpPacket};
-use tokio::sync::{Mutex, Semaphore};
-use std::time::Instant;
+use surge_ping::{Client, PingIdentifier, PingSequence, IcmpPacket};
 
-use crate::config::{MAX_CONCURRENT_PINGS, PING_RETRIES, PING_TIMEOUT};
+use super::audit::{audit_probe, ProbeKind};
+use super::marking::{icmp_client, probe_marking};
+use super::limits::concurrency_limits;
+use super::pacer::{shared_pacer, Congestion};
+#[cfg(any(test, feature = "sim"))]
+use super::sim::{sim_backend, SimBackend};
+use crate::config::{ICMP_PAYLOAD_SIZE, PING_RETRIES, PING_SAMPLES, PING_SAMPLE_INTERVAL, PING_TIMEOUT};
+use crate::models::LatencyStats;
 
 /// Logs a message to stderr
 macro_rules! log_stderr {
@@ -26,11 +39,158 @@ macro_rules! log_warn {
     };
 }
 
+/// ICMP type 3 codes meaning the packet was administratively prohibited
+const PROHIBITED_CODES: &[u8] = &[9, 10, 13];
+
+/// Largest echo payload an IPv4 datagram can carry (65535 - 20 IP - 8 ICMP)
+const MAX_ICMP_PAYLOAD: usize = 65_507;
+
+/// Sets the echo payload for the CLI as `<size>[:<pattern>]`, see `IcmpPayload`
+pub const ICMP_PAYLOAD_ENV: &str = "HOST_DISCOVERY_ICMP_PAYLOAD";
+
+/// How the echo payload is filled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadPattern {
+    Zeros,
+    /// Every byte set to one value
+    Byte(u8),
+    /// 0x00, 0x01, ... 0xff, 0x00, ... (like BSD ping)
+    Incrementing,
+    /// Text repeated over the payload, to spot the probes in a capture
+    Text(String),
+}
+
+/// Echo request payload: size and fill pattern
+///
+/// Defaults to the 56 zero bytes of a classic `ping`. Sizes near the link
+/// MTU (1472 bytes on Ethernet) test whether full-size frames get through.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IcmpPayload {
+    pub size: usize,
+    pub pattern: PayloadPattern,
+}
+
+impl IcmpPayload {
+    pub fn new(size: usize, pattern: PayloadPattern) -> Result<Self> {
+        if size > MAX_ICMP_PAYLOAD {
+            return Err(anyhow!("ICMP payload of {} bytes exceeds the {} byte maximum", size, MAX_ICMP_PAYLOAD));
+        }
+        if matches!(&pattern, PayloadPattern::Text(text) if text.is_empty()) {
+            return Err(anyhow!("ICMP payload text must not be empty"));
+        }
+        Ok(Self { size, pattern })
+    }
+
+    /// Payload bytes as sent
+    pub fn bytes(&self) -> Vec<u8> {
+        match &self.pattern {
+            PayloadPattern::Zeros => vec![0; self.size],
+            PayloadPattern::Byte(b) => vec![*b; self.size],
+            PayloadPattern::Incrementing => (0..self.size).map(|i| i as u8).collect(),
+            PayloadPattern::Text(text) => text.bytes().cycle().take(self.size).collect(),
+        }
+    }
+}
+
+impl Default for IcmpPayload {
+    fn default() -> Self {
+        Self { size: ICMP_PAYLOAD_SIZE, pattern: PayloadPattern::Zeros }
+    }
+}
+
+impl FromStr for IcmpPayload {
+    type Err = anyhow::Error;
+
+    /// `<size>[:<pattern>]`, pattern `zeros`, `incrementing`, a byte such as
+    /// `0xa5`, or any other text to repeat (e.g. `1472:0xff`, `64:netscan`)
+    fn from_str(s: &str) -> Result<Self> {
+        let (size, pattern) = s.trim().split_once(':').unwrap_or((s.trim(), "zeros"));
+        let size = size.trim().parse().map_err(|_| anyhow!("Invalid ICMP payload size '{}'", size))?;
+        let pattern = match pattern {
+            "zeros" => PayloadPattern::Zeros,
+            "incrementing" => PayloadPattern::Incrementing,
+            hex if hex.starts_with("0x") => PayloadPattern::Byte(
+                u8::from_str_radix(&hex[2..], 16).map_err(|_| anyhow!("Invalid ICMP payload byte '{}'", hex))?,
+            ),
+            text => PayloadPattern::Text(text.to_string()),
+        };
+        Self::new(size, pattern)
+    }
+}
+
+/// ICMP view of a probed host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostState {
+    /// Answered echo requests
+    Up,
+    /// Reported unreachable, or silent with no sign of presence
+    Down,
+    /// Present but ICMP is blocked (administratively prohibited, or seen on
+    /// the link via ARP without answering pings)
+    Filtered,
+}
+
+impl HostState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HostState::Up => "up",
+            HostState::Down => "down",
+            HostState::Filtered => "filtered",
+        }
+    }
+
+    /// Combine the echo outcome with any destination-unreachable received
+    pub fn classify(replied: bool, unreachable_code: Option<u8>, seen_on_link: bool) -> Self {
+        match unreachable_code {
+            _ if replied => HostState::Up,
+            Some(code) if PROHIBITED_CODES.contains(&code) => HostState::Filtered,
+            Some(_) => HostState::Down,
+            None if seen_on_link => HostState::Filtered,
+            None => HostState::Down,
+        }
+    }
+}
+
 /// Result of an ICMP ping including TTL for OS fingerprinting
 #[derive(Debug, Clone)]
 pub struct IcmpResult {
-    pub duration: Duration,
+    pub state: HostState,
+    /// RTT of the first reply (`None` unless the host is up)
+    pub duration: Option<Duration>,
     pub ttl: Option<u8>,
+    /// Statistics over all latency samples taken for the host
+    pub stats: Option<LatencyStats>,
+    /// Echo requests sent, including retries before the first reply
+    pub sent: u32,
+    /// Echo replies received
+    pub received: u32,
+    /// Code of the ICMP destination-unreachable received for the host, if any
+    pub unreachable_code: Option<u8>,
+}
+
+impl IcmpResult {
+    pub fn is_up(&self) -> bool {
+        self.state == HostState::Up
+    }
+
+    /// Percentage of echo requests without a reply (0-100)
+    pub fn loss_percent(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        (self.sent - self.received) as f64 / self.sent as f64 * 100.0
+    }
+}
+
+/// Where echo requests go
+#[derive(Clone)]
+pub(super) enum EchoClient {
+    Socket(Client),
+    /// Answered by the installed simulated network
+    #[cfg(any(test, feature = "sim"))]
+    Sim(Arc<SimBackend>),
 }
 
 /// Generates a random ping identifier
@@ -57,38 +217,150 @@ pub fn guess_os_from_ttl(ttl: u8) -> String {
     }
 }
 
-/// Pings a single IP address with retries, returns duration and TTL
-async fn ping_host_with_retries(client: &Client, ip: Ipv4Addr) -> Option<IcmpResult> {
-    let payload = [0u8; 56];
+/// Pings a host until `samples` replies have been collected
+///
+/// Up to PING_RETRIES requests are sent to get a first reply; an unresponsive
+/// host returns `None`. Once the host has answered, up to `samples` further
+/// lost requests are tolerated before the sampling stops. With more than one
+/// sample the result carries `LatencyStats` over every request sent.
+#[tracing::instrument(name = "ping", skip_all, fields(%ip, samples = samples))]
+pub(super) async fn ping_host_with_retries(
+    client: &EchoClient,
+    ip: Ipv4Addr,
+    samples: u8,
+    payload: &[u8],
+) -> Option<IcmpResult> {
+    #[cfg(any(test, feature = "sim"))]
+    let client = match client {
+        EchoClient::Socket(client) => client,
+        EchoClient::Sim(sim) => return sim.ping(ip, samples),
+    };
+    #[cfg(not(any(test, feature = "sim")))]
+    let EchoClient::Socket(client) = client;
+    let wanted = samples.max(1) as usize;
+    let mut pinger = client.pinger(IpAddr::V4(ip), PingIdentifier(rand_id())).await;
+    pinger.timeout(PING_TIMEOUT);
 
-    for attempt in 0..PING_RETRIES {
-        let start = Instant::now();
-        match client
-            .pinger(IpAddr::V4(ip), PingIdentifier(rand_id()))
-            .await
-            .timeout(PING_TIMEOUT)
-            .ping(PingSequence(attempt as u16), &payload)
-            .await
-        {
-            Ok((packet, _rtt)) => {
+    let mut rtts: Vec<Option<f64>> = Vec::new();
+    let mut first: Option<(Duration, Option<u8>)> = None;
+    let mut received = 0usize;
+    let mut lost = 0usize;
+    let mut seq: u16 = 0;
+
+    while received < wanted {
+        if first.is_some() {
+            tokio::time::sleep(PING_SAMPLE_INTERVAL).await;
+        }
+
+        audit_probe(ip, ProbeKind::Icmp, None);
+        match pinger.ping(PingSequence(seq), payload).await {
+            Ok((packet, rtt)) => {
                 let ttl = match packet {
                     IcmpPacket::V4(p) => p.get_ttl(),
                     IcmpPacket::V6(_) => None,
                 };
-                return Some(IcmpResult {
-                    duration: start.elapsed(),
-                    ttl,
-                });
+                first.get_or_insert((rtt, ttl));
+                rtts.push(Some(rtt.as_secs_f64() * 1000.0));
+                received += 1;
+            }
+            Err(_) => {
+                rtts.push(None);
+                lost += 1;
+                if first.is_none() && lost >= PING_RETRIES.max(1) as usize {
+                    return None;
+                }
+                if first.is_some() && lost > wanted {
+                    break;
+                }
             }
-            Err(_) => continue,
         }
+        seq = seq.wrapping_add(1);
+    }
+
+    let (duration, ttl) = first?;
+    Some(IcmpResult {
+        state: HostState::Up,
+        duration: Some(duration),
+        ttl,
+        stats: if wanted > 1 { LatencyStats::from_samples(&rtts) } else { None },
+        sent: rtts.len() as u32,
+        received: received as u32,
+        unreachable_code: None,
+    })
+}
+
+/// ICMP destination-unreachable about a datagram we sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Unreachable {
+    /// Destination of the original datagram
+    pub dest: Ipv4Addr,
+    /// IP protocol of the original datagram
+    pub protocol: u8,
+    pub code: u8,
+}
+
+/// Parse an IPv4 packet carrying an ICMP destination-unreachable
+pub(super) fn parse_unreachable(packet: &[u8]) -> Option<Unreachable> {
+    let header_len = ((*packet.first()? & 0x0F) as usize) * 4;
+    let icmp = packet.get(header_len..)?;
+    if icmp.len() < 8 || icmp[0] != 3 {
+        return None;
+    }
+
+    // The quoted original datagram starts after the 8-byte ICMP header
+    let original = &icmp[8..];
+    if original.len() < 20 {
+        return None;
+    }
+    Some(Unreachable {
+        dest: Ipv4Addr::new(original[16], original[17], original[18], original[19]),
+        protocol: original[9],
+        code: icmp[1],
+    })
+}
+
+/// Collect destination-unreachable messages until `stop` is set
+///
+/// Needs a raw socket; returns `None` when one cannot be opened, in which case
+/// silent hosts are classified from ARP presence alone.
+pub(super) fn spawn_unreachable_listener(stop: Arc<AtomicBool>) -> Option<JoinHandle<HashMap<Ipv4Addr, u8>>> {
+    #[cfg(any(test, feature = "sim"))]
+    if sim_backend().is_some() {
+        return None;
     }
-    None
+    let protocol = TransportChannelType::Layer3(IpNextHeaderProtocols::Icmp);
+    let (_tx, mut rx) = transport_channel(4096, protocol).ok()?;
+
+    Some(std::thread::spawn(move || {
+        let mut unreachable = HashMap::new();
+        let mut packets = ipv4_packet_iter(&mut rx);
+        while !stop.load(Ordering::Relaxed) {
+            if let Ok(Some((packet, _))) = packets.next_with_timeout(Duration::from_millis(100)) {
+                // Source quench: a router on the path is dropping our probes
+                if packet.payload().first() == Some(&4) {
+                    shared_pacer().report(Congestion::RateLimited);
+                }
+                // Only unreachables about our echo requests
+                if let Some(u) = parse_unreachable(packet.packet()).filter(|u| u.protocol == 1) {
+                    unreachable.insert(u.dest, u.code);
+                }
+            }
+        }
+        unreachable
+    }))
 }
 
 /// Performs ICMP scan on discovered hosts to get response times and TTL
 pub async fn icmp_scan(
     arp_hosts: &HashMap<Ipv4Addr, MacAddr>,
+) -> Result<HashMap<Ipv4Addr, IcmpResult>> {
+    icmp_scan_with_payload(arp_hosts, &IcmpPayload::default()).await
+}
+
+/// ICMP scan with a custom echo payload (e.g. MTU-sized or marked probes)
+pub async fn icmp_scan_with_payload(
+    arp_hosts: &HashMap<Ipv4Addr, MacAddr>,
+    payload: &IcmpPayload,
 ) -> Result<HashMap<Ipv4Addr, IcmpResult>> {
     if arp_hosts.is_empty() {
         return Ok(HashMap::new());
@@ -99,43 +371,127 @@ pub async fn icmp_scan(
         arp_hosts.len()
     );
 
-    let config = Config::default();
-    let client = match Client::new(&config) {
-        Ok(c) => Arc::new(c),
+    let client = match icmp_client(probe_marking()) {
+        Ok(c) => c,
         Err(e) => {
             log_warn!("ICMP client unavailable ({}), skipping latency measurement", e);
             return Ok(HashMap::new());
         }
     };
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_PINGS));
-    let results = Arc::new(Mutex::new(HashMap::new()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let listener = spawn_unreachable_listener(Arc::clone(&stop));
 
-    let mut handles = Vec::new();
+    // A bounded stream keeps at most `pings` probes in flight without a task
+    // per host; dropping the returned future cancels every outstanding ping
+    let client = &client;
+    let payload = &payload.bytes();
+    let replies: HashMap<Ipv4Addr, IcmpResult> = stream::iter(arp_hosts.keys().copied())
+        .map(|ip| async move { ping_host_with_retries(client, ip, PING_SAMPLES, payload).await.map(|r| (ip, r)) })
+        .buffer_unordered(concurrency_limits().pings)
+        .filter_map(|result| async move { result })
+        .collect()
+        .await;
 
-    for &ip in arp_hosts.keys() {
-        let client = Arc::clone(&client);
-        let semaphore = Arc::clone(&semaphore);
-        let results = Arc::clone(&results);
+    stop.store(true, Ordering::Relaxed);
+    Ok(complete_icmp_results(replies, arp_hosts.keys().copied(), listener).await)
+}
 
-        let handle = tokio::spawn(async move {
-            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+/// Stop the unreachable listener and fill in hosts that did not reply
+pub(super) async fn complete_icmp_results(
+    replies: HashMap<Ipv4Addr, IcmpResult>,
+    hosts: impl IntoIterator<Item = Ipv4Addr>,
+    listener: Option<JoinHandle<HashMap<Ipv4Addr, u8>>>,
+) -> HashMap<Ipv4Addr, IcmpResult> {
+    let unreachable = match listener {
+        Some(handle) => tokio::task::spawn_blocking(move || handle.join().unwrap_or_default())
+            .await
+            .unwrap_or_default(),
+        None => HashMap::new(),
+    };
 
-            if let Some(icmp_result) = ping_host_with_retries(&client, ip).await {
-                let mut res = results.lock().await;
-                res.insert(ip, icmp_result);
+    // Every target answered ARP, so silence alone means ICMP is filtered
+    let mut results = replies;
+    for ip in hosts {
+        results.entry(ip).or_insert_with(|| {
+            let code = unreachable.get(&ip).copied();
+            IcmpResult {
+                state: HostState::classify(false, code, true),
+                duration: None,
+                ttl: None,
+                stats: None,
+                sent: PING_RETRIES.max(1) as u32,
+                received: 0,
+                unreachable_code: code,
             }
         });
+    }
 
-        handles.push(handle);
+    let up = results.values().filter(|r| r.is_up()).count();
+    let filtered = results.values().filter(|r| r.state == HostState::Filtered).count();
+    log_stderr!(
+        "Phase 2 complete: {} hosts responded to ICMP, {} filtered, {} down",
+        up,
+        filtered,
+        results.len() - up - filtered
+    );
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_packet(code: u8, original_dest: [u8; 4], original_protocol: u8) -> Vec<u8> {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet.extend_from_slice(&[3, code, 0, 0, 0, 0, 0, 0]);
+        let mut original = vec![0u8; 28];
+        original[0] = 0x45;
+        original[9] = original_protocol;
+        original[16..20].copy_from_slice(&original_dest);
+        packet.extend_from_slice(&original);
+        packet
     }
 
-    for handle in handles {
-        let _ = handle.await;
+    #[test]
+    fn test_parse_unreachable() {
+        let packet = unreachable_packet(13, [10, 0, 0, 7], 1);
+        assert_eq!(
+            parse_unreachable(&packet),
+            Some(Unreachable {
+                dest: Ipv4Addr::new(10, 0, 0, 7),
+                protocol: 1,
+                code: 13
+            })
+        );
+        assert_eq!(parse_unreachable(&unreachable_packet(1, [10, 0, 0, 7], 6)).unwrap().protocol, 6);
+        assert_eq!(parse_unreachable(&[0x45; 10]), None);
     }
 
-    let res = results.lock().await;
-    log_stderr!("Phase 2 complete: {} hosts responded to ICMP", res.len());
+    #[test]
+    fn test_host_state_classification() {
+        assert_eq!(HostState::classify(true, None, true), HostState::Up);
+        assert_eq!(HostState::classify(false, Some(13), false), HostState::Filtered);
+        assert_eq!(HostState::classify(false, Some(1), false), HostState::Down);
+        assert_eq!(HostState::classify(false, None, true), HostState::Filtered);
+        assert_eq!(HostState::classify(false, None, false), HostState::Down);
+    }
 
-    Ok(res.clone())
+    #[test]
+    fn test_icmp_payload() {
+        assert_eq!(IcmpPayload::default().bytes(), vec![0u8; 56]);
+
+        let mtu: IcmpPayload = "1472:0xa5".parse().unwrap();
+        assert_eq!(mtu.bytes().len(), 1472);
+        assert!(mtu.bytes().iter().all(|b| *b == 0xa5));
+
+        assert_eq!("300:incrementing".parse::<IcmpPayload>().unwrap().bytes()[257], 1);
+        assert_eq!("7:scan".parse::<IcmpPayload>().unwrap().bytes(), b"scanscan"[..7].to_vec());
+
+        assert!("70000".parse::<IcmpPayload>().is_err());
+        assert!("64:0xzz".parse::<IcmpPayload>().is_err());
+        assert!("big".parse::<IcmpPayload>().is_err());
+    }
 }
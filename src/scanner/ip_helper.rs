@@ -0,0 +1,85 @@
+//! ARP resolution through the Windows IP Helper API
+//!
+//! Sending and capturing raw frames on Windows needs Npcap. Without it the
+//! OS can still be asked to resolve addresses: `SendARP` broadcasts a
+//! request from the kernel's own stack and blocks until the host answers or
+//! about three seconds pass. That is far slower than the raw sweep and one
+//! blocked thread per request, but works on a stock install.
+
+use pnet::util::MacAddr;
+use std::ffi::c_void;
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use super::audit::{audit_probe, ProbeKind};
+use super::pacer::shared_pacer;
+
+/// `SendARP` calls in flight, each blocking its own thread
+const SEND_ARP_THREADS: usize = 64;
+
+/// `NO_ERROR` from the Win32 API
+const NO_ERROR: u32 = 0;
+
+#[link(name = "iphlpapi")]
+extern "system" {
+    /// `IPAddr` arguments are IPv4 addresses in network byte order
+    fn SendARP(dest_ip: u32, src_ip: u32, mac_addr: *mut c_void, phy_addr_len: *mut u32) -> u32;
+}
+
+/// Resolve `target` from local address `source`, `None` if it did not answer
+fn send_arp(target: Ipv4Addr, source: Ipv4Addr) -> Option<MacAddr> {
+    // SendARP writes up to 8 bytes even for 6-byte Ethernet addresses
+    let mut mac = [0u8; 8];
+    let mut length = mac.len() as u32;
+    let result = unsafe {
+        SendARP(
+            u32::from_ne_bytes(target.octets()),
+            u32::from_ne_bytes(source.octets()),
+            mac.as_mut_ptr().cast(),
+            &mut length,
+        )
+    };
+    if result != NO_ERROR || length < 6 {
+        return None;
+    }
+    Some(MacAddr::new(mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]))
+}
+
+/// Resolve every target, `SEND_ARP_THREADS` at a time
+///
+/// `on_reply` runs on the calling thread with each answering host and the
+/// time its request took, in the order replies arrive.
+pub(super) fn send_arp_sweep(
+    targets: &[Ipv4Addr],
+    source_for: impl Fn(Ipv4Addr) -> Ipv4Addr + Sync,
+    mut on_reply: impl FnMut(Ipv4Addr, MacAddr, Duration),
+) {
+    let next = AtomicUsize::new(0);
+    let (reply_tx, reply_rx) = mpsc::channel::<(Ipv4Addr, MacAddr, Duration)>();
+
+    std::thread::scope(|scope| {
+        for _ in 0..SEND_ARP_THREADS.min(targets.len()) {
+            let reply_tx = reply_tx.clone();
+            let (next, source_for) = (&next, &source_for);
+            scope.spawn(move || {
+                while let Some(&target) = targets.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    shared_pacer().acquire(1);
+                    audit_probe(target, ProbeKind::Arp, None);
+                    let started = Instant::now();
+                    if let Some(mac) = send_arp(target, source_for(target)) {
+                        if reply_tx.send((target, mac, started.elapsed())).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+        // Workers hold the remaining senders; the loop ends when all are done
+        drop(reply_tx);
+        for (ip, mac, latency) in reply_rx {
+            on_reply(ip, mac, latency);
+        }
+    });
+}
@@ -0,0 +1,312 @@
+//! IP protocol scan
+//!
+//! Sends bare IPv4 datagrams with each protocol number of interest and
+//! watches for ICMP "protocol unreachable" replies. Hosts that reject most
+//! protocols but not GRE/ESP/AH reveal VPN concentrators, and routing
+//! protocols (OSPF, EIGRP, VRRP) reveal routers, even when no TCP or UDP
+//! service is exposed. Requires raw socket privileges.
+
+use anyhow::{anyhow, Result};
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::{self, Ipv4Packet, MutableIpv4Packet};
+use pnet::packet::Packet;
+use pnet::transport::{ipv4_packet_iter, transport_channel, TransportChannelType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use super::audit::{audit_probe, ProbeKind};
+use super::icmp::parse_unreachable;
+use super::pacer::{is_send_congestion, shared_pacer, Congestion};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// ICMP destination-unreachable code for "protocol unreachable"
+const PROTOCOL_UNREACHABLE: u8 = 2;
+
+/// How long to wait for responses after each send round
+const PROTOCOL_SCAN_WAIT: Duration = Duration::from_secs(1);
+
+/// Send rounds; hosts rate-limit ICMP errors, so silent protocols are retried
+const PROTOCOL_SCAN_ROUNDS: u8 = 2;
+
+/// Protocols probed when the caller passes none
+pub const DEFAULT_SCAN_PROTOCOLS: &[u8] = &[1, 2, 6, 17, 41, 47, 50, 51, 88, 89, 103, 112, 115, 132];
+
+/// Protocols whose presence suggests a VPN endpoint
+const VPN_PROTOCOLS: &[u8] = &[47, 50, 51, 115];
+
+/// Protocols whose presence suggests a router
+const ROUTING_PROTOCOLS: &[u8] = &[88, 89, 103, 112];
+
+/// Common name of an IP protocol number
+pub fn protocol_name(number: u8) -> &'static str {
+    match number {
+        1 => "ICMP",
+        2 => "IGMP",
+        6 => "TCP",
+        17 => "UDP",
+        41 => "IPv6-in-IPv4",
+        47 => "GRE",
+        50 => "ESP",
+        51 => "AH",
+        88 => "EIGRP",
+        89 => "OSPF",
+        103 => "PIM",
+        112 => "VRRP",
+        115 => "L2TP",
+        132 => "SCTP",
+        _ => "unknown",
+    }
+}
+
+/// Whether a host handles an IP protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtocolState {
+    /// The host answered in the protocol (ICMP echo reply)
+    Open,
+    /// ICMP protocol unreachable received
+    Closed,
+    /// ICMP administratively prohibited / other unreachable received
+    Filtered,
+    /// No response: supported, or dropped on the way
+    OpenFiltered,
+}
+
+impl ProtocolState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtocolState::Open => "open",
+            ProtocolState::Closed => "closed",
+            ProtocolState::Filtered => "filtered",
+            ProtocolState::OpenFiltered => "open|filtered",
+        }
+    }
+
+    fn from_unreachable(code: u8) -> Self {
+        // Any other unreachable (administratively prohibited, host
+        // unreachable) comes from a filter rather than the host's stack
+        if code == PROTOCOL_UNREACHABLE {
+            ProtocolState::Closed
+        } else {
+            ProtocolState::Filtered
+        }
+    }
+}
+
+/// State of one protocol on the target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolProbe {
+    pub number: u8,
+    pub name: String,
+    pub state: ProtocolState,
+}
+
+/// IP protocol scan result for one host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolScanResult {
+    pub target: Ipv4Addr,
+    pub protocols: Vec<ProtocolProbe>,
+}
+
+impl ProtocolScanResult {
+    fn supported(&self, number: u8) -> bool {
+        self.protocols
+            .iter()
+            .any(|p| p.number == number && matches!(p.state, ProtocolState::Open | ProtocolState::OpenFiltered))
+    }
+
+    /// Device roles suggested by the supported protocols
+    ///
+    /// Only conclusive when the host rejects some protocols: a host that
+    /// answers nothing leaves every protocol open|filtered.
+    pub fn role_hints(&self) -> Vec<String> {
+        if !self.protocols.iter().any(|p| p.state == ProtocolState::Closed) {
+            return Vec::new();
+        }
+
+        let mut hints = Vec::new();
+        let vpn: Vec<&str> = VPN_PROTOCOLS
+            .iter()
+            .filter(|n| self.supported(**n))
+            .map(|n| protocol_name(*n))
+            .collect();
+        if !vpn.is_empty() {
+            hints.push(format!("VPN endpoint ({})", vpn.join(", ")));
+        }
+        let routing: Vec<&str> = ROUTING_PROTOCOLS
+            .iter()
+            .filter(|n| self.supported(**n))
+            .map(|n| protocol_name(*n))
+            .collect();
+        if !routing.is_empty() {
+            hints.push(format!("Router ({})", routing.join(", ")));
+        }
+        hints
+    }
+}
+
+/// Build an IPv4 datagram for `protocol` (ICMP probes carry an echo request)
+fn build_datagram(source: Ipv4Addr, target: Ipv4Addr, protocol: u8, ident: u16) -> Vec<u8> {
+    let payload: Vec<u8> = if protocol == 1 {
+        let mut echo = vec![8, 0, 0, 0, (ident >> 8) as u8, ident as u8, 0, 1];
+        let sum: u32 = echo.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]]) as u32).sum();
+        let checksum = !((sum & 0xFFFF) + (sum >> 16)) as u16;
+        echo[2..4].copy_from_slice(&checksum.to_be_bytes());
+        echo
+    } else {
+        Vec::new()
+    };
+
+    let mut buffer = vec![0u8; 20 + payload.len()];
+    {
+        let mut ip = MutableIpv4Packet::new(&mut buffer).expect("buffer holds an IPv4 header");
+        ip.set_version(4);
+        ip.set_header_length(5);
+        ip.set_total_length((20 + payload.len()) as u16);
+        ip.set_identification(ident);
+        ip.set_ttl(64);
+        ip.set_next_level_protocol(IpNextHeaderProtocol::new(protocol));
+        ip.set_source(source);
+        ip.set_destination(target);
+        ip.set_payload(&payload);
+        let checksum = ipv4::checksum(&ip.to_immutable());
+        ip.set_checksum(checksum);
+    }
+    buffer
+}
+
+/// Enumerate the IP protocols `target` handles (blocking)
+///
+/// An empty `protocols` list probes `DEFAULT_SCAN_PROTOCOLS`.
+pub fn ip_protocol_scan(source: Ipv4Addr, target: Ipv4Addr, protocols: &[u8]) -> Result<ProtocolScanResult> {
+    let protocols = if protocols.is_empty() { DEFAULT_SCAN_PROTOCOLS } else { protocols };
+    let (mut tx, _) = transport_channel(4096, TransportChannelType::Layer3(IpNextHeaderProtocols::Reserved))
+        .map_err(|e| anyhow!("Raw IP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+    let (_, mut icmp_rx) = transport_channel(4096, TransportChannelType::Layer3(IpNextHeaderProtocols::Icmp))
+        .map_err(|e| anyhow!("Raw ICMP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+
+    log_stderr!("IP protocol scan: {} protocols on {}...", protocols.len(), target);
+
+    let ident = std::process::id() as u16;
+    let mut states: HashMap<u8, ProtocolState> = HashMap::new();
+    let mut packets = ipv4_packet_iter(&mut icmp_rx);
+    let pacer = shared_pacer();
+
+    for round in 0..PROTOCOL_SCAN_ROUNDS {
+        let pending: Vec<u8> = protocols.iter().copied().filter(|p| !states.contains_key(p)).collect();
+        if pending.is_empty() {
+            break;
+        }
+
+        for &protocol in &pending {
+            let datagram = build_datagram(source, target, protocol, ident);
+            pacer.acquire(1);
+            let packet = Ipv4Packet::new(&datagram).expect("datagram holds an IPv4 header");
+            audit_probe(target, ProbeKind::IpProtocol, Some(protocol as u16));
+            match tx.send_to(packet, IpAddr::V4(target)) {
+                Ok(_) => {}
+                Err(e) if is_send_congestion(&e) => pacer.report(Congestion::SendError),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let answered_before = states.len();
+
+        let deadline = Instant::now() + PROTOCOL_SCAN_WAIT;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let (packet, from) = match packets.next_with_timeout(remaining) {
+                Ok(Some(received)) => received,
+                Ok(None) => break,
+                Err(e) => return Err(e.into()),
+            };
+            if from != IpAddr::V4(target) {
+                continue;
+            }
+
+            let icmp = packet.payload();
+            if icmp.first() == Some(&0) {
+                // Echo reply: ICMP itself is supported
+                states.insert(1, ProtocolState::Open);
+            } else if let Some(u) = parse_unreachable(packet.packet()).filter(|u| u.dest == target) {
+                states.entry(u.protocol).or_insert(ProtocolState::from_unreachable(u.code));
+            }
+        }
+
+        // Unreachables that only come back on a retry are being rate limited
+        if round > 0 && states.len() > answered_before {
+            pacer.report(Congestion::RateLimited);
+        }
+    }
+
+    let result = ProtocolScanResult {
+        target,
+        protocols: protocols
+            .iter()
+            .map(|&number| ProtocolProbe {
+                number,
+                name: protocol_name(number).to_string(),
+                state: states.get(&number).copied().unwrap_or(ProtocolState::OpenFiltered),
+            })
+            .collect(),
+    };
+
+    let closed = result.protocols.iter().filter(|p| p.state == ProtocolState::Closed).count();
+    log_stderr!(
+        "IP protocol scan complete: {} of {} protocols rejected by {}",
+        closed,
+        protocols.len(),
+        target
+    );
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(number: u8, state: ProtocolState) -> ProtocolProbe {
+        ProtocolProbe {
+            number,
+            name: protocol_name(number).to_string(),
+            state,
+        }
+    }
+
+    #[test]
+    fn test_role_hints() {
+        let concentrator = ProtocolScanResult {
+            target: Ipv4Addr::new(10, 0, 0, 1),
+            protocols: vec![
+                probe(50, ProtocolState::OpenFiltered),
+                probe(47, ProtocolState::Closed),
+                probe(89, ProtocolState::Closed),
+            ],
+        };
+        assert_eq!(concentrator.role_hints(), vec!["VPN endpoint (ESP)".to_string()]);
+
+        // Nothing rejected: inconclusive
+        let silent = ProtocolScanResult {
+            target: Ipv4Addr::new(10, 0, 0, 2),
+            protocols: vec![probe(50, ProtocolState::OpenFiltered)],
+        };
+        assert!(silent.role_hints().is_empty());
+    }
+
+    #[test]
+    fn test_build_datagram() {
+        let datagram = build_datagram(Ipv4Addr::new(10, 0, 0, 9), Ipv4Addr::new(10, 0, 0, 1), 47, 7);
+        let ip = Ipv4Packet::new(&datagram).unwrap();
+        assert_eq!(ip.get_next_level_protocol(), IpNextHeaderProtocols::Gre);
+        assert_eq!(ip.get_total_length(), 20);
+        assert_eq!(ip.get_checksum(), ipv4::checksum(&ip));
+        assert_eq!(ProtocolState::from_unreachable(PROTOCOL_UNREACHABLE), ProtocolState::Closed);
+    }
+}
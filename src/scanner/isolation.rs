@@ -0,0 +1,267 @@
+//! Wireless client isolation check
+//!
+//! Run from a client on a guest SSID: verifies that other guest clients and the
+//! main LAN are unreachable. A TCP RST (connection refused) proves reachability
+//! just as well as an accepted connection, so both count as a failure.
+
+use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use crate::config::TCP_PROBE_TIMEOUT;
+use crate::insights::{Priority, Recommendation};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Ports tried when testing reachability of a target
+const ISOLATION_PROBE_PORTS: &[u16] = &[22, 53, 80, 443, 445, 8080];
+
+/// Outcome of a single TCP reachability attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    /// Connection accepted
+    Open,
+    /// Connection refused (RST received) - host is reachable
+    Refused,
+    /// No answer within the timeout
+    Silent,
+}
+
+impl Reachability {
+    pub fn is_reachable(&self) -> bool {
+        !matches!(self, Reachability::Silent)
+    }
+}
+
+/// Reachability evidence for one target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsolationProbe {
+    pub target: Ipv4Addr,
+    /// Answered ARP, i.e. shares the layer-2 segment with the scanner
+    pub arp_reachable: bool,
+    /// First port that produced a TCP answer, if any
+    pub tcp_port: Option<u16>,
+    pub tcp_result: Reachability,
+}
+
+impl IsolationProbe {
+    pub fn is_reachable(&self) -> bool {
+        self.arp_reachable || self.tcp_result.is_reachable()
+    }
+}
+
+/// Pass/fail result of the isolation check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsolationReport {
+    pub peers_tested: usize,
+    /// Other guest clients that could be reached
+    pub reachable_peers: Vec<IsolationProbe>,
+    pub lan_tested: usize,
+    /// Main LAN targets that could be reached
+    pub reachable_lan: Vec<IsolationProbe>,
+    pub passed: bool,
+    pub summary: String,
+}
+
+impl IsolationReport {
+    fn from_probes(peers: Vec<IsolationProbe>, lan: Vec<IsolationProbe>) -> Self {
+        let peers_tested = peers.len();
+        let lan_tested = lan.len();
+        let reachable_peers: Vec<IsolationProbe> = peers.into_iter().filter(|p| p.is_reachable()).collect();
+        let reachable_lan: Vec<IsolationProbe> = lan.into_iter().filter(|p| p.is_reachable()).collect();
+        let passed = reachable_peers.is_empty() && reachable_lan.is_empty();
+
+        let summary = if passed {
+            format!(
+                "✅ Client isolation PASS: {} guest client(s) and {} LAN target(s) unreachable",
+                peers_tested, lan_tested
+            )
+        } else {
+            format!(
+                "❌ Client isolation FAIL: {}/{} guest client(s) and {}/{} LAN target(s) reachable",
+                reachable_peers.len(),
+                peers_tested,
+                reachable_lan.len(),
+                lan_tested
+            )
+        };
+
+        Self {
+            peers_tested,
+            reachable_peers,
+            lan_tested,
+            reachable_lan,
+            passed,
+            summary,
+        }
+    }
+
+    /// Insight for a failed check
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        if self.passed {
+            return None;
+        }
+
+        let mut advice = Vec::new();
+        if !self.reachable_peers.is_empty() {
+            advice.push("enable client (AP) isolation on the guest SSID");
+        }
+        if !self.reachable_lan.is_empty() {
+            advice.push("put the guest SSID on its own VLAN and block guest-to-LAN traffic on the router");
+        }
+
+        Some(Recommendation {
+            priority: if self.reachable_lan.is_empty() { Priority::Medium } else { Priority::High },
+            category: "Wireless Isolation".to_string(),
+            title: "Guest network is not isolated".to_string(),
+            description: format!("{}. To fix: {}.", self.summary, advice.join(" and ")),
+            affected_devices: self
+                .reachable_peers
+                .iter()
+                .chain(&self.reachable_lan)
+                .map(|p| p.target.to_string())
+                .collect(),
+        })
+    }
+}
+
+/// Classify the result of a TCP connect attempt
+fn classify_connect<T>(result: Result<std::io::Result<T>, tokio::time::error::Elapsed>) -> Reachability {
+    match result {
+        Ok(Ok(_)) => Reachability::Open,
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Reachability::Refused,
+        _ => Reachability::Silent,
+    }
+}
+
+/// Try each probe port until one answers
+async fn probe_target(ip: Ipv4Addr, arp_reachable: bool) -> IsolationProbe {
+    for &port in ISOLATION_PROBE_PORTS {
+        let addr = SocketAddr::new(IpAddr::V4(ip), port);
+        let result = classify_connect(timeout(TCP_PROBE_TIMEOUT, TcpStream::connect(addr)).await);
+        if result.is_reachable() {
+            return IsolationProbe {
+                target: ip,
+                arp_reachable,
+                tcp_port: Some(port),
+                tcp_result: result,
+            };
+        }
+    }
+
+    IsolationProbe {
+        target: ip,
+        arp_reachable,
+        tcp_port: None,
+        tcp_result: Reachability::Silent,
+    }
+}
+
+/// Check client isolation on a guest network
+///
+/// `guest_hosts` are ARP scan results from the guest subnet (the gateway and
+/// the scanner itself are excluded as peers); `lan_targets` are addresses on
+/// the main LAN that must not be reachable from the guest network.
+pub async fn isolation_check(
+    guest_hosts: &HashMap<Ipv4Addr, MacAddr>,
+    gateway: Ipv4Addr,
+    local_ip: Ipv4Addr,
+    lan_targets: &[Ipv4Addr],
+) -> IsolationReport {
+    let peers: Vec<Ipv4Addr> = guest_hosts
+        .keys()
+        .copied()
+        .filter(|ip| *ip != gateway && *ip != local_ip)
+        .collect();
+
+    log_stderr!(
+        "Isolation check: probing {} guest peers and {} LAN targets...",
+        peers.len(),
+        lan_targets.len()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let results: Arc<Mutex<Vec<(bool, IsolationProbe)>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut handles = Vec::new();
+
+    // An AP doing proxy ARP answers for every client with its own MAC; such
+    // replies do not prove the peer itself is reachable
+    let gateway_mac = guest_hosts.get(&gateway).copied();
+    let targets: Vec<(Ipv4Addr, bool, bool)> = peers
+        .iter()
+        .map(|ip| (*ip, true, guest_hosts.get(ip).copied() != gateway_mac))
+        .chain(lan_targets.iter().map(|ip| (*ip, false, false)))
+        .collect();
+
+    for (ip, is_peer, arp_reachable) in targets {
+        let semaphore = Arc::clone(&semaphore);
+        let results = Arc::clone(&results);
+
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+            let probe = probe_target(ip, arp_reachable).await;
+            results.lock().await.push((is_peer, probe));
+        }));
+    }
+
+    for handle in handles {
+        let _ = handle.await;
+    }
+
+    let probes = std::mem::take(&mut *results.lock().await);
+    let (peer_probes, lan_probes): (Vec<_>, Vec<_>) = probes.into_iter().partition(|(is_peer, _)| *is_peer);
+
+    let report = IsolationReport::from_probes(
+        peer_probes.into_iter().map(|(_, p)| p).collect(),
+        lan_probes.into_iter().map(|(_, p)| p).collect(),
+    );
+    log_stderr!("{}", report.summary);
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(last_octet: u8, arp: bool, tcp: Reachability) -> IsolationProbe {
+        IsolationProbe {
+            target: Ipv4Addr::new(192, 168, 50, last_octet),
+            arp_reachable: arp,
+            tcp_port: None,
+            tcp_result: tcp,
+        }
+    }
+
+    #[test]
+    fn test_isolation_pass() {
+        let report = IsolationReport::from_probes(vec![], vec![probe(1, false, Reachability::Silent)]);
+        assert!(report.passed);
+        assert!(report.recommendation().is_none());
+    }
+
+    #[test]
+    fn test_isolation_fail_on_peer_and_lan() {
+        let report = IsolationReport::from_probes(
+            vec![probe(20, true, Reachability::Silent)],
+            vec![probe(1, false, Reachability::Refused)],
+        );
+        assert!(!report.passed);
+        assert_eq!(report.reachable_peers.len(), 1);
+        assert_eq!(report.reachable_lan.len(), 1);
+        assert_eq!(report.recommendation().unwrap().priority, Priority::High);
+    }
+}
@@ -0,0 +1,220 @@
+//! Runtime concurrency limits
+//!
+//! The fixed defaults are too low for /16 scans on a workstation and too high
+//! for constrained embedded hosts, so the limits are sized from the open file
+//! limit, CPU count and available memory at startup. Environment variables or
+//! an explicit `set_concurrency_limits` call take precedence over detection.
+//! The send rate is sized the same way from the interface's link speed.
+
+use std::sync::RwLock;
+
+use crate::config::{MAX_CONCURRENT_PINGS, MAX_CONCURRENT_TCP_CONNECTS, MIN_SEND_RATE_PPS};
+
+/// Overrides the detected ping limit
+pub const PING_LIMIT_ENV: &str = "HOST_DISCOVERY_MAX_PINGS";
+
+/// Overrides the detected TCP connect limit
+pub const TCP_LIMIT_ENV: &str = "HOST_DISCOVERY_MAX_TCP_CONNECTS";
+
+/// File descriptors left for the database, logs, capture sockets, etc.
+const RESERVED_FDS: u64 = 64;
+
+/// Bounds applied to detected limits
+const MIN_LIMIT: usize = 8;
+const MAX_PING_LIMIT: usize = 2048;
+const MAX_TCP_LIMIT: usize = 4096;
+
+/// Pings in flight per CPU core
+const PINGS_PER_CORE: usize = 128;
+
+/// Memory held by one in-flight TCP connect (socket buffers, task state)
+const MEMORY_PER_CONNECT: u64 = 64 * 1024;
+
+/// In-flight probes may use 1/N of the available memory
+const MEMORY_SHARE: u64 = 16;
+
+/// Probes may use 1/N of the link's capacity
+const LINK_SHARE: u64 = 10;
+
+/// Bits on the wire per minimum-size frame (64 B plus preamble and gap)
+const MIN_FRAME_BITS: u64 = 84 * 8;
+
+/// Upper bound of a link-derived send rate
+const MAX_LINK_SEND_RATE_PPS: u32 = 100_000;
+
+/// How many probes each scanner phase may have in flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    /// Concurrent ICMP ping operations
+    pub pings: usize,
+    /// Concurrent TCP connect attempts (each holds a socket)
+    pub tcp_connects: usize,
+}
+
+impl Default for ConcurrencyLimits {
+    fn default() -> Self {
+        Self {
+            pings: MAX_CONCURRENT_PINGS,
+            tcp_connects: MAX_CONCURRENT_TCP_CONNECTS,
+        }
+    }
+}
+
+impl ConcurrencyLimits {
+    /// Size limits from the open file limit, CPU count and available memory,
+    /// then apply env overrides
+    pub fn detect() -> Self {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).ok();
+        let mut limits = Self::from_resources(open_file_limit(), cores, available_memory());
+
+        if let Some(pings) = env_limit(PING_LIMIT_ENV) {
+            limits.pings = pings;
+        }
+        if let Some(tcp) = env_limit(TCP_LIMIT_ENV) {
+            limits.tcp_connects = tcp;
+        }
+        limits
+    }
+
+    /// Derive limits from available file descriptors, cores and memory
+    ///
+    /// Every in-flight TCP connect holds a socket; half the descriptor budget is
+    /// given to TCP so exposure checks and SNMP can run alongside, and a share
+    /// of available memory bounds it on small machines. Pings share a single
+    /// ICMP socket and are bounded by CPU instead.
+    fn from_resources(fd_limit: Option<u64>, cores: Option<usize>, memory: Option<u64>) -> Self {
+        let defaults = Self::default();
+        let fd_budget = fd_limit.map(|limit| (limit.saturating_sub(RESERVED_FDS) / 2) as usize);
+        let memory_budget = memory.map(|bytes| (bytes / MEMORY_SHARE / MEMORY_PER_CONNECT) as usize);
+
+        let tcp_connects = match (fd_budget, memory_budget) {
+            (Some(fds), Some(memory)) => Some(fds.min(memory)),
+            (fds, memory) => fds.or(memory),
+        }
+        .map(|budget| budget.clamp(MIN_LIMIT, MAX_TCP_LIMIT))
+        .unwrap_or(defaults.tcp_connects);
+
+        let mut pings = cores
+            .map(|cores| (cores * PINGS_PER_CORE).clamp(MIN_LIMIT, MAX_PING_LIMIT))
+            .unwrap_or(defaults.pings);
+        if let Some(budget) = fd_budget {
+            pings = pings.min(budget.max(MIN_LIMIT));
+        }
+
+        Self { pings, tcp_connects }
+    }
+}
+
+static LIMITS: RwLock<Option<ConcurrencyLimits>> = RwLock::new(None);
+
+/// Limits used by the scanners (detected on first use)
+pub fn concurrency_limits() -> ConcurrencyLimits {
+    if let Some(limits) = *LIMITS.read().unwrap() {
+        return limits;
+    }
+    *LIMITS.write().unwrap().get_or_insert_with(ConcurrencyLimits::detect)
+}
+
+/// Replace the limits for subsequent scans (zero values are raised to 1)
+pub fn set_concurrency_limits(limits: ConcurrencyLimits) {
+    *LIMITS.write().unwrap() = Some(ConcurrencyLimits {
+        pings: limits.pings.max(1),
+        tcp_connects: limits.tcp_connects.max(1),
+    });
+}
+
+/// Send rate for a link of `speed_mbps`: a share of its capacity in
+/// minimum-size frames, so a scan never saturates a slow uplink and is not
+/// held back on a fast one
+pub fn send_rate_for_link(speed_mbps: u32) -> u32 {
+    let pps = speed_mbps as u64 * 1_000_000 / LINK_SHARE / MIN_FRAME_BITS;
+    (pps.min(MAX_LINK_SEND_RATE_PPS as u64) as u32).max(MIN_SEND_RATE_PPS)
+}
+
+/// Negotiated speed of an interface in Mbps, if the platform reports one
+/// (Wi-Fi and virtual interfaces usually do not)
+#[cfg(target_os = "linux")]
+pub fn link_speed_mbps(interface: &str) -> Option<u32> {
+    let speed = std::fs::read_to_string(format!("/sys/class/net/{}/speed", interface)).ok()?;
+    speed.trim().parse::<i64>().ok().filter(|s| *s > 0).map(|s| s as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn link_speed_mbps(_interface: &str) -> Option<u32> {
+    None
+}
+
+fn env_limit(name: &str) -> Option<usize> {
+    std::env::var(name).ok()?.trim().parse().ok().filter(|n| *n > 0)
+}
+
+/// Soft RLIMIT_NOFILE, if the platform has one
+#[cfg(unix)]
+fn open_file_limit() -> Option<u64> {
+    let mut rlim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlim) } != 0 || rlim.rlim_cur == libc::RLIM_INFINITY {
+        return None;
+    }
+    Some(rlim.rlim_cur)
+}
+
+#[cfg(not(unix))]
+fn open_file_limit() -> Option<u64> {
+    None
+}
+
+/// Memory available to new allocations without swapping (MemAvailable)
+#[cfg(target_os = "linux")]
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_scale_with_resources() {
+        let workstation = ConcurrencyLimits::from_resources(Some(65536), Some(8), Some(16 << 30));
+        assert_eq!(workstation.tcp_connects, MAX_TCP_LIMIT);
+        assert_eq!(workstation.pings, 1024);
+
+        // Default Linux soft limit
+        let desktop = ConcurrencyLimits::from_resources(Some(1024), Some(4), None);
+        assert_eq!(desktop.tcp_connects, 480);
+        assert_eq!(desktop.pings, 480);
+
+        // Embedded host with a tight descriptor limit
+        let embedded = ConcurrencyLimits::from_resources(Some(80), Some(1), None);
+        assert_eq!(embedded.tcp_connects, MIN_LIMIT);
+        assert_eq!(embedded.pings, MIN_LIMIT);
+
+        // Plenty of descriptors but 64 MiB free
+        let small = ConcurrencyLimits::from_resources(Some(65536), Some(2), Some(64 << 20));
+        assert_eq!(small.tcp_connects, 64);
+        assert_eq!(small.pings, 256);
+    }
+
+    #[test]
+    fn test_send_rate_follows_link_speed() {
+        assert_eq!(send_rate_for_link(100), 14_880);
+        assert_eq!(send_rate_for_link(10_000), MAX_LINK_SEND_RATE_PPS);
+        assert_eq!(send_rate_for_link(1), MIN_SEND_RATE_PPS);
+    }
+
+    #[test]
+    fn test_limits_fall_back_to_defaults() {
+        assert_eq!(ConcurrencyLimits::from_resources(None, None, None), ConcurrencyLimits::default());
+    }
+}
@@ -0,0 +1,302 @@
+//! IP header marking of probe packets: DSCP class and TTL
+//!
+//! Switches and Wi-Fi access points may queue traffic by its DSCP class
+//! (voice as EF, video as AF41, bulk as CS1). Marking probes lets a scan
+//! check whether that happens: pinging the same host with EF-marked and
+//! unmarked echo requests and comparing the round trips shows whether the
+//! LAN honors the class under load.
+//!
+//! A TTL override bounds how far probes travel: TTL 1 keeps a scan on the
+//! local segment, larger values stop it a known number of routers out,
+//! which answer with time-exceeded. The traceroute sends through the same
+//! socket setup with its per-hop TTL.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use surge_ping::{Client, Config};
+use tokio::net::{TcpSocket, TcpStream};
+
+use super::audit::{audit_probe, ProbeKind};
+use super::icmp::{ping_host_with_retries, EchoClient, IcmpPayload};
+#[cfg(any(test, feature = "sim"))]
+use super::sim::sim_backend;
+
+/// Sets the DSCP class of all probes for the CLI (`ef`, `af41`, `cs1`, or 0-63)
+pub const PROBE_DSCP_ENV: &str = "HOST_DISCOVERY_DSCP";
+
+/// Sets the IP TTL of all probes for the CLI (1-255)
+pub const PROBE_TTL_ENV: &str = "HOST_DISCOVERY_TTL";
+
+/// Named classes (RFC 4594) and their code points
+const NAMED_CLASSES: &[(&str, u8)] = &[
+    ("be", 0),
+    ("cs1", 8),
+    ("af11", 10),
+    ("af21", 18),
+    ("af31", 26),
+    ("af41", 34),
+    ("cs5", 40),
+    ("ef", 46),
+    ("cs6", 48),
+    ("cs7", 56),
+];
+
+/// Differentiated Services code point (6 bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    pub const BEST_EFFORT: Dscp = Dscp(0);
+    pub const EXPEDITED_FORWARDING: Dscp = Dscp(46);
+
+    pub fn new(value: u8) -> Result<Self> {
+        if value > 63 {
+            return Err(anyhow!("DSCP value {} out of range (0-63)", value));
+        }
+        Ok(Dscp(value))
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// IPv4 ToS byte: DSCP in the upper six bits, ECN bits clear
+    pub fn tos(&self) -> u8 {
+        self.0 << 2
+    }
+}
+
+impl FromStr for Dscp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_ascii_lowercase();
+        if let Some((_, value)) = NAMED_CLASSES.iter().find(|(n, _)| *n == name) {
+            return Ok(Dscp(*value));
+        }
+        let value = name.parse().map_err(|_| anyhow!("Unknown DSCP class '{}'", s))?;
+        Dscp::new(value)
+    }
+}
+
+impl fmt::Display for Dscp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match NAMED_CLASSES.iter().find(|(_, v)| *v == self.0) {
+            Some((name, _)) => f.write_str(&name.to_uppercase()),
+            None => write!(f, "DSCP {}", self.0),
+        }
+    }
+}
+
+/// IP header fields set on outgoing probes; `None` keeps the OS default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ProbeMarking {
+    pub dscp: Option<Dscp>,
+    pub ttl: Option<u8>,
+}
+
+impl ProbeMarking {
+    pub fn is_default(&self) -> bool {
+        self.dscp.is_none() && self.ttl.is_none()
+    }
+
+    /// Same marking with another TTL (traceroute hops)
+    pub fn with_ttl(self, ttl: u8) -> Self {
+        Self { ttl: Some(ttl), ..self }
+    }
+
+    /// Apply the marking to an IPv4 socket
+    #[cfg(unix)]
+    fn apply(&self, socket: &impl std::os::fd::AsRawFd) -> std::io::Result<()> {
+        if let Some(dscp) = self.dscp {
+            set_ip_option(socket, libc::IP_TOS, dscp.tos())?;
+        }
+        if let Some(ttl) = self.ttl {
+            set_ip_option(socket, libc::IP_TTL, ttl)?;
+        }
+        Ok(())
+    }
+
+    /// Windows ignores IP_TOS unless QoS policy allows it; not supported
+    #[cfg(not(unix))]
+    fn apply<T>(&self, _socket: &T) -> std::io::Result<()> {
+        if self.is_default() {
+            return Ok(());
+        }
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "Probe marking needs a Unix platform"))
+    }
+}
+
+/// Set an IPv4 header option on a socket
+#[cfg(unix)]
+fn set_ip_option(socket: &impl std::os::fd::AsRawFd, name: libc::c_int, value: u8) -> std::io::Result<()> {
+    let value = value as libc::c_int;
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            name,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+static PROBE_MARKING: OnceLock<ProbeMarking> = OnceLock::new();
+
+/// Mark every ICMP, TCP and UDP probe with `marking`
+///
+/// Returns `false` if probes were already sent with another setting.
+pub fn init_probe_marking(marking: ProbeMarking) -> bool {
+    PROBE_MARKING.set(marking).is_ok()
+}
+
+/// Marking applied to probes
+pub fn probe_marking() -> ProbeMarking {
+    *PROBE_MARKING.get_or_init(ProbeMarking::default)
+}
+
+/// ICMP client whose echo requests carry `marking`
+pub(super) fn icmp_client(marking: ProbeMarking) -> std::io::Result<EchoClient> {
+    #[cfg(any(test, feature = "sim"))]
+    if let Some(sim) = sim_backend() {
+        return Ok(EchoClient::Sim(sim));
+    }
+    let mut config = Config::builder();
+    if let Some(ttl) = marking.ttl {
+        config = config.ttl(ttl as u32);
+    }
+    let client = Client::new(&config.build())?;
+    let marking = ProbeMarking { ttl: None, ..marking };
+    if !marking.is_default() {
+        // surge-ping's socket hides its fd behind `get_native_sock`
+        let socket = client.get_socket();
+        #[cfg(unix)]
+        // SAFETY: `client` keeps the socket open for the whole borrow
+        let socket = unsafe { std::os::fd::BorrowedFd::borrow_raw(socket.get_native_sock()) };
+        marking.apply(&socket)?;
+    }
+    Ok(EchoClient::Socket(client))
+}
+
+/// TCP connect with the probe marking applied to the SYN
+pub(super) async fn connect_tcp(addr: SocketAddr) -> std::io::Result<TcpStream> {
+    audit_probe(addr.ip(), ProbeKind::TcpConnect, Some(addr.port()));
+    let marking = probe_marking();
+    if marking.is_default() {
+        return TcpStream::connect(addr).await;
+    }
+    let socket = TcpSocket::new_v4()?;
+    marking.apply(&socket)?;
+    socket.connect(addr).await
+}
+
+/// Blocking UDP socket with `marking` applied
+pub(super) fn udp_socket(marking: ProbeMarking) -> std::io::Result<UdpSocket> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    if let Some(ttl) = marking.ttl {
+        socket.set_ttl(ttl as u32)?;
+    }
+    ProbeMarking { ttl: None, ..marking }.apply(&socket)?;
+    Ok(socket)
+}
+
+/// Round trips to one host with and without a DSCP class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QosLatency {
+    pub ip: String,
+    pub dscp: Dscp,
+    /// Median RTT of the marked echo requests
+    pub marked_ms: Option<f64>,
+    /// Median RTT of unmarked (best-effort) echo requests
+    pub best_effort_ms: Option<f64>,
+}
+
+impl QosLatency {
+    /// How much faster marked probes were (positive = the class is prioritized)
+    pub fn advantage_ms(&self) -> Option<f64> {
+        Some(self.best_effort_ms? - self.marked_ms?)
+    }
+}
+
+/// Ping each host with `dscp`-marked and with unmarked echo requests
+///
+/// Samples alternate between the two clients so both see the same load.
+/// Comparisons are only meaningful while the link is busy; an idle LAN
+/// forwards every class immediately.
+pub async fn dscp_latency_comparison(hosts: &[Ipv4Addr], dscp: Dscp, samples: u8) -> Result<Vec<QosLatency>> {
+    let base = probe_marking();
+    let marked = ProbeMarking { dscp: Some(dscp), ..base };
+    let marked = icmp_client(marked).map_err(|e| anyhow!("Cannot mark ICMP probes with {}: {}", dscp, e))?;
+    let unmarked = icmp_client(ProbeMarking { dscp: None, ..base })?;
+    let payload = IcmpPayload::default().bytes();
+
+    let mut results = Vec::with_capacity(hosts.len());
+    for &ip in hosts {
+        let mut marked_rtts = Vec::new();
+        let mut unmarked_rtts = Vec::new();
+        for _ in 0..samples.max(1) {
+            if let Some(rtt) = ping_host_with_retries(&marked, ip, 1, &payload).await.and_then(|r| r.duration) {
+                marked_rtts.push(rtt.as_secs_f64() * 1000.0);
+            }
+            if let Some(rtt) = ping_host_with_retries(&unmarked, ip, 1, &payload).await.and_then(|r| r.duration) {
+                unmarked_rtts.push(rtt.as_secs_f64() * 1000.0);
+            }
+        }
+        results.push(QosLatency {
+            ip: ip.to_string(),
+            dscp,
+            marked_ms: median_ms(&mut marked_rtts),
+            best_effort_ms: median_ms(&mut unmarked_rtts),
+        });
+    }
+    Ok(results)
+}
+
+fn median_ms(samples: &mut [f64]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_by(|a, b| a.total_cmp(b));
+    let mid = samples.len() / 2;
+    Some(if samples.len().is_multiple_of(2) { (samples[mid - 1] + samples[mid]) / 2.0 } else { samples[mid] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dscp() {
+        let ef: Dscp = "EF".parse().unwrap();
+        assert_eq!(ef, Dscp::EXPEDITED_FORWARDING);
+        assert_eq!(ef.tos(), 0xb8);
+        assert_eq!("af41".parse::<Dscp>().unwrap().value(), 34);
+        assert_eq!("10".parse::<Dscp>().unwrap().to_string(), "AF11");
+        assert_eq!(Dscp::new(5).unwrap().to_string(), "DSCP 5");
+        assert!("64".parse::<Dscp>().is_err());
+        assert!("gold".parse::<Dscp>().is_err());
+
+        let comparison = QosLatency {
+            ip: "10.0.0.2".to_string(),
+            dscp: ef,
+            marked_ms: Some(2.0),
+            best_effort_ms: Some(9.5),
+        };
+        assert_eq!(comparison.advantage_ms(), Some(7.5));
+        assert_eq!(median_ms(&mut [3.0, 1.0, 2.0, 10.0]), Some(2.5));
+
+        let marking = ProbeMarking { dscp: Some(ef), ttl: None };
+        assert!(!marking.is_default() && ProbeMarking::default().is_default());
+        assert_eq!(marking.with_ttl(3), ProbeMarking { dscp: Some(ef), ttl: Some(3) });
+    }
+}
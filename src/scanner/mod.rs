@@ -1,13 +1,134 @@
 //! Scanner module - ARP, ICMP, TCP, and SNMP scanning
 
+/// Active Directory domain controller detection
+mod active_directory;
 mod arp;
+/// Append-only audit trail of sent probes
+mod audit;
+/// Captive portal detection
+mod captive;
+/// Kernel-filtered frame capture
+mod capture;
+/// Scan checkpoints for resuming interrupted scans
+mod checkpoint;
+/// Per-host clock skew measurement
+mod clock_skew;
+/// User-defined probe payloads
+mod custom_probe;
+/// DHCPv6 server discovery
+mod dhcpv6;
+/// DNS resolver discovery and open-resolver check
+mod dns_servers;
+/// Cleartext and unauthenticated service exposure checks
+mod exposure;
+/// Switch forwarding database walks
+mod fdb;
+/// IoT firmware version probing
+mod firmware;
+/// Ethernet/ARP frame parsing
+mod frame;
 mod icmp;
+/// ARP through the Windows IP Helper API when Npcap is missing
+#[cfg(windows)]
+mod ip_helper;
+/// IP protocol scan
+mod ipproto;
+/// Wireless client isolation check
+mod isolation;
+/// Concurrency limits sized from system resources
+mod limits;
+/// DSCP and TTL marking of probes
+mod marking;
+/// NAT, double-NAT and CGNAT detection
+mod nat;
+/// NTP server discovery
+mod ntp;
+/// Token-bucket send pacing
+mod pacer;
+/// Pipelined ARP, ICMP and TCP discovery
+mod pipeline;
+/// Path MTU discovery
+mod pmtu;
+/// Scan profiles
+mod profile;
+/// Raw TCP scans for firewall rule mapping
+mod raw_tcp;
+/// Router ARP table and DHCP lease import
+mod router_import;
+/// Simulated network for tests (`sim` feature)
+#[cfg(any(test, feature = "sim"))]
+mod sim;
+/// Smart-home device detection
+mod smart_home;
+/// SMB share enumeration
+mod smb;
 mod snmp;
+/// SNMP community string checks
+mod snmp_community;
 mod tcp;
+/// Uptime estimation from TCP timestamps
+mod tcp_timestamps;
+/// Gateway throughput benchmark
+mod throughput;
+/// RTT-adaptive probe timeouts
+mod timeouts;
+/// TLS configuration assessment
+mod tls;
+/// UPnP IGD port-mapping enumeration
+mod upnp;
+/// External IP and internet reachability checks
+mod wan;
+/// Security posture of LAN web interfaces
+mod web_posture;
+/// DNS zone transfer checks
+mod zone_transfer;
 pub mod passive;
 
-pub use arp::active_arp_scan;
-pub use icmp::{icmp_scan, guess_os_from_ttl, IcmpResult};
+pub use arp::{active_arp_scan, arp_scan_ips, on_link_targets, parse_target_list, streaming_arp_scan, TARGETS_ENV};
+#[cfg(feature = "lab-mode")]
+pub use arp::{lab_arp_scan, LabArpSender};
+pub use audit::{init_audit_log, probes_to, read_audit_log, AuditRecord, ProbeKind, AUDIT_LOG_ENV};
+pub use captive::{captive_portal_check, ConnectivityCheck, ConnectivityState};
+pub use capture::{CaptureFilter, FrameReceiver};
+pub use checkpoint::{ScanCheckpoint, CHECKPOINT_ENV};
+pub use clock_skew::{clock_skew_recommendation, clock_skew_scan, ClockSkew, ClockSource};
+pub use custom_probe::{
+    apply_custom_probes, custom_probe_scan, CustomProbe, CustomProbeMatch, ProbeRegistry, ResponseMatcher,
+    CUSTOM_PROBES_ENV,
+};
+pub use dhcpv6::dhcpv6_probe;
+pub use dns_servers::{dns_server_scan, DnsServerInfo, DnsServerSource};
+pub use active_directory::active_directory_scan;
+pub use zone_transfer::{local_zone_candidates, zone_transfer_scan, ZoneTransferResult};
+pub use exposure::{exposure_scan, ExposureFinding, ExposureKind};
+pub use firmware::{apply_firmware, firmware_scan};
+pub use fdb::{fdb_scan, locate_hosts, FdbEntry, SwitchFdb};
+pub use frame::{parse_arp_frame, ArpFrame, ArpOperation, FrameError};
+pub use icmp::{icmp_scan, icmp_scan_with_payload, guess_os_from_ttl, HostState, IcmpPayload, IcmpResult, PayloadPattern, ICMP_PAYLOAD_ENV};
+pub use ipproto::{ip_protocol_scan, ProtocolProbe, ProtocolScanResult, ProtocolState, DEFAULT_SCAN_PROTOCOLS};
+pub use isolation::{isolation_check, IsolationProbe, IsolationReport, Reachability};
+pub use limits::{concurrency_limits, link_speed_mbps, send_rate_for_link, set_concurrency_limits, ConcurrencyLimits};
+pub use marking::{dscp_latency_comparison, init_probe_marking, probe_marking, Dscp, ProbeMarking, QosLatency, PROBE_DSCP_ENV, PROBE_TTL_ENV};
+pub use nat::{nat_check, stun_mapped_address, NatKind, NatReport};
+pub use ntp::{ntp_server_scan, NtpServerInfo};
+pub use pacer::{init_shared_pacer, shared_pacer, Congestion, Pacer};
+pub use pipeline::{pipelined_discovery, DiscoveryResult};
+pub use pmtu::{pmtu_probe, PmtuResult};
+pub use profile::{ScanConfig, ScanProfile, SCAN_PROFILE_ENV};
+pub use raw_tcp::{raw_tcp_scan, RawScanResult, TcpScanMode};
+pub use router_import::{router_import, RouterEntrySource, RouterHost, RouterKind};
+#[cfg(feature = "sim")]
+pub use sim::{install_sim_backend, SimBackend, SimGuard, SimHost};
+pub use smart_home::{apply_smart_home, smart_home_scan, SmartHomeDevice, SmartHomeKind};
+pub use smb::{smb_shares, SmbSessionKind, SmbShare, SmbShareReport, SmbShareType};
 pub use snmp::{snmp_enrich, SnmpData, SnmpNeighbor};
-pub use tcp::tcp_probe_scan;
-pub use passive::{PassiveScanner, ArpMonitor, ArpEvent};
+pub use snmp_community::{community_findings, probe_communities, snmp_community_scan, CommunityAccess, CommunityAccessLevel};
+pub use tcp::{connect_times, open_ports, tcp_port_scan, tcp_port_scan_adaptive, tcp_probe_scan};
+pub use tcp_timestamps::{estimate_uptime, tcp_uptime_scan, TcpUptime};
+pub use tls::{tls_assess, TlsReport, TlsVersion, WeakCipherClass, TLS_PORTS};
+pub use throughput::{throughput_probe, ThroughputMethod, ThroughputResult};
+pub use timeouts::{adaptive_timeout, HostTimeouts};
+pub use upnp::{upnp_port_mappings, PortMapping, UpnpReport};
+pub use wan::{sample_wan_latency, wan_check};
+pub use web_posture::{management_plane, ManagementPlaneReport, WebAuth, WebInterface};
+pub use passive::{PassiveScanner, ArpMonitor, ArpEvent, DhcpMonitor, DhcpEvent, advertised_dns_servers, StpMonitor, StpBpdu, measure_frame_rates, FrameRates, capture_router_advertisements, RouterAdvertisement};
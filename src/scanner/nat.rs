@@ -0,0 +1,302 @@
+//! NAT, double-NAT and CGNAT detection
+//!
+//! Traces the first few hops toward an internet host (UDP probes with
+//! increasing TTL, answered by ICMP time-exceeded) and asks a STUN server
+//! for the public address the traffic leaves from. A second private hop
+//! behind the gateway means another router is translating (double NAT);
+//! a hop or mapped address in 100.64.0.0/10 means the ISP itself is
+//! translating (CGNAT). Both break inbound port forwarding and some VPNs.
+
+use anyhow::{anyhow, Context, Result};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::Packet;
+use pnet::transport::{ipv4_packet_iter, transport_channel, TransportChannelType};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+use super::audit::{audit_probe, ProbeKind};
+use super::marking::{probe_marking, udp_socket};
+use crate::config::{NAT_STUN_SERVER, NAT_TRACE_MAX_HOPS};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// First destination port of the traceroute probes (as in traceroute(8))
+const TRACE_BASE_PORT: u16 = 33434;
+
+/// How long to wait for each hop to answer
+const TRACE_HOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Timeout for the STUN binding request
+const STUN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// STUN magic cookie (RFC 5389)
+const STUN_MAGIC_COOKIE: u32 = 0x2112_A442;
+
+/// STUN attributes carrying the mapped address
+const STUN_MAPPED_ADDRESS: u16 = 0x0001;
+const STUN_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+
+/// Address translation situation between the LAN and the internet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NatKind {
+    /// The gateway translates straight to a public address
+    Single,
+    /// Another private router sits behind the gateway
+    Double,
+    /// The ISP translates again (shared address space 100.64.0.0/10)
+    Carrier,
+    /// Not enough hops answered to tell
+    Unknown,
+}
+
+impl NatKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NatKind::Single => "single",
+            NatKind::Double => "double",
+            NatKind::Carrier => "cgnat",
+            NatKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// Result of the NAT check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatReport {
+    /// Hops toward the internet (`None` = hop did not answer)
+    pub hops: Vec<Option<Ipv4Addr>>,
+    /// Public address reported by the STUN server
+    pub public_ip: Option<Ipv4Addr>,
+    pub kind: NatKind,
+}
+
+impl NatReport {
+    /// Classify the path from the traced hops and the STUN mapped address
+    pub fn classify(hops: Vec<Option<Ipv4Addr>>, public_ip: Option<Ipv4Addr>) -> Self {
+        let answered: Vec<Ipv4Addr> = hops.iter().flatten().copied().collect();
+
+        let kind = if answered.iter().chain(public_ip.iter()).any(is_shared_address) {
+            NatKind::Carrier
+        } else {
+            // Private hops before the first public one are NAT layers; the
+            // first is the gateway itself
+            let private_hops = answered.iter().take_while(|ip| ip.is_private()).count();
+            let reached_public = answered.iter().any(|ip| !ip.is_private()) || public_ip.is_some();
+            match (private_hops, reached_public) {
+                (0, _) => NatKind::Unknown,
+                (1, true) => NatKind::Single,
+                (1, false) => NatKind::Unknown,
+                _ => NatKind::Double,
+            }
+        };
+
+        Self { hops, public_ip, kind }
+    }
+
+    /// Double NAT and CGNAT both prevent inbound connections
+    pub fn blocks_port_forwarding(&self) -> bool {
+        matches!(self.kind, NatKind::Double | NatKind::Carrier)
+    }
+}
+
+/// RFC 6598 shared address space used by carrier-grade NAT
+fn is_shared_address(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    a == 100 && (64..128).contains(&b)
+}
+
+/// Build a STUN binding request with the given transaction ID
+fn stun_binding_request(transaction: [u8; 12]) -> [u8; 20] {
+    let mut request = [0u8; 20];
+    request[..2].copy_from_slice(&0x0001u16.to_be_bytes());
+    request[4..8].copy_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+    request[8..].copy_from_slice(&transaction);
+    request
+}
+
+/// Extract the mapped IPv4 address from a STUN binding success response
+fn parse_stun_response(transaction: [u8; 12], data: &[u8]) -> Option<Ipv4Addr> {
+    if data.get(..2)? != [0x01, 0x01] || data.get(8..20)? != transaction {
+        return None;
+    }
+
+    let mut mapped = None;
+    let mut attributes = data.get(20..)?;
+    while attributes.len() >= 4 {
+        let kind = u16::from_be_bytes([attributes[0], attributes[1]]);
+        let len = u16::from_be_bytes([attributes[2], attributes[3]]) as usize;
+        let value = attributes.get(4..4 + len)?;
+        // Family 0x01 = IPv4: [reserved, family, port(2), address(4)]
+        if value.len() >= 8 && value[1] == 0x01 {
+            let address = u32::from_be_bytes([value[4], value[5], value[6], value[7]]);
+            match kind {
+                STUN_XOR_MAPPED_ADDRESS => return Some(Ipv4Addr::from(address ^ STUN_MAGIC_COOKIE)),
+                STUN_MAPPED_ADDRESS => mapped = Some(Ipv4Addr::from(address)),
+                _ => {}
+            }
+        }
+        // Attributes are padded to a multiple of 4 bytes
+        attributes = attributes.get(4 + len.div_ceil(4) * 4..).unwrap_or_default();
+    }
+    mapped
+}
+
+/// Ask a STUN server which public address our traffic leaves from
+pub async fn stun_mapped_address(server: SocketAddr) -> Result<Ipv4Addr> {
+    let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+    let mut transaction = [0u8; 12];
+    transaction[..4].copy_from_slice(&std::process::id().to_be_bytes());
+    transaction[4..].copy_from_slice(&chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default().to_be_bytes());
+    socket.send_to(&stun_binding_request(transaction), server).await?;
+
+    let mut buf = [0u8; 512];
+    let (n, _) = timeout(STUN_TIMEOUT, socket.recv_from(&mut buf))
+        .await
+        .map_err(|_| anyhow!("STUN server {} did not answer", server))??;
+    parse_stun_response(transaction, &buf[..n]).ok_or_else(|| anyhow!("Invalid STUN response from {}", server))
+}
+
+/// ICMP error quoting one of our probes: (type, quoted destination, quoted UDP port)
+fn parse_probe_error(packet: &[u8]) -> Option<(u8, Ipv4Addr, u16)> {
+    let header_len = ((*packet.first()? & 0x0F) as usize) * 4;
+    let icmp = packet.get(header_len..)?;
+    let kind = *icmp.first()?;
+    if kind != 3 && kind != 11 {
+        return None;
+    }
+    let original = icmp.get(8..)?;
+    let original_len = ((*original.first()? & 0x0F) as usize) * 4;
+    let dest: [u8; 4] = original.get(16..20)?.try_into().ok()?;
+    let port = u16::from_be_bytes([*original.get(original_len + 2)?, *original.get(original_len + 3)?]);
+    Some((kind, Ipv4Addr::from(dest), port))
+}
+
+/// Trace up to `max_hops` hops toward `target` (blocking, needs a raw socket)
+pub fn trace_hops(target: Ipv4Addr, max_hops: u8) -> Result<Vec<Option<Ipv4Addr>>> {
+    let (_, mut rx) = transport_channel(4096, TransportChannelType::Layer3(IpNextHeaderProtocols::Icmp))
+        .map_err(|e| anyhow!("Raw ICMP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+    let mut packets = ipv4_packet_iter(&mut rx);
+    let marking = probe_marking();
+    let mut hops = Vec::new();
+
+    for ttl in 1..=max_hops {
+        let port = TRACE_BASE_PORT + ttl as u16;
+        let socket = udp_socket(marking.with_ttl(ttl))?;
+        audit_probe(target, ProbeKind::Udp, Some(port));
+        socket.send_to(&[0u8; 8], (target, port))?;
+
+        let mut hop = None;
+        let mut reached = false;
+        let deadline = Instant::now() + TRACE_HOP_TIMEOUT;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let (packet, from) = match packets.next_with_timeout(remaining) {
+                Ok(Some(received)) => received,
+                Ok(None) => break,
+                Err(e) => return Err(e.into()),
+            };
+            let IpAddr::V4(from) = from else { continue };
+            if let Some((kind, dest, quoted_port)) = parse_probe_error(packet.packet()) {
+                if dest == target && quoted_port == port {
+                    hop = Some(from);
+                    reached = kind == 3;
+                    break;
+                }
+            }
+        }
+
+        hops.push(hop);
+        if reached {
+            break;
+        }
+    }
+
+    Ok(hops)
+}
+
+/// Detect double NAT / CGNAT between this host and the internet
+///
+/// Traces toward the STUN server (NAT_STUN_SERVER) and asks it for the
+/// public address; either half may fail without failing the check.
+pub async fn nat_check() -> Result<NatReport> {
+    let server = tokio::net::lookup_host(NAT_STUN_SERVER)
+        .await
+        .with_context(|| format!("Failed to resolve {}", NAT_STUN_SERVER))?
+        .find_map(|addr| match addr {
+            SocketAddr::V4(v4) => Some(v4),
+            SocketAddr::V6(_) => None,
+        })
+        .ok_or_else(|| anyhow!("No IPv4 address for {}", NAT_STUN_SERVER))?;
+    let target = *server.ip();
+
+    log_stderr!("NAT check: tracing {} hops toward {}...", NAT_TRACE_MAX_HOPS, target);
+
+    let trace = tokio::task::spawn_blocking(move || trace_hops(target, NAT_TRACE_MAX_HOPS));
+    let public_ip = match stun_mapped_address(SocketAddr::V4(server)).await {
+        Ok(ip) => Some(ip),
+        Err(e) => {
+            log_stderr!("NAT check: STUN failed: {}", e);
+            None
+        }
+    };
+    let hops = match trace.await? {
+        Ok(hops) => hops,
+        Err(e) => {
+            log_stderr!("NAT check: traceroute failed: {}", e);
+            Vec::new()
+        }
+    };
+
+    let report = NatReport::classify(hops, public_ip);
+    log_stderr!(
+        "NAT check complete: {} NAT (public IP {})",
+        report.kind.as_str(),
+        report.public_ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string())
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> Option<Ipv4Addr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_nat_classification() {
+        let single = NatReport::classify(vec![ip("192.168.1.1"), ip("84.17.1.1")], ip("84.17.9.9"));
+        assert_eq!(single.kind, NatKind::Single);
+
+        let double = NatReport::classify(vec![ip("192.168.1.1"), None, ip("192.168.0.1"), ip("84.17.1.1")], None);
+        assert_eq!(double.kind, NatKind::Double);
+        assert!(double.blocks_port_forwarding());
+
+        let carrier = NatReport::classify(vec![ip("192.168.1.1"), ip("100.72.0.1")], ip("84.17.9.9"));
+        assert_eq!(carrier.kind, NatKind::Carrier);
+
+        assert_eq!(NatReport::classify(vec![None, None], None).kind, NatKind::Unknown);
+    }
+
+    #[test]
+    fn test_stun_response() {
+        let transaction = [7u8; 12];
+        let mut response = stun_binding_request(transaction).to_vec();
+        response[..2].copy_from_slice(&[0x01, 0x01]);
+        response[2..4].copy_from_slice(&12u16.to_be_bytes());
+        let xored = u32::from(Ipv4Addr::new(203, 0, 113, 5)) ^ STUN_MAGIC_COOKIE;
+        response.extend_from_slice(&[0x00, 0x20, 0x00, 0x08, 0x00, 0x01, 0x12, 0x34]);
+        response.extend_from_slice(&xored.to_be_bytes());
+
+        assert_eq!(parse_stun_response(transaction, &response), Some(Ipv4Addr::new(203, 0, 113, 5)));
+        assert_eq!(parse_stun_response([8u8; 12], &response), None);
+    }
+}
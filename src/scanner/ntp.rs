@@ -0,0 +1,211 @@
+//! NTP server discovery and amplification exposure check
+//!
+//! Sends an NTP client request to every discovered host on UDP/123 and
+//! records the stratum and reference ID of those that answer. Responders
+//! are then asked for their variables over mode 6 (ntpq) and for the
+//! monlist table over mode 7 (ntpdc). Both legacy control modes return far
+//! more data than they receive and are classic DDoS amplification vectors;
+//! they are commonly left enabled on routers, NAS boxes and switches.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+use super::audit::{audit_probe, ProbeKind};
+use super::limits::concurrency_limits;
+use crate::models::HostInfo;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// NTP port
+const NTP_PORT: u16 = 123;
+
+/// Timeout for each NTP exchange
+const NTP_QUERY_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Mode 3 client request, version 4 (LI 0, VN 4, mode 3)
+const CLIENT_REQUEST_HEADER: u8 = 0x23;
+
+/// Mode 6 READVAR request, version 2, sequence 1, association 0
+const MODE6_READVAR: [u8; 12] = [0x16, 0x02, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// Mode 7 MON_GETLIST_1 request (implementation XNTPD, request code 42)
+const MODE7_MONLIST: [u8; 8] = [0x17, 0x00, 0x03, 0x2a, 0, 0, 0, 0];
+
+/// NTP service found on a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NtpServerInfo {
+    pub ip: String,
+    /// 1 = primary reference (GPS, atomic), 16 = unsynchronized
+    pub stratum: u8,
+    /// Reference clock code (stratum 1) or upstream server address
+    pub refid: String,
+    /// Answers mode 6 control queries (ntpq)
+    pub mode6_enabled: bool,
+    /// Answers the mode 7 monlist request (ntpdc)
+    pub monlist_enabled: bool,
+    /// Bytes returned for the single control request that was sent
+    pub amplification_bytes: usize,
+}
+
+impl NtpServerInfo {
+    /// The server is not synchronized to any source
+    pub fn is_unsynchronized(&self) -> bool {
+        self.stratum == 0 || self.stratum >= 16
+    }
+
+    /// Either legacy control mode can be abused for amplification
+    pub fn is_amplification_risk(&self) -> bool {
+        self.mode6_enabled || self.monlist_enabled
+    }
+}
+
+/// Build a mode 3 client request
+fn client_request() -> [u8; 48] {
+    let mut packet = [0u8; 48];
+    packet[0] = CLIENT_REQUEST_HEADER;
+    packet
+}
+
+/// Parse stratum and reference ID from a mode 4 server reply
+fn parse_server_reply(data: &[u8]) -> Option<(u8, String)> {
+    if data.len() < 48 || data[0] & 0x07 != 4 {
+        return None;
+    }
+    let stratum = data[1];
+    let id = &data[12..16];
+    let refid = if stratum <= 1 {
+        // Kiss code or reference clock: up to four ASCII characters
+        String::from_utf8_lossy(id).trim_end_matches('\0').to_string()
+    } else {
+        Ipv4Addr::new(id[0], id[1], id[2], id[3]).to_string()
+    };
+    Some((stratum, refid))
+}
+
+/// A mode 6 reply has the response bit set and no error bit
+fn is_mode6_reply(data: &[u8]) -> bool {
+    data.len() >= 12 && data[0] & 0x07 == 6 && data[1] & 0x80 != 0 && data[1] & 0x40 == 0
+}
+
+/// A mode 7 reply has the response bit set and no error code
+fn is_mode7_reply(data: &[u8]) -> bool {
+    data.len() >= 8 && data[0] & 0x87 == 0x87 && data[3] == MODE7_MONLIST[3] && data[4] & 0xF0 == 0
+}
+
+/// Send one request and collect every datagram returned before the timeout
+async fn exchange(ip: Ipv4Addr, request: &[u8]) -> Vec<Vec<u8>> {
+    let mut replies = Vec::new();
+    let Ok(socket) = UdpSocket::bind("0.0.0.0:0").await else {
+        return replies;
+    };
+    let target = SocketAddr::from((ip, NTP_PORT));
+    audit_probe(ip, ProbeKind::Udp, Some(NTP_PORT));
+    if socket.send_to(request, target).await.is_err() {
+        return replies;
+    }
+
+    let mut buf = [0u8; 1500];
+    let deadline = tokio::time::Instant::now() + NTP_QUERY_TIMEOUT;
+    while let Some(remaining) = deadline.checked_duration_since(tokio::time::Instant::now()) {
+        match timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(Ok((n, from))) if from == target => replies.push(buf[..n].to_vec()),
+            Ok(Ok(_)) => continue,
+            _ => break,
+        }
+    }
+    replies
+}
+
+async fn check_host(ip: Ipv4Addr) -> Option<NtpServerInfo> {
+    let (stratum, refid) = exchange(ip, &client_request())
+        .await
+        .iter()
+        .find_map(|reply| parse_server_reply(reply))?;
+
+    let (mode6, monlist) = tokio::join!(exchange(ip, &MODE6_READVAR), exchange(ip, &MODE7_MONLIST));
+    let mode6: Vec<&Vec<u8>> = mode6.iter().filter(|r| is_mode6_reply(r)).collect();
+    let monlist: Vec<&Vec<u8>> = monlist.iter().filter(|r| is_mode7_reply(r)).collect();
+
+    Some(NtpServerInfo {
+        ip: ip.to_string(),
+        stratum,
+        refid,
+        mode6_enabled: !mode6.is_empty(),
+        monlist_enabled: !monlist.is_empty(),
+        amplification_bytes: mode6.iter().chain(monlist.iter()).map(|r| r.len()).sum(),
+    })
+}
+
+/// Find NTP servers among `hosts` and check them for control-mode exposure
+pub async fn ntp_server_scan(hosts: &[HostInfo]) -> Vec<NtpServerInfo> {
+    let ips: Vec<Ipv4Addr> = hosts.iter().filter_map(|h| h.ip.parse().ok()).collect();
+    if ips.is_empty() {
+        return Vec::new();
+    }
+
+    log_stderr!("NTP: querying {} hosts on UDP/{}...", ips.len(), NTP_PORT);
+
+    let servers: Vec<NtpServerInfo> = stream::iter(ips)
+        .map(check_host)
+        .buffer_unordered(concurrency_limits().tcp_connects)
+        .filter_map(|server| async move { server })
+        .collect()
+        .await;
+
+    for server in servers.iter().filter(|s| s.is_amplification_risk()) {
+        log_stderr!(
+            "NTP: {} answers {} ({} bytes returned)",
+            server.ip,
+            if server.monlist_enabled { "monlist" } else { "mode 6 queries" },
+            server.amplification_bytes
+        );
+    }
+    log_stderr!(
+        "NTP complete: {} servers, {} with amplification exposure",
+        servers.len(),
+        servers.iter().filter(|s| s.is_amplification_risk()).count()
+    );
+
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_reply() {
+        let mut reply = [0u8; 48];
+        reply[0] = 0x24; // VN 4, mode 4
+        reply[1] = 2;
+        reply[12..16].copy_from_slice(&[192, 168, 1, 1]);
+        assert_eq!(parse_server_reply(&reply), Some((2, "192.168.1.1".to_string())));
+
+        reply[1] = 1;
+        reply[12..16].copy_from_slice(b"GPS\0");
+        assert_eq!(parse_server_reply(&reply), Some((1, "GPS".to_string())));
+
+        // Our own client request is not a server reply
+        assert!(parse_server_reply(&client_request()).is_none());
+    }
+
+    #[test]
+    fn test_control_mode_replies() {
+        assert!(is_mode6_reply(&[0x16, 0x82, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!is_mode6_reply(&[0x16, 0xC2, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(!is_mode6_reply(&MODE6_READVAR));
+
+        assert!(is_mode7_reply(&[0x97, 0x00, 0x03, 0x2a, 0x00, 0x06, 0x00, 0x48]));
+        assert!(!is_mode7_reply(&[0x97, 0x00, 0x03, 0x2a, 0x40, 0x00, 0x00, 0x00]));
+        assert!(!is_mode7_reply(&MODE7_MONLIST));
+    }
+}
@@ -0,0 +1,346 @@
+//! Token-bucket send pacing
+//!
+//! Smooths probe bursts to a configured packet rate. Senders reserve tokens
+//! before each transmission; when the bucket is empty the reservation returns
+//! the exact delay to wait, so several senders sharing one pacer are
+//! serialized fairly without a dedicated scheduler.
+//!
+//! The rate adapts to the path: senders report congestion (local send
+//! errors, rate-limited ICMP, replies that only arrive on a retry) and the
+//! pacer halves its rate, then climbs back towards the configured rate while
+//! the scan runs clean. One default thus serves both gigabit LANs and
+//! congested Wi-Fi.
+//!
+//! Optional jitter adds a random delay before every send, so probes do not
+//! leave at the metronomic intervals IDS signatures look for and do not
+//! keep landing in the same slot of a Wi-Fi client's power-save cycle.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::{ADAPTIVE_THROTTLING_ENABLED, MIN_SEND_RATE_PPS, SEND_BURST, SEND_JITTER, SEND_RATE_PPS};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Below this, sleeping is done by spinning since OS sleeps overshoot
+const SPIN_THRESHOLD: Duration = Duration::from_micros(200);
+
+/// Ignore further congestion reports this soon after a back-off, so one
+/// burst of errors counts once
+const BACKOFF_HOLD: Duration = Duration::from_millis(200);
+
+/// Clean period after which the rate is raised one step
+const RECOVERY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Rate multiplier applied per recovery step
+const RECOVERY_FACTOR: f64 = 1.25;
+
+/// Evidence that probes are being sent faster than the path can take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Congestion {
+    /// The local stack refused a send (ENOBUFS, no free ports)
+    SendError,
+    /// A router or target reported rate limiting (ICMP source quench)
+    RateLimited,
+    /// Replies arrived only for retransmitted probes
+    ReplyLoss,
+}
+
+impl Congestion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Congestion::SendError => "send errors",
+            Congestion::RateLimited => "rate limiting",
+            Congestion::ReplyLoss => "reply loss",
+        }
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+    /// Current rate, between `min_rate` and `max_rate`
+    rate: f64,
+    /// Last back-off or recovery step
+    last_adjust: Instant,
+    backed_off: bool,
+    /// xorshift state for jitter
+    rng: u64,
+}
+
+/// Token-bucket rate limiter for packet senders
+pub struct Pacer {
+    /// Configured rate in packets per second (0 = unlimited)
+    max_rate: f64,
+    /// Floor for adaptive back-off (equal to `max_rate` when not adaptive)
+    min_rate: f64,
+    /// Bucket capacity
+    burst: f64,
+    /// Upper bound of the random delay added to each send (zero = none)
+    jitter: Duration,
+    state: Mutex<BucketState>,
+}
+
+impl Pacer {
+    pub fn new(rate_pps: u32, burst: u32) -> Self {
+        Self::adaptive(rate_pps, rate_pps, burst)
+    }
+
+    /// Pacer that backs off to no less than `min_pps` under congestion
+    pub fn adaptive(rate_pps: u32, min_pps: u32, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        let now = Instant::now();
+        Self {
+            max_rate: rate_pps as f64,
+            min_rate: min_pps.min(rate_pps) as f64,
+            burst,
+            jitter: Duration::ZERO,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                last_refill: now,
+                rate: rate_pps as f64,
+                last_adjust: now,
+                backed_off: false,
+                // Any odd seed works; RandomState is seeded per process
+                rng: RandomState::new().build_hasher().finish() | 1,
+            }),
+        }
+    }
+
+    /// Add a random delay of up to `jitter` before every send
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Pacer that never waits
+    pub fn unlimited() -> Self {
+        Self::new(0, 1)
+    }
+
+    /// Configured rate in packets per second (0 = unlimited)
+    pub fn rate_pps(&self) -> u32 {
+        self.max_rate as u32
+    }
+
+    /// Rate currently in effect after adaptive back-off
+    pub fn current_rate_pps(&self) -> u32 {
+        self.state.lock().unwrap().rate as u32
+    }
+
+    /// Report congestion: halve the rate, down to the adaptive floor
+    ///
+    /// Unlimited pacers have no rate to reduce and ignore reports.
+    pub fn report(&self, signal: Congestion) {
+        if self.max_rate <= 0.0 || self.min_rate >= self.max_rate {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.backed_off && now.duration_since(state.last_adjust) < BACKOFF_HOLD {
+            return;
+        }
+        let reduced = (state.rate / 2.0).max(self.min_rate);
+        if reduced < state.rate {
+            log_stderr!("Pacer: {} detected, send rate {} -> {} pps", signal.as_str(), state.rate as u32, reduced as u32);
+        }
+        state.rate = reduced;
+        state.last_adjust = now;
+        state.backed_off = true;
+    }
+
+    /// Largest batch that can be sent without waiting mid-batch
+    ///
+    /// One packet when jittering, so every packet gets its own delay.
+    pub fn burst(&self) -> usize {
+        if self.jitter.is_zero() {
+            self.burst as usize
+        } else {
+            1
+        }
+    }
+
+    /// Reserve `n` tokens and return how long to wait before sending
+    ///
+    /// The bucket may go into debt, which makes later callers wait longer.
+    /// Jitter is added on top and is not charged to the bucket.
+    fn reserve(&self, n: u32) -> Duration {
+        if self.max_rate <= 0.0 && self.jitter.is_zero() {
+            return Duration::ZERO;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let jitter = if self.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            // xorshift64: cheap and plenty for timing noise
+            state.rng ^= state.rng << 13;
+            state.rng ^= state.rng >> 7;
+            state.rng ^= state.rng << 17;
+            self.jitter.mul_f64((state.rng >> 11) as f64 / (1u64 << 53) as f64)
+        };
+        if self.max_rate <= 0.0 {
+            return jitter;
+        }
+
+        let now = Instant::now();
+        if state.rate < self.max_rate && now.duration_since(state.last_adjust) >= RECOVERY_INTERVAL {
+            state.rate = (state.rate * RECOVERY_FACTOR).min(self.max_rate);
+            state.last_adjust = now;
+            state.backed_off = false;
+        }
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * state.rate).min(self.burst);
+        state.last_refill = now;
+        state.tokens -= n as f64;
+
+        if state.tokens >= 0.0 {
+            jitter
+        } else {
+            Duration::from_secs_f64(-state.tokens / state.rate) + jitter
+        }
+    }
+
+    /// Block the current thread until `n` packets may be sent
+    pub fn acquire(&self, n: u32) {
+        let wait = self.reserve(n);
+        if !wait.is_zero() {
+            precise_sleep(wait);
+        }
+    }
+
+    /// Wait asynchronously until `n` packets may be sent
+    pub async fn acquire_async(&self, n: u32) {
+        let wait = self.reserve(n);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+static PACER: OnceLock<Pacer> = OnceLock::new();
+
+fn configured_pacer(rate_pps: u32, jitter: Duration) -> Pacer {
+    let pacer = if ADAPTIVE_THROTTLING_ENABLED {
+        Pacer::adaptive(rate_pps, MIN_SEND_RATE_PPS, SEND_BURST)
+    } else {
+        Pacer::new(rate_pps, SEND_BURST)
+    };
+    pacer.with_jitter(jitter)
+}
+
+/// Pacer shared by all active senders (ARP, TCP connect, UDP probes)
+pub fn shared_pacer() -> &'static Pacer {
+    PACER.get_or_init(|| configured_pacer(SEND_RATE_PPS, SEND_JITTER))
+}
+
+/// Set the shared send rate and jitter before scanning starts
+///
+/// Returns `false` if a sender already used the pacer at its configured rate.
+pub fn init_shared_pacer(rate_pps: u32, jitter: Duration) -> bool {
+    PACER.set(configured_pacer(rate_pps, jitter)).is_ok()
+}
+
+/// Whether a send error means the local stack is overwhelmed (rather than
+/// the target being unreachable)
+pub(crate) fn is_send_congestion(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    if error.raw_os_error() == Some(libc::ENOBUFS) {
+        return true;
+    }
+    matches!(error.kind(), std::io::ErrorKind::AddrNotAvailable | std::io::ErrorKind::WouldBlock)
+}
+
+/// Sleep with microsecond accuracy: coarse OS sleep, then spin the remainder
+fn precise_sleep(duration: Duration) {
+    let deadline = Instant::now() + duration;
+    if duration > SPIN_THRESHOLD {
+        std::thread::sleep(duration - SPIN_THRESHOLD);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_paced() {
+        let pacer = Pacer::new(1000, 10);
+        for _ in 0..10 {
+            assert_eq!(pacer.reserve(1), Duration::ZERO);
+        }
+        let wait = pacer.reserve(1);
+        assert!(wait > Duration::from_micros(800) && wait <= Duration::from_millis(1));
+
+        // Debt accumulates for later callers
+        assert!(pacer.reserve(1) > wait);
+    }
+
+    #[test]
+    fn test_unlimited_never_waits() {
+        let pacer = Pacer::unlimited();
+        for _ in 0..1000 {
+            assert_eq!(pacer.reserve(100), Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_backoff_and_recovery() {
+        let pacer = Pacer::adaptive(10_000, 1_000, 1);
+        pacer.report(Congestion::SendError);
+        assert_eq!(pacer.current_rate_pps(), 5_000);
+
+        // A burst of reports counts once
+        pacer.report(Congestion::ReplyLoss);
+        assert_eq!(pacer.current_rate_pps(), 5_000);
+
+        // Floor holds after repeated back-offs
+        for _ in 0..10 {
+            pacer.state.lock().unwrap().last_adjust -= BACKOFF_HOLD;
+            pacer.report(Congestion::RateLimited);
+        }
+        assert_eq!(pacer.current_rate_pps(), 1_000);
+
+        // Clean sending recovers one step per interval
+        pacer.state.lock().unwrap().last_adjust -= RECOVERY_INTERVAL;
+        pacer.reserve(1);
+        assert_eq!(pacer.current_rate_pps(), 1_250);
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        let jitter = Duration::from_millis(2);
+        let pacer = Pacer::unlimited().with_jitter(jitter);
+        assert_eq!(pacer.burst(), 1);
+        let waits: Vec<Duration> = (0..100).map(|_| pacer.reserve(1)).collect();
+        assert!(waits.iter().all(|w| *w < jitter));
+        assert!(waits.windows(2).any(|w| w[0] != w[1]));
+
+        // Jitter comes on top of pacing and is not charged to the bucket
+        let paced = Pacer::new(1000, 1).with_jitter(jitter);
+        assert!(paced.reserve(1) < jitter);
+        let wait = paced.reserve(1);
+        assert!(wait > Duration::from_micros(800) && wait < Duration::from_millis(1) + jitter);
+    }
+
+    #[test]
+    fn test_acquire_respects_rate() {
+        let pacer = Pacer::new(10_000, 1);
+        let start = Instant::now();
+        for _ in 0..51 {
+            pacer.acquire(1);
+        }
+        // 50 paced packets at 10k pps take at least 5ms
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+}
@@ -4,11 +4,10 @@
 //! Captures MAC addresses and IP assignments
 
 use pnet::datalink::{self, Channel, NetworkInterface};
-use pnet::packet::arp::{ArpOperations, ArpPacket};
-use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
-use pnet::packet::Packet;
 use tokio::sync::mpsc;
 
+use crate::scanner::{parse_arp_frame, ArpOperation, CaptureFilter, FrameError, FrameReceiver};
+
 /// ARP event captured from network
 #[derive(Debug, Clone)]
 pub struct ArpEvent {
@@ -42,54 +41,49 @@ impl ArpMonitor {
         // Create datalink channel in non-promiscuous mode
         let channel = datalink::channel(&self.interface, Default::default())?;
         
-        let mut rx = match channel {
+        let rx = match channel {
             Channel::Ethernet(_, rx) => rx,
             _ => return Err("Unsupported channel type".into()),
         };
+
+        // Let the kernel drop non-ARP traffic where supported (mirror ports are busy)
+        let mut rx = FrameReceiver::open(&self.interface, CaptureFilter::Arp, rx);
         
         tracing::info!("🎧 Started ARP monitoring on interface: {}", interface_name);
         
         // Listen for ARP packets
         loop {
-            match rx.next() {
-                Ok(packet) => {
-                    // Parse Ethernet frame
-                    if let Some(ethernet) = EthernetPacket::new(packet) {
-                        // Check if it's an ARP packet
-                        if ethernet.get_ethertype() == EtherTypes::Arp {
-                            if let Some(arp) = ArpPacket::new(ethernet.payload()) {
-                                let event = ArpEvent {
-                                    sender_mac: format!(
-                                        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-                                        arp.get_sender_hw_addr().0,
-                                        arp.get_sender_hw_addr().1,
-                                        arp.get_sender_hw_addr().2,
-                                        arp.get_sender_hw_addr().3,
-                                        arp.get_sender_hw_addr().4,
-                                        arp.get_sender_hw_addr().5,
-                                    ),
-                                    sender_ip: arp.get_sender_proto_addr().to_string(),
-                                    target_ip: arp.get_target_proto_addr().to_string(),
-                                    is_request: arp.get_operation() == ArpOperations::Request,
-                                    timestamp: chrono::Utc::now(),
-                                };
-                                
-                                tracing::debug!(
-                                    "🎧 ARP: {} ({}) {} {}",
-                                    event.sender_ip,
-                                    event.sender_mac,
-                                    if event.is_request { "→" } else { "←" },
-                                    event.target_ip
-                                );
-                                
-                                // Send event
-                                if tx.send(event).await.is_err() {
-                                    tracing::warn!("ARP monitoring channel closed");
-                                    break;
-                                }
-                            }
+            match rx.next_frame() {
+                Ok(packet) => match parse_arp_frame(packet) {
+                    Ok(arp) => {
+                        let event = ArpEvent {
+                            sender_mac: arp.sender_mac.to_string(),
+                            sender_ip: arp.sender_ip.to_string(),
+                            target_ip: arp.target_ip.to_string(),
+                            is_request: arp.operation == ArpOperation::Request,
+                            timestamp: chrono::Utc::now(),
+                        };
+
+                        tracing::debug!(
+                            "🎧 ARP: {} ({}) {} {}",
+                            event.sender_ip,
+                            event.sender_mac,
+                            if event.is_request { "→" } else { "←" },
+                            event.target_ip
+                        );
+
+                        // Send event
+                        if tx.send(event).await.is_err() {
+                            tracing::warn!("ARP monitoring channel closed");
+                            break;
                         }
                     }
+                    // Unfiltered captures see all traffic
+                    Err(FrameError::NotArp { .. }) => {}
+                    Err(e) => tracing::debug!("🎧 Malformed ARP frame ignored: {}", e),
+                },
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    // Filtered frame or receive timeout
                 }
                 Err(e) => {
                     tracing::error!("ARP monitoring error: {}", e);
@@ -0,0 +1,318 @@
+//! DHCP passive snooping
+//!
+//! Parses DHCP broadcasts seen on the wire to learn client hostnames
+//! (option 12), requested/assigned addresses, lease times and the DNS
+//! servers handed out to clients (option 6). Clients
+//! announce their hostname when requesting a lease, which often names
+//! devices that answer no active probe.
+
+use pnet::datalink::{self, Channel, NetworkInterface};
+use std::net::Ipv4Addr;
+use tokio::sync::mpsc;
+
+use crate::models::HostInfo;
+use crate::scanner::{CaptureFilter, FrameReceiver};
+
+/// Offset of the DHCP options within the BOOTP payload (after the magic cookie)
+const DHCP_OPTIONS_OFFSET: usize = 240;
+
+/// DHCP magic cookie (RFC 2131)
+const DHCP_MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+/// DHCP message type (option 53)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhcpMessageType {
+    Discover,
+    Offer,
+    Request,
+    Ack,
+    Other(u8),
+}
+
+impl DhcpMessageType {
+    fn from_code(code: u8) -> Self {
+        match code {
+            1 => DhcpMessageType::Discover,
+            2 => DhcpMessageType::Offer,
+            3 => DhcpMessageType::Request,
+            5 => DhcpMessageType::Ack,
+            other => DhcpMessageType::Other(other),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DhcpMessageType::Discover => "DISCOVER",
+            DhcpMessageType::Offer => "OFFER",
+            DhcpMessageType::Request => "REQUEST",
+            DhcpMessageType::Ack => "ACK",
+            DhcpMessageType::Other(_) => "OTHER",
+        }
+    }
+}
+
+/// DHCP message captured from the network
+#[derive(Debug, Clone)]
+pub struct DhcpEvent {
+    pub message_type: DhcpMessageType,
+    /// Client hardware address (chaddr)
+    pub client_mac: String,
+    /// Hostname announced by the client (option 12)
+    pub hostname: Option<String>,
+    /// Address requested by the client (option 50)
+    pub requested_ip: Option<Ipv4Addr>,
+    /// Address assigned by the server (yiaddr of an ACK)
+    pub assigned_ip: Option<Ipv4Addr>,
+    /// Lease duration in seconds (option 51)
+    pub lease_seconds: Option<u32>,
+    /// DNS servers offered by the server (option 6)
+    pub dns_servers: Vec<Ipv4Addr>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl DhcpEvent {
+    /// Address the event binds the client MAC to, if any
+    pub fn client_ip(&self) -> Option<Ipv4Addr> {
+        self.assigned_ip.or(self.requested_ip)
+    }
+}
+
+/// Parse a DHCP message from an Ethernet frame
+///
+/// Returns `None` for anything that is not an IPv4/UDP BOOTP packet with a
+/// DHCP message type option.
+pub fn parse_dhcp_frame(frame: &[u8]) -> Option<DhcpEvent> {
+    if frame.get(12..14)? != [0x08, 0x00] {
+        return None;
+    }
+    let ip = frame.get(14..)?;
+    let ip_header_len = ((*ip.first()? & 0x0F) as usize) * 4;
+    if *ip.get(9)? != 17 {
+        return None;
+    }
+    let udp = ip.get(ip_header_len..)?;
+    let dst_port = u16::from_be_bytes([*udp.get(2)?, *udp.get(3)?]);
+    if dst_port != 67 && dst_port != 68 {
+        return None;
+    }
+    let bootp = udp.get(8..)?;
+    if bootp.get(236..240)? != DHCP_MAGIC_COOKIE {
+        return None;
+    }
+
+    let chaddr = bootp.get(28..34)?;
+    let client_mac = chaddr.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+    let yiaddr = Ipv4Addr::new(bootp[16], bootp[17], bootp[18], bootp[19]);
+
+    let mut message_type = None;
+    let mut hostname = None;
+    let mut requested_ip = None;
+    let mut lease_seconds = None;
+    let mut dns_servers = Vec::new();
+
+    let mut options = bootp.get(DHCP_OPTIONS_OFFSET..)?;
+    while let Some((&code, rest)) = options.split_first() {
+        match code {
+            0 => {
+                options = rest;
+                continue;
+            }
+            255 => break,
+            _ => {}
+        }
+        let (&len, rest) = rest.split_first()?;
+        let value = rest.get(..len as usize)?;
+        match (code, value.len()) {
+            (53, 1) => message_type = Some(DhcpMessageType::from_code(value[0])),
+            (12, n) if n > 0 => hostname = Some(String::from_utf8_lossy(value).trim_end_matches('\0').to_string()),
+            (50, 4) => requested_ip = Some(Ipv4Addr::new(value[0], value[1], value[2], value[3])),
+            (51, 4) => lease_seconds = Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]])),
+            (6, n) if n % 4 == 0 => {
+                dns_servers = value.chunks(4).map(|c| Ipv4Addr::new(c[0], c[1], c[2], c[3])).collect()
+            }
+            _ => {}
+        }
+        options = &rest[len as usize..];
+    }
+
+    let message_type = message_type?;
+    Some(DhcpEvent {
+        message_type,
+        client_mac,
+        hostname: hostname.filter(|h| !h.is_empty()),
+        requested_ip,
+        assigned_ip: (message_type == DhcpMessageType::Ack && !yiaddr.is_unspecified()).then_some(yiaddr),
+        lease_seconds,
+        dns_servers,
+        timestamp: chrono::Utc::now(),
+    })
+}
+
+/// Fill in missing hostnames from snooped DHCP traffic (matched by MAC)
+///
+/// Returns the number of hosts that gained a hostname.
+pub fn apply_dhcp_hostnames(hosts: &mut [HostInfo], events: &[DhcpEvent]) -> usize {
+    let mut named = 0;
+    for host in hosts.iter_mut().filter(|h| h.hostname.is_none()) {
+        let hostname = events
+            .iter()
+            .rev()
+            .filter(|e| e.client_mac.eq_ignore_ascii_case(&host.mac))
+            .find_map(|e| e.hostname.clone());
+        if hostname.is_some() {
+            host.hostname = hostname;
+            named += 1;
+        }
+    }
+    named
+}
+
+/// DNS servers advertised to clients in snooped DHCP offers/ACKs
+pub fn advertised_dns_servers(events: &[DhcpEvent]) -> Vec<Ipv4Addr> {
+    let mut servers: Vec<Ipv4Addr> = events.iter().flat_map(|e| e.dns_servers.iter().copied()).collect();
+    servers.sort();
+    servers.dedup();
+    servers
+}
+
+/// DHCP monitor for passive hostname and lease discovery
+pub struct DhcpMonitor {
+    interface: NetworkInterface,
+}
+
+impl DhcpMonitor {
+    /// Create a new DHCP monitor for the given interface
+    pub fn new(interface: NetworkInterface) -> Self {
+        Self { interface }
+    }
+
+    /// Start snooping DHCP traffic (passive listening)
+    ///
+    /// Sends parsed DHCP messages through the channel
+    pub async fn start_monitoring(
+        &self,
+        tx: mpsc::Sender<DhcpEvent>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let channel = datalink::channel(&self.interface, Default::default())?;
+        let rx = match channel {
+            Channel::Ethernet(_, rx) => rx,
+            _ => return Err("Unsupported channel type".into()),
+        };
+
+        let mut rx = FrameReceiver::open(&self.interface, CaptureFilter::Dhcp, rx);
+
+        tracing::info!("🎧 Started DHCP snooping on interface: {}", self.interface.name);
+
+        loop {
+            match rx.next_frame() {
+                Ok(frame) => {
+                    if let Some(event) = parse_dhcp_frame(frame) {
+                        tracing::debug!(
+                            "🎧 DHCP {}: {} {:?} {:?}",
+                            event.message_type.as_str(),
+                            event.client_mac,
+                            event.hostname,
+                            event.client_ip()
+                        );
+
+                        if tx.send(event).await.is_err() {
+                            tracing::warn!("DHCP monitoring channel closed");
+                            break;
+                        }
+                    }
+                }
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    // Filtered frame or receive timeout
+                }
+                Err(e) => {
+                    tracing::error!("DHCP monitoring error: {}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dhcp_request_frame(hostname: &str) -> Vec<u8> {
+        let mut bootp = vec![0u8; 240];
+        bootp[0] = 1;
+        bootp[28..34].copy_from_slice(&[0xaa, 0xbb, 0xcc, 0x00, 0x00, 0x01]);
+        bootp[236..240].copy_from_slice(&DHCP_MAGIC_COOKIE);
+        bootp.extend_from_slice(&[53, 1, 3]);
+        bootp.extend_from_slice(&[50, 4, 192, 168, 1, 42]);
+        bootp.push(12);
+        bootp.push(hostname.len() as u8);
+        bootp.extend_from_slice(hostname.as_bytes());
+        bootp.extend_from_slice(&[51, 4, 0, 0, 0x0e, 0x10, 255]);
+
+        let mut frame = vec![0xff; 6];
+        frame.extend_from_slice(&[0xaa, 0xbb, 0xcc, 0x00, 0x00, 0x01, 0x08, 0x00]);
+        let mut ip = vec![0u8; 20];
+        ip[0] = 0x45;
+        ip[9] = 17;
+        frame.extend_from_slice(&ip);
+        frame.extend_from_slice(&[0, 68, 0, 67, 0, 0, 0, 0]);
+        frame.extend_from_slice(&bootp);
+        frame
+    }
+
+    fn host(ip: &str, mac: &str) -> HostInfo {
+        HostInfo {
+            ip: ip.to_string(),
+            mac: mac.to_string(),
+            device_type: "UNKNOWN".to_string(),
+            discovery_method: "ARP".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_dhcp_request() {
+        let event = parse_dhcp_frame(&dhcp_request_frame("Living-Room-TV")).unwrap();
+        assert_eq!(event.message_type, DhcpMessageType::Request);
+        assert_eq!(event.client_mac, "aa:bb:cc:00:00:01");
+        assert_eq!(event.hostname.as_deref(), Some("Living-Room-TV"));
+        assert_eq!(event.client_ip(), Some(Ipv4Addr::new(192, 168, 1, 42)));
+        assert_eq!(event.lease_seconds, Some(3600));
+        assert!(event.dns_servers.is_empty());
+
+        // Truncated frames are rejected instead of panicking
+        let frame = dhcp_request_frame("tv");
+        assert!(parse_dhcp_frame(&frame[..frame.len() - 8]).is_none());
+        assert!(parse_dhcp_frame(&frame[..100]).is_none());
+    }
+
+    #[test]
+    fn test_parse_dhcp_ack_dns_servers() {
+        let mut frame = dhcp_request_frame("tv");
+        let options = 14 + 20 + 8 + DHCP_OPTIONS_OFFSET;
+        frame[options + 2] = 5; // ACK
+        frame[14 + 20 + 8 + 16..14 + 20 + 8 + 20].copy_from_slice(&[192, 168, 1, 42]);
+        frame.pop();
+        frame.extend_from_slice(&[6, 8, 192, 168, 1, 1, 1, 1, 1, 1, 255]);
+
+        let event = parse_dhcp_frame(&frame).unwrap();
+        assert_eq!(event.message_type, DhcpMessageType::Ack);
+        assert_eq!(event.assigned_ip, Some(Ipv4Addr::new(192, 168, 1, 42)));
+        assert_eq!(
+            advertised_dns_servers(&[event.clone(), event]),
+            vec![Ipv4Addr::new(1, 1, 1, 1), Ipv4Addr::new(192, 168, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_apply_dhcp_hostnames() {
+        let event = parse_dhcp_frame(&dhcp_request_frame("kitchen-speaker")).unwrap();
+        let mut hosts = vec![host("192.168.1.42", "AA:BB:CC:00:00:01"), host("192.168.1.43", "AA:BB:CC:00:00:02")];
+
+        assert_eq!(apply_dhcp_hostnames(&mut hosts, &[event]), 1);
+        assert_eq!(hosts[0].hostname.as_deref(), Some("kitchen-speaker"));
+        assert!(hosts[1].hostname.is_none());
+    }
+}
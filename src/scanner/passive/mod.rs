@@ -4,9 +4,20 @@
 //! - mDNS/DNS-SD: Listen for service announcements
 //! - ARP monitoring: Observe ARP traffic
 //! - DHCP snooping: Capture DHCP requests
+//! - STP: Capture spanning tree BPDUs
+//! - Storms: Measure broadcast/multicast frame rates
+//! - Router Advertisements: Learn IPv6 prefixes, RDNSS and advertising routers
 
 pub mod mdns;
 pub mod arp;
+pub mod dhcp;
+pub mod stp;
+pub mod storm;
+pub mod ra;
 
 pub use mdns::PassiveScanner;
 pub use arp::{ArpMonitor, ArpEvent};
+pub use dhcp::{advertised_dns_servers, apply_dhcp_hostnames, DhcpEvent, DhcpMonitor};
+pub use stp::{parse_bpdu_frame, BridgeId, StpBpdu, StpMonitor};
+pub use ra::{capture_router_advertisements, parse_ra_frame, RaPrefix, RouterAdvertisement};
+pub use storm::{classify_frame, measure_frame_rates, FrameClass, FrameRateCounter, FrameRates, FrameSource};
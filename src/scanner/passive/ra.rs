@@ -0,0 +1,243 @@
+//! IPv6 Router Advertisement listening
+//!
+//! Routers multicast RAs to ff02::1 announcing the prefixes hosts should
+//! autoconfigure, the DNS servers to use (RDNSS) and how long they are
+//! willing to be the default router. Capturing them tells us which IPv6
+//! prefixes are live on the link without any neighbor cache, and shows
+//! every device that claims to be a router, including rogue ones.
+
+use anyhow::{anyhow, Result};
+use ipnetwork::Ipv6Network;
+use pnet::datalink::{self, Channel, NetworkInterface};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv6Addr;
+use std::time::{Duration, Instant};
+
+use crate::network::{Ipv6Neighbor, MacAddress};
+use crate::scanner::{CaptureFilter, FrameReceiver};
+
+/// ICMPv6 type of a Router Advertisement
+const ICMPV6_ROUTER_ADVERTISEMENT: u8 = 134;
+
+/// RA options (RFC 4861, RFC 8106)
+const OPT_PREFIX_INFORMATION: u8 = 3;
+const OPT_MTU: u8 = 5;
+const OPT_RDNSS: u8 = 25;
+
+/// Prefix information flags
+const PREFIX_ON_LINK: u8 = 0x80;
+const PREFIX_AUTONOMOUS: u8 = 0x40;
+
+/// RA flags
+const FLAG_MANAGED: u8 = 0x80;
+const FLAG_OTHER: u8 = 0x40;
+
+/// A prefix announced in an RA
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaPrefix {
+    pub prefix: Ipv6Addr,
+    pub prefix_len: u8,
+    /// Hosts may treat addresses in the prefix as on-link
+    pub on_link: bool,
+    /// Hosts may autoconfigure (SLAAC) addresses in the prefix
+    pub autonomous: bool,
+    pub valid_lifetime: u32,
+    pub preferred_lifetime: u32,
+}
+
+/// A captured Router Advertisement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouterAdvertisement {
+    /// Link-local source address of the router
+    pub router: Ipv6Addr,
+    /// MAC address in upper-case colon notation
+    pub router_mac: String,
+    /// Seconds the router offers to be a default router; 0 = not a default router
+    pub router_lifetime: u16,
+    pub hop_limit: u8,
+    /// Addresses come from DHCPv6 (M flag)
+    pub managed: bool,
+    /// Other configuration comes from DHCPv6 (O flag)
+    pub other_config: bool,
+    pub prefixes: Vec<RaPrefix>,
+    /// Recursive DNS servers (RDNSS option)
+    pub rdns_servers: Vec<Ipv6Addr>,
+    pub mtu: Option<u32>,
+}
+
+impl RouterAdvertisement {
+    /// Announced prefixes as networks
+    pub fn networks(&self) -> Vec<Ipv6Network> {
+        self.prefixes
+            .iter()
+            .filter_map(|p| Ipv6Network::new(p.prefix, p.prefix_len).ok())
+            .collect()
+    }
+
+    /// The advertising router as a neighbor entry, for dual-stack correlation
+    pub fn as_neighbor(&self) -> Ipv6Neighbor {
+        Ipv6Neighbor {
+            address: self.router,
+            mac: self.router_mac.clone(),
+            is_router: true,
+        }
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?))
+}
+
+fn ipv6_at(bytes: &[u8]) -> Option<Ipv6Addr> {
+    let octets: [u8; 16] = bytes.get(..16)?.try_into().ok()?;
+    Some(Ipv6Addr::from(octets))
+}
+
+/// Parse a Router Advertisement from an Ethernet frame
+///
+/// RAs must arrive with hop limit 255 from a link-local source (RFC 4861
+/// 6.1.2); anything else has crossed a router and is ignored, as hosts would.
+pub fn parse_ra_frame(frame: &[u8]) -> Option<RouterAdvertisement> {
+    if frame.get(12..14)? != [0x86, 0xdd] {
+        return None;
+    }
+    let ip = frame.get(14..)?;
+    if ip.get(6)? != &58 || ip.get(7)? != &255 {
+        return None;
+    }
+    let router = ipv6_at(ip.get(8..)?)?;
+    if (router.segments()[0] & 0xffc0) != 0xfe80 {
+        return None;
+    }
+    let ra = ip.get(40..)?;
+    if *ra.first()? != ICMPV6_ROUTER_ADVERTISEMENT || *ra.get(1)? != 0 {
+        return None;
+    }
+    let source_mac: [u8; 6] = frame.get(6..12)?.try_into().ok()?;
+    let flags = *ra.get(5)?;
+    let mut advertisement = RouterAdvertisement {
+        router,
+        router_mac: format!("{:X}", MacAddress(source_mac)),
+        router_lifetime: u16::from_be_bytes([*ra.get(6)?, *ra.get(7)?]),
+        hop_limit: *ra.get(4)?,
+        managed: flags & FLAG_MANAGED != 0,
+        other_config: flags & FLAG_OTHER != 0,
+        prefixes: Vec::new(),
+        rdns_servers: Vec::new(),
+        mtu: None,
+    };
+
+    let mut options = ra.get(16..)?;
+    while options.len() >= 8 {
+        let length = options[1] as usize * 8;
+        if length == 0 || length > options.len() {
+            return None;
+        }
+        let option = &options[..length];
+        match option[0] {
+            OPT_PREFIX_INFORMATION if length == 32 => advertisement.prefixes.push(RaPrefix {
+                prefix: ipv6_at(&option[16..])?,
+                prefix_len: option[2],
+                on_link: option[3] & PREFIX_ON_LINK != 0,
+                autonomous: option[3] & PREFIX_AUTONOMOUS != 0,
+                valid_lifetime: be_u32(&option[4..])?,
+                preferred_lifetime: be_u32(&option[8..])?,
+            }),
+            OPT_MTU => advertisement.mtu = be_u32(&option[4..]),
+            OPT_RDNSS => {
+                advertisement.rdns_servers.extend(option[8..].chunks_exact(16).filter_map(ipv6_at));
+            }
+            _ => {}
+        }
+        options = &options[length..];
+    }
+    Some(advertisement)
+}
+
+/// Listen for Router Advertisements on an interface (blocking)
+///
+/// Call from `spawn_blocking`; returns after `duration` with the latest RA
+/// from each router. Routers advertise unsolicited every few minutes at
+/// most, so short windows only catch chatty or rogue advertisers.
+pub fn capture_router_advertisements(
+    interface: &NetworkInterface,
+    duration: Duration,
+) -> Result<Vec<RouterAdvertisement>> {
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let rx = match datalink::channel(interface, config) {
+        Ok(Channel::Ethernet(_, rx)) => rx,
+        Ok(_) => return Err(anyhow!("Unsupported channel type")),
+        Err(e) => return Err(anyhow!("Failed to open datalink channel: {}", e)),
+    };
+    let mut rx = FrameReceiver::open(interface, CaptureFilter::Icmpv6, rx);
+
+    let mut advertisements: Vec<RouterAdvertisement> = Vec::new();
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        match rx.next_frame() {
+            Ok(frame) => {
+                if let Some(ra) = parse_ra_frame(frame) {
+                    advertisements.retain(|seen| seen.router != ra.router || seen.router_mac != ra.router_mac);
+                    advertisements.push(ra);
+                }
+            }
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                // Filtered frame or receive timeout, check the deadline again
+            }
+            Err(e) => return Err(anyhow!("Capture failed on {}: {}", interface.name, e)),
+        }
+    }
+    Ok(advertisements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ra_frame(hop_limit: u8) -> Vec<u8> {
+        let mut frame = vec![0x33, 0x33, 0, 0, 0, 1, 0xaa, 0, 0, 0, 0, 1, 0x86, 0xdd];
+        let mut icmp = vec![ICMPV6_ROUTER_ADVERTISEMENT, 0, 0, 0, 64, FLAG_OTHER, 0x07, 0x08];
+        icmp.extend_from_slice(&[0; 8]);
+        // Prefix 2001:db8:1::/64, on-link and autonomous
+        icmp.extend_from_slice(&[OPT_PREFIX_INFORMATION, 4, 64, 0xc0]);
+        icmp.extend_from_slice(&86_400u32.to_be_bytes());
+        icmp.extend_from_slice(&14_400u32.to_be_bytes());
+        icmp.extend_from_slice(&[0; 4]);
+        icmp.extend_from_slice(&"2001:db8:1::".parse::<Ipv6Addr>().unwrap().octets());
+        // RDNSS with one server
+        icmp.extend_from_slice(&[OPT_RDNSS, 3, 0, 0]);
+        icmp.extend_from_slice(&1_800u32.to_be_bytes());
+        icmp.extend_from_slice(&"2001:db8:1::53".parse::<Ipv6Addr>().unwrap().octets());
+        icmp.extend_from_slice(&[OPT_MTU, 1, 0, 0]);
+        icmp.extend_from_slice(&1_500u32.to_be_bytes());
+
+        let mut ip = vec![0x60, 0, 0, 0];
+        ip.extend_from_slice(&(icmp.len() as u16).to_be_bytes());
+        ip.extend_from_slice(&[58, hop_limit]);
+        ip.extend_from_slice(&"fe80::1".parse::<Ipv6Addr>().unwrap().octets());
+        ip.extend_from_slice(&"ff02::1".parse::<Ipv6Addr>().unwrap().octets());
+        frame.extend_from_slice(&ip);
+        frame.extend_from_slice(&icmp);
+        frame
+    }
+
+    #[test]
+    fn test_parse_router_advertisement() {
+        let ra = parse_ra_frame(&ra_frame(255)).unwrap();
+        assert_eq!(ra.router, "fe80::1".parse::<Ipv6Addr>().unwrap());
+        assert_eq!(ra.router_mac, "AA:00:00:00:00:01");
+        assert_eq!(ra.router_lifetime, 1800);
+        assert!(ra.other_config && !ra.managed);
+        assert_eq!(ra.networks(), vec!["2001:db8:1::/64".parse::<Ipv6Network>().unwrap()]);
+        assert!(ra.prefixes[0].on_link && ra.prefixes[0].autonomous);
+        assert_eq!(ra.rdns_servers, vec!["2001:db8:1::53".parse::<Ipv6Addr>().unwrap()]);
+        assert_eq!(ra.mtu, Some(1500));
+
+        // Forwarded by a router: not a valid RA
+        assert!(parse_ra_frame(&ra_frame(254)).is_none());
+        assert!(parse_ra_frame(&ra_frame(255)[..60]).is_none());
+    }
+}
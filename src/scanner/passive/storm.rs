@@ -0,0 +1,195 @@
+//! Broadcast and multicast rate sampling
+//!
+//! Every broadcast and flooded multicast frame reaches every port and wakes
+//! every host on the segment. A loop, a misbehaving NIC or a chatty
+//! discovery protocol shows up as a sustained frame rate far above the
+//! handful per second a healthy LAN produces. The capture only counts
+//! frames with the group bit set in the destination MAC, and keeps per
+//! source counts so the worst talkers can be named.
+
+use anyhow::{anyhow, Result};
+use pnet::datalink::{self, Channel, NetworkInterface};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::config::{BROADCAST_STORM_PPS, MULTICAST_STORM_PPS};
+use crate::scanner::{CaptureFilter, FrameReceiver};
+
+/// Number of top sources kept in the rates summary
+const TOP_SOURCES: usize = 5;
+
+/// Kind of group-addressed frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameClass {
+    Broadcast,
+    Multicast,
+}
+
+/// Classify a frame by its destination MAC, `None` for unicast
+pub fn classify_frame(frame: &[u8]) -> Option<FrameClass> {
+    let dst = frame.get(..6)?;
+    if dst == [0xff; 6] {
+        Some(FrameClass::Broadcast)
+    } else if dst[0] & 0x01 != 0 {
+        Some(FrameClass::Multicast)
+    } else {
+        None
+    }
+}
+
+/// Group-addressed traffic sent by one station
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameSource {
+    /// Source MAC address in upper-case colon notation
+    pub mac: String,
+    /// Broadcast and multicast frames per second
+    pub pps: f64,
+}
+
+/// Broadcast and multicast frame rates over a sampling window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameRates {
+    pub window_secs: f64,
+    pub broadcast_frames: u64,
+    pub multicast_frames: u64,
+    pub broadcast_pps: f64,
+    /// Multicast frames per second, broadcast excluded
+    pub multicast_pps: f64,
+    /// Busiest senders, highest rate first
+    pub top_sources: Vec<FrameSource>,
+}
+
+impl FrameRates {
+    pub fn is_broadcast_storm(&self) -> bool {
+        self.broadcast_pps > BROADCAST_STORM_PPS
+    }
+
+    pub fn is_multicast_storm(&self) -> bool {
+        self.multicast_pps > MULTICAST_STORM_PPS
+    }
+}
+
+/// Counts group-addressed frames per class and per source
+#[derive(Debug, Default)]
+pub struct FrameRateCounter {
+    broadcast: u64,
+    multicast: u64,
+    by_source: HashMap<[u8; 6], u64>,
+}
+
+impl FrameRateCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one frame; unicast and truncated frames are ignored
+    pub fn record(&mut self, frame: &[u8]) {
+        let Some(class) = classify_frame(frame) else { return };
+        let Some(src) = frame.get(6..12) else { return };
+
+        match class {
+            FrameClass::Broadcast => self.broadcast += 1,
+            FrameClass::Multicast => self.multicast += 1,
+        }
+        let mut mac = [0u8; 6];
+        mac.copy_from_slice(src);
+        *self.by_source.entry(mac).or_insert(0) += 1;
+    }
+
+    /// Rates over the elapsed sampling window
+    pub fn rates(&self, elapsed: Duration) -> FrameRates {
+        let secs = elapsed.as_secs_f64().max(0.001);
+
+        let mut sources: Vec<(&[u8; 6], &u64)> = self.by_source.iter().collect();
+        sources.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let top_sources = sources
+            .into_iter()
+            .take(TOP_SOURCES)
+            .map(|(mac, count)| FrameSource {
+                mac: mac.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":"),
+                pps: *count as f64 / secs,
+            })
+            .collect();
+
+        FrameRates {
+            window_secs: secs,
+            broadcast_frames: self.broadcast,
+            multicast_frames: self.multicast,
+            broadcast_pps: self.broadcast as f64 / secs,
+            multicast_pps: self.multicast as f64 / secs,
+            top_sources,
+        }
+    }
+}
+
+/// Sample broadcast and multicast traffic on an interface (blocking)
+///
+/// Call from `spawn_blocking`; returns after `duration`.
+pub fn measure_frame_rates(interface: &NetworkInterface, duration: Duration) -> Result<FrameRates> {
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let rx = match datalink::channel(interface, config) {
+        Ok(Channel::Ethernet(_, rx)) => rx,
+        Ok(_) => return Err(anyhow!("Unsupported channel type")),
+        Err(e) => return Err(anyhow!("Failed to open datalink channel: {}", e)),
+    };
+    let mut rx = FrameReceiver::open(interface, CaptureFilter::Multicast, rx);
+
+    let mut counter = FrameRateCounter::new();
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        match rx.next_frame() {
+            Ok(frame) => counter.record(frame),
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                // Filtered frame or receive timeout, check the deadline again
+            }
+            Err(e) => return Err(anyhow!("Capture failed on {}: {}", interface.name, e)),
+        }
+    }
+
+    Ok(counter.rates(start.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(dst: [u8; 6], src_last: u8) -> Vec<u8> {
+        let mut frame = dst.to_vec();
+        frame.extend_from_slice(&[0xaa, 0, 0, 0, 0, src_last, 0x08, 0x00]);
+        frame
+    }
+
+    #[test]
+    fn test_classify_frame() {
+        assert_eq!(classify_frame(&frame([0xff; 6], 1)), Some(FrameClass::Broadcast));
+        assert_eq!(
+            classify_frame(&frame([0x33, 0x33, 0, 0, 0, 1], 1)),
+            Some(FrameClass::Multicast)
+        );
+        assert_eq!(classify_frame(&frame([0xaa, 0, 0, 0, 0, 9], 1)), None);
+        assert_eq!(classify_frame(&[0xff; 4]), None);
+    }
+
+    #[test]
+    fn test_frame_rates() {
+        let mut counter = FrameRateCounter::new();
+        for _ in 0..3000 {
+            counter.record(&frame([0xff; 6], 1));
+        }
+        for _ in 0..100 {
+            counter.record(&frame([0x01, 0x00, 0x5e, 0, 0, 0xfb], 2));
+        }
+        counter.record(&frame([0xaa, 0, 0, 0, 0, 9], 3));
+
+        let rates = counter.rates(Duration::from_secs(5));
+        assert_eq!(rates.broadcast_pps, 600.0);
+        assert_eq!(rates.multicast_pps, 20.0);
+        assert!(rates.is_broadcast_storm() && !rates.is_multicast_storm());
+        assert_eq!(rates.top_sources.len(), 2);
+        assert_eq!(rates.top_sources[0].mac, "AA:00:00:00:00:01");
+    }
+}
@@ -0,0 +1,199 @@
+//! Spanning tree BPDU snooping
+//!
+//! Switches running STP/RSTP/MSTP send BPDUs to 01:80:c2:00:00:00 every
+//! hello interval. Each one names the current root bridge and its
+//! priority, the sending bridge, and whether a topology change is in
+//! progress, which is enough to spot an unintended root bridge and
+//! topology-change storms without querying any switch.
+
+use pnet::datalink::{self, Channel, NetworkInterface};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::scanner::{CaptureFilter, FrameReceiver};
+
+/// 802.2 LLC header of a BPDU (DSAP/SSAP 0x42, UI frame)
+const STP_LLC: [u8; 3] = [0x42, 0x42, 0x03];
+
+/// BPDU types
+const BPDU_CONFIG: u8 = 0x00;
+const BPDU_TCN: u8 = 0x80;
+const BPDU_RST: u8 = 0x02;
+
+/// Topology change flag
+const FLAG_TOPOLOGY_CHANGE: u8 = 0x01;
+
+/// Bridge identifier: priority (including the VLAN/instance extension) and MAC
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BridgeId {
+    pub priority: u16,
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+}
+
+impl BridgeId {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..8)?;
+        Some(Self {
+            priority: u16::from_be_bytes([bytes[0], bytes[1]]),
+            mac: bytes[2..8].iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":"),
+        })
+    }
+}
+
+/// A captured BPDU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StpBpdu {
+    /// Protocol version: 0 = STP, 2 = RSTP, 3 = MSTP
+    pub version: u8,
+    /// Topology Change Notification (carries no bridge information)
+    pub is_tcn: bool,
+    /// Topology change flag set on a configuration BPDU
+    pub topology_change: bool,
+    pub root: Option<BridgeId>,
+    pub root_path_cost: Option<u32>,
+    /// Bridge that sent the BPDU
+    pub bridge: Option<BridgeId>,
+    pub port_id: Option<u16>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Parse a BPDU from an 802.3/LLC Ethernet frame
+pub fn parse_bpdu_frame(frame: &[u8]) -> Option<StpBpdu> {
+    if frame.get(..6)? != [0x01, 0x80, 0xc2, 0x00, 0x00, 0x00] || frame.get(14..17)? != STP_LLC {
+        return None;
+    }
+    let bpdu = frame.get(17..)?;
+    if bpdu.get(..2)? != [0, 0] {
+        return None;
+    }
+    let version = *bpdu.get(2)?;
+
+    match *bpdu.get(3)? {
+        BPDU_TCN => Some(StpBpdu {
+            version,
+            is_tcn: true,
+            topology_change: true,
+            root: None,
+            root_path_cost: None,
+            bridge: None,
+            port_id: None,
+            timestamp: chrono::Utc::now(),
+        }),
+        BPDU_CONFIG | BPDU_RST => {
+            let flags = *bpdu.get(4)?;
+            let cost = bpdu.get(13..17)?;
+            let port = bpdu.get(25..27)?;
+            Some(StpBpdu {
+                version,
+                is_tcn: false,
+                topology_change: flags & FLAG_TOPOLOGY_CHANGE != 0,
+                root: Some(BridgeId::parse(bpdu.get(5..13)?)?),
+                root_path_cost: Some(u32::from_be_bytes([cost[0], cost[1], cost[2], cost[3]])),
+                bridge: Some(BridgeId::parse(bpdu.get(17..25)?)?),
+                port_id: Some(u16::from_be_bytes([port[0], port[1]])),
+                timestamp: chrono::Utc::now(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Spanning tree monitor (passive BPDU capture)
+pub struct StpMonitor {
+    interface: NetworkInterface,
+}
+
+impl StpMonitor {
+    /// Create a new STP monitor for the given interface
+    pub fn new(interface: NetworkInterface) -> Self {
+        Self { interface }
+    }
+
+    /// Start capturing BPDUs (passive listening)
+    ///
+    /// Sends parsed BPDUs through the channel
+    pub async fn start_monitoring(&self, tx: mpsc::Sender<StpBpdu>) -> Result<(), Box<dyn std::error::Error>> {
+        let channel = datalink::channel(&self.interface, Default::default())?;
+        let rx = match channel {
+            Channel::Ethernet(_, rx) => rx,
+            _ => return Err("Unsupported channel type".into()),
+        };
+
+        let mut rx = FrameReceiver::open(&self.interface, CaptureFilter::Stp, rx);
+
+        tracing::info!("🎧 Started STP BPDU capture on interface: {}", self.interface.name);
+
+        loop {
+            match rx.next_frame() {
+                Ok(frame) => {
+                    if let Some(bpdu) = parse_bpdu_frame(frame) {
+                        tracing::debug!(
+                            "🎧 BPDU v{} root {:?} from {:?} (TC: {})",
+                            bpdu.version,
+                            bpdu.root,
+                            bpdu.bridge,
+                            bpdu.topology_change
+                        );
+
+                        if tx.send(bpdu).await.is_err() {
+                            tracing::warn!("STP monitoring channel closed");
+                            break;
+                        }
+                    }
+                }
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    // Filtered frame or receive timeout
+                }
+                Err(e) => {
+                    tracing::error!("STP monitoring error: {}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rstp_frame(flags: u8) -> Vec<u8> {
+        let mut frame = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x00, 0xaa, 0, 0, 0, 0, 2, 0x00, 0x27];
+        frame.extend_from_slice(&STP_LLC);
+        frame.extend_from_slice(&[0, 0, 2, BPDU_RST, flags]);
+        // Root: priority 32768 + VLAN 1, MAC aa:00:00:00:00:01
+        frame.extend_from_slice(&[0x80, 0x01, 0xaa, 0, 0, 0, 0, 1]);
+        frame.extend_from_slice(&20_000u32.to_be_bytes());
+        frame.extend_from_slice(&[0x80, 0x01, 0xaa, 0, 0, 0, 0, 2]);
+        frame.extend_from_slice(&[0x80, 0x05, 0, 0, 0x14, 0, 2, 0, 0x0f, 0, 0]);
+        frame
+    }
+
+    #[test]
+    fn test_parse_rstp_bpdu() {
+        let bpdu = parse_bpdu_frame(&rstp_frame(FLAG_TOPOLOGY_CHANGE)).unwrap();
+        assert_eq!(bpdu.version, 2);
+        assert!(bpdu.topology_change && !bpdu.is_tcn);
+        let root = bpdu.root.unwrap();
+        assert_eq!(root.priority, 32769);
+        assert_eq!(root.mac, "AA:00:00:00:00:01");
+        assert_eq!(bpdu.root_path_cost, Some(20_000));
+        assert_eq!(bpdu.bridge.unwrap().mac, "AA:00:00:00:00:02");
+        assert_eq!(bpdu.port_id, Some(0x8005));
+
+        assert!(!parse_bpdu_frame(&rstp_frame(0)).unwrap().topology_change);
+        assert!(parse_bpdu_frame(&rstp_frame(0)[..30]).is_none());
+    }
+
+    #[test]
+    fn test_parse_tcn_bpdu() {
+        let mut frame = rstp_frame(0);
+        frame.truncate(17);
+        frame.extend_from_slice(&[0, 0, 0, BPDU_TCN]);
+        let bpdu = parse_bpdu_frame(&frame).unwrap();
+        assert!(bpdu.is_tcn && bpdu.root.is_none());
+    }
+}
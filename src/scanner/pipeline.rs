@@ -0,0 +1,155 @@
+//! Pipelined discovery
+//!
+//! Running the phases back to back leaves the ICMP and TCP probers idle
+//! until the last ARP round has timed out, which on a /16 is most of the
+//! scan. Here each ARP reply is handed straight to the latency and port
+//! probers, so hosts that answer early are fully probed while the sweep is
+//! still going. Results are the same as ARP, then ICMP and TCP in sequence.
+//!
+//! Per host, the ping runs before the port probes so the measured round
+//! trip can size that host's connect timeouts.
+
+use anyhow::{Context, Result};
+use ipnetwork::Ipv4Network;
+use pnet::util::MacAddr;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+
+use super::arp::streaming_arp_scan;
+use super::marking::{icmp_client, probe_marking};
+use super::icmp::{complete_icmp_results, ping_host_with_retries, spawn_unreachable_listener, IcmpPayload, IcmpResult};
+use super::limits::concurrency_limits;
+use super::tcp::{probe_host_ports, PORTS_PER_TASK};
+use super::timeouts::{probe_rtt, HostTimeouts};
+use crate::config::{PING_SAMPLES, TCP_PROBE_TIMEOUT};
+use crate::models::{ArpReplyStats, InterfaceInfo, Port};
+use crate::network::{lookup_vendors, VendorInfo};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Hosts found by ARP with their vendor, latency and port probes
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryResult {
+    pub hosts: HashMap<Ipv4Addr, MacAddr>,
+    /// How each host answered the ARP sweep
+    pub arp_replies: HashMap<Ipv4Addr, ArpReplyStats>,
+    pub vendors: HashMap<Ipv4Addr, VendorInfo>,
+    pub latency: HashMap<Ipv4Addr, IcmpResult>,
+    pub ports: HashMap<Ipv4Addr, Vec<Port>>,
+}
+
+/// ARP sweep with ICMP (if `icmp` is set, with that payload) and TCP probes
+/// of `ports` started per host as soon as its ARP reply arrives
+pub async fn pipelined_discovery(
+    interface: &InterfaceInfo,
+    ips: &[Ipv4Addr],
+    subnet: &Ipv4Network,
+    ports: &[u16],
+    icmp: Option<&IcmpPayload>,
+) -> Result<DiscoveryResult> {
+    let (found_tx, mut found_rx) = mpsc::unbounded_channel();
+    let arp = tokio::task::spawn_blocking({
+        let interface = interface.clone();
+        let ips = ips.to_vec();
+        let subnet = *subnet;
+        move || streaming_arp_scan(&interface, &ips, &subnet, found_tx)
+    });
+
+    let payload = icmp.map(IcmpPayload::bytes).unwrap_or_default();
+    let client = match icmp.map(|_| icmp_client(probe_marking())) {
+        Some(Ok(client)) => Some(client),
+        Some(Err(e)) => {
+            log_stderr!("ICMP client unavailable ({}), skipping latency measurement", e);
+            None
+        }
+        None => None,
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let listener = client.as_ref().and_then(|_| spawn_unreachable_listener(Arc::clone(&stop)));
+
+    let limits = concurrency_limits();
+    let ping_slots = Arc::new(Semaphore::new(limits.pings));
+    let connect_slots = Arc::new(Semaphore::new(limits.tcp_connects));
+    let mut tasks = Vec::new();
+
+    // Ends when the ARP scan finishes and drops the sender
+    while let Some((ip, _)) = found_rx.recv().await {
+        let client = client.clone();
+        let ping_slots = Arc::clone(&ping_slots);
+        let connect_slots = Arc::clone(&connect_slots);
+        let ports = ports.to_vec();
+        let payload = payload.clone();
+        tasks.push(tokio::spawn(async move {
+            let reply = match &client {
+                Some(client) => {
+                    let _permit = ping_slots.acquire_owned().await.expect("Semaphore closed");
+                    ping_host_with_retries(client, ip, PING_SAMPLES, &payload).await
+                }
+                None => None,
+            };
+            let mut timeouts = HostTimeouts::default();
+            if let Some(rtt) = reply.as_ref().filter(|r| r.is_up()).and_then(probe_rtt) {
+                timeouts.insert(ip, rtt);
+            }
+            let timeout = timeouts.timeout_for(ip, TCP_PROBE_TIMEOUT);
+
+            let batches: Vec<_> = ports
+                .chunks(PORTS_PER_TASK)
+                .map(|batch| {
+                    let slots = Arc::clone(&connect_slots);
+                    let batch = batch.to_vec();
+                    tokio::spawn(async move {
+                        let _permit = slots.acquire_owned().await.expect("Semaphore closed");
+                        probe_host_ports(ip, &batch, timeout).await
+                    })
+                })
+                .collect();
+            let mut found = Vec::new();
+            for batch in batches {
+                if let Ok(probes) = batch.await {
+                    found.extend(probes);
+                }
+            }
+            (ip, reply, found)
+        }));
+    }
+
+    let (hosts, arp_replies) = arp.await.context("ARP scan task failed")??;
+    log_stderr!("ARP sweep done, finishing probes of {} hosts...", hosts.len());
+
+    let mut replies = HashMap::new();
+    let mut port_results: HashMap<Ipv4Addr, Vec<Port>> = HashMap::new();
+    for task in tasks {
+        if let Ok((ip, reply, found)) = task.await {
+            if let Some(result) = reply {
+                replies.insert(ip, result);
+            }
+            port_results.entry(ip).or_default().extend(found);
+        }
+    }
+    stop.store(true, Ordering::Relaxed);
+    let latency = match client {
+        Some(_) => complete_icmp_results(replies, hosts.keys().copied(), listener).await,
+        None => HashMap::new(),
+    };
+
+    for found in port_results.values_mut() {
+        found.sort_by_key(|p| p.number);
+    }
+
+    Ok(DiscoveryResult {
+        vendors: lookup_vendors(&hosts),
+        hosts,
+        arp_replies,
+        latency,
+        ports: port_results,
+    })
+}
@@ -0,0 +1,389 @@
+//! Path MTU discovery
+//!
+//! Sends Don't-Fragment echo requests toward a target and binary-searches the
+//! largest packet that is answered. When large probes vanish without an ICMP
+//! "fragmentation needed" ever reaching the scanner, the path has an MTU black
+//! hole - a common cause of stalled TLS handshakes and hanging file transfers
+//! on VPN-connected LANs where a tunnel lowers the MTU behind a firewall that
+//! drops ICMP.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+use crate::insights::{Priority, Recommendation};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Smallest MTU every IPv4 path must carry (RFC 791)
+const MIN_PATH_MTU: u16 = 576;
+
+/// IPv4 header (20) + ICMP echo header (8)
+const ICMP_OVERHEAD: u16 = 28;
+
+/// Outcome of one DF-flagged probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeOutcome {
+    /// Echo reply received
+    Answered,
+    /// Rejected locally: a "fragmentation needed" lowered the cached path MTU
+    TooBig,
+    /// Nothing came back
+    Silent,
+}
+
+/// Path MTU toward one target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PmtuResult {
+    pub target: Ipv4Addr,
+    /// MTU of the local interface (upper bound of the search)
+    pub interface_mtu: u16,
+    /// Largest packet that crossed the path, `None` if the target never answered
+    pub path_mtu: Option<u16>,
+    /// An ICMP "fragmentation needed" was received for oversized probes
+    pub frag_needed_seen: bool,
+    /// Oversized probes were silently dropped
+    pub black_hole: bool,
+}
+
+impl PmtuResult {
+    fn from_search(target: Ipv4Addr, interface_mtu: u16, path_mtu: Option<u16>, frag_needed_seen: bool) -> Self {
+        let black_hole = path_mtu.is_some_and(|mtu| mtu < interface_mtu) && !frag_needed_seen;
+        Self {
+            target,
+            interface_mtu,
+            path_mtu,
+            frag_needed_seen,
+            black_hole,
+        }
+    }
+
+    /// Insight for a reduced or black-holed path
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        let path_mtu = self.path_mtu?;
+        if path_mtu >= self.interface_mtu {
+            return None;
+        }
+
+        let (priority, title, description) = if self.black_hole {
+            (
+                Priority::High,
+                "Path MTU black hole detected".to_string(),
+                format!(
+                    "Packets larger than {} bytes toward {} are dropped without an ICMP \"fragmentation needed\" reply. \
+                     Large transfers and TLS handshakes will stall. Allow ICMP type 3 code 4 through the firewall, \
+                     or clamp TCP MSS / lower the MTU to {} on the tunnel or router.",
+                    path_mtu, self.target, path_mtu
+                ),
+            )
+        } else {
+            (
+                Priority::Low,
+                "Reduced path MTU".to_string(),
+                format!(
+                    "The path toward {} carries at most {} bytes (interface MTU {}), typically due to a VPN or PPPoE link. \
+                     Path MTU discovery is working, so no action is required unless applications misbehave.",
+                    self.target, path_mtu, self.interface_mtu
+                ),
+            )
+        };
+
+        Some(Recommendation {
+            priority,
+            category: "Network Performance".to_string(),
+            title,
+            description,
+            affected_devices: vec![self.target.to_string()],
+        })
+    }
+}
+
+/// Binary-search the largest answered packet size in `[MIN_PATH_MTU, interface_mtu]`
+///
+/// Returns the path MTU (if the target answers at all) and whether any probe
+/// was rejected because of a "fragmentation needed" reply.
+fn search_path_mtu(interface_mtu: u16, mut probe: impl FnMut(u16) -> ProbeOutcome) -> (Option<u16>, bool) {
+    let mut frag_needed_seen = false;
+    let mut check = |size: u16, seen: &mut bool| match probe(size) {
+        ProbeOutcome::Answered => true,
+        ProbeOutcome::TooBig => {
+            *seen = true;
+            false
+        }
+        ProbeOutcome::Silent => false,
+    };
+
+    if check(interface_mtu, &mut frag_needed_seen) {
+        return (Some(interface_mtu), frag_needed_seen);
+    }
+    if !check(MIN_PATH_MTU, &mut frag_needed_seen) {
+        return (None, frag_needed_seen);
+    }
+
+    // Invariant: `lo` answered, `hi` did not
+    let (mut lo, mut hi) = (MIN_PATH_MTU, interface_mtu);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if check(mid, &mut frag_needed_seen) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    (Some(lo), frag_needed_seen)
+}
+
+/// Discover the path MTU toward `target` with DF-flagged pings
+///
+/// `interface_mtu` bounds the search (1500 on Ethernet/Wi-Fi). Requires an
+/// ICMP socket: unprivileged ping sockets are used when the kernel allows
+/// them, raw sockets otherwise.
+pub async fn pmtu_probe(target: Ipv4Addr, interface_mtu: u16) -> Result<PmtuResult> {
+    if interface_mtu < MIN_PATH_MTU {
+        return Err(anyhow!("Interface MTU {} is below the IPv4 minimum", interface_mtu));
+    }
+
+    log_stderr!("PMTU: probing path toward {} (interface MTU {})...", target, interface_mtu);
+
+    let result = tokio::task::spawn_blocking(move || probe_blocking(target, interface_mtu)).await??;
+    match (result.path_mtu, result.black_hole) {
+        (Some(mtu), true) => {
+            log_stderr!("PMTU: {} bytes toward {} - black hole (no fragmentation-needed)", mtu, target);
+        }
+        (Some(mtu), false) => {
+            log_stderr!("PMTU: {} bytes toward {}", mtu, target);
+        }
+        (None, _) => {
+            log_stderr!("PMTU: {} did not answer echo requests", target);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(target_os = "linux")]
+fn probe_blocking(target: Ipv4Addr, interface_mtu: u16) -> Result<PmtuResult> {
+    let mut pinger = linux::DfPinger::open(target)?;
+    let (path_mtu, frag_needed_seen) = search_path_mtu(interface_mtu, |size| pinger.probe(size));
+    Ok(PmtuResult::from_search(target, interface_mtu, path_mtu, frag_needed_seen))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_blocking(_target: Ipv4Addr, _interface_mtu: u16) -> Result<PmtuResult> {
+    Err(anyhow!("Path MTU discovery is only supported on Linux"))
+}
+
+/// Build an ICMP echo request carrying `payload_len` bytes
+fn echo_request(ident: u16, seq: u16, payload_len: usize) -> Vec<u8> {
+    let mut packet = vec![0u8; 8 + payload_len];
+    packet[0] = 8; // echo request
+    packet[4..6].copy_from_slice(&ident.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    for (i, byte) in packet[8..].iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let checksum = internet_checksum(&packet);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    packet
+}
+
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{echo_request, ProbeOutcome, ICMP_OVERHEAD};
+    use anyhow::{anyhow, Result};
+    use std::io;
+    use std::net::Ipv4Addr;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::{Duration, Instant};
+
+    /// How long to wait for the reply to one probe
+    const PROBE_TIMEOUT: Duration = Duration::from_millis(600);
+
+    /// Attempts per size before declaring it silent
+    const PROBE_ATTEMPTS: u8 = 2;
+
+    /// ICMP socket connected to the target with the DF bit forced on
+    pub struct DfPinger {
+        fd: OwnedFd,
+        /// Raw sockets deliver the IP header; ping sockets do not
+        raw: bool,
+        ident: u16,
+        seq: u16,
+    }
+
+    impl DfPinger {
+        pub fn open(target: Ipv4Addr) -> Result<Self> {
+            let (raw_fd, raw) = match socket(libc::SOCK_DGRAM) {
+                Ok(fd) => (fd, false),
+                Err(_) => (socket(libc::SOCK_RAW).map_err(|e| anyhow!("ICMP socket unavailable: {}", e))?, true),
+            };
+            let fd = unsafe { OwnedFd::from_raw_fd(raw_fd) };
+
+            let pmtudisc: libc::c_int = libc::IP_PMTUDISC_DO;
+            setsockopt(&fd, libc::IPPROTO_IP, libc::IP_MTU_DISCOVER, &pmtudisc)?;
+            let timeout = libc::timeval {
+                tv_sec: 0,
+                tv_usec: 100_000,
+            };
+            setsockopt(&fd, libc::SOL_SOCKET, libc::SO_RCVTIMEO, &timeout)?;
+
+            let mut addr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+            addr.sin_family = libc::AF_INET as libc::sa_family_t;
+            addr.sin_addr.s_addr = u32::from(target).to_be();
+            let ret = unsafe {
+                libc::connect(
+                    fd.as_raw_fd(),
+                    &addr as *const _ as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                )
+            };
+            if ret < 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+
+            Ok(Self {
+                fd,
+                raw,
+                ident: std::process::id() as u16,
+                seq: 0,
+            })
+        }
+
+        /// Send DF echo requests of `size` bytes (IP total length)
+        pub fn probe(&mut self, size: u16) -> ProbeOutcome {
+            for _ in 0..PROBE_ATTEMPTS {
+                self.seq = self.seq.wrapping_add(1);
+                let packet = echo_request(self.ident, self.seq, (size - ICMP_OVERHEAD) as usize);
+                let sent = unsafe {
+                    libc::send(self.fd.as_raw_fd(), packet.as_ptr() as *const libc::c_void, packet.len(), 0)
+                };
+                if sent < 0 {
+                    if io::Error::last_os_error().raw_os_error() == Some(libc::EMSGSIZE) {
+                        return ProbeOutcome::TooBig;
+                    }
+                    continue;
+                }
+                if self.await_reply(self.seq) {
+                    return ProbeOutcome::Answered;
+                }
+            }
+            ProbeOutcome::Silent
+        }
+
+        fn await_reply(&self, seq: u16) -> bool {
+            let deadline = Instant::now() + PROBE_TIMEOUT;
+            let mut buf = [0u8; 2048];
+
+            while Instant::now() < deadline {
+                let n = unsafe {
+                    libc::recv(self.fd.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0)
+                };
+                if n <= 0 {
+                    continue;
+                }
+                let mut icmp = &buf[..n as usize];
+                if self.raw {
+                    let header_len = ((icmp[0] & 0x0F) as usize) * 4;
+                    icmp = match icmp.get(header_len..) {
+                        Some(rest) => rest,
+                        None => continue,
+                    };
+                }
+                // Ping sockets rewrite the identifier, so only raw sockets check it
+                let is_reply = icmp.len() >= 8
+                    && icmp[0] == 0
+                    && u16::from_be_bytes([icmp[6], icmp[7]]) == seq
+                    && (!self.raw || u16::from_be_bytes([icmp[4], icmp[5]]) == self.ident);
+                if is_reply {
+                    return true;
+                }
+            }
+            false
+        }
+    }
+
+    fn socket(kind: libc::c_int) -> io::Result<libc::c_int> {
+        let fd = unsafe { libc::socket(libc::AF_INET, kind | libc::SOCK_CLOEXEC, libc::IPPROTO_ICMP) };
+        if fd < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(fd)
+        }
+    }
+
+    fn setsockopt<T>(fd: &OwnedFd, level: libc::c_int, name: libc::c_int, value: &T) -> io::Result<()> {
+        let ret = unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                level,
+                name,
+                value as *const T as *const libc::c_void,
+                std::mem::size_of::<T>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_finds_tunnel_mtu() {
+        // 1420-byte WireGuard tunnel that reports fragmentation-needed
+        let (mtu, seen) = search_path_mtu(1500, |size| {
+            if size <= 1420 {
+                ProbeOutcome::Answered
+            } else {
+                ProbeOutcome::TooBig
+            }
+        });
+        assert_eq!(mtu, Some(1420));
+        assert!(seen);
+        assert!(!PmtuResult::from_search(Ipv4Addr::new(10, 0, 0, 1), 1500, mtu, seen).black_hole);
+    }
+
+    #[test]
+    fn test_search_detects_black_hole() {
+        let (mtu, seen) = search_path_mtu(1500, |size| {
+            if size <= 1400 {
+                ProbeOutcome::Answered
+            } else {
+                ProbeOutcome::Silent
+            }
+        });
+        let result = PmtuResult::from_search(Ipv4Addr::new(10, 0, 0, 1), 1500, mtu, seen);
+        assert_eq!(result.path_mtu, Some(1400));
+        assert!(result.black_hole);
+        assert_eq!(result.recommendation().unwrap().priority, Priority::High);
+    }
+
+    #[test]
+    fn test_echo_request_checksum() {
+        let packet = echo_request(0x1234, 1, 32);
+        assert_eq!(packet.len(), 40);
+        assert_eq!(internet_checksum(&packet), 0);
+    }
+}
@@ -0,0 +1,180 @@
+//! Scan profiles
+//!
+//! Most users want to pick "quick" or "deep", not tune a dozen phase
+//! switches, port lists and send rates. A profile expands into a
+//! `ScanConfig` holding every lower-level knob; the scan reads the knobs,
+//! so a profile can still be adjusted field by field afterwards.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::{
+    FIRMWARE_PROBES_ENABLED, SEND_JITTER, SEND_RATE_PPS, SMART_HOME_DETECTION_ENABLED, SNMP_ENABLED,
+    STEALTH_PASSIVE_LISTEN, STEALTH_SEND_JITTER, STEALTH_SEND_RATE_PPS, TCP_PROBE_PORTS,
+};
+use super::marking::ProbeMarking;
+use super::icmp::IcmpPayload;
+use super::limits::{link_speed_mbps, send_rate_for_link};
+use crate::network::HostnamePolicy;
+
+/// Selects the scan profile for the CLI (`quick`, `standard`, `deep`, `stealth`)
+pub const SCAN_PROFILE_ENV: &str = "HOST_DISCOVERY_PROFILE";
+
+/// High-level scan depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanProfile {
+    /// ARP sweep only
+    Quick,
+    /// ARP, ICMP latency and the common TCP ports
+    #[default]
+    Standard,
+    /// Every TCP port, banners and firmware, SNMP, mDNS/smart-home
+    Deep,
+    /// Listen passively first, then a slow active sweep
+    Stealth,
+}
+
+impl ScanProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanProfile::Quick => "quick",
+            ScanProfile::Standard => "standard",
+            ScanProfile::Deep => "deep",
+            ScanProfile::Stealth => "stealth",
+        }
+    }
+}
+
+impl FromStr for ScanProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "quick" => Ok(ScanProfile::Quick),
+            "standard" => Ok(ScanProfile::Standard),
+            "deep" => Ok(ScanProfile::Deep),
+            "stealth" => Ok(ScanProfile::Stealth),
+            other => Err(anyhow!("Unknown scan profile '{}' (quick, standard, deep, stealth)", other)),
+        }
+    }
+}
+
+/// Every phase switch and rate the scan reads
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanConfig {
+    pub profile: ScanProfile,
+    /// ICMP latency and TTL measurement
+    pub icmp: bool,
+    /// Echo request payload size and fill pattern
+    pub icmp_payload: IcmpPayload,
+    /// TCP ports to probe (empty skips the TCP phase)
+    pub tcp_ports: Vec<u16>,
+    /// SNMP enrichment and switch FDB walks
+    pub snmp: bool,
+    /// Service banners and firmware versions
+    pub banners: bool,
+    /// mDNS/SSDP smart-home identification
+    pub smart_home: bool,
+    /// Passive ARP listening before any packet is sent
+    pub passive_listen: Option<Duration>,
+    /// Shared send rate in packets per second (0 = unlimited)
+    pub send_rate_pps: u32,
+    /// Upper bound of the random delay before each send (zero = none)
+    pub send_jitter: Duration,
+    /// DSCP class and TTL of ICMP, TCP and UDP probes
+    pub marking: ProbeMarking,
+    /// Which of PTR, mDNS, SNMP and DHCP names becomes the hostname
+    pub hostname_policy: HostnamePolicy,
+}
+
+impl ScanConfig {
+    /// Expand a profile into its knobs
+    pub fn from_profile(profile: ScanProfile) -> Self {
+        let standard = Self {
+            profile,
+            icmp: true,
+            icmp_payload: IcmpPayload::default(),
+            tcp_ports: TCP_PROBE_PORTS.to_vec(),
+            snmp: SNMP_ENABLED,
+            banners: FIRMWARE_PROBES_ENABLED,
+            smart_home: SMART_HOME_DETECTION_ENABLED,
+            passive_listen: None,
+            send_rate_pps: SEND_RATE_PPS,
+            send_jitter: SEND_JITTER,
+            marking: ProbeMarking::default(),
+            hostname_policy: HostnamePolicy::default(),
+        };
+
+        match profile {
+            ScanProfile::Quick => Self {
+                icmp: false,
+                tcp_ports: Vec::new(),
+                snmp: false,
+                banners: false,
+                smart_home: false,
+                ..standard
+            },
+            ScanProfile::Standard => standard,
+            ScanProfile::Deep => Self {
+                tcp_ports: (1..=u16::MAX).collect(),
+                snmp: true,
+                banners: true,
+                smart_home: true,
+                ..standard
+            },
+            ScanProfile::Stealth => Self {
+                icmp: false,
+                snmp: false,
+                banners: false,
+                smart_home: false,
+                passive_listen: Some(STEALTH_PASSIVE_LISTEN),
+                send_rate_pps: STEALTH_SEND_RATE_PPS,
+                send_jitter: STEALTH_SEND_JITTER,
+                ..standard
+            },
+        }
+    }
+
+    /// Replace the fixed default send rate with one sized for the link
+    /// speed of `interface`; profiles with a rate of their own keep it
+    pub fn size_send_rate(&mut self, interface: &str) {
+        if self.send_rate_pps != SEND_RATE_PPS {
+            return;
+        }
+        if let Some(speed) = link_speed_mbps(interface) {
+            self.send_rate_pps = send_rate_for_link(speed);
+        }
+    }
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self::from_profile(ScanProfile::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_map_to_knobs() {
+        let quick = ScanConfig::from_profile("Quick".parse().unwrap());
+        assert!(!quick.icmp && quick.tcp_ports.is_empty() && !quick.snmp);
+
+        let deep = ScanConfig::from_profile(ScanProfile::Deep);
+        assert_eq!(deep.tcp_ports.len(), 65535);
+        assert!(deep.snmp && deep.banners && deep.smart_home);
+
+        let stealth = ScanConfig::from_profile(ScanProfile::Stealth);
+        assert_eq!(stealth.send_rate_pps, STEALTH_SEND_RATE_PPS);
+        assert!(!stealth.send_jitter.is_zero());
+        assert!(stealth.passive_listen.is_some() && !stealth.icmp);
+
+        assert_eq!(ScanConfig::default().profile, ScanProfile::Standard);
+        assert!("thorough".parse::<ScanProfile>().is_err());
+    }
+}
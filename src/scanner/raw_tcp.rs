@@ -0,0 +1,245 @@
+//! Raw TCP scans for firewall rule mapping
+//!
+//! Crafts TCP segments with chosen flags instead of using the OS connect()
+//! path. An ACK scan never opens a connection: a stateless ACL lets the
+//! stray ACK through and the host answers with RST ("unfiltered"), while a
+//! filtering rule drops it silently ("filtered"). Mapping which ports come
+//! back unfiltered from another segment reveals the router's ACL rules.
+//!
+//! FIN, NULL and Xmas scans exploit RFC 793: a closed port answers any
+//! segment without SYN/RST/ACK with RST, while an open port stays silent.
+//! They slip past filters that only drop SYNs and are useful for checking
+//! whether an IDS notices them. Windows and some embedded stacks answer RST
+//! regardless, so "closed" from those hosts is not conclusive.
+//! Requires raw socket privileges.
+
+use anyhow::{anyhow, Result};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::tcp::{ipv4_checksum, MutableTcpPacket, TcpFlags, TcpPacket};
+use pnet::transport::{tcp_packet_iter, transport_channel, TransportChannelType, TransportProtocol};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use super::audit::{audit_probe, ProbeKind};
+use super::pacer::{is_send_congestion, shared_pacer, Congestion};
+use crate::models::{Port, PortState};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// TCP header without options
+const TCP_HEADER_LEN: usize = 20;
+
+/// How long to wait for responses after each send round
+const RAW_SCAN_WAIT: Duration = Duration::from_millis(800);
+
+/// Send rounds; unanswered ports are re-probed to absorb packet loss
+const RAW_SCAN_ROUNDS: u8 = 2;
+
+/// Flag combination sent by a raw scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TcpScanMode {
+    /// Bare ACK: maps filtered/unfiltered ports
+    Ack,
+    /// FIN only
+    Fin,
+    /// No flags at all
+    Null,
+    /// FIN + PSH + URG ("lit up like a Christmas tree")
+    Xmas,
+}
+
+impl TcpScanMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TcpScanMode::Ack => "ack",
+            TcpScanMode::Fin => "fin",
+            TcpScanMode::Null => "null",
+            TcpScanMode::Xmas => "xmas",
+        }
+    }
+
+    fn flags(&self) -> u8 {
+        match self {
+            TcpScanMode::Ack => TcpFlags::ACK,
+            TcpScanMode::Fin => TcpFlags::FIN,
+            TcpScanMode::Null => 0,
+            TcpScanMode::Xmas => TcpFlags::FIN | TcpFlags::PSH | TcpFlags::URG,
+        }
+    }
+
+    /// Interpret the TCP flags of the response (`None` = no response)
+    fn classify(&self, response: Option<u8>) -> PortState {
+        let reset = response.is_some_and(|flags| flags & TcpFlags::RST != 0);
+        match (self, reset) {
+            (TcpScanMode::Ack, true) => PortState::Unfiltered,
+            (TcpScanMode::Ack, false) => PortState::Filtered,
+            (_, true) => PortState::Closed,
+            (_, false) => PortState::OpenFiltered,
+        }
+    }
+}
+
+/// Raw scan result for one host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawScanResult {
+    pub target: Ipv4Addr,
+    pub mode: TcpScanMode,
+    /// Probed ports; `Unfiltered`/`Filtered` for ACK scans, `Closed`/`OpenFiltered` otherwise
+    pub ports: Vec<Port>,
+}
+
+impl RawScanResult {
+    /// Ports in the given state
+    pub fn ports_in(&self, state: PortState) -> Vec<u16> {
+        self.ports.iter().filter(|p| p.state == state).map(|p| p.number).collect()
+    }
+
+    /// One-line description of the ports per state
+    pub fn summary(&self) -> String {
+        let states: &[PortState] = match self.mode {
+            TcpScanMode::Ack => &[PortState::Unfiltered, PortState::Filtered],
+            _ => &[PortState::OpenFiltered, PortState::Closed],
+        };
+        let parts: Vec<String> = states
+            .iter()
+            .map(|state| {
+                let ports = self.ports_in(*state);
+                let list = if ports.is_empty() {
+                    "none".to_string()
+                } else {
+                    ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+                };
+                format!("{} {}", state.as_str(), list)
+            })
+            .collect();
+        format!("{} {} scan: {}", self.target, self.mode.as_str(), parts.join("; "))
+    }
+}
+
+/// Build a flag-only TCP segment with a valid checksum
+fn build_segment(
+    buffer: &mut [u8; TCP_HEADER_LEN],
+    source: Ipv4Addr,
+    target: Ipv4Addr,
+    source_port: u16,
+    port: u16,
+    flags: u8,
+) {
+    buffer.fill(0);
+    let mut tcp = MutableTcpPacket::new(&mut buffer[..]).expect("buffer holds a TCP header");
+    tcp.set_source(source_port);
+    tcp.set_destination(port);
+    tcp.set_sequence(u32::from(target) ^ ((port as u32) << 16));
+    tcp.set_acknowledgement(if flags & TcpFlags::ACK != 0 { u32::from(source).rotate_left(7) } else { 0 });
+    tcp.set_data_offset((TCP_HEADER_LEN / 4) as u8);
+    tcp.set_flags(flags);
+    tcp.set_window(1024);
+    let checksum = ipv4_checksum(&tcp.to_immutable(), &source, &target);
+    tcp.set_checksum(checksum);
+}
+
+/// Run a raw TCP scan of `ports` on `target` (blocking)
+///
+/// `source` must be the local address used to reach the target; it is part
+/// of the TCP checksum.
+pub fn raw_tcp_scan(source: Ipv4Addr, target: Ipv4Addr, ports: &[u16], mode: TcpScanMode) -> Result<RawScanResult> {
+    let protocol = TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Tcp));
+    let (mut tx, mut rx) = transport_channel(4096, protocol)
+        .map_err(|e| anyhow!("Raw TCP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+
+    log_stderr!("Raw {} scan: {} ports on {}...", mode.as_str(), ports.len(), target);
+
+    let source_port = 40_000 + (std::process::id() % 20_000) as u16;
+    let mut responses: HashMap<u16, u8> = HashMap::new();
+    let mut buffer = [0u8; TCP_HEADER_LEN];
+    let mut packets = tcp_packet_iter(&mut rx);
+    let pacer = shared_pacer();
+
+    for round in 0..RAW_SCAN_ROUNDS {
+        let pending: Vec<u16> = ports.iter().copied().filter(|p| !responses.contains_key(p)).collect();
+        if pending.is_empty() {
+            break;
+        }
+
+        for &port in &pending {
+            build_segment(&mut buffer, source, target, source_port, port, mode.flags());
+            pacer.acquire(1);
+            let segment = TcpPacket::new(&buffer[..]).expect("buffer holds a TCP header");
+            audit_probe(target, ProbeKind::TcpRaw, Some(port));
+            match tx.send_to(segment, IpAddr::V4(target)) {
+                Ok(_) => {}
+                Err(e) if is_send_congestion(&e) => pacer.report(Congestion::SendError),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let answered_before = responses.len();
+
+        let deadline = Instant::now() + RAW_SCAN_WAIT;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match packets.next_with_timeout(remaining) {
+                Ok(Some((reply, IpAddr::V4(from)))) if from == target && reply.get_destination() == source_port => {
+                    responses.entry(reply.get_source()).or_insert(reply.get_flags());
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // Answers to retransmissions mean the first round was lost
+        if round > 0 && responses.len() > answered_before {
+            pacer.report(Congestion::ReplyLoss);
+        }
+    }
+
+    let result = RawScanResult {
+        target,
+        mode,
+        ports: ports.iter().map(|&p| Port::tcp(p, mode.classify(responses.get(&p).copied()))).collect(),
+    };
+    log_stderr!("{}", result.summary());
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ack_classification() {
+        assert_eq!(TcpScanMode::Ack.classify(Some(TcpFlags::RST)), PortState::Unfiltered);
+        assert_eq!(TcpScanMode::Ack.classify(None), PortState::Filtered);
+    }
+
+    #[test]
+    fn test_stealth_classification() {
+        for mode in [TcpScanMode::Fin, TcpScanMode::Null, TcpScanMode::Xmas] {
+            assert_eq!(mode.classify(Some(TcpFlags::RST | TcpFlags::ACK)), PortState::Closed);
+            assert_eq!(mode.classify(None), PortState::OpenFiltered);
+        }
+        assert_eq!(TcpScanMode::Null.flags(), 0);
+        assert_eq!(TcpScanMode::Xmas.flags(), TcpFlags::FIN | TcpFlags::PSH | TcpFlags::URG);
+    }
+
+    #[test]
+    fn test_segment_flags_and_checksum() {
+        let source = Ipv4Addr::new(192, 168, 1, 10);
+        let target = Ipv4Addr::new(192, 168, 2, 1);
+        let mut buffer = [0u8; TCP_HEADER_LEN];
+        build_segment(&mut buffer, source, target, 40_123, 443, TcpScanMode::Ack.flags());
+
+        let tcp = TcpPacket::new(&buffer).unwrap();
+        assert_eq!(tcp.get_destination(), 443);
+        assert_eq!(tcp.get_flags(), TcpFlags::ACK);
+        assert_eq!(tcp.get_checksum(), ipv4_checksum(&tcp, &source, &target));
+    }
+}
@@ -0,0 +1,233 @@
+//! Router ARP table and DHCP lease import over SSH
+//!
+//! Runs read-only commands on a router through the system `ssh` client and
+//! parses its ARP cache and DHCP leases. The router sees every VLAN it
+//! routes, so this catches hosts the scanning machine cannot reach at L2.
+//! Authentication is left to ssh (keys/agent); BatchMode makes a missing
+//! key fail fast instead of prompting.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::config::ROUTER_SSH_TIMEOUT;
+use crate::network::MacAddress;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Router firmware, which decides where the lease table lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterKind {
+    /// dnsmasq leases in /tmp/dhcp.leases
+    OpenWrt,
+    /// Ubiquiti EdgeOS / VyOS, leases from the op-mode command
+    EdgeOs,
+    /// Generic Linux router running dnsmasq
+    Linux,
+}
+
+impl RouterKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouterKind::OpenWrt => "openwrt",
+            RouterKind::EdgeOs => "edgeos",
+            RouterKind::Linux => "linux",
+        }
+    }
+
+    /// Remote command printing the DHCP leases
+    fn lease_command(&self) -> &'static str {
+        match self {
+            RouterKind::OpenWrt => "cat /tmp/dhcp.leases",
+            RouterKind::EdgeOs => "/opt/vyatta/bin/vyatta-op-cmd-wrapper show dhcp leases",
+            RouterKind::Linux => "cat /var/lib/misc/dnsmasq.leases",
+        }
+    }
+}
+
+/// Where a router entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterEntrySource {
+    Arp,
+    DhcpLease,
+}
+
+/// A host known to the router
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouterHost {
+    pub ip: Ipv4Addr,
+    /// MAC address in upper-case colon notation
+    pub mac: String,
+    pub hostname: Option<String>,
+    /// Router interface the host was seen on (ARP entries only)
+    pub interface: Option<String>,
+    pub source: RouterEntrySource,
+}
+
+/// Parse /proc/net/arp, skipping incomplete entries
+pub fn parse_proc_net_arp(output: &str) -> Vec<RouterHost> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (ip, flags, mac, device) = (fields.first()?, fields.get(2)?, fields.get(3)?, fields.get(5)?);
+            // 0x0 = incomplete
+            if *flags == "0x0" || *mac == "00:00:00:00:00:00" {
+                return None;
+            }
+            Some(RouterHost {
+                ip: ip.parse().ok()?,
+                mac: MacAddress::normalize(mac),
+                hostname: None,
+                interface: Some(device.to_string()),
+                source: RouterEntrySource::Arp,
+            })
+        })
+        .collect()
+}
+
+/// Parse a dnsmasq lease file: `expiry mac ip hostname client-id`
+pub fn parse_dnsmasq_leases(output: &str) -> Vec<RouterHost> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            Some(RouterHost {
+                ip: fields.get(2)?.parse().ok()?,
+                mac: MacAddress::normalize(fields.get(1)?),
+                hostname: fields.get(3).filter(|h| **h != "*").map(|h| h.to_string()),
+                interface: None,
+                source: RouterEntrySource::DhcpLease,
+            })
+        })
+        .collect()
+}
+
+/// Parse EdgeOS `show dhcp leases`: `IP  MAC  expiration  pool  client-name`
+pub fn parse_edgeos_leases(output: &str) -> Vec<RouterHost> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let ip: Ipv4Addr = fields.first()?.parse().ok()?;
+            let mac = fields.get(1)?;
+            if mac.len() != 17 {
+                return None;
+            }
+            // Expiration spans two fields (date and time), then pool, then client name
+            Some(RouterHost {
+                ip,
+                mac: MacAddress::normalize(mac),
+                hostname: fields.get(5).filter(|h| **h != "?").map(|h| h.to_string()),
+                interface: None,
+                source: RouterEntrySource::DhcpLease,
+            })
+        })
+        .collect()
+}
+
+/// Merge ARP entries and leases by IP; ARP wins for the MAC, leases add hostnames
+fn merge_entries(arp: Vec<RouterHost>, leases: Vec<RouterHost>) -> Vec<RouterHost> {
+    let mut hosts = arp;
+    for lease in leases {
+        match hosts.iter_mut().find(|h| h.ip == lease.ip) {
+            Some(host) => {
+                if host.hostname.is_none() && host.mac == lease.mac {
+                    host.hostname = lease.hostname;
+                }
+            }
+            None => hosts.push(lease),
+        }
+    }
+    hosts.sort_by_key(|h| h.ip);
+    hosts
+}
+
+/// Run one command on the router
+async fn ssh_run(target: &str, command: &str) -> Result<String> {
+    let output = timeout(
+        ROUTER_SSH_TIMEOUT,
+        Command::new("ssh")
+            .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5", target, command])
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow!("SSH to {} timed out", target))?
+    .context("Failed to run ssh")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`{}` failed on {}: {}",
+            command,
+            target,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Import the ARP table and DHCP leases from a router
+///
+/// `target` is anything ssh accepts (`admin@192.168.1.1`, a host alias).
+/// A missing lease file is not fatal; the ARP table alone is returned.
+pub async fn router_import(target: &str, kind: RouterKind) -> Result<Vec<RouterHost>> {
+    log_stderr!("Router import: reading ARP and DHCP tables from {} ({})...", target, kind.as_str());
+
+    let arp = parse_proc_net_arp(&ssh_run(target, "cat /proc/net/arp").await?);
+    let leases = match ssh_run(target, kind.lease_command()).await {
+        Ok(output) if kind == RouterKind::EdgeOs => parse_edgeos_leases(&output),
+        Ok(output) => parse_dnsmasq_leases(&output),
+        Err(e) => {
+            log_stderr!("Router import: no DHCP leases ({})", e);
+            Vec::new()
+        }
+    };
+
+    let hosts = merge_entries(arp, leases);
+    log_stderr!("Router import complete: {} hosts", hosts.len());
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arp_and_dnsmasq() {
+        let arp = "IP address       HW type     Flags       HW address            Mask     Device\n\
+                   192.168.1.20     0x1         0x2         aa:bb:cc:00:00:20     *        br-lan\n\
+                   192.168.20.5     0x1         0x2         aa:bb:cc:00:20:05     *        eth0.20\n\
+                   192.168.1.99     0x1         0x0         00:00:00:00:00:00     *        br-lan\n";
+        let leases = "1700000000 aa:bb:cc:00:00:20 192.168.1.20 laptop 01:aa:bb:cc:00:00:20\n\
+                      1700000000 aa:bb:cc:00:00:30 192.168.1.30 * *\n";
+
+        let hosts = merge_entries(parse_proc_net_arp(arp), parse_dnsmasq_leases(leases));
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts[0].hostname.as_deref(), Some("laptop"));
+        assert_eq!(hosts[0].source, RouterEntrySource::Arp);
+        assert_eq!(hosts[1].source, RouterEntrySource::DhcpLease);
+        assert_eq!(hosts[1].hostname, None);
+        assert_eq!(hosts[2].interface.as_deref(), Some("eth0.20"));
+    }
+
+    #[test]
+    fn test_parse_edgeos_leases() {
+        let output = "IP address      Hardware Address   Lease expiration     Pool     Client Name\n\
+                      ----------      ----------------   ----------------     ----     -----------\n\
+                      192.168.1.50    aa:bb:cc:00:00:50  2024/01/02 03:04:05  LAN      printer\n";
+        let hosts = parse_edgeos_leases(output);
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].mac, "AA:BB:CC:00:00:50");
+        assert_eq!(hosts[0].hostname.as_deref(), Some("printer"));
+    }
+}
@@ -0,0 +1,242 @@
+//! Simulated network for deterministic scans (`sim` feature)
+//!
+//! A `SimBackend` scripts how each host answers ARP, echo requests and TCP
+//! connects. While one is installed, the ARP sweep, ICMP pings and TCP port
+//! probes answer from the script instead of opening sockets, so a full
+//! pipeline runs in CI without root or a network. Everything above the send
+//! paths (pipelining, timeouts, result assembly) runs unchanged, and probes
+//! are still recorded in the audit trail.
+
+use pnet::util::MacAddr;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::Duration;
+
+use super::audit::{audit_probe, ProbeKind};
+use super::icmp::{HostState, IcmpResult};
+use crate::models::{ArpReplyStats, LatencyStats, Port, PortState};
+
+/// How a simulated host answers probes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimHost {
+    pub mac: MacAddr,
+    /// Echo round trip, `None` if the host drops pings
+    pub rtt: Option<Duration>,
+    /// TTL of echo replies
+    pub ttl: u8,
+    /// Ports accepting connections; every other port refuses them
+    pub open_ports: Vec<u16>,
+    /// Ports silently dropping connects
+    pub filtered_ports: Vec<u16>,
+}
+
+impl SimHost {
+    /// A Linux-like host answering pings in 1 ms with every port closed
+    pub fn new(mac: MacAddr) -> Self {
+        Self {
+            mac,
+            rtt: Some(Duration::from_millis(1)),
+            ttl: 64,
+            open_ports: Vec::new(),
+            filtered_ports: Vec::new(),
+        }
+    }
+
+    pub fn rtt(mut self, rtt: Option<Duration>) -> Self {
+        self.rtt = rtt;
+        self
+    }
+
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn open_ports(mut self, ports: &[u16]) -> Self {
+        self.open_ports = ports.to_vec();
+        self
+    }
+
+    pub fn filtered_ports(mut self, ports: &[u16]) -> Self {
+        self.filtered_ports = ports.to_vec();
+        self
+    }
+
+    fn port_state(&self, port: u16) -> PortState {
+        if self.open_ports.contains(&port) {
+            PortState::Open
+        } else if self.filtered_ports.contains(&port) {
+            PortState::Filtered
+        } else {
+            PortState::Closed
+        }
+    }
+}
+
+/// Scripted network; addresses without a host stay silent
+#[derive(Debug, Clone, Default)]
+pub struct SimBackend {
+    hosts: HashMap<Ipv4Addr, SimHost>,
+}
+
+impl SimBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) the host at `ip`
+    pub fn host(&mut self, ip: Ipv4Addr, host: SimHost) -> &mut Self {
+        self.hosts.insert(ip, host);
+        self
+    }
+
+    /// Answer an ARP sweep of `targets`, every host on the first request
+    pub(super) fn arp_sweep(
+        &self,
+        targets: &[Ipv4Addr],
+        mut on_host: impl FnMut(Ipv4Addr, MacAddr),
+    ) -> HashMap<Ipv4Addr, (MacAddr, ArpReplyStats)> {
+        let mut replies = HashMap::new();
+        for &ip in targets {
+            audit_probe(ip, ProbeKind::Arp, None);
+            if let Some(host) = self.hosts.get(&ip) {
+                let stats = ArpReplyStats {
+                    round: 1,
+                    requests: 1,
+                    latency_ms: host.rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+                };
+                replies.insert(ip, (host.mac, stats));
+                on_host(ip, host.mac);
+            }
+        }
+        replies
+    }
+
+    /// Answer `samples` echo requests like `ping_host_with_retries`
+    pub(super) fn ping(&self, ip: Ipv4Addr, samples: u8) -> Option<IcmpResult> {
+        audit_probe(ip, ProbeKind::Icmp, None);
+        let host = self.hosts.get(&ip)?;
+        let rtt = host.rtt?;
+        let wanted = samples.max(1) as usize;
+        let rtts = vec![Some(rtt.as_secs_f64() * 1000.0); wanted];
+        Some(IcmpResult {
+            state: HostState::Up,
+            duration: Some(rtt),
+            ttl: Some(host.ttl),
+            stats: if wanted > 1 { LatencyStats::from_samples(&rtts) } else { None },
+            sent: wanted as u32,
+            received: wanted as u32,
+            unreachable_code: None,
+        })
+    }
+
+    /// Answer a TCP connect to `port`; absent hosts look filtered
+    pub(super) fn probe_port(&self, ip: Ipv4Addr, port: u16) -> Port {
+        audit_probe(ip, ProbeKind::TcpConnect, Some(port));
+        let host = self.hosts.get(&ip);
+        let state = host.map_or(PortState::Filtered, |h| h.port_state(port));
+        Port {
+            connect_ms: host
+                .filter(|_| state == PortState::Open)
+                .map(|h| h.rtt.unwrap_or_default().as_secs_f64() * 1000.0),
+            ..Port::tcp(port, state)
+        }
+    }
+}
+
+static SIM_BACKEND: RwLock<Option<Arc<SimBackend>>> = RwLock::new(None);
+
+/// Held while a backend is installed so simulations never overlap
+static SIM_SESSION: Mutex<()> = Mutex::new(());
+
+/// Installed simulated network; real probes resume when it is dropped
+#[must_use = "the simulated network is removed when the guard is dropped"]
+pub struct SimGuard {
+    _session: MutexGuard<'static, ()>,
+}
+
+impl Drop for SimGuard {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = SIM_BACKEND.write() {
+            *slot = None;
+        }
+    }
+}
+
+/// Answer every ARP, ICMP and TCP probe from `backend` while the guard lives
+///
+/// Waits for any other installed backend to be dropped first, so concurrent
+/// tests each scan their own network, and a panicking test still removes
+/// its backend when unwinding.
+pub fn install_sim_backend(backend: SimBackend) -> SimGuard {
+    let session = SIM_SESSION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Ok(mut slot) = SIM_BACKEND.write() {
+        *slot = Some(Arc::new(backend));
+    }
+    SimGuard { _session: session }
+}
+
+/// The installed simulated network, if any
+pub(super) fn sim_backend() -> Option<Arc<SimBackend>> {
+    SIM_BACKEND.read().ok().and_then(|slot| slot.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InterfaceInfo;
+    use crate::scanner::{pipelined_discovery, IcmpPayload};
+    use pnet::datalink::NetworkInterface;
+
+    #[tokio::test]
+    async fn test_pipelined_discovery_on_simulated_network() {
+        let router = Ipv4Addr::new(192, 168, 50, 1);
+        let nas = Ipv4Addr::new(192, 168, 50, 20);
+        let mut sim = SimBackend::new();
+        sim.host(router, SimHost::new(MacAddr::new(0, 0x1b, 0x21, 0, 0, 1)).ttl(255).open_ports(&[53, 80]))
+            .host(
+                nas,
+                SimHost::new(MacAddr::new(0, 0x11, 0x32, 0, 0, 20))
+                    .rtt(None)
+                    .open_ports(&[445])
+                    .filtered_ports(&[22]),
+            );
+        let guard = install_sim_backend(sim);
+
+        let interface = InterfaceInfo {
+            name: "sim0".to_string(),
+            ip: Ipv4Addr::new(192, 168, 50, 10),
+            mac: MacAddr::new(2, 0, 0, 0, 0, 10),
+            prefix_len: 24,
+            aliases: Vec::new(),
+            pnet_interface: NetworkInterface {
+                name: "sim0".to_string(),
+                description: "Simulated interface".to_string(),
+                index: 0,
+                mac: None,
+                ips: vec![],
+                flags: 0,
+            },
+        };
+        let subnet = "192.168.50.0/24".parse().unwrap();
+        let ips: Vec<Ipv4Addr> = (1..=30).map(|i| Ipv4Addr::new(192, 168, 50, i)).collect();
+        let result = pipelined_discovery(&interface, &ips, &subnet, &[22, 53, 80, 445], Some(&IcmpPayload::default()))
+            .await
+            .unwrap();
+        drop(guard);
+
+        assert_eq!(result.hosts.len(), 2);
+        assert_eq!(result.latency[&router].ttl, Some(255));
+        assert_eq!(result.latency[&nas].state, HostState::Filtered);
+        let states = |ip: Ipv4Addr| result.ports[&ip].iter().map(|p| (p.number, p.state)).collect::<Vec<_>>();
+        assert_eq!(
+            states(router),
+            vec![(22, PortState::Closed), (53, PortState::Open), (80, PortState::Open), (445, PortState::Closed)]
+        );
+        assert_eq!(
+            states(nas),
+            vec![(22, PortState::Filtered), (53, PortState::Closed), (80, PortState::Closed), (445, PortState::Open)]
+        );
+    }
+}
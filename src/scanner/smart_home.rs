@@ -0,0 +1,377 @@
+//! Smart-home device detection
+//!
+//! Consumer smart-home gear mostly ships Wi-Fi modules from a handful of
+//! chipset vendors, so the OUI only says "Espressif Inc.". Each product
+//! family answers a cheap, unauthenticated identification request:
+//! - Chromecast / Google Cast: `/setup/eureka_info` on TCP 8008
+//! - Philips Hue bridge: `/api/config` on TCP 80
+//! - Sonos: UPnP device description on TCP 1400
+//! - MQTT brokers: a CONNECT on TCP 1883 answered by CONNACK
+//! - Matter and Thread border routers: `_matter._tcp` / `_meshcop._udp` over mDNS
+
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::timeout;
+
+use super::limits::concurrency_limits;
+use crate::models::{HostInfo, HostnameRecord, HostnameSource};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Timeout for each connect/read step of a probe
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// How long to browse mDNS for Matter/Thread announcements
+const MDNS_BROWSE_WINDOW: Duration = Duration::from_secs(3);
+
+const CAST_PORT: u16 = 8008;
+const SONOS_PORT: u16 = 1400;
+const MQTT_PORT: u16 = 1883;
+
+/// DNS-SD services announced by Thread border routers and Matter devices
+const MESHCOP_SERVICE: &str = "_meshcop._udp.local.";
+const MATTER_SERVICE: &str = "_matter._tcp.local.";
+
+/// MQTT 3.1.1 CONNECT, clean session, client id "ntm-probe", no credentials
+const MQTT_CONNECT: &[u8] = &[
+    0x10, 0x15, 0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04, 0x02, 0x00, 0x0a, 0x00, 0x09, b'n', b't', b'm', b'-',
+    b'p', b'r', b'o', b'b', b'e',
+];
+
+/// MQTT DISCONNECT
+const MQTT_DISCONNECT: &[u8] = &[0xe0, 0x00];
+
+/// Smart-home product family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartHomeKind {
+    Chromecast,
+    HueBridge,
+    Sonos,
+    MqttBroker,
+    ThreadBorderRouter,
+    MatterDevice,
+}
+
+impl SmartHomeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmartHomeKind::Chromecast => "chromecast",
+            SmartHomeKind::HueBridge => "hue_bridge",
+            SmartHomeKind::Sonos => "sonos",
+            SmartHomeKind::MqttBroker => "mqtt_broker",
+            SmartHomeKind::ThreadBorderRouter => "thread_border_router",
+            SmartHomeKind::MatterDevice => "matter_device",
+        }
+    }
+
+    /// Human-readable product description
+    pub fn label(&self) -> &'static str {
+        match self {
+            SmartHomeKind::Chromecast => "Google Cast device",
+            SmartHomeKind::HueBridge => "Philips Hue Bridge",
+            SmartHomeKind::Sonos => "Sonos speaker",
+            SmartHomeKind::MqttBroker => "MQTT broker",
+            SmartHomeKind::ThreadBorderRouter => "Thread border router",
+            SmartHomeKind::MatterDevice => "Matter device",
+        }
+    }
+
+    /// Device type the host is classified as
+    fn device_type(&self) -> &'static str {
+        match self {
+            SmartHomeKind::Chromecast => "SMART_TV",
+            _ => "IOT_DEVICE",
+        }
+    }
+}
+
+/// A recognized smart-home device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartHomeDevice {
+    pub ip: Ipv4Addr,
+    pub kind: SmartHomeKind,
+    /// Name the owner gave the device in its app ("Living Room")
+    pub name: Option<String>,
+    pub model: Option<String>,
+    /// Broker accepted a CONNECT without credentials (MQTT only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub anonymous_access: bool,
+}
+
+impl SmartHomeDevice {
+    fn new(ip: Ipv4Addr, kind: SmartHomeKind) -> Self {
+        Self {
+            ip,
+            kind,
+            name: None,
+            model: None,
+            anonymous_access: false,
+        }
+    }
+
+    /// Description for reports, e.g. "Sonos speaker (Play:1)"
+    pub fn description(&self) -> String {
+        match &self.model {
+            Some(model) => format!("{} ({})", self.kind.label(), model),
+            None => self.kind.label().to_string(),
+        }
+    }
+}
+
+/// Connect to a TCP port with the probe timeout
+async fn connect(ip: Ipv4Addr, port: u16) -> Option<TcpStream> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    match timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => Some(stream),
+        _ => None,
+    }
+}
+
+/// GET a path and return the body of a 200 response
+async fn http_get(ip: Ipv4Addr, port: u16, path: &str) -> Option<String> {
+    let mut stream = connect(ip, port).await?;
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", path, ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut response = Vec::new();
+    let _ = timeout(PROBE_TIMEOUT, stream.read_to_end(&mut response)).await;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if !head.starts_with("HTTP/1.") || head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    Some(body.to_string())
+}
+
+/// Text between `<tag>` and `</tag>`
+fn xml_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].trim()).filter(|v| !v.is_empty())
+}
+
+/// String field of a JSON object
+fn json_str(value: &serde_json::Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(str::to_string).filter(|v| !v.is_empty())
+}
+
+/// Google Cast `/setup/eureka_info`
+fn parse_eureka_info(ip: Ipv4Addr, body: &str) -> Option<SmartHomeDevice> {
+    let info: serde_json::Value = serde_json::from_str(body).ok()?;
+    let name = json_str(&info, "name")?;
+    let mut device = SmartHomeDevice::new(ip, SmartHomeKind::Chromecast);
+    device.name = Some(name);
+    device.model = json_str(&info, "model_name");
+    Some(device)
+}
+
+/// Hue `/api/config` (the unauthenticated subset includes name and model)
+fn parse_hue_config(ip: Ipv4Addr, body: &str) -> Option<SmartHomeDevice> {
+    let config: serde_json::Value = serde_json::from_str(body).ok()?;
+    let model = json_str(&config, "modelid")?;
+    json_str(&config, "bridgeid")?;
+    let mut device = SmartHomeDevice::new(ip, SmartHomeKind::HueBridge);
+    device.name = json_str(&config, "name");
+    device.model = Some(model);
+    Some(device)
+}
+
+/// Sonos UPnP device description
+fn parse_sonos_description(ip: Ipv4Addr, xml: &str) -> Option<SmartHomeDevice> {
+    if !xml_tag(xml, "manufacturer")?.contains("Sonos") {
+        return None;
+    }
+    let mut device = SmartHomeDevice::new(ip, SmartHomeKind::Sonos);
+    device.name = xml_tag(xml, "roomName").map(str::to_string);
+    device.model = xml_tag(xml, "modelName").map(str::to_string);
+    Some(device)
+}
+
+/// MQTT CONNACK: `Some(true)` when the broker accepted the anonymous CONNECT
+fn parse_connack(reply: &[u8]) -> Option<bool> {
+    match reply {
+        [0x20, 0x02, _, code, ..] => Some(*code == 0),
+        _ => None,
+    }
+}
+
+async fn probe_cast(ip: Ipv4Addr) -> Option<SmartHomeDevice> {
+    parse_eureka_info(ip, &http_get(ip, CAST_PORT, "/setup/eureka_info").await?)
+}
+
+async fn probe_hue(ip: Ipv4Addr) -> Option<SmartHomeDevice> {
+    parse_hue_config(ip, &http_get(ip, 80, "/api/config").await?)
+}
+
+async fn probe_sonos(ip: Ipv4Addr) -> Option<SmartHomeDevice> {
+    parse_sonos_description(ip, &http_get(ip, SONOS_PORT, "/xml/device_description.xml").await?)
+}
+
+async fn probe_mqtt(ip: Ipv4Addr) -> Option<SmartHomeDevice> {
+    let mut stream = connect(ip, MQTT_PORT).await?;
+    stream.write_all(MQTT_CONNECT).await.ok()?;
+
+    let mut reply = [0u8; 4];
+    timeout(PROBE_TIMEOUT, stream.read_exact(&mut reply)).await.ok()?.ok()?;
+    let accepted = parse_connack(&reply)?;
+    if accepted {
+        let _ = stream.write_all(MQTT_DISCONNECT).await;
+    }
+
+    let mut device = SmartHomeDevice::new(ip, SmartHomeKind::MqttBroker);
+    device.anonymous_access = accepted;
+    Some(device)
+}
+
+/// Probe one host for every smart-home family; the first match wins
+async fn probe_host(ip: Ipv4Addr) -> Option<SmartHomeDevice> {
+    if let Some(device) = probe_cast(ip).await {
+        return Some(device);
+    }
+    if let Some(device) = probe_hue(ip).await {
+        return Some(device);
+    }
+    if let Some(device) = probe_sonos(ip).await {
+        return Some(device);
+    }
+    probe_mqtt(ip).await
+}
+
+/// Browse mDNS for Thread border routers and Matter devices (blocking)
+fn browse_matter_thread() -> Vec<SmartHomeDevice> {
+    let Ok(mdns) = ServiceDaemon::new() else { return Vec::new() };
+    let mut found: Vec<SmartHomeDevice> = Vec::new();
+
+    for (service, kind) in [
+        (MESHCOP_SERVICE, SmartHomeKind::ThreadBorderRouter),
+        (MATTER_SERVICE, SmartHomeKind::MatterDevice),
+    ] {
+        let Ok(receiver) = mdns.browse(service) else { continue };
+        let deadline = Instant::now() + MDNS_BROWSE_WINDOW;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let Ok(event) = receiver.recv_timeout(remaining) else { break };
+            let ServiceEvent::ServiceResolved(info) = event else { continue };
+            for addr in info.get_addresses() {
+                let IpAddr::V4(ip) = addr else { continue };
+                if found.iter().any(|d| d.ip == *ip && d.kind == kind) {
+                    continue;
+                }
+                let mut device = SmartHomeDevice::new(*ip, kind);
+                // Border routers announce their vendor and product name in TXT records
+                device.name = info.get_property_val_str("nn").map(str::to_string);
+                device.model = info.get_property_val_str("mn").map(str::to_string);
+                found.push(device);
+            }
+        }
+        let _ = mdns.stop_browse(service);
+    }
+    let _ = mdns.shutdown();
+    found
+}
+
+/// Detect smart-home devices among the scanned hosts
+pub async fn smart_home_scan(hosts: &[HostInfo]) -> Vec<SmartHomeDevice> {
+    log_stderr!("Smart-home detection: probing {} hosts...", hosts.len());
+
+    let mdns = tokio::task::spawn_blocking(browse_matter_thread);
+
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let mut handles = Vec::new();
+    for host in hosts {
+        let Ok(ip) = host.ip.parse::<Ipv4Addr>() else { continue };
+        let semaphore = Arc::clone(&semaphore);
+        let results = Arc::clone(&results);
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+            if let Some(device) = probe_host(ip).await {
+                results.lock().await.push(device);
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.await;
+    }
+
+    let mut devices = results.lock().await.clone();
+    // HTTP identification is more specific than a Matter announcement
+    for device in mdns.await.unwrap_or_default() {
+        if !devices.iter().any(|d| d.ip == device.ip) {
+            devices.push(device);
+        }
+    }
+    devices.sort_by_key(|d| d.ip);
+
+    log_stderr!("Smart-home detection complete: {} devices", devices.len());
+    devices
+}
+
+/// Classify hosts from detected smart-home devices
+///
+/// Sets the device type, and fills the hostname and description when the
+/// scan found none, so the device shows up as "Living Room (Google Cast
+/// device)" rather than by its chipset vendor.
+pub fn apply_smart_home(hosts: &mut [HostInfo], devices: &[SmartHomeDevice]) {
+    let by_ip: HashMap<String, &SmartHomeDevice> = devices.iter().map(|d| (d.ip.to_string(), d)).collect();
+    for host in hosts.iter_mut() {
+        let Some(device) = by_ip.get(&host.ip) else { continue };
+        // A broker on a NAS or server keeps its classification
+        if device.kind != SmartHomeKind::MqttBroker || host.device_type == "UNKNOWN" {
+            host.device_type = device.kind.device_type().to_string();
+        }
+        if host.hostname.is_none() {
+            host.hostname = device.name.clone();
+        }
+        if let Some(name) = &device.name {
+            host.hostname_records.push(HostnameRecord { source: HostnameSource::Mdns, name: name.clone() });
+        }
+        if host.system_description.is_none() {
+            host.system_description = Some(device.description());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 40);
+
+    #[test]
+    fn test_parse_identification_responses() {
+        let cast = parse_eureka_info(IP, r#"{"name": "Living Room TV", "model_name": "Chromecast Ultra"}"#).unwrap();
+        assert_eq!(cast.kind, SmartHomeKind::Chromecast);
+        assert_eq!(cast.description(), "Google Cast device (Chromecast Ultra)");
+
+        let hue = parse_hue_config(IP, r#"{"name": "Hue Bridge", "bridgeid": "001788FFFE000000", "modelid": "BSB002"}"#).unwrap();
+        assert_eq!(hue.model.as_deref(), Some("BSB002"));
+        assert!(parse_hue_config(IP, r#"{"name": "router"}"#).is_none());
+
+        let xml = "<root><device><manufacturer>Sonos, Inc.</manufacturer><modelName>Sonos One</modelName>\
+                   <roomName>Kitchen</roomName></device></root>";
+        let sonos = parse_sonos_description(IP, xml).unwrap();
+        assert_eq!(sonos.name.as_deref(), Some("Kitchen"));
+        assert!(parse_sonos_description(IP, "<manufacturer>Other</manufacturer>").is_none());
+    }
+
+    #[test]
+    fn test_parse_connack() {
+        assert_eq!(parse_connack(&[0x20, 0x02, 0x00, 0x00]), Some(true));
+        assert_eq!(parse_connack(&[0x20, 0x02, 0x00, 0x05]), Some(false));
+        assert_eq!(parse_connack(b"HTTP"), None);
+        assert_eq!(MQTT_CONNECT.len(), 2 + MQTT_CONNECT[1] as usize);
+    }
+}
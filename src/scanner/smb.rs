@@ -0,0 +1,726 @@
+//! SMB share enumeration
+//!
+//! Speaks just enough SMB2 to list the shares a host offers without
+//! credentials: an anonymous (null) session is tried first, then a guest
+//! session via an unknown user name, which servers configured with
+//! "map to guest = bad user" accept. The share list comes from the srvsvc
+//! NetrShareEnum call over the IPC$ pipe, and each disk share's access is
+//! read from the MaximalAccess mask returned by TREE_CONNECT, so no file is
+//! ever opened or written.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::exposure::{ExposureFinding, ExposureKind};
+
+/// SMB over TCP port
+pub const SMB_PORT: u16 = 445;
+
+/// Timeout for each request/response exchange
+const SMB_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Upper bound on a single SMB message accepted from a server
+const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+
+/// Unknown user name sent to trigger guest mapping
+const GUEST_PROBE_USER: &str = "netmapper-guest-probe";
+
+// SMB2 commands
+const NEGOTIATE: u16 = 0x0000;
+const SESSION_SETUP: u16 = 0x0001;
+const TREE_CONNECT: u16 = 0x0003;
+const TREE_DISCONNECT: u16 = 0x0004;
+const CREATE: u16 = 0x0005;
+const READ: u16 = 0x0008;
+const WRITE: u16 = 0x0009;
+
+// NT status codes
+const STATUS_SUCCESS: u32 = 0x0000_0000;
+const STATUS_PENDING: u32 = 0x0000_0103;
+const STATUS_BUFFER_OVERFLOW: u32 = 0x8000_0005;
+const STATUS_MORE_PROCESSING_REQUIRED: u32 = 0xC000_0016;
+
+// SESSION_SETUP response flags
+const SESSION_FLAG_IS_GUEST: u16 = 0x0001;
+const SESSION_FLAG_IS_NULL: u16 = 0x0002;
+
+// Access mask bits relevant to share exposure
+const FILE_READ_DATA: u32 = 0x0000_0001;
+const FILE_WRITE_DATA: u32 = 0x0000_0002;
+const FILE_APPEND_DATA: u32 = 0x0000_0004;
+const GENERIC_ALL: u32 = 0x1000_0000;
+
+/// NTLMSSP flags: unicode, request target, NTLM, always sign, extended session security, 128/56-bit
+const NTLM_FLAGS: u32 = 0xA008_8205;
+const NTLM_FLAG_ANONYMOUS: u32 = 0x0000_0800;
+
+/// srvsvc interface 4b324fc8-1670-01d3-1278-5a47bf6ee188 v3.0
+const SRVSVC_UUID: [u8; 16] = [
+    0xc8, 0x4f, 0x32, 0x4b, 0x70, 0x16, 0xd3, 0x01, 0x12, 0x78, 0x5a, 0x47, 0xbf, 0x6e, 0xe1, 0x88,
+];
+
+/// NDR transfer syntax 8a885d04-1ceb-11c9-9fe8-08002b104860 v2
+const NDR_UUID: [u8; 16] = [
+    0x04, 0x5d, 0x88, 0x8a, 0xeb, 0x1c, 0xc9, 0x11, 0x9f, 0xe8, 0x08, 0x00, 0x2b, 0x10, 0x48, 0x60,
+];
+
+/// NetrShareEnum operation number
+const OPNUM_NETR_SHARE_ENUM: u16 = 15;
+
+/// Kind of session the server granted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmbSessionKind {
+    /// Anonymous null session
+    Anonymous,
+    /// Unknown user mapped to the guest account
+    Guest,
+}
+
+impl SmbSessionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmbSessionKind::Anonymous => "anonymous",
+            SmbSessionKind::Guest => "guest",
+        }
+    }
+}
+
+/// Share type from SHARE_INFO_1, special-share bit stripped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmbShareType {
+    Disk,
+    Printer,
+    Device,
+    Ipc,
+}
+
+impl SmbShareType {
+    fn from_raw(raw: u32) -> Self {
+        match raw & 0x0FFF_FFFF {
+            1 => SmbShareType::Printer,
+            2 => SmbShareType::Device,
+            3 => SmbShareType::Ipc,
+            _ => SmbShareType::Disk,
+        }
+    }
+}
+
+/// One share listed by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmbShare {
+    pub name: String,
+    pub share_type: SmbShareType,
+    pub remark: String,
+    /// Administrative share such as C$ or ADMIN$
+    pub special: bool,
+    /// Files can be read without credentials
+    pub readable: bool,
+    /// Files can be created or modified without credentials
+    pub writable: bool,
+}
+
+/// Shares visible on one host without credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmbShareReport {
+    pub ip: Ipv4Addr,
+    /// Session used for the enumeration
+    pub session: SmbSessionKind,
+    pub shares: Vec<SmbShare>,
+}
+
+impl SmbShareReport {
+    /// Readable and writable non-administrative disk shares
+    pub fn findings(&self) -> Vec<ExposureFinding> {
+        self.shares
+            .iter()
+            .filter(|s| s.share_type == SmbShareType::Disk && !s.special && s.readable)
+            .map(|share| {
+                let (kind, severity, access) = if share.writable {
+                    (ExposureKind::SmbShareWritable, "HIGH", "read/write")
+                } else {
+                    (ExposureKind::SmbShareReadable, "MEDIUM", "read")
+                };
+                ExposureFinding {
+                    kind,
+                    port: SMB_PORT,
+                    service: "SMB".to_string(),
+                    severity: severity.to_string(),
+                    evidence: format!("\\\\{}\\{} grants {} access to {} sessions", self.ip, share.name, access, self.session.as_str()),
+                    recommendation: "Disable guest access on the share and require an account; keep sensitive data off \
+                                     shares anyone on the network can reach"
+                        .to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Encode a string as UTF-16LE
+fn utf16(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+/// Raw SMB2 message returned by the server
+struct Response {
+    status: u32,
+    session_id: u64,
+    tree_id: u32,
+    /// Full message including the 64-byte header, offsets in bodies are relative to it
+    message: Vec<u8>,
+}
+
+impl Response {
+    fn body(&self) -> &[u8] {
+        &self.message[64..]
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        self.body().get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        self.body().get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+/// Minimal SMB2 client connection
+struct SmbConnection {
+    stream: TcpStream,
+    message_id: u64,
+    session_id: u64,
+    tree_id: u32,
+}
+
+impl SmbConnection {
+    async fn connect(ip: Ipv4Addr) -> Result<Self> {
+        let addr = SocketAddr::new(IpAddr::V4(ip), SMB_PORT);
+        let stream = timeout(SMB_TIMEOUT, TcpStream::connect(addr))
+            .await
+            .map_err(|_| anyhow!("Connection to {} timed out", addr))??;
+        Ok(Self { stream, message_id: 0, session_id: 0, tree_id: 0 })
+    }
+
+    /// Send one request and wait for its final response
+    async fn request(&mut self, command: u16, body: &[u8]) -> Result<Response> {
+        let mut message = Vec::with_capacity(68 + body.len());
+        message.extend_from_slice(&((64 + body.len()) as u32).to_be_bytes()); // NetBIOS session header
+        message.extend_from_slice(b"\xfeSMB");
+        message.extend_from_slice(&64u16.to_le_bytes());
+        message.extend_from_slice(&0u16.to_le_bytes()); // credit charge
+        message.extend_from_slice(&0u32.to_le_bytes()); // status
+        message.extend_from_slice(&command.to_le_bytes());
+        message.extend_from_slice(&31u16.to_le_bytes()); // credits requested
+        message.extend_from_slice(&0u32.to_le_bytes()); // flags
+        message.extend_from_slice(&0u32.to_le_bytes()); // next command
+        message.extend_from_slice(&self.message_id.to_le_bytes());
+        message.extend_from_slice(&0xFEFFu32.to_le_bytes()); // process id
+        message.extend_from_slice(&self.tree_id.to_le_bytes());
+        message.extend_from_slice(&self.session_id.to_le_bytes());
+        message.extend_from_slice(&[0u8; 16]); // signature
+        message.extend_from_slice(body);
+        self.message_id += 1;
+
+        timeout(SMB_TIMEOUT, self.stream.write_all(&message))
+            .await
+            .map_err(|_| anyhow!("SMB write timed out"))??;
+
+        loop {
+            let response = timeout(SMB_TIMEOUT, self.read_message())
+                .await
+                .map_err(|_| anyhow!("SMB response timed out"))??;
+            // Interim responses precede the real answer
+            if response.status != STATUS_PENDING {
+                return Ok(response);
+            }
+        }
+    }
+
+    async fn read_message(&mut self) -> Result<Response> {
+        let mut header = [0u8; 4];
+        self.stream.read_exact(&mut header).await?;
+        let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if !(64..=MAX_MESSAGE_BYTES).contains(&length) {
+            return Err(anyhow!("Invalid SMB message length {}", length));
+        }
+        let mut message = vec![0u8; length];
+        self.stream.read_exact(&mut message).await?;
+        if &message[..4] != b"\xfeSMB" {
+            return Err(anyhow!("Not an SMB2 response"));
+        }
+
+        let u32_at = |o: usize| u32::from_le_bytes([message[o], message[o + 1], message[o + 2], message[o + 3]]);
+        let mut session = [0u8; 8];
+        session.copy_from_slice(&message[40..48]);
+        Ok(Response {
+            status: u32_at(8),
+            tree_id: u32_at(36),
+            session_id: u64::from_le_bytes(session),
+            message,
+        })
+    }
+
+    /// Negotiate SMB 2.0.2/2.1, which need neither pre-auth hashing nor encryption
+    async fn negotiate(&mut self) -> Result<()> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&36u16.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes()); // dialect count
+        body.extend_from_slice(&1u16.to_le_bytes()); // signing enabled
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes()); // capabilities
+        body.extend_from_slice(b"NetMapperClient\0"); // client GUID
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&0x0202u16.to_le_bytes());
+        body.extend_from_slice(&0x0210u16.to_le_bytes());
+
+        let response = self.request(NEGOTIATE, &body).await?;
+        if response.status != STATUS_SUCCESS {
+            return Err(anyhow!("SMB2 negotiate failed (status {:#010x})", response.status));
+        }
+        Ok(())
+    }
+
+    async fn session_setup_round(&mut self, token: &[u8]) -> Result<Response> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&25u16.to_le_bytes());
+        body.push(0); // flags
+        body.push(1); // signing enabled
+        body.extend_from_slice(&0u32.to_le_bytes()); // capabilities
+        body.extend_from_slice(&0u32.to_le_bytes()); // channel
+        body.extend_from_slice(&(64u16 + 24).to_le_bytes());
+        body.extend_from_slice(&(token.len() as u16).to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes()); // previous session
+        body.extend_from_slice(token);
+        self.request(SESSION_SETUP, &body).await
+    }
+
+    /// Authenticate without a password; returns the kind of session granted
+    async fn session_setup(&mut self, kind: SmbSessionKind) -> Result<SmbSessionKind> {
+        let challenge = self.session_setup_round(&ntlm_negotiate()).await?;
+        if challenge.status != STATUS_MORE_PROCESSING_REQUIRED {
+            return Err(anyhow!("NTLM negotiate rejected (status {:#010x})", challenge.status));
+        }
+        self.session_id = challenge.session_id;
+
+        let response = self.session_setup_round(&ntlm_authenticate(kind)).await?;
+        if response.status != STATUS_SUCCESS {
+            return Err(anyhow!("{} session rejected (status {:#010x})", kind.as_str(), response.status));
+        }
+        let flags = response.u16_at(2).unwrap_or(0);
+        if flags & SESSION_FLAG_IS_NULL != 0 {
+            Ok(SmbSessionKind::Anonymous)
+        } else if flags & SESSION_FLAG_IS_GUEST != 0 {
+            Ok(SmbSessionKind::Guest)
+        } else {
+            // The made-up user name must never map to a real account
+            Err(anyhow!("Server granted an authenticated session to an unknown user"))
+        }
+    }
+
+    /// Connect to a share; returns the share's maximal access mask
+    async fn tree_connect(&mut self, ip: Ipv4Addr, share: &str) -> Result<u32> {
+        let path = utf16(&format!("\\\\{}\\{}", ip, share));
+        let mut body = Vec::new();
+        body.extend_from_slice(&9u16.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(&(64u16 + 8).to_le_bytes());
+        body.extend_from_slice(&(path.len() as u16).to_le_bytes());
+        body.extend_from_slice(&path);
+
+        self.tree_id = 0;
+        let response = self.request(TREE_CONNECT, &body).await?;
+        if response.status != STATUS_SUCCESS {
+            return Err(anyhow!("Tree connect to {} failed (status {:#010x})", share, response.status));
+        }
+        self.tree_id = response.tree_id;
+        response.u32_at(12).ok_or_else(|| anyhow!("Truncated tree connect response"))
+    }
+
+    async fn tree_disconnect(&mut self) {
+        let body = [4u8, 0, 0, 0];
+        let _ = self.request(TREE_DISCONNECT, &body).await;
+        self.tree_id = 0;
+    }
+
+    /// Open a named pipe on the connected IPC$ tree; returns its file id
+    async fn open_pipe(&mut self, name: &str) -> Result<[u8; 16]> {
+        let name = utf16(name);
+        let mut body = Vec::new();
+        body.extend_from_slice(&57u16.to_le_bytes());
+        body.push(0); // security flags
+        body.push(0); // oplock level
+        body.extend_from_slice(&2u32.to_le_bytes()); // impersonation
+        body.extend_from_slice(&0u64.to_le_bytes()); // create flags
+        body.extend_from_slice(&0u64.to_le_bytes());
+        body.extend_from_slice(&0x0012_019Fu32.to_le_bytes()); // generic read/write
+        body.extend_from_slice(&0u32.to_le_bytes()); // file attributes
+        body.extend_from_slice(&3u32.to_le_bytes()); // share read/write
+        body.extend_from_slice(&1u32.to_le_bytes()); // FILE_OPEN
+        body.extend_from_slice(&0u32.to_le_bytes()); // create options
+        body.extend_from_slice(&(64u16 + 56).to_le_bytes());
+        body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes()); // create contexts
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&name);
+
+        let response = self.request(CREATE, &body).await?;
+        if response.status != STATUS_SUCCESS {
+            return Err(anyhow!("Opening pipe failed (status {:#010x})", response.status));
+        }
+        let mut file_id = [0u8; 16];
+        file_id.copy_from_slice(response.body().get(64..80).ok_or_else(|| anyhow!("Truncated create response"))?);
+        Ok(file_id)
+    }
+
+    async fn write(&mut self, file_id: &[u8; 16], data: &[u8]) -> Result<()> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&49u16.to_le_bytes());
+        body.extend_from_slice(&(64u16 + 48).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes()); // offset
+        body.extend_from_slice(file_id);
+        body.extend_from_slice(&[0u8; 16]); // channel, remaining, channel info, flags
+        body.extend_from_slice(data);
+
+        let response = self.request(WRITE, &body).await?;
+        if response.status != STATUS_SUCCESS {
+            return Err(anyhow!("Pipe write failed (status {:#010x})", response.status));
+        }
+        Ok(())
+    }
+
+    async fn read(&mut self, file_id: &[u8; 16]) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&49u16.to_le_bytes());
+        body.push(0x50); // padding
+        body.push(0); // flags
+        body.extend_from_slice(&65_535u32.to_le_bytes());
+        body.extend_from_slice(&0u64.to_le_bytes()); // offset
+        body.extend_from_slice(file_id);
+        body.extend_from_slice(&[0u8; 16]); // minimum count, channel, remaining, channel info
+        body.push(0);
+
+        let response = self.request(READ, &body).await?;
+        if response.status != STATUS_SUCCESS && response.status != STATUS_BUFFER_OVERFLOW {
+            return Err(anyhow!("Pipe read failed (status {:#010x})", response.status));
+        }
+        let offset = *response.body().get(2).ok_or_else(|| anyhow!("Truncated read response"))? as usize;
+        let length = response.u32_at(4).unwrap_or(0) as usize;
+        response
+            .message
+            .get(offset..offset + length)
+            .map(|d| d.to_vec())
+            .ok_or_else(|| anyhow!("Read data outside the response"))
+    }
+}
+
+/// Append an NTLMSSP security buffer descriptor
+fn push_security_buffer(out: &mut Vec<u8>, length: usize, offset: usize) {
+    out.extend_from_slice(&(length as u16).to_le_bytes());
+    out.extend_from_slice(&(length as u16).to_le_bytes());
+    out.extend_from_slice(&(offset as u32).to_le_bytes());
+}
+
+/// NTLMSSP NEGOTIATE_MESSAGE
+fn ntlm_negotiate() -> Vec<u8> {
+    let mut token = b"NTLMSSP\0".to_vec();
+    token.extend_from_slice(&1u32.to_le_bytes());
+    token.extend_from_slice(&NTLM_FLAGS.to_le_bytes());
+    token.extend_from_slice(&[0u8; 16]); // domain and workstation
+    token
+}
+
+/// NTLMSSP AUTHENTICATE_MESSAGE with no password
+///
+/// Anonymous sends an empty user and a single-byte LM response, as the
+/// spec requires. Guest sends an unknown user whose empty response a
+/// guest-mapping server never checks.
+fn ntlm_authenticate(kind: SmbSessionKind) -> Vec<u8> {
+    let (lm, nt, user, flags) = match kind {
+        SmbSessionKind::Anonymous => (vec![0u8], Vec::new(), Vec::new(), NTLM_FLAGS | NTLM_FLAG_ANONYMOUS),
+        SmbSessionKind::Guest => (vec![0u8; 24], vec![0u8; 24], utf16(GUEST_PROBE_USER), NTLM_FLAGS),
+    };
+
+    // Payload follows the fixed header: LM, NT, domain, user, workstation, session key
+    let mut token = b"NTLMSSP\0".to_vec();
+    token.extend_from_slice(&3u32.to_le_bytes());
+    let mut offset = 64;
+    for length in [lm.len(), nt.len(), 0, user.len(), 0, 0] {
+        push_security_buffer(&mut token, length, offset);
+        offset += length;
+    }
+    token.extend_from_slice(&flags.to_le_bytes());
+    token.extend_from_slice(&lm);
+    token.extend_from_slice(&nt);
+    token.extend_from_slice(&user);
+    token
+}
+
+/// DCE/RPC bind to srvsvc
+fn rpc_bind() -> Vec<u8> {
+    let mut pdu = vec![5, 0, 11, 0x03, 0x10, 0, 0, 0];
+    pdu.extend_from_slice(&72u16.to_le_bytes()); // fragment length
+    pdu.extend_from_slice(&0u16.to_le_bytes()); // auth length
+    pdu.extend_from_slice(&1u32.to_le_bytes()); // call id
+    pdu.extend_from_slice(&4280u16.to_le_bytes()); // max transmit fragment
+    pdu.extend_from_slice(&4280u16.to_le_bytes()); // max receive fragment
+    pdu.extend_from_slice(&0u32.to_le_bytes()); // association group
+    pdu.extend_from_slice(&[1, 0, 0, 0]); // one context
+    pdu.extend_from_slice(&0u16.to_le_bytes()); // context id
+    pdu.extend_from_slice(&[1, 0]); // one transfer syntax
+    pdu.extend_from_slice(&SRVSVC_UUID);
+    pdu.extend_from_slice(&3u16.to_le_bytes());
+    pdu.extend_from_slice(&0u16.to_le_bytes());
+    pdu.extend_from_slice(&NDR_UUID);
+    pdu.extend_from_slice(&2u32.to_le_bytes());
+    pdu
+}
+
+/// Append an NDR conformant varying string, NUL-terminated and 4-byte padded
+fn push_ndr_string(out: &mut Vec<u8>, s: &str) {
+    let chars = s.encode_utf16().count() as u32 + 1;
+    out.extend_from_slice(&chars.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&chars.to_le_bytes());
+    out.extend_from_slice(&utf16(s));
+    out.extend_from_slice(&[0, 0]);
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+}
+
+/// DCE/RPC request for NetrShareEnum at information level 1
+fn rpc_share_enum(ip: Ipv4Addr) -> Vec<u8> {
+    let mut stub = Vec::new();
+    stub.extend_from_slice(&0x0002_0000u32.to_le_bytes()); // server name referent
+    push_ndr_string(&mut stub, &format!("\\\\{}", ip));
+    stub.extend_from_slice(&1u32.to_le_bytes()); // level
+    stub.extend_from_slice(&1u32.to_le_bytes()); // union switch
+    stub.extend_from_slice(&0x0002_0004u32.to_le_bytes()); // container referent
+    stub.extend_from_slice(&0u32.to_le_bytes()); // entries read
+    stub.extend_from_slice(&0u32.to_le_bytes()); // null buffer
+    stub.extend_from_slice(&u32::MAX.to_le_bytes()); // preferred maximum length
+    stub.extend_from_slice(&0u32.to_le_bytes()); // null resume handle
+
+    let length = 24 + stub.len();
+    let mut pdu = vec![5, 0, 0, 0x03, 0x10, 0, 0, 0];
+    pdu.extend_from_slice(&(length as u16).to_le_bytes());
+    pdu.extend_from_slice(&0u16.to_le_bytes());
+    pdu.extend_from_slice(&2u32.to_le_bytes()); // call id
+    pdu.extend_from_slice(&(stub.len() as u32).to_le_bytes()); // allocation hint
+    pdu.extend_from_slice(&0u16.to_le_bytes()); // context id
+    pdu.extend_from_slice(&OPNUM_NETR_SHARE_ENUM.to_le_bytes());
+    pdu.extend_from_slice(&stub);
+    pdu
+}
+
+/// Split DCE/RPC response fragments into their stub data
+///
+/// Returns `None` until the fragment flagged last has arrived.
+fn rpc_response_stub(data: &[u8]) -> Option<Result<Vec<u8>>> {
+    let mut stub = Vec::new();
+    let mut rest = data;
+    while rest.len() >= 16 {
+        let frag_len = u16::from_le_bytes([rest[8], rest[9]]) as usize;
+        if frag_len < 24 || rest.len() < frag_len {
+            return None;
+        }
+        match rest[2] {
+            2 => {}
+            3 => {
+                let status = rest.get(24..28).map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+                return Some(Err(anyhow!("NetrShareEnum fault {:#010x}", status)));
+            }
+            ptype => return Some(Err(anyhow!("Unexpected DCE/RPC packet type {}", ptype))),
+        }
+        stub.extend_from_slice(&rest[24..frag_len]);
+        if rest[3] & 0x02 != 0 {
+            return Some(Ok(stub));
+        }
+        rest = &rest[frag_len..];
+    }
+    None
+}
+
+/// Cursor over little-endian NDR data
+struct NdrReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NdrReader<'a> {
+    fn u32(&mut self) -> Option<u32> {
+        self.pos = (self.pos + 3) & !3;
+        let b = self.data.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Conformant varying UTF-16 string
+    fn string(&mut self) -> Option<String> {
+        let _max = self.u32()?;
+        let _offset = self.u32()?;
+        let count = self.u32()? as usize;
+        let bytes = self.data.get(self.pos..self.pos + count * 2)?;
+        self.pos += count * 2;
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        Some(String::from_utf16_lossy(&units).trim_end_matches('\0').to_string())
+    }
+}
+
+/// Parse a NetrShareEnum level 1 response into (name, type, remark)
+fn parse_share_enum(stub: &[u8]) -> Option<Vec<(String, u32, String)>> {
+    let mut r = NdrReader { data: stub, pos: 0 };
+    let _level = r.u32()?;
+    let _switch = r.u32()?;
+    if r.u32()? == 0 {
+        return Some(Vec::new());
+    }
+    let count = r.u32()? as usize;
+    if r.u32()? == 0 {
+        return Some(Vec::new());
+    }
+    if r.u32()? as usize != count {
+        return None;
+    }
+
+    let mut entries = Vec::with_capacity(count.min(1024));
+    for _ in 0..count {
+        let name_ptr = r.u32()?;
+        let share_type = r.u32()?;
+        let remark_ptr = r.u32()?;
+        entries.push((name_ptr, share_type, remark_ptr));
+    }
+    entries
+        .into_iter()
+        .map(|(name_ptr, share_type, remark_ptr)| {
+            let name = if name_ptr != 0 { r.string()? } else { String::new() };
+            let remark = if remark_ptr != 0 { r.string()? } else { String::new() };
+            Some((name, share_type, remark))
+        })
+        .collect()
+}
+
+/// Session and share list obtained with one identity
+async fn enumerate_as(ip: Ipv4Addr, kind: SmbSessionKind) -> Result<SmbShareReport> {
+    let mut conn = SmbConnection::connect(ip).await?;
+    conn.negotiate().await?;
+    let session = conn.session_setup(kind).await?;
+
+    conn.tree_connect(ip, "IPC$").await?;
+    let pipe = conn.open_pipe("srvsvc").await?;
+    conn.write(&pipe, &rpc_bind()).await?;
+    let ack = conn.read(&pipe).await?;
+    if ack.get(2) != Some(&12) {
+        return Err(anyhow!("srvsvc bind rejected"));
+    }
+
+    conn.write(&pipe, &rpc_share_enum(ip)).await?;
+    let mut data = Vec::new();
+    let stub = loop {
+        let chunk = conn.read(&pipe).await?;
+        if chunk.is_empty() {
+            return Err(anyhow!("Truncated NetrShareEnum response"));
+        }
+        data.extend_from_slice(&chunk);
+        if let Some(stub) = rpc_response_stub(&data) {
+            break stub?;
+        }
+    };
+    let listed = parse_share_enum(&stub).ok_or_else(|| anyhow!("Malformed NetrShareEnum response"))?;
+    conn.tree_disconnect().await;
+
+    let mut shares = Vec::new();
+    for (name, raw_type, remark) in listed {
+        let share_type = SmbShareType::from_raw(raw_type);
+        let access = match share_type {
+            SmbShareType::Disk => conn.tree_connect(ip, &name).await.ok(),
+            _ => None,
+        };
+        if access.is_some() {
+            conn.tree_disconnect().await;
+        }
+        let access = access.unwrap_or(0);
+        shares.push(SmbShare {
+            name,
+            share_type,
+            remark,
+            special: raw_type & 0x8000_0000 != 0,
+            readable: access & (FILE_READ_DATA | GENERIC_ALL) != 0,
+            writable: access & (FILE_WRITE_DATA | FILE_APPEND_DATA | GENERIC_ALL) != 0,
+        });
+    }
+
+    Ok(SmbShareReport { ip, session, shares })
+}
+
+/// List shares a host exposes to anonymous or guest sessions
+pub async fn smb_shares(ip: Ipv4Addr) -> Result<SmbShareReport> {
+    let mut last_error = anyhow!("No SMB session available");
+    for kind in [SmbSessionKind::Anonymous, SmbSessionKind::Guest] {
+        match enumerate_as(ip, kind).await {
+            Ok(report) => return Ok(report),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_share_enum() {
+        let mut stub = Vec::new();
+        for v in [1u32, 1, 0x20000, 2, 0x20004, 2] {
+            stub.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in [0x20008u32, 0, 0x2000c, 0x20010, 0x8000_0003, 0x20014] {
+            stub.extend_from_slice(&v.to_le_bytes());
+        }
+        push_ndr_string(&mut stub, "public");
+        push_ndr_string(&mut stub, "Shared files");
+        push_ndr_string(&mut stub, "IPC$");
+        push_ndr_string(&mut stub, "IPC Service");
+
+        let shares = parse_share_enum(&stub).unwrap();
+        assert_eq!(shares.len(), 2);
+        assert_eq!(shares[0], ("public".to_string(), 0, "Shared files".to_string()));
+        assert_eq!(SmbShareType::from_raw(shares[1].1), SmbShareType::Ipc);
+        assert!(parse_share_enum(&stub[..30]).is_none());
+    }
+
+    #[test]
+    fn test_share_findings() {
+        let share = |name: &str, special: bool, readable: bool, writable: bool| SmbShare {
+            name: name.to_string(),
+            share_type: SmbShareType::Disk,
+            remark: String::new(),
+            special,
+            readable,
+            writable,
+        };
+        let report = SmbShareReport {
+            ip: Ipv4Addr::new(192, 168, 1, 20),
+            session: SmbSessionKind::Guest,
+            shares: vec![
+                share("public", false, true, true),
+                share("media", false, true, false),
+                share("private", false, false, false),
+                share("C$", true, true, true),
+            ],
+        };
+        let findings = report.findings();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].kind, ExposureKind::SmbShareWritable);
+        assert_eq!(findings[1].severity, "MEDIUM");
+        assert!(findings[1].evidence.contains("\\\\192.168.1.20\\media grants read access to guest sessions"));
+    }
+}
@@ -0,0 +1,174 @@
+//! SNMP community string checker
+//!
+//! Tries the candidate communities from `SNMP_COMMUNITY_CANDIDATES` against
+//! a host and records the access each one grants. Read access is a sysDescr
+//! GET; write access is tested by setting sysLocation to the value it
+//! already holds, so a writable community is detected without changing the
+//! device's configuration.
+
+use serde::{Deserialize, Serialize};
+use snmp2::{AsyncSession, Oid, Value};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::timeout;
+
+use super::exposure::{ExposureFinding, ExposureKind};
+use super::limits::concurrency_limits;
+use crate::config::{SNMP_COMMUNITY_CANDIDATES, SNMP_PORT, SNMP_TIMEOUT, SNMP_WRITE_CHECK_ENABLED};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+const OID_SYS_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+const OID_SYS_LOCATION: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 6, 0];
+
+/// Access a community string grants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityAccessLevel {
+    Read,
+    Write,
+}
+
+impl CommunityAccessLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommunityAccessLevel::Read => "read",
+            CommunityAccessLevel::Write => "write",
+        }
+    }
+}
+
+/// A community string the agent accepted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommunityAccess {
+    pub community: String,
+    pub access: CommunityAccessLevel,
+}
+
+/// Exposure findings for the communities a host accepted
+pub fn community_findings(accepted: &[CommunityAccess]) -> Vec<ExposureFinding> {
+    accepted
+        .iter()
+        .map(|a| {
+            let (kind, severity) = match (a.access, a.community.as_str()) {
+                (CommunityAccessLevel::Write, _) => (ExposureKind::SnmpWritableCommunity, "CRITICAL"),
+                (CommunityAccessLevel::Read, "public") => (ExposureKind::SnmpPublicCommunity, "HIGH"),
+                (CommunityAccessLevel::Read, _) => (ExposureKind::SnmpGuessableCommunity, "HIGH"),
+            };
+            ExposureFinding {
+                kind,
+                port: SNMP_PORT,
+                service: "SNMP".to_string(),
+                severity: severity.to_string(),
+                evidence: format!("Community \"{}\" grants {} access", a.community, a.access.as_str()),
+                recommendation: "Change the default community string or migrate to SNMPv3 with authentication"
+                    .to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Highest access one community grants, `None` if the agent ignores it
+async fn probe_community(ip: Ipv4Addr, community: &str) -> Option<CommunityAccessLevel> {
+    let addr = format!("{}:{}", ip, SNMP_PORT);
+    let mut session = match timeout(SNMP_TIMEOUT, AsyncSession::new_v2c(&addr, community.as_bytes(), 0)).await {
+        Ok(Ok(s)) => s,
+        _ => return None,
+    };
+
+    let oid = Oid::from(OID_SYS_DESCR).ok()?;
+    match timeout(SNMP_TIMEOUT, session.get(&oid)).await {
+        Ok(Ok(response)) if response.error_status == 0 => {}
+        _ => return None,
+    }
+    if !SNMP_WRITE_CHECK_ENABLED {
+        return Some(CommunityAccessLevel::Read);
+    }
+
+    // Write back the current sysLocation unchanged
+    let location_oid = Oid::from(OID_SYS_LOCATION).ok()?;
+    let location = match timeout(SNMP_TIMEOUT, session.get(&location_oid)).await {
+        Ok(Ok(mut response)) => match response.varbinds.next() {
+            Some((_, Value::OctetString(bytes))) => bytes.to_vec(),
+            _ => return Some(CommunityAccessLevel::Read),
+        },
+        _ => return Some(CommunityAccessLevel::Read),
+    };
+    match timeout(SNMP_TIMEOUT, session.set(&[(&location_oid, Value::OctetString(&location))])).await {
+        Ok(Ok(response)) if response.error_status == 0 => Some(CommunityAccessLevel::Write),
+        _ => Some(CommunityAccessLevel::Read),
+    }
+}
+
+/// Try every candidate community against one host
+pub async fn probe_communities(ip: Ipv4Addr) -> Vec<CommunityAccess> {
+    let mut accepted = Vec::new();
+    for &community in SNMP_COMMUNITY_CANDIDATES {
+        if let Some(access) = probe_community(ip, community).await {
+            accepted.push(CommunityAccess { community: community.to_string(), access });
+        }
+    }
+    accepted
+}
+
+/// Check candidate communities on every host
+///
+/// SNMP is UDP, so the TCP port scan cannot show which hosts run an agent;
+/// every host is tried. Returns accepted communities keyed by host IP (hosts
+/// accepting none are omitted).
+pub async fn snmp_community_scan(hosts: &[Ipv4Addr]) -> HashMap<Ipv4Addr, Vec<CommunityAccess>> {
+    log_stderr!(
+        "SNMP community check: trying {} communities on {} hosts...",
+        SNMP_COMMUNITY_CANDIDATES.len(),
+        hosts.len()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let results = Arc::new(Mutex::new(HashMap::new()));
+    let mut handles = Vec::new();
+    for &ip in hosts {
+        let semaphore = Arc::clone(&semaphore);
+        let results = Arc::clone(&results);
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+            let accepted = probe_communities(ip).await;
+            if !accepted.is_empty() {
+                results.lock().await.insert(ip, accepted);
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.await;
+    }
+
+    let res = results.lock().await.clone();
+    log_stderr!("SNMP community check complete: {} hosts accept a guessable community", res.len());
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_community_findings() {
+        let accepted = vec![
+            CommunityAccess { community: "public".to_string(), access: CommunityAccessLevel::Read },
+            CommunityAccess { community: "monitor".to_string(), access: CommunityAccessLevel::Read },
+            CommunityAccess { community: "private".to_string(), access: CommunityAccessLevel::Write },
+        ];
+        let findings = community_findings(&accepted);
+        assert_eq!(findings[0].kind, ExposureKind::SnmpPublicCommunity);
+        assert_eq!(findings[1].kind, ExposureKind::SnmpGuessableCommunity);
+        assert_eq!(findings[2].kind, ExposureKind::SnmpWritableCommunity);
+        assert_eq!(findings[2].severity, "CRITICAL");
+        assert_eq!(findings[2].evidence, "Community \"private\" grants write access");
+    }
+}
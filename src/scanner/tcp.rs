@@ -2,12 +2,21 @@
 
 use anyhow::Result;
 use pnet::util::MacAddr;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::ErrorKind;
 use std::net::Ipv4Addr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, Semaphore};
 
-use crate::config::{MAX_CONCURRENT_PINGS, TCP_PROBE_PORTS, TCP_PROBE_TIMEOUT};
+use crate::config::{TCP_PROBE_PORTS, TCP_PROBE_TIMEOUT};
+use crate::models::{Port, PortState};
+use super::marking::connect_tcp;
+use super::limits::concurrency_limits;
+use super::pacer::{is_send_congestion, shared_pacer, Congestion};
+#[cfg(any(test, feature = "sim"))]
+use super::sim::sim_backend;
+use super::timeouts::HostTimeouts;
 
 /// Logs a message to stderr
 macro_rules! log_stderr {
@@ -16,73 +25,177 @@ macro_rules! log_stderr {
     };
 }
 
-/// Probes a single host for open ports
-async fn probe_host_ports(ip: Ipv4Addr) -> Vec<u16> {
-    let mut open_ports = Vec::new();
-    
-    for &port in TCP_PROBE_PORTS {
+/// Open port numbers from a host's probe results
+pub fn open_ports(probes: &[Port]) -> Vec<u16> {
+    probes
+        .iter()
+        .filter(|p| p.is_open())
+        .map(|p| p.number)
+        .collect()
+}
+
+/// Handshake time per open port, in milliseconds
+///
+/// A slow accept (an overloaded SMB or database service) shows up here
+/// even when ICMP round trips look healthy.
+pub fn connect_times(probes: &[Port]) -> BTreeMap<u16, f64> {
+    probes
+        .iter()
+        .filter_map(|p| Some((p.number, p.connect_ms?)))
+        .collect()
+}
+
+/// Classify the result of a connect attempt
+fn classify_connect<T>(result: Result<std::io::Result<T>, tokio::time::error::Elapsed>) -> PortState {
+    match result {
+        Ok(Ok(_)) => PortState::Open,
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => PortState::Closed,
+        // Host/network unreachable and timeouts both mean nothing answered
+        _ => PortState::Filtered,
+    }
+}
+
+/// Ports probed by one task; long port lists are split so a full-range
+/// scan of one host runs in parallel
+pub(super) const PORTS_PER_TASK: usize = 64;
+
+/// Probes a batch of one host's ports
+#[tracing::instrument(name = "tcp_probe", skip_all, fields(%ip, ports = ports.len()))]
+pub(super) async fn probe_host_ports(ip: Ipv4Addr, ports: &[u16], timeout: Duration) -> Vec<Port> {
+    let mut probes = Vec::with_capacity(ports.len());
+    #[cfg(any(test, feature = "sim"))]
+    let sim = sim_backend();
+
+    for &port in ports {
+        shared_pacer().acquire_async(1).await;
+        #[cfg(any(test, feature = "sim"))]
+        if let Some(sim) = &sim {
+            probes.push(sim.probe_port(ip, port));
+            continue;
+        }
         let addr = std::net::SocketAddr::new(std::net::IpAddr::V4(ip), port);
-        
-        match tokio::time::timeout(
-            TCP_PROBE_TIMEOUT,
-            tokio::net::TcpStream::connect(addr)
-        ).await {
-            Ok(Ok(_)) => {
-                open_ports.push(port);
+
+        let started = Instant::now();
+        let result = tokio::time::timeout(timeout, connect_tcp(addr)).await;
+        let elapsed = started.elapsed();
+        if let Ok(Err(e)) = &result {
+            if is_send_congestion(e) {
+                shared_pacer().report(Congestion::SendError);
             }
-            _ => {}
         }
+        let state = classify_connect(result);
+        probes.push(Port {
+            connect_ms: (state == PortState::Open).then_some(elapsed.as_secs_f64() * 1000.0),
+            ..Port::tcp(port, state)
+        });
     }
-    
-    open_ports
+
+    probes
 }
 
 /// Performs TCP probe scan on discovered hosts
+///
+/// Returns the state of every probed port for every host.
 pub async fn tcp_probe_scan(
     hosts: &HashMap<Ipv4Addr, MacAddr>,
-) -> Result<HashMap<Ipv4Addr, Vec<u16>>> {
+) -> Result<HashMap<Ipv4Addr, Vec<Port>>> {
+    tcp_port_scan(hosts, TCP_PROBE_PORTS).await
+}
+
+/// TCP probe scan of an explicit port list (e.g. a scan profile's ports)
+pub async fn tcp_port_scan(
+    hosts: &HashMap<Ipv4Addr, MacAddr>,
+    ports: &[u16],
+) -> Result<HashMap<Ipv4Addr, Vec<Port>>> {
+    tcp_port_scan_adaptive(hosts, ports, &HostTimeouts::default()).await
+}
+
+/// TCP probe scan with each host's connect timeout sized by its measured round trip
+pub async fn tcp_port_scan_adaptive(
+    hosts: &HashMap<Ipv4Addr, MacAddr>,
+    ports: &[u16],
+    timeouts: &HostTimeouts,
+) -> Result<HashMap<Ipv4Addr, Vec<Port>>> {
     log_stderr!(
         "Phase 3: TCP probing {} hosts ({} ports each)...",
         hosts.len(),
-        TCP_PROBE_PORTS.len()
+        ports.len()
     );
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_PINGS));
-    let port_results: Arc<Mutex<HashMap<Ipv4Addr, Vec<u16>>>> =
+    let semaphore = Arc::new(Semaphore::new(concurrency_limits().tcp_connects));
+    let port_results: Arc<Mutex<HashMap<Ipv4Addr, Vec<Port>>>> =
         Arc::new(Mutex::new(HashMap::new()));
 
     let mut handles = Vec::new();
 
     for &ip in hosts.keys() {
-        let semaphore = Arc::clone(&semaphore);
-        let port_results = Arc::clone(&port_results);
-
-        let handle = tokio::spawn(async move {
-            let _permit = semaphore.acquire().await.expect("Semaphore closed");
-            
-            let open_ports = probe_host_ports(ip).await;
-            if !open_ports.is_empty() {
-                let mut results = port_results.lock().await;
-                results.insert(ip, open_ports);
-            }
-        });
+        for batch in ports.chunks(PORTS_PER_TASK) {
+            let semaphore = Arc::clone(&semaphore);
+            let port_results = Arc::clone(&port_results);
+            let batch = batch.to_vec();
+            let timeout = timeouts.timeout_for(ip, TCP_PROBE_TIMEOUT);
 
-        handles.push(handle);
+            let handle = tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.expect("Semaphore closed");
+
+                let probes = probe_host_ports(ip, &batch, timeout).await;
+                port_results.lock().await.entry(ip).or_default().extend(probes);
+            });
+
+            handles.push(handle);
+        }
     }
 
     for handle in handles {
         let _ = handle.await;
     }
 
-    let results = port_results.lock().await;
-    let hosts_with_ports = results.len();
-    let total_ports: usize = results.values().map(|v| v.len()).sum();
-    
+    let mut results = port_results.lock().await;
+    for probes in results.values_mut() {
+        probes.sort_by_key(|p| p.number);
+    }
+    let count = |state: PortState| -> usize {
+        results.values().map(|v| v.iter().filter(|p| p.state == state).count()).sum()
+    };
+    let hosts_with_ports = results.values().filter(|v| !open_ports(v).is_empty()).count();
+
     log_stderr!(
-        "Phase 3 complete: {} hosts with open ports ({} ports total)",
+        "Phase 3 complete: {} hosts with open ports ({} open, {} closed, {} filtered)",
         hosts_with_ports,
-        total_ports
+        count(PortState::Open),
+        count(PortState::Closed),
+        count(PortState::Filtered)
     );
 
     Ok(results.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_classify_connect() {
+        assert_eq!(classify_connect::<()>(Ok(Ok(()))), PortState::Open);
+        assert_eq!(
+            classify_connect::<()>(Ok(Err(io::Error::from(ErrorKind::ConnectionRefused)))),
+            PortState::Closed
+        );
+        assert_eq!(
+            classify_connect::<()>(Ok(Err(io::Error::from(ErrorKind::TimedOut)))),
+            PortState::Filtered
+        );
+    }
+
+    #[test]
+    fn test_open_ports() {
+        let probes = vec![
+            Port::tcp(22, PortState::Closed),
+            Port { connect_ms: Some(1.5), ..Port::tcp(80, PortState::Open) },
+            Port::tcp(445, PortState::Filtered),
+        ];
+        assert_eq!(open_ports(&probes), vec![80]);
+        assert_eq!(connect_times(&probes), BTreeMap::from([(80, 1.5)]));
+    }
+}
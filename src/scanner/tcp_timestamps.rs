@@ -0,0 +1,198 @@
+//! Uptime estimation from TCP timestamps
+//!
+//! Many TCP stacks derive the RFC 7323 timestamp (TSval) from a clock that
+//! starts at boot. Two SYN-ACKs a known interval apart give the clock rate,
+//! and TSval divided by that rate is the time since boot. Embedded devices
+//! and older kernels do this faithfully, which is where uptime matters most.
+//! Hosts whose clock rate does not land on a standard tick frequency
+//! (randomized timestamps) are left without an estimate. Windows disables
+//! timestamps by default, and Linux 4.10+ adds a random offset per peer
+//! that keeps the rate intact, so the figure is a hint, not a measurement:
+//! it never replaces an SNMP uptime and is only compared across scans to
+//! spot reboots (the offset stays fixed until the host restarts).
+//! Requires raw socket privileges.
+
+use anyhow::{anyhow, Result};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::tcp::{ipv4_checksum, MutableTcpPacket, TcpFlags, TcpOption, TcpOptionNumbers, TcpPacket};
+use pnet::packet::Packet;
+use pnet::transport::{
+    tcp_packet_iter, transport_channel, TcpTransportChannelIterator, TransportChannelType, TransportProtocol,
+    TransportSender,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use super::pacer::shared_pacer;
+use crate::config::TCP_TIMESTAMP_SAMPLE_INTERVAL;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// TCP header plus NOP, NOP, Timestamps option
+const SYN_LEN: usize = 32;
+
+/// How long to wait for SYN-ACKs after each round
+const REPLY_WAIT: Duration = Duration::from_millis(800);
+
+/// Timestamp clock rates used by real stacks (Hz)
+const STANDARD_RATES: &[f64] = &[1.0, 2.0, 10.0, 100.0, 200.0, 250.0, 1000.0];
+
+/// Relative error tolerated when snapping a measured rate
+const RATE_TOLERANCE: f64 = 0.15;
+
+/// Uptime derived from a host's TCP timestamp clock
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TcpUptime {
+    /// Timestamp clock frequency
+    pub hz: u32,
+    /// Time since the clock started; low confidence, see the module docs
+    pub uptime_seconds: u64,
+    /// Last TSval observed
+    pub tsval: u32,
+}
+
+/// Build a SYN carrying a Timestamps option, with a valid checksum
+fn build_syn(buffer: &mut [u8; SYN_LEN], source: Ipv4Addr, target: Ipv4Addr, source_port: u16, port: u16) {
+    buffer.fill(0);
+    let mut tcp = MutableTcpPacket::new(&mut buffer[..]).expect("buffer holds a TCP header");
+    tcp.set_source(source_port);
+    tcp.set_destination(port);
+    tcp.set_sequence(u32::from(target).rotate_left(11) ^ port as u32);
+    tcp.set_data_offset((SYN_LEN / 4) as u8);
+    tcp.set_flags(TcpFlags::SYN);
+    tcp.set_window(1024);
+    // TSval can be any non-zero value; TSecr is zero on a SYN
+    let tsval = u32::from(source).wrapping_add(port as u32);
+    tcp.set_options(&[TcpOption::nop(), TcpOption::nop(), TcpOption::timestamp(tsval, 0)]);
+    let checksum = ipv4_checksum(&tcp.to_immutable(), &source, &target);
+    tcp.set_checksum(checksum);
+}
+
+/// TSval from a segment's Timestamps option
+fn parse_tsval(segment: &TcpPacket) -> Option<u32> {
+    segment
+        .get_options_iter()
+        .find(|o| o.get_number() == TcpOptionNumbers::TIMESTAMPS)
+        .and_then(|o| o.payload().get(..4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+}
+
+/// Estimate uptime from two TSvals `elapsed` apart
+///
+/// Returns `None` when the clock did not advance or its rate is not a
+/// standard tick frequency (randomized or per-connection timestamps).
+pub fn estimate_uptime(first: u32, second: u32, elapsed: Duration) -> Option<TcpUptime> {
+    let ticks = second.wrapping_sub(first);
+    if ticks == 0 || ticks > u32::MAX / 2 {
+        return None;
+    }
+    let measured = ticks as f64 / elapsed.as_secs_f64().max(0.001);
+    let hz = *STANDARD_RATES.iter().find(|&&rate| (measured - rate).abs() <= rate * RATE_TOLERANCE)?;
+    Some(TcpUptime {
+        hz: hz as u32,
+        uptime_seconds: (second as f64 / hz) as u64,
+        tsval: second,
+    })
+}
+
+/// Send one round of SYNs and collect TSvals from the SYN-ACKs
+fn sample_round(
+    tx: &mut TransportSender,
+    packets: &mut TcpTransportChannelIterator<'_>,
+    source: Ipv4Addr,
+    source_port: u16,
+    targets: &[(Ipv4Addr, u16)],
+) -> Result<HashMap<Ipv4Addr, (u32, Instant)>> {
+    let mut buffer = [0u8; SYN_LEN];
+    let pacer = shared_pacer();
+    for &(target, port) in targets {
+        build_syn(&mut buffer, source, target, source_port, port);
+        pacer.acquire(1);
+        let segment = TcpPacket::new(&buffer[..]).expect("buffer holds a TCP header");
+        tx.send_to(segment, IpAddr::V4(target))?;
+    }
+
+    let mut samples = HashMap::new();
+    let deadline = Instant::now() + REPLY_WAIT;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match packets.next_with_timeout(remaining) {
+            Ok(Some((reply, IpAddr::V4(from)))) if reply.get_destination() == source_port => {
+                let syn_ack = TcpFlags::SYN | TcpFlags::ACK;
+                if reply.get_flags() & syn_ack == syn_ack {
+                    if let Some(tsval) = parse_tsval(&reply) {
+                        samples.entry(from).or_insert((tsval, Instant::now()));
+                    }
+                }
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(samples)
+}
+
+/// Estimate uptime of hosts from their TCP timestamps (blocking)
+///
+/// Each target is one open port per host. `source` must be the local
+/// address used to reach the targets; it is part of the TCP checksum.
+pub fn tcp_uptime_scan(source: Ipv4Addr, targets: &[(Ipv4Addr, u16)]) -> Result<HashMap<Ipv4Addr, TcpUptime>> {
+    let protocol = TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Tcp));
+    let (mut tx, mut rx) = transport_channel(4096, protocol)
+        .map_err(|e| anyhow!("Raw TCP socket unavailable (requires root/CAP_NET_RAW): {}", e))?;
+    let mut packets = tcp_packet_iter(&mut rx);
+
+    log_stderr!("TCP timestamps: sampling {} hosts...", targets.len());
+    let source_port = 41_000 + (std::process::id() % 20_000) as u16;
+    let first = sample_round(&mut tx, &mut packets, source, source_port, targets)?;
+    std::thread::sleep(TCP_TIMESTAMP_SAMPLE_INTERVAL);
+    let answered: Vec<(Ipv4Addr, u16)> = targets.iter().copied().filter(|(ip, _)| first.contains_key(ip)).collect();
+    let second = sample_round(&mut tx, &mut packets, source, source_port, &answered)?;
+
+    let uptimes: HashMap<Ipv4Addr, TcpUptime> = second
+        .iter()
+        .filter_map(|(ip, &(tsval, at))| {
+            let &(first_tsval, first_at) = first.get(ip)?;
+            estimate_uptime(first_tsval, tsval, at.duration_since(first_at)).map(|u| (*ip, u))
+        })
+        .collect();
+    log_stderr!("TCP timestamps complete: uptime estimated for {} hosts", uptimes.len());
+    Ok(uptimes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_uptime() {
+        // 100 Hz clock, ~30 days up
+        let base = 30 * 86_400 * 100;
+        let uptime = estimate_uptime(base, base + 102, Duration::from_millis(1010)).unwrap();
+        assert_eq!(uptime.hz, 100);
+        assert_eq!(uptime.uptime_seconds, 30 * 86_400 + 1);
+
+        // Randomized timestamps: no standard rate
+        assert!(estimate_uptime(1_000, 1_000 + 37_000, Duration::from_secs(1)).is_none());
+        assert!(estimate_uptime(5, 5, Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn test_syn_timestamp_option() {
+        let source = Ipv4Addr::new(192, 168, 1, 10);
+        let target = Ipv4Addr::new(192, 168, 1, 20);
+        let mut buffer = [0u8; SYN_LEN];
+        build_syn(&mut buffer, source, target, 41_000, 22);
+
+        let tcp = TcpPacket::new(&buffer).unwrap();
+        assert_eq!(tcp.get_flags(), TcpFlags::SYN);
+        assert_eq!(parse_tsval(&tcp), Some(u32::from(source) + 22));
+        assert_eq!(tcp.get_checksum(), ipv4_checksum(&tcp, &source, &target));
+    }
+}
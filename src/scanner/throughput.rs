@@ -0,0 +1,183 @@
+//! Gateway throughput micro-benchmark
+//!
+//! Moves a bounded amount of data to or from the gateway (or another agreed
+//! host) over TCP and reports the achieved rate, so high-latency findings can
+//! be told apart from a saturated link. Both the duration and the byte count
+//! are capped, so the probe never turns into a load test.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::config::{TCP_PROBE_TIMEOUT, THROUGHPUT_MAX_BYTES, THROUGHPUT_MAX_DURATION};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Size of each write/read
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// How data is moved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThroughputMethod {
+    /// Repeatedly GET `path` from an HTTP server (e.g. the router web UI)
+    HttpDownload { path: String },
+    /// Stream data to a TCP sink on an agreed host (e.g. `nc -l > /dev/null`)
+    Upload,
+}
+
+impl ThroughputMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThroughputMethod::HttpDownload { .. } => "http_download",
+            ThroughputMethod::Upload => "upload",
+        }
+    }
+}
+
+/// Measured transfer rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThroughputResult {
+    pub target: String,
+    pub method: ThroughputMethod,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    pub mbps: f64,
+}
+
+impl ThroughputResult {
+    fn new(target: SocketAddr, method: ThroughputMethod, bytes: u64, elapsed_secs: f64) -> Self {
+        let mbps = if elapsed_secs > 0.0 {
+            bytes as f64 * 8.0 / elapsed_secs / 1_000_000.0
+        } else {
+            0.0
+        };
+        Self {
+            target: target.to_string(),
+            method,
+            bytes,
+            duration_ms: (elapsed_secs * 1000.0) as u64,
+            mbps,
+        }
+    }
+}
+
+/// Estimate bandwidth toward `target` within THROUGHPUT_MAX_DURATION / THROUGHPUT_MAX_BYTES
+pub async fn throughput_probe(target: SocketAddr, method: ThroughputMethod) -> Result<ThroughputResult> {
+    log_stderr!("Throughput: {} probe toward {}...", method.as_str(), target);
+
+    let start = Instant::now();
+    let bytes = match &method {
+        ThroughputMethod::HttpDownload { path } => http_download(target, path, start).await?,
+        ThroughputMethod::Upload => upload(target, start).await?,
+    };
+
+    let result = ThroughputResult::new(target, method, bytes, start.elapsed().as_secs_f64());
+    log_stderr!(
+        "Throughput: {:.1} Mbps ({} bytes in {} ms)",
+        result.mbps,
+        result.bytes,
+        result.duration_ms
+    );
+    Ok(result)
+}
+
+async fn connect(target: SocketAddr) -> Result<TcpStream> {
+    timeout(TCP_PROBE_TIMEOUT, TcpStream::connect(target))
+        .await
+        .map_err(|_| anyhow!("Connection to {} timed out", target))?
+        .with_context(|| format!("Failed to connect to {}", target))
+}
+
+/// Fetch `path` repeatedly until the time or byte budget is used up
+async fn http_download(target: SocketAddr, path: &str, start: Instant) -> Result<u64> {
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: NetworkTopologyMapper\r\nConnection: close\r\n\r\n",
+        path,
+        target.ip()
+    );
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+
+    while total < THROUGHPUT_MAX_BYTES && start.elapsed() < THROUGHPUT_MAX_DURATION {
+        let mut stream = connect(target).await?;
+        stream.write_all(request.as_bytes()).await?;
+
+        loop {
+            let remaining = THROUGHPUT_MAX_DURATION.saturating_sub(start.elapsed());
+            match timeout(remaining, stream.read(&mut buf)).await {
+                Ok(Ok(0)) => break,
+                Ok(Ok(n)) => total += n as u64,
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_) => return Ok(total),
+            }
+            if total >= THROUGHPUT_MAX_BYTES {
+                return Ok(total);
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// Stream zeros to a sink until the time or byte budget is used up
+async fn upload(target: SocketAddr, start: Instant) -> Result<u64> {
+    let mut stream = connect(target).await?;
+    let chunk = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+
+    while total < THROUGHPUT_MAX_BYTES {
+        let remaining = THROUGHPUT_MAX_DURATION.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            break;
+        }
+        match timeout(remaining, stream.write_all(&chunk)).await {
+            Ok(Ok(())) => total += CHUNK_SIZE as u64,
+            Ok(Err(e)) => return Err(e.into()),
+            Err(_) => break,
+        }
+    }
+
+    // Wait for the send buffer to drain so buffered bytes are not counted as delivered
+    let remaining = THROUGHPUT_MAX_DURATION.saturating_sub(start.elapsed()) + TCP_PROBE_TIMEOUT;
+    let _ = timeout(remaining, stream.shutdown()).await;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_rate_calculation() {
+        let target: SocketAddr = "192.168.1.1:80".parse().unwrap();
+        let result = ThroughputResult::new(target, ThroughputMethod::Upload, 12_500_000, 2.0);
+        assert_eq!(result.mbps, 50.0);
+        assert_eq!(result.duration_ms, 2000);
+    }
+
+    #[tokio::test]
+    async fn test_upload_to_local_sink_is_bounded() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; CHUNK_SIZE];
+            while socket.read(&mut buf).await.unwrap_or(0) > 0 {}
+        });
+
+        let result = throughput_probe(target, ThroughputMethod::Upload).await.unwrap();
+        assert!(result.bytes > 0 && result.bytes <= THROUGHPUT_MAX_BYTES);
+        assert!(result.duration_ms <= (THROUGHPUT_MAX_DURATION + TCP_PROBE_TIMEOUT).as_millis() as u64);
+    }
+}
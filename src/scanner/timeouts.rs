@@ -0,0 +1,102 @@
+//! RTT-adaptive probe timeouts
+//!
+//! One global timeout is wrong at both ends: a LAN host answers a SYN in
+//! under a millisecond, so waiting 300 ms on each filtered port is wasted,
+//! while a host across a VPN may need longer than that just for the
+//! handshake. Once the ICMP phase has measured a host's round trip, its
+//! TCP and UDP probes wait a multiple of that instead.
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use super::icmp::IcmpResult;
+use crate::config::{ADAPTIVE_TIMEOUT_MAX, ADAPTIVE_TIMEOUT_MIN, ADAPTIVE_TIMEOUT_RTT_MULTIPLIER};
+use crate::models::HostInfo;
+
+/// Probe timeout for a host with round-trip time `rtt`, `fallback` when unmeasured
+pub fn adaptive_timeout(rtt: Option<Duration>, fallback: Duration) -> Duration {
+    match rtt {
+        Some(rtt) => (rtt * ADAPTIVE_TIMEOUT_RTT_MULTIPLIER).clamp(ADAPTIVE_TIMEOUT_MIN, ADAPTIVE_TIMEOUT_MAX),
+        None => fallback,
+    }
+}
+
+/// Round trip a host's probes are sized by: the p95 of its samples if
+/// several were taken (one slow sample should not be ignored), else the first reply
+pub(super) fn probe_rtt(result: &IcmpResult) -> Option<Duration> {
+    match &result.stats {
+        Some(stats) if stats.received > 0 => Some(Duration::from_secs_f64(stats.p95_ms / 1000.0)),
+        _ => result.duration,
+    }
+}
+
+/// Measured round-trip time per host
+#[derive(Debug, Clone, Default)]
+pub struct HostTimeouts {
+    rtts: HashMap<Ipv4Addr, Duration>,
+}
+
+impl HostTimeouts {
+    /// Round trips learned during the ICMP phase
+    pub fn from_icmp(results: &HashMap<Ipv4Addr, IcmpResult>) -> Self {
+        let rtts = results
+            .iter()
+            .filter(|(_, r)| r.is_up())
+            .filter_map(|(ip, r)| Some((*ip, probe_rtt(r)?)))
+            .collect();
+        Self { rtts }
+    }
+
+    /// Round trips already recorded on scanned hosts
+    pub fn from_hosts(hosts: &[HostInfo]) -> Self {
+        let rtts = hosts
+            .iter()
+            .filter_map(|h| {
+                let rtt = match &h.latency_stats {
+                    Some(stats) if stats.received > 0 => stats.p95_ms,
+                    _ => h.response_time_ms? as f64,
+                };
+                Some((h.ip.parse().ok()?, Duration::from_secs_f64(rtt / 1000.0)))
+            })
+            .collect();
+        Self { rtts }
+    }
+
+    pub fn insert(&mut self, ip: Ipv4Addr, rtt: Duration) {
+        self.rtts.insert(ip, rtt);
+    }
+
+    pub fn rtt(&self, ip: Ipv4Addr) -> Option<Duration> {
+        self.rtts.get(&ip).copied()
+    }
+
+    /// Timeout for probes to `ip`; `fallback` for hosts without a measured round trip
+    pub fn timeout_for(&self, ip: Ipv4Addr, fallback: Duration) -> Duration {
+        adaptive_timeout(self.rtt(ip), fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_timeout_bounds() {
+        let fallback = Duration::from_millis(300);
+        assert_eq!(adaptive_timeout(None, fallback), fallback);
+        // LAN: clamped up to the floor
+        assert_eq!(adaptive_timeout(Some(Duration::from_micros(400)), fallback), ADAPTIVE_TIMEOUT_MIN);
+        // VPN: a multiple of the round trip, above the global timeout
+        let vpn = adaptive_timeout(Some(Duration::from_millis(120)), fallback);
+        assert_eq!(vpn, Duration::from_millis(120) * ADAPTIVE_TIMEOUT_RTT_MULTIPLIER);
+        assert!(vpn > fallback);
+        assert_eq!(adaptive_timeout(Some(Duration::from_secs(5)), fallback), ADAPTIVE_TIMEOUT_MAX);
+
+        let mut timeouts = HostTimeouts::default();
+        let ip = Ipv4Addr::new(10, 8, 0, 5);
+        timeouts.insert(ip, Duration::from_millis(120));
+        assert_eq!(timeouts.timeout_for(ip, fallback), vpn);
+        assert_eq!(timeouts.timeout_for(Ipv4Addr::new(10, 8, 0, 6), fallback), fallback);
+    }
+}
@@ -0,0 +1,410 @@
+//! TLS configuration assessment
+//!
+//! The crate has no TLS client, and none is needed: which protocol versions
+//! and cipher suites a server accepts is decided in its ServerHello, before
+//! any key exchange. Each probe sends a hand-built ClientHello offering one
+//! version (or one class of weak suites) and reads the answer; an alert or
+//! a ServerHello for something else means the offer was refused.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::exposure::{ExposureFinding, ExposureKind};
+
+/// Ports commonly serving HTTPS admin interfaces
+pub const TLS_PORTS: &[u16] = &[443, 4443, 8443, 9443, 10443];
+
+/// Timeout for each connect/read step of a handshake probe
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Protocol version offered or negotiated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsVersion {
+    Tls10,
+    Tls11,
+    Tls12,
+    Tls13,
+}
+
+impl TlsVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsVersion::Tls10 => "TLS 1.0",
+            TlsVersion::Tls11 => "TLS 1.1",
+            TlsVersion::Tls12 => "TLS 1.2",
+            TlsVersion::Tls13 => "TLS 1.3",
+        }
+    }
+
+    fn wire(&self) -> u16 {
+        match self {
+            TlsVersion::Tls10 => 0x0301,
+            TlsVersion::Tls11 => 0x0302,
+            TlsVersion::Tls12 => 0x0303,
+            TlsVersion::Tls13 => 0x0304,
+        }
+    }
+
+    /// Deprecated by RFC 8996
+    pub fn is_legacy(&self) -> bool {
+        matches!(self, TlsVersion::Tls10 | TlsVersion::Tls11)
+    }
+}
+
+/// Family of broken cipher suites
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeakCipherClass {
+    /// 40/56-bit export suites (FREAK, Logjam)
+    Export,
+    /// No encryption at all
+    Null,
+    /// Unauthenticated key exchange, trivially intercepted
+    Anonymous,
+    Rc4,
+    /// Single DES and 3DES (Sweet32)
+    Des,
+}
+
+impl WeakCipherClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WeakCipherClass::Export => "export",
+            WeakCipherClass::Null => "NULL",
+            WeakCipherClass::Anonymous => "anonymous",
+            WeakCipherClass::Rc4 => "RC4",
+            WeakCipherClass::Des => "DES/3DES",
+        }
+    }
+
+    /// Classes that void confidentiality outright rather than weakening it
+    fn is_broken(&self) -> bool {
+        matches!(self, WeakCipherClass::Export | WeakCipherClass::Null | WeakCipherClass::Anonymous)
+    }
+
+    const ALL: [WeakCipherClass; 5] = [
+        WeakCipherClass::Export,
+        WeakCipherClass::Null,
+        WeakCipherClass::Anonymous,
+        WeakCipherClass::Rc4,
+        WeakCipherClass::Des,
+    ];
+}
+
+/// Known cipher suites: code, IANA name, weakness
+const CIPHER_SUITES: &[(u16, &str, Option<WeakCipherClass>)] = &[
+    (0x1301, "TLS_AES_128_GCM_SHA256", None),
+    (0x1302, "TLS_AES_256_GCM_SHA384", None),
+    (0x1303, "TLS_CHACHA20_POLY1305_SHA256", None),
+    (0xC02B, "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256", None),
+    (0xC02F, "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256", None),
+    (0xC02C, "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384", None),
+    (0xC030, "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384", None),
+    (0xCCA9, "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256", None),
+    (0xCCA8, "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256", None),
+    (0xC009, "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA", None),
+    (0xC013, "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA", None),
+    (0xC00A, "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA", None),
+    (0xC014, "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA", None),
+    (0x009E, "TLS_DHE_RSA_WITH_AES_128_GCM_SHA256", None),
+    (0x009F, "TLS_DHE_RSA_WITH_AES_256_GCM_SHA384", None),
+    (0x0033, "TLS_DHE_RSA_WITH_AES_128_CBC_SHA", None),
+    (0x0039, "TLS_DHE_RSA_WITH_AES_256_CBC_SHA", None),
+    (0x009C, "TLS_RSA_WITH_AES_128_GCM_SHA256", None),
+    (0x009D, "TLS_RSA_WITH_AES_256_GCM_SHA384", None),
+    (0x002F, "TLS_RSA_WITH_AES_128_CBC_SHA", None),
+    (0x0035, "TLS_RSA_WITH_AES_256_CBC_SHA", None),
+    (0x0003, "TLS_RSA_EXPORT_WITH_RC4_40_MD5", Some(WeakCipherClass::Export)),
+    (0x0006, "TLS_RSA_EXPORT_WITH_RC2_CBC_40_MD5", Some(WeakCipherClass::Export)),
+    (0x0008, "TLS_RSA_EXPORT_WITH_DES40_CBC_SHA", Some(WeakCipherClass::Export)),
+    (0x0014, "TLS_DHE_RSA_EXPORT_WITH_DES40_CBC_SHA", Some(WeakCipherClass::Export)),
+    (0x0062, "TLS_RSA_EXPORT1024_WITH_DES_CBC_SHA", Some(WeakCipherClass::Export)),
+    (0x0064, "TLS_RSA_EXPORT1024_WITH_RC4_56_SHA", Some(WeakCipherClass::Export)),
+    (0x0001, "TLS_RSA_WITH_NULL_MD5", Some(WeakCipherClass::Null)),
+    (0x0002, "TLS_RSA_WITH_NULL_SHA", Some(WeakCipherClass::Null)),
+    (0x003B, "TLS_RSA_WITH_NULL_SHA256", Some(WeakCipherClass::Null)),
+    (0xC010, "TLS_ECDHE_RSA_WITH_NULL_SHA", Some(WeakCipherClass::Null)),
+    (0x0018, "TLS_DH_anon_WITH_RC4_128_MD5", Some(WeakCipherClass::Anonymous)),
+    (0x0034, "TLS_DH_anon_WITH_AES_128_CBC_SHA", Some(WeakCipherClass::Anonymous)),
+    (0x003A, "TLS_DH_anon_WITH_AES_256_CBC_SHA", Some(WeakCipherClass::Anonymous)),
+    (0xC018, "TLS_ECDH_anon_WITH_AES_128_CBC_SHA", Some(WeakCipherClass::Anonymous)),
+    (0x0004, "TLS_RSA_WITH_RC4_128_MD5", Some(WeakCipherClass::Rc4)),
+    (0x0005, "TLS_RSA_WITH_RC4_128_SHA", Some(WeakCipherClass::Rc4)),
+    (0xC007, "TLS_ECDHE_ECDSA_WITH_RC4_128_SHA", Some(WeakCipherClass::Rc4)),
+    (0xC011, "TLS_ECDHE_RSA_WITH_RC4_128_SHA", Some(WeakCipherClass::Rc4)),
+    (0x0009, "TLS_RSA_WITH_DES_CBC_SHA", Some(WeakCipherClass::Des)),
+    (0x000A, "TLS_RSA_WITH_3DES_EDE_CBC_SHA", Some(WeakCipherClass::Des)),
+    (0x0016, "TLS_DHE_RSA_WITH_3DES_EDE_CBC_SHA", Some(WeakCipherClass::Des)),
+    (0xC012, "TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA", Some(WeakCipherClass::Des)),
+];
+
+fn cipher_name(code: u16) -> String {
+    CIPHER_SUITES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, name, _)| name.to_string())
+        .unwrap_or_else(|| format!("0x{:04X}", code))
+}
+
+fn cipher_weakness(code: u16) -> Option<WeakCipherClass> {
+    CIPHER_SUITES.iter().find(|(c, _, _)| *c == code).and_then(|(_, _, weak)| *weak)
+}
+
+/// Protocol versions and cipher suites one endpoint accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsReport {
+    pub ip: Ipv4Addr,
+    pub port: u16,
+    /// Accepted versions, oldest first
+    pub versions: Vec<TlsVersion>,
+    /// Suites the server selected across all probes
+    pub ciphers: Vec<String>,
+    /// Weak suite families the server agreed to
+    pub weak_ciphers: Vec<WeakCipherClass>,
+}
+
+impl TlsReport {
+    /// Legacy-protocol and weak-cipher findings
+    ///
+    /// `admin_interface` raises severity for management UIs of network
+    /// infrastructure, where intercepted sessions leak admin credentials.
+    pub fn findings(&self, admin_interface: bool) -> Vec<ExposureFinding> {
+        let mut findings = Vec::new();
+        let service = if admin_interface { "HTTPS admin" } else { "HTTPS" }.to_string();
+
+        let legacy: Vec<&str> = self.versions.iter().filter(|v| v.is_legacy()).map(|v| v.as_str()).collect();
+        if !legacy.is_empty() {
+            findings.push(ExposureFinding {
+                kind: ExposureKind::TlsLegacyProtocol,
+                port: self.port,
+                service: service.clone(),
+                severity: if admin_interface { "HIGH" } else { "MEDIUM" }.to_string(),
+                evidence: format!("Accepts {}", legacy.join(", ")),
+                recommendation: "Disable TLS 1.0 and 1.1; update the firmware if the device offers no setting".to_string(),
+            });
+        }
+
+        if !self.weak_ciphers.is_empty() {
+            let broken = self.weak_ciphers.iter().any(|c| c.is_broken());
+            let classes: Vec<&str> = self.weak_ciphers.iter().map(|c| c.as_str()).collect();
+            findings.push(ExposureFinding {
+                kind: ExposureKind::TlsWeakCipher,
+                port: self.port,
+                service,
+                severity: match (broken, admin_interface) {
+                    (true, true) => "CRITICAL",
+                    (true, false) | (false, true) => "HIGH",
+                    (false, false) => "MEDIUM",
+                }
+                .to_string(),
+                evidence: format!("Negotiates {} cipher suites", classes.join(", ")),
+                recommendation: "Restrict the server to AEAD suites with forward secrecy (ECDHE with AES-GCM or \
+                                 ChaCha20)"
+                    .to_string(),
+            });
+        }
+
+        findings
+    }
+}
+
+/// Build a ClientHello record offering `version` and `suites`
+fn client_hello(version: TlsVersion, suites: &[u16]) -> Vec<u8> {
+    let mut random = [0u8; 32];
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    for (i, b) in random.iter_mut().enumerate() {
+        *b = (nanos >> ((i % 16) * 8)) as u8 ^ (i as u8).wrapping_mul(0x9d);
+    }
+
+    let mut extensions = Vec::new();
+    let mut push_ext = |kind: u16, data: &[u8]| {
+        extensions.extend_from_slice(&kind.to_be_bytes());
+        extensions.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        extensions.extend_from_slice(data);
+    };
+    // x25519, secp256r1, secp384r1
+    push_ext(0x000a, &[0x00, 0x06, 0x00, 0x1d, 0x00, 0x17, 0x00, 0x18]);
+    push_ext(0x000b, &[0x01, 0x00]);
+    push_ext(
+        0x000d,
+        &[0x00, 0x10, 0x04, 0x03, 0x05, 0x03, 0x08, 0x04, 0x08, 0x05, 0x04, 0x01, 0x05, 0x01, 0x02, 0x01, 0x02, 0x03],
+    );
+    push_ext(0xff01, &[0x00]);
+    if version == TlsVersion::Tls13 {
+        push_ext(0x002b, &[0x02, 0x03, 0x04]);
+        // A key share is mandatory; any 32 bytes are a usable X25519 point for the ServerHello
+        let mut key_share = vec![0x00, 0x24, 0x00, 0x1d, 0x00, 0x20];
+        key_share.extend_from_slice(&random);
+        push_ext(0x0033, &key_share);
+    }
+
+    let mut body = Vec::new();
+    // TLS 1.3 is negotiated through supported_versions; the legacy field stays at 1.2
+    body.extend_from_slice(&version.wire().min(0x0303).to_be_bytes());
+    body.extend_from_slice(&random);
+    body.push(0); // session id
+    body.extend_from_slice(&((suites.len() * 2) as u16).to_be_bytes());
+    for suite in suites {
+        body.extend_from_slice(&suite.to_be_bytes());
+    }
+    body.extend_from_slice(&[0x01, 0x00]); // null compression
+    body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+    body.extend_from_slice(&extensions);
+
+    let mut handshake = vec![0x01];
+    handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+    handshake.extend_from_slice(&body);
+
+    let mut record = vec![0x16, 0x03, 0x01];
+    record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+    record.extend_from_slice(&handshake);
+    record
+}
+
+/// Negotiated (version, cipher) from a ServerHello record, `None` for alerts and anything else
+fn parse_server_hello(record: &[u8]) -> Option<(u16, u16)> {
+    if record.first() != Some(&0x16) || record.get(5) != Some(&0x02) {
+        return None;
+    }
+    let hello = record.get(9..)?;
+    let mut version = u16::from_be_bytes([*hello.first()?, *hello.get(1)?]);
+    let session_len = *hello.get(34)? as usize;
+    let mut pos = 35 + session_len;
+    let cipher = u16::from_be_bytes([*hello.get(pos)?, *hello.get(pos + 1)?]);
+    pos += 3; // cipher + compression
+
+    // supported_versions carries the real version for TLS 1.3
+    if let Some(ext_len) = hello.get(pos..pos + 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as usize) {
+        let mut ext = hello.get(pos + 2..pos + 2 + ext_len).unwrap_or(&[]);
+        while ext.len() >= 4 {
+            let kind = u16::from_be_bytes([ext[0], ext[1]]);
+            let len = u16::from_be_bytes([ext[2], ext[3]]) as usize;
+            let data = ext.get(4..4 + len)?;
+            if kind == 0x002b && len == 2 {
+                version = u16::from_be_bytes([data[0], data[1]]);
+            }
+            ext = &ext[4 + len..];
+        }
+    }
+    Some((version, cipher))
+}
+
+/// Note a suite the server selected
+fn record_cipher(report: &mut TlsReport, code: u16) {
+    let name = cipher_name(code);
+    if !report.ciphers.contains(&name) {
+        report.ciphers.push(name);
+    }
+    if let Some(weak) = cipher_weakness(code) {
+        if !report.weak_ciphers.contains(&weak) {
+            report.weak_ciphers.push(weak);
+        }
+    }
+}
+
+/// Send one ClientHello and return the negotiated (version, cipher)
+async fn handshake(ip: Ipv4Addr, port: u16, version: TlsVersion, suites: &[u16]) -> Option<(u16, u16)> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    let mut stream = match timeout(HANDSHAKE_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return None,
+    };
+    stream.write_all(&client_hello(version, suites)).await.ok()?;
+
+    let mut header = [0u8; 5];
+    timeout(HANDSHAKE_TIMEOUT, stream.read_exact(&mut header)).await.ok()?.ok()?;
+    let length = u16::from_be_bytes([header[3], header[4]]) as usize;
+    let mut record = header.to_vec();
+    record.resize(5 + length.min(16 * 1024), 0);
+    timeout(HANDSHAKE_TIMEOUT, stream.read_exact(&mut record[5..])).await.ok()?.ok()?;
+    parse_server_hello(&record)
+}
+
+/// Probe the versions and weak suites a TLS endpoint accepts
+///
+/// Returns `None` if the port does not complete any handshake.
+pub async fn tls_assess(ip: Ipv4Addr, port: u16) -> Option<TlsReport> {
+    let mut report = TlsReport { ip, port, versions: Vec::new(), ciphers: Vec::new(), weak_ciphers: Vec::new() };
+    let all_suites: Vec<u16> = CIPHER_SUITES.iter().map(|(code, _, _)| *code).collect();
+    for version in [TlsVersion::Tls10, TlsVersion::Tls11, TlsVersion::Tls12, TlsVersion::Tls13] {
+        if let Some((negotiated, cipher)) = handshake(ip, port, version, &all_suites).await {
+            if negotiated == version.wire() {
+                report.versions.push(version);
+                record_cipher(&mut report, cipher);
+            }
+        }
+    }
+    if report.versions.is_empty() {
+        return None;
+    }
+
+    for class in WeakCipherClass::ALL {
+        let suites: Vec<u16> = CIPHER_SUITES.iter().filter(|(_, _, w)| *w == Some(class)).map(|(c, _, _)| *c).collect();
+        // Export suites are forbidden from TLS 1.1 on, so offer them at 1.0
+        let offer = if class == WeakCipherClass::Export { TlsVersion::Tls10 } else { TlsVersion::Tls12 };
+        if let Some((_, cipher)) = handshake(ip, port, offer, &suites).await {
+            if suites.contains(&cipher) {
+                record_cipher(&mut report, cipher);
+            }
+        }
+    }
+
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_hello(version: u16, cipher: u16, extensions: &[u8]) -> Vec<u8> {
+        let mut body = version.to_be_bytes().to_vec();
+        body.extend_from_slice(&[0xAB; 32]);
+        body.push(0);
+        body.extend_from_slice(&cipher.to_be_bytes());
+        body.push(0);
+        body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        body.extend_from_slice(extensions);
+        let mut record = vec![0x16, 0x03, 0x03];
+        record.extend_from_slice(&((body.len() + 4) as u16).to_be_bytes());
+        record.push(0x02);
+        record.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        record.extend_from_slice(&body);
+        record
+    }
+
+    #[test]
+    fn test_client_and_server_hello() {
+        let hello = client_hello(TlsVersion::Tls10, &[0x0004, 0x000A]);
+        assert_eq!(&hello[..3], &[0x16, 0x03, 0x01]);
+        assert_eq!(u16::from_be_bytes([hello[3], hello[4]]) as usize, hello.len() - 5);
+        assert_eq!(&hello[9..11], &[0x03, 0x01]);
+
+        assert_eq!(parse_server_hello(&server_hello(0x0301, 0x0005, &[])), Some((0x0301, 0x0005)));
+        let tls13 = server_hello(0x0303, 0x1301, &[0x00, 0x2b, 0x00, 0x02, 0x03, 0x04]);
+        assert_eq!(parse_server_hello(&tls13), Some((0x0304, 0x1301)));
+        // handshake_failure alert
+        assert_eq!(parse_server_hello(&[0x15, 0x03, 0x01, 0x00, 0x02, 0x02, 0x28]), None);
+    }
+
+    #[test]
+    fn test_tls_findings() {
+        let report = TlsReport {
+            ip: Ipv4Addr::new(192, 168, 1, 1),
+            port: 443,
+            versions: vec![TlsVersion::Tls10, TlsVersion::Tls12],
+            ciphers: vec![cipher_name(0xC02F), cipher_name(0x0005)],
+            weak_ciphers: vec![WeakCipherClass::Rc4],
+        };
+        let findings = report.findings(true);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].evidence, "Accepts TLS 1.0");
+        assert_eq!(findings[1].severity, "HIGH");
+        assert_eq!(report.ciphers[1], "TLS_RSA_WITH_RC4_128_SHA");
+        assert!(report.findings(false)[1].severity == "MEDIUM");
+    }
+}
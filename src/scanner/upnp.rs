@@ -0,0 +1,349 @@
+//! UPnP IGD port-mapping enumeration
+//!
+//! Home routers let any LAN device open inbound port forwards through UPnP
+//! Internet Gateway Device control, with no authentication. Malware uses it
+//! to expose a compromised host, and forgotten game consoles and NAS boxes
+//! leave forwards open for years. The check locates the gateway's
+//! WANIPConnection (or WANPPPConnection) service over SSDP and walks its
+//! mapping table with GetGenericPortMappingEntry, which is read-only.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::time::{timeout, Instant};
+
+use super::exposure::{ExposureFinding, ExposureKind};
+use crate::insights::{Priority, Recommendation};
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+const SSDP_MULTICAST: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// How long to wait for SSDP answers
+const SSDP_WAIT: Duration = Duration::from_secs(2);
+
+/// Timeout for each HTTP/SOAP request
+const HTTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Upper bound on mapping indexes walked, protects against looping devices
+const MAX_MAPPINGS: u32 = 256;
+
+const IGD_DEVICE: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services holding the port-mapping table, in order of preference
+const WAN_SERVICES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// Internal ports whose exposure is a remote-access or file-sharing risk
+const SENSITIVE_PORTS: &[u16] = &[21, 22, 23, 445, 3389, 5900, 8291];
+
+/// One inbound forward configured through UPnP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortMapping {
+    pub external_port: u16,
+    /// "TCP" or "UDP"
+    pub protocol: String,
+    pub internal_client: String,
+    pub internal_port: u16,
+    pub enabled: bool,
+    /// Description the requesting application gave
+    pub description: String,
+    /// Seconds until expiry, 0 = permanent
+    pub lease_duration: u32,
+    /// Allowed remote address, empty = any
+    pub remote_host: String,
+}
+
+/// Port mappings read from the gateway
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpnpReport {
+    pub gateway: Ipv4Addr,
+    /// Control service the table was read from
+    pub service_type: String,
+    pub mappings: Vec<PortMapping>,
+}
+
+impl UpnpReport {
+    /// One exposure finding per enabled forward, keyed by internal target host
+    pub fn findings(&self) -> HashMap<Ipv4Addr, Vec<ExposureFinding>> {
+        let mut findings: HashMap<Ipv4Addr, Vec<ExposureFinding>> = HashMap::new();
+        for mapping in self.mappings.iter().filter(|m| m.enabled) {
+            let Ok(target) = mapping.internal_client.parse::<Ipv4Addr>() else { continue };
+            let lease = match mapping.lease_duration {
+                0 => "permanent".to_string(),
+                secs => format!("expires in {}s", secs),
+            };
+            let source = if mapping.remote_host.is_empty() { "any address" } else { mapping.remote_host.as_str() };
+            findings.entry(target).or_default().push(ExposureFinding {
+                kind: ExposureKind::UpnpPortForward,
+                port: mapping.internal_port,
+                service: "UPnP port forward".to_string(),
+                severity: if SENSITIVE_PORTS.contains(&mapping.internal_port) { "HIGH" } else { "MEDIUM" }.to_string(),
+                evidence: format!(
+                    "{} {} on {} forwarded from {} to {}:{} (\"{}\", {})",
+                    mapping.protocol,
+                    mapping.external_port,
+                    self.gateway,
+                    source,
+                    mapping.internal_client,
+                    mapping.internal_port,
+                    mapping.description,
+                    lease
+                ),
+                recommendation: "Confirm the forward is expected; remove it on the router and disable UPnP if no \
+                                 application needs it"
+                    .to_string(),
+            });
+        }
+        findings
+    }
+
+    /// Insight when the gateway has forwards open
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        let enabled: Vec<&PortMapping> = self.mappings.iter().filter(|m| m.enabled).collect();
+        if enabled.is_empty() {
+            return None;
+        }
+        let sensitive = enabled.iter().any(|m| SENSITIVE_PORTS.contains(&m.internal_port));
+
+        let mut targets: Vec<String> = enabled.iter().map(|m| m.internal_client.clone()).collect();
+        targets.sort();
+        targets.dedup();
+
+        Some(Recommendation {
+            priority: if sensitive { Priority::High } else { Priority::Medium },
+            category: "UPnP".to_string(),
+            title: format!("{} UPnP port forward(s) open on the gateway", enabled.len()),
+            description: "Devices opened inbound forwards through UPnP without any authentication. Forwards \
+                          nobody remembers creating are a classic sign of a compromised host. Review each \
+                          forward, remove unexpected ones and disable UPnP on the router if nothing needs it."
+                .to_string(),
+            affected_devices: targets,
+        })
+    }
+}
+
+/// Value of a `LOCATION:` header in an SSDP response
+fn parse_ssdp_location(response: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+    })
+}
+
+/// Address and path of an `http://a.b.c.d[:port]/path` URL
+fn split_url(url: &str) -> Option<(SocketAddr, String)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], rest[i..].to_string()),
+        None => (rest, "/".to_string()),
+    };
+    let addr = match authority.split_once(':') {
+        Some((host, port)) => SocketAddr::new(IpAddr::V4(host.parse().ok()?), port.parse().ok()?),
+        None => SocketAddr::new(IpAddr::V4(authority.parse().ok()?), 80),
+    };
+    Some((addr, path))
+}
+
+/// Text of the first `<tag>` element at or after `from`, ignoring namespace prefixes
+fn xml_tag_from(xml: &str, tag: &str, from: usize) -> Option<(String, usize)> {
+    let open = [format!("<{}>", tag), format!(":{}>", tag)];
+    let start = open
+        .iter()
+        .filter_map(|o| xml[from..].find(o.as_str()).map(|i| from + i + o.len()))
+        .min()?;
+    let end = start + xml[start..].find("</")?;
+    Some((xml[start..end].trim().to_string(), end))
+}
+
+fn xml_tag(xml: &str, tag: &str) -> Option<String> {
+    xml_tag_from(xml, tag, 0).map(|(value, _)| value)
+}
+
+/// WAN connection service type and its control path from a device description
+fn find_wan_service(description: &str) -> Option<(String, String)> {
+    WAN_SERVICES.iter().find_map(|service| {
+        let at = description.find(&format!(">{}<", service))?;
+        let (control, _) = xml_tag_from(description, "controlURL", at)?;
+        let path = match control.strip_prefix("http://") {
+            Some(_) => split_url(&control)?.1,
+            None if control.starts_with('/') => control,
+            None => format!("/{}", control),
+        };
+        Some((service.to_string(), path))
+    })
+}
+
+/// Parse a GetGenericPortMappingEntry response
+fn parse_mapping_entry(response: &str) -> Option<PortMapping> {
+    Some(PortMapping {
+        external_port: xml_tag(response, "NewExternalPort")?.parse().ok()?,
+        protocol: xml_tag(response, "NewProtocol")?,
+        internal_client: xml_tag(response, "NewInternalClient")?,
+        internal_port: xml_tag(response, "NewInternalPort")?.parse().ok()?,
+        enabled: xml_tag(response, "NewEnabled").is_none_or(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+        description: xml_tag(response, "NewPortMappingDescription").unwrap_or_default(),
+        lease_duration: xml_tag(response, "NewLeaseDuration").and_then(|v| v.parse().ok()).unwrap_or(0),
+        remote_host: xml_tag(response, "NewRemoteHost").unwrap_or_default(),
+    })
+}
+
+/// Find the gateway's IGD description URL over SSDP
+async fn discover_igd(gateway: Ipv4Addr) -> Result<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await.context("Failed to bind SSDP socket")?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\nMX: 1\r\nST: {}\r\n\r\n",
+        IGD_DEVICE
+    );
+    // Some routers only answer unicast searches, others only multicast
+    for target in [SocketAddr::new(IpAddr::V4(gateway), 1900), SSDP_MULTICAST] {
+        socket.send_to(search.as_bytes(), target).await?;
+    }
+
+    let deadline = Instant::now() + SSDP_WAIT;
+    let mut buf = [0u8; 2048];
+    while let Ok(Ok((n, from))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        if from.ip() != IpAddr::V4(gateway) {
+            continue;
+        }
+        if let Some(location) = parse_ssdp_location(&String::from_utf8_lossy(&buf[..n])) {
+            return Ok(location);
+        }
+    }
+    Err(anyhow!("No UPnP IGD answered on {}", gateway))
+}
+
+/// Send a raw HTTP/1.0 request and return (status code, body)
+async fn http_request(addr: SocketAddr, request: &str) -> Result<(u16, String)> {
+    let mut stream = timeout(HTTP_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .map_err(|_| anyhow!("Connection to {} timed out", addr))??;
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    let _ = timeout(HTTP_TIMEOUT, stream.read_to_end(&mut response)).await;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| anyhow!("Malformed HTTP response"))?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow!("Malformed HTTP status line"))?;
+    Ok((status, body.to_string()))
+}
+
+/// Read one mapping; `None` once the index is past the end of the table
+async fn get_mapping(addr: SocketAddr, path: &str, service: &str, index: u32) -> Result<Option<PortMapping>> {
+    let body = format!(
+        "<?xml version=\"1.0\"?><s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+         <u:GetGenericPortMappingEntry xmlns:u=\"{}\"><NewPortMappingIndex>{}</NewPortMappingIndex>\
+         </u:GetGenericPortMappingEntry></s:Body></s:Envelope>",
+        service, index
+    );
+    let request = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: text/xml; charset=\"utf-8\"\r\n\
+         SOAPAction: \"{}#GetGenericPortMappingEntry\"\r\nContent-Length: {}\r\n\r\n{}",
+        path,
+        addr,
+        service,
+        body.len(),
+        body
+    );
+
+    let (status, response) = http_request(addr, &request).await?;
+    match status {
+        200 => Ok(parse_mapping_entry(&response)),
+        // SOAP fault, normally 713 SpecifiedArrayIndexInvalid at the end of the table
+        500 => Ok(None),
+        _ => Err(anyhow!("GetGenericPortMappingEntry returned HTTP {}", status)),
+    }
+}
+
+/// Enumerate the UPnP port forwards configured on a gateway
+pub async fn upnp_port_mappings(gateway: Ipv4Addr) -> Result<UpnpReport> {
+    log_stderr!("UPnP: looking for an IGD on {}...", gateway);
+
+    let location = discover_igd(gateway).await?;
+    let (addr, path) = split_url(&location).ok_or_else(|| anyhow!("Unsupported IGD location {}", location))?;
+    let (status, description) =
+        http_request(addr, &format!("GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", path, addr)).await?;
+    if status != 200 {
+        return Err(anyhow!("IGD description returned HTTP {}", status));
+    }
+    let (service_type, control_path) =
+        find_wan_service(&description).ok_or_else(|| anyhow!("IGD exposes no WAN connection service"))?;
+
+    let mut mappings = Vec::new();
+    for index in 0..MAX_MAPPINGS {
+        match get_mapping(addr, &control_path, &service_type, index).await? {
+            Some(mapping) => mappings.push(mapping),
+            None => break,
+        }
+    }
+
+    log_stderr!("UPnP: {} port mappings on {}", mappings.len(), gateway);
+    Ok(UpnpReport {
+        gateway,
+        service_type,
+        mappings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_discovery_and_description() {
+        let ssdp = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\nLocation: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        let location = parse_ssdp_location(ssdp).unwrap();
+        let (addr, path) = split_url(&location).unwrap();
+        assert_eq!(addr, "192.168.1.1:5000".parse().unwrap());
+        assert_eq!(path, "/rootDesc.xml");
+
+        let description = "<service><serviceType>urn:schemas-upnp-org:service:WANCommonInterfaceConfig:1</serviceType>\
+                           <controlURL>/ctl/CmnIfCfg</controlURL></service>\
+                           <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+                           <serviceId>urn:upnp-org:serviceId:WANIPConn1</serviceId><controlURL>/ctl/IPConn</controlURL></service>";
+        assert_eq!(
+            find_wan_service(description),
+            Some(("urn:schemas-upnp-org:service:WANIPConnection:1".to_string(), "/ctl/IPConn".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_mapping_findings() {
+        let response = "<s:Envelope><s:Body><u:GetGenericPortMappingEntryResponse>\
+                        <NewRemoteHost></NewRemoteHost><NewExternalPort>3389</NewExternalPort><NewProtocol>TCP</NewProtocol>\
+                        <NewInternalPort>3389</NewInternalPort><NewInternalClient>192.168.1.20</NewInternalClient>\
+                        <NewEnabled>1</NewEnabled><NewPortMappingDescription>svc</NewPortMappingDescription>\
+                        <NewLeaseDuration>0</NewLeaseDuration></u:GetGenericPortMappingEntryResponse></s:Body></s:Envelope>";
+        let mapping = parse_mapping_entry(response).unwrap();
+        assert_eq!(mapping.internal_client, "192.168.1.20");
+        assert_eq!(mapping.remote_host, "");
+
+        let report = UpnpReport {
+            gateway: Ipv4Addr::new(192, 168, 1, 1),
+            service_type: WAN_SERVICES[1].to_string(),
+            mappings: vec![mapping],
+        };
+        let findings = report.findings();
+        let finding = &findings[&Ipv4Addr::new(192, 168, 1, 20)][0];
+        assert_eq!(finding.severity, "HIGH");
+        assert!(finding.evidence.contains("from any address to 192.168.1.20:3389"));
+        assert_eq!(report.recommendation().unwrap().priority, Priority::High);
+    }
+}
@@ -0,0 +1,164 @@
+//! External IP and internet reachability check
+//!
+//! Determines the public egress address (STUN first, plain-text HTTP
+//! endpoints as a fallback) and times TCP connects to a few well-known
+//! upstream hosts. The result ties LAN findings to the WAN: a healthy LAN
+//! with an unreachable internet points at the ISP, not at local devices.
+
+use futures::future::join_all;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::nat::stun_mapped_address;
+use crate::config::{WAN_IP_HTTP_ENDPOINTS, WAN_LATENCY_TARGETS, WAN_STUN_SERVERS};
+use crate::models::WanHealth;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// Timeout for each connect/request to an upstream endpoint
+const WAN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Public address from the first STUN server that answers
+async fn ip_from_stun() -> Option<(Ipv4Addr, String)> {
+    for server in WAN_STUN_SERVERS {
+        let Ok(Some(addr)) = tokio::net::lookup_host(server).await.map(|mut a| a.find(|a| a.is_ipv4())) else {
+            continue;
+        };
+        if let Ok(ip) = stun_mapped_address(addr).await {
+            return Some((ip, format!("stun://{}", server)));
+        }
+    }
+    None
+}
+
+/// Extract an IPv4 address from a plain-text HTTP response body
+fn parse_ip_response(response: &str) -> Option<Ipv4Addr> {
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    body.trim().parse().ok()
+}
+
+/// Public address from the first HTTP endpoint that answers
+async fn ip_from_http() -> Option<(Ipv4Addr, String)> {
+    for (host, path) in WAN_IP_HTTP_ENDPOINTS {
+        let Ok(Ok(mut stream)) = timeout(WAN_TIMEOUT, TcpStream::connect((*host, 80))).await else {
+            continue;
+        };
+        let request = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: NetworkTopologyMapper\r\n\r\n",
+            path, host
+        );
+        if stream.write_all(request.as_bytes()).await.is_err() {
+            continue;
+        }
+        let mut body = Vec::new();
+        let _ = timeout(WAN_TIMEOUT, stream.read_to_end(&mut body)).await;
+        if let Some(ip) = parse_ip_response(&String::from_utf8_lossy(&body)) {
+            return Some((ip, format!("http://{}{}", host, path)));
+        }
+    }
+    None
+}
+
+/// TCP connect time to `target`, or `None` if unreachable
+async fn connect_latency(target: SocketAddr) -> Option<f64> {
+    let start = Instant::now();
+    match timeout(WAN_TIMEOUT, TcpStream::connect(target)).await {
+        Ok(Ok(_)) => Some(start.elapsed().as_secs_f64() * 1000.0),
+        _ => None,
+    }
+}
+
+/// Median of the measured latencies
+fn median(latencies: &mut [f64]) -> Option<f64> {
+    if latencies.is_empty() {
+        return None;
+    }
+    latencies.sort_by(|a, b| a.total_cmp(b));
+    let mid = latencies.len() / 2;
+    Some(if latencies.len().is_multiple_of(2) {
+        (latencies[mid - 1] + latencies[mid]) / 2.0
+    } else {
+        latencies[mid]
+    })
+}
+
+/// Connect times to the reachable WAN_LATENCY_TARGETS, and the target count
+async fn measure_targets() -> (Vec<f64>, usize) {
+    let targets: Vec<SocketAddr> = WAN_LATENCY_TARGETS.iter().filter_map(|t| t.parse().ok()).collect();
+    let latencies = join_all(targets.iter().map(|t| connect_latency(*t))).await;
+    (latencies.into_iter().flatten().collect(), targets.len())
+}
+
+/// One latency sample across the upstream anchors (median connect time)
+///
+/// Returns `None` when no anchor could be reached.
+pub async fn sample_wan_latency() -> Option<f64> {
+    let (mut reachable, _) = measure_targets().await;
+    median(&mut reachable)
+}
+
+/// Determine the public IP and upstream reachability/latency
+pub async fn wan_check() -> WanHealth {
+    log_stderr!("WAN check: public IP and {} upstream targets...", WAN_LATENCY_TARGETS.len());
+
+    let (public, (mut reachable, total_targets)) = tokio::join!(
+        async {
+            match ip_from_stun().await {
+                Some(found) => Some(found),
+                None => ip_from_http().await,
+            }
+        },
+        measure_targets()
+    );
+
+    let health = WanHealth {
+        public_ip: public.as_ref().map(|(ip, _)| ip.to_string()),
+        public_ip_source: public.map(|(_, source)| source),
+        reachable_targets: reachable.len(),
+        total_targets,
+        latency_ms: median(&mut reachable),
+    };
+
+    log_stderr!(
+        "WAN check complete: public IP {}, {}/{} targets reachable{}",
+        health.public_ip.as_deref().unwrap_or("unknown"),
+        health.reachable_targets,
+        health.total_targets,
+        health.latency_ms.map(|ms| format!(", {:.0} ms", ms)).unwrap_or_default()
+    );
+    health
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ip_response() {
+        assert_eq!(
+            parse_ip_response("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\n203.0.113.7\n"),
+            Some(Ipv4Addr::new(203, 0, 113, 7))
+        );
+        // A captive portal page is not an address
+        assert!(parse_ip_response("HTTP/1.1 200 OK\r\n\r\n<html>login</html>").is_none());
+        assert!(parse_ip_response("HTTP/1.1 302 Found\r\n\r\n203.0.113.7").is_none());
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut [30.0, 10.0, 20.0]), Some(20.0));
+        assert_eq!(median(&mut [10.0, 40.0]), Some(25.0));
+        assert_eq!(median(&mut []), None);
+    }
+}
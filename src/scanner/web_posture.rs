@@ -0,0 +1,320 @@
+//! HTTP security posture of LAN web interfaces
+//!
+//! Fetches `/` on each web port a host exposes (following one same-host
+//! redirect) and records how the interface protects itself: whether plain
+//! HTTP redirects to HTTPS, which authentication the login uses and whether
+//! it is a recognizable vendor default login page. The per-port results
+//! are folded into one management-plane finding per device. HTTPS ports are
+//! only noted as present: the crate has no TLS client to fetch them.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::exposure::{ExposureFinding, ExposureKind};
+use super::tls::TLS_PORTS;
+
+/// Plain HTTP ports web UIs listen on
+pub const HTTP_PORTS: &[u16] = &[80, 81, 8000, 8080, 8888];
+
+/// Timeout for each connect/read step
+const FETCH_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Upper bound on the response read from a device
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// Markers of a login or admin page in the response body
+const LOGIN_MARKERS: &[&str] = &["type=\"password\"", "type='password'", "login", "sign in", "password", "admin"];
+
+/// Vendor default login pages: lowercase marker, product
+const DEFAULT_LOGIN_PAGES: &[(&str, &str)] = &[
+    ("luci", "OpenWrt LuCI"),
+    ("dd-wrt", "DD-WRT"),
+    ("routeros", "MikroTik RouterOS"),
+    ("mikrotik", "MikroTik RouterOS"),
+    ("pfsense", "pfSense"),
+    ("fritz!box", "FRITZ!Box"),
+    ("asuswrt", "ASUSWRT"),
+    ("tp-link", "TP-Link"),
+    ("netgear", "NETGEAR"),
+    ("linksys", "Linksys"),
+    ("d-link", "D-Link"),
+    ("unifi", "UniFi"),
+    ("synology", "Synology DSM"),
+    ("qnap", "QNAP QTS"),
+    ("hikvision", "Hikvision"),
+    ("doc/page/login.asp", "Hikvision"),
+];
+
+/// How an interface asks for credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebAuth {
+    /// Page served without any login
+    None,
+    /// HTTP Basic, credentials sent base64-encoded
+    Basic,
+    Digest,
+    /// HTML login form
+    Form,
+}
+
+impl WebAuth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebAuth::None => "no login",
+            WebAuth::Basic => "HTTP Basic auth",
+            WebAuth::Digest => "HTTP Digest auth",
+            WebAuth::Form => "login form",
+        }
+    }
+}
+
+/// Behaviour of one plain HTTP web interface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebInterface {
+    pub port: u16,
+    /// Status of the first response
+    pub status: u16,
+    /// Location header of the first response, if it redirected
+    pub redirect: Option<String>,
+    pub redirects_to_https: bool,
+    pub auth: WebAuth,
+    /// Page looks like a management UI (login or admin markers)
+    pub admin_page: bool,
+    /// Vendor whose default login page is served
+    pub default_login: Option<String>,
+    pub server: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Web management surface of one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagementPlaneReport {
+    pub ip: Ipv4Addr,
+    pub interfaces: Vec<WebInterface>,
+    /// Open HTTPS ports (not fetched)
+    pub https_ports: Vec<u16>,
+}
+
+impl ManagementPlaneReport {
+    /// One finding summarizing how exposed the device's management UI is
+    pub fn finding(&self) -> Option<ExposureFinding> {
+        let plaintext: Vec<&WebInterface> =
+            self.interfaces.iter().filter(|i| i.admin_page && !i.redirects_to_https).collect();
+        let default_login = self.interfaces.iter().find_map(|i| i.default_login.as_deref());
+        if plaintext.is_empty() && default_login.is_none() {
+            return None;
+        }
+
+        let mut evidence: Vec<String> = plaintext
+            .iter()
+            .map(|i| format!("port {} serves {} over plain HTTP (status {})", i.port, i.auth.as_str(), i.status))
+            .collect();
+        if let Some(product) = default_login {
+            evidence.push(format!("default {} login page exposed", product));
+        }
+        if self.https_ports.is_empty() {
+            evidence.push("no HTTPS interface".to_string());
+        }
+
+        // Unauthenticated or Basic-auth admin pages hand out control or credentials to anyone sniffing
+        let severity = if plaintext.iter().any(|i| matches!(i.auth, WebAuth::None | WebAuth::Basic)) {
+            "HIGH"
+        } else if !plaintext.is_empty() {
+            "MEDIUM"
+        } else {
+            "LOW"
+        };
+
+        Some(ExposureFinding {
+            kind: ExposureKind::ManagementPlaneExposure,
+            port: plaintext.first().map_or_else(|| self.interfaces[0].port, |i| i.port),
+            service: "HTTP".to_string(),
+            severity: severity.to_string(),
+            evidence: evidence.join("; "),
+            recommendation: "Serve the management UI over HTTPS only (redirect HTTP), change the default admin \
+                             credentials and restrict access to a management VLAN"
+                .to_string(),
+        })
+    }
+}
+
+/// Response headers as (lowercase name, value)
+type Headers = Vec<(String, String)>;
+
+/// Status code, headers and body of an HTTP response
+fn parse_response(response: &str) -> Option<(u16, Headers, &str)> {
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response, ""));
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    Some((status, headers, body))
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+fn html_title(body: &str) -> Option<String> {
+    let lower = body.to_lowercase();
+    let start = lower.find("<title>")? + "<title>".len();
+    let end = start + lower[start..].find("</title>")?;
+    Some(body[start..end].trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Classify the login mechanism of a response
+fn classify_auth(status: u16, headers: &[(String, String)], body: &str) -> WebAuth {
+    if status == 401 {
+        return match header(headers, "www-authenticate").map(|v| v.to_lowercase()) {
+            Some(scheme) if scheme.starts_with("digest") => WebAuth::Digest,
+            _ => WebAuth::Basic,
+        };
+    }
+    let lower = body.to_lowercase();
+    if lower.contains("type=\"password\"") || lower.contains("type='password'") || lower.contains("type=password") {
+        WebAuth::Form
+    } else {
+        WebAuth::None
+    }
+}
+
+/// Vendor of a recognizable default login page
+fn default_login_product(headers: &[(String, String)], body: &str) -> Option<&'static str> {
+    let haystack = format!("{} {}", header(headers, "server").unwrap_or_default(), body).to_lowercase();
+    DEFAULT_LOGIN_PAGES.iter().find(|(marker, _)| haystack.contains(marker)).map(|(_, product)| *product)
+}
+
+/// GET a path and return the raw response
+async fn fetch(ip: Ipv4Addr, port: u16, path: &str) -> Option<String> {
+    let addr = SocketAddr::new(IpAddr::V4(ip), port);
+    let mut stream = match timeout(FETCH_TIMEOUT, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return None,
+    };
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", path, ip);
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut response = Vec::new();
+    let _ = timeout(FETCH_TIMEOUT, (&mut stream).take(MAX_RESPONSE_BYTES).read_to_end(&mut response)).await;
+    (!response.is_empty()).then(|| String::from_utf8_lossy(&response).into_owned())
+}
+
+/// Path of a redirect that stays on the same plain HTTP interface
+fn same_host_path(location: &str, ip: Ipv4Addr, port: u16) -> Option<String> {
+    if location.starts_with('/') {
+        return Some(location.to_string());
+    }
+    let rest = location.strip_prefix("http://")?;
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let same = authority == ip.to_string() || (port != 80 && authority == format!("{}:{}", ip, port));
+    same.then(|| if path.is_empty() { "/".to_string() } else { path.to_string() })
+}
+
+/// Inspect one plain HTTP port
+async fn inspect_port(ip: Ipv4Addr, port: u16) -> Option<WebInterface> {
+    let response = fetch(ip, port, "/").await?;
+    let (status, headers, body) = parse_response(&response)?;
+    let redirect = header(&headers, "location").map(str::to_string).filter(|_| (300..400).contains(&status));
+    let redirects_to_https = redirect.as_deref().is_some_and(|l| l.to_lowercase().starts_with("https://"));
+
+    // Follow one same-host redirect so "/" -> "/login.html" is classified by the login page
+    let followed = match redirect.as_deref().and_then(|l| same_host_path(l, ip, port)) {
+        Some(path) => fetch(ip, port, &path).await,
+        None => None,
+    };
+    let (page_status, page_headers, page_body) = match followed.as_deref().and_then(parse_response) {
+        Some(page) => page,
+        None => (status, headers.clone(), body),
+    };
+
+    let auth = classify_auth(page_status, &page_headers, page_body);
+    let lower = page_body.to_lowercase();
+    let admin_page = auth != WebAuth::None || (page_status == 200 && LOGIN_MARKERS.iter().any(|m| lower.contains(m)));
+    let default_login = if admin_page { default_login_product(&page_headers, page_body) } else { None };
+
+    Some(WebInterface {
+        port,
+        status,
+        redirect,
+        redirects_to_https,
+        auth,
+        admin_page,
+        default_login: default_login.map(str::to_string),
+        server: header(&headers, "server").map(str::to_string),
+        title: html_title(page_body),
+    })
+}
+
+/// Record the web management surface of a host from its open ports
+///
+/// Returns `None` when the host serves no web interface.
+pub async fn management_plane(ip: Ipv4Addr, open_ports: &[u16]) -> Option<ManagementPlaneReport> {
+    let mut interfaces = Vec::new();
+    for &port in HTTP_PORTS.iter().filter(|p| open_ports.contains(*p)) {
+        if let Some(interface) = inspect_port(ip, port).await {
+            interfaces.push(interface);
+        }
+    }
+    let https_ports: Vec<u16> = TLS_PORTS.iter().copied().filter(|p| open_ports.contains(p)).collect();
+    if interfaces.is_empty() {
+        return None;
+    }
+    Some(ManagementPlaneReport { ip, interfaces, https_ports })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_responses() {
+        let basic = "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"TP-LINK\"\r\nServer: httpd\r\n\r\n";
+        let (status, headers, body) = parse_response(basic).unwrap();
+        assert_eq!(classify_auth(status, &headers, body), WebAuth::Basic);
+
+        let form = "HTTP/1.0 200 OK\r\n\r\n<title>LuCI</title><form><input type=\"password\" name=\"luci_password\"></form>";
+        let (status, headers, body) = parse_response(form).unwrap();
+        assert_eq!(classify_auth(status, &headers, body), WebAuth::Form);
+        assert_eq!(default_login_product(&headers, body), Some("OpenWrt LuCI"));
+        assert_eq!(html_title(body).as_deref(), Some("LuCI"));
+
+        let ip = Ipv4Addr::new(192, 168, 1, 1);
+        assert_eq!(same_host_path("/cgi-bin/luci", ip, 80).as_deref(), Some("/cgi-bin/luci"));
+        assert_eq!(same_host_path("http://192.168.1.1", ip, 80).as_deref(), Some("/"));
+        assert_eq!(same_host_path("https://192.168.1.1/", ip, 80), None);
+    }
+
+    #[test]
+    fn test_management_plane_finding() {
+        let interface = |port: u16, auth: WebAuth, redirects_to_https: bool| WebInterface {
+            port,
+            status: if redirects_to_https { 301 } else { 200 },
+            redirect: None,
+            redirects_to_https,
+            auth,
+            admin_page: true,
+            default_login: None,
+            server: None,
+            title: None,
+        };
+        let mut report = ManagementPlaneReport {
+            ip: Ipv4Addr::new(192, 168, 1, 1),
+            interfaces: vec![interface(80, WebAuth::Form, false)],
+            https_ports: vec![],
+        };
+        let finding = report.finding().unwrap();
+        assert_eq!(finding.severity, "MEDIUM");
+        assert_eq!(finding.evidence, "port 80 serves login form over plain HTTP (status 200); no HTTPS interface");
+
+        report.interfaces = vec![interface(80, WebAuth::Form, true)];
+        report.https_ports = vec![443];
+        assert!(report.finding().is_none());
+    }
+}
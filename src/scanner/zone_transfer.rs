@@ -0,0 +1,203 @@
+//! DNS zone transfer (AXFR) exposure check
+//!
+//! An internal DNS server that hands its zone to anyone who asks gives
+//! away the whole naming map of the network — every host, server and
+//! appliance — in one request. Transfers should be limited to secondary
+//! servers. The check asks each responding DNS server for the local zone
+//! over TCP/53 and only reads the first response message: a NOERROR
+//! answer carrying records means the transfer was allowed.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::dns_servers::DnsServerInfo;
+use super::limits::concurrency_limits;
+use crate::models::HostInfo;
+
+/// Logs a message to stderr
+macro_rules! log_stderr {
+    ($($arg:tt)*) => {
+        eprintln!("[INFO] {}", format!($($arg)*));
+    };
+}
+
+/// DNS port
+const DNS_PORT: u16 = 53;
+
+/// Timeout for connecting and for reading the first response message
+const AXFR_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// QTYPE AXFR
+const QTYPE_AXFR: u16 = 252;
+
+/// DNS header flag: query/response
+const FLAG_QR: u16 = 0x8000;
+
+/// Response code NOERROR
+const RCODE_NOERROR: u8 = 0;
+
+/// Outcome of a zone transfer attempt against one server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneTransferResult {
+    pub ip: String,
+    pub zone: String,
+    /// The server sent zone records
+    pub allowed: bool,
+    /// Records in the first response message
+    pub records: u16,
+    /// Response code, when the server answered
+    pub rcode: Option<u8>,
+}
+
+/// Build an AXFR query for `zone`, prefixed with its TCP length
+fn build_axfr_query(id: u16, zone: &str) -> Vec<u8> {
+    let mut message = Vec::with_capacity(12 + zone.len() + 6);
+    message.extend_from_slice(&id.to_be_bytes());
+    message.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in zone.split('.').filter(|l| !l.is_empty()) {
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+    message.extend_from_slice(&QTYPE_AXFR.to_be_bytes());
+    message.extend_from_slice(&[0, 1]); // QCLASS IN
+
+    let mut framed = (message.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(&message);
+    framed
+}
+
+/// Response code and answer count of a reply to query `id`
+fn parse_axfr_reply(id: u16, message: &[u8]) -> Option<(u8, u16)> {
+    let header = message.get(..12)?;
+    let flags = u16::from_be_bytes([header[2], header[3]]);
+    if u16::from_be_bytes([header[0], header[1]]) != id || flags & FLAG_QR == 0 {
+        return None;
+    }
+    Some(((flags & 0x000F) as u8, u16::from_be_bytes([header[6], header[7]])))
+}
+
+/// Zones worth asking for: the domain suffixes of the scanned hosts' names,
+/// most common first, then the resolver's search domains
+pub fn local_zone_candidates(hosts: &[HostInfo]) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for hostname in hosts.iter().filter_map(|h| h.hostname.as_deref()) {
+        let name = hostname.trim_end_matches('.').to_lowercase();
+        if let Some((_, domain)) = name.split_once('.') {
+            *counts.entry(domain.to_string()).or_default() += 1;
+        }
+    }
+    let mut zones: Vec<(String, usize)> = counts.into_iter().collect();
+    zones.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut zones: Vec<String> = zones.into_iter().map(|(zone, _)| zone).collect();
+
+    if let Ok(resolv) = std::fs::read_to_string("/etc/resolv.conf") {
+        for line in resolv.lines() {
+            let mut fields = line.split_whitespace();
+            if matches!(fields.next(), Some("search" | "domain")) {
+                for domain in fields.map(|d| d.trim_end_matches('.').to_lowercase()) {
+                    if !zones.contains(&domain) {
+                        zones.push(domain);
+                    }
+                }
+            }
+        }
+    }
+    zones
+}
+
+/// Request `zone` from `ip` and read the first response message
+async fn attempt_transfer(ip: Ipv4Addr, zone: String, id: u16) -> ZoneTransferResult {
+    let reply = async {
+        let mut stream = timeout(AXFR_TIMEOUT, TcpStream::connect(SocketAddr::from((ip, DNS_PORT)))).await.ok()?.ok()?;
+        stream.write_all(&build_axfr_query(id, &zone)).await.ok()?;
+        let mut length = [0u8; 2];
+        timeout(AXFR_TIMEOUT, stream.read_exact(&mut length)).await.ok()?.ok()?;
+        let mut message = vec![0u8; u16::from_be_bytes(length) as usize];
+        timeout(AXFR_TIMEOUT, stream.read_exact(&mut message)).await.ok()?.ok()?;
+        parse_axfr_reply(id, &message)
+    }
+    .await;
+
+    ZoneTransferResult {
+        ip: ip.to_string(),
+        allowed: reply.is_some_and(|(rcode, answers)| rcode == RCODE_NOERROR && answers > 0),
+        records: reply.map_or(0, |(_, answers)| answers),
+        rcode: reply.map(|(rcode, _)| rcode),
+        zone,
+    }
+}
+
+/// Try a zone transfer of each zone from every responding DNS server
+///
+/// `servers` comes from `dns_server_scan`, `zones` usually from
+/// `local_zone_candidates`. Only private addresses are tried.
+pub async fn zone_transfer_scan(servers: &[DnsServerInfo], zones: &[String]) -> Vec<ZoneTransferResult> {
+    let targets: Vec<(Ipv4Addr, String)> = servers
+        .iter()
+        .filter(|s| s.responding)
+        .filter_map(|s| s.ip.parse::<Ipv4Addr>().ok())
+        .filter(|ip| ip.is_private())
+        .flat_map(|ip| zones.iter().map(move |zone| (ip, zone.clone())))
+        .collect();
+    if targets.is_empty() {
+        return Vec::new();
+    }
+
+    log_stderr!("Zone transfer: trying AXFR of {} zone(s) on {} attempts...", zones.len(), targets.len());
+
+    let base_id = std::process::id() as u16;
+    let results: Vec<ZoneTransferResult> = stream::iter(targets.into_iter().enumerate())
+        .map(|(i, (ip, zone))| attempt_transfer(ip, zone, base_id.wrapping_add(i as u16)))
+        .buffer_unordered(concurrency_limits().tcp_connects)
+        .collect()
+        .await;
+
+    for result in results.iter().filter(|r| r.allowed) {
+        log_stderr!("Zone transfer: {} allows AXFR of {} ({} records in first message)", result.ip, result.zone, result.records);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axfr_query_and_reply() {
+        let query = build_axfr_query(0x0102, "corp.example");
+        assert_eq!(u16::from_be_bytes([query[0], query[1]]) as usize, query.len() - 2);
+        assert_eq!(&query[2..6], &[0x01, 0x02, 0x00, 0x00]);
+        assert_eq!(&query[14..], b"\x04corp\x07example\x00\x00\xfc\x00\x01");
+
+        let mut reply = query[2..].to_vec();
+        reply[2..4].copy_from_slice(&(FLAG_QR | 0x0400).to_be_bytes());
+        reply[7] = 12;
+        assert_eq!(parse_axfr_reply(0x0102, &reply), Some((RCODE_NOERROR, 12)));
+        // REFUSED
+        reply[3] = 0x05;
+        assert_eq!(parse_axfr_reply(0x0102, &reply), Some((5, 12)));
+        assert!(parse_axfr_reply(0x0103, &reply).is_none());
+    }
+
+    #[test]
+    fn test_local_zone_candidates() {
+        let host = |name: &str| {
+            HostInfo {
+                device_type: "PC".to_string(),
+                discovery_method: "ARP".to_string(),
+                hostname: Some(name.to_string()),
+                ..Default::default()
+            }
+        };
+        let hosts = vec![host("nas.corp.example."), host("printer.corp.example"), host("tv.lan"), host("laptop")];
+        let zones = local_zone_candidates(&hosts);
+        assert_eq!(&zones[..2], &["corp.example".to_string(), "lan".to_string()]);
+    }
+}
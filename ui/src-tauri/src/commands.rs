@@ -2,6 +2,7 @@
 //!
 //! These commands are callable from the React frontend via `invoke()`.
 
+use std::collections::BTreeMap;
 use std::sync::Mutex;
 use std::time::Instant;
 use tokio::sync::Mutex as TokioMutex;
@@ -9,13 +10,13 @@ use tauri::Emitter;
 
 // Re-export types from the scanner library
 use host_discovery::{
-    ScanResult, HostInfo,
+    ScanResult, HostInfo, MacAddress, Topology,
     find_valid_interface, calculate_subnet_ips,
-    active_arp_scan, icmp_scan, tcp_probe_scan, dns_scan,
-    lookup_vendor_info, infer_device_type, calculate_risk_score,
-    guess_os_from_ttl,
+    active_arp_scan, icmp_scan, tcp_port_scan_adaptive, HostTimeouts, TCP_PROBE_PORTS, dns_scan, open_ports, connect_times,
+    lookup_vendor_info, lookup_vendors, infer_device_type, calculate_risk_score,
+    guess_os_from_ttl, classify_link_media, classify_virtual_machines, identify_services, correlate_dual_stack, ipv6_neighbors, apply_firmware, firmware_scan,
     // Database
-    Database, DeviceRecord, ScanRecord, NetworkStats, AlertRecord,
+    Database, DeviceRecord, DeviceTag, ScanRecord, NetworkStats, AlertRecord,
     database::queries::{self, lookup_port_warnings},
     // Monitoring
     BackgroundMonitor, MonitoringStatus, NetworkEvent,
@@ -86,17 +87,17 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
     };
 
     let arp_count = arp_hosts.len();
+    let vendors = lookup_vendors(&arp_hosts);
 
-    // Run ICMP and TCP scans in parallel
-    let (response_times, port_results) = tokio::join!(
-        icmp_scan(&arp_hosts),
-        tcp_probe_scan(&arp_hosts)
-    );
-
-    let response_times = response_times.map_err(|e| format!("ICMP scan failed: {}", e))?;
-    let port_results = port_results.map_err(|e| format!("TCP scan failed: {}", e))?;
+    // ICMP first: each host's round trip sizes its TCP connect timeout
+    let response_times = icmp_scan(&arp_hosts)
+        .await
+        .map_err(|e| format!("ICMP scan failed: {}", e))?;
+    let port_results = tcp_port_scan_adaptive(&arp_hosts, TCP_PROBE_PORTS, &HostTimeouts::from_icmp(&response_times))
+        .await
+        .map_err(|e| format!("TCP scan failed: {}", e))?;
 
-    let icmp_count = response_times.len();
+    let icmp_count = response_times.values().filter(|r| r.is_up()).count();
 
     // DNS lookup
     let host_ips: Vec<std::net::Ipv4Addr> = arp_hosts
@@ -113,13 +114,13 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
         .filter(|(ip, _)| **ip != interface.ip)
         .map(|(ip, mac)| {
             let icmp_result = response_times.get(ip);
-            let response_time = icmp_result.map(|r| r.duration.as_millis() as u64);
+            let response_time = icmp_result.and_then(|r| r.duration).map(|d| d.as_millis() as u64);
             let ttl = icmp_result.and_then(|r| r.ttl);
             let os_guess = ttl.map(guess_os_from_ttl);
-            let open_ports = port_results.get(ip).cloned().unwrap_or_default();
+            let open_ports = port_results.get(ip).map(|p| open_ports(p)).unwrap_or_default();
             
-            let mac_str = format!("{}", mac);
-            let vendor_info = lookup_vendor_info(&mac_str);
+            let mac_str = MacAddress::from(*mac).to_string();
+            let vendor_info = vendors.get(ip).cloned().unwrap_or_default();
             
             let is_gateway = ip.octets()[3] == 1 || open_ports.contains(&80);
             let device_type = infer_device_type(
@@ -188,20 +189,35 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
                 vendor: vendor_info.vendor,
                 is_randomized: vendor_info.is_randomized,
                 mac: mac_str,
+                ipv6_addresses: Vec::new(),
                 response_time_ms: response_time,
+                latency_stats: icmp_result.and_then(|r| r.stats.clone()),
+                packet_loss_percent: icmp_result.filter(|r| r.is_up()).map(|r| r.loss_percent()),
                 ttl,
                 os_guess,
                 device_type: device_type.as_str().to_string(),
                 risk_score,
+                port_connect_ms: port_results.get(ip).map(|p| connect_times(p)).unwrap_or_default(),
                 open_ports,
+                ports: port_results.get(ip).cloned().unwrap_or_default(),
                 discovery_method: method,
                 hostname: dns_hostnames.get(ip).cloned(),
                 system_description: None,
                 uptime_seconds: None,
+                tcp_uptime_seconds: None,
+                firmware: None,
                 neighbors: Vec::new(),
+                switch_port: None,
+                link_medium: None,
                 vulnerabilities,
                 port_warnings,
                 security_grade: String::new(),
+                friendly_name: None,
+                notes: None,
+                hostname_records: Vec::new(),
+                observed_interfaces: Vec::new(),
+                service_guesses: Vec::new(),
+                arp_reply: None,
             };
             
             // Calculate security grade
@@ -244,26 +260,49 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
         vendor: local_vendor_info.vendor,
         is_randomized: local_vendor_info.is_randomized,
         mac: local_mac,
+        ipv6_addresses: Vec::new(),
         response_time_ms: Some(0),
+        latency_stats: None,
+        packet_loss_percent: None,
         ttl: None,
         os_guess: None,
         device_type: local_device_type.as_str().to_string(),
         risk_score: 0,
         open_ports: Vec::new(),
+        ports: Vec::new(),
         discovery_method: "LOCAL".to_string(),
         hostname: None,
         system_description: None,
         uptime_seconds: None,
+        tcp_uptime_seconds: None,
+        firmware: None,
         neighbors: Vec::new(),
+        switch_port: None,
+        link_medium: None,
         vulnerabilities: local_vulnerabilities,
         port_warnings: Vec::new(),
         security_grade: String::new(),
+        friendly_name: None,
+        notes: None,
+        hostname_records: Vec::new(),
+        port_connect_ms: BTreeMap::new(),
+        observed_interfaces: Vec::new(),
+        service_guesses: Vec::new(),
+        arp_reply: None,
     };
     
     // Calculate security grade for local machine
     local_host.security_grade = calculate_security_grade(&local_host);
     
     active_hosts.push(local_host);
+    correlate_dual_stack(&mut active_hosts, &ipv6_neighbors());
+    classify_virtual_machines(&mut active_hosts);
+    identify_services(&mut active_hosts);
+    classify_link_media(&mut active_hosts, &[]);
+
+    // Firmware versions of cameras/printers/plugs, checked against firmware CVEs below
+    let firmware = firmware_scan(&active_hosts).await;
+    apply_firmware(&mut active_hosts, &firmware);
 
     // Sort by IP
     active_hosts.sort_by(|a, b| {
@@ -274,7 +313,7 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
 
     let duration = start.elapsed().as_millis() as u64;
 
-    let scan_result = ScanResult {
+    let mut scan_result = ScanResult {
         interface_name: interface.name,
         local_ip: interface.ip.to_string(),
         local_mac: format!("{}", interface.mac),
@@ -284,14 +323,30 @@ pub async fn scan_network(state: tauri::State<'_, AppState>) -> Result<ScanResul
         icmp_discovered: icmp_count,
         total_hosts: active_hosts.len(),
         scan_duration_ms: duration,
+        wan: None,
+        nat: None,
+        active_directory: None,
+        container_hosts: Vec::new(),
         active_hosts,
     };
 
-    // Save scan result to database
+    // Save scan result to database, merging user annotations into the hosts
     {
         let db = state.db.lock().unwrap();
         let conn = db.connection();
         let conn = conn.lock().unwrap();
+        if let Err(e) = queries::apply_host_annotations(&conn, &mut scan_result.active_hosts) {
+            eprintln!("[WARN] Failed to load host annotations: {}", e);
+        }
+        for host in scan_result.active_hosts.iter_mut() {
+            let Some(firmware) = host.firmware.clone() else { continue };
+            if let Ok(vulns) = queries::lookup_firmware_vulnerabilities(&conn, host.vendor.as_deref(), &firmware) {
+                if !vulns.is_empty() {
+                    host.vulnerabilities.extend(vulns);
+                    host.security_grade = calculate_security_grade(host);
+                }
+            }
+        }
         if let Err(e) = queries::insert_scan(&conn, &scan_result) {
             eprintln!("[WARN] Failed to save scan to database: {}", e);
         }
@@ -357,6 +412,55 @@ pub fn update_device_name(state: tauri::State<'_, AppState>, mac: String, name:
         .map_err(|e| format!("Failed to update device name: {}", e))
 }
 
+/// Tag a device by its identity (MAC, or IP for hosts without one)
+#[tauri::command]
+pub fn add_device_tag(state: tauri::State<'_, AppState>, host_id: String, tag: String) -> Result<(), String> {
+    let db = state.db.lock().unwrap();
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    queries::add_device_tag(&conn, &host_id, &tag)
+        .map_err(|e| format!("Failed to add tag: {}", e))
+}
+
+/// Remove a tag from a device
+#[tauri::command]
+pub fn remove_device_tag(state: tauri::State<'_, AppState>, host_id: String, tag: String) -> Result<(), String> {
+    let db = state.db.lock().unwrap();
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    queries::remove_device_tag(&conn, &host_id, &tag)
+        .map_err(|e| format!("Failed to remove tag: {}", e))
+}
+
+/// Set a device's friendly name and notes (empty values clear them)
+#[tauri::command]
+pub fn set_host_annotation(
+    state: tauri::State<'_, AppState>,
+    host_id: String,
+    friendly_name: Option<String>,
+    notes: Option<String>,
+) -> Result<(), String> {
+    let db = state.db.lock().unwrap();
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    queries::set_host_annotation(&conn, &host_id, friendly_name.as_deref(), notes.as_deref())
+        .map_err(|e| format!("Failed to save annotation: {}", e))
+}
+
+/// Get all device tags
+#[tauri::command]
+pub fn get_device_tags(state: tauri::State<'_, AppState>) -> Result<Vec<DeviceTag>, String> {
+    let db = state.db.lock().unwrap();
+    let conn = db.connection();
+    let conn = conn.lock().unwrap();
+
+    queries::get_device_tags(&conn)
+        .map_err(|e| format!("Failed to get tags: {}", e))
+}
+
 /// Get network statistics
 #[tauri::command]
 pub fn get_network_stats(state: tauri::State<'_, AppState>) -> Result<NetworkStats, String> {
@@ -614,6 +718,14 @@ pub fn export_topology_to_json(
         .map_err(|e| format!("Failed to export JSON: {}", e))
 }
 
+/// Build the topology graph for the current scan hosts
+#[tauri::command]
+pub fn build_topology(
+    hosts: Vec<HostInfo>,
+) -> Topology {
+    Topology::from_hosts(&hosts)
+}
+
 /// Export full scan result to JSON
 #[tauri::command]
 pub fn export_scan_to_json(
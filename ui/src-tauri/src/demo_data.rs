@@ -19,6 +19,10 @@ pub fn generate_demo_scan() -> ScanResult {
         icmp_discovered: hosts.len(),
         total_hosts: hosts.len(),
         scan_duration_ms: 2500,
+        wan: None,
+        nat: None,
+        active_directory: None,
+        container_hosts: Vec::new(),
         active_hosts: hosts,
     }
 }
@@ -30,379 +34,623 @@ fn generate_demo_hosts() -> Vec<HostInfo> {
         HostInfo {
             ip: "192.168.1.1".to_string(),
             mac: "34:4a:c3:22:6f:90".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("TP-Link Technologies Co., Ltd.".to_string()),
             hostname: Some("Router.local".to_string()),
             device_type: "Router".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(2),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![80, 443, 22],
+            ports: Vec::new(),
             risk_score: 35,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "B".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 2. Windows PC - HIGH RISK
         HostInfo {
             ip: "192.168.1.10".to_string(),
             mac: "00:0c:29:5a:8f:1d".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Dell Inc.".to_string()),
             hostname: Some("WIN-PC-001".to_string()),
             device_type: "Desktop".to_string(),
             os_guess: Some("Windows".to_string()),
             response_time_ms: Some(5),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(128),
             open_ports: vec![445, 3389, 135],
+            ports: Vec::new(),
             risk_score: 85,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![
                 VulnerabilityInfo {
                     cve_id: "CVE-2017-0144".to_string(),
                     severity: "Critical".to_string(),
                     description: "EternalBlue SMBv1 Remote Code Execution".to_string(),
                     cvss_score: Some(9.3),
+                    cvss_vector: None,
                 },
                 VulnerabilityInfo {
                     cve_id: "CVE-2019-0708".to_string(),
                     severity: "Critical".to_string(),
                     description: "BlueKeep RDP Remote Code Execution".to_string(),
                     cvss_score: Some(9.8),
+                    cvss_vector: None,
                 },
             ],
             port_warnings: vec![],
             security_grade: "F".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 3. Android Phone (Randomized MAC)
         HostInfo {
             ip: "192.168.1.25".to_string(),
             mac: "d2:81:c8:45:6b:71".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Private Device (Randomized MAC)".to_string()),
             hostname: Some("Galaxy-S23".to_string()),
             device_type: "Phone".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(12),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![],
+            ports: Vec::new(),
             risk_score: 10,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: true,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 4. Network Printer
         HostInfo {
             ip: "192.168.1.30".to_string(),
             mac: "00:25:b3:a4:56:78".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Hewlett Packard".to_string()),
             hostname: Some("HP-Printer-01".to_string()),
             device_type: "Printer".to_string(),
             os_guess: None,
             response_time_ms: Some(3),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![80, 631, 9100],
+            ports: Vec::new(),
             risk_score: 40,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "C".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 5. IP Camera - HIGH RISK
         HostInfo {
             ip: "192.168.1.70".to_string(),
             mac: "44:19:b6:12:34:56".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Hangzhou Hikvision Digital".to_string()),
             hostname: Some("IP-Camera-Front".to_string()),
             device_type: "Camera".to_string(),
             os_guess: None,
             response_time_ms: Some(10),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![80, 554],
+            ports: Vec::new(),
             risk_score: 60,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![
                 VulnerabilityInfo {
                     cve_id: "CVE-2021-36260".to_string(),
                     severity: "Critical".to_string(),
                     description: "Hikvision IP Camera Command Injection".to_string(),
                     cvss_score: Some(9.8),
+                    cvss_vector: None,
                 },
             ],
             port_warnings: vec![],
             security_grade: "D".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 6. Managed Switch (Cisco)
         HostInfo {
             ip: "192.168.1.2".to_string(),
             mac: "00:1e:14:7b:3c:90".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Cisco Systems, Inc.".to_string()),
             hostname: Some("SW-Core-01".to_string()),
             device_type: "Switch".to_string(),
             os_guess: Some("Cisco IOS".to_string()),
             response_time_ms: Some(1),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(255),
             open_ports: vec![22, 23, 80, 443],
+            ports: Vec::new(),
             risk_score: 25,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "B".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 7. MacBook Pro
         HostInfo {
             ip: "192.168.1.15".to_string(),
             mac: "3c:22:fb:89:12:34".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Apple, Inc.".to_string()),
             hostname: Some("MacBook-Pro.local".to_string()),
             device_type: "Laptop".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(4),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![],
+            ports: Vec::new(),
             risk_score: 15,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 8. iPhone (Randomized MAC)
         HostInfo {
             ip: "192.168.1.26".to_string(),
             mac: "f2:a3:b4:c5:d6:e7".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Private Device (Randomized MAC)".to_string()),
             hostname: Some("iPhone-14".to_string()),
             device_type: "Phone".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(15),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![],
+            ports: Vec::new(),
             risk_score: 10,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: true,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 9. Smart TV (Samsung)
         HostInfo {
             ip: "192.168.1.40".to_string(),
             mac: "e8:50:8b:12:34:56".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Samsung Electronics Co., Ltd.".to_string()),
             hostname: Some("Samsung-TV".to_string()),
             device_type: "SmartTV".to_string(),
             os_guess: Some("Tizen OS".to_string()),
             response_time_ms: Some(8),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![8001, 8002],
+            ports: Vec::new(),
             risk_score: 30,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "C".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 10. Raspberry Pi Server
         HostInfo {
             ip: "192.168.1.50".to_string(),
             mac: "b8:27:eb:45:67:89".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Raspberry Pi Foundation".to_string()),
             hostname: Some("RasPi-Server".to_string()),
             device_type: "Server".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(3),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![22, 80, 3306],
+            ports: Vec::new(),
             risk_score: 50,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![
                 VulnerabilityInfo {
                     cve_id: "CVE-2023-4911".to_string(),
                     severity: "High".to_string(),
                     description: "Looney Tunables - glibc buffer overflow".to_string(),
                     cvss_score: Some(7.8),
+                    cvss_vector: None,
                 },
             ],
             port_warnings: vec![],
             security_grade: "D".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 11. NAS Storage (Synology)
         HostInfo {
             ip: "192.168.1.60".to_string(),
             mac: "00:11:32:ab:cd:ef".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Synology Incorporated".to_string()),
             hostname: Some("NAS-Storage".to_string()),
             device_type: "Storage".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(4),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![80, 443, 5000, 5001],
+            ports: Vec::new(),
             risk_score: 35,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "B".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 12. Wireless Access Point
         HostInfo {
             ip: "192.168.1.3".to_string(),
             mac: "24:a4:3c:56:78:90".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Ubiquiti Networks Inc.".to_string()),
             hostname: Some("UAP-AC-Pro".to_string()),
             device_type: "AccessPoint".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(2),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![22, 80, 443],
+            ports: Vec::new(),
             risk_score: 20,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 13. Xbox Gaming Console
         HostInfo {
             ip: "192.168.1.45".to_string(),
             mac: "98:5f:d3:12:34:56".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Microsoft Corporation".to_string()),
             hostname: Some("Xbox-Series-X".to_string()),
             device_type: "Gaming".to_string(),
             os_guess: Some("Xbox OS".to_string()),
             response_time_ms: Some(6),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(128),
             open_ports: vec![],
+            ports: Vec::new(),
             risk_score: 15,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 14. Canon Printer
         HostInfo {
             ip: "192.168.1.31".to_string(),
             mac: "00:1e:8f:ab:cd:12".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Canon Inc.".to_string()),
             hostname: Some("Canon-MX920".to_string()),
             device_type: "Printer".to_string(),
             os_guess: None,
             response_time_ms: Some(5),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![80, 631],
+            ports: Vec::new(),
             risk_score: 38,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "C".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 15. Amazon Echo Dot
         HostInfo {
             ip: "192.168.1.41".to_string(),
             mac: "50:dc:e7:89:12:34".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Amazon Technologies Inc.".to_string()),
             hostname: Some("Echo-Dot".to_string()),
             device_type: "IoT".to_string(),
             os_guess: Some("Fire OS".to_string()),
             response_time_ms: Some(7),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![],
+            ports: Vec::new(),
             risk_score: 25,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "B".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
         
         // 16. Linux Workstation (HP)
         HostInfo {
             ip: "192.168.1.20".to_string(),
             mac: "3c:52:82:1f:34:b8".to_string(),
+            ipv6_addresses: Vec::new(),
             vendor: Some("Hewlett Packard".to_string()),
             hostname: Some("HP-Workstation".to_string()),
             device_type: "Desktop".to_string(),
             os_guess: Some("Linux/Unix/macOS".to_string()),
             response_time_ms: Some(3),
+            latency_stats: None,
+            packet_loss_percent: None,
             ttl: Some(64),
             open_ports: vec![22],
+            ports: Vec::new(),
             risk_score: 20,
             discovery_method: "Demo".to_string(),
             system_description: None,
             uptime_seconds: None,
+            tcp_uptime_seconds: None,
+            firmware: None,
             is_randomized: false,
             neighbors: vec![],
+            switch_port: None,
+            link_medium: None,
             vulnerabilities: vec![],
             port_warnings: vec![],
             security_grade: "A".to_string(),
+            friendly_name: None,
+            notes: None,
+            hostname_records: Vec::new(),
+            port_connect_ms: Default::default(),
+            observed_interfaces: Vec::new(),
+            service_guesses: Vec::new(),
+            arp_reply: None,
         },
     ]
 }
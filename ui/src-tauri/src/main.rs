@@ -13,8 +13,8 @@ mod demo_data;
 use commands::{AppState, MonitorState};
 
 fn main() {
-    // Initialize structured logging system
-    if let Err(e) = host_discovery::logging::init_logging() {
+    // Initialize structured logging system (inside the runtime, for span export)
+    if let Err(e) = tauri::async_runtime::block_on(async { host_discovery::logging::init_logging() }) {
         eprintln!("Warning: Failed to initialize logging: {}", e);
         eprintln!("Continuing without file logging...");
     }
@@ -48,6 +48,10 @@ fn main() {
             commands::get_all_devices,
             commands::get_device_by_mac,
             commands::update_device_name,
+            commands::set_host_annotation,
+            commands::add_device_tag,
+            commands::remove_device_tag,
+            commands::get_device_tags,
             // Database commands - Stats
             commands::get_network_stats,
             // Database commands - Alerts
@@ -65,6 +69,7 @@ fn main() {
             commands::export_devices_to_csv,
             commands::export_scan_to_csv,
             commands::export_topology_to_json,
+            commands::build_topology,
             commands::export_scan_to_json,
             commands::export_scan_report,
             commands::export_security_report,
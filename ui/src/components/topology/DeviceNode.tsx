@@ -15,6 +15,8 @@ import {
   Gamepad2,
   HardDrive,
   Shield,
+  Box,
+  Layers,
 } from 'lucide-react';
 import { useTheme } from '../../hooks/useTheme';
 
@@ -35,6 +37,8 @@ const deviceIcons: Record<string, React.ElementType> = {
   PRINTER: Printer,
   CAMERA: Camera,
   GAME_CONSOLE: Gamepad2,
+  CONTAINER: Box,
+  VIRTUAL_MACHINE: Layers,
   UNKNOWN: HelpCircle,
 };
 
@@ -55,6 +59,8 @@ const deviceColors: Record<string, string> = {
   PRINTER: '#14B8A6',
   CAMERA: '#F97316',
   GAME_CONSOLE: '#8B5CF6',
+  CONTAINER: '#0EA5E9',
+  VIRTUAL_MACHINE: '#6366F1',
   UNKNOWN: '#9CA3AF',
 };
 
@@ -34,6 +34,13 @@ export interface DeviceRecord {
   notes?: string;
 }
 
+export interface DeviceTag {
+  id: number;
+  host_id: string; // MAC, or IP for hosts without one
+  tag: string;
+  created_at: string;
+}
+
 export interface AlertRecord {
   id: number;
   created_at: string;
@@ -145,11 +152,63 @@ export function useDevices() {
     [fetchDevices],
   );
 
+  const setAnnotation = useCallback(
+    async (hostId: string, friendlyName: string | null, notes: string | null) => {
+      try {
+        await invoke("set_host_annotation", { hostId, friendlyName, notes });
+        await fetchDevices();
+      } catch (err) {
+        throw new Error(err instanceof Error ? err.message : String(err));
+      }
+    },
+    [fetchDevices],
+  );
+
   useEffect(() => {
     fetchDevices();
   }, [fetchDevices]);
 
-  return { devices, loading, error, refetch: fetchDevices, updateDeviceName };
+  return { devices, loading, error, refetch: fetchDevices, updateDeviceName, setAnnotation };
+}
+
+/**
+ * Hook for user-defined device tags
+ */
+export function useDeviceTags() {
+  const [tags, setTags] = useState<DeviceTag[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchTags = useCallback(async () => {
+    setError(null);
+    try {
+      const result = await invoke<DeviceTag[]>("get_device_tags");
+      setTags(result);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  }, []);
+
+  const addTag = useCallback(
+    async (hostId: string, tag: string) => {
+      await invoke("add_device_tag", { hostId, tag });
+      await fetchTags();
+    },
+    [fetchTags],
+  );
+
+  const removeTag = useCallback(
+    async (hostId: string, tag: string) => {
+      await invoke("remove_device_tag", { hostId, tag });
+      await fetchTags();
+    },
+    [fetchTags],
+  );
+
+  useEffect(() => {
+    fetchTags();
+  }, [fetchTags]);
+
+  return { tags, error, refetch: fetchTags, addTag, removeTag };
 }
 
 /**
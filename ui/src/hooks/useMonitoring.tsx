@@ -48,6 +48,8 @@ export interface MonitoringStatus {
   last_scan_time?: string;
   devices_online: number;
   devices_total: number;
+  captive_portal?: boolean;
+  wan_baseline_ms?: number | null;
 }
 
 export type NetworkEventType =
@@ -78,6 +80,22 @@ export type NetworkEventType =
       type: "DeviceIpChanged";
       data: { mac: string; old_ip: string; new_ip: string };
     }
+  | {
+      type: "CaptivePortalChanged";
+      data: { detected: boolean; portal_url?: string };
+    }
+  | {
+      type: "WanLatencyDegraded";
+      data: { latency_ms?: number | null; baseline_ms: number };
+    }
+  | {
+      type: "WanLatencyRecovered";
+      data: { latency_ms: number; baseline_ms: number };
+    }
+  | {
+      type: "NetworkChanged";
+      data: { previous: string; current: string };
+    }
   | { type: "MonitoringError"; data: { message: string } };
 
 export interface MonitoringState {
@@ -311,6 +329,14 @@ export function getEventStyle(eventType: string): {
       return { icon: "📶", color: "text-green-500" };
     case "DeviceIpChanged":
       return { icon: "🔄", color: "text-orange-500" };
+    case "CaptivePortalChanged":
+      return { icon: "🚪", color: "text-yellow-500" };
+    case "WanLatencyDegraded":
+      return { icon: "🌐", color: "text-orange-500" };
+    case "WanLatencyRecovered":
+      return { icon: "🌐", color: "text-green-500" };
+    case "NetworkChanged":
+      return { icon: "🔀", color: "text-blue-500" };
     case "MonitoringError":
       return { icon: "❌", color: "text-red-500" };
     default:
@@ -341,6 +367,18 @@ export function formatEventMessage(event: NetworkEventType): string {
       return `Online: ${event.data.hostname || event.data.ip}`;
     case "DeviceIpChanged":
       return `IP changed: ${event.data.old_ip} → ${event.data.new_ip}`;
+    case "CaptivePortalChanged":
+      return event.data.detected
+        ? `Behind captive portal${event.data.portal_url ? `: ${event.data.portal_url}` : ""}`
+        : "Captive portal cleared";
+    case "WanLatencyDegraded":
+      return event.data.latency_ms == null
+        ? "Internet unreachable"
+        : `Internet latency ${event.data.latency_ms.toFixed(0)} ms (baseline ${event.data.baseline_ms.toFixed(0)} ms)`;
+    case "WanLatencyRecovered":
+      return `Internet latency back to normal (${event.data.latency_ms.toFixed(0)} ms)`;
+    case "NetworkChanged":
+      return `Network changed: ${event.data.current}, rescanning`;
     case "MonitoringError":
       return `Error: ${event.data.message}`;
     default:
@@ -21,27 +21,110 @@ export interface PortWarning {
   recommendation?: string;
 }
 
+export interface SwitchPort {
+  switch: string;
+  switch_name?: string;
+  port: string;
+}
+
+export interface FirmwareInfo {
+  product?: string;
+  version: string;
+  source: string; // endpoint the version was read from
+}
+
 export interface HostInfo {
   ip: string;
   mac: string;
+  observed_interfaces?: string[]; // local interfaces that saw the host (multi-interface scans)
+  service_guesses?: ServiceGuess[];
+  ipv6_addresses?: string[]; // same-MAC IPv6 addresses, global first
   vendor?: string;
   is_randomized?: boolean;
   response_time_ms?: number | null;
+  arp_reply?: ArpReplyStats;
   ttl?: number;
   os_guess?: string;
   device_type: string;
   risk_score: number;
   open_ports?: number[];
+  port_connect_ms?: Record<string, number>; // TCP handshake time per open port
   discovery_method: string;
   hostname?: string;
+  hostname_records?: HostnameRecord[]; // every name by source, when the policy keeps all
   system_description?: string;
   uptime_seconds?: number;
+  firmware?: FirmwareInfo;
+  switch_port?: SwitchPort;
+  link_medium?: 'wired' | 'wireless';
   vulnerabilities?: VulnerabilityInfo[];
   port_warnings?: PortWarning[];
   security_grade?: string;
+  friendly_name?: string; // user-assigned display name
+  notes?: string;
   last_seen?: string; // ISO timestamp of last detection
 }
 
+export interface ArpReplyStats {
+  round: number;
+  requests: number; // ARP requests sent before the host answered
+  latency_ms?: number | null;
+}
+
+export interface HostnameRecord {
+  source: 'ptr' | 'mdns' | 'snmp' | 'dhcp';
+  name: string;
+}
+
+export interface ServiceGuess {
+  service: string;
+  port?: number; // absent for host-wide evidence (SNMP sysDescr)
+  evidence: 'banner' | 'port_heuristic' | 'tls_certificate' | 'snmp_sys_descr';
+  detail?: string;
+  confidence: number; // 0.0-1.0
+}
+
+export type NodeKind = 'host' | 'switch' | 'router';
+export type EdgeKind = 'l2_adjacency' | 'l3_hop' | 'wireless_association';
+export type EdgeSource = 'lldp' | 'fdb' | 'traceroute' | 'heuristic';
+
+export interface TopologyNode {
+  id: string; // host identity (MAC, else IP)
+  kind: NodeKind;
+  ip?: string;
+  label: string;
+  scanned: boolean;
+  hypervisor?: string; // node id of the likely hypervisor host (VM guests)
+}
+
+export interface TopologyEdge {
+  source: string;
+  target: string;
+  kind: EdgeKind;
+  evidence: EdgeSource;
+  source_port?: string;
+  target_port?: string;
+}
+
+export interface TopologySubnet {
+  cidr: string;
+  gateway?: string; // node id of the router
+}
+
+export interface LayerLink {
+  node: string;
+  subnet: string;
+  ip: string;
+}
+
+export interface Topology {
+  nodes: TopologyNode[];
+  l2_edges: TopologyEdge[]; // cabling, switch ports, wireless association
+  l3_edges: TopologyEdge[]; // routed hops
+  subnets?: TopologySubnet[];
+  cross_links?: LayerLink[]; // node <-> subnet membership
+}
+
 export interface ScanResult {
   interface_name: string;
   local_ip: string;
@@ -52,7 +135,49 @@ export interface ScanResult {
   icmp_discovered: number;
   total_hosts: number;
   scan_duration_ms: number;
+  wan?: WanHealth;
+  active_directory?: ActiveDirectoryInfo;
   active_hosts: HostInfo[];
+  container_hosts?: HostInfo[];
+}
+
+export interface WanHealth {
+  public_ip?: string | null;
+  public_ip_source?: string | null;
+  reachable_targets: number;
+  total_targets: number;
+  latency_ms?: number | null;
+}
+
+export interface DomainController {
+  ip: string;
+  hostname?: string | null;
+  kerberos: boolean;
+  ldap: boolean;
+  ldaps: boolean;
+}
+
+export interface ActiveDirectoryInfo {
+  domain?: string | null;
+  domain_controllers: DomainController[];
+  srv_targets: string[];
+}
+
+export interface HostSighting {
+  subnet: string;
+  interface_name: string;
+  ip: string;
+}
+
+export interface CrossSubnetDuplicate {
+  mac: string;
+  sightings: HostSighting[];
+}
+
+// Several subnets/interfaces scanned in one run
+export interface ScanReport {
+  scans: ScanResult[];
+  duplicates?: CrossSubnetDuplicate[];
 }
 
 export type ScanStatus = 'ready' | 'scanning' | 'complete';